@@ -7,7 +7,6 @@ use reqwest;
 use serde_json;
 use std::collections::HashMap;
 use tracing::info;
-use uuid::Uuid;
 
 #[derive(Serialize)]
 struct NoteRequest {
@@ -46,8 +45,25 @@ struct PerformMelodyResponse {
     effects: Vec<String>,
 }
 
+#[derive(Serialize)]
+struct Credentials<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+struct LoginResponse {
+    token: String,
+    player_id: PlayerId,
+}
+
+/// Where services are expected unless told otherwise.
+pub const DEFAULT_BASE_URL: &str = "http://localhost";
+
 pub struct EnhancedClient {
     pub player_id: PlayerId,
+    /// Bearer token from the gateway once signed in.
+    pub access_token: Option<String>,
     pub player_name: String,
     pub service_urls: HashMap<String, String>,
     pub client: reqwest::Client,
@@ -57,16 +73,9 @@ pub struct EnhancedClient {
 }
 
 impl EnhancedClient {
-    pub fn new(player_name: String) -> Self {
+    /// A client for services on their usual ports at `base_url`.
+    pub fn new(player_name: String, base_url: &str) -> Self {
         let mut service_urls = HashMap::new();
-        
-        // Check if we're using docker or local development
-        let base_url = if std::env::var("USE_DOCKER").is_ok() {
-            "http://localhost"
-        } else {
-            "http://localhost"
-        };
-        
         service_urls.insert("song".to_string(), format!("{}:3001", base_url));
         service_urls.insert("world".to_string(), format!("{}:3002", base_url));
         service_urls.insert("echo".to_string(), format!("{}:3003", base_url));
@@ -78,6 +87,7 @@ impl EnhancedClient {
         service_urls.insert("silence".to_string(), format!("{}:3009", base_url));
         service_urls.insert("procedural".to_string(), format!("{}:3010", base_url));
         service_urls.insert("behavior".to_string(), format!("{}:3011", base_url));
+        service_urls.insert("gateway".to_string(), format!("{}:8080", base_url));
         
         let mut echo_bonds = HashMap::new();
         echo_bonds.insert(EchoType::Lumi, 0);
@@ -86,7 +96,9 @@ impl EnhancedClient {
         echo_bonds.insert(EchoType::Ignis, 0);
         
         Self {
-            player_id: PlayerId(Uuid::new_v4()),
+            // A guest id until sign_in replaces it with the account's
+            player_id: utils::new_player_id(),
+            access_token: None,
             player_name,
             service_urls,
             client: reqwest::Client::new(),
@@ -96,6 +108,32 @@ impl EnhancedClient {
         }
     }
     
+    /// Sign in through the gateway, registering the name first if it is
+    /// new, so the player keeps the same id from one run to the next.
+    pub async fn sign_in(&mut self, password: &str) -> anyhow::Result<()> {
+        let gateway = &self.service_urls["gateway"];
+        let credentials = Credentials { username: &self.player_name, password };
+
+        let mut response = self.client.post(format!("{}/login", gateway)).json(&credentials).send().await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let registered = self.client.post(format!("{}/register", gateway)).json(&credentials).send().await?;
+            match registered.status() {
+                status if status.is_success() => info!("Registered {}", self.player_name),
+                reqwest::StatusCode::CONFLICT => anyhow::bail!("wrong password for {}", self.player_name),
+                _ => {
+                    let error: serde_json::Value = registered.json().await.unwrap_or_default();
+                    anyhow::bail!("could not register: {}", error["error"].as_str().unwrap_or("unknown error"));
+                }
+            }
+            response = self.client.post(format!("{}/login", gateway)).json(&credentials).send().await?;
+        }
+
+        let login: LoginResponse = response.error_for_status()?.json().await?;
+        self.player_id = login.player_id;
+        self.access_token = Some(login.token);
+        Ok(())
+    }
+
     pub async fn view_progression(&self) -> anyhow::Result<()> {
         let response = self.client
             .get(&format!("{}/progression/{}", self.service_urls["harmony"], self.player_id.0))
@@ -131,13 +169,13 @@ impl EnhancedClient {
             
             println!("\n📜 Your Chronicle:");
             
-            if let Some(legends) = chronicle["legends"].as_array() {
-                if legends.is_empty() {
+            if let Some(entries) = chronicle["entries"].as_array() {
+                if entries.is_empty() {
                     println!("   No legends recorded yet. Your story is just beginning!");
                 } else {
-                    println!("   Legends ({}):", legends.len());
-                    for legend in legends.iter().take(5) {
-                        println!("   - {} ({})", legend["title"], legend["impact"]);
+                    println!("   Latest of {} moments:", chronicle["total"]);
+                    for entry in entries.iter().take(5) {
+                        println!("   - {} ({})", entry["text"].as_str().unwrap_or("?"), entry["recorded_at"].as_str().unwrap_or("?"));
                     }
                 }
             }
@@ -157,27 +195,34 @@ impl EnhancedClient {
     
     pub async fn request_quest(&self) -> anyhow::Result<()> {
         let request = serde_json::json!({
-            "player_id": self.player_id.0.to_string(),
-            "region": self.current_region.as_ref().map(|r| r.0.to_string()).unwrap_or_else(|| "Terra Nova".to_string()),
+            "region": self.current_region.as_ref().map(|r| r.0.to_string()),
         });
         
-        let response = self.client
-            .post(&format!("{}/quest/generate", self.service_urls["story"]))
-            .json(&request)
-            .send()
-            .await?;
+        let mut builder = self.client
+            .post(format!("{}/quest/generate", self.service_urls["story"]))
+            .json(&request);
+        if let Some(token) = &self.access_token {
+            builder = builder.bearer_auth(token);
+        }
+        let response = builder.send().await?;
         
         if response.status().is_success() {
             let quest: serde_json::Value = response.json().await?;
             
             println!("\n🎯 New Quest Received!");
-            println!("   Title: {}", quest["title"]);
-            println!("   {}", quest["description"]);
-            println!("   Quest Giver: {}", quest["quest_giver"]);
+            println!("   Title: {}", quest["title"].as_str().unwrap_or("Untitled"));
+            println!("   {}", quest["description"].as_str().unwrap_or(""));
+            println!("   Objectives:");
+            for objective in quest["objectives"].as_array().into_iter().flatten() {
+                let optional = if objective["optional"].as_bool().unwrap_or(false) { " (optional)" } else { "" };
+                println!("     - {}{}", objective["description"].as_str().unwrap_or(""), optional);
+            }
             println!("   Rewards:");
-            println!("     - Creative: +{}", quest["reward_resonance"]["creative"]);
-            println!("     - Exploration: +{}", quest["reward_resonance"]["exploration"]);
-            println!("     - Restoration: +{}", quest["reward_resonance"]["restoration"]);
+            println!("     - Creative: +{}", quest["rewards"]["resonance"]["creative"]);
+            println!("     - Exploration: +{}", quest["rewards"]["resonance"]["exploration"]);
+            println!("     - Restoration: +{}", quest["rewards"]["resonance"]["restoration"]);
+        } else if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            println!("❌ Sign in to receive quests");
         } else {
             println!("❌ Failed to generate quest");
         }
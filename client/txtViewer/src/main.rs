@@ -4,49 +4,106 @@
 pub mod enhanced_client;
 mod tui;
 
+use clap::{Parser, Subcommand};
 use enhanced_client::EnhancedClient;
 use finalverse_core::*;
-use serde::{Serialize, Deserialize};
+use finalverse_sdk::song::{
+    CoordinatesRequest, MelodyRequest, NoteRequest, PerformMelodyRequest, PerformMelodyResponse, PERFORM_MELODY_PATH,
+};
+use finalverse_sdk::world::REGIONS_PATH;
 use finalverse_protocol::*;
 use std::io::{self, Write};
 use tracing::info;
 use crossterm::{execute, cursor::MoveTo, terminal::{Clear, ClearType}};
 
-#[derive(Serialize)]
-struct NoteRequest {
-    frequency: f32,
-    duration: f32,
-    intensity: f32,
+/// Runs the interactive menu unless given a command; commands do one thing
+/// and exit non-zero if it fails, for scripts and smoke tests.
+#[derive(Parser)]
+#[command(about = "Finalverse text client")]
+struct Cli {
+    /// Use the simplified full-screen interface.
+    #[arg(long)]
+    tui: bool,
+    /// Where the services listen.
+    #[arg(long, default_value = enhanced_client::DEFAULT_BASE_URL)]
+    host: String,
+    /// Player to act as; commands play as a guest unless --password is given too.
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long)]
+    password: Option<String>,
+    /// Print command results as JSON.
+    #[arg(long)]
+    json: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-#[derive(Serialize)]
-struct MelodyRequest {
-    notes: Vec<NoteRequest>,
-    tempo: f32,
-    harmony_type: String,
+#[derive(Subcommand)]
+enum Command {
+    Melody {
+        #[command(subcommand)]
+        action: MelodyCommand,
+    },
+    Region {
+        #[command(subcommand)]
+        action: RegionCommand,
+    },
 }
 
-#[derive(Serialize)]
-struct CoordinatesRequest {
-    x: f32,
-    y: f32,
-    z: f32,
+#[derive(Subcommand)]
+enum MelodyCommand {
+    /// Perform a melody at a location.
+    Perform {
+        /// healing, creation, discovery or courage
+        #[arg(long = "type")]
+        melody_type: String,
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        x: f64,
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        y: f64,
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        z: f64,
+    },
 }
 
-#[derive(Serialize)]
-struct PerformMelodyRequest {
-    player_id: String,
-    melody: MelodyRequest,
-    target_location: CoordinatesRequest,
+#[derive(Subcommand)]
+enum RegionCommand {
+    /// List regions with their harmony and weather.
+    List,
 }
 
-#[derive(Deserialize)]
-struct PerformMelodyResponse {
-    success: bool,
-    resonance_gained: f32,
-    harmony_impact: f32,
-    message: String,
-    effects: Vec<String>,
+async fn run_command(client: &EnhancedClient, command: Command, json: bool) -> anyhow::Result<()> {
+    match command {
+        Command::Melody { action: MelodyCommand::Perform { melody_type, x, y, z } } => {
+            let result = client.perform_melody_at(&melody_type, &Coordinates { x, y, z }).await?;
+            if json {
+                println!("{}", serde_json::to_string(&result)?);
+            } else {
+                println!(
+                    "{} (resonance +{:.1}, harmony {:+.1})",
+                    result.message, result.resonance_gained, result.harmony_impact
+                );
+            }
+        }
+        Command::Region { action: RegionCommand::List } => {
+            let regions = client.list_regions().await?;
+            if json {
+                println!("{}", serde_json::to_string(&regions)?);
+            } else {
+                for region in &regions {
+                    println!(
+                        "{}\t{}\t{:.1}\t{}",
+                        region["id"].as_str().unwrap_or("?"),
+                        region["name"].as_str().unwrap_or("?"),
+                        region["harmony_level"].as_f64().unwrap_or(0.0),
+                        region["weather"]
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 fn print_main_menu() {
@@ -95,7 +152,7 @@ fn print_status(client: &EnhancedClient) {
 
 async fn select_region(client: &mut EnhancedClient) -> anyhow::Result<()> {
     let response = match client.client
-        .get(&format!("{}/regions", client.service_urls["world"]))
+        .get(format!("{}{}", client.service_urls["world"], REGIONS_PATH))
         .send()
         .await {
         Ok(resp) => resp,
@@ -111,42 +168,41 @@ async fn select_region(client: &mut EnhancedClient) -> anyhow::Result<()> {
         let data: serde_json::Value = response.json().await?;
         
         println!("\n🌍 Available Regions:");
-        if let Some(regions) = data["regions"].as_array() {
-            if regions.is_empty() {
-                println!("   No regions available. Creating default region...");
-                client.current_region = Some(RegionId(uuid::Uuid::new_v4()));
+        let regions = regions_from(data);
+        if regions.is_empty() {
+            println!("   No regions available. Creating default region...");
+            client.current_region = Some(RegionId(uuid::Uuid::new_v4()));
+            return Ok(());
+        }
+        
+        for (i, region) in regions.iter().enumerate() {
+            println!("{}. {} (Harmony: {:.1}%, Weather: {})",
+                i + 1,
+                region["name"],
+                region["harmony_level"],
+                region["weather"]
+            );
+        }
+        
+        print!("\nSelect region (number): ");
+        io::stdout().flush().unwrap();
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        
+        if let Ok(index) = input.trim().parse::<usize>() {
+            if index > 0 && index <= regions.len() {
+                let region_id = regions[index - 1]["id"].as_str().unwrap();
+                client.current_region = Some(RegionId(uuid::Uuid::parse_str(region_id)?));
+                println!("✅ Selected region: {}", regions[index - 1]["name"]);
                 return Ok(());
             }
-            
-            for (i, region) in regions.iter().enumerate() {
-                println!("{}. {} (Harmony: {:.1}%, Weather: {})",
-                    i + 1,
-                    region["name"],
-                    region["harmony_level"],
-                    region["weather"]
-                );
-            }
-            
-            print!("\nSelect region (number): ");
-            io::stdout().flush().unwrap();
-            
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            
-            if let Ok(index) = input.trim().parse::<usize>() {
-                if index > 0 && index <= regions.len() {
-                    let region_id = regions[index - 1]["id"].as_str().unwrap();
-                    client.current_region = Some(RegionId(uuid::Uuid::parse_str(region_id)?));
-                    println!("✅ Selected region: {}", regions[index - 1]["name"]);
-                    return Ok(());
-                }
-            }
-            
-            // Default to first region if invalid selection
-            let region_id = regions[0]["id"].as_str().unwrap();
-            client.current_region = Some(RegionId(uuid::Uuid::parse_str(region_id)?));
-            println!("✅ Selected default region: {}", regions[0]["name"]);
         }
+        
+        // Default to first region if invalid selection
+        let region_id = regions[0]["id"].as_str().unwrap();
+        client.current_region = Some(RegionId(uuid::Uuid::parse_str(region_id)?));
+        println!("✅ Selected default region: {}", regions[0]["name"]);
     } else {
         println!("❌ Failed to get regions. Using default.");
         client.current_region = Some(RegionId(uuid::Uuid::new_v4()));
@@ -157,31 +213,60 @@ async fn select_region(client: &mut EnhancedClient) -> anyhow::Result<()> {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
     // Simple logging without complex formatting
     tracing_subscriber::fmt()
         .with_target(false)
         .with_level(false)
         .init();
+
+    if let Some(command) = cli.command {
+        let name = cli.name.unwrap_or_else(|| "guest".to_string());
+        let mut client = EnhancedClient::new(name, &cli.host);
+        if let Some(password) = &cli.password {
+            client.sign_in(password).await?;
+        }
+        return run_command(&client, command, cli.json).await;
+    }
     
     println!("╔════════════════════════════════════════╗");
     println!("║     🌟 Welcome to Finalverse! 🌟       ║");
     println!("║  Where Stories Meet Infinite Worlds    ║");
     println!("╚════════════════════════════════════════╝");
     
-    print!("\nEnter your name, Songweaver: ");
-    io::stdout().flush().unwrap();
-    
-    let mut player_name = String::new();
-    io::stdin().read_line(&mut player_name)?;
-    let player_name = player_name.trim().to_string();
+    let player_name = match cli.name {
+        Some(name) => name,
+        None => {
+            print!("\nEnter your name, Songweaver: ");
+            io::stdout().flush().unwrap();
+            let mut player_name = String::new();
+            io::stdin().read_line(&mut player_name)?;
+            player_name.trim().to_string()
+        }
+    };
     
-    let mut client = EnhancedClient::new(player_name.clone());
+    let password = match cli.password {
+        Some(password) => password,
+        None => {
+            print!("Password: ");
+            io::stdout().flush().unwrap();
+            let mut password = String::new();
+            io::stdin().read_line(&mut password)?;
+            password.trim().to_string()
+        }
+    };
+
+    let mut client = EnhancedClient::new(player_name.clone(), &cli.host);
+    if let Err(e) = client.sign_in(&password).await {
+        println!("⚠️  Could not sign in ({}); playing as a guest this time.", e);
+    }
     println!("\n✨ Welcome, {}!", player_name);
     println!("Your unique ID: {}", client.player_id.0);
 
     // If the user requested TUI mode via `--tui`, run the simplified TUI and
     // exit afterwards.
-    if std::env::args().any(|a| a == "--tui") {
+    if cli.tui {
         tui::run_tui(&mut client).await?;
         return Ok(());
     }
@@ -438,6 +523,12 @@ impl EnhancedClient {
     }
     
     pub async fn perform_melody(&self, melody_type: &str) -> anyhow::Result<()> {
+        self.perform_melody_at(melody_type, &Coordinates { x: 100.0, y: 50.0, z: 200.0 }).await?;
+        println!("\n🎵 Melody performed!");
+        Ok(())
+    }
+
+    async fn perform_melody_at(&self, melody_type: &str, target: &Coordinates) -> anyhow::Result<PerformMelodyResponse> {
         let (harmony_type, power) = match melody_type {
             "healing" => ("restoration", 10.0),
             "creation" => ("creative", 20.0),
@@ -452,55 +543,66 @@ impl EnhancedClient {
             intensity: 1.0,
         }];
 
+        let token = self
+            .access_token
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Sign in to perform melodies"))?;
+
         let request = PerformMelodyRequest {
-            player_id: self.player_id.0.to_string(),
             melody: MelodyRequest {
                 notes,
                 tempo: 120.0,
                 harmony_type: harmony_type.to_string(),
             },
-            target_location: CoordinatesRequest { x: 100.0, y: 50.0, z: 200.0 },
+            target_location: CoordinatesRequest { x: target.x as f32, y: target.y as f32, z: target.z as f32 },
+            dry_run: false,
         };
         
         let response = self.client
-            .post(&format!("{}/melody", self.service_urls["song"]))
+            .post(format!("{}{}", self.service_urls["song"], PERFORM_MELODY_PATH))
+            .bearer_auth(token)
             .json(&request)
             .send()
             .await?;
         
         if response.status().is_success() {
-            let _result: PerformMelodyResponse = response.json().await?;
-            println!("\n🎵 Melody performed!");
+            Ok(response.json().await?)
         } else {
-            return Err(anyhow::anyhow!("Server returned error: {}", response.status()));
+            Err(anyhow::anyhow!("Server returned error: {}", response.status()))
         }
-        
-        Ok(())
+    }
+
+    async fn list_regions(&self) -> anyhow::Result<Vec<serde_json::Value>> {
+        let data: serde_json::Value = self.client
+            .get(format!("{}{}", self.service_urls["world"], REGIONS_PATH))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(regions_from(data))
     }
     
     pub async fn view_world_state(&self) -> anyhow::Result<()> {
         let response = self.client
-            .get(&format!("{}/regions", self.service_urls["world"]))
+            .get(format!("{}{}", self.service_urls["world"], REGIONS_PATH))
             .send()
             .await?;
         
         if response.status().is_success() {
             let data: serde_json::Value = response.json().await?;
             println!("\n🌍 World State:");
-            println!("   Cosmic Time: {}", data["cosmic_time"]);
             
-            if let Some(regions) = data["regions"].as_array() {
-                for region in regions {
-                    println!("\n   Region: {}", region["name"]);
-                    println!("   - Harmony: {:.1}%", region["harmony_level"]);
-                    println!("   - Weather: {:?}", region["weather"]);
-                    println!("   - Active Players: {}", region["active_players"]);
-                    
-                    // Check if this is our current region
-                    if let Some(current) = &self.current_region {
-                        if region["id"].as_str() == Some(&current.0.to_string()) {
-                            println!("   📍 You are here!");
-                        }
+            for region in regions_from(data) {
+                println!("\n   Region: {}", region["name"]);
+                println!("   - Harmony: {:.1}%", region["harmony_level"]);
+                println!("   - Weather: {:?}", region["weather"]);
+                println!("   - Active Players: {}", region["active_players"]);
+                
+                // Check if this is our current region
+                if let Some(current) = &self.current_region {
+                    if region["id"].as_str() == Some(&current.0.to_string()) {
+                        println!("   📍 You are here!");
                     }
                 }
             }
@@ -532,4 +634,29 @@ impl EnhancedClient {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// World-engine lists regions as a bare array.
+fn regions_from(data: serde_json::Value) -> Vec<serde_json::Value> {
+    match data {
+        serde_json::Value::Array(regions) => regions,
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_come_from_the_bare_array_world_engine_returns() {
+        let data = serde_json::json!([
+            { "id": "0b7e8f9a-2c1d-4e5f-8a9b-0c1d2e3f4a5b", "harmony_level": 0.8, "weather": { "weather_type": "Clear" } },
+            { "id": "1c8f9a0b-3d2e-4f6a-9b0c-1d2e3f4a5b6c", "harmony_level": 0.4, "weather": { "weather_type": "Rain" } },
+        ]);
+
+        let regions = regions_from(data);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[1]["harmony_level"], 0.4);
+        assert!(regions_from(serde_json::json!({ "regions": [] })).is_empty());
+    }
+}
@@ -167,4 +167,16 @@ pub enum AudioQuality {
     Medium,  // 128kbps
     High,    // 256kbps
     Lossless, // FLAC
-}
\ No newline at end of file
+}
+
+impl AudioQuality {
+    /// Target bitrate in kbps; `None` for lossless streams.
+    pub fn bitrate_kbps(&self) -> Option<u32> {
+        match self {
+            AudioQuality::Low => Some(64),
+            AudioQuality::Medium => Some(128),
+            AudioQuality::High => Some(256),
+            AudioQuality::Lossless => None,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// crates/auth/src/axum.rs
+//! Axum middleware: layer player routes with [`require_player`] and take
+//! an [`AuthenticatedPlayer`] argument in their handlers.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{AuthError, AuthenticatedPlayer, PlayerAuth};
+
+/// Reject requests without a valid player token, and make the verified
+/// player available to the handler.
+///
+/// ```ignore
+/// Router::new()
+///     .route("/api/melody/perform", post(perform_melody))
+///     .route_layer(axum::middleware::from_fn_with_state(auth, require_player))
+/// ```
+pub async fn require_player(State(auth): State<PlayerAuth>, mut request: Request, next: Next) -> Result<Response, AuthError> {
+    let authorization = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    let player = auth.authenticate(authorization)?;
+    request.extensions_mut().insert(player);
+    Ok(next.run(request).await)
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AuthenticatedPlayer {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<AuthenticatedPlayer>().cloned().ok_or(AuthError::MissingToken)
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AuthError::WrongTenant { .. } => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
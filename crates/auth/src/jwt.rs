@@ -0,0 +1,120 @@
+// crates/auth/src/jwt.rs
+//! HS256 JSON Web Tokens signed with the shared `security.jwt_secret`.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use finalverse_config::SecurityConfig;
+use finalverse_core::{PlayerId, TenantId};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::AuthError;
+
+/// `{"alg":"HS256","typ":"JWT"}`, the only header we issue or accept.
+const HEADER: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    /// The player (account) the token was issued to.
+    pub sub: Uuid,
+    /// The gateway session, so revoking a session can reject its tokens.
+    pub sid: Uuid,
+    #[serde(default)]
+    pub tenant: TenantId,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+impl Claims {
+    pub fn player_id(&self) -> PlayerId {
+        PlayerId(self.sub)
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.exp, 0).unwrap_or_default()
+    }
+}
+
+/// Issues and verifies access tokens. Every service built from the same
+/// config shares the secret, so a token from the gateway verifies anywhere.
+#[derive(Clone)]
+pub struct JwtAuth {
+    secret: Vec<u8>,
+    ttl: Duration,
+}
+
+impl std::fmt::Debug for JwtAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtAuth").field("ttl", &self.ttl).finish_non_exhaustive()
+    }
+}
+
+impl JwtAuth {
+    pub fn new(secret: impl AsRef<[u8]>, ttl: Duration) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+            ttl,
+        }
+    }
+
+    pub fn from_config(security: &SecurityConfig) -> Self {
+        Self::new(&security.jwt_secret, Duration::hours(security.jwt_expiration_hours as i64))
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// A token for `player` in `session`, valid for the configured lifetime.
+    pub fn issue(&self, player: PlayerId, session: Uuid, tenant: &TenantId) -> (String, Claims) {
+        let now = Utc::now();
+        let claims = Claims {
+            sub: player.0,
+            sid: session,
+            tenant: tenant.clone(),
+            iat: now.timestamp(),
+            exp: (now + self.ttl).timestamp(),
+        };
+        (self.encode(&claims), claims)
+    }
+
+    pub fn encode(&self, claims: &Claims) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).expect("claims serialize"));
+        let signing_input = format!("{}.{}", HEADER, payload);
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&signing_input).finalize().into_bytes());
+        format!("{}.{}", signing_input, signature)
+    }
+
+    /// Check the signature and expiry and return the token's claims.
+    pub fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(AuthError::Malformed);
+        };
+        if header != HEADER {
+            return Err(AuthError::Malformed);
+        }
+
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| AuthError::Malformed)?;
+        self.mac(&token[..header.len() + 1 + payload.len()])
+            .verify_slice(&signature)
+            .map_err(|_| AuthError::BadSignature)?;
+
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| AuthError::Malformed)?;
+        let claims: Claims = serde_json::from_slice(&payload).map_err(|_| AuthError::Malformed)?;
+        if claims.exp <= Utc::now().timestamp() {
+            return Err(AuthError::Expired);
+        }
+        Ok(claims)
+    }
+
+    fn mac(&self, signing_input: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(signing_input.as_bytes());
+        mac
+    }
+}
@@ -0,0 +1,142 @@
+// crates/auth/src/lib.rs
+//! Player authentication shared by every HTTP service.
+//!
+//! The API gateway issues a signed access token at login; services verify
+//! it locally with the same secret and learn which player is calling
+//! without a round trip to the gateway. Enable the `axum` or `warp`
+//! feature for the matching middleware.
+
+pub mod jwt;
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+#[cfg(feature = "warp")]
+pub mod warp;
+
+pub use jwt::{Claims, JwtAuth};
+
+use finalverse_core::{PlayerId, TenantId};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AuthError {
+    #[error("Missing bearer token")]
+    MissingToken,
+
+    #[error("Malformed token")]
+    Malformed,
+
+    #[error("Invalid token signature")]
+    BadSignature,
+
+    #[error("Token expired")]
+    Expired,
+
+    #[error("Token was issued for tenant {token}, not {service}")]
+    WrongTenant { token: TenantId, service: TenantId },
+}
+
+/// The verified caller of a player request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthenticatedPlayer {
+    pub player_id: PlayerId,
+    pub session_id: Uuid,
+    pub tenant: TenantId,
+}
+
+impl From<Claims> for AuthenticatedPlayer {
+    fn from(claims: Claims) -> Self {
+        Self {
+            player_id: claims.player_id(),
+            session_id: claims.sid,
+            tenant: claims.tenant,
+        }
+    }
+}
+
+/// Verifies player tokens for one service, which only accepts tokens
+/// issued for the tenant it serves.
+#[derive(Debug, Clone)]
+pub struct PlayerAuth {
+    tokens: Arc<JwtAuth>,
+    tenant: TenantId,
+}
+
+impl PlayerAuth {
+    pub fn new(tokens: JwtAuth, tenant: TenantId) -> Self {
+        Self {
+            tokens: Arc::new(tokens),
+            tenant,
+        }
+    }
+
+    /// Secret from the default config, tenant from `FINALVERSE_TENANT_ID`.
+    pub fn load() -> Self {
+        let security = finalverse_config::load_default_config()
+            .map(|config| config.security)
+            .unwrap_or_default();
+        Self::new(JwtAuth::from_config(&security), TenantId::from_env())
+    }
+
+    /// Authenticate an `Authorization` header value.
+    pub fn authenticate(&self, authorization: Option<&str>) -> Result<AuthenticatedPlayer, AuthError> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AuthError::MissingToken)?;
+        let claims = self.tokens.verify(token.trim())?;
+        if claims.tenant != self.tenant {
+            return Err(AuthError::WrongTenant {
+                token: claims.tenant,
+                service: self.tenant.clone(),
+            });
+        }
+        Ok(claims.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn only_untampered_current_tokens_for_our_tenant_pass() {
+        let tokens = JwtAuth::new("a-test-secret-that-is-at-least-32-chars", Duration::hours(1));
+        let auth = PlayerAuth::new(tokens.clone(), TenantId::default());
+        let player = PlayerId(Uuid::new_v4());
+        let session = Uuid::new_v4();
+
+        let (token, claims) = tokens.issue(player.clone(), session, &TenantId::default());
+        let bearer = format!("Bearer {}", token);
+        let caller = auth.authenticate(Some(&bearer)).unwrap();
+        assert_eq!(caller.player_id, player);
+        assert_eq!(caller.session_id, session);
+
+        assert_eq!(auth.authenticate(None), Err(AuthError::MissingToken));
+        assert_eq!(auth.authenticate(Some(&token)), Err(AuthError::MissingToken));
+        assert_eq!(auth.authenticate(Some("Bearer not-a-token")), Err(AuthError::Malformed));
+
+        // Changing the claims invalidates the signature
+        let forged = tokens.encode(&Claims { sub: Uuid::new_v4(), ..claims.clone() });
+        let signature = token.rsplit('.').next().unwrap();
+        let forged = format!("{}.{}", forged.rsplit_once('.').unwrap().0, signature);
+        assert_eq!(auth.authenticate(Some(&format!("Bearer {}", forged))), Err(AuthError::BadSignature));
+
+        let other_secret = JwtAuth::new("another-secret-that-is-at-least-32-chars", Duration::hours(1));
+        let foreign = other_secret.encode(&claims);
+        assert_eq!(auth.authenticate(Some(&format!("Bearer {}", foreign))), Err(AuthError::BadSignature));
+
+        let expired = tokens.encode(&Claims { exp: claims.iat - 1, ..claims.clone() });
+        assert_eq!(auth.authenticate(Some(&format!("Bearer {}", expired))), Err(AuthError::Expired));
+
+        let staging = TenantId::parse("staging").unwrap();
+        let (other_tenant, _) = tokens.issue(player, session, &staging);
+        assert!(matches!(
+            auth.authenticate(Some(&format!("Bearer {}", other_tenant))),
+            Err(AuthError::WrongTenant { .. })
+        ));
+    }
+}
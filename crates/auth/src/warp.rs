@@ -0,0 +1,34 @@
+// crates/auth/src/warp.rs
+//! Warp filters: add [`player`] to player routes and [`recover`] to the
+//! combined routes so auth failures become JSON errors.
+
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+use crate::{AuthError, AuthenticatedPlayer, PlayerAuth};
+
+impl warp::reject::Reject for AuthError {}
+
+/// Extracts the verified player, rejecting requests without a valid token.
+pub fn player(auth: PlayerAuth) -> impl Filter<Extract = (AuthenticatedPlayer,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization").and_then(move |authorization: Option<String>| {
+        let result = auth.authenticate(authorization.as_deref());
+        async move { result.map_err(warp::reject::custom) }
+    })
+}
+
+/// Turns an [`AuthError`] rejection into a 401/403 response; other
+/// rejections pass through untouched.
+pub async fn recover(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    let Some(error) = rejection.find::<AuthError>() else {
+        return Err(rejection);
+    };
+    let status = match error {
+        AuthError::WrongTenant { .. } => StatusCode::FORBIDDEN,
+        _ => StatusCode::UNAUTHORIZED,
+    };
+    Ok(warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "error": error.to_string() })),
+        status,
+    ))
+}
@@ -0,0 +1,96 @@
+// crates/chaos/src/event_bus.rs
+use async_trait::async_trait;
+use finalverse_events::GameEventBus;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::FaultInjector;
+
+/// Event bus wrapper that drops a share of published events according to
+/// the active chaos scenarios.
+pub struct ChaosEventBus {
+    inner: Arc<dyn GameEventBus>,
+    injector: FaultInjector,
+}
+
+impl ChaosEventBus {
+    pub fn new(inner: Arc<dyn GameEventBus>, injector: FaultInjector) -> Self {
+        Self { inner, injector }
+    }
+}
+
+/// `bus` wrapped in a [`ChaosEventBus`] when an injector is active,
+/// otherwise `bus` itself.
+pub fn wrap_event_bus(bus: Arc<dyn GameEventBus>, injector: Option<&FaultInjector>) -> Arc<dyn GameEventBus> {
+    match injector {
+        Some(injector) => Arc::new(ChaosEventBus::new(bus, injector.clone())),
+        None => bus,
+    }
+}
+
+#[async_trait]
+impl GameEventBus for ChaosEventBus {
+    async fn publish_raw(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        if self.injector.should_drop_event(topic) {
+            debug!("Chaos dropped event on {}", topic);
+            return Ok(());
+        }
+        self.inner.publish_raw(topic, payload).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        topic: &str,
+        handler: Box<dyn Fn(Vec<u8>) + Send + Sync + 'static>,
+    ) -> anyhow::Result<String> {
+        self.inner.subscribe_raw(topic, handler).await
+    }
+
+    async fn unsubscribe(&self, subscription_id: &str) -> anyhow::Result<()> {
+        self.inner.unsubscribe(subscription_id).await
+    }
+
+    async fn debug_info(&self) -> serde_json::Value {
+        serde_json::json!({ "chaos": true, "inner": self.inner.debug_info().await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{injector, scenario};
+    use finalverse_config::ChaosScenario;
+    use finalverse_events::LocalEventBus;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn delivered(bus: &dyn GameEventBus, topic: &str) -> usize {
+        let count = Arc::new(AtomicUsize::new(0));
+        let seen = count.clone();
+        bus.subscribe_raw(topic, Box::new(move |_| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        }))
+        .await
+        .unwrap();
+        bus.publish_raw(topic, b"{}".to_vec()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        count.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn drops_events_on_targeted_topics() {
+        let injector = injector(vec![ChaosScenario {
+            event_drop_rate: 1.0,
+            ..scenario("quiet-harmony", "events.harmony")
+        }]);
+        let bus = wrap_event_bus(Arc::new(LocalEventBus::new()), Some(&injector));
+        assert_eq!(delivered(bus.as_ref(), "events.harmony").await, 0);
+        assert_eq!(delivered(bus.as_ref(), "events.world").await, 1);
+    }
+
+    #[tokio::test]
+    async fn leaves_the_bus_alone_without_an_injector() {
+        let bus = wrap_event_bus(Arc::new(LocalEventBus::new()), None);
+        assert_eq!(delivered(bus.as_ref(), "events.harmony").await, 1);
+        assert!(bus.debug_info().await.get("chaos").is_none());
+    }
+}
@@ -0,0 +1,192 @@
+// crates/chaos/src/lib.rs
+//! Controlled fault injection for rehearsing partial outages.
+//!
+//! Scenarios come from the `[chaos]` section of `FinalverseConfig`. Every
+//! entry point refuses to activate when the configured environment is
+//! production, so shipping a chaos-enabled build cannot harm live players.
+
+pub mod event_bus;
+
+pub use event_bus::{wrap_event_bus, ChaosEventBus};
+
+use finalverse_config::{ChaosConfig, ChaosScenario, Environment, FinalverseConfig};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+#[derive(Error, Debug)]
+pub enum ChaosError {
+    #[error("Injected fault from scenario {scenario} for {target}")]
+    InjectedFault { scenario: String, target: String },
+}
+
+/// Applies configured faults to calls made against named services.
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector {
+    scenarios: Vec<ChaosScenario>,
+}
+
+impl FaultInjector {
+    /// Build an injector from a chaos config. Returns `None` when chaos is
+    /// disabled or the environment is production.
+    pub fn from_config(chaos: &ChaosConfig, environment: &Environment) -> Option<Self> {
+        if !chaos.is_active(environment) {
+            if chaos.enabled {
+                warn!("Chaos scenarios ignored in {:?} environment", environment);
+            }
+            return None;
+        }
+
+        Some(Self {
+            scenarios: chaos.scenarios.clone(),
+        })
+    }
+
+    /// Convenience wrapper around [`FaultInjector::from_config`] for a full configuration.
+    pub fn from_finalverse_config(config: &FinalverseConfig) -> Option<Self> {
+        Self::from_config(&config.chaos, &config.general.environment)
+    }
+
+    /// Injector for the config file named by `FINALVERSE_CONFIG`, for
+    /// services wiring chaos into their clients and event bus at startup.
+    pub fn from_default_config() -> Option<Self> {
+        let config = finalverse_config::load_default_config().ok()?;
+        Self::from_finalverse_config(&config)
+    }
+
+    pub fn scenarios(&self) -> &[ChaosScenario] {
+        &self.scenarios
+    }
+
+    /// Scenarios that apply to the given service.
+    pub fn scenarios_for<'a>(&'a self, target: &'a str) -> impl Iterator<Item = &'a ChaosScenario> + 'a {
+        self.scenarios
+            .iter()
+            .filter(move |s| s.target == "*" || s.target == target)
+    }
+
+    /// Delay and possibly fail a call to `target`. Call this before sending
+    /// the actual request.
+    pub async fn before_call(&self, target: &str) -> Result<(), ChaosError> {
+        let latency: u64 = self.scenarios_for(target).map(|s| s.latency_ms).sum();
+        if latency > 0 {
+            tokio::time::sleep(Duration::from_millis(latency)).await;
+        }
+
+        for scenario in self.scenarios_for(target) {
+            if roll(scenario.error_rate) {
+                warn!("💥 Chaos scenario {} failing call to {}", scenario.name, target);
+                return Err(ChaosError::InjectedFault {
+                    scenario: scenario.name.clone(),
+                    target: target.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether an event published on `topic` should be silently dropped.
+    /// Scenarios match the topic against their target, so `events.harmony`
+    /// can be targeted directly or everything via `*`.
+    pub fn should_drop_event(&self, topic: &str) -> bool {
+        self.scenarios_for(topic).any(|s| roll(s.event_drop_rate))
+    }
+
+    /// Services paired with the interval at which they should be killed.
+    pub fn kill_schedule(&self) -> Vec<(String, Duration)> {
+        self.scenarios
+            .iter()
+            .filter_map(|s| {
+                s.kill_interval_secs
+                    .map(|secs| (s.target.clone(), Duration::from_secs(secs)))
+            })
+            .collect()
+    }
+}
+
+fn roll(rate: f32) -> bool {
+    rate > 0.0 && rand::random::<f32>() < rate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn scenario(name: &str, target: &str) -> ChaosScenario {
+        ChaosScenario {
+            name: name.to_string(),
+            target: target.to_string(),
+            latency_ms: 0,
+            error_rate: 0.0,
+            event_drop_rate: 0.0,
+            kill_interval_secs: None,
+        }
+    }
+
+    pub(crate) fn injector(scenarios: Vec<ChaosScenario>) -> FaultInjector {
+        let chaos = ChaosConfig { enabled: true, scenarios };
+        FaultInjector::from_config(&chaos, &Environment::Development).unwrap()
+    }
+
+    #[test]
+    fn refuses_production_and_disabled_configs() {
+        let mut chaos = ChaosConfig {
+            enabled: true,
+            scenarios: vec![scenario("outage", "*")],
+        };
+        assert!(FaultInjector::from_config(&chaos, &Environment::Production).is_none());
+        assert!(FaultInjector::from_config(&chaos, &Environment::Staging).is_some());
+        chaos.enabled = false;
+        assert!(FaultInjector::from_config(&chaos, &Environment::Development).is_none());
+    }
+
+    #[test]
+    fn scenarios_match_their_target_or_wildcard() {
+        let injector = injector(vec![scenario("all", "*"), scenario("songs", "song-engine")]);
+        let names = |target| injector.scenarios_for(target).map(|s| s.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names("song-engine"), ["all", "songs"]);
+        assert_eq!(names("world-engine"), ["all"]);
+    }
+
+    #[tokio::test]
+    async fn before_call_fails_targeted_calls_only() {
+        let injector = injector(vec![ChaosScenario {
+            error_rate: 1.0,
+            ..scenario("songs-down", "song-engine")
+        }]);
+        let err = injector.before_call("song-engine").await.unwrap_err();
+        assert!(matches!(err, ChaosError::InjectedFault { scenario, .. } if scenario == "songs-down"));
+        assert!(injector.before_call("world-engine").await.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn before_call_adds_latency_from_every_matching_scenario() {
+        let injector = injector(vec![
+            ChaosScenario { latency_ms: 200, ..scenario("slow", "*") },
+            ChaosScenario { latency_ms: 300, ..scenario("slower", "song-engine") },
+        ]);
+        let started = tokio::time::Instant::now();
+        injector.before_call("song-engine").await.unwrap();
+        assert_eq!(started.elapsed(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn drops_events_by_topic() {
+        let injector = injector(vec![ChaosScenario {
+            event_drop_rate: 1.0,
+            ..scenario("quiet-harmony", "events.harmony")
+        }]);
+        assert!(injector.should_drop_event("events.harmony"));
+        assert!(!injector.should_drop_event("events.world"));
+    }
+
+    #[test]
+    fn kill_schedule_lists_scenarios_with_an_interval() {
+        let injector = injector(vec![
+            ChaosScenario { kill_interval_secs: Some(30), ..scenario("crashy", "song-engine") },
+            scenario("slow", "*"),
+        ]);
+        assert_eq!(injector.kill_schedule(), [("song-engine".to_string(), Duration::from_secs(30))]);
+    }
+}
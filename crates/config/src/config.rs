@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 
+use crate::sections::{AiOrchestraSection, GatewaySection, SongEngineSection, WorldEngineSection};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FinalverseConfig {
     pub general: GeneralConfig,
@@ -17,6 +19,20 @@ pub struct FinalverseConfig {
     pub monitoring: MonitoringConfig,
     pub game: GameConfig,
     pub grpc_services: GrpcServiceRegistry,
+    #[serde(default)]
+    pub chaos: ChaosConfig,
+    #[serde(default)]
+    pub tenancy: TenancyConfig,
+    #[serde(default)]
+    pub event_bus: EventBusConfig,
+    #[serde(default)]
+    pub song_engine: SongEngineSection,
+    #[serde(default)]
+    pub ai_orchestra: AiOrchestraSection,
+    #[serde(default)]
+    pub world_engine: WorldEngineSection,
+    #[serde(default)]
+    pub gateway: GatewaySection,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +67,48 @@ pub struct NetworkConfig {
     pub connection_timeout_secs: u64,
     pub enable_http3: bool,      // Enable QUIC/HTTP3
     pub enable_webtransport: bool, // Enable WebTransport protocol
+    #[serde(default)]
+    pub interest: InterestConfig,
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+}
+
+/// What the realtime gateway streams to each client: entities within
+/// `radius` of the player, sent as deltas at most `update_rate_hz` times a
+/// second.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InterestConfig {
+    pub radius: f32,         // World units around the player
+    pub update_rate_hz: f32, // Entity updates per client per second
+}
+
+impl Default for InterestConfig {
+    fn default() -> Self {
+        Self {
+            radius: 150.0,
+            update_rate_hz: 10.0,
+        }
+    }
+}
+
+/// How the gateways detect dead sockets: a ping every
+/// `ping_interval_secs`, and a connection that sends nothing (not even a
+/// pong) for `idle_timeout_secs` is dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    pub ping_interval_secs: u64,
+    pub idle_timeout_secs: u64, // Must exceed the ping interval
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval_secs: 15,
+            idle_timeout_secs: 45,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +116,10 @@ pub struct ServicesConfig {
     pub service_mesh: ServiceMeshConfig,
     pub service_discovery: ServiceDiscoveryConfig,
     pub internal_services: InternalServicesConfig,
+    #[serde(default)]
+    pub outbound_http: OutboundHttpConfig,
+    #[serde(default)]
+    pub processes: HashMap<String, ManagedProcessConfig>, // Service name -> process the server manager runs
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +148,82 @@ pub struct InternalServicesConfig {
     pub default_retries: u32,
 }
 
+/// Policy applied by the shared HTTP client to every service-to-service call.
+/// Timeouts and retries default to `internal_services` unless overridden
+/// per target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutboundHttpConfig {
+    pub connect_timeout_ms: u64,
+    pub retry_backoff_ms: u64,
+    pub retry_budget_ratio: f64,        // Retries allowed per request sent (0.0-1.0)
+    pub propagate_headers: Vec<String>, // Inbound headers forwarded on outbound calls; add
+                                        // "authorization" only where callees should act as the caller
+    pub targets: HashMap<String, TargetHttpPolicy>,
+}
+
+impl Default for OutboundHttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 1000,
+            retry_backoff_ms: 100,
+            retry_budget_ratio: 0.2,
+            propagate_headers: vec![
+                "traceparent".to_string(),
+                "tracestate".to_string(),
+                "x-request-id".to_string(),
+            ],
+            targets: HashMap::new(),
+        }
+    }
+}
+
+/// A service the server manager launches as a child process and restarts
+/// when it exits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedProcessConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub restart: RestartPolicyConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    Never,
+    #[default]
+    OnFailure,
+    Always,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicyConfig {
+    pub mode: RestartMode,
+    pub max_retries: u32,    // Restarts in a row before the service is left down
+    pub backoff_ms: u64,     // Delay before the first restart, doubled for each one after
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RestartPolicyConfig {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::OnFailure,
+            max_retries: 5,
+            backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TargetHttpPolicy {
+    pub timeout_ms: Option<u64>,
+    pub max_retries: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceEndpoint {
     pub enabled: bool,
@@ -115,6 +253,74 @@ impl Default for GrpcServiceRegistry {
     }
 }
 
+/// Fault-injection settings used to rehearse partial outages.
+/// Only honoured outside of the production environment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    pub scenarios: Vec<ChaosScenario>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChaosScenario {
+    pub name: String,
+    pub target: String,              // Service name, or "*" for every service
+    #[serde(default)]
+    pub latency_ms: u64,             // Extra latency added to each call
+    #[serde(default)]
+    pub error_rate: f32,             // Probability (0.0-1.0) that a call fails
+    #[serde(default)]
+    pub event_drop_rate: f32,        // Probability (0.0-1.0) that an event is dropped
+    #[serde(default)]
+    pub kill_interval_secs: Option<u64>, // Periodically kill the target service
+}
+
+/// Isolated game worlds hosted alongside the default one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenancyConfig {
+    pub tenants: Vec<TenantConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantConfig {
+    pub id: String,                          // Lowercase letters, digits and '-'
+    #[serde(default)]
+    pub hosts: Vec<String>,                  // Host names routed to this tenant by the gateway
+    #[serde(default)]
+    pub services: HashMap<String, String>,   // Service name -> base URL of the tenant's deployment
+}
+
+/// Delivery guarantees of the NATS event bus. Publishes and handlers that
+/// keep failing are retried with exponential backoff, then dead-lettered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventBusConfig {
+    pub max_attempts: u32,           // Tries per publish or delivery, including the first
+    pub initial_backoff_ms: u64,     // Delay before the first retry; doubles after each one
+    pub max_backoff_ms: u64,
+    pub dead_letter_subject: String, // Where events that exhaust their retries are published
+    pub dead_letter_capacity: usize, // Dead letters kept in memory for inspection
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 5_000,
+            dead_letter_subject: "events.dead_letter".to_string(),
+            dead_letter_capacity: 1_000,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Chaos is never permitted in production, regardless of `enabled`.
+    pub fn is_active(&self, environment: &Environment) -> bool {
+        self.enabled && !matches!(environment, Environment::Production)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConfig {
     pub llm_orchestra: LLMConfig,
@@ -231,14 +437,41 @@ pub struct SecurityConfig {
     pub rate_limiting: RateLimitConfig,
     pub encryption: EncryptionConfig,
     pub allowed_origins: Vec<String>,
+    #[serde(default)]
+    pub sessions: SessionPolicyConfig,
+    #[serde(default)]
+    pub operator_token: Option<String>, // Required by /debug endpoints; unset disables them
+}
+
+/// Limits on devices and concurrent sessions linked to one account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionPolicyConfig {
+    pub max_devices_per_account: usize,
+    pub max_concurrent_sessions: usize,
+    pub on_limit: SessionLimitAction,
+    pub refresh_token_ttl_days: u64,
+    pub resume_window_secs: u64,   // How long a dropped realtime connection can be resumed; 0 disables
+    pub max_pending_events: usize, // Events queued for a dropped connection before the oldest are discarded
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLimitAction {
+    KickOldest,  // End the least recently active session
+    Reject,      // Refuse the new login
 }
 
+/// Token-bucket limits applied by every public HTTP service.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RateLimitConfig {
     pub enabled: bool,
-    pub requests_per_minute: u32,
+    pub requests_per_minute: u32,        // Per client IP
     pub burst_size: u32,
-    pub ip_whitelist: Vec<String>,
+    pub ip_whitelist: Vec<String>,       // Exempt from per-IP limits, e.g. co-located services; none by default
+    pub player_requests_per_minute: u32, // Per authenticated player, across all their IPs
+    pub player_burst_size: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -266,6 +499,29 @@ pub struct MonitoringConfig {
     pub tracing_enabled: bool,
     pub tracing_endpoint: String,
     pub log_sampling_rate: f32,
+    #[serde(default)]
+    pub log_files: LogFilesConfig,
+}
+
+/// Per-service log files written by the server manager.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogFilesConfig {
+    pub enabled: bool,
+    pub dir: String,
+    pub max_bytes: u64,    // Size a file grows to before it is rotated
+    pub max_files: usize,  // Rotated files kept per service
+}
+
+impl Default for LogFilesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: "logs".to_string(),
+            max_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -274,6 +530,10 @@ pub struct GameConfig {
     pub harmony_settings: HarmonySettings,
     pub echo_settings: EchoSettings,
     pub event_settings: EventSettings,
+    #[serde(default)]
+    pub stamina: StaminaSettings,
+    #[serde(default)]
+    pub melody_limits: MelodyLimitSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -283,6 +543,30 @@ pub struct WorldSettings {
     pub day_night_cycle_minutes: u32,
     pub weather_change_probability: f32,
     pub ecosystem_update_rate_seconds: u32,
+    #[serde(default)]
+    pub region_diffusion: RegionDiffusionSettings,
+    /// JSON file listing where each region lies; see
+    /// `finalverse_core::region_map` for the format.
+    #[serde(default)]
+    pub region_map: Option<String>,
+}
+
+/// Share of the gap to the neighbours' average that a region closes each
+/// simulation tick; 0 keeps regions independent, 1 levels them at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegionDiffusionSettings {
+    pub dissonance_rate: f32,
+    pub political_tension_rate: f32,
+}
+
+impl Default for RegionDiffusionSettings {
+    fn default() -> Self {
+        Self {
+            dissonance_rate: 0.05,
+            political_tension_rate: 0.03,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -309,6 +593,92 @@ pub struct EventSettings {
     pub max_concurrent_events: u32,
 }
 
+/// Songweaving stamina curves. A melody of power `p` costs
+/// `base_cost + cost_per_power * p^cost_exponent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaminaSettings {
+    pub max_stamina: f32,
+    pub base_cost: f32,
+    pub cost_per_power: f32,
+    pub cost_exponent: f32,
+    pub regen_per_second: f32,
+    /// Regeneration multiplier in a region at 0 harmony...
+    pub harmony_regen_min: f32,
+    /// ...and at full (100) harmony, interpolated linearly between.
+    pub harmony_regen_max: f32,
+    /// Extra regeneration multiplier inside a rest location.
+    pub rest_multiplier: f32,
+    pub rest_locations: Vec<RestLocation>,
+}
+
+/// How often a player may perform melodies. Each melody a player
+/// performed in the same region within `repeat_window_seconds` multiplies
+/// the next one's power by `repeat_falloff`, down to
+/// `min_repeat_multiplier`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MelodyLimitSettings {
+    /// Minimum time between two melodies by the same player.
+    pub cooldown_seconds: f32,
+    pub repeat_window_seconds: f32,
+    pub repeat_falloff: f32,
+    pub min_repeat_multiplier: f32,
+}
+
+impl MelodyLimitSettings {
+    /// Power multiplier for a melody after `recent` others in its region.
+    pub fn repeat_multiplier(&self, recent: usize) -> f32 {
+        self.repeat_falloff
+            .powi(i32::try_from(recent).unwrap_or(i32::MAX))
+            .max(self.min_repeat_multiplier)
+    }
+}
+
+impl Default for MelodyLimitSettings {
+    fn default() -> Self {
+        Self {
+            cooldown_seconds: 2.0,
+            repeat_window_seconds: 300.0,
+            repeat_falloff: 0.7,
+            min_repeat_multiplier: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestLocation {
+    pub name: String,
+    pub position: [f32; 3],
+    pub radius: f32,
+}
+
+impl StaminaSettings {
+    pub fn melody_cost(&self, power: f32) -> f32 {
+        self.base_cost + self.cost_per_power * power.max(0.0).powf(self.cost_exponent)
+    }
+
+    /// Regeneration per second given the region's harmony (0-100).
+    pub fn regen_rate(&self, harmony: f32, resting: bool) -> f32 {
+        let t = (harmony / 100.0).clamp(0.0, 1.0);
+        let multiplier = self.harmony_regen_min + (self.harmony_regen_max - self.harmony_regen_min) * t;
+        let rest = if resting { self.rest_multiplier } else { 1.0 };
+        self.regen_per_second * multiplier * rest
+    }
+
+    pub fn rest_location_at(&self, position: [f32; 3]) -> Option<&RestLocation> {
+        self.rest_locations.iter().find(|location| {
+            let distance_sq: f32 = location
+                .position
+                .iter()
+                .zip(position)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum();
+            distance_sq <= location.radius * location.radius
+        })
+    }
+}
+
 impl Default for FinalverseConfig {
     fn default() -> Self {
         Self {
@@ -333,6 +703,8 @@ impl Default for FinalverseConfig {
                 connection_timeout_secs: 30,
                 enable_http3: false,
                 enable_webtransport: false,
+                interest: InterestConfig::default(),
+                heartbeat: HeartbeatConfig::default(),
             },
             services: ServicesConfig {
                 service_mesh: ServiceMeshConfig {
@@ -355,6 +727,8 @@ impl Default for FinalverseConfig {
                     default_timeout_ms: 5000,
                     default_retries: 3,
                 },
+                outbound_http: OutboundHttpConfig::default(),
+                processes: HashMap::new(),
             },
             ai: AIConfig {
                 llm_orchestra: LLMConfig::default(),
@@ -376,6 +750,13 @@ impl Default for FinalverseConfig {
             monitoring: MonitoringConfig::default(),
             game: GameConfig::default(),
             grpc_services: GrpcServiceRegistry::default(),
+            chaos: ChaosConfig::default(),
+            tenancy: TenancyConfig::default(),
+            event_bus: EventBusConfig::default(),
+            song_engine: SongEngineSection::default(),
+            ai_orchestra: AiOrchestraSection::default(),
+            world_engine: WorldEngineSection::default(),
+            gateway: GatewaySection::default(),
         }
     }
 }
@@ -505,6 +886,21 @@ impl Default for SecurityConfig {
             rate_limiting: RateLimitConfig::default(),
             encryption: EncryptionConfig::default(),
             allowed_origins: vec!["*".to_string()],
+            sessions: SessionPolicyConfig::default(),
+            operator_token: None,
+        }
+    }
+}
+
+impl Default for SessionPolicyConfig {
+    fn default() -> Self {
+        Self {
+            max_devices_per_account: 10,
+            max_concurrent_sessions: 3,
+            on_limit: SessionLimitAction::KickOldest,
+            refresh_token_ttl_days: 30,
+            resume_window_secs: 120,
+            max_pending_events: 256,
         }
     }
 }
@@ -516,6 +912,8 @@ impl Default for RateLimitConfig {
             requests_per_minute: 60,
             burst_size: 10,
             ip_whitelist: vec![],
+            player_requests_per_minute: 120,
+            player_burst_size: 20,
         }
     }
 }
@@ -552,6 +950,7 @@ impl Default for MonitoringConfig {
             tracing_enabled: true,
             tracing_endpoint: "http://localhost:14268/api/traces".to_string(),
             log_sampling_rate: 1.0,
+            log_files: LogFilesConfig::default(),
         }
     }
 }
@@ -563,6 +962,28 @@ impl Default for GameConfig {
             harmony_settings: HarmonySettings::default(),
             echo_settings: EchoSettings::default(),
             event_settings: EventSettings::default(),
+            stamina: StaminaSettings::default(),
+            melody_limits: MelodyLimitSettings::default(),
+        }
+    }
+}
+
+impl Default for StaminaSettings {
+    fn default() -> Self {
+        Self {
+            max_stamina: 100.0,
+            base_cost: 2.0,
+            cost_per_power: 1.0,
+            cost_exponent: 1.3,
+            regen_per_second: 0.5,
+            harmony_regen_min: 0.5,
+            harmony_regen_max: 1.5,
+            rest_multiplier: 3.0,
+            rest_locations: vec![RestLocation {
+                name: "Memory Grotto".to_string(),
+                position: [0.0, 0.0, 0.0],
+                radius: 25.0,
+            }],
         }
     }
 }
@@ -575,6 +996,8 @@ impl Default for WorldSettings {
             day_night_cycle_minutes: 60,
             weather_change_probability: 0.1,
             ecosystem_update_rate_seconds: 30,
+            region_diffusion: RegionDiffusionSettings::default(),
+            region_map: None,
         }
     }
 }
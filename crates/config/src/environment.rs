@@ -10,6 +10,15 @@ pub fn apply_env_overrides(config: &mut FinalverseConfig) -> Result<()> {
         config.general.server_name = server_name;
     }
     
+    if let Ok(environment) = env::var("FINALVERSE_ENVIRONMENT") {
+        config.general.environment = match environment.to_lowercase().as_str() {
+            "development" => crate::config::Environment::Development,
+            "staging" => crate::config::Environment::Staging,
+            "production" => crate::config::Environment::Production,
+            _ => return Err(ConfigError::Environment("Invalid FINALVERSE_ENVIRONMENT".to_string())),
+        };
+    }
+    
     if let Ok(debug) = env::var("FINALVERSE_DEBUG") {
         config.general.debug_mode = debug.parse().unwrap_or(false);
     }
@@ -57,8 +66,16 @@ pub fn apply_env_overrides(config: &mut FinalverseConfig) -> Result<()> {
         config.security.jwt_secret = jwt_secret;
     }
     
+    if let Ok(operator_token) = env::var("FINALVERSE_OPERATOR_TOKEN") {
+        config.security.operator_token = Some(operator_token);
+    }
+    
     // AI settings
     apply_ai_env_overrides(&mut config.ai)?;
+
+    if let Ok(prompts_dir) = env::var("AI_PROMPTS_DIR") {
+        config.ai_orchestra.prompts_dir = Some(prompts_dir);
+    }
     
     // Performance settings
     if let Ok(workers) = env::var("FINALVERSE_WORKER_THREADS") {
@@ -4,11 +4,15 @@ pub mod config;
 pub mod loader;
 pub mod validator;
 pub mod environment;
+pub mod sections;
 
 pub use config::*;
 pub use loader::ConfigLoader;
 pub use validator::ConfigValidator;
 pub use environment::apply_env_overrides;
+pub use sections::{
+    AiOrchestraSection, GatewaySection, ServiceSection, SongEngineSection, WorldEngineSection, SERVICE_SECTIONS,
+};
 
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -27,6 +31,12 @@ pub enum ConfigError {
 
     #[error("Environment variable error: {0}")]
     Environment(String),
+
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("No config section for service: {0}")]
+    UnknownService(String),
 }
 
 pub type Result<T> = std::result::Result<T, ConfigError>;
@@ -1,5 +1,5 @@
 use axum::{routing::get, Router, Json};
-use finalverse_config::{load_default_config, GrpcServiceRegistry};
+use finalverse_config::{load_default_config, SERVICE_SECTIONS};
 use std::sync::Arc;
 use std::net::SocketAddr;
 
@@ -7,6 +7,22 @@ use std::net::SocketAddr;
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
     let config = load_default_config()?;
+
+    // `finalverse-config show <service>` prints the merged, validated
+    // config that service runs with; no arguments serves the registry
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => {}
+        ["show", service] => {
+            print!("{}", toml::to_string_pretty(&config.for_service(service)?)?);
+            return Ok(());
+        }
+        _ => anyhow::bail!(
+            "usage: finalverse-config [show <service>]\nservices: {}",
+            SERVICE_SECTIONS.join(", ")
+        ),
+    }
+
     let registry = Arc::new(config.grpc_services);
     let app = Router::new().route(
         "/services/grpc",
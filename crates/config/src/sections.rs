@@ -0,0 +1,235 @@
+// finalverse-config/src/sections.rs
+//! Settings owned by a single service, each read from its own table
+//! (`[song_engine]`, `[ai_orchestra]`, `[world_engine]`, `[gateway]`).
+//! Every section has defaults, so a config file only names what it changes,
+//! and validates itself as part of `ConfigValidator::validate`.
+
+use crate::{ConfigError, FinalverseConfig, Result};
+use serde::{Deserialize, Serialize};
+
+pub trait ServiceSection: Serialize + Default {
+    /// The table the section is read from.
+    const NAME: &'static str;
+
+    fn validate(&self) -> Result<()>;
+}
+
+/// Every section's table name, in the order they are validated.
+pub const SERVICE_SECTIONS: [&str; 4] = [
+    SongEngineSection::NAME,
+    AiOrchestraSection::NAME,
+    WorldEngineSection::NAME,
+    GatewaySection::NAME,
+];
+
+fn invalid(section: &str, message: &str) -> ConfigError {
+    ConfigError::Validation(format!("[{}] {}", section, message))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SongEngineSection {
+    pub http_port: u16,
+    pub max_melody_notes: usize, // Longer melodies are rejected
+}
+
+impl Default for SongEngineSection {
+    fn default() -> Self {
+        Self {
+            http_port: 3001,
+            max_melody_notes: 64,
+        }
+    }
+}
+
+impl ServiceSection for SongEngineSection {
+    const NAME: &'static str = "song_engine";
+
+    fn validate(&self) -> Result<()> {
+        if self.http_port == 0 {
+            return Err(invalid(Self::NAME, "HTTP port cannot be 0"));
+        }
+        if self.max_melody_notes == 0 {
+            return Err(invalid(Self::NAME, "Max melody notes must be greater than 0"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AiOrchestraSection {
+    pub http_port: u16,
+    pub prompts_dir: Option<String>,    // Overridden by AI_PROMPTS_DIR
+    pub request_timeout_secs: u64,      // Per call to an LLM provider
+    pub max_concurrent_requests: usize, // Provider calls in flight; more wait their turn
+}
+
+impl Default for AiOrchestraSection {
+    fn default() -> Self {
+        Self {
+            http_port: 3004,
+            prompts_dir: None,
+            request_timeout_secs: 30,
+            max_concurrent_requests: 16,
+        }
+    }
+}
+
+impl ServiceSection for AiOrchestraSection {
+    const NAME: &'static str = "ai_orchestra";
+
+    fn validate(&self) -> Result<()> {
+        if self.http_port == 0 {
+            return Err(invalid(Self::NAME, "HTTP port cannot be 0"));
+        }
+        if self.prompts_dir.as_deref().is_some_and(|dir| dir.trim().is_empty()) {
+            return Err(invalid(Self::NAME, "Prompts directory cannot be empty; omit it to use the built-in prompts"));
+        }
+        if self.request_timeout_secs == 0 {
+            return Err(invalid(Self::NAME, "Request timeout must be greater than 0"));
+        }
+        if self.max_concurrent_requests == 0 {
+            return Err(invalid(Self::NAME, "Max concurrent requests must be greater than 0"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldEngineSection {
+    pub http_port: u16,
+    pub grpc_port: u16, // Overridden by WORLD_ENGINE_GRPC_PORT
+    pub ecosystem_rollup_secs: u64,
+    pub event_timeline: Option<String>, // JSON list of scripted world events
+    pub item_catalog: Option<String>, // JSON items and recipes; built-in starter set if unset
+}
+
+impl Default for WorldEngineSection {
+    fn default() -> Self {
+        Self {
+            http_port: 3002,
+            grpc_port: 3003,
+            ecosystem_rollup_secs: 60,
+            event_timeline: None,
+            item_catalog: None,
+        }
+    }
+}
+
+impl ServiceSection for WorldEngineSection {
+    const NAME: &'static str = "world_engine";
+
+    fn validate(&self) -> Result<()> {
+        if self.http_port == 0 || self.grpc_port == 0 {
+            return Err(invalid(Self::NAME, "Ports cannot be 0"));
+        }
+        if self.http_port == self.grpc_port {
+            return Err(invalid(Self::NAME, "HTTP and gRPC ports must differ"));
+        }
+        if self.ecosystem_rollup_secs == 0 {
+            return Err(invalid(Self::NAME, "Ecosystem rollup interval must be greater than 0"));
+        }
+        if self.event_timeline.as_deref().is_some_and(|path| path.trim().is_empty()) {
+            return Err(invalid(Self::NAME, "Event timeline path cannot be empty; omit it to start with no scripted events"));
+        }
+        if self.item_catalog.as_deref().is_some_and(|path| path.trim().is_empty()) {
+            return Err(invalid(Self::NAME, "Item catalog path cannot be empty; omit it to use the built-in items"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewaySection {
+    pub websocket_port: u16,
+    pub max_message_bytes: usize,
+    pub wire_formats: Vec<String>, // Formats clients may negotiate: json, msgpack
+}
+
+impl Default for GatewaySection {
+    fn default() -> Self {
+        Self {
+            websocket_port: 3000,
+            max_message_bytes: 64 * 1024,
+            wire_formats: vec!["json".to_string(), "msgpack".to_string()],
+        }
+    }
+}
+
+impl ServiceSection for GatewaySection {
+    const NAME: &'static str = "gateway";
+
+    fn validate(&self) -> Result<()> {
+        if self.websocket_port == 0 {
+            return Err(invalid(Self::NAME, "WebSocket port cannot be 0"));
+        }
+        if self.max_message_bytes == 0 {
+            return Err(invalid(Self::NAME, "Max message size must be greater than 0"));
+        }
+        let valid_formats = ["json", "msgpack"];
+        if let Some(format) = self.wire_formats.iter().find(|format| !valid_formats.contains(&format.as_str())) {
+            return Err(invalid(
+                Self::NAME,
+                &format!("Invalid wire format: {}. Must be one of: {:?}", format, valid_formats),
+            ));
+        }
+        if !self.wire_formats.iter().any(|format| format == "json") {
+            return Err(invalid(Self::NAME, "JSON must stay enabled for clients that don't negotiate"));
+        }
+        Ok(())
+    }
+}
+
+impl FinalverseConfig {
+    /// The config a service runs with: the shared sections plus its own,
+    /// without the sections of other services. Accepts `song-engine` as
+    /// well as `song_engine`.
+    pub fn for_service(&self, service: &str) -> Result<toml::Value> {
+        let section = service.replace('-', "_");
+        if !SERVICE_SECTIONS.contains(&section.as_str()) {
+            return Err(ConfigError::UnknownService(service.to_string()));
+        }
+        let mut value = toml::Value::try_from(self)?;
+        if let Some(table) = value.as_table_mut() {
+            table.retain(|key, _| section == key || !SERVICE_SECTIONS.contains(&key));
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigValidator;
+
+    #[test]
+    fn service_view_keeps_shared_sections_and_its_own() {
+        let config = FinalverseConfig::default();
+        let view = config.for_service("world-engine").unwrap();
+        let table = view.as_table().unwrap();
+        assert!(table.contains_key("network"));
+        assert_eq!(table["world_engine"]["grpc_port"].as_integer(), Some(3003));
+        assert!(!table.contains_key("song_engine"));
+        assert!(!table.contains_key("gateway"));
+        assert!(matches!(config.for_service("harmony"), Err(ConfigError::UnknownService(_))));
+    }
+
+    #[test]
+    fn sections_are_validated_with_the_config() {
+        let mut config = FinalverseConfig::default();
+        config.gateway.wire_formats = vec!["protobuf".to_string()];
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        let mut config = FinalverseConfig::default();
+        config.world_engine.grpc_port = config.world_engine.http_port;
+        assert!(ConfigValidator::validate(&config).is_err());
+
+        // Unnamed fields keep their defaults
+        let song_engine: SongEngineSection = toml::from_str("max_melody_notes = 0").unwrap();
+        assert_eq!(song_engine.http_port, 3001);
+        assert!(song_engine.validate().is_err());
+    }
+}
@@ -1,6 +1,6 @@
 // finalverse-config/src/validator.rs
 
-use crate::{FinalverseConfig, ConfigError, Result};
+use crate::{FinalverseConfig, ConfigError, Result, ServiceSection};
 use std::collections::HashSet;
 
 pub struct ConfigValidator;
@@ -18,6 +18,15 @@ impl ConfigValidator {
         Self::validate_performance(&config.performance)?;
         Self::validate_monitoring(&config.monitoring)?;
         Self::validate_game(&config.game)?;
+        Self::validate_chaos(&config.chaos, &config.general.environment)?;
+        Self::validate_tenancy(&config.tenancy)?;
+        Self::validate_event_bus(&config.event_bus)?;
+
+        // Per-service sections validate themselves
+        config.song_engine.validate()?;
+        config.ai_orchestra.validate()?;
+        config.world_engine.validate()?;
+        config.gateway.validate()?;
         
         Ok(())
     }
@@ -71,6 +80,24 @@ impl ConfigValidator {
         if network.connection_timeout_secs == 0 {
             return Err(ConfigError::Validation("Connection timeout must be greater than 0".to_string()));
         }
+
+        if network.interest.radius <= 0.0 {
+            return Err(ConfigError::Validation("Interest radius must be greater than 0".to_string()));
+        }
+
+        if network.interest.update_rate_hz <= 0.0 {
+            return Err(ConfigError::Validation("Interest update rate must be greater than 0".to_string()));
+        }
+
+        if network.heartbeat.ping_interval_secs == 0 {
+            return Err(ConfigError::Validation("Heartbeat ping interval must be greater than 0".to_string()));
+        }
+
+        if network.heartbeat.idle_timeout_secs <= network.heartbeat.ping_interval_secs {
+            return Err(ConfigError::Validation(
+                "Heartbeat idle timeout must be longer than the ping interval".to_string(),
+            ));
+        }
         
         Ok(())
     }
@@ -105,6 +132,34 @@ impl ConfigValidator {
             return Err(ConfigError::Validation("Default timeout cannot be 0".to_string()));
         }
         
+        // Validate outbound HTTP policy
+        let outbound = &services.outbound_http;
+        if !(0.0..=1.0).contains(&outbound.retry_budget_ratio) {
+            return Err(ConfigError::Validation(
+                "Outbound retry budget ratio must be between 0.0 and 1.0".to_string()
+            ));
+        }
+        for (target, policy) in &outbound.targets {
+            if policy.timeout_ms == Some(0) {
+                return Err(ConfigError::Validation(
+                    format!("Outbound timeout for {} cannot be 0", target)
+                ));
+            }
+        }
+
+        for (name, process) in &services.processes {
+            if process.command.trim().is_empty() {
+                return Err(ConfigError::Validation(
+                    format!("Process for {} needs a command", name)
+                ));
+            }
+            if process.restart.backoff_ms > process.restart.max_backoff_ms {
+                return Err(ConfigError::Validation(
+                    format!("Restart backoff for {} exceeds its maximum", name)
+                ));
+            }
+        }
+        
         Ok(())
     }
     
@@ -215,7 +270,32 @@ impl ConfigValidator {
         if security.rate_limiting.enabled && security.rate_limiting.requests_per_minute == 0 {
             return Err(ConfigError::Validation("Rate limit requests per minute must be greater than 0".to_string()));
         }
+
+        if security.rate_limiting.enabled && security.rate_limiting.player_requests_per_minute == 0 {
+            return Err(ConfigError::Validation("Per-player rate limit must be greater than 0".to_string()));
+        }
+
+        if let Some(ip) = security
+            .rate_limiting
+            .ip_whitelist
+            .iter()
+            .find(|ip| ip.parse::<std::net::IpAddr>().is_err())
+        {
+            return Err(ConfigError::Validation(format!("Rate limit whitelist entry {} is not an IP address", ip)));
+        }
         
+        if security.sessions.max_concurrent_sessions == 0 || security.sessions.max_devices_per_account == 0 {
+            return Err(ConfigError::Validation("Session and device limits must be greater than 0".to_string()));
+        }
+        
+        if security.sessions.refresh_token_ttl_days == 0 {
+            return Err(ConfigError::Validation("Refresh token TTL must be greater than 0".to_string()));
+        }
+
+        if security.sessions.resume_window_secs > 0 && security.sessions.max_pending_events == 0 {
+            return Err(ConfigError::Validation("Resumable sessions need room for at least one pending event".to_string()));
+        }
+
         Ok(())
     }
     
@@ -247,6 +327,10 @@ impl ConfigValidator {
         if monitoring.log_sampling_rate < 0.0 || monitoring.log_sampling_rate > 1.0 {
             return Err(ConfigError::Validation("Log sampling rate must be between 0.0 and 1.0".to_string()));
         }
+
+        if monitoring.log_files.enabled && monitoring.log_files.max_bytes == 0 {
+            return Err(ConfigError::Validation("Log file size limit cannot be 0".to_string()));
+        }
         
         Ok(())
     }
@@ -265,6 +349,11 @@ impl ConfigValidator {
            game.world_settings.weather_change_probability > 1.0 {
             return Err(ConfigError::Validation("Weather change probability must be between 0.0 and 1.0".to_string()));
         }
+
+        let diffusion = &game.world_settings.region_diffusion;
+        if !(0.0..=1.0).contains(&diffusion.dissonance_rate) || !(0.0..=1.0).contains(&diffusion.political_tension_rate) {
+            return Err(ConfigError::Validation("Region diffusion rates must be between 0.0 and 1.0".to_string()));
+        }
         
         // Validate harmony settings
         if game.harmony_settings.max_attunement_level == 0 {
@@ -280,6 +369,42 @@ impl ConfigValidator {
             return Err(ConfigError::Validation("Max bond level must be greater than 0".to_string()));
         }
         
+        // Validate stamina curves
+        let stamina = &game.stamina;
+        if stamina.max_stamina <= 0.0 {
+            return Err(ConfigError::Validation("Max stamina must be greater than 0".to_string()));
+        }
+
+        if stamina.base_cost < 0.0 || stamina.cost_per_power < 0.0 || stamina.cost_exponent <= 0.0 {
+            return Err(ConfigError::Validation(
+                "Stamina costs must not be negative and the cost exponent must be positive".to_string(),
+            ));
+        }
+
+        if stamina.regen_per_second < 0.0 || stamina.harmony_regen_min < 0.0 || stamina.harmony_regen_min > stamina.harmony_regen_max {
+            return Err(ConfigError::Validation(
+                "Stamina regeneration must not be negative and harmony_regen_min must not exceed harmony_regen_max".to_string(),
+            ));
+        }
+
+        if stamina.rest_multiplier < 1.0 {
+            return Err(ConfigError::Validation("Rest multiplier must be at least 1.0".to_string()));
+        }
+
+        // Validate melody limits
+        let limits = &game.melody_limits;
+        if limits.cooldown_seconds < 0.0 || limits.repeat_window_seconds < 0.0 {
+            return Err(ConfigError::Validation(
+                "Melody cooldown and repeat window must not be negative".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&limits.repeat_falloff) || !(0.0..=1.0).contains(&limits.min_repeat_multiplier) {
+            return Err(ConfigError::Validation(
+                "Melody repeat falloff and minimum multiplier must be between 0 and 1".to_string(),
+            ));
+        }
+
         // Validate event settings
         if game.event_settings.max_concurrent_events == 0 {
             return Err(ConfigError::Validation("Max concurrent events must be greater than 0".to_string()));
@@ -287,6 +412,85 @@ impl ConfigValidator {
         
         Ok(())
     }
+    
+    fn validate_event_bus(event_bus: &crate::config::EventBusConfig) -> Result<()> {
+        if event_bus.max_attempts == 0 {
+            return Err(ConfigError::Validation("Event bus max attempts must be at least 1".to_string()));
+        }
+
+        if event_bus.initial_backoff_ms > event_bus.max_backoff_ms {
+            return Err(ConfigError::Validation(
+                "Event bus initial backoff cannot exceed the maximum backoff".to_string()
+            ));
+        }
+
+        if event_bus.dead_letter_subject.is_empty() {
+            return Err(ConfigError::Validation("Event bus dead-letter subject cannot be empty".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn validate_tenancy(tenancy: &crate::config::TenancyConfig) -> Result<()> {
+        let mut ids = HashSet::new();
+        let mut hosts = HashSet::new();
+        for tenant in &tenancy.tenants {
+            let valid_id = !tenant.id.is_empty()
+                && tenant.id.len() <= 32
+                && !tenant.id.starts_with('-')
+                && tenant.id.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+            if !valid_id || tenant.id == "default" {
+                return Err(ConfigError::Validation(format!("Invalid tenant id: {:?}", tenant.id)));
+            }
+
+            if !ids.insert(tenant.id.as_str()) {
+                return Err(ConfigError::Validation(format!("Duplicate tenant: {}", tenant.id)));
+            }
+
+            for host in &tenant.hosts {
+                if !hosts.insert(host.to_ascii_lowercase()) {
+                    return Err(ConfigError::Validation(format!("Host {} is routed to more than one tenant", host)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_chaos(chaos: &crate::config::ChaosConfig, environment: &crate::config::Environment) -> Result<()> {
+        if !chaos.enabled {
+            return Ok(());
+        }
+        
+        if matches!(environment, crate::config::Environment::Production) {
+            return Err(ConfigError::Validation("Chaos scenarios cannot be enabled in production".to_string()));
+        }
+        
+        let mut names = HashSet::new();
+        for scenario in &chaos.scenarios {
+            if scenario.name.is_empty() || scenario.target.is_empty() {
+                return Err(ConfigError::Validation("Chaos scenario name and target cannot be empty".to_string()));
+            }
+            
+            if !names.insert(scenario.name.as_str()) {
+                return Err(ConfigError::Validation(format!("Duplicate chaos scenario: {}", scenario.name)));
+            }
+            
+            if !(0.0..=1.0).contains(&scenario.error_rate) || !(0.0..=1.0).contains(&scenario.event_drop_rate) {
+                return Err(ConfigError::Validation(
+                    format!("Chaos scenario {} rates must be between 0.0 and 1.0", scenario.name)
+                ));
+            }
+            
+            if scenario.kill_interval_secs == Some(0) {
+                return Err(ConfigError::Validation(
+                    format!("Chaos scenario {} kill interval must be greater than 0", scenario.name)
+                ));
+            }
+        }
+        
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -312,4 +516,35 @@ mod tests {
         config.security.jwt_secret = "short".to_string();
         assert!(ConfigValidator::validate(&config).is_err());
     }
+    
+    #[test]
+    fn test_validate_chaos_rejected_in_production() {
+        let mut config = FinalverseConfig::default();
+        config.chaos.enabled = true;
+        assert!(ConfigValidator::validate(&config).is_ok());
+        
+        config.general.environment = crate::config::Environment::Production;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+    
+    #[test]
+    fn test_validate_stamina_regen_range() {
+        let mut config = FinalverseConfig::default();
+        config.game.stamina.harmony_regen_min = 2.0;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_melody_repeat_falloff() {
+        let mut config = FinalverseConfig::default();
+        config.game.melody_limits.repeat_falloff = 1.5;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_heartbeat_timeout_exceeds_interval() {
+        let mut config = FinalverseConfig::default();
+        config.network.heartbeat.idle_timeout_secs = config.network.heartbeat.ping_interval_secs;
+        assert!(ConfigValidator::validate(&config).is_err());
+    }
 }
@@ -0,0 +1,74 @@
+// crates/core/src/annotations.rs
+//! Player-placed markers and notes that persist in the world. Each grid
+//! cell's annotations form one [`AnnotationDoc`], so shards only exchange
+//! the cells that changed.
+
+use crate::crdt::AddWinsSet;
+use crate::{Coordinates, RegionId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Side length of an annotation grid cell, in world units.
+pub const ANNOTATION_GRID_SIZE: f64 = 100.0;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AnnotationGrid {
+    pub region_id: RegionId,
+    pub x: i32,
+    pub y: i32,
+}
+
+impl AnnotationGrid {
+    pub fn containing(region_id: RegionId, position: &Coordinates) -> Self {
+        Self {
+            region_id,
+            x: (position.x / ANNOTATION_GRID_SIZE).floor() as i32,
+            y: (position.y / ANNOTATION_GRID_SIZE).floor() as i32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationKind {
+    Marker,
+    Note,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum AnnotationVisibility {
+    Public,
+    /// Only members of the ensemble see it.
+    Ensemble { ensemble_id: String },
+    /// Only the author sees it.
+    Private,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: Uuid,
+    pub author_id: Uuid,
+    pub grid: AnnotationGrid,
+    pub position: Coordinates,
+    pub kind: AnnotationKind,
+    pub text: String,
+    pub visibility: AnnotationVisibility,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Annotation {
+    pub fn visible_to(&self, player_id: Uuid, ensemble_id: Option<&str>) -> bool {
+        match &self.visibility {
+            AnnotationVisibility::Public => true,
+            AnnotationVisibility::Ensemble { ensemble_id: owner } => {
+                self.author_id == player_id || ensemble_id == Some(owner.as_str())
+            }
+            AnnotationVisibility::Private => self.author_id == player_id,
+        }
+    }
+}
+
+/// The annotations in one grid cell, keyed by annotation id.
+pub type AnnotationDoc = AddWinsSet<Uuid, Annotation>;
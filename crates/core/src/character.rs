@@ -2,6 +2,9 @@
 use crate::types::Coordinates as Position;
 use uuid::Uuid;
 use crate::echo::{Echo, InteractionRecord};
+use crate::infection::SilenceInfection;
+use crate::stamina::Stamina;
+use crate::tenant::TenantId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,6 +12,9 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Character {
     pub id: Uuid,
+    /// The world the character lives in.
+    #[serde(default)]
+    pub tenant_id: TenantId,
     pub name: String,
     pub character_type: CharacterType,
     pub position: Position,
@@ -19,6 +25,11 @@ pub struct Character {
     pub companion: Option<Companion>,
     pub personal_story: PersonalStory,
     pub appearance: CharacterAppearance,
+    /// Silence exposure, saved and restored with the character.
+    #[serde(default)]
+    pub silence_infection: SilenceInfection,
+    #[serde(default)]
+    pub stamina: Stamina,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -556,6 +567,9 @@ impl Character {
                     "Musical notation tattoo on left wrist".to_string(),
                 ],
             },
+            silence_infection: SilenceInfection::default(),
+            stamina: Stamina::default(),
+            tenant_id: TenantId::default(),
         }
     }
 
@@ -634,6 +648,9 @@ impl Character {
                 distinguishing_features: vec![],
                 cultural_markers: vec![],
             },
+            silence_infection: SilenceInfection::default(),
+            stamina: Stamina::default(),
+            tenant_id: TenantId::default(),
         }
     }
 
@@ -0,0 +1,198 @@
+// crates/core/src/crdt.rs
+//! Delta-state CRDTs for data edited concurrently on several shards. Every
+//! change returns a delta that is itself a (small) state; shards exchange
+//! deltas, and merging them in any order, any number of times, converges.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+/// One event on one replica.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Dot {
+    pub replica: Uuid,
+    pub counter: u64,
+}
+
+/// The dots a replica has seen: a contiguous prefix per replica plus any
+/// received out of order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CausalContext {
+    clock: BTreeMap<Uuid, u64>,
+    cloud: BTreeSet<Dot>,
+}
+
+impl CausalContext {
+    pub fn contains(&self, dot: &Dot) -> bool {
+        self.clock.get(&dot.replica).is_some_and(|counter| dot.counter <= *counter) || self.cloud.contains(dot)
+    }
+
+    fn next(&mut self, replica: Uuid) -> Dot {
+        let counter = self.clock.entry(replica).or_insert(0);
+        *counter += 1;
+        Dot { replica, counter: *counter }
+    }
+
+    fn insert(&mut self, dot: Dot) {
+        if !self.contains(&dot) {
+            self.cloud.insert(dot);
+            self.compact();
+        }
+    }
+
+    fn merge(&mut self, other: &CausalContext) {
+        for (replica, counter) in &other.clock {
+            let ours = self.clock.entry(*replica).or_insert(0);
+            *ours = (*ours).max(*counter);
+        }
+        self.cloud.extend(other.cloud.iter().copied());
+        self.compact();
+    }
+
+    /// Fold cloud dots that extend a replica's prefix into the clock.
+    fn compact(&mut self) {
+        for dot in std::mem::take(&mut self.cloud) {
+            let counter = self.clock.entry(dot.replica).or_insert(0);
+            if dot.counter == *counter + 1 {
+                *counter += 1;
+            } else if dot.counter > *counter {
+                self.cloud.insert(dot);
+            }
+        }
+    }
+}
+
+/// A keyed add-wins set. A remove only cancels the adds it has observed,
+/// so an add made concurrently with a remove survives the merge. When one
+/// key is written concurrently on two replicas, the write with the highest
+/// dot is the one read back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize, V: Serialize",
+    deserialize = "K: Deserialize<'de> + Ord, V: Deserialize<'de>"
+))]
+pub struct AddWinsSet<K, V> {
+    entries: BTreeMap<K, BTreeMap<Dot, V>>,
+    context: CausalContext,
+}
+
+impl<K: Ord + Clone, V: Clone> Default for AddWinsSet<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            context: CausalContext::default(),
+        }
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> AddWinsSet<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or overwrite `key` on `replica`. Returns the delta to ship.
+    pub fn insert(&mut self, replica: Uuid, key: K, value: V) -> Self {
+        let dot = self.context.next(replica);
+        let mut delta = Self::new();
+        if let Some(previous) = self.entries.get(&key) {
+            for dot in previous.keys() {
+                delta.context.insert(*dot);
+            }
+        }
+        delta.context.insert(dot);
+        delta.entries.insert(key.clone(), BTreeMap::from([(dot, value.clone())]));
+        self.entries.insert(key, BTreeMap::from([(dot, value)]));
+        delta
+    }
+
+    /// Remove `key`. Returns the delta to ship, or `None` if it wasn't present.
+    pub fn remove(&mut self, key: &K) -> Option<Self> {
+        let removed = self.entries.remove(key)?;
+        let mut delta = Self::new();
+        for dot in removed.keys() {
+            delta.context.insert(*dot);
+        }
+        Some(delta)
+    }
+
+    /// Merge a delta or full state from another replica.
+    pub fn merge(&mut self, other: &Self) {
+        let keys: BTreeSet<K> = self.entries.keys().chain(other.entries.keys()).cloned().collect();
+        for key in keys {
+            let ours = self.entries.remove(&key).unwrap_or_default();
+            let theirs = other.entries.get(&key);
+            let mut merged: BTreeMap<Dot, V> = ours
+                .into_iter()
+                .filter(|(dot, _)| theirs.is_some_and(|theirs| theirs.contains_key(dot)) || !other.context.contains(dot))
+                .collect();
+            for (dot, value) in theirs.into_iter().flatten() {
+                if !self.context.contains(dot) {
+                    merged.insert(*dot, value.clone());
+                }
+            }
+            if !merged.is_empty() {
+                self.entries.insert(key, merged);
+            }
+        }
+        self.context.merge(&other.context);
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).and_then(|writes| writes.values().next_back())
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries
+            .iter()
+            .filter_map(|(key, writes)| writes.values().next_back().map(|value| (key, value)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_add_wins_over_remove_in_any_merge_order() {
+        let (a_id, b_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut a: AddWinsSet<&str, u32> = AddWinsSet::new();
+        let mut b: AddWinsSet<&str, u32> = AddWinsSet::new();
+
+        let first = a.insert(a_id, "cairn", 1);
+        b.merge(&first);
+
+        // A removes what it has seen while B concurrently re-adds
+        let removal = a.remove(&"cairn").unwrap();
+        let readd = b.insert(b_id, "cairn", 2);
+        let other = b.insert(b_id, "note", 7);
+
+        a.merge(&readd);
+        a.merge(&other);
+        b.merge(&removal);
+        // Redelivery and stale deltas change nothing
+        a.merge(&first);
+        b.merge(&removal);
+
+        for replica in [&a, &b] {
+            assert_eq!(replica.get(&"cairn"), Some(&2));
+            assert_eq!(replica.get(&"note"), Some(&7));
+            assert_eq!(replica.len(), 2);
+        }
+
+        let removal = b.remove(&"note").unwrap();
+        a.merge(&removal);
+        assert!(!a.contains_key(&"note"));
+    }
+}
@@ -7,6 +7,7 @@ use super::{Repository, RepositoryError};
 use crate::database::connection::DbConnection;
 use crate::database::schema::worlds;
 use crate::models::world_state::{WorldState, WorldSong, WorldStatistics};
+use crate::tenant::TenantId;
 use diesel::prelude::*;
 use uuid::Uuid;
 use chrono::Utc;
@@ -20,11 +21,12 @@ impl WorldRepository {
         Self
     }
 
-    /// Find world by name
-    pub fn find_by_name(&self, conn: &mut DbConnection, name: &str) -> Result<WorldState, RepositoryError> {
+    /// Find a tenant's world by name
+    pub fn find_by_name(&self, conn: &mut DbConnection, tenant: &TenantId, name: &str) -> Result<WorldState, RepositoryError> {
         use crate::database::schema::worlds::dsl;
 
         let record = dsl::worlds
+            .filter(dsl::tenant_id.eq(tenant.as_str()))
             .filter(dsl::name.eq(name))
             .first::<WorldRecord>(conn)?;
 
@@ -68,6 +70,7 @@ impl WorldRepository {
     fn record_to_model(&self, record: WorldRecord) -> WorldState {
         WorldState {
             id: record.id,
+            tenant_id: TenantId::parse(&record.tenant_id).unwrap_or_default(),
             name: record.name,
             world_song: serde_json::from_value(record.world_song).unwrap_or_default(),
             global_harmony: record.global_harmony,
@@ -85,6 +88,7 @@ impl WorldRepository {
     /// Convert domain model to database record
     fn model_to_record(&self, model: &WorldState) -> NewWorldRecord {
         NewWorldRecord {
+            tenant_id: model.tenant_id.to_string(),
             name: model.name.clone(),
             world_song: serde_json::to_value(&model.world_song).unwrap(),
             global_harmony: model.global_harmony,
@@ -175,6 +179,7 @@ impl Repository for WorldRepository {
 #[derive(Queryable, Debug)]
 struct WorldRecord {
     id: Uuid,
+    tenant_id: String,
     name: String,
     world_song: serde_json::Value,
     global_harmony: f32,
@@ -189,6 +194,7 @@ struct WorldRecord {
 #[derive(Insertable)]
 #[diesel(table_name = worlds)]
 struct NewWorldRecord {
+    tenant_id: String,
     name: String,
     world_song: serde_json::Value,
     global_harmony: f32,
@@ -12,6 +12,7 @@ use serde_json::Value as JsonValue;
 table! {
     worlds (id) {
         id -> Uuid,
+        tenant_id -> Varchar,
         name -> Varchar,
         world_song -> Jsonb,
         global_harmony -> Float4,
@@ -56,6 +57,7 @@ table! {
 table! {
     entities (id) {
         id -> Uuid,
+        tenant_id -> Varchar,
         entity_type -> Varchar,
         entity_data -> Jsonb,
         grid_id -> Nullable<Uuid>,
@@ -71,6 +73,7 @@ table! {
 table! {
     players (id) {
         id -> Uuid,
+        tenant_id -> Varchar,
         account_id -> Uuid,
         character_name -> Varchar,
         entity_id -> Uuid,
@@ -88,6 +91,7 @@ table! {
 table! {
     events (id) {
         id -> Uuid,
+        tenant_id -> Varchar,
         event_type -> Varchar,
         event_data -> Jsonb,
         source -> Jsonb,
@@ -114,6 +118,7 @@ table! {
 table! {
     npc_memories (id) {
         id -> Uuid,
+        tenant_id -> Varchar,
         npc_id -> Uuid,
         memory_type -> Varchar,
         memory_data -> Jsonb,
@@ -1,71 +1,119 @@
 use thiserror::Error;
 use axum::http::StatusCode;
 
+/// Errors a service reports to its callers. Each variant maps to one HTTP
+/// status and one gRPC code, so axum, warp and tonic services answer the
+/// same failure the same way.
 #[derive(Error, Debug)]
 pub enum FinalverseError {
-    #[error("Song weaving failed: {0}")]
-    SongweavingFailed(String),
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
 
-    #[error("Echo not found: {0}")]
-    EchoNotFound(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
 
-    #[error("Player not found: {0}")]
-    PlayerNotFound(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
 
-    #[error("Region not found: {0}")]
-    RegionNotFound(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
 
-    #[error("Insufficient resonance: required {required}, available {available}")]
-    InsufficientResonance { required: f32, available: f32 },
+    #[error("Rate limited: {message}")]
+    RateLimited { message: String, retry_after_secs: Option<u64> },
 
-    #[error("Invalid melody structure: {0}")]
-    InvalidMelody(String),
+    /// A service this one depends on failed or could not be reached.
+    #[error("{service} failed: {message}")]
+    Upstream { service: String, message: String },
 
-    #[error("Silence corruption detected: {0}")]
-    SilenceCorruption(String),
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
 
     #[error("Network error: {0}")]
-    NetworkError(#[from] reqwest::Error),
+    Network(#[from] reqwest::Error),
 
     #[error("Serialization error: {0}")]
-    SerializationError(#[from] serde_json::Error),
+    Serialization(#[from] serde_json::Error),
 
     #[error("UUID parsing error: {0}")]
-    UuidError(#[from] uuid::Error),
-
-    #[error("Database error: {0}")]
-    DatabaseError(String),
-
-    #[error("AI service error: {0}")]
-    AIServiceError(String),
-
-    #[error("Service error: {0}")]
-    ServiceError(String),
+    Uuid(#[from] uuid::Error),
+}
 
-    #[error("Permission denied: {0}")]
-    PermissionDenied(String),
+impl FinalverseError {
+    pub fn upstream(service: impl Into<String>, error: impl std::fmt::Display) -> Self {
+        FinalverseError::Upstream { service: service.into(), message: error.to_string() }
+    }
 
-    #[error("Resource not available: {0}")]
-    ResourceUnavailable(String),
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            FinalverseError::BadRequest(_) | FinalverseError::Serialization(_) | FinalverseError::Uuid(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            FinalverseError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            FinalverseError::Forbidden(_) => StatusCode::FORBIDDEN,
+            FinalverseError::NotFound(_) => StatusCode::NOT_FOUND,
+            FinalverseError::Conflict(_) => StatusCode::CONFLICT,
+            FinalverseError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            FinalverseError::Upstream { .. } | FinalverseError::Network(_) => StatusCode::BAD_GATEWAY,
+            FinalverseError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            FinalverseError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
 
-    #[error("Invalid configuration: {0}")]
-    InvalidConfiguration(String),
+    /// Stable name for the kind of error, sent alongside the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FinalverseError::BadRequest(_) | FinalverseError::Serialization(_) | FinalverseError::Uuid(_) => {
+                "bad_request"
+            }
+            FinalverseError::Unauthorized(_) => "unauthorized",
+            FinalverseError::Forbidden(_) => "forbidden",
+            FinalverseError::NotFound(_) => "not_found",
+            FinalverseError::Conflict(_) => "conflict",
+            FinalverseError::RateLimited { .. } => "rate_limited",
+            FinalverseError::Upstream { .. } | FinalverseError::Network(_) => "upstream",
+            FinalverseError::Unavailable(_) => "unavailable",
+            FinalverseError::Internal(_) => "internal",
+        }
+    }
 
-    #[error("Bad request: {0}")]
-    BadRequest(String),
+    /// The JSON body every transport sends.
+    pub fn body(&self) -> serde_json::Value {
+        let mut body = serde_json::json!({ "error": self.to_string(), "code": self.code() });
+        match self {
+            FinalverseError::Upstream { service, .. } => body["service"] = service.clone().into(),
+            FinalverseError::RateLimited { retry_after_secs: Some(secs), .. } => body["retry_after_secs"] = (*secs).into(),
+            _ => {}
+        }
+        body
+    }
 
-    #[error("Internal server error: {0}")]
-    InternalServerError(String),
+    fn retry_after(&self) -> Option<u64> {
+        match self {
+            FinalverseError::RateLimited { retry_after_secs, .. } => *retry_after_secs,
+            _ => None,
+        }
+    }
 }
 
 // Implement From for StatusCode to make error handling easier
 impl From<StatusCode> for FinalverseError {
     fn from(status: StatusCode) -> Self {
+        let message = status.canonical_reason().unwrap_or("HTTP error").to_string();
         match status {
-            StatusCode::BAD_REQUEST => FinalverseError::BadRequest("Bad request".to_string()),
-            StatusCode::NOT_FOUND => FinalverseError::ResourceUnavailable("Resource not found".to_string()),
-            StatusCode::INTERNAL_SERVER_ERROR => FinalverseError::InternalServerError("Internal server error".to_string()),
-            _ => FinalverseError::ServiceError(format!("HTTP error: {}", status)),
+            StatusCode::BAD_REQUEST => FinalverseError::BadRequest(message),
+            StatusCode::UNAUTHORIZED => FinalverseError::Unauthorized(message),
+            StatusCode::FORBIDDEN => FinalverseError::Forbidden(message),
+            StatusCode::NOT_FOUND => FinalverseError::NotFound(message),
+            StatusCode::CONFLICT => FinalverseError::Conflict(message),
+            StatusCode::TOO_MANY_REQUESTS => FinalverseError::RateLimited { message, retry_after_secs: None },
+            StatusCode::SERVICE_UNAVAILABLE => FinalverseError::Unavailable(message),
+            _ => FinalverseError::Internal(format!("HTTP error: {}", status)),
         }
     }
 }
@@ -75,18 +123,81 @@ impl axum::response::IntoResponse for FinalverseError {
     fn into_response(self) -> axum::response::Response {
         use axum::response::Json;
 
-        let (status, message) = match self {
-            FinalverseError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
-            FinalverseError::PlayerNotFound(msg) |
-            FinalverseError::EchoNotFound(msg) |
-            FinalverseError::RegionNotFound(msg) |
-            FinalverseError::ResourceUnavailable(msg) => (StatusCode::NOT_FOUND, msg),
-            FinalverseError::PermissionDenied(msg) => (StatusCode::FORBIDDEN, msg),
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        let mut response = (self.status_code(), Json(self.body())).into_response();
+        if let Some(secs) = self.retry_after() {
+            response.headers_mut().insert(axum::http::header::RETRY_AFTER, secs.into());
+        }
+        response
+    }
+}
+
+/// Warp routes reject with a [`FinalverseError`] and add [`warp::recover`]
+/// to the combined routes to answer with the same JSON as axum services.
+#[cfg(feature = "warp")]
+pub mod warp {
+    use super::FinalverseError;
+    use ::warp::{http::StatusCode, Rejection, Reply};
+
+    impl ::warp::reject::Reject for FinalverseError {}
+
+    pub fn reject(error: FinalverseError) -> Rejection {
+        ::warp::reject::custom(error)
+    }
+
+    /// Turns a [`FinalverseError`] rejection into its response; other
+    /// rejections pass through untouched.
+    pub async fn recover(rejection: Rejection) -> Result<impl Reply, Rejection> {
+        let Some(error) = rejection.find::<FinalverseError>() else {
+            return Err(rejection);
         };
+        let status = StatusCode::from_u16(error.status_code().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let reply = ::warp::reply::with_status(::warp::reply::json(&error.body()), status);
+        Ok(match error.retry_after() {
+            Some(secs) => ::warp::reply::with_header(reply, "retry-after", secs.to_string()).into_response(),
+            None => reply.into_response(),
+        })
+    }
+}
 
-        (status, Json(serde_json::json!({"error": message}))).into_response()
+#[cfg(feature = "tonic")]
+impl From<FinalverseError> for tonic::Status {
+    fn from(error: FinalverseError) -> Self {
+        let message = error.to_string();
+        match error {
+            FinalverseError::BadRequest(_) | FinalverseError::Serialization(_) | FinalverseError::Uuid(_) => {
+                tonic::Status::invalid_argument(message)
+            }
+            FinalverseError::Unauthorized(_) => tonic::Status::unauthenticated(message),
+            FinalverseError::Forbidden(_) => tonic::Status::permission_denied(message),
+            FinalverseError::NotFound(_) => tonic::Status::not_found(message),
+            FinalverseError::Conflict(_) => tonic::Status::aborted(message),
+            FinalverseError::RateLimited { .. } => tonic::Status::resource_exhausted(message),
+            FinalverseError::Upstream { .. } | FinalverseError::Network(_) | FinalverseError::Unavailable(_) => tonic::Status::unavailable(message),
+            FinalverseError::Internal(_) => tonic::Status::internal(message),
+        }
     }
 }
 
-pub type Result<T> = std::result::Result<T, FinalverseError>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, FinalverseError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    #[test]
+    fn errors_map_to_statuses_and_bodies() {
+        let limited = FinalverseError::RateLimited { message: "too many melodies".to_string(), retry_after_secs: Some(3) };
+        let response = limited.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[axum::http::header::RETRY_AFTER], "3");
+
+        let upstream = FinalverseError::upstream("song-engine", "connection refused");
+        assert_eq!(upstream.status_code(), StatusCode::BAD_GATEWAY);
+        assert_eq!(upstream.body()["service"], "song-engine");
+        assert_eq!(upstream.body()["code"], "upstream");
+
+        assert!(matches!(FinalverseError::from(StatusCode::CONFLICT), FinalverseError::Conflict(_)));
+        assert_eq!(FinalverseError::from(uuid::Uuid::parse_str("nope").unwrap_err()).status_code(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,119 @@
+// crates/core/src/infection.rs
+//! Silence infection carried by characters. Exposure builds up while a
+//! character lingers in corrupted grids and never fades on its own; only
+//! restoration melodies and ritual sites cleanse it. Each stage weakens the
+//! character's melodies.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Exposure is kept within `0.0..=MAX_EXPOSURE`.
+pub const MAX_EXPOSURE: f32 = 100.0;
+
+/// Exposure gained per second spent in a fully corrupted grid.
+pub const EXPOSURE_PER_SECOND: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InfectionStage {
+    #[default]
+    Clear,
+    Touched,
+    Afflicted,
+    Consumed,
+}
+
+impl InfectionStage {
+    pub fn from_exposure(exposure: f32) -> Self {
+        match exposure {
+            e if e >= 80.0 => InfectionStage::Consumed,
+            e if e >= 50.0 => InfectionStage::Afflicted,
+            e if e >= 25.0 => InfectionStage::Touched,
+            _ => InfectionStage::Clear,
+        }
+    }
+
+    /// Factor applied to the power of every melody the character performs.
+    pub fn melody_power_multiplier(&self) -> f32 {
+        match self {
+            InfectionStage::Clear => 1.0,
+            InfectionStage::Touched => 0.9,
+            InfectionStage::Afflicted => 0.7,
+            InfectionStage::Consumed => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageChange {
+    pub from: InfectionStage,
+    pub to: InfectionStage,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SilenceInfection {
+    pub exposure: f32,
+    pub stage: InfectionStage,
+    pub last_cleansed: Option<DateTime<Utc>>,
+}
+
+impl SilenceInfection {
+    pub fn melody_power_multiplier(&self) -> f32 {
+        self.stage.melody_power_multiplier()
+    }
+
+    /// Clamp exposure and re-derive the stage, e.g. after loading a saved
+    /// character.
+    pub fn normalized(mut self) -> Self {
+        self.exposure = self.exposure.clamp(0.0, MAX_EXPOSURE);
+        self.stage = InfectionStage::from_exposure(self.exposure);
+        self
+    }
+
+    /// Accumulate exposure for `seconds` spent at `corruption` (0.0..=1.0).
+    pub fn expose(&mut self, corruption: f32, seconds: f32) -> Option<StageChange> {
+        let gained = corruption.clamp(0.0, 1.0) * EXPOSURE_PER_SECOND * seconds.max(0.0);
+        self.set_exposure(self.exposure + gained)
+    }
+
+    /// Remove up to `amount` exposure. Returns the stage change, if any.
+    pub fn cleanse(&mut self, amount: f32) -> Option<StageChange> {
+        if amount > 0.0 {
+            self.last_cleansed = Some(Utc::now());
+        }
+        self.set_exposure(self.exposure - amount.max(0.0))
+    }
+
+    fn set_exposure(&mut self, exposure: f32) -> Option<StageChange> {
+        if !exposure.is_finite() {
+            return None;
+        }
+        self.exposure = exposure.clamp(0.0, MAX_EXPOSURE);
+        let from = self.stage;
+        self.stage = InfectionStage::from_exposure(self.exposure);
+        (from != self.stage).then_some(StageChange { from, to: self.stage })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposure_advances_stages_until_cleansed() {
+        let mut infection = SilenceInfection::default();
+        assert_eq!(infection.expose(0.0, 600.0), None);
+
+        let change = infection.expose(1.0, 60.0).unwrap();
+        assert_eq!((change.from, change.to), (InfectionStage::Clear, InfectionStage::Touched));
+        assert_eq!(infection.melody_power_multiplier(), 0.9);
+
+        infection.expose(1.0, 1_000.0);
+        assert_eq!(infection.exposure, MAX_EXPOSURE);
+        assert_eq!(infection.stage, InfectionStage::Consumed);
+
+        let change = infection.cleanse(80.0).unwrap();
+        assert_eq!(change.to, InfectionStage::Clear);
+        assert!(infection.last_cleansed.is_some());
+    }
+}
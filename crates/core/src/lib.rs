@@ -5,28 +5,121 @@ pub mod types;
 pub mod error;
 pub mod echo;
 pub mod character;
+pub mod world_clock;
+pub mod markers;
+pub mod infection;
+pub mod population;
+pub mod terraform;
+pub mod crdt;
+pub mod annotations;
+pub mod stamina;
+pub mod tenant;
+pub mod region_map;
+pub mod region_effect;
 
 pub use events::*;
 pub use types::*;
 pub use error::*;
 pub use character::*;
 pub use echo::*;
+pub use world_clock::{WorldClockClient, WorldClockState, WorldInstant};
+pub use markers::{MarkerIcon, MarkerId, ObjectiveMarker};
+pub use infection::{InfectionStage, SilenceInfection, StageChange};
+pub use population::{NpcActivity, NpcPresence};
+pub use terraform::TerraformKind;
+pub use stamina::{Stamina, StaminaStatus};
+pub use tenant::{TenantId, DEFAULT_TENANT, TENANT_HEADER};
+pub use region_map::{RegionBoundary, RegionBounds, RegionMap, RegionMapError, REGION_MAP_PATH};
+pub use region_effect::{RegionEffect, RegionEffects};
+pub use annotations::{Annotation, AnnotationDoc, AnnotationGrid, AnnotationKind, AnnotationVisibility};
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
 // Core domain types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Unique identifier for a player. Events and saves from before ids were
+/// unified carried free-form strings (`"ayla"`); those still deserialize,
+/// to the id `PlayerId::from_legacy` derives from them.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct PlayerId(pub Uuid);
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct RegionId(pub Uuid);
 
-/// Unique identifier for an Echo
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Unique identifier for an Echo. Older clients and saves name the echo
+/// instead (`"lumi"`); those still deserialize, to the id `EchoType::id`
+/// gives that echo.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct EchoId(pub Uuid);
 
+impl PlayerId {
+    /// The stable id for a pre-uuid string player id; uuids parse as-is.
+    pub fn from_legacy(raw: &str) -> Self {
+        Uuid::parse_str(raw)
+            .map(PlayerId)
+            .unwrap_or_else(|_| PlayerId(Uuid::new_v5(&Uuid::NAMESPACE_OID, format!("player:{raw}").as_bytes())))
+    }
+}
+
+impl FromStr for PlayerId {
+    type Err = std::convert::Infallible;
+
+    fn from_str(raw: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(PlayerId::from_legacy(raw))
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayerId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Uuid::deserialize(deserializer).map(PlayerId);
+        }
+        let raw = String::deserialize(deserializer)?;
+        Ok(PlayerId::from_legacy(&raw))
+    }
+}
+
+impl fmt::Display for PlayerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("'{0}' is neither an echo id nor an echo name")]
+pub struct UnknownEcho(pub String);
+
+impl FromStr for EchoId {
+    type Err = UnknownEcho;
+
+    fn from_str(raw: &str) -> std::result::Result<Self, Self::Err> {
+        if let Ok(uuid) = Uuid::parse_str(raw) {
+            return Ok(EchoId(uuid));
+        }
+        EchoType::from_name(raw).map(|echo| echo.id()).ok_or_else(|| UnknownEcho(raw.to_string()))
+    }
+}
+
+impl<'de> Deserialize<'de> for EchoId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        // Binary formats only ever carried the uuid
+        if !deserializer.is_human_readable() {
+            return Uuid::deserialize(deserializer).map(EchoId);
+        }
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for EchoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coordinates {
     pub x: f64,
@@ -57,8 +150,8 @@ pub enum Melody {
     Courage { intensity: f32 },
 }
 
-// Echo types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+// Echo types; `types::EchoType` is this same type
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum EchoType {
     Lumi,   // Hope & Discovery
     KAI,    // Logic & Understanding
@@ -77,6 +170,17 @@ impl EchoType {
         };
         EchoId(uuid)
     }
+
+    /// The echo with this name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "lumi" => Some(EchoType::Lumi),
+            "kai" => Some(EchoType::KAI),
+            "terra" => Some(EchoType::Terra),
+            "ignis" => Some(EchoType::Ignis),
+            _ => None,
+        }
+    }
 }
 
 // Events
@@ -132,38 +236,63 @@ pub struct ServiceInfo {
     pub uptime_seconds: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Serialized lowercase, as `/health` always reported it; the capitalized
+/// names this type used to write are still read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ServiceStatus {
+    #[serde(alias = "Healthy")]
     Healthy,
+    #[serde(alias = "Degraded")]
     Degraded,
+    #[serde(alias = "Unhealthy")]
     Unhealthy,
 }
 
-// Error types
-#[derive(Debug, thiserror::Error)]
-pub enum FinalverseError {
-    #[error("Service communication error: {0}")]
-    ServiceError(String),
-    
-    #[error("Invalid request: {0}")]
-    InvalidRequest(String),
-    
-    #[error("Database error: {0}")]
-    DatabaseError(String),
-    
-    #[error("AI processing error: {0}")]
-    AIError(String),
-}
-
 // Utilities
 pub mod utils {
     use super::*;
     
+    /// A fresh id for a player without an account, such as a guest.
+    /// Registered players get theirs from api-gateway.
     pub fn new_player_id() -> PlayerId {
-        PlayerId(Uuid::new_v5(&Uuid::NAMESPACE_OID, b"finalverse.com"))
+        PlayerId(Uuid::new_v4())
     }
     
     pub fn new_region_id() -> RegionId {
         RegionId(Uuid::new_v5(&Uuid::NAMESPACE_OID, b"finalverse.com"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echo_ids_read_from_uuids_and_legacy_names() {
+        let lumi = EchoType::Lumi.id();
+        let json = serde_json::to_string(&lumi).unwrap();
+        assert_eq!(serde_json::from_str::<EchoId>(&json).unwrap(), lumi);
+        assert_eq!(serde_json::from_str::<EchoId>("\"Lumi\"").unwrap(), lumi);
+        assert_eq!("kai".parse::<EchoId>().unwrap(), EchoType::KAI.id());
+        assert!(serde_json::from_str::<EchoId>("\"silence\"").is_err());
+    }
+
+    #[test]
+    fn player_ids_read_from_uuids_and_legacy_strings() {
+        let player = PlayerId(Uuid::new_v4());
+        let json = serde_json::to_string(&player).unwrap();
+        assert_eq!(serde_json::from_str::<PlayerId>(&json).unwrap(), player);
+        let ayla = serde_json::from_str::<PlayerId>("\"ayla\"").unwrap();
+        assert_eq!(ayla, PlayerId::from_legacy("ayla"));
+        assert_ne!(ayla, PlayerId::from_legacy("kael"));
+        assert_eq!("ayla".parse::<PlayerId>().unwrap(), ayla);
+    }
+
+    #[test]
+    fn service_status_reads_both_spellings() {
+        assert_eq!(serde_json::to_string(&ServiceStatus::Degraded).unwrap(), "\"degraded\"");
+        assert_eq!(serde_json::from_str::<ServiceStatus>("\"Healthy\"").unwrap(), ServiceStatus::Healthy);
+        assert_eq!(serde_json::from_str::<ServiceStatus>("\"unhealthy\"").unwrap(), ServiceStatus::Unhealthy);
+    }
+}
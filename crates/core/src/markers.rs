@@ -0,0 +1,58 @@
+// crates/core/src/markers.rs
+//! World-anchored "go here" markers attached to quest objectives. The
+//! quest subsystem owns them; the spatial streaming layer delivers them to
+//! players who have the quest active.
+
+use crate::Coordinates;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MarkerId(pub Uuid);
+
+/// How the client draws the marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerIcon {
+    Destination,
+    Area,
+    Interaction,
+    Performance,
+    Gathering,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveMarker {
+    pub id: MarkerId,
+    pub quest_id: Uuid,
+    pub objective_id: Uuid,
+    pub position: Coordinates,
+    /// Objective counts as reached anywhere within this distance.
+    pub radius: f32,
+    pub icon: MarkerIcon,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ObjectiveMarker {
+    pub fn new(quest_id: Uuid, objective_id: Uuid, position: Coordinates, radius: f32, icon: MarkerIcon) -> Self {
+        Self {
+            id: MarkerId(Uuid::new_v4()),
+            quest_id,
+            objective_id,
+            position,
+            radius,
+            icon,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
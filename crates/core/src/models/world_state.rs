@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use crate::tenant::TenantId;
 
 /// The complete state of a Finalverse world instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +15,10 @@ pub struct WorldState {
     /// Unique identifier for this world
     pub id: Uuid,
 
+    /// The tenant hosting this world
+    #[serde(default)]
+    pub tenant_id: TenantId,
+
     /// Human-readable name (e.g., "Terra Nova", "Aethelgard")
     pub name: String,
 
@@ -149,6 +154,7 @@ impl WorldState {
     pub fn new(name: String, world_song: WorldSong) -> Self {
         Self {
             id: Uuid::new_v4(),
+            tenant_id: TenantId::default(),
             name,
             regions: Vec::new(),
             global_harmony: 0.5, // Start balanced
@@ -0,0 +1,34 @@
+// crates/core/src/population.rs
+//! Background NPCs as seen by clients. Procedural generation owns the
+//! population; the streaming layer forwards presences to nearby players.
+
+use crate::{Coordinates, NPCRole};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What a background NPC is doing according to their daily schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NpcActivity {
+    Sleeping,
+    Eating,
+    Working,
+    Patrolling,
+    Socializing,
+    Commuting,
+}
+
+/// Where a background NPC is right now and what they are doing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpcPresence {
+    pub npc_id: Uuid,
+    pub settlement_id: Uuid,
+    pub name: String,
+    pub role: NPCRole,
+    pub activity: NpcActivity,
+    pub position: Coordinates,
+    /// Latest action chosen by behavior-ai while a player is close enough
+    /// for full-fidelity simulation. `None` means the NPC follows its
+    /// schedule.
+    pub behavior: Option<String>,
+}
@@ -0,0 +1,37 @@
+// crates/core/src/region_effect.rs
+//! Changes other services make to a region. world-engine owns region
+//! state; song-engine, silence-service and the rest describe what they did
+//! to a region as effects and post them to `/regions/{id}/effects`, reading
+//! the region's state back from the reply rather than keeping their own.
+
+use serde::{Deserialize, Serialize};
+
+/// One change to a region. Levels run 0..=1 and are clamped after each
+/// effect.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegionEffect {
+    /// Raise (or, negative, lower) the region's harmony.
+    Harmony { delta: f64 },
+    /// Raise (or, negative, lower) the region's discord.
+    Discord { delta: f64 },
+    /// Raise (or, negative, lower) the region's political tension.
+    Tension { delta: f64 },
+}
+
+/// Effects applied together, in order, with who caused them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionEffects {
+    /// The service that caused the effects, e.g. `"song-engine"`.
+    pub source: String,
+    pub effects: Vec<RegionEffect>,
+}
+
+impl RegionEffects {
+    pub fn new(source: impl Into<String>, effects: Vec<RegionEffect>) -> Self {
+        Self {
+            source: source.into(),
+            effects,
+        }
+    }
+}
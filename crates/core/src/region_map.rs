@@ -0,0 +1,126 @@
+// crates/core/src/region_map.rs
+//! Where each region lies. Regions are rectangles or polygons on the x/y
+//! ground plane, the plane of `GridCoordinate`; height is ignored. Maps
+//! are JSON lists of [`RegionBoundary`], read from the file named by the
+//! `game.world_settings.region_map` setting or served by world-engine at
+//! [`REGION_MAP_PATH`].
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::types::{Coordinates, RegionId};
+
+/// Where world-engine serves its region map.
+pub const REGION_MAP_PATH: &str = "/regions/map";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegionMapError {
+    #[error("Can't read region map: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid region map: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum RegionBounds {
+    /// Edges included.
+    Rect { min: [f32; 2], max: [f32; 2] },
+    /// Vertices in order; the last joins back to the first.
+    Polygon { points: Vec<[f32; 2]> },
+}
+
+impl RegionBounds {
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        match self {
+            RegionBounds::Rect { min, max } => (min[0]..=max[0]).contains(&x) && (min[1]..=max[1]).contains(&y),
+            RegionBounds::Polygon { points } => {
+                // Count crossings of a ray running from the point towards +x
+                let mut inside = false;
+                let mut previous = match points.last() {
+                    Some(point) => *point,
+                    None => return false,
+                };
+                for &point in points {
+                    let [x1, y1] = previous;
+                    let [x2, y2] = point;
+                    if (y1 > y) != (y2 > y) && x < x1 + (y - y1) / (y2 - y1) * (x2 - x1) {
+                        inside = !inside;
+                    }
+                    previous = point;
+                }
+                inside
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionBoundary {
+    pub id: RegionId,
+    pub name: String,
+    pub bounds: RegionBounds,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RegionMap {
+    regions: Vec<RegionBoundary>,
+}
+
+impl RegionMap {
+    pub fn new(regions: Vec<RegionBoundary>) -> Self {
+        Self { regions }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegionMapError> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn regions(&self) -> &[RegionBoundary] {
+        &self.regions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// The region containing `coordinates`. Where regions overlap, the one
+    /// listed first wins.
+    pub fn region_at(&self, coordinates: &Coordinates) -> Option<&RegionBoundary> {
+        self.regions
+            .iter()
+            .find(|region| region.bounds.contains(coordinates.x, coordinates.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn at(x: f32, y: f32) -> Coordinates {
+        Coordinates { x, y, z: 100.0 }
+    }
+
+    #[test]
+    fn points_map_to_the_first_region_containing_them() {
+        let json = r#"[
+            {"id": "00000000-0000-0000-0000-000000000001", "name": "Weaver's Landing",
+             "bounds": {"shape": "polygon", "points": [[0, 0], [10, 0], [0, 10]]}},
+            {"id": "00000000-0000-0000-0000-000000000002", "name": "Whisperwood",
+             "bounds": {"shape": "rect", "min": [0, 0], "max": [20, 20]}}
+        ]"#;
+        let map: RegionMap = serde_json::from_str(json).unwrap();
+        let name = |x, y| map.region_at(&at(x, y)).map(|region| region.name.as_str());
+
+        assert_eq!(name(2.0, 2.0), Some("Weaver's Landing"));
+        // Across the triangle's diagonal, but still in the rectangle
+        assert_eq!(name(8.0, 8.0), Some("Whisperwood"));
+        assert_eq!(name(20.0, 20.0), Some("Whisperwood"));
+        assert_eq!(name(-1.0, 5.0), None);
+        assert_eq!(map.regions()[0].id, RegionId(Uuid::from_u128(1)));
+    }
+}
@@ -0,0 +1,68 @@
+// crates/core/src/stamina.rs
+//! Songweaving stamina. Melodies spend it in proportion to their power and
+//! it refills over time; song-engine enforces the costs and works out the
+//! refill rate from the curves in `finalverse-config`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_MAX_STAMINA: f32 = 100.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// When `current` was last brought up to date.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self::full(DEFAULT_MAX_STAMINA, Utc::now())
+    }
+}
+
+impl Stamina {
+    pub fn full(max: f32, now: DateTime<Utc>) -> Self {
+        Self {
+            current: max,
+            max,
+            updated_at: now,
+        }
+    }
+
+    /// Refill at `per_second` for the time since the last update.
+    pub fn regenerate(&mut self, per_second: f32, now: DateTime<Utc>) {
+        let elapsed = (now - self.updated_at).num_milliseconds().max(0) as f32 / 1000.0;
+        self.current = (self.current + elapsed * per_second.max(0.0)).min(self.max);
+        self.updated_at = now;
+    }
+
+    /// Spend `cost`, or return how much is missing without spending.
+    pub fn spend(&mut self, cost: f32) -> Result<(), f32> {
+        if cost > self.current {
+            return Err(cost - self.current);
+        }
+        self.current -= cost;
+        Ok(())
+    }
+
+    pub fn status(&self, regen_per_second: f32, resting: bool) -> StaminaStatus {
+        StaminaStatus {
+            current: self.current,
+            max: self.max,
+            regen_per_second,
+            resting,
+        }
+    }
+}
+
+/// Stamina as shown to the player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaminaStatus {
+    pub current: f32,
+    pub max: f32,
+    pub regen_per_second: f32,
+    /// Inside a rest location, refilling faster.
+    pub resting: bool,
+}
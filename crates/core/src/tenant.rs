@@ -0,0 +1,96 @@
+// crates/core/src/tenant.rs
+//! Tenants are isolated game worlds hosted on one deployment, such as a
+//! staging event or a partner shard. Everything a tenant owns carries its
+//! [`TenantId`]; the `default` tenant is the main world and keeps the
+//! unprefixed event subjects and cache keys it always had.
+
+use crate::FinalverseError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Header carrying the tenant on requests between the gateway and services.
+pub const TENANT_HEADER: &str = "x-finalverse-tenant";
+
+const MAX_TENANT_ID_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TenantId(String);
+
+impl Default for TenantId {
+    fn default() -> Self {
+        Self(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl TenantId {
+    /// Tenant ids end up in NATS subjects, Redis keys and database rows, so
+    /// only lowercase letters, digits and `-` are allowed.
+    pub fn parse(id: &str) -> Result<Self, FinalverseError> {
+        let valid = !id.is_empty()
+            && id.len() <= MAX_TENANT_ID_LEN
+            && !id.starts_with('-')
+            && id.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+        if valid {
+            Ok(Self(id.to_string()))
+        } else {
+            Err(FinalverseError::BadRequest(format!("Invalid tenant id: {:?}", id)))
+        }
+    }
+
+    /// The tenant this process serves, from `FINALVERSE_TENANT_ID`.
+    pub fn from_env() -> Self {
+        std::env::var("FINALVERSE_TENANT_ID")
+            .ok()
+            .and_then(|id| Self::parse(&id).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_TENANT
+    }
+
+    /// The event-bus subject for `topic` within this tenant.
+    pub fn subject(&self, topic: &str) -> String {
+        if self.is_default() {
+            topic.to_string()
+        } else {
+            format!("tenants.{}.{}", self.0, topic)
+        }
+    }
+
+    /// A cache key or pub/sub channel for `key` within this tenant.
+    pub fn scoped_key(&self, key: &str) -> String {
+        if self.is_default() {
+            key.to_string()
+        } else {
+            format!("tenant:{}:{}", self.0, key)
+        }
+    }
+}
+
+impl TryFrom<String> for TenantId {
+    type Error = FinalverseError;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        Self::parse(&id)
+    }
+}
+
+impl From<TenantId> for String {
+    fn from(id: TenantId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for TenantId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
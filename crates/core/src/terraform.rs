@@ -0,0 +1,45 @@
+// crates/core/src/terraform.rs
+//! Community-approved terraforming. Community runs the proposal and vote;
+//! world-engine carries out the transformation stage by stage.
+
+use crate::types::TerrainType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerraformKind {
+    /// Regrow woodland over barren ground.
+    Reforest,
+    /// Turn desert into grassland.
+    Irrigate,
+    /// Heal land lost to the Silence.
+    Purify,
+}
+
+impl TerraformKind {
+    pub fn target_terrain(&self) -> TerrainType {
+        match self {
+            TerraformKind::Reforest => TerrainType::Forest,
+            TerraformKind::Irrigate | TerraformKind::Purify => TerrainType::Plains,
+        }
+    }
+
+    /// Resources that must be pledged before the vote closes.
+    pub fn resource_cost(&self) -> f64 {
+        match self {
+            TerraformKind::Reforest => 500.0,
+            TerraformKind::Irrigate => 400.0,
+            TerraformKind::Purify => 800.0,
+        }
+    }
+
+    /// The visible states the region passes through. The target terrain is
+    /// applied when the last one is reached.
+    pub fn stages(&self) -> &'static [&'static str] {
+        match self {
+            TerraformKind::Reforest => &["seeding", "saplings", "young_woodland", "forest"],
+            TerraformKind::Irrigate => &["channels_dug", "wetting", "grassland"],
+            TerraformKind::Purify => &["cleansing", "quieting", "regrowth", "restored"],
+        }
+    }
+}
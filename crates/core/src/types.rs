@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 // Re-export identifier types from the crate root to keep a single definition
-pub use crate::{PlayerId, RegionId, EchoId};
+pub use crate::{PlayerId, RegionId, EchoId, EchoType};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Coordinates {
@@ -33,6 +33,8 @@ pub enum HarmonyType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldState {
+    #[serde(default)]
+    pub tenant_id: crate::tenant::TenantId,
     pub regions: std::collections::HashMap<RegionId, RegionState>,
     pub global_harmony: f32,
     pub active_events: Vec<String>,
@@ -46,7 +48,7 @@ pub struct RegionState {
     pub corruption_level: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TerrainType {
     Forest,
     Desert,
@@ -56,7 +58,7 @@ pub enum TerrainType {
     Corrupted,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WeatherType {
     Clear,
     Cloudy,
@@ -118,13 +120,6 @@ impl Note {
 }
 
 // Echo-specific types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum EchoType {
-    Lumi, // Hope & Discovery
-    KAI,  // Logic & Understanding
-    Terra, // Resilience & Growth
-    Ignis, // Courage & Creation
-}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EchoState {
@@ -280,4 +275,73 @@ pub enum RewardType {
     Ability,
     Title,
     Access,
-}
\ No newline at end of file
+}
+
+// Client capability types
+/// Content features a client may or may not be able to render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContentFeature {
+    Audio,
+    #[serde(rename = "3d-streaming")]
+    Streaming3d,
+    CompressedEncoding,
+}
+
+/// Capabilities declared by a client during the gateway handshake.
+///
+/// Clients that never send a handshake are treated as full clients so that
+/// existing integrations keep receiving every payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientCapabilities {
+    pub audio: bool,
+    #[serde(rename = "3d-streaming")]
+    pub streaming_3d: bool,
+    pub compressed_encoding: bool,
+    pub locales: Vec<String>,
+}
+
+impl Default for ClientCapabilities {
+    fn default() -> Self {
+        Self {
+            audio: true,
+            streaming_3d: true,
+            compressed_encoding: false,
+            locales: vec!["en".to_string()],
+        }
+    }
+}
+
+impl ClientCapabilities {
+    pub fn text_only() -> Self {
+        Self {
+            audio: false,
+            streaming_3d: false,
+            compressed_encoding: false,
+            ..Default::default()
+        }
+    }
+
+    pub fn supports(&self, feature: ContentFeature) -> bool {
+        match feature {
+            ContentFeature::Audio => self.audio,
+            ContentFeature::Streaming3d => self.streaming_3d,
+            ContentFeature::CompressedEncoding => self.compressed_encoding,
+        }
+    }
+
+    /// Pick the first client locale that the caller can serve, falling back
+    /// to the caller's first available locale.
+    pub fn preferred_locale<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        self.locales
+            .iter()
+            .find_map(|wanted| {
+                available
+                    .iter()
+                    .find(|have| have.eq_ignore_ascii_case(wanted))
+                    .copied()
+            })
+            .or_else(|| available.first().copied())
+    }
+}
@@ -0,0 +1,291 @@
+// libs/common/src/world_clock.rs
+//! Shared in-game clock. world-engine owns the authoritative
+//! [`WorldClockState`]; every other service mirrors it through a
+//! [`WorldClockClient`] so they all agree on world time, including while
+//! time is dilated or paused.
+
+use crate::FinalverseError;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock, RwLock};
+use tracing::{debug, warn};
+
+pub const SECONDS_PER_WORLD_DAY: f64 = 86_400.0;
+
+/// Wall-clock minutes per world day when nothing else is configured.
+pub const DEFAULT_DAY_LENGTH_MINUTES: u32 = 60;
+
+/// How often mirrors re-sync with world-engine.
+pub const SYNC_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// World time at which a fresh clock starts: dawn of day 1.
+const START_SECONDS: f64 = 6.0 * 3600.0;
+
+/// A point in world time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorldInstant {
+    /// World seconds since the start of day 1.
+    pub seconds: f64,
+    pub day: u32,
+    pub hour: f32,
+}
+
+impl WorldInstant {
+    pub fn from_seconds(seconds: f64) -> Self {
+        let seconds = seconds.max(0.0);
+        let day = (seconds / SECONDS_PER_WORLD_DAY).floor();
+        let hour = (seconds - day * SECONDS_PER_WORLD_DAY) / 3600.0;
+        Self {
+            seconds,
+            day: day as u32 + 1,
+            hour: hour as f32,
+        }
+    }
+}
+
+/// Linear mapping from wall-clock time to world time, re-anchored whenever
+/// the rate changes so earlier world time is never rewritten.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldClockState {
+    pub anchor_wall: DateTime<Utc>,
+    pub anchor_world_seconds: f64,
+    /// World seconds per wall-clock second.
+    pub rate: f64,
+    pub paused: bool,
+    /// Bumped on every rate or pause change.
+    pub revision: u64,
+}
+
+impl Default for WorldClockState {
+    fn default() -> Self {
+        Self::for_day_length(DEFAULT_DAY_LENGTH_MINUTES)
+    }
+}
+
+impl WorldClockState {
+    /// A running clock where one world day lasts `minutes` of wall time.
+    pub fn for_day_length(minutes: u32) -> Self {
+        Self {
+            anchor_wall: Utc::now(),
+            anchor_world_seconds: START_SECONDS,
+            rate: 1440.0 / minutes.max(1) as f64,
+            paused: false,
+            revision: 0,
+        }
+    }
+
+    pub fn world_seconds_at(&self, wall: DateTime<Utc>) -> f64 {
+        if self.paused {
+            return self.anchor_world_seconds;
+        }
+        let elapsed = (wall - self.anchor_wall).num_milliseconds() as f64 / 1000.0;
+        // Never run backwards, even for wall times before the anchor
+        self.anchor_world_seconds + elapsed.max(0.0) * self.rate
+    }
+
+    pub fn at(&self, wall: DateTime<Utc>) -> WorldInstant {
+        WorldInstant::from_seconds(self.world_seconds_at(wall))
+    }
+
+    fn reanchor(&mut self, wall: DateTime<Utc>) {
+        self.anchor_world_seconds = self.world_seconds_at(wall);
+        self.anchor_wall = wall;
+        self.revision += 1;
+    }
+
+    pub fn set_rate(&mut self, rate: f64, wall: DateTime<Utc>) -> Result<(), String> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err("rate must be a positive number; pause the clock instead of setting it to zero".to_string());
+        }
+        self.reanchor(wall);
+        self.rate = rate;
+        Ok(())
+    }
+
+    pub fn pause(&mut self, wall: DateTime<Utc>) {
+        if !self.paused {
+            self.reanchor(wall);
+            self.paused = true;
+        }
+    }
+
+    pub fn resume(&mut self, wall: DateTime<Utc>) {
+        if self.paused {
+            self.reanchor(wall);
+            self.paused = false;
+        }
+    }
+}
+
+/// Body of world-engine's `GET /clock`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockSync {
+    pub state: WorldClockState,
+    /// Authority's wall clock when the response was built.
+    pub server_time: DateTime<Utc>,
+    pub now: WorldInstant,
+}
+
+impl ClockSync {
+    pub fn capture(state: &WorldClockState) -> Self {
+        let server_time = Utc::now();
+        Self {
+            state: state.clone(),
+            server_time,
+            now: state.at(server_time),
+        }
+    }
+}
+
+struct Mirror {
+    state: WorldClockState,
+    /// Authority wall clock minus ours.
+    offset: Duration,
+    synced: bool,
+}
+
+/// Local mirror of the world-engine clock. Reads never block on the
+/// network; [`WorldClockClient::sync`] refreshes the mirror and corrects for
+/// drift between this host's clock and the authority's.
+pub struct WorldClockClient {
+    sync_url: String,
+    http: reqwest::Client,
+    mirror: RwLock<Mirror>,
+}
+
+impl WorldClockClient {
+    /// `fallback` is used until the first successful sync.
+    pub fn new(world_engine_url: &str, fallback: WorldClockState) -> Self {
+        Self {
+            sync_url: format!("{}/clock", world_engine_url.trim_end_matches('/')),
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(2))
+                .build()
+                .unwrap_or_default(),
+            mirror: RwLock::new(Mirror {
+                state: fallback,
+                offset: Duration::zero(),
+                synced: false,
+            }),
+        }
+    }
+
+    /// Point at `WORLD_ENGINE_URL`, defaulting to the local world-engine.
+    pub fn from_env() -> Self {
+        let url = std::env::var("WORLD_ENGINE_URL").unwrap_or_else(|_| "http://localhost:3002".to_string());
+        Self::new(&url, WorldClockState::default())
+    }
+
+    pub fn now(&self) -> WorldInstant {
+        let mirror = self.mirror.read().unwrap_or_else(|e| e.into_inner());
+        mirror.state.at(Utc::now() + mirror.offset)
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.mirror.read().unwrap_or_else(|e| e.into_inner()).synced
+    }
+
+    pub fn state(&self) -> WorldClockState {
+        self.mirror.read().unwrap_or_else(|e| e.into_inner()).state.clone()
+    }
+
+    pub async fn sync(&self) -> Result<(), FinalverseError> {
+        let sent = Utc::now();
+        let response = self
+            .http
+            .get(&self.sync_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| FinalverseError::upstream("world-engine", format!("clock sync failed: {}", e)))?;
+        let sync: ClockSync = response
+            .json()
+            .await
+            .map_err(|e| FinalverseError::upstream("world-engine", format!("invalid clock sync response: {}", e)))?;
+        let received = Utc::now();
+
+        // Assume the authority stamped its reply halfway through the round trip
+        let midpoint = sent + (received - sent) / 2;
+        let offset = sync.server_time - midpoint;
+
+        let mut mirror = self.mirror.write().unwrap_or_else(|e| e.into_inner());
+        if mirror.state.revision != sync.state.revision {
+            debug!("World clock now at revision {} (rate {}, paused {})", sync.state.revision, sync.state.rate, sync.state.paused);
+        }
+        mirror.state = sync.state;
+        mirror.offset = offset;
+        mirror.synced = true;
+        Ok(())
+    }
+
+    /// Keep the mirror fresh in the background.
+    pub fn spawn_sync(self: &Arc<Self>, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let clock = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                if let Err(e) = clock.sync().await {
+                    warn!("{}", e);
+                }
+            }
+        })
+    }
+}
+
+static INSTALLED: OnceLock<Arc<WorldClockClient>> = OnceLock::new();
+
+/// Make `clock` the process-wide world clock, used to stamp outgoing
+/// events. Returns false if one was already installed.
+pub fn install(clock: Arc<WorldClockClient>) -> bool {
+    INSTALLED.set(clock).is_ok()
+}
+
+/// Mirror world-engine's clock from `WORLD_ENGINE_URL`, keep it synced and
+/// install it as the process-wide clock.
+pub fn start_from_env() -> Arc<WorldClockClient> {
+    let clock = Arc::new(WorldClockClient::from_env());
+    clock.spawn_sync(SYNC_INTERVAL);
+    install(clock.clone());
+    clock
+}
+
+/// Current world time from the installed clock, if any.
+pub fn now() -> Option<WorldInstant> {
+    INSTALLED.get().map(|clock| clock.now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dilation_and_pause_keep_world_time_continuous() {
+        let start = Utc::now();
+        let mut clock = WorldClockState::for_day_length(60);
+        clock.anchor_wall = start;
+
+        // 24x: one wall minute is 24 world minutes
+        let t1 = start + Duration::minutes(1);
+        assert_eq!(clock.world_seconds_at(t1), START_SECONDS + 1440.0);
+
+        clock.set_rate(48.0, t1).unwrap();
+        let t2 = t1 + Duration::minutes(1);
+        assert_eq!(clock.world_seconds_at(t2), START_SECONDS + 1440.0 + 2880.0);
+
+        clock.pause(t2);
+        let frozen = clock.world_seconds_at(t2 + Duration::hours(5));
+        assert_eq!(frozen, START_SECONDS + 4320.0);
+
+        clock.resume(t2 + Duration::hours(5));
+        assert_eq!(clock.revision, 3);
+        assert!(clock.set_rate(0.0, t2).is_err());
+    }
+
+    #[test]
+    fn instants_roll_over_days() {
+        let instant = WorldInstant::from_seconds(SECONDS_PER_WORLD_DAY + 7200.0);
+        assert_eq!(instant.day, 2);
+        assert_eq!(instant.hour, 2.0);
+    }
+}
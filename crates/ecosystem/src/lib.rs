@@ -6,7 +6,11 @@ use std::collections::HashMap;
 use uuid::Uuid;
 
 pub mod simulator;
+pub mod stats;
 pub use simulator::{EcosystemSimulator, EcosystemObserver, EcosystemEvent, SpeciesProfile};
+pub use stats::{
+    AggregationWindow, ConservationStatus, ConservationThresholds, PopulationRollup, RollupBucket, RollupQuery,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ecosystem {
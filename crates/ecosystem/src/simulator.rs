@@ -1,3 +1,7 @@
+use crate::stats::{
+    ConservationStatus, ConservationThresholds, MigrationEdge, PeriodCounts, PopulationRollup,
+    PopulationSeries, RegionPopulation, RollupBucket, RollupQuery,
+};
 use crate::Species;
 use finalverse_metobolism::{RegionId, TerrainType};
 use serde::{Serialize, Deserialize};
@@ -5,6 +9,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Share of a region's population born and lost per tick, before noise.
+const BIRTH_RATE: f64 = 0.02;
+const DEATH_RATE: f64 = 0.018;
+/// Share of the source region's population that moves in a migration.
+const MIGRATION_SHARE: f64 = 0.1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeciesProfile {
     pub id: String,
@@ -13,11 +23,20 @@ pub struct SpeciesProfile {
     pub population: u64,
     pub migration_pattern: Vec<RegionId>,
     pub preferred_terrain: Vec<TerrainType>,
+    /// Population by region; seeded into the first migration region when empty.
+    #[serde(default)]
+    pub region_population: HashMap<RegionId, u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EcosystemEvent {
     CreatureMigration { species: String, from: RegionId, to: RegionId },
+    ConservationStatusChanged {
+        species: String,
+        from: ConservationStatus,
+        to: ConservationStatus,
+        population: u64,
+    },
 }
 
 #[async_trait::async_trait]
@@ -28,13 +47,25 @@ pub trait EcosystemObserver: Send + Sync {
 pub struct EcosystemSimulator {
     species: Arc<RwLock<HashMap<String, SpeciesProfile>>>,
     observers: Arc<RwLock<Vec<Arc<dyn EcosystemObserver>>>>,
+    counts: Arc<RwLock<HashMap<String, PeriodCounts>>>,
+    series: Arc<RwLock<PopulationSeries>>,
+    statuses: Arc<RwLock<HashMap<String, ConservationStatus>>>,
+    thresholds: ConservationThresholds,
 }
 
 impl EcosystemSimulator {
     pub fn new() -> Self {
+        Self::with_thresholds(ConservationThresholds::default())
+    }
+
+    pub fn with_thresholds(thresholds: ConservationThresholds) -> Self {
         Self {
             species: Arc::new(RwLock::new(HashMap::new())),
             observers: Arc::new(RwLock::new(Vec::new())),
+            counts: Arc::new(RwLock::new(HashMap::new())),
+            series: Arc::new(RwLock::new(PopulationSeries::default())),
+            statuses: Arc::new(RwLock::new(HashMap::new())),
+            thresholds,
         }
     }
 
@@ -42,28 +73,206 @@ impl EcosystemSimulator {
         self.observers.write().await.push(observer);
     }
 
+    async fn notify(&self, event: &EcosystemEvent) {
+        let observers = self.observers.read().await;
+        for obs in observers.iter() {
+            obs.notify(event).await;
+        }
+    }
+
     pub async fn simulate_tick(&self) {
-        let species_list = self.species.read().await;
-        for (_, sp) in species_list.iter() {
-            if rand::random::<f64>() < 0.1 {
-                if sp.migration_pattern.len() >= 2 {
+        let mut migrations = Vec::new();
+        {
+            let mut species_list = self.species.write().await;
+            let mut counts = self.counts.write().await;
+            for sp in species_list.values_mut() {
+                let period = counts.entry(sp.id.clone()).or_default();
+
+                // Births and deaths in every occupied region
+                for population in sp.region_population.values_mut() {
+                    let births = vary(*population as f64 * BIRTH_RATE);
+                    let deaths = vary(*population as f64 * DEATH_RATE).min(*population + births);
+                    *population = *population + births - deaths;
+                    period.births += births;
+                    period.deaths += deaths;
+                }
+
+                if rand::random::<f64>() < 0.1 && sp.migration_pattern.len() >= 2 {
                     let from = sp.migration_pattern[0].clone();
                     let to = sp.migration_pattern[1].clone();
-                    let event = EcosystemEvent::CreatureMigration {
+                    let available = sp.region_population.get(&from).copied().unwrap_or(0);
+                    let moving = ((available as f64 * MIGRATION_SHARE).round() as u64).max(1).min(available);
+                    if moving > 0 {
+                        *sp.region_population.entry(from.clone()).or_default() -= moving;
+                        *sp.region_population.entry(to.clone()).or_default() += moving;
+                        *period.migrations.entry((from.clone(), to.clone())).or_default() += moving;
+                    }
+                    migrations.push(EcosystemEvent::CreatureMigration {
                         species: sp.name.clone(),
                         from,
                         to,
-                    };
-                    let observers = self.observers.read().await;
-                    for obs in observers.iter() {
-                        obs.notify(&event).await;
-                    }
+                    });
+                }
+
+                if !sp.region_population.is_empty() {
+                    sp.population = sp.region_population.values().sum();
                 }
             }
         }
+
+        for event in migrations {
+            self.notify(&event).await;
+        }
+    }
+
+    /// Close the current period: record a rollup per species and report
+    /// species that crossed a conservation threshold.
+    pub async fn rollup(&self) -> Vec<PopulationRollup> {
+        let taken_at = chrono::Utc::now();
+        let species: Vec<SpeciesProfile> = self.species.read().await.values().cloned().collect();
+        let mut counts = std::mem::take(&mut *self.counts.write().await);
+
+        let mut rollups = Vec::new();
+        let mut changes = Vec::new();
+        {
+            let mut series = self.series.write().await;
+            let mut statuses = self.statuses.write().await;
+            for sp in species {
+                let period = counts.remove(&sp.id).unwrap_or_default();
+                let rollup = PopulationRollup {
+                    species_id: sp.id.clone(),
+                    species_name: sp.name.clone(),
+                    taken_at,
+                    population: sp.population,
+                    regions: sp
+                        .region_population
+                        .iter()
+                        .map(|(region_id, population)| RegionPopulation {
+                            region_id: region_id.clone(),
+                            population: *population,
+                        })
+                        .collect(),
+                    births: period.births,
+                    deaths: period.deaths,
+                    migrations: period
+                        .migrations
+                        .into_iter()
+                        .map(|((from, to), count)| MigrationEdge { from, to, count })
+                        .collect(),
+                };
+                series.push(rollup.clone());
+                rollups.push(rollup);
+
+                // A species' first rollup sets its status without reporting a change
+                let status = self.thresholds.status_for(sp.population);
+                let previous = statuses.insert(sp.id.clone(), status).unwrap_or(status);
+                if previous != status {
+                    changes.push(EcosystemEvent::ConservationStatusChanged {
+                        species: sp.name,
+                        from: previous,
+                        to: status,
+                        population: sp.population,
+                    });
+                }
+            }
+        }
+
+        for event in changes {
+            self.notify(&event).await;
+        }
+        rollups
+    }
+
+    pub async fn query_rollups(&self, query: &RollupQuery) -> Vec<RollupBucket> {
+        self.series.read().await.query(query)
     }
 
-    pub async fn add_species(&self, species: SpeciesProfile) {
+    pub async fn add_species(&self, mut species: SpeciesProfile) {
+        if species.region_population.is_empty() {
+            if let Some(home) = species.migration_pattern.first() {
+                species.region_population.insert(home.clone(), species.population);
+            }
+        }
         self.species.write().await.insert(species.id.clone(), species);
     }
+
+    pub async fn species(&self) -> Vec<SpeciesProfile> {
+        self.species.read().await.values().cloned().collect()
+    }
+
+    /// Replace every species, as when loading a saved world. Rollups already
+    /// recorded are kept; the open period and known statuses start over.
+    pub async fn restore_species(&self, species: Vec<SpeciesProfile>) {
+        self.counts.write().await.clear();
+        self.statuses.write().await.clear();
+        self.species.write().await.clear();
+        for profile in species {
+            self.add_species(profile).await;
+        }
+    }
+}
+
+/// Round an expected count with +/-50% noise.
+fn vary(expected: f64) -> u64 {
+    (expected * (0.5 + rand::random::<f64>())).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<EcosystemEvent>>);
+
+    #[async_trait::async_trait]
+    impl EcosystemObserver for Recorder {
+        async fn notify(&self, event: &EcosystemEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    fn salamanders(population: u64) -> SpeciesProfile {
+        SpeciesProfile {
+            id: "storm-salamander".to_string(),
+            name: "Storm Salamander".to_string(),
+            species: Species::StormSalamander { electric_charge: 0.5 },
+            population,
+            migration_pattern: vec![RegionId(Uuid::from_u128(1))],
+            preferred_terrain: Vec::new(),
+            region_population: HashMap::new(),
+        }
+    }
+
+    fn status_changes(recorder: &Recorder) -> Vec<(ConservationStatus, ConservationStatus)> {
+        recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                EcosystemEvent::ConservationStatusChanged { from, to, .. } => Some((*from, *to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn first_rollup_sets_status_without_reporting_a_change() {
+        let simulator = EcosystemSimulator::new();
+        let recorder = Arc::new(Recorder::default());
+        simulator.register_observer(recorder.clone()).await;
+
+        simulator.add_species(salamanders(10)).await;
+        simulator.rollup().await;
+        assert!(status_changes(&recorder).is_empty());
+
+        simulator.add_species(salamanders(0)).await;
+        simulator.rollup().await;
+        assert_eq!(
+            status_changes(&recorder),
+            [(ConservationStatus::Endangered, ConservationStatus::Extinct)]
+        );
+    }
 }
@@ -0,0 +1,264 @@
+//! Population rollups feeding the ecosystem dashboard.
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use finalverse_metobolism::RegionId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// How long rollups are kept before being discarded.
+const RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionPopulation {
+    pub region_id: RegionId,
+    pub population: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationEdge {
+    pub from: RegionId,
+    pub to: RegionId,
+    pub count: u64,
+}
+
+/// One species' population at the end of a rollup period, plus the
+/// births, deaths and migrations recorded during it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopulationRollup {
+    pub species_id: String,
+    pub species_name: String,
+    pub taken_at: DateTime<Utc>,
+    pub population: u64,
+    pub regions: Vec<RegionPopulation>,
+    pub births: u64,
+    pub deaths: u64,
+    pub migrations: Vec<MigrationEdge>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConservationStatus {
+    Stable,
+    Endangered,
+    Extinct,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConservationThresholds {
+    /// Species below this population are endangered.
+    pub endangered_below: u64,
+}
+
+impl Default for ConservationThresholds {
+    fn default() -> Self {
+        Self { endangered_below: 50 }
+    }
+}
+
+impl ConservationThresholds {
+    pub fn status_for(&self, population: u64) -> ConservationStatus {
+        if population == 0 {
+            ConservationStatus::Extinct
+        } else if population < self.endangered_below {
+            ConservationStatus::Endangered
+        } else {
+            ConservationStatus::Stable
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationWindow {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+impl AggregationWindow {
+    fn duration(&self) -> Duration {
+        match self {
+            AggregationWindow::Minute => Duration::minutes(1),
+            AggregationWindow::Hour => Duration::hours(1),
+            AggregationWindow::Day => Duration::days(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RollupQuery {
+    pub species: Option<String>,
+    pub window: AggregationWindow,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Rollups for one species folded into a single aggregation window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollupBucket {
+    pub species_id: String,
+    pub window_start: DateTime<Utc>,
+    pub samples: usize,
+    pub average_population: f64,
+    pub min_population: u64,
+    pub max_population: u64,
+    /// Population at the last rollup in the window.
+    pub closing_population: u64,
+    pub births: u64,
+    pub deaths: u64,
+    pub migrations: Vec<MigrationEdge>,
+}
+
+/// Births, deaths and migrations counted since the last rollup.
+#[derive(Debug, Default)]
+pub(crate) struct PeriodCounts {
+    pub births: u64,
+    pub deaths: u64,
+    pub migrations: HashMap<(RegionId, RegionId), u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct PopulationSeries {
+    rollups: VecDeque<PopulationRollup>,
+}
+
+impl PopulationSeries {
+    pub fn push(&mut self, rollup: PopulationRollup) {
+        let cutoff = rollup.taken_at - Duration::days(RETENTION_DAYS);
+        while self.rollups.front().is_some_and(|oldest| oldest.taken_at < cutoff) {
+            self.rollups.pop_front();
+        }
+        self.rollups.push_back(rollup);
+    }
+
+    pub fn query(&self, query: &RollupQuery) -> Vec<RollupBucket> {
+        let window = query.window.duration();
+        let mut buckets: Vec<RollupBucket> = Vec::new();
+        let mut index: HashMap<(String, DateTime<Utc>), usize> = HashMap::new();
+
+        let matching = self.rollups.iter().filter(|rollup| {
+            query.species.as_ref().is_none_or(|species| &rollup.species_id == species)
+                && query.since.is_none_or(|since| rollup.taken_at >= since)
+                && query.until.is_none_or(|until| rollup.taken_at < until)
+        });
+
+        for rollup in matching {
+            let window_start = rollup.taken_at.duration_trunc(window).unwrap_or(rollup.taken_at);
+            let key = (rollup.species_id.clone(), window_start);
+            let position = *index.entry(key).or_insert_with(|| {
+                buckets.push(RollupBucket {
+                    species_id: rollup.species_id.clone(),
+                    window_start,
+                    samples: 0,
+                    average_population: 0.0,
+                    min_population: u64::MAX,
+                    max_population: 0,
+                    closing_population: 0,
+                    births: 0,
+                    deaths: 0,
+                    migrations: Vec::new(),
+                });
+                buckets.len() - 1
+            });
+
+            let bucket = &mut buckets[position];
+            bucket.average_population = (bucket.average_population * bucket.samples as f64
+                + rollup.population as f64)
+                / (bucket.samples + 1) as f64;
+            bucket.samples += 1;
+            bucket.min_population = bucket.min_population.min(rollup.population);
+            bucket.max_population = bucket.max_population.max(rollup.population);
+            bucket.closing_population = rollup.population;
+            bucket.births += rollup.births;
+            bucket.deaths += rollup.deaths;
+            for edge in &rollup.migrations {
+                match bucket
+                    .migrations
+                    .iter_mut()
+                    .find(|existing| existing.from == edge.from && existing.to == edge.to)
+                {
+                    Some(existing) => existing.count += edge.count,
+                    None => bucket.migrations.push(edge.clone()),
+                }
+            }
+        }
+
+        buckets.sort_by(|a, b| a.window_start.cmp(&b.window_start).then(a.species_id.cmp(&b.species_id)));
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    fn rollup(species: &str, taken_at: DateTime<Utc>, population: u64, births: u64) -> PopulationRollup {
+        PopulationRollup {
+            species_id: species.to_string(),
+            species_name: species.to_string(),
+            taken_at,
+            population,
+            regions: Vec::new(),
+            births,
+            deaths: 0,
+            migrations: vec![MigrationEdge {
+                from: RegionId(Uuid::from_u128(1)),
+                to: RegionId(Uuid::from_u128(2)),
+                count: 1,
+            }],
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn thresholds_grade_population() {
+        let thresholds = ConservationThresholds { endangered_below: 50 };
+        assert_eq!(thresholds.status_for(0), ConservationStatus::Extinct);
+        assert_eq!(thresholds.status_for(49), ConservationStatus::Endangered);
+        assert_eq!(thresholds.status_for(50), ConservationStatus::Stable);
+    }
+
+    #[test]
+    fn rollups_fold_into_windows_per_species() {
+        let mut series = PopulationSeries::default();
+        series.push(rollup("stag", at(10, 5), 100, 2));
+        series.push(rollup("stag", at(10, 40), 80, 3));
+        series.push(rollup("turtle", at(10, 50), 30, 1));
+        series.push(rollup("stag", at(11, 10), 90, 4));
+
+        let buckets = series.query(&RollupQuery::default());
+        let keys: Vec<_> = buckets.iter().map(|bucket| (bucket.species_id.as_str(), bucket.window_start)).collect();
+        assert_eq!(keys, [("stag", at(10, 0)), ("turtle", at(10, 0)), ("stag", at(11, 0))]);
+
+        let first = &buckets[0];
+        assert_eq!((first.samples, first.min_population, first.max_population), (2, 80, 100));
+        assert_eq!(first.average_population, 90.0);
+        assert_eq!((first.closing_population, first.births), (80, 5));
+        assert_eq!(first.migrations.len(), 1);
+        assert_eq!(first.migrations[0].count, 2);
+
+        let stag_after_ten = series.query(&RollupQuery {
+            species: Some("stag".to_string()),
+            window: AggregationWindow::Day,
+            since: Some(at(10, 30)),
+            until: None,
+        });
+        assert_eq!(stag_after_ten.len(), 1);
+        assert_eq!((stag_after_ten[0].samples, stag_after_ten[0].closing_population), (2, 90));
+    }
+
+    #[test]
+    fn rollups_older_than_retention_are_dropped() {
+        let mut series = PopulationSeries::default();
+        series.push(rollup("stag", at(10, 0) - Duration::days(RETENTION_DAYS + 1), 100, 0));
+        series.push(rollup("stag", at(10, 0), 100, 0));
+        assert_eq!(series.query(&RollupQuery::default()).len(), 1);
+    }
+}
@@ -31,7 +31,7 @@ pub trait GameEventBus: Send + Sync {
         self.subscribe_raw(
             &topic,
             Box::new(move |payload| {
-                if let Ok(event) = serde_json::from_slice::<Event>(&payload) {
+                if let Ok(event) = crate::schema::decode(&payload) {
                     handler(event);
                 }
             }),
@@ -41,4 +41,9 @@ pub trait GameEventBus: Send + Sync {
     
     /// Unsubscribe from a topic
     async fn unsubscribe(&self, subscription_id: &str) -> anyhow::Result<()>;
+    
+    /// Backend details for operator debug endpoints
+    async fn debug_info(&self) -> serde_json::Value {
+        serde_json::json!({ "backend": "unknown" })
+    }
 }
\ No newline at end of file
@@ -1,12 +1,13 @@
 // crates/events/src/events.rs
 use serde::{Deserialize, Serialize};
-use finalverse_core::{RegionId, TerrainType, WeatherType};
+use finalverse_core::{world_clock, AnnotationDoc, AnnotationGrid, InfectionStage, RegionId, TenantId, TerraformKind, TerrainType, WeatherType, WorldInstant};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-// Player types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub struct PlayerId(pub String);
+use crate::schema::{CURRENT_SCHEMA_VERSION, UNVERSIONED_SCHEMA_VERSION};
+
+// Player types; string ids from older events still deserialize
+pub use finalverse_core::PlayerId;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coordinates {
@@ -20,6 +21,9 @@ pub struct Coordinates {
 pub struct Event {
     pub id: String,
     pub timestamp: DateTime<Utc>,
+    /// World time at emission, when the service runs a world clock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_time: Option<WorldInstant>,
     pub event_type: EventType,
     pub metadata: EventMetadata,
 }
@@ -29,6 +33,7 @@ impl Event {
         Self {
             id: Uuid::new_v4().to_string(),
             timestamp: Utc::now(),
+            world_time: world_clock::now(),
             event_type,
             metadata: EventMetadata::default(),
         }
@@ -48,16 +53,40 @@ impl Event {
             EventType::Echo(_) => "events.echo".to_string(),
             EventType::Silence(_) => "events.silence".to_string(),
             EventType::System(_) => "events.system".to_string(),
+            EventType::Community(_) => "events.community".to_string(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventMetadata {
     pub source: Option<String>,
     pub correlation_id: Option<String>,
     pub causation_id: Option<String>,
     pub tags: Vec<String>,
+    /// The world the event happened in; stamped by [`crate::TenantEventBus`].
+    #[serde(default)]
+    pub tenant_id: TenantId,
+    /// Schema the event was written with; see [`crate::schema`].
+    #[serde(default = "unversioned")]
+    pub schema_version: u32,
+}
+
+fn unversioned() -> u32 {
+    UNVERSIONED_SCHEMA_VERSION
+}
+
+impl Default for EventMetadata {
+    fn default() -> Self {
+        Self {
+            source: None,
+            correlation_id: None,
+            causation_id: None,
+            tags: Vec::new(),
+            tenant_id: TenantId::default(),
+            schema_version: CURRENT_SCHEMA_VERSION,
+        }
+    }
 }
 
 // Event types
@@ -70,6 +99,7 @@ pub enum EventType {
     Echo(EchoEvent),
     Silence(SilenceEvent),
     System(SystemEvent),
+    Community(CommunityEvent),
 }
 
 // Player events
@@ -80,6 +110,21 @@ pub enum PlayerEvent {
     Moved { player_id: PlayerId, from: Coordinates, to: Coordinates },
     ActionPerformed { player_id: PlayerId, action: PlayerAction },
     LevelUp { player_id: PlayerId, new_level: u32 },
+    /// The player's streaming context moved into an instanced vision scene.
+    VisionEntered { player_id: PlayerId, vision_id: String, instance_id: String, origin: Coordinates },
+    /// The player left a vision and must be restored to `return_to`.
+    VisionEnded {
+        player_id: PlayerId,
+        vision_id: String,
+        instance_id: String,
+        return_to: Coordinates,
+        state: serde_json::Value,
+        reason: String,
+    },
+    /// A player crafted `quantity` of `item_id` with `recipe_id`.
+    ItemCrafted { player_id: PlayerId, recipe_id: String, item_id: String, item_name: String, quantity: u32 },
+    /// A player used up `quantity` of `item_id`.
+    ItemConsumed { player_id: PlayerId, item_id: String, item_name: String, quantity: u32 },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +144,11 @@ pub enum WorldEvent {
     CreatureMigration { species: String, from: RegionId, to: RegionId },
     CelestialEvent { event_type: CelestialEventType, duration: u64 },
     GeologicalEvent { event_type: GeologicalEventType, location: Coordinates },
+    EntitySpawnRequested { entity_type: String, location: Coordinates, requested_by: String },
+    ScriptedEvent { name: String, entity_id: String, player_id: PlayerId, payload: serde_json::Value },
+    /// A player came near an echo. `grid_x`/`grid_y` is the world3d grid
+    /// the echo stands in.
+    EchoAppeared { echo_id: Uuid, echo_name: String, player_id: PlayerId, location: Coordinates, grid_x: i32, grid_y: i32 },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +198,13 @@ pub enum HarmonyEvent {
         harmony: String,
         tier_required: u32,
     },
+    ResonanceGifted {
+        from: PlayerId,
+        to: PlayerId,
+        resonance_type: ResonanceType,
+        amount: f64,
+        received: f64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -176,6 +233,31 @@ pub enum SongEvent {
         symphony_type: String,
         success: bool,
     },
+    /// Someone joined or added power to a symphony that is still gathering.
+    SymphonyProgressed {
+        symphony_id: String,
+        symphony_type: String,
+        participants: Vec<PlayerId>,
+        current_power: f64,
+        required_power: f64,
+    },
+    /// A player performed a melody through the song engine.
+    MelodyWoven {
+        player_id: PlayerId,
+        harmony_type: String,
+        power: f64,
+        location: Coordinates,
+        /// None outside every mapped region.
+        region_id: Option<RegionId>,
+    },
+    /// A melody moved a region's harmony, or the world's when it was
+    /// performed outside every region.
+    HarmonyAchieved {
+        player_id: PlayerId,
+        region_id: Option<RegionId>,
+        harmony_impact: f64,
+        harmony_level: f64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -205,6 +287,16 @@ pub enum EchoEvent {
         echo_name: String,
         ability: String,
     },
+    /// A bond grew, through interaction or a reward. Levels run from 0 to 1.
+    EchoBondIncreased {
+        player_id: PlayerId,
+        echo_id: Uuid,
+        echo_name: String,
+        gained: f32,
+        new_level: f32,
+        /// "stranger", "companion" or "kindred".
+        tier: String,
+    },
 }
 
 // Silence events
@@ -229,6 +321,41 @@ pub enum SilenceEvent {
         purifier_id: PlayerId,
         area_restored: f64,
     },
+    /// A player's Silence infection crossed a stage threshold.
+    InfectionStageChanged {
+        player_id: PlayerId,
+        from: InfectionStage,
+        to: InfectionStage,
+        exposure: f32,
+    },
+    /// Exposure was cleansed by a restoration melody or a ritual site.
+    InfectionCleansed {
+        player_id: PlayerId,
+        source: String,
+        amount: f32,
+        remaining: f32,
+    },
+    /// The Silence broke out in a low-harmony region and spreads from
+    /// `epicenter`.
+    SilenceOutbreak {
+        outbreak_id: Uuid,
+        region_id: RegionId,
+        epicenter: Coordinates,
+        radius: f64,
+        intensity: f64,
+    },
+    /// An outbreak's reach changed. `progress` is the share cleansed so far.
+    OutbreakSpread {
+        outbreak_id: Uuid,
+        radius: f64,
+        progress: f64,
+    },
+    /// Melodies within an outbreak wore it away entirely.
+    OutbreakCleansed {
+        outbreak_id: Uuid,
+        region_id: RegionId,
+        cleansers: Vec<PlayerId>,
+    },
 }
 
 // System events
@@ -239,4 +366,35 @@ pub enum SystemEvent {
     ServiceHealthChanged { service_name: String, healthy: bool },
     MaintenanceScheduled { start_time: DateTime<Utc>, duration: u64 },
     ServerRestart { reason: String, countdown: u64 },
-}
\ No newline at end of file
+}
+
+// Community events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CommunityEvent {
+    StewardshipClaimed { region_id: RegionId, ensemble_id: String },
+    StewardshipContested { region_id: RegionId, steward_id: String, challenger_id: String, resolves_at: DateTime<Utc> },
+    StewardshipTransferred { region_id: RegionId, from: String, to: String },
+    StewardshipLapsed { region_id: RegionId, ensemble_id: String },
+    UpkeepPerformed { region_id: RegionId, ensemble_id: String, harmony_restored: f64 },
+    RegionNamed { region_id: RegionId, ensemble_id: String, name: String },
+    TerraformProposed { proposal_id: Uuid, region_id: RegionId, ensemble_id: String, kind: TerraformKind, closes_at: DateTime<Utc> },
+    /// Voting ended. `outcome` is "approved", "rejected" or "underfunded".
+    TerraformDecided { proposal_id: Uuid, region_id: RegionId, kind: TerraformKind, outcome: String },
+    /// Annotation changes from one shard, to be merged by the others.
+    AnnotationsChanged { shard_id: Uuid, grid: AnnotationGrid, delta: AnnotationDoc },
+    AnnotationSettingsChanged { shard_id: Uuid, region_id: RegionId, enabled: bool, allow_public: bool },
+    PartyFormed { party_id: Uuid, leader_id: PlayerId },
+    /// The last member left.
+    PartyDisbanded { party_id: Uuid },
+    GuildFounded { guild_id: Uuid, name: String, founder_id: PlayerId },
+    MemberInvited { group: GroupKind, group_id: Uuid, player_id: PlayerId, invited_by: PlayerId },
+    MemberJoined { group: GroupKind, group_id: Uuid, player_id: PlayerId },
+    MemberLeft { group: GroupKind, group_id: Uuid, player_id: PlayerId },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupKind {
+    Party,
+    Guild,
+}
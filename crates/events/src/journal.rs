@@ -0,0 +1,231 @@
+// crates/events/src/journal.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+
+use crate::event_bus::GameEventBus;
+use crate::events::Event;
+
+/// One recorded event. Sequence numbers start at 1 and increase by one per
+/// append, so a reader can resume after the last entry it saw.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub sequence: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub topic: String,
+    pub event: Event,
+}
+
+/// Where a replay starts. Both bounds are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayFrom {
+    Sequence(u64),
+    Timestamp(DateTime<Utc>),
+}
+
+impl ReplayFrom {
+    fn includes(&self, entry: &JournalEntry) -> bool {
+        match self {
+            ReplayFrom::Sequence(sequence) => entry.sequence >= *sequence,
+            ReplayFrom::Timestamp(since) => entry.event.timestamp >= *since,
+        }
+    }
+}
+
+/// Append-only record of published events.
+#[async_trait]
+pub trait EventJournal: Send + Sync {
+    /// Record an event and return its sequence number.
+    async fn append(&self, topic: &str, event: &Event) -> anyhow::Result<u64>;
+
+    /// Entries from `from` onwards in sequence order, at most `limit` of them.
+    async fn replay(&self, from: ReplayFrom, limit: Option<usize>) -> anyhow::Result<Vec<JournalEntry>>;
+
+    /// Sequence number of the latest entry, 0 while the journal is empty.
+    async fn last_sequence(&self) -> u64;
+}
+
+/// Journal kept as JSON lines in a local file. A line torn by a crash
+/// mid-write is skipped on replay.
+pub struct FileJournal {
+    path: PathBuf,
+    writer: Mutex<(File, u64)>,
+}
+
+impl FileJournal {
+    pub async fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+        let last = read_entries(&path)
+            .await?
+            .last()
+            .map(|entry| entry.sequence)
+            .unwrap_or(0);
+        Ok(Self {
+            path,
+            writer: Mutex::new((file, last)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+async fn read_entries(path: &Path) -> anyhow::Result<Vec<JournalEntry>> {
+    let mut lines = BufReader::new(File::open(path).await?).lines();
+    let mut entries = Vec::new();
+    while let Some(line) = lines.next_line().await? {
+        if let Ok(entry) = serde_json::from_str::<JournalEntry>(&line) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+#[async_trait]
+impl EventJournal for FileJournal {
+    async fn append(&self, topic: &str, event: &Event) -> anyhow::Result<u64> {
+        let mut writer = self.writer.lock().await;
+        let entry = JournalEntry {
+            sequence: writer.1 + 1,
+            recorded_at: Utc::now(),
+            topic: topic.to_string(),
+            event: event.clone(),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        writer.0.write_all(&line).await?;
+        writer.0.flush().await?;
+        writer.1 = entry.sequence;
+        Ok(entry.sequence)
+    }
+
+    async fn replay(&self, from: ReplayFrom, limit: Option<usize>) -> anyhow::Result<Vec<JournalEntry>> {
+        // Hold the writer so a replay never sees a half-written line
+        let _writer = self.writer.lock().await;
+        Ok(read_entries(&self.path)
+            .await?
+            .into_iter()
+            .filter(|entry| from.includes(entry))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn last_sequence(&self) -> u64 {
+        self.writer.lock().await.1
+    }
+}
+
+/// Records every event published through it before handing it on. Wrap the
+/// backend bus, inside any [`crate::TenantEventBus`], so the journal sees
+/// events as they go out on the wire.
+pub struct JournaledEventBus {
+    inner: Arc<dyn GameEventBus>,
+    journal: Arc<dyn EventJournal>,
+}
+
+impl JournaledEventBus {
+    pub fn new(inner: Arc<dyn GameEventBus>, journal: Arc<dyn EventJournal>) -> Self {
+        Self { inner, journal }
+    }
+
+    /// Journal to the file named by `EVENT_JOURNAL_PATH`, if set. Returns
+    /// the journal alongside the bus so the service can replay from it.
+    pub async fn from_env(
+        inner: Arc<dyn GameEventBus>,
+    ) -> anyhow::Result<(Arc<dyn GameEventBus>, Option<Arc<dyn EventJournal>>)> {
+        let Ok(path) = std::env::var("EVENT_JOURNAL_PATH") else {
+            return Ok((inner, None));
+        };
+        let journal: Arc<dyn EventJournal> = Arc::new(FileJournal::open(path).await?);
+        Ok((Arc::new(Self::new(inner, journal.clone())), Some(journal)))
+    }
+
+    pub fn journal(&self) -> &Arc<dyn EventJournal> {
+        &self.journal
+    }
+}
+
+#[async_trait]
+impl GameEventBus for JournaledEventBus {
+    async fn publish_raw(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        // Raw payloads that aren't events have nothing to replay
+        if let Ok(event) = crate::schema::decode(&payload) {
+            self.journal.append(topic, &event).await?;
+        }
+        self.inner.publish_raw(topic, payload).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        topic: &str,
+        handler: Box<dyn Fn(Vec<u8>) + Send + Sync + 'static>,
+    ) -> anyhow::Result<String> {
+        self.inner.subscribe_raw(topic, handler).await
+    }
+
+    async fn unsubscribe(&self, subscription_id: &str) -> anyhow::Result<()> {
+        self.inner.unsubscribe(subscription_id).await
+    }
+
+    async fn debug_info(&self) -> serde_json::Value {
+        serde_json::json!({
+            "journal_sequence": self.journal.last_sequence().await,
+            "inner": self.inner.debug_info().await,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventType, SystemEvent};
+    use crate::LocalEventBus;
+
+    #[tokio::test]
+    async fn published_events_replay_after_reopening() {
+        let path = std::env::temp_dir().join(format!("fv-journal-{}.jsonl", uuid::Uuid::new_v4()));
+        let journal = Arc::new(FileJournal::open(&path).await.unwrap());
+        let bus = JournaledEventBus::new(Arc::new(LocalEventBus::new()), journal.clone());
+
+        let mut published = Vec::new();
+        for service in ["story-engine", "song-engine", "harmony-service"] {
+            let event = Event::new(EventType::System(SystemEvent::ServiceStarted {
+                service_name: service.to_string(),
+            }));
+            published.push(event.clone());
+            bus.publish(event).await.unwrap();
+        }
+        bus.publish_raw("events.raw", b"not an event".to_vec()).await.unwrap();
+        drop(bus);
+        drop(journal);
+
+        // A restarted service picks up the numbering where it left off
+        let reopened = FileJournal::open(&path).await.unwrap();
+        assert_eq!(reopened.last_sequence().await, 3);
+
+        let tail = reopened.replay(ReplayFrom::Sequence(2), None).await.unwrap();
+        assert_eq!(tail.iter().map(|entry| entry.sequence).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(tail[0].event.id, published[1].id);
+        assert_eq!(tail[0].topic, "events.system");
+
+        let since = reopened.replay(ReplayFrom::Timestamp(published[2].timestamp), None).await.unwrap();
+        assert_eq!(since.len(), 1);
+        assert_eq!(reopened.replay(ReplayFrom::Sequence(1), Some(1)).await.unwrap().len(), 1);
+
+        let event = Event::new(EventType::System(SystemEvent::ServiceStopped {
+            service_name: "story-engine".to_string(),
+        }));
+        assert_eq!(reopened.append("events.system", &event).await.unwrap(), 4);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -1,13 +1,19 @@
 // crates/events/src/lib.rs
 pub mod event_bus;
 pub mod events;
+pub mod journal;
 pub mod nats;
 pub mod local;
+pub mod schema;
+pub mod tenant;
 
 pub use event_bus::GameEventBus;
 pub use events::*;
-pub use nats::NatsEventBus;
+pub use journal::{EventJournal, FileJournal, JournalEntry, JournaledEventBus, ReplayFrom};
+pub use nats::{DeadLetter, FailureStage, NatsEventBus, RetryPolicy};
 pub use local::LocalEventBus;
+pub use schema::{SchemaError, SchemaRegistry, CURRENT_SCHEMA_VERSION};
+pub use tenant::TenantEventBus;
 
 // Re-export commonly used types
 pub use async_trait::async_trait;
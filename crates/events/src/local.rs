@@ -65,7 +65,9 @@ impl GameEventBus for LocalEventBus {
         let sub_id_clone = subscription_id.clone();
         let subscriptions = self.subscriptions.clone();
         tokio::spawn(async move {
-            if let Some(mut receiver) = subscriptions.write().await.remove(&sub_id_clone) {
+            // Take the receiver in its own statement so the lock isn't held while receiving
+            let receiver = subscriptions.write().await.remove(&sub_id_clone);
+            if let Some(mut receiver) = receiver {
                 let handler = handler;
                 while let Ok(payload) = receiver.recv().await {
                     handler(payload);
@@ -80,4 +82,15 @@ impl GameEventBus for LocalEventBus {
         self.subscriptions.write().await.remove(subscription_id);
         Ok(())
     }
+    
+    async fn debug_info(&self) -> serde_json::Value {
+        let topics: HashMap<String, usize> = self
+            .channels
+            .read()
+            .await
+            .iter()
+            .map(|(topic, sender)| (topic.clone(), sender.receiver_count()))
+            .collect();
+        serde_json::json!({ "backend": "local", "subscribers_by_topic": topics })
+    }
 }
\ No newline at end of file
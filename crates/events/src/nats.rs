@@ -1,37 +1,226 @@
 // crates/events/src/nats.rs
 use futures_util::StreamExt;
 use async_nats::{Client, Subscriber};
+use chrono::{DateTime, Utc};
+use finalverse_config::EventBusConfig;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
-use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::event_bus::GameEventBus;
+use crate::events::Event;
+
+/// Dead letters listed by `debug_info`.
+const DEBUG_DEAD_LETTERS: usize = 10;
+
+/// How often failed publishes and deliveries are retried before the event
+/// is dead-lettered.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub dead_letter_subject: String,
+    pub dead_letter_capacity: usize,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &EventBusConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            dead_letter_subject: config.dead_letter_subject.clone(),
+            dead_letter_capacity: config.dead_letter_capacity,
+        }
+    }
+
+    pub fn load() -> Self {
+        let config = finalverse_config::load_default_config()
+            .map(|config| config.event_bus)
+            .unwrap_or_default();
+        Self::from_config(&config)
+    }
+
+    /// Delay before retry number `retry`, counting from 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&EventBusConfig::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureStage {
+    Publish,  // NATS refused the publish
+    Delivery, // A local subscriber's handler panicked
+}
+
+/// An event that exhausted its retries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: String,
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub stage: FailureStage,
+    pub attempts: u32,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    /// The payload as a typed event, when it is one.
+    pub fn event(&self) -> Option<Event> {
+        crate::schema::decode(&self.payload).ok()
+    }
+}
+
+/// Dead letters kept in memory, newest last, and mirrored to the
+/// dead-letter subject for anything listening there.
+struct DeadLetterQueue {
+    client: Arc<RwLock<Client>>,
+    policy: RetryPolicy,
+    letters: Mutex<VecDeque<DeadLetter>>,
+}
+
+impl DeadLetterQueue {
+    async fn record(&self, topic: &str, payload: Vec<u8>, stage: FailureStage, attempts: u32, error: String) {
+        error!("Dead-lettering event on {} after {} attempts: {}", topic, attempts, error);
+        let letter = DeadLetter {
+            id: Uuid::new_v4().to_string(),
+            topic: topic.to_string(),
+            payload,
+            stage,
+            attempts,
+            error,
+            failed_at: Utc::now(),
+        };
+
+        // A failing dead-letter subject must not feed itself
+        if topic != self.policy.dead_letter_subject {
+            if let Ok(body) = serde_json::to_vec(&letter) {
+                let client = self.client.read().await;
+                if let Err(e) = client.publish(self.policy.dead_letter_subject.clone(), body.into()).await {
+                    warn!("Failed to publish dead letter {}: {}", letter.id, e);
+                }
+            }
+        }
+
+        let mut letters = self.letters.lock().unwrap();
+        letters.push_back(letter);
+        while letters.len() > self.policy.dead_letter_capacity {
+            letters.pop_front();
+        }
+    }
+}
 
 pub struct NatsEventBus {
     client: Arc<RwLock<Client>>,
     subscriptions: Arc<RwLock<HashMap<String, Subscriber>>>,
+    dead_letters: Arc<DeadLetterQueue>,
 }
 
 impl NatsEventBus {
+    /// Connect with the retry policy from `event_bus` in `FinalverseConfig`.
     pub async fn new(nats_url: &str) -> anyhow::Result<Self> {
-        let client = async_nats::connect(nats_url).await?;
+        Self::with_retry_policy(nats_url, RetryPolicy::load()).await
+    }
+
+    pub async fn with_retry_policy(nats_url: &str, policy: RetryPolicy) -> anyhow::Result<Self> {
+        let client = Arc::new(RwLock::new(async_nats::connect(nats_url).await?));
         Ok(Self {
-            client: Arc::new(RwLock::new(client)),
+            client: client.clone(),
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            dead_letters: Arc::new(DeadLetterQueue {
+                client,
+                policy,
+                letters: Mutex::new(VecDeque::new()),
+            }),
         })
     }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.dead_letters.policy
+    }
+
+    /// Dead letters still held in memory, oldest first.
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.letters.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Take the held dead letters, e.g. to republish them once the cause
+    /// is fixed.
+    pub fn drain_dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.letters.lock().unwrap().drain(..).collect()
+    }
+}
+
+/// Run `handler`, retrying with backoff while it panics. Returns the number
+/// of attempts and the last panic message once the policy gives up.
+async fn deliver(
+    handler: &(dyn Fn(Vec<u8>) + Send + Sync),
+    payload: &[u8],
+    policy: &RetryPolicy,
+) -> Result<(), (u32, String)> {
+    let mut attempt = 1;
+    loop {
+        match catch_unwind(AssertUnwindSafe(|| handler(payload.to_vec()))) {
+            Ok(()) => return Ok(()),
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "handler panicked".to_string());
+                if attempt >= policy.max_attempts {
+                    return Err((attempt, message));
+                }
+                warn!("Event handler failed (attempt {}): {}", attempt, message);
+                tokio::time::sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
 impl GameEventBus for NatsEventBus {
     async fn publish_raw(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()> {
-        self.client
-            .read()
-            .await
-            .publish(topic.to_string(), payload.into())
-            .await?;
-        Ok(())
+        let policy = &self.dead_letters.policy;
+        let mut attempt = 1;
+        loop {
+            let result = self
+                .client
+                .read()
+                .await
+                .publish(topic.to_string(), payload.clone().into())
+                .await;
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < policy.max_attempts => {
+                    warn!("Publish to {} failed (attempt {}): {}", topic, attempt, e);
+                    tokio::time::sleep(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.dead_letters
+                        .record(topic, payload, FailureStage::Publish, attempt, e.to_string())
+                        .await;
+                    return Err(e.into());
+                }
+            }
+        }
     }
 
     async fn subscribe_raw(
@@ -41,27 +230,95 @@ impl GameEventBus for NatsEventBus {
     ) -> anyhow::Result<String> {
         let subscriber = self.client.read().await.subscribe(topic.to_string()).await?;
         let subscription_id = Uuid::new_v4().to_string();
-        
+
         let sub_id_clone = subscription_id.clone();
         let subscriptions = self.subscriptions.clone();
-        
+        let dead_letters = self.dead_letters.clone();
+
         // Store the subscriber
         subscriptions.write().await.insert(sub_id_clone.clone(), subscriber);
-        
-        // Spawn handler task
+
+        // Spawn handler task. Retries hold up later messages on this
+        // subscription, which keeps delivery in order.
         tokio::spawn(async move {
             let mut sub = subscriptions.write().await.remove(&sub_id_clone).unwrap();
             let handler = handler;
             while let Some(msg) = sub.next().await {
-                handler(msg.payload.to_vec());
+                if let Err((attempts, error)) = deliver(handler.as_ref(), &msg.payload, &dead_letters.policy).await {
+                    dead_letters
+                        .record(msg.subject.as_str(), msg.payload.to_vec(), FailureStage::Delivery, attempts, error)
+                        .await;
+                }
             }
         });
-        
+
         Ok(subscription_id)
     }
-    
+
     async fn unsubscribe(&self, subscription_id: &str) -> anyhow::Result<()> {
         self.subscriptions.write().await.remove(subscription_id);
         Ok(())
     }
+
+    async fn debug_info(&self) -> serde_json::Value {
+        let state = self.client.read().await.connection_state();
+        let letters = self.dead_letters.letters.lock().unwrap();
+        let recent: Vec<_> = letters
+            .iter()
+            .rev()
+            .take(DEBUG_DEAD_LETTERS)
+            .map(|letter| {
+                serde_json::json!({
+                    "id": letter.id,
+                    "topic": letter.topic,
+                    "stage": letter.stage,
+                    "attempts": letter.attempts,
+                    "error": letter.error,
+                    "failed_at": letter.failed_at,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "backend": "nats",
+            "connection_state": format!("{:?}", state),
+            "dead_letter_subject": self.dead_letters.policy.dead_letter_subject,
+            "dead_letters": letters.len(),
+            "recent_dead_letters": recent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn failing_handlers_are_retried_with_backoff_then_given_up() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(1));
+        assert_eq!(policy.backoff(2), Duration::from_millis(2));
+        assert_eq!(policy.backoff(10), Duration::from_millis(2));
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let flaky = move |_: Vec<u8>| {
+            if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                panic!("store unavailable");
+            }
+        };
+        assert_eq!(deliver(&flaky, b"{}", &policy).await, Ok(()));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let broken = |_: Vec<u8>| panic!("bad payload {}", 7);
+        assert_eq!(
+            deliver(&broken, b"{}", &policy).await,
+            Err((3, "bad payload 7".to_string()))
+        );
+    }
 }
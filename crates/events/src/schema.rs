@@ -0,0 +1,190 @@
+// crates/events/src/schema.rs
+//! Event schema versions.
+//!
+//! Every event records the schema version it was written with in
+//! `metadata.schema_version`. Subscribers decode through [`decode`], which
+//! upgrades payloads from older producers one version at a time before
+//! deserialising them, so services on different releases can share a bus
+//! during a rolling deploy. Payloads from newer producers are read as-is
+//! when they still fit; unknown fields are ignored.
+//!
+//! When a change would stop older payloads from deserialising, bump
+//! [`CURRENT_SCHEMA_VERSION`] and register an upgrade from the previous
+//! version in [`SchemaRegistry::standard`].
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+use crate::events::Event;
+
+/// The version this build writes.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Payloads written before versioning carry no version.
+pub const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// Rewrites a payload of one version into the next.
+pub type Upgrade = fn(&mut Value);
+
+#[derive(Error, Debug)]
+pub enum SchemaError {
+    #[error("Event payload is not valid JSON: {0}")]
+    Malformed(serde_json::Error),
+
+    #[error("No upgrade registered from event schema version {0}")]
+    MissingUpgrade(u32),
+
+    #[error("Event schema version {version} is newer than {current} and can't be read: {source}")]
+    Unsupported {
+        version: u32,
+        current: u32,
+        source: serde_json::Error,
+    },
+
+    #[error("Event doesn't match schema version {version}: {source}")]
+    Invalid { version: u32, source: serde_json::Error },
+}
+
+/// Upgrades between schema versions, keyed by the version they upgrade from.
+pub struct SchemaRegistry {
+    current: u32,
+    upgrades: BTreeMap<u32, Upgrade>,
+}
+
+impl SchemaRegistry {
+    pub fn new(current: u32) -> Self {
+        Self {
+            current,
+            upgrades: BTreeMap::new(),
+        }
+    }
+
+    /// The upgrades every release since versioning began needs.
+    pub fn standard() -> Self {
+        Self::new(CURRENT_SCHEMA_VERSION).with_upgrade(1, v1_to_v2)
+    }
+
+    pub fn with_upgrade(mut self, from: u32, upgrade: Upgrade) -> Self {
+        self.upgrades.insert(from, upgrade);
+        self
+    }
+
+    pub fn current(&self) -> u32 {
+        self.current
+    }
+
+    /// The version a payload was written with.
+    pub fn version_of(payload: &Value) -> u32 {
+        payload
+            .pointer("/metadata/schema_version")
+            .and_then(Value::as_u64)
+            .map(|version| version as u32)
+            .unwrap_or(UNVERSIONED_SCHEMA_VERSION)
+    }
+
+    /// Bring a payload up to the current version, returning the version it
+    /// started at. Newer payloads are left alone.
+    pub fn upgrade(&self, payload: &mut Value) -> Result<u32, SchemaError> {
+        let original = Self::version_of(payload);
+        let mut version = original;
+        while version < self.current {
+            let upgrade = self.upgrades.get(&version).ok_or(SchemaError::MissingUpgrade(version))?;
+            upgrade(payload);
+            version += 1;
+            if let Some(metadata) = payload.get_mut("metadata").and_then(Value::as_object_mut) {
+                metadata.insert("schema_version".to_string(), version.into());
+            }
+        }
+        Ok(original)
+    }
+
+    pub fn decode(&self, payload: &[u8]) -> Result<Event, SchemaError> {
+        let mut value: Value = serde_json::from_slice(payload).map_err(SchemaError::Malformed)?;
+        let version = self.upgrade(&mut value)?;
+        serde_json::from_value(value).map_err(|source| {
+            if version > self.current {
+                SchemaError::Unsupported {
+                    version,
+                    current: self.current,
+                    source,
+                }
+            } else {
+                SchemaError::Invalid { version, source }
+            }
+        })
+    }
+}
+
+/// Decode an event with the standard upgrades.
+pub fn decode(payload: &[u8]) -> Result<Event, SchemaError> {
+    static STANDARD: OnceLock<SchemaRegistry> = OnceLock::new();
+    STANDARD.get_or_init(SchemaRegistry::standard).decode(payload)
+}
+
+/// Version 1 producers could leave out metadata tags, which are required
+/// from version 2.
+fn v1_to_v2(payload: &mut Value) {
+    if let Some(metadata) = payload.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.entry("tags").or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventType, SystemEvent};
+
+    // A system event as version 1 producers wrote it: no schema version,
+    // tenant or tags.
+    const V1_SERVICE_STARTED: &str = r#"{
+        "id": "evt-1",
+        "timestamp": "2025-05-01T12:00:00Z",
+        "event_type": { "System": { "ServiceStarted": { "service_name": "story-engine" } } },
+        "metadata": { "source": "story-engine", "correlation_id": null, "causation_id": null }
+    }"#;
+
+    #[test]
+    fn services_on_neighbouring_versions_read_each_other() {
+        // Older producer, current consumer
+        let event = decode(V1_SERVICE_STARTED.as_bytes()).unwrap();
+        assert_eq!(event.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+        assert!(event.metadata.tags.is_empty());
+        assert!(event.metadata.tenant_id.is_default());
+        assert!(matches!(
+            event.event_type,
+            EventType::System(SystemEvent::ServiceStarted { ref service_name }) if service_name == "story-engine"
+        ));
+
+        // Current producer round-trips
+        let current = Event::new(EventType::System(SystemEvent::ServiceStopped {
+            service_name: "song-engine".to_string(),
+        }));
+        let decoded = decode(&serde_json::to_vec(&current).unwrap()).unwrap();
+        assert_eq!(decoded.id, current.id);
+        assert_eq!(decoded.metadata.schema_version, CURRENT_SCHEMA_VERSION);
+
+        // Newer producer that only added fields
+        let mut newer = serde_json::to_value(&current).unwrap();
+        newer["metadata"]["schema_version"] = (CURRENT_SCHEMA_VERSION + 1).into();
+        newer["metadata"]["priority"] = "high".into();
+        newer["trace"] = serde_json::json!({ "span": "abc" });
+        let decoded = decode(&serde_json::to_vec(&newer).unwrap()).unwrap();
+        assert_eq!(decoded.id, current.id);
+
+        // Newer producer with a variant this build doesn't know
+        newer["event_type"] = serde_json::json!({ "Weather": { "Storm": {} } });
+        assert!(matches!(
+            decode(&serde_json::to_vec(&newer).unwrap()),
+            Err(SchemaError::Unsupported { version, .. }) if version == CURRENT_SCHEMA_VERSION + 1
+        ));
+
+        // A gap in the upgrade chain is reported rather than guessed at
+        let registry = SchemaRegistry::new(CURRENT_SCHEMA_VERSION + 1).with_upgrade(1, v1_to_v2);
+        assert!(matches!(
+            registry.decode(V1_SERVICE_STARTED.as_bytes()),
+            Err(SchemaError::MissingUpgrade(2))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+// crates/events/src/tenant.rs
+use async_trait::async_trait;
+use finalverse_core::TenantId;
+use std::sync::Arc;
+
+use crate::event_bus::GameEventBus;
+use crate::events::Event;
+
+/// Confines a shared event bus to one tenant. Topics are mapped onto the
+/// tenant's subjects, published events are stamped with the tenant, and
+/// subscribers only see events stamped with it, so worlds sharing a NATS
+/// cluster never observe each other's traffic.
+pub struct TenantEventBus {
+    inner: Arc<dyn GameEventBus>,
+    tenant: TenantId,
+}
+
+impl TenantEventBus {
+    pub fn new(inner: Arc<dyn GameEventBus>, tenant: TenantId) -> Self {
+        Self { inner, tenant }
+    }
+
+    /// Scope `inner` to the tenant named by `FINALVERSE_TENANT_ID`.
+    pub fn from_env(inner: Arc<dyn GameEventBus>) -> Arc<dyn GameEventBus> {
+        Arc::new(Self::new(inner, TenantId::from_env()))
+    }
+
+    pub fn tenant(&self) -> &TenantId {
+        &self.tenant
+    }
+}
+
+#[async_trait]
+impl GameEventBus for TenantEventBus {
+    async fn publish_raw(&self, topic: &str, payload: Vec<u8>) -> anyhow::Result<()> {
+        self.inner.publish_raw(&self.tenant.subject(topic), payload).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        topic: &str,
+        handler: Box<dyn Fn(Vec<u8>) + Send + Sync + 'static>,
+    ) -> anyhow::Result<String> {
+        self.inner.subscribe_raw(&self.tenant.subject(topic), handler).await
+    }
+
+    async fn publish(&self, mut event: Event) -> anyhow::Result<()> {
+        if event.metadata.tenant_id.is_default() {
+            event.metadata.tenant_id = self.tenant.clone();
+        } else if event.metadata.tenant_id != self.tenant {
+            anyhow::bail!(
+                "Event {} belongs to tenant {}, not {}",
+                event.id,
+                event.metadata.tenant_id,
+                self.tenant
+            );
+        }
+        let payload = serde_json::to_vec(&event)?;
+        self.publish_raw(&event.topic(), payload).await
+    }
+
+    async fn subscribe(
+        &self,
+        topic: &str,
+        handler: Box<dyn Fn(Event) + Send + Sync + 'static>,
+    ) -> anyhow::Result<String> {
+        let tenant = self.tenant.clone();
+        self.subscribe_raw(
+            topic,
+            Box::new(move |payload| {
+                if let Ok(event) = crate::schema::decode(&payload) {
+                    if event.metadata.tenant_id == tenant {
+                        handler(event);
+                    }
+                }
+            }),
+        )
+        .await
+    }
+
+    async fn unsubscribe(&self, subscription_id: &str) -> anyhow::Result<()> {
+        self.inner.unsubscribe(subscription_id).await
+    }
+
+    async fn debug_info(&self) -> serde_json::Value {
+        let mut info = self.inner.debug_info().await;
+        if let Some(info) = info.as_object_mut() {
+            info.insert("tenant".to_string(), serde_json::json!(self.tenant));
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventMetadata, EventType, SystemEvent};
+    use crate::LocalEventBus;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn tenant(id: &str) -> TenantId {
+        TenantId::parse(id).unwrap()
+    }
+
+    fn started(message: &str) -> Event {
+        Event::new(EventType::System(SystemEvent::ServiceStarted {
+            service_name: message.to_string(),
+        }))
+    }
+
+    async fn received(rx: &mut mpsc::UnboundedReceiver<Event>) -> Vec<String> {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut messages = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let EventType::System(SystemEvent::ServiceStarted { service_name }) = event.event_type {
+                messages.push(service_name);
+            }
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn tenants_sharing_a_bus_are_isolated() {
+        let shared: Arc<dyn GameEventBus> = Arc::new(LocalEventBus::new());
+        let main = TenantEventBus::new(shared.clone(), TenantId::default());
+        let staging = TenantEventBus::new(shared.clone(), tenant("staging"));
+        let partner = TenantEventBus::new(shared.clone(), tenant("partner-eu"));
+
+        let mut inboxes = Vec::new();
+        for bus in [&main, &staging, &partner] {
+            let (tx, rx) = mpsc::unbounded_channel();
+            bus.subscribe("events.system", Box::new(move |event| {
+                let _ = tx.send(event);
+            }))
+            .await
+            .unwrap();
+            inboxes.push(rx);
+        }
+
+        main.publish(started("main")).await.unwrap();
+        staging.publish(started("staging")).await.unwrap();
+        partner.publish(started("partner")).await.unwrap();
+
+        // One tenant can't publish events stamped as another's...
+        let foreign = started("foreign").with_metadata(EventMetadata {
+            tenant_id: tenant("staging"),
+            ..Default::default()
+        });
+        assert!(partner.publish(foreign.clone()).await.is_err());
+
+        // ...and a forged event on a tenant's subject is still dropped
+        let payload = serde_json::to_vec(&foreign).unwrap();
+        shared.publish_raw("tenants.partner-eu.events.system", payload).await.unwrap();
+
+        assert_eq!(received(&mut inboxes[0]).await, vec!["main"]);
+        assert_eq!(received(&mut inboxes[1]).await, vec!["staging"]);
+        assert_eq!(received(&mut inboxes[2]).await, vec!["partner"]);
+    }
+}
@@ -0,0 +1,69 @@
+// crates/grpc-client/src/discovery.rs
+//! Channels to services found through the service registry. Each channel
+//! is fed by a task that looks the service up again every
+//! [`REFRESH_INTERVAL`] and moves the channel to the new instance when the
+//! registry hands out a different one.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use service_registry::RegistryClient;
+use tokio::sync::mpsc::Sender;
+use tonic::transport::{Channel, Endpoint};
+use tower::discover::Change;
+
+/// How often a discovered service is looked up again.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+/// Endpoint changes waiting to be applied to a channel.
+const CHANGE_BUFFER: usize = 16;
+
+/// A channel to `service`. Calls made before the registry has returned an
+/// instance wait for one.
+pub fn discovered_channel(registry: Arc<RegistryClient>, service: &str) -> Channel {
+    let (channel, changes) = Channel::balance_channel(CHANGE_BUFFER);
+    tokio::spawn(follow(registry, service.to_string(), changes));
+    channel
+}
+
+/// Keep `changes` pointed at the instance the registry returns for
+/// `service`, until the channel is dropped.
+async fn follow(registry: Arc<RegistryClient>, service: String, changes: Sender<Change<String, Endpoint>>) {
+    let mut current: Option<String> = None;
+    let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        ticker.tick().await;
+        if changes.is_closed() {
+            return;
+        }
+
+        let addr = match registry.discover(&service).await {
+            Ok(Some(instance)) => format!("http://{}:{}", instance.host, instance.port),
+            // Keep using the last instance until the registry knows better
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("Looking up gRPC service {} failed: {}", service, e);
+                continue;
+            }
+        };
+        if current.as_ref() == Some(&addr) {
+            continue;
+        }
+
+        let endpoint = match crate::endpoint(&addr) {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                tracing::warn!("Registry returned an invalid address {} for {}: {}", addr, service, e);
+                continue;
+            }
+        };
+        tracing::info!("gRPC service {} is now at {}", service, addr);
+        if changes.send(Change::Insert(addr.clone(), endpoint)).await.is_err() {
+            return;
+        }
+        if let Some(old) = current.replace(addr) {
+            if changes.send(Change::Remove(old)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
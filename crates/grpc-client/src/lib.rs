@@ -1,9 +1,19 @@
 // crates/grpc-client/src/lib.rs
+pub mod discovery;
+pub mod prediction;
+
+pub use prediction::MovePredictor;
+
 use tonic::transport::{Channel, Endpoint};
+use std::sync::Arc;
 use std::time::Duration;
 use finalverse_proto::world::world_service_client::WorldServiceClient;
 use finalverse_proto::story::story_service_client::StoryServiceClient;
+use service_registry::RegistryClient;
 
+/// Channels connect on their first call and reconnect by themselves after
+/// transport errors, so cloning the client is cheap and a service that is
+/// down at start-up only fails the calls made while it is down.
 #[derive(Clone)]
 pub struct FinalverseGrpcClient {
     pub world: WorldServiceClient<Channel>,
@@ -11,26 +21,38 @@ pub struct FinalverseGrpcClient {
 }
 
 impl FinalverseGrpcClient {
+    /// Use fixed addresses. Fails only if an address isn't a valid URI.
     pub async fn connect(
         world_addr: &str,
         story_addr: &str,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let world_channel = create_channel(world_addr).await?;
-        let story_channel = create_channel(story_addr).await?;
+        let world_channel = create_channel(world_addr)?;
+        let story_channel = create_channel(story_addr)?;
 
         Ok(Self {
             world: WorldServiceClient::new(world_channel),
             story: StoryServiceClient::new(story_channel),
         })
     }
+
+    /// Find the services by name in the service registry, following them
+    /// as their instances change.
+    pub fn discover(registry: Arc<RegistryClient>, world_service: &str, story_service: &str) -> Self {
+        Self {
+            world: WorldServiceClient::new(discovery::discovered_channel(registry.clone(), world_service)),
+            story: StoryServiceClient::new(discovery::discovered_channel(registry, story_service)),
+        }
+    }
 }
 
-async fn create_channel(addr: &str) -> Result<Channel, tonic::transport::Error> {
-    Endpoint::from_shared(addr.to_string())?
+fn endpoint(addr: &str) -> Result<Endpoint, tonic::transport::Error> {
+    Ok(Endpoint::from_shared(addr.to_string())?
         .connect_timeout(Duration::from_secs(5))
-        .timeout(Duration::from_secs(10))
-        .connect()
-        .await
+        .timeout(Duration::from_secs(10)))
+}
+
+fn create_channel(addr: &str) -> Result<Channel, tonic::transport::Error> {
+    Ok(endpoint(addr)?.connect_lazy())
 }
 
 // Convenience functions for common operations
@@ -66,4 +88,29 @@ pub mod helpers {
         let response = client.process_action(request).await?;
         Ok(response.into_inner())
     }
+
+    /// Move a player with client-side prediction: the move shows locally
+    /// at once, and the returned position is the prediction after the
+    /// server's answer is reconciled with any moves still in flight.
+    pub async fn predict_move(
+        client: &mut finalverse_proto::world3d::world3_d_service_client::World3DServiceClient<Channel>,
+        predictor: &mut MovePredictor,
+        position: (f32, f32, f32),
+    ) -> Result<(f32, f32, f32), Box<dyn std::error::Error>> {
+        let input = predictor.predict(position);
+        let result = client.validate_move(input).await?.into_inner();
+        Ok(predictor.reconcile(&result))
+    }
+
+    /// Stream region deltas, starting with the current state of every
+    /// watched region. An empty `region_ids` watches the whole world.
+    pub async fn watch_world_state(
+        client: &mut WorldServiceClient<Channel>,
+        region_ids: Vec<String>,
+        include_events: bool,
+    ) -> Result<tonic::Streaming<WorldUpdate>, Box<dyn std::error::Error>> {
+        let request = WatchWorldStateRequest { region_ids, include_events };
+        let response = client.watch_world_state(request).await?;
+        Ok(response.into_inner())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,111 @@
+// crates/grpc-client/src/prediction.rs
+//! Client-side movement prediction. The player moves locally as soon as
+//! input happens; each move is sent to `World3DService::ValidateMove` and
+//! remembered until the server answers. An answer gives the authoritative
+//! position after one input, and the moves sent since are replayed on top
+//! of it.
+
+use finalverse_proto::world3d::{MoveInput, MoveResult, Position3D};
+use std::collections::VecDeque;
+
+pub type Position = (f32, f32, f32);
+
+pub struct MovePredictor {
+    player_id: String,
+    position: Position,
+    next_sequence: u64,
+    /// Moves sent but not yet answered, with how far each went.
+    pending: VecDeque<(u64, Position)>,
+}
+
+impl MovePredictor {
+    pub fn new(player_id: impl Into<String>, position: Position) -> Self {
+        Self {
+            player_id: player_id.into(),
+            position,
+            next_sequence: 1,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Where the client should show the player.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Moves the server hasn't answered yet.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Move the player locally and return the input to send for it.
+    pub fn predict(&mut self, position: Position) -> MoveInput {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        let step = (
+            position.0 - self.position.0,
+            position.1 - self.position.1,
+            position.2 - self.position.2,
+        );
+        self.pending.push_back((sequence, step));
+        self.position = position;
+
+        MoveInput {
+            player_id: self.player_id.clone(),
+            sequence,
+            position: Some(Position3D {
+                x: position.0,
+                y: position.1,
+                z: position.2,
+            }),
+            client_timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+        }
+    }
+
+    /// Take the server's answer and return the corrected position.
+    pub fn reconcile(&mut self, result: &MoveResult) -> Position {
+        while self.pending.front().is_some_and(|(sequence, _)| *sequence <= result.sequence) {
+            self.pending.pop_front();
+        }
+        if let Some(server) = &result.position {
+            self.position = self.pending.iter().fold((server.x, server.y, server.z), |at, (_, step)| {
+                (at.0 + step.0, at.1 + step.1, at.2 + step.2)
+            });
+        }
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrections_are_replayed_under_unanswered_moves() {
+        let mut predictor = MovePredictor::new("player-1", (0.0, 0.0, 0.0));
+        predictor.predict((5.0, 0.0, 0.0));
+        predictor.predict((10.0, 0.0, 0.0));
+        predictor.predict((10.0, 4.0, 0.0));
+        assert_eq!(predictor.pending(), 3);
+
+        // The server only let the first move go 3 units
+        let corrected = predictor.reconcile(&MoveResult {
+            sequence: 1,
+            accepted: false,
+            position: Some(Position3D { x: 3.0, y: 0.0, z: 0.0 }),
+            correction: "too_fast".to_string(),
+        });
+        assert_eq!(corrected, (8.0, 4.0, 0.0));
+        assert_eq!(predictor.pending(), 2);
+
+        // Accepting the rest leaves the prediction where it was
+        let settled = predictor.reconcile(&MoveResult {
+            sequence: 3,
+            accepted: true,
+            position: Some(Position3D { x: 8.0, y: 4.0, z: 0.0 }),
+            correction: String::new(),
+        });
+        assert_eq!(settled, (8.0, 4.0, 0.0));
+        assert_eq!(predictor.pending(), 0);
+    }
+}
@@ -0,0 +1,162 @@
+// crates/health/src/database.rs
+//! Health checkers that talk to Postgres and Redis with their own
+//! protocols. Each keeps a small pool of connections between checks and
+//! fails a check that takes longer than its timeout.
+
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::{CheckStatus, HealthCheck, HealthChecker};
+
+/// How long a check may take unless the checker says otherwise.
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Turn the result of a timed check into a `HealthCheck`.
+fn outcome<E: Display>(
+    name: &str,
+    start: Instant,
+    timeout: Duration,
+    result: Result<Result<(), E>, tokio::time::error::Elapsed>,
+) -> HealthCheck {
+    let (status, message) = match result {
+        Ok(Ok(())) => (CheckStatus::Pass, None),
+        Ok(Err(e)) => (CheckStatus::Fail, Some(format!("Check failed: {}", e))),
+        Err(_) => (CheckStatus::Fail, Some(format!("No answer within {:?}", timeout))),
+    };
+    HealthCheck {
+        name: name.to_string(),
+        latency_ms: (status == CheckStatus::Pass).then(|| start.elapsed().as_millis() as u64),
+        status,
+        message,
+    }
+}
+
+/// Runs `SELECT 1` against a Postgres database.
+#[cfg(feature = "postgres")]
+pub struct PostgresChecker {
+    name: String,
+    pool: sqlx::PgPool,
+    timeout: Duration,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresChecker {
+    /// Connections kept open for checks.
+    const POOL_SIZE: u32 = 2;
+
+    /// Connects on the first check, so an unreachable database only fails
+    /// its checks. Errors if `url` isn't a valid Postgres URL.
+    pub fn new(name: impl Into<String>, url: &str) -> Result<Self, sqlx::Error> {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(Self::POOL_SIZE)
+            .acquire_timeout(DEFAULT_CHECK_TIMEOUT)
+            .connect_lazy(url)?;
+        Ok(Self {
+            name: name.into(),
+            pool,
+            timeout: DEFAULT_CHECK_TIMEOUT,
+        })
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait::async_trait]
+impl HealthChecker for PostgresChecker {
+    async fn check(&self) -> HealthCheck {
+        let start = Instant::now();
+        let query = async {
+            sqlx::query("SELECT 1").execute(&self.pool).await?;
+            Ok::<_, sqlx::Error>(())
+        };
+        let result = tokio::time::timeout(self.timeout, query).await;
+        outcome(&self.name, start, self.timeout, result)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Sends `PING` to a Redis server.
+#[cfg(feature = "redis")]
+pub struct RedisChecker {
+    name: String,
+    client: redis::Client,
+    /// Multiplexed and reconnecting, so one is shared by all checks.
+    connection: tokio::sync::OnceCell<redis::aio::ConnectionManager>,
+    timeout: Duration,
+}
+
+#[cfg(feature = "redis")]
+impl RedisChecker {
+    /// Connects on the first check, so an unreachable server only fails
+    /// its checks. Errors if `url` isn't a valid Redis URL.
+    pub fn new(name: impl Into<String>, url: &str) -> redis::RedisResult<Self> {
+        Ok(Self {
+            name: name.into(),
+            client: redis::Client::open(url)?,
+            connection: tokio::sync::OnceCell::new(),
+            timeout: DEFAULT_CHECK_TIMEOUT,
+        })
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    async fn ping(&self) -> redis::RedisResult<()> {
+        let mut connection = self
+            .connection
+            .get_or_try_init(|| redis::aio::ConnectionManager::new(self.client.clone()))
+            .await?
+            .clone();
+        redis::cmd("PING").query_async::<_, String>(&mut connection).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait::async_trait]
+impl HealthChecker for RedisChecker {
+    async fn check(&self) -> HealthCheck {
+        let start = Instant::now();
+        let result = tokio::time::timeout(self.timeout, self.ping()).await;
+        outcome(&self.name, start, self.timeout, result)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcomes_carry_status_and_latency() {
+        let start = Instant::now();
+        let passed = outcome::<String>("db", start, DEFAULT_CHECK_TIMEOUT, Ok(Ok(())));
+        assert_eq!(passed.status, CheckStatus::Pass);
+        assert!(passed.latency_ms.is_some());
+
+        let failed = outcome("db", start, DEFAULT_CHECK_TIMEOUT, Ok(Err("connection refused")));
+        assert_eq!(failed.status, CheckStatus::Fail);
+        assert!(failed.message.unwrap().contains("connection refused"));
+    }
+
+    #[cfg(feature = "redis")]
+    #[tokio::test]
+    async fn unreachable_redis_fails_its_check() {
+        let checker = RedisChecker::new("redis", "redis://127.0.0.1:1")
+            .unwrap()
+            .with_timeout(Duration::from_millis(500));
+        let check = checker.check().await;
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.latency_ms.is_none());
+    }
+}
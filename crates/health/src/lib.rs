@@ -9,6 +9,18 @@ use tokio::sync::RwLock;
 use warp::{Filter, Rejection, Reply};
 use axum::{routing::get, Router, Json, http::StatusCode, response::IntoResponse};
 
+pub mod database;
+pub mod debug;
+pub mod operator;
+
+#[cfg(feature = "postgres")]
+pub use database::PostgresChecker;
+#[cfg(feature = "redis")]
+pub use database::RedisChecker;
+pub use debug::{DebugEndpoints, DebugSource};
+pub use operator::{require_operator, OperatorGuard, OPERATOR_TOKEN_HEADER};
+pub use finalverse_core::{ServiceInfo, ServiceStatus};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthStatus {
     pub service: String,
@@ -20,14 +32,6 @@ pub struct HealthStatus {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum ServiceStatus {
-    Healthy,
-    Degraded,
-    Unhealthy,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthCheck {
     pub name: String,
@@ -73,6 +77,8 @@ pub struct HealthMonitor {
     start_time: Instant,
     checks: Arc<RwLock<Vec<Box<dyn HealthChecker + Send + Sync>>>>,
     metrics: Arc<RwLock<HealthMetrics>>,
+    /// Outcome of the latest `get_status`, so `/info` doesn't rerun checks.
+    last_status: Arc<RwLock<ServiceStatus>>,
 }
 
 #[async_trait::async_trait]
@@ -144,6 +150,7 @@ impl HealthMonitor {
             start_time: Instant::now(),
             checks: Arc::new(RwLock::new(Vec::new())),
             metrics: Arc::new(RwLock::new(HealthMetrics::default())),
+            last_status: Arc::new(RwLock::new(ServiceStatus::Healthy)),
         }
     }
     
@@ -177,6 +184,7 @@ impl HealthMonitor {
         };
         
         let metrics = self.metrics.read().await.clone();
+        *self.last_status.write().await = status;
         
         HealthStatus {
             service: self.service_name.clone(),
@@ -189,6 +197,17 @@ impl HealthMonitor {
         }
     }
     
+    /// Name, version and uptime, with the status the last health check
+    /// found. Runs no checks.
+    pub async fn info(&self) -> ServiceInfo {
+        ServiceInfo {
+            name: self.service_name.clone(),
+            version: self.version.clone(),
+            status: *self.last_status.read().await,
+            uptime_seconds: self.start_time.elapsed().as_secs(),
+        }
+    }
+    
     pub fn create_routes(self: Arc<Self>) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
         let health = {
             let monitor = Arc::clone(&self);
@@ -218,12 +237,9 @@ impl HealthMonitor {
             let monitor = Arc::clone(&self);
             warp::path("info")
                 .and(warp::get())
-                .map(move || {
-                    warp::reply::json(&ServiceInfo {
-                        name: monitor.service_name.clone(),
-                        version: monitor.version.clone(),
-                        uptime_seconds: monitor.start_time.elapsed().as_secs(),
-                    })
+                .and_then(move || {
+                    let monitor = Arc::clone(&monitor);
+                    async move { Ok::<_, warp::Rejection>(warp::reply::json(&monitor.info().await)) }
                 })
         };
         
@@ -251,14 +267,7 @@ impl HealthMonitor {
             let monitor = Arc::clone(&self);
             get(move || {
                 let monitor = Arc::clone(&monitor);
-                async move {
-                    let status = monitor.get_status().await;
-                    Json(ServiceInfo {
-                        name: status.service,
-                        version: status.version,
-                        uptime_seconds: status.uptime_seconds,
-                    })
-                }
+                async move { Json(monitor.info().await) }
             })
         };
 
@@ -266,26 +275,65 @@ impl HealthMonitor {
     }
 }
 
-#[derive(Debug, Serialize)]
-pub struct ServiceInfo {
-    pub name: String,
-    pub version: String,
-    pub uptime_seconds: u64,
-}
-
 // Convenience function to add standard checks
 pub async fn add_standard_checks(monitor: &HealthMonitor, postgres_url: Option<&str>, redis_url: Option<&str>) {
     if let Some(pg_url) = postgres_url {
-        monitor.add_checker(Box::new(ConnectivityChecker::new(
-            "postgres".to_string(),
-            format!("{}/health", pg_url.replace("postgres://", "http://").split('@').last().unwrap_or("localhost:5432")),
-        ))).await;
+        #[cfg(feature = "postgres")]
+        match PostgresChecker::new("postgres", pg_url) {
+            Ok(checker) => monitor.add_checker(Box::new(checker)).await,
+            Err(e) => tracing::warn!("Not checking postgres, invalid URL: {}", e),
+        }
+        #[cfg(not(feature = "postgres"))]
+        tracing::warn!("Not checking postgres at {}, built without the postgres feature", pg_url);
     }
-    
+
     if let Some(redis_url) = redis_url {
-        monitor.add_checker(Box::new(ConnectivityChecker::new(
-            "redis".to_string(),
-            redis_url.replace("redis://", "http://"),
-        ))).await;
+        #[cfg(feature = "redis")]
+        match RedisChecker::new("redis", redis_url) {
+            Ok(checker) => monitor.add_checker(Box::new(checker)).await,
+            Err(e) => tracing::warn!("Not checking redis, invalid URL: {}", e),
+        }
+        #[cfg(not(feature = "redis"))]
+        tracing::warn!("Not checking redis at {}, built without the redis feature", redis_url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Failing(Arc<AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl HealthChecker for Failing {
+        async fn check(&self) -> HealthCheck {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            HealthCheck {
+                name: "db".to_string(),
+                status: CheckStatus::Fail,
+                message: None,
+                latency_ms: None,
+            }
+        }
+
+        fn name(&self) -> &str {
+            "db"
+        }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn info_reports_the_last_check_without_running_one() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let monitor = HealthMonitor::new("song-engine", "1.0.0");
+        monitor.add_checker(Box::new(Failing(runs.clone()))).await;
+
+        assert_eq!(monitor.info().await.status, ServiceStatus::Healthy);
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        assert_eq!(monitor.get_status().await.status, ServiceStatus::Unhealthy);
+        let info = monitor.info().await;
+        assert_eq!((info.name.as_str(), info.status), ("song-engine", ServiceStatus::Unhealthy));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+}
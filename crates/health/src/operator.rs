@@ -0,0 +1,108 @@
+// crates/health/src/operator.rs
+// Operator token checks for admin routes that change live state
+
+use axum::{
+    extract::{Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use finalverse_config::FinalverseConfig;
+use finalverse_core::FinalverseError;
+use warp::{filters::BoxedFilter, Filter};
+
+/// Header carrying the operator token.
+pub const OPERATOR_TOKEN_HEADER: &str = "x-operator-token";
+
+/// Admits requests carrying the configured operator token. With no token
+/// configured every request is refused, so admin routes are closed until
+/// an operator sets one.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorGuard {
+    token: Option<String>,
+}
+
+impl OperatorGuard {
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            token: token.filter(|token| !token.is_empty()),
+        }
+    }
+
+    pub fn from_config(config: &FinalverseConfig) -> Self {
+        Self::new(config.security.operator_token.clone())
+    }
+
+    /// Build from the central configuration, with environment overrides
+    /// applied when no config file is present.
+    pub fn load() -> Self {
+        let config = finalverse_config::load_default_config().unwrap_or_else(|_| {
+            let mut config = FinalverseConfig::default();
+            let _ = finalverse_config::apply_env_overrides(&mut config);
+            config
+        });
+        Self::from_config(&config)
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// The configured token, for calling other services' admin routes.
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn check(&self, presented: Option<&str>) -> Result<(), FinalverseError> {
+        match (&self.token, presented) {
+            (Some(expected), Some(presented)) if constant_time_eq(presented.as_bytes(), expected.as_bytes()) => Ok(()),
+            _ => Err(FinalverseError::Unauthorized("Operator token required".to_string())),
+        }
+    }
+
+    pub fn check_headers(&self, headers: &HeaderMap) -> Result<(), FinalverseError> {
+        self.check(headers.get(OPERATOR_TOKEN_HEADER).and_then(|value| value.to_str().ok()))
+    }
+
+    /// A warp filter passing only requests with the operator token. Refusals
+    /// are [`FinalverseError`] rejections, answered by
+    /// `finalverse_core::warp::recover`.
+    pub fn warp_filter(&self) -> BoxedFilter<()> {
+        let guard = self.clone();
+        warp::header::optional::<String>(OPERATOR_TOKEN_HEADER)
+            .and_then(move |presented: Option<String>| {
+                let checked = guard.check(presented.as_deref()).map_err(finalverse_core::warp::reject);
+                async move { checked }
+            })
+            .untuple_one()
+            .boxed()
+    }
+}
+
+/// axum middleware for `from_fn_with_state(guard, require_operator)`.
+pub async fn require_operator(State(guard): State<OperatorGuard>, request: Request, next: Next) -> Response {
+    match guard.check_headers(request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guard_needs_the_configured_token() {
+        assert!(OperatorGuard::new(None).check(Some("")).is_err());
+        assert!(OperatorGuard::new(Some(String::new())).check(Some("")).is_err());
+
+        let guard = OperatorGuard::new(Some("op".to_string()));
+        assert!(guard.check(Some("op")).is_ok());
+        assert!(guard.check(Some("nope")).is_err());
+        assert!(guard.check(None).is_err());
+    }
+}
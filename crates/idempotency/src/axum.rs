@@ -0,0 +1,95 @@
+// crates/idempotency/src/axum.rs
+//! Axum middleware. Layer it onto just the routes that take keys, inside
+//! any auth layer so unauthenticated requests never claim one.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{Claim, IdempotencyCache, IdempotencyError, StoredResponse, IDEMPOTENCY_KEY_HEADER, REPLAYED_HEADER};
+
+/// Largest request body buffered for the fingerprint; axum's own default
+/// body limit.
+const MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// ```ignore
+/// let cache = IdempotencyCache::default();
+/// router.route(
+///     "/api/melody/perform",
+///     post(perform_melody).layer(axum::middleware::from_fn_with_state(cache, idempotent)),
+/// )
+/// ```
+pub async fn idempotent(
+    State(cache): State<IdempotencyCache>,
+    request: Request,
+    next: Next,
+) -> Result<Response, IdempotencyError> {
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+    else {
+        return Ok(next.run(request).await);
+    };
+    let method = request.method().to_string();
+    let uri = request.uri().to_string();
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let authorization = authorization.to_string();
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_REQUEST_BYTES).await else {
+        return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+    };
+    let pending = match cache.claim(&key, &[&method, &uri, &authorization], &body)? {
+        Claim::Replay(stored) => return Ok(replay(stored)),
+        Claim::Fresh(pending) => pending,
+    };
+
+    let request = Request::from_parts(parts, Body::from(body));
+    let (parts, body) = next.run(request).await.into_parts();
+    let Ok(body) = to_bytes(body, usize::MAX).await else {
+        // Dropping the claim lets the retry run
+        return Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    };
+    pending.complete(StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    });
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored.content_type.and_then(|value| HeaderValue::from_str(&value).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+impl IntoResponse for IdempotencyError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            IdempotencyError::KeyTooLong => StatusCode::BAD_REQUEST,
+            IdempotencyError::InFlight => StatusCode::CONFLICT,
+            IdempotencyError::BodyMismatch => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
@@ -0,0 +1,249 @@
+// crates/idempotency/src/lib.rs
+//! Idempotency keys for state-changing endpoints.
+//!
+//! A client that retries a request after a dropped connection can't tell
+//! whether the first attempt was applied. Sending the same
+//! `Idempotency-Key` header with every attempt makes the retry safe: the
+//! first attempt runs, and later ones within the TTL get its response back
+//! without running again. A retry that arrives while the first attempt is
+//! still running is refused with 409. Keys only match requests with the
+//! same method, path and `Authorization` header; reusing a key with a
+//! different body is refused with 422 rather than replaying a response to
+//! some other request. Requests without a key run as usual. Enable the `axum` or `warp` feature for the middleware.
+
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "warp")]
+pub mod warp;
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// The request header carrying the key.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Set on responses replayed from an earlier request.
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// How long responses are replayed for unless the service says otherwise.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Longest key accepted; UUIDs fit comfortably.
+pub const MAX_KEY_LEN: usize = 255;
+
+/// Above this many keys, expired responses are dropped.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum IdempotencyError {
+    #[error("Idempotency keys can be at most {MAX_KEY_LEN} characters")]
+    KeyTooLong,
+    #[error("A request with this idempotency key is still being handled")]
+    InFlight,
+    #[error("This idempotency key was already used with a different request body")]
+    BodyMismatch,
+}
+
+/// A response as it was first sent, for replaying.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// What to do with a request carrying a key.
+#[derive(Debug)]
+pub enum Claim {
+    /// The key was used before; send this instead of running the request.
+    Replay(StoredResponse),
+    /// The key is new; run the request and complete this with its response.
+    Fresh(Pending),
+}
+
+#[derive(Debug)]
+enum Entry {
+    Running { body: BodyHash },
+    Done { response: StoredResponse, expires: Instant, body: BodyHash },
+}
+
+type ScopedKey = [u8; 32];
+type BodyHash = [u8; 32];
+
+/// Responses by key, shared by every route of a service that takes keys.
+#[derive(Debug, Clone)]
+pub struct IdempotencyCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<ScopedKey, Entry>>>,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Claim `key` for a request with `body`, within `scope`: the method,
+    /// path and whatever else must match for two requests to be the same
+    /// one.
+    pub fn claim(&self, key: &str, scope: &[&str], body: &[u8]) -> Result<Claim, IdempotencyError> {
+        self.claim_at(key, scope, body, Instant::now())
+    }
+
+    fn claim_at(&self, key: &str, scope: &[&str], body: &[u8], now: Instant) -> Result<Claim, IdempotencyError> {
+        if key.len() > MAX_KEY_LEN {
+            return Err(IdempotencyError::KeyTooLong);
+        }
+        let mut hasher = Sha256::new();
+        for part in scope.iter().chain([&key]) {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let id: ScopedKey = hasher.finalize().into();
+        let body: BodyHash = Sha256::digest(body).into();
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= PRUNE_THRESHOLD {
+            entries.retain(|_, entry| match entry {
+                Entry::Running { .. } => true,
+                Entry::Done { expires, .. } => *expires > now,
+            });
+        }
+        let live = entries.get(&id).filter(|entry| match entry {
+            Entry::Running { .. } => true,
+            Entry::Done { expires, .. } => *expires > now,
+        });
+        match live {
+            Some(Entry::Running { body: first } | Entry::Done { body: first, .. }) if *first != body => {
+                Err(IdempotencyError::BodyMismatch)
+            }
+            Some(Entry::Running { .. }) => Err(IdempotencyError::InFlight),
+            Some(Entry::Done { response, .. }) => Ok(Claim::Replay(response.clone())),
+            None => {
+                entries.insert(id, Entry::Running { body });
+                Ok(Claim::Fresh(Pending {
+                    cache: self.clone(),
+                    id: Some(id),
+                    body,
+                }))
+            }
+        }
+    }
+}
+
+/// A claimed key whose request is running. Dropping it without completing,
+/// as when the client goes away mid-request, frees the key for a retry.
+#[derive(Debug)]
+pub struct Pending {
+    cache: IdempotencyCache,
+    id: Option<ScopedKey>,
+    body: BodyHash,
+}
+
+impl Pending {
+    /// Keep `response` for replaying. Server errors aren't kept, so the
+    /// retry runs again.
+    pub fn complete(mut self, response: StoredResponse) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let mut entries = self.cache.entries.lock().unwrap();
+        if response.status >= 500 {
+            entries.remove(&id);
+        } else {
+            let expires = Instant::now() + self.cache.ttl;
+            entries.insert(id, Entry::Done { response, expires, body: self.body });
+        }
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.cache.entries.lock().unwrap().remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, body: &str) -> StoredResponse {
+        StoredResponse {
+            status,
+            content_type: Some("application/json".to_string()),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn fresh(claim: Result<Claim, IdempotencyError>) -> Pending {
+        match claim {
+            Ok(Claim::Fresh(pending)) => pending,
+            other => panic!("expected a fresh claim, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn keys_replay_the_first_response_until_they_expire() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let scope = ["POST", "/api/melody/perform", "Bearer a"];
+
+        let first = fresh(cache.claim_at("k1", &scope, b"{}", start));
+        assert_eq!(cache.claim_at("k1", &scope, b"{}", start).unwrap_err(), IdempotencyError::InFlight);
+        first.complete(response(200, "{\"ok\":1}"));
+        match cache.claim_at("k1", &scope, b"{}", start + Duration::from_secs(30)) {
+            Ok(Claim::Replay(replayed)) => assert_eq!(replayed, response(200, "{\"ok\":1}")),
+            other => panic!("expected a replay, got {:?}", other),
+        }
+
+        // Another caller's key of the same name is its own
+        drop(fresh(cache.claim_at("k1", &["POST", "/api/melody/perform", "Bearer b"], b"{}", start)));
+        // Abandoned and failed requests free the key
+        drop(fresh(cache.claim_at("k2", &scope, b"{}", start)));
+        fresh(cache.claim_at("k2", &scope, b"{}", start)).complete(response(503, ""));
+        drop(fresh(cache.claim_at("k2", &scope, b"{}", start)));
+
+        let late = Instant::now() + Duration::from_secs(61);
+        assert!(matches!(cache.claim_at("k1", &scope, b"{}", late), Ok(Claim::Fresh(_))));
+        assert_eq!(
+            cache.claim_at(&"k".repeat(MAX_KEY_LEN + 1), &scope, b"{}", start).unwrap_err(),
+            IdempotencyError::KeyTooLong
+        );
+    }
+
+    #[test]
+    fn keys_reused_with_another_body_are_refused() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let scope = ["POST", "/api/melody/perform", "Bearer a"];
+
+        let first = fresh(cache.claim_at("k1", &scope, b"{\"notes\":1}", start));
+        assert_eq!(
+            cache.claim_at("k1", &scope, b"{\"notes\":2}", start).unwrap_err(),
+            IdempotencyError::BodyMismatch
+        );
+        first.complete(response(200, "{}"));
+        assert_eq!(
+            cache.claim_at("k1", &scope, b"{\"notes\":2}", start).unwrap_err(),
+            IdempotencyError::BodyMismatch
+        );
+        assert!(matches!(cache.claim_at("k1", &scope, b"{\"notes\":1}", start), Ok(Claim::Replay(_))));
+
+        // Once the first response expires the key is free for any body
+        let late = start + Duration::from_secs(61);
+        assert!(matches!(cache.claim_at("k1", &scope, b"{\"notes\":2}", late), Ok(Claim::Fresh(_))));
+    }
+}
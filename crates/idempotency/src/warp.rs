@@ -0,0 +1,103 @@
+// crates/idempotency/src/warp.rs
+//! Warp filters: wrap each route that takes keys in [`idempotent`].
+
+use warp::http::{header, HeaderValue, StatusCode};
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+use crate::{Claim, IdempotencyCache, IdempotencyError, Pending, StoredResponse, IDEMPOTENCY_KEY_HEADER, REPLAYED_HEADER};
+
+impl warp::reject::Reject for IdempotencyError {}
+
+#[derive(Debug)]
+struct Replayed(StoredResponse);
+
+impl warp::reject::Reject for Replayed {}
+
+/// Run `route` at most once per key, replaying its first response to
+/// retries. Requests `route` rejects don't hold on to their key. The
+/// request body is read here for the fingerprint, so `route` must take its
+/// input from the path and query.
+///
+/// ```ignore
+/// let add_resonance = idempotent(cache.clone(), warp::path!("resonance" / String).and_then(handler));
+/// ```
+pub fn idempotent<F, R>(
+    cache: IdempotencyCache,
+    route: F,
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    let query = warp::query::raw().or(warp::any().map(String::new)).unify();
+    warp::header::optional::<String>(IDEMPOTENCY_KEY_HEADER)
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(query)
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::bytes())
+        .and_then(move |key: Option<String>, method: warp::http::Method, path: warp::path::FullPath, query: String, authorization: Option<String>, body: warp::hyper::body::Bytes| {
+            let claim = key.map(|key| {
+                cache.claim(&key, &[method.as_str(), path.as_str(), &query, authorization.as_deref().unwrap_or_default()], &body)
+            });
+            async move {
+                match claim {
+                    None => Ok(None),
+                    Some(Ok(Claim::Fresh(pending))) => Ok(Some(pending)),
+                    Some(Ok(Claim::Replay(stored))) => Err(warp::reject::custom(Replayed(stored))),
+                    Some(Err(e)) => Err(warp::reject::custom(e)),
+                }
+            }
+        })
+        .and(route)
+        .and_then(|pending: Option<Pending>, reply: R| async move { Ok::<_, Rejection>(keep(pending, reply).await) })
+        .recover(recover)
+        .unify()
+}
+
+async fn keep<R: Reply>(pending: Option<Pending>, reply: R) -> Response {
+    let response = reply.into_response();
+    let Some(pending) = pending else {
+        return response;
+    };
+    let (parts, body) = response.into_parts();
+    let Ok(body) = warp::hyper::body::to_bytes(body).await else {
+        // Dropping the claim lets the retry run
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    pending.complete(StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    });
+    Response::from_parts(parts, body.into())
+}
+
+/// Replays and refused keys become responses; other rejections pass
+/// through to the service's own recovery.
+async fn recover(rejection: Rejection) -> Result<Response, Rejection> {
+    if let Some(Replayed(stored)) = rejection.find::<Replayed>() {
+        let mut response = Response::new(stored.body.clone().into());
+        *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+        let headers = response.headers_mut();
+        if let Some(content_type) = stored.content_type.as_deref().and_then(|value| HeaderValue::from_str(value).ok()) {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+        headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+        return Ok(response);
+    }
+    let Some(error) = rejection.find::<IdempotencyError>() else {
+        return Err(rejection);
+    };
+    let status = match error {
+        IdempotencyError::KeyTooLong => StatusCode::BAD_REQUEST,
+        IdempotencyError::InFlight => StatusCode::CONFLICT,
+        IdempotencyError::BodyMismatch => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&serde_json::json!({ "error": error.to_string() })), status).into_response())
+}
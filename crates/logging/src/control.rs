@@ -0,0 +1,322 @@
+// crates/logging/src/control.rs
+//! Runtime log control: per-target levels, temporary boosts that expire on
+//! their own, and sampling of high-volume debug/trace events.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{Level, Metadata};
+use tracing_subscriber::{
+    layer::{Context, Filter},
+    reload, EnvFilter, Registry,
+};
+
+/// Key used for the global (target-less) level.
+const GLOBAL: &str = "*";
+
+#[derive(Debug, thiserror::Error)]
+pub enum LogControlError {
+    #[error("Invalid log level: {0}")]
+    InvalidLevel(String),
+    #[error("Invalid log target: {0}")]
+    InvalidTarget(String),
+    #[error("Sample rate must be in (0, 1], got {0}")]
+    InvalidRate(f64),
+    #[error("Failed to apply log filter: {0}")]
+    Reload(String),
+}
+
+/// A change requested through the log-control endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum LogCommand {
+    /// Persistently set a level; `level: None` drops the override.
+    SetLevel { target: Option<String>, level: Option<String> },
+    /// Raise a level until `duration_secs` elapses.
+    Boost { target: Option<String>, level: String, duration_secs: u64 },
+    /// Keep only `rate` of debug/trace events from `target`; 1.0 disables sampling.
+    Sample { target: String, rate: f64 },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BoostInfo {
+    pub level: String,
+    pub expires_in_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogControlSnapshot {
+    pub filter: String,
+    pub overrides: BTreeMap<String, String>,
+    pub boosts: BTreeMap<String, BoostInfo>,
+    pub sampling: BTreeMap<String, f64>,
+}
+
+struct Boost {
+    level: String,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct Levels {
+    overrides: BTreeMap<String, String>,
+    boosts: BTreeMap<String, Boost>,
+}
+
+struct SampleRule {
+    rate: f64,
+    every: u64,
+    seen: AtomicU64,
+}
+
+/// Sampling rules shared between [`LogControl`] and the fmt layer's filter.
+#[derive(Clone, Default)]
+pub(crate) struct Sampler {
+    rules: Arc<RwLock<HashMap<String, SampleRule>>>,
+}
+
+impl Sampler {
+    fn set(&self, target: String, rate: f64) {
+        let mut rules = self.rules.write().unwrap_or_else(|e| e.into_inner());
+        if rate >= 1.0 {
+            rules.remove(&target);
+        } else {
+            let every = (1.0 / rate).round().max(1.0) as u64;
+            rules.insert(target, SampleRule { rate, every, seen: AtomicU64::new(0) });
+        }
+    }
+
+    fn rates(&self) -> BTreeMap<String, f64> {
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        rules.iter().map(|(target, rule)| (target.clone(), rule.rate)).collect()
+    }
+
+    fn keep(&self, metadata: &Metadata<'_>) -> bool {
+        if *metadata.level() < Level::DEBUG {
+            return true;
+        }
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        // The longest matching target prefix decides
+        let rule = rules
+            .iter()
+            .filter(|(target, _)| metadata.target().starts_with(target.as_str()))
+            .max_by_key(|(target, _)| target.len())
+            .map(|(_, rule)| rule);
+        match rule {
+            Some(rule) => rule.seen.fetch_add(1, Ordering::Relaxed) % rule.every == 0,
+            None => true,
+        }
+    }
+}
+
+impl<S> Filter<S> for Sampler {
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: &Context<'_, S>) -> bool {
+        // Spans are never sampled; only events are high-volume
+        !metadata.is_event() || self.keep(metadata)
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> tracing::subscriber::Interest {
+        if metadata.is_event() && *metadata.level() >= Level::DEBUG {
+            tracing::subscriber::Interest::sometimes()
+        } else {
+            tracing::subscriber::Interest::always()
+        }
+    }
+}
+
+/// Handle for changing the process's log filter at runtime.
+pub struct LogControl {
+    base: String,
+    handle: reload::Handle<EnvFilter, Registry>,
+    levels: Mutex<Levels>,
+    sampler: Sampler,
+}
+
+impl LogControl {
+    pub(crate) fn new(base: String, handle: reload::Handle<EnvFilter, Registry>, sampler: Sampler) -> Self {
+        Self {
+            base,
+            handle,
+            levels: Mutex::new(Levels::default()),
+            sampler,
+        }
+    }
+
+    pub fn apply(&self, command: LogCommand) -> Result<LogControlSnapshot, LogControlError> {
+        match command {
+            LogCommand::SetLevel { target, level } => {
+                let target = target_key(target)?;
+                let mut levels = self.levels.lock().unwrap_or_else(|e| e.into_inner());
+                match level {
+                    Some(level) => {
+                        levels.overrides.insert(target, parse_level(&level)?);
+                    }
+                    None => {
+                        levels.overrides.remove(&target);
+                    }
+                }
+                self.reload(&levels)?;
+            }
+            LogCommand::Boost { target, level, duration_secs } => {
+                let target = target_key(target)?;
+                let level = parse_level(&level)?;
+                let mut levels = self.levels.lock().unwrap_or_else(|e| e.into_inner());
+                let expires_at = Instant::now() + Duration::from_secs(duration_secs);
+                levels.boosts.insert(target, Boost { level, expires_at });
+                self.reload(&levels)?;
+            }
+            LogCommand::Sample { target, rate } => {
+                if !(rate > 0.0 && rate <= 1.0) {
+                    return Err(LogControlError::InvalidRate(rate));
+                }
+                self.sampler.set(target_key(Some(target))?, rate);
+            }
+        }
+        Ok(self.snapshot())
+    }
+
+    /// Drop boosts whose time is up. Called periodically by the logging
+    /// crate's expiry thread.
+    pub fn expire_boosts(&self) {
+        let mut levels = self.levels.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let before = levels.boosts.len();
+        levels.boosts.retain(|_, boost| boost.expires_at > now);
+        if levels.boosts.len() != before {
+            if let Err(e) = self.reload(&levels) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> LogControlSnapshot {
+        let levels = self.levels.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        LogControlSnapshot {
+            filter: self.directives(&levels),
+            overrides: levels.overrides.clone(),
+            boosts: levels
+                .boosts
+                .iter()
+                .map(|(target, boost)| {
+                    let info = BoostInfo {
+                        level: boost.level.clone(),
+                        expires_in_secs: boost.expires_at.saturating_duration_since(now).as_secs(),
+                    };
+                    (target.clone(), info)
+                })
+                .collect(),
+            sampling: self.sampler.rates(),
+        }
+    }
+
+    /// Base directives, then overrides, then boosts; later entries win.
+    fn directives(&self, levels: &Levels) -> String {
+        let mut effective: BTreeMap<&str, &str> = BTreeMap::new();
+        for (target, level) in &levels.overrides {
+            effective.insert(target, level);
+        }
+        let now = Instant::now();
+        for (target, boost) in &levels.boosts {
+            if boost.expires_at > now {
+                effective.insert(target, &boost.level);
+            }
+        }
+
+        let mut directives: Vec<String> = self
+            .base
+            .split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            // A global override replaces the base default level
+            .filter(|directive| directive.contains('=') || !effective.contains_key(GLOBAL))
+            .map(str::to_string)
+            .collect();
+        for (target, level) in effective {
+            if target == GLOBAL {
+                directives.push(level.to_string());
+            } else {
+                directives.push(format!("{}={}", target, level));
+            }
+        }
+        directives.join(",")
+    }
+
+    fn reload(&self, levels: &Levels) -> Result<(), LogControlError> {
+        let directives = self.directives(levels);
+        let filter = EnvFilter::try_new(&directives).map_err(|e| LogControlError::Reload(e.to_string()))?;
+        self.handle
+            .reload(filter)
+            .map_err(|e| LogControlError::Reload(e.to_string()))
+    }
+}
+
+fn target_key(target: Option<String>) -> Result<String, LogControlError> {
+    match target {
+        None => Ok(GLOBAL.to_string()),
+        Some(target) => {
+            let valid = !target.is_empty()
+                && target
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '-' | '.'));
+            if valid {
+                Ok(target)
+            } else {
+                Err(LogControlError::InvalidTarget(target))
+            }
+        }
+    }
+}
+
+fn parse_level(level: &str) -> Result<String, LogControlError> {
+    let level = level.trim().to_lowercase();
+    if level == "off" || Level::from_str(&level).is_ok() {
+        Ok(level)
+    } else {
+        Err(LogControlError::InvalidLevel(level))
+    }
+}
+
+/// Parse `FINALVERSE_LOG_SAMPLING`, e.g. `story_engine=0.1,hyper=0.01`.
+pub(crate) fn sampling_from_env(sampler: &Sampler) {
+    let Ok(spec) = std::env::var("FINALVERSE_LOG_SAMPLING") else {
+        return;
+    };
+    for entry in spec.split(',').filter(|entry| !entry.trim().is_empty()) {
+        let parsed = entry
+            .split_once('=')
+            .and_then(|(target, rate)| Some((target.trim().to_string(), rate.trim().parse::<f64>().ok()?)));
+        match parsed {
+            Some((target, rate)) if rate > 0.0 && rate <= 1.0 && !target.is_empty() => sampler.set(target, rate),
+            _ => eprintln!("Ignoring invalid FINALVERSE_LOG_SAMPLING entry: {}", entry),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_and_boosts_compose_into_directives() {
+        let (_layer, handle) = reload::Layer::<EnvFilter, Registry>::new(EnvFilter::new("info,hyper=warn"));
+        let control = LogControl::new("info,hyper=warn".to_string(), handle, Sampler::default());
+
+        let snapshot = control
+            .apply(LogCommand::SetLevel { target: Some("story_engine".into()), level: Some("debug".into()) })
+            .unwrap();
+        assert_eq!(snapshot.filter, "info,hyper=warn,story_engine=debug");
+
+        let snapshot = control
+            .apply(LogCommand::Boost { target: None, level: "trace".into(), duration_secs: 60 })
+            .unwrap();
+        // A global boost replaces the base default level
+        assert_eq!(snapshot.filter, "hyper=warn,trace,story_engine=debug");
+
+        assert!(control.apply(LogCommand::SetLevel { target: None, level: Some("loud".into()) }).is_err());
+        assert!(control.apply(LogCommand::Sample { target: "hyper".into(), rate: 0.0 }).is_err());
+    }
+}
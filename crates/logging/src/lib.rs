@@ -1,15 +1,26 @@
+use once_cell::sync::OnceCell;
 use std::sync::Once;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer};
 use finalverse_config::{load_default_config, FinalverseConfig};
 
+pub mod control;
+
+pub use control::{LogCommand, LogControl, LogControlError, LogControlSnapshot};
+
 static INIT: Once = Once::new();
+static CONTROL: OnceCell<LogControl> = OnceCell::new();
+
+/// How often expired level boosts are dropped.
+const BOOST_EXPIRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 
 /// Initialize global logging subscriber.
 ///
 /// `level` can override the default log level. If `None`, the
 /// `FINALVERSE_LOG_LEVEL` or `RUST_LOG` env vars are used, defaulting to `info`.
 /// The log format is chosen based on `FinalverseConfig::general.log_format`,
-/// falling back to `text` if configuration loading fails.
+/// falling back to `text` if configuration loading fails. The filter can be
+/// changed afterwards through [`control`], and debug sampling can be preset
+/// with `FINALVERSE_LOG_SAMPLING`.
 pub fn init(level: Option<&str>) {
     INIT.call_once(|| {
         let config: Option<FinalverseConfig> = load_default_config().ok();
@@ -18,19 +29,38 @@ pub fn init(level: Option<&str>) {
             .or_else(|| std::env::var("FINALVERSE_LOG_LEVEL").ok())
             .or_else(|| std::env::var("RUST_LOG").ok())
             .unwrap_or_else(|| "info".to_string());
-        let env_filter = EnvFilter::new(log_level);
+        let (filter_layer, handle) = reload::Layer::new(EnvFilter::new(&log_level));
+
+        let sampler = control::Sampler::default();
+        control::sampling_from_env(&sampler);
 
         let log_format = config
             .as_ref()
             .map(|c| c.general.log_format.as_str())
             .unwrap_or("text");
 
-        let subscriber_builder = fmt().with_env_filter(env_filter);
-        match log_format {
-            "json" => subscriber_builder.json().init(),
-            "pretty" => subscriber_builder.pretty().init(),
-            _ => subscriber_builder.init(),
+        let fmt_layer = match log_format {
+            "json" => fmt::layer().json().boxed(),
+            "pretty" => fmt::layer().pretty().boxed(),
+            _ => fmt::layer().boxed(),
+        };
+        tracing_subscriber::registry()
+            .with(filter_layer)
+            .with(fmt_layer.with_filter(sampler.clone()))
+            .init();
+
+        if CONTROL.set(LogControl::new(log_level, handle, sampler)).is_ok() {
+            std::thread::spawn(|| loop {
+                std::thread::sleep(BOOST_EXPIRY_INTERVAL);
+                if let Some(control) = CONTROL.get() {
+                    control.expire_boosts();
+                }
+            });
         }
     });
 }
 
+/// Runtime log control, available once [`init`] has run.
+pub fn control() -> Option<&'static LogControl> {
+    CONTROL.get()
+}
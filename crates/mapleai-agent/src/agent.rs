@@ -1,5 +1,7 @@
 use crate::{planner::Planner, llm_bridge::LLMBridge};
+use crate::behavior::BehaviorTree;
 use finalverse_protocol::{AgentState, ReasoningContext, BehaviorAction};
+use std::sync::Arc;
 use tokio::task::JoinHandle;
 
 #[derive(Clone)]
@@ -33,6 +35,12 @@ impl Agent {
         }
     }
 
+    /// Decide with `tree` before falling back on the built-in heuristics.
+    pub fn with_behavior(mut self, tree: Arc<BehaviorTree>) -> Self {
+        self.planner = Planner::with_tree(tree);
+        self
+    }
+
     pub fn state(&self) -> &AgentState {
         &self.state
     }
@@ -70,7 +78,6 @@ mod tests {
     use super::*;
     use crate::llm_bridge::LLMEngine;
     use ai_orchestra::{GenerationRequest, GenerationResponse};
-    use std::sync::Arc;
 
     struct MockLLM;
     #[async_trait::async_trait]
@@ -0,0 +1,279 @@
+//! Behavior trees for designers to describe NPCs with, loaded from TOML or
+//! JSON files. A tree is made of selectors, sequences, utility choices,
+//! conditions on the [`ReasoningContext`] and the actions an agent takes.
+
+use finalverse_protocol::{BehaviorAction, ReasoningContext};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BehaviorError {
+    #[error("Can't read behavior tree: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid behavior tree: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Invalid behavior tree: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Behavior trees must be .toml or .json files: {0}")]
+    UnsupportedFormat(String),
+}
+
+/// A named tree, as authored in a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BehaviorTree {
+    pub name: String,
+    pub root: Node,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Node {
+    /// Succeeds with the first child that succeeds.
+    Selector { children: Vec<Node> },
+    /// Succeeds if every child does, in order, with the last action chosen.
+    Sequence { children: Vec<Node> },
+    /// Tries its options best-scoring first and succeeds with the first
+    /// that succeeds.
+    Utility { options: Vec<UtilityOption> },
+    /// Succeeds without an action if the condition holds.
+    Condition { condition: Condition },
+    /// Succeeds with the action, if it can be taken.
+    Action { action: ActionSpec },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "is", rename_all = "snake_case")]
+pub enum Condition {
+    TensionAbove { value: f32 },
+    TensionBelow { value: f32 },
+    HarmonyAbove { value: f32 },
+    HarmonyBelow { value: f32 },
+    AtLocation { location: String },
+    /// A particular entity is nearby, or any if `entity` is left out.
+    EntityNearby { entity: Option<String> },
+    /// Something in memory mentions `text`.
+    Remembers { text: String },
+    Not { condition: Box<Condition> },
+}
+
+impl Condition {
+    pub fn holds(&self, ctx: &ReasoningContext) -> bool {
+        match self {
+            Condition::TensionAbove { value } => ctx.tension > *value,
+            Condition::TensionBelow { value } => ctx.tension < *value,
+            Condition::HarmonyAbove { value } => ctx.harmony_level > *value,
+            Condition::HarmonyBelow { value } => ctx.harmony_level < *value,
+            Condition::AtLocation { location } => ctx.location == *location,
+            Condition::EntityNearby { entity: None } => !ctx.nearby_entities.is_empty(),
+            Condition::EntityNearby { entity: Some(entity) } => ctx.nearby_entities.contains(entity),
+            Condition::Remembers { text } => ctx.memory.iter().any(|memory| memory.contains(text.as_str())),
+            Condition::Not { condition } => !condition.holds(ctx),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "do", rename_all = "snake_case")]
+pub enum ActionSpec {
+    Wander,
+    Rest,
+    Flee { reason: String },
+    Migrate { target_region: String },
+    /// With `entity`, or the first nearby entity if it is left out.
+    Interact { entity: Option<String>, action: String },
+}
+
+impl ActionSpec {
+    /// The action to take, or None if it can't be taken here.
+    pub fn resolve(&self, ctx: &ReasoningContext) -> Option<BehaviorAction> {
+        Some(match self {
+            ActionSpec::Wander => BehaviorAction::Wander,
+            ActionSpec::Rest => BehaviorAction::Rest,
+            ActionSpec::Flee { reason } => BehaviorAction::Flee(reason.clone()),
+            ActionSpec::Migrate { target_region } => BehaviorAction::Migrate {
+                target_region: target_region.clone(),
+            },
+            ActionSpec::Interact { entity, action } => BehaviorAction::Interact {
+                entity_id: entity.clone().or_else(|| ctx.nearby_entities.first().cloned())?,
+                action: action.clone(),
+            },
+        })
+    }
+}
+
+/// An option's score is `base` plus each weight times its part of the
+/// context.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilityOption {
+    #[serde(default)]
+    pub base: f32,
+    #[serde(default)]
+    pub harmony: f32,
+    #[serde(default)]
+    pub tension: f32,
+    /// Per nearby entity.
+    #[serde(default)]
+    pub nearby: f32,
+    pub node: Node,
+}
+
+impl UtilityOption {
+    pub fn score(&self, ctx: &ReasoningContext) -> f32 {
+        self.base
+            + self.harmony * ctx.harmony_level
+            + self.tension * ctx.tension
+            + self.nearby * ctx.nearby_entities.len() as f32
+    }
+}
+
+/// How a node went: failed, or succeeded with or without an action.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Failure,
+    Success(Option<BehaviorAction>),
+}
+
+impl Node {
+    pub fn tick(&self, ctx: &ReasoningContext) -> Outcome {
+        match self {
+            Node::Selector { children } => children
+                .iter()
+                .map(|child| child.tick(ctx))
+                .find(|outcome| matches!(outcome, Outcome::Success(_)))
+                .unwrap_or(Outcome::Failure),
+            Node::Sequence { children } => {
+                let mut chosen = None;
+                for child in children {
+                    match child.tick(ctx) {
+                        Outcome::Failure => return Outcome::Failure,
+                        Outcome::Success(action) => chosen = action.or(chosen),
+                    }
+                }
+                Outcome::Success(chosen)
+            }
+            Node::Utility { options } => {
+                let mut ranked: Vec<(f32, &UtilityOption)> =
+                    options.iter().map(|option| (option.score(ctx), option)).collect();
+                ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+                ranked
+                    .into_iter()
+                    .map(|(_, option)| option.node.tick(ctx))
+                    .find(|outcome| matches!(outcome, Outcome::Success(_)))
+                    .unwrap_or(Outcome::Failure)
+            }
+            Node::Condition { condition } => {
+                if condition.holds(ctx) {
+                    Outcome::Success(None)
+                } else {
+                    Outcome::Failure
+                }
+            }
+            Node::Action { action } => match action.resolve(ctx) {
+                Some(action) => Outcome::Success(Some(action)),
+                None => Outcome::Failure,
+            },
+        }
+    }
+}
+
+impl BehaviorTree {
+    /// The action the tree chooses, if any.
+    pub fn choose(&self, ctx: &ReasoningContext) -> Option<BehaviorAction> {
+        match self.root.tick(ctx) {
+            Outcome::Success(action) => action,
+            Outcome::Failure => None,
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, BehaviorError> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, BehaviorError> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// A tree from a `.toml` or `.json` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BehaviorError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("json") => Self::from_json(&text),
+            _ => Err(BehaviorError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    /// Every tree in `dir`, by name. Other files are ignored.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<HashMap<String, BehaviorTree>, BehaviorError> {
+        let mut trees = HashMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml" | "json")) {
+                let tree = Self::load(&path)?;
+                trees.insert(tree.name.clone(), tree);
+            }
+        }
+        Ok(trees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUARD: &str = r#"
+        name = "guard"
+
+        [root]
+        type = "selector"
+
+        [[root.children]]
+        type = "sequence"
+        children = [
+            { type = "condition", condition = { is = "tension_above", value = 0.7 } },
+            { type = "action", action = { do = "flee", reason = "danger" } },
+        ]
+
+        [[root.children]]
+        type = "utility"
+
+        [[root.children.options]]
+        base = 1.0
+        nearby = 1.0
+        node = { type = "action", action = { do = "interact", action = "greet" } }
+
+        [[root.children.options]]
+        base = 0.5
+        node = { type = "action", action = { do = "rest" } }
+    "#;
+
+    fn ctx(tension: f32, nearby: &[&str]) -> ReasoningContext {
+        ReasoningContext {
+            location: "grove".into(),
+            nearby_entities: nearby.iter().map(|entity| entity.to_string()).collect(),
+            harmony_level: 0.5,
+            tension,
+            memory: vec![],
+        }
+    }
+
+    #[test]
+    fn test_tree_from_toml_chooses_by_context() {
+        let tree = BehaviorTree::from_toml(GUARD).unwrap();
+        assert_eq!(tree.name, "guard");
+
+        assert!(matches!(tree.choose(&ctx(0.9, &[])), Some(BehaviorAction::Flee(reason)) if reason == "danger"));
+        assert!(matches!(
+            tree.choose(&ctx(0.1, &["player1"])),
+            Some(BehaviorAction::Interact { entity_id, .. }) if entity_id == "player1"
+        ));
+        // Greeting scores highest but there is no one to greet
+        assert!(matches!(tree.choose(&ctx(0.1, &[])), Some(BehaviorAction::Rest)));
+    }
+}
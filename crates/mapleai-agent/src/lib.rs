@@ -1,5 +1,7 @@
 pub mod agent;
+pub mod behavior;
 pub mod planner;
 pub mod llm_bridge;
 
 pub use agent::{Agent, AgentHandle};
+pub use behavior::{BehaviorError, BehaviorTree};
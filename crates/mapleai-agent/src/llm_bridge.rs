@@ -35,6 +35,7 @@ impl LLMBridge {
             player_id: None,
             temperature: Some(0.5),
             max_tokens: Some(32),
+            bypass_cache: false,
         };
         match self.engine.generate(request).await {
             Ok(res) => res.text,
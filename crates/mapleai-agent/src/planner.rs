@@ -1,10 +1,25 @@
+use std::sync::Arc;
+
 use finalverse_protocol::{BehaviorAction, ReasoningContext};
 
+use crate::behavior::BehaviorTree;
+
 #[derive(Clone, Default)]
-pub struct Planner;
+pub struct Planner {
+    /// Consulted first; the built-in heuristics decide when it has no answer.
+    tree: Option<Arc<BehaviorTree>>,
+}
 
 impl Planner {
+    pub fn with_tree(tree: Arc<BehaviorTree>) -> Self {
+        Self { tree: Some(tree) }
+    }
+
     pub fn plan(&self, ctx: &ReasoningContext) -> BehaviorAction {
+        if let Some(action) = self.tree.as_ref().and_then(|tree| tree.choose(ctx)) {
+            return action;
+        }
+
         if ctx.tension > 0.7 {
             BehaviorAction::Flee("danger".into())
         } else if ctx.harmony_level < 0.3 {
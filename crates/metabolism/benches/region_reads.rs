@@ -0,0 +1,143 @@
+//! Region read latency while a writer ticks continuously: the previous
+//! `RwLock`-guarded map against the published snapshot.
+//!
+//! `cargo bench -p finalverse-metobolism --bench region_reads`
+
+use finalverse_metobolism::{MetabolismSimulator, RegionId, RegionState, TerrainType, WeatherState, WeatherType};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+const REGIONS: usize = 256;
+const READERS: usize = 8;
+const RUN_FOR: Duration = Duration::from_secs(3);
+
+fn region() -> RegionState {
+    RegionState {
+        id: RegionId(Uuid::new_v4()),
+        harmony_level: 0.8,
+        discord_level: 0.2,
+        terrain_type: TerrainType::Forest,
+        weather: WeatherState {
+            weather_type: WeatherType::Clear,
+            intensity: 0.0,
+            wind_direction: 0.0,
+            wind_speed: 0.0,
+        },
+        political_tension: 0.0,
+    }
+}
+
+/// Run `READERS` readers against one writer and collect every read latency.
+async fn measure<R, W, RF, WF>(read: R, write: W) -> Vec<Duration>
+where
+    R: Fn(usize) -> RF + Send + Sync + 'static,
+    RF: std::future::Future<Output = ()> + Send,
+    W: Fn() -> WF + Send + Sync + 'static,
+    WF: std::future::Future<Output = ()> + Send,
+{
+    let read = Arc::new(read);
+    let deadline = Instant::now() + RUN_FOR;
+    let writer = tokio::spawn(async move {
+        while Instant::now() < deadline {
+            write().await;
+            tokio::task::yield_now().await;
+        }
+    });
+
+    let readers: Vec<_> = (0..READERS)
+        .map(|reader| {
+            let read = read.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                let mut i = reader;
+                while Instant::now() < deadline {
+                    let started = Instant::now();
+                    read(i).await;
+                    latencies.push(started.elapsed());
+                    i += 1;
+                }
+                latencies
+            })
+        })
+        .collect();
+
+    let mut latencies = Vec::new();
+    for reader in readers {
+        latencies.extend(reader.await.unwrap());
+    }
+    writer.await.unwrap();
+    latencies.sort();
+    latencies
+}
+
+fn report(name: &str, latencies: &[Duration]) {
+    let at = |quantile: f64| latencies[((latencies.len() - 1) as f64 * quantile) as usize];
+    println!(
+        "{:<10} reads={:>10} p50={:>10?} p99={:>10?} p99.9={:>10?} max={:>10?}",
+        name,
+        latencies.len(),
+        at(0.5),
+        at(0.99),
+        at(0.999),
+        latencies[latencies.len() - 1],
+    );
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(READERS + 1)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let regions: Vec<RegionState> = (0..REGIONS).map(|_| region()).collect();
+    let ids: Arc<Vec<RegionId>> = Arc::new(regions.iter().map(|region| region.id.clone()).collect());
+
+    runtime.block_on(async {
+        let locked: Arc<RwLock<HashMap<RegionId, RegionState>>> = Arc::new(RwLock::new(
+            regions.iter().map(|region| (region.id.clone(), region.clone())).collect(),
+        ));
+        let (reader_map, reader_ids) = (locked.clone(), ids.clone());
+        let latencies = measure(
+            move |i| {
+                let (map, ids) = (reader_map.clone(), reader_ids.clone());
+                async move {
+                    std::hint::black_box(map.read().await.get(&ids[i % ids.len()]).cloned());
+                }
+            },
+            move || {
+                let map = locked.clone();
+                async move {
+                    for region in map.write().await.values_mut() {
+                        region.harmony_level *= 0.99;
+                    }
+                }
+            },
+        )
+        .await;
+        report("rwlock", &latencies);
+
+        let simulator = Arc::new(MetabolismSimulator::new());
+        for region in &regions {
+            simulator.add_region(region.clone()).await;
+        }
+        let (reader_sim, reader_ids) = (simulator.clone(), ids.clone());
+        let latencies = measure(
+            move |i| {
+                let (simulator, ids) = (reader_sim.clone(), reader_ids.clone());
+                async move {
+                    std::hint::black_box(simulator.get_region(&ids[i % ids.len()]));
+                }
+            },
+            move || {
+                let simulator = simulator.clone();
+                async move { simulator.simulate_tick().await }
+            },
+        )
+        .await;
+        report("snapshot", &latencies);
+    });
+}
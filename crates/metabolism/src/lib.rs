@@ -1,10 +1,10 @@
+use arc_swap::ArcSwap;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 // Use shared domain types from finalverse-core
-pub use finalverse_core::{RegionId, TerrainType, WeatherType};
+pub use finalverse_core::{RegionEffect, RegionId, TerrainType, WeatherType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherState {
@@ -21,54 +21,311 @@ pub struct RegionState {
     pub discord_level: f64,
     pub terrain_type: TerrainType,
     pub weather: WeatherState,
+    #[serde(default)]
+    pub political_tension: f64,
 }
 
+/// Which regions border which. Borders are undirected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionGraph {
+    neighbors: HashMap<RegionId, HashSet<RegionId>>,
+}
+
+impl RegionGraph {
+    pub fn connect(&mut self, a: &RegionId, b: &RegionId) {
+        if a == b {
+            return;
+        }
+        self.neighbors.entry(a.clone()).or_default().insert(b.clone());
+        self.neighbors.entry(b.clone()).or_default().insert(a.clone());
+    }
+
+    pub fn disconnect(&mut self, a: &RegionId, b: &RegionId) {
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(neighbors) = self.neighbors.get_mut(from) {
+                neighbors.remove(to);
+                if neighbors.is_empty() {
+                    self.neighbors.remove(from);
+                }
+            }
+        }
+    }
+
+    pub fn neighbors(&self, id: &RegionId) -> impl Iterator<Item = &RegionId> {
+        self.neighbors.get(id).into_iter().flatten()
+    }
+}
+
+/// How fast dissonance and political tension flow across borders each
+/// tick, as the share of the gap to the neighbours' average a region
+/// closes. 0 keeps regions independent; rates above 1 overshoot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffusionRates {
+    pub dissonance: f64,
+    pub political_tension: f64,
+}
+
+impl Default for DiffusionRates {
+    fn default() -> Self {
+        Self {
+            dissonance: 0.05,
+            political_tension: 0.03,
+        }
+    }
+}
+
+/// Every region as of the last write.
+pub type RegionSnapshot = Arc<HashMap<RegionId, RegionState>>;
+
+/// Region state is read far more often than it changes, so readers never
+/// lock: writers are serialised on `regions` and publish an immutable copy
+/// to `snapshot` when they finish, and readers load whichever copy is
+/// current.
 pub struct MetabolismSimulator {
-    regions: Arc<RwLock<HashMap<RegionId, RegionState>>>,
+    regions: Mutex<HashMap<RegionId, RegionState>>,
+    snapshot: ArcSwap<HashMap<RegionId, RegionState>>,
+    graph: Mutex<RegionGraph>,
     harmony_decay_rate: f64,
     discord_spread_rate: f64,
+    diffusion: DiffusionRates,
 }
 
 impl MetabolismSimulator {
     pub fn new() -> Self {
         Self {
-            regions: Arc::new(RwLock::new(HashMap::new())),
+            regions: Mutex::new(HashMap::new()),
+            snapshot: ArcSwap::from_pointee(HashMap::new()),
+            graph: Mutex::new(RegionGraph::default()),
             harmony_decay_rate: 0.01,
             discord_spread_rate: 0.02,
+            diffusion: DiffusionRates::default(),
         }
     }
 
+    pub fn with_diffusion(self, diffusion: DiffusionRates) -> Self {
+        Self { diffusion, ..self }
+    }
+
+    /// Apply `change` to the regions and publish the result to readers.
+    fn write<R>(&self, change: impl FnOnce(&mut HashMap<RegionId, RegionState>) -> R) -> R {
+        let mut regions = self.regions.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let result = change(&mut regions);
+        self.snapshot.store(Arc::new(regions.clone()));
+        result
+    }
+
+    /// The regions as last published. Doesn't block on writers.
+    pub fn snapshot(&self) -> RegionSnapshot {
+        self.snapshot.load_full()
+    }
+
     pub async fn simulate_tick(&self) {
-        let mut regions = self.regions.write().await;
-        for (_, region) in regions.iter_mut() {
-            region.harmony_level *= 1.0 - self.harmony_decay_rate;
-            if region.discord_level > 0.1 {
-                region.discord_level *= 1.0 + self.discord_spread_rate;
-                if region.discord_level > 0.8 {
-                    region.terrain_type = TerrainType::Corrupted;
+        self.write(|regions| {
+            for region in regions.values_mut() {
+                region.harmony_level *= 1.0 - self.harmony_decay_rate;
+                if region.discord_level > 0.1 {
+                    region.discord_level *= 1.0 + self.discord_spread_rate;
+                    if region.discord_level > 0.8 {
+                        region.terrain_type = TerrainType::Corrupted;
+                    }
                 }
             }
-            if region.discord_level > 0.5 && rand::random::<f64>() < 0.3 {
-                region.weather.weather_type = WeatherType::DissonanceStorm;
-            }
-        }
+
+            let graph = self.graph.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            diffuse(regions, &graph, self.diffusion);
+        });
+    }
+
+    /// Let dissonance and tension flow between two regions from now on.
+    pub async fn connect_regions(&self, a: &RegionId, b: &RegionId) {
+        self.graph.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).connect(a, b);
+    }
+
+    pub async fn disconnect_regions(&self, a: &RegionId, b: &RegionId) {
+        self.graph.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).disconnect(a, b);
+    }
+
+    pub fn neighbors(&self, id: &RegionId) -> Vec<RegionId> {
+        let graph = self.graph.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        graph.neighbors(id).cloned().collect()
     }
 
     pub async fn add_region(&self, region: RegionState) {
-        self.regions.write().await.insert(region.id.clone(), region);
+        self.write(|regions| regions.insert(region.id.clone(), region));
     }
 
-    pub async fn get_region(&self, id: &RegionId) -> Option<RegionState> {
-        self.regions.read().await.get(id).cloned()
+    pub fn get_region(&self, id: &RegionId) -> Option<RegionState> {
+        self.snapshot.load().get(id).cloned()
+    }
+
+    pub fn regions(&self) -> Vec<RegionState> {
+        self.snapshot.load().values().cloned().collect()
+    }
+
+    /// A copy of the region borders.
+    pub fn graph(&self) -> RegionGraph {
+        self.graph.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Replace every region and border, as when loading a saved world.
+    pub async fn restore(&self, regions: Vec<RegionState>, graph: RegionGraph) {
+        *self.graph.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = graph;
+        self.write(|current| {
+            *current = regions.into_iter().map(|region| (region.id.clone(), region)).collect();
+        });
+    }
+
+    pub async fn set_terrain(&self, id: &RegionId, terrain: TerrainType) -> bool {
+        self.write(|regions| match regions.get_mut(id) {
+            Some(region) => {
+                region.terrain_type = terrain;
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Replace the weather of the given regions in one write.
+    pub async fn apply_weather(&self, weather: HashMap<RegionId, WeatherState>) {
+        self.write(|regions| {
+            for (id, state) in weather {
+                if let Some(region) = regions.get_mut(&id) {
+                    region.weather = state;
+                }
+            }
+        });
+    }
+
+    pub async fn update_tension(&self, id: &RegionId, delta: f64) -> Option<f64> {
+        self.write(|regions| {
+            let region = regions.get_mut(id)?;
+            region.political_tension = (region.political_tension + delta).clamp(0.0, 1.0);
+            Some(region.political_tension)
+        })
     }
 
     pub async fn update_harmony(&self, id: &RegionId, delta: f64) -> Option<f64> {
-        let mut regions = self.regions.write().await;
-        if let Some(region) = regions.get_mut(id) {
+        self.write(|regions| {
+            let region = regions.get_mut(id)?;
             region.harmony_level = (region.harmony_level + delta).clamp(0.0, 1.0);
             Some(region.harmony_level)
-        } else {
-            None
+        })
+    }
+
+    /// Apply `effects` to a region in order, as one write, and return the
+    /// region as it ends up. `None` if there's no such region.
+    pub async fn apply_effects(&self, id: &RegionId, effects: &[RegionEffect]) -> Option<RegionState> {
+        self.write(|regions| {
+            let region = regions.get_mut(id)?;
+            for effect in effects {
+                let (level, delta) = match *effect {
+                    RegionEffect::Harmony { delta } => (&mut region.harmony_level, delta),
+                    RegionEffect::Discord { delta } => (&mut region.discord_level, delta),
+                    RegionEffect::Tension { delta } => (&mut region.political_tension, delta),
+                };
+                *level = (*level + delta).clamp(0.0, 1.0);
+            }
+            Some(region.clone())
+        })
+    }
+}
+
+/// Move each region's dissonance and tension toward the average of its
+/// neighbours. Every region is updated from the levels at the start of the
+/// step, so the result doesn't depend on iteration order.
+fn diffuse(regions: &mut HashMap<RegionId, RegionState>, graph: &RegionGraph, rates: DiffusionRates) {
+    let levels: HashMap<RegionId, (f64, f64)> = regions
+        .iter()
+        .map(|(id, region)| (id.clone(), (region.discord_level, region.political_tension)))
+        .collect();
+
+    for (id, region) in regions.iter_mut() {
+        let (discord, tension) = levels[id];
+        let (mut discord_gap, mut tension_gap, mut count) = (0.0, 0.0, 0);
+        for (neighbor_discord, neighbor_tension) in graph.neighbors(id).filter_map(|neighbor| levels.get(neighbor)) {
+            discord_gap += neighbor_discord - discord;
+            tension_gap += neighbor_tension - tension;
+            count += 1;
+        }
+        if count > 0 {
+            region.discord_level = discord + rates.dissonance * discord_gap / count as f64;
+            region.political_tension = tension + rates.political_tension * tension_gap / count as f64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn region(discord_level: f64, political_tension: f64) -> RegionState {
+        RegionState {
+            id: RegionId(Uuid::new_v4()),
+            harmony_level: 0.5,
+            discord_level,
+            terrain_type: TerrainType::Plains,
+            weather: WeatherState {
+                weather_type: WeatherType::Clear,
+                intensity: 0.0,
+                wind_direction: 0.0,
+                wind_speed: 0.0,
+            },
+            political_tension,
         }
     }
+
+    #[tokio::test]
+    async fn outbreaks_spread_one_border_per_tick() {
+        let simulator = MetabolismSimulator::new().with_diffusion(DiffusionRates {
+            dissonance: 0.5,
+            political_tension: 0.5,
+        });
+        // source - middle - far, plus an island with no borders
+        let source = region(0.6, 0.8);
+        let middle = region(0.0, 0.0);
+        let far = region(0.0, 0.0);
+        let island = region(0.0, 0.0);
+        for r in [&source, &middle, &far, &island] {
+            simulator.add_region(r.clone()).await;
+        }
+        simulator.connect_regions(&source.id, &middle.id).await;
+        simulator.connect_regions(&middle.id, &far.id).await;
+
+        simulator.simulate_tick().await;
+        let level = |id: &RegionId| simulator.get_region(id).unwrap();
+        // The source grows 2% locally to 0.612 before sharing half its gap
+        assert!((level(&source.id).discord_level - 0.306).abs() < 1e-9);
+        assert!((level(&middle.id).discord_level - 0.153).abs() < 1e-9);
+        assert!((level(&middle.id).political_tension - 0.2).abs() < 1e-9);
+        assert_eq!(level(&far.id).discord_level, 0.0);
+
+        simulator.simulate_tick().await;
+        assert!(level(&far.id).discord_level > 0.0);
+        assert!(level(&far.id).political_tension > 0.0);
+        assert_eq!(level(&island.id).discord_level, 0.0);
+        assert_eq!(simulator.neighbors(&middle.id).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn effects_apply_in_order_and_clamp() {
+        let simulator = MetabolismSimulator::new();
+        let target = region(0.2, 0.0);
+        simulator.add_region(target.clone()).await;
+
+        let effects = [
+            RegionEffect::Harmony { delta: 0.7 },
+            RegionEffect::Harmony { delta: -0.1 },
+            RegionEffect::Discord { delta: -0.5 },
+            RegionEffect::Tension { delta: 0.25 },
+        ];
+        let applied = simulator.apply_effects(&target.id, &effects).await.unwrap();
+        // 0.5 + 0.7 clamps to 1.0 before the -0.1
+        assert!((applied.harmony_level - 0.9).abs() < 1e-9);
+        assert_eq!(applied.discord_level, 0.0);
+        assert_eq!(applied.political_tension, 0.25);
+        assert_eq!(simulator.get_region(&target.id).unwrap().harmony_level, applied.harmony_level);
+
+        assert!(simulator.apply_effects(&RegionId(Uuid::new_v4()), &effects).await.is_none());
+    }
 }
@@ -0,0 +1,145 @@
+// crates/metrics/src/export.rs
+//! Prometheus exporter: scraped at `GET /metrics`, and optionally pushed to
+//! the server console.
+
+use axum::{http::header, response::IntoResponse, routing::get, Router};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::OnceCell;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Buckets for every `*_seconds` histogram.
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// How often metrics are pushed when no interval is configured.
+const DEFAULT_PUSH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The server console only takes pushes carrying the operator token, sent
+/// in the same header `finalverse_health` checks.
+const OPERATOR_TOKEN_HEADER: &str = "x-operator-token";
+
+static HANDLE: OnceCell<MetricsHandle> = OnceCell::new();
+
+#[derive(Debug, thiserror::Error)]
+pub enum MetricsError {
+    #[error("Failed to install metrics exporter: {0}")]
+    Install(String),
+    #[error("Failed to push metrics: {0}")]
+    Push(String),
+}
+
+/// The installed exporter for this process.
+#[derive(Clone)]
+pub struct MetricsHandle {
+    service: String,
+    prometheus: PrometheusHandle,
+}
+
+/// Install the process-wide exporter, labelling every series with
+/// `service`. Calling it again returns the existing handle. When
+/// `FINALVERSE_METRICS_PUSH_URL` is set, metrics are also pushed there
+/// every `FINALVERSE_METRICS_PUSH_INTERVAL_SECS` (default 15) with the
+/// `FINALVERSE_OPERATOR_TOKEN`.
+pub fn init(service: &str) -> Result<MetricsHandle, MetricsError> {
+    let handle = HANDLE.get_or_try_init(|| {
+        let prometheus = PrometheusBuilder::new()
+            .add_global_label("service", service)
+            .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), LATENCY_BUCKETS)
+            .map_err(|e| MetricsError::Install(e.to_string()))?
+            .install_recorder()
+            .map_err(|e| MetricsError::Install(e.to_string()))?;
+        crate::http::describe();
+
+        let handle = MetricsHandle {
+            service: service.to_string(),
+            prometheus,
+        };
+        if let Ok(url) = std::env::var("FINALVERSE_METRICS_PUSH_URL") {
+            let every = std::env::var("FINALVERSE_METRICS_PUSH_INTERVAL_SECS")
+                .ok()
+                .and_then(|secs| secs.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_PUSH_INTERVAL);
+            let operator_token = std::env::var("FINALVERSE_OPERATOR_TOKEN").ok().filter(|token| !token.is_empty());
+            if operator_token.is_none() {
+                warn!("No operator token configured; the server console will refuse pushed metrics");
+            }
+            info!("Pushing metrics to {} every {:?}", url, every);
+            handle.spawn_push(url, every, operator_token);
+        }
+        Ok(handle)
+    })?;
+    Ok(handle.clone())
+}
+
+impl MetricsHandle {
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    /// Current metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        self.prometheus.run_upkeep();
+        self.prometheus.render()
+    }
+
+    /// `GET /metrics` for Prometheus to scrape.
+    pub fn axum_routes(&self) -> Router {
+        let handle = self.clone();
+        Router::new().route(
+            "/metrics",
+            get(move || {
+                let handle = handle.clone();
+                async move {
+                    (
+                        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                        handle.render(),
+                    )
+                        .into_response()
+                }
+            }),
+        )
+    }
+
+    /// Send the current metrics to `{url}/{service}`, e.g. the server
+    /// console's `/admin/metrics`.
+    pub async fn push(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        operator_token: Option<&str>,
+    ) -> Result<(), MetricsError> {
+        let target = format!("{}/{}", url.trim_end_matches('/'), self.service);
+        let mut request = client
+            .post(&target)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(self.render());
+        if let Some(token) = operator_token {
+            request = request.header(OPERATOR_TOKEN_HEADER, token);
+        }
+        request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| MetricsError::Push(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Keep pushing in the background.
+    pub fn spawn_push(&self, url: String, every: Duration, operator_token: Option<String>) -> tokio::task::JoinHandle<()> {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            let client = reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default();
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                if let Err(e) = handle.push(&client, &url, operator_token.as_deref()).await {
+                    warn!("{}", e);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,49 @@
+// crates/metrics/src/http.rs
+//! Request metrics for axum services.
+
+use crate::{counter, histogram, CounterDef, HistogramDef};
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+
+pub const HTTP_REQUESTS: CounterDef<3> = CounterDef::new(
+    "finalverse_http_requests_total",
+    "HTTP requests served",
+    ["method", "route", "status"],
+);
+
+pub const HTTP_REQUEST_DURATION: HistogramDef<2> = HistogramDef::new(
+    "finalverse_http_request_duration_seconds",
+    "Time taken to serve HTTP requests",
+    ["method", "route"],
+);
+
+pub(crate) fn describe() {
+    HTTP_REQUESTS.describe();
+    HTTP_REQUEST_DURATION.describe();
+}
+
+/// Middleware recording request counts and latencies. Routes are labelled
+/// by their pattern (`/echoes/:id`), never the raw path, to bound cardinality:
+///
+/// ```ignore
+/// router.layer(axum::middleware::from_fn(finalverse_metrics::http::track_requests))
+/// ```
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+
+    counter!(HTTP_REQUESTS, method = method, route = route, status = response.status().as_u16()).increment(1);
+    histogram!(HTTP_REQUEST_DURATION, method = method, route = route).record(started.elapsed().as_secs_f64());
+    response
+}
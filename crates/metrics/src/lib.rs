@@ -0,0 +1,152 @@
+// crates/metrics/src/lib.rs
+//! Typed, namespaced metrics for Finalverse services.
+//!
+//! Metrics are declared once as constants, which fixes their name, help text
+//! and label set:
+//!
+//! ```ignore
+//! const MELODIES: CounterDef<2> = CounterDef::new(
+//!     "finalverse_song_melodies_total",
+//!     "Melodies performed",
+//!     ["harmony_type", "dry_run"],
+//! );
+//!
+//! finalverse_metrics::counter!(MELODIES, harmony_type = "creative", dry_run = false).increment(1);
+//! ```
+//!
+//! Names must carry the `finalverse_` namespace, and passing the wrong
+//! number of labels fails to compile. Every series is additionally labelled
+//! with the reporting service by the exporter installed through [`init`].
+
+pub mod export;
+pub mod http;
+
+pub use export::{init, MetricsError, MetricsHandle};
+
+/// Prefix every metric name must start with.
+pub const NAMESPACE: &str = "finalverse_";
+
+const fn check_definition<const N: usize>(name: &str, labels: &[&str; N]) {
+    let name = name.as_bytes();
+    let prefix = NAMESPACE.as_bytes();
+    assert!(name.len() > prefix.len(), "metric name must be namespaced with finalverse_");
+    let mut i = 0;
+    while i < prefix.len() {
+        assert!(name[i] == prefix[i], "metric name must be namespaced with finalverse_");
+        i += 1;
+    }
+    let mut i = 0;
+    while i < N {
+        assert!(!labels[i].is_empty(), "metric labels must be named");
+        i += 1;
+    }
+}
+
+macro_rules! metric_def {
+    ($(#[$doc:meta])* $def:ident, $handle:ident, $register:ident, $describe:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $def<const N: usize> {
+            pub name: &'static str,
+            pub help: &'static str,
+            pub labels: [&'static str; N],
+        }
+
+        impl<const N: usize> $def<N> {
+            pub const fn new(name: &'static str, help: &'static str, labels: [&'static str; N]) -> Self {
+                check_definition(name, &labels);
+                Self { name, help, labels }
+            }
+
+            /// Register the help text with the exporter.
+            pub fn describe(&self) {
+                metrics::$describe!(self.name, self.help);
+            }
+
+            /// Handle for the series with these label values, given in
+            /// declaration order. Prefer the macro of the same name, which
+            /// also checks label names.
+            pub fn with(&self, values: [String; N]) -> metrics::$handle {
+                let labels: Vec<metrics::Label> = self
+                    .labels
+                    .iter()
+                    .zip(values)
+                    .map(|(key, value)| metrics::Label::new(*key, value))
+                    .collect();
+                metrics::$register!(self.name, labels)
+            }
+
+            #[doc(hidden)]
+            pub fn labeled(&self, labels: [(&'static str, String); N]) -> metrics::$handle {
+                debug_assert!(
+                    labels.iter().map(|(key, _)| *key).eq(self.labels.iter().copied()),
+                    "labels for {} must be {:?}",
+                    self.name,
+                    self.labels
+                );
+                self.with(labels.map(|(_, value)| value))
+            }
+        }
+    };
+}
+
+metric_def!(
+    /// A monotonically increasing count.
+    CounterDef, Counter, counter, describe_counter
+);
+metric_def!(
+    /// A value that can go up and down.
+    GaugeDef, Gauge, gauge, describe_gauge
+);
+metric_def!(
+    /// A distribution of observed values. Names ending in `_seconds` are
+    /// exported with latency buckets.
+    HistogramDef, Histogram, histogram, describe_histogram
+);
+
+/// `counter!(DEF, label = value, ...)`: the counter for a [`CounterDef`]
+/// with labels given by name, in declaration order.
+#[macro_export]
+macro_rules! counter {
+    ($def:expr $(, $label:ident = $value:expr)* $(,)?) => {
+        $def.labeled([$((stringify!($label), ($value).to_string())),*])
+    };
+}
+
+/// `gauge!(DEF, label = value, ...)`, see [`counter!`].
+#[macro_export]
+macro_rules! gauge {
+    ($def:expr $(, $label:ident = $value:expr)* $(,)?) => {
+        $def.labeled([$((stringify!($label), ($value).to_string())),*])
+    };
+}
+
+/// `histogram!(DEF, label = value, ...)`, see [`counter!`].
+#[macro_export]
+macro_rules! histogram {
+    ($def:expr $(, $label:ident = $value:expr)* $(,)?) => {
+        $def.labeled([$((stringify!($label), ($value).to_string())),*])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    const REQUESTS: CounterDef<2> = CounterDef::new("finalverse_test_requests_total", "Test requests", ["route", "status"]);
+
+    #[test]
+    fn typed_counters_render_with_labels() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            REQUESTS.describe();
+            counter!(REQUESTS, route = "/login", status = 200).increment(2);
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains("# HELP finalverse_test_requests_total Test requests"));
+        assert!(rendered.contains("finalverse_test_requests_total{route=\"/login\",status=\"200\"} 2"));
+    }
+}
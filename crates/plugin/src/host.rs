@@ -0,0 +1,369 @@
+// crates/plugin/src/host.rs
+//! Hot-reloadable HTTP plugins. The host keeps one live instance per
+//! plugin and hands out leases on it; routers built with
+//! [`PluginHost::router`] look the instance up for every request, so a
+//! reload only affects requests that arrive after the swap. The replaced
+//! instance is unloaded once the requests holding it have finished.
+//!
+//! Plugins that register gRPC services are refused: the tonic router is
+//! built once at startup and would keep calling into the old library.
+//! Load those through [`crate::discover_plugins`] instead.
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Router as AxumRouter;
+use futures_util::StreamExt;
+use serde_json::Value;
+use service_registry::LocalServiceRegistry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tower::ServiceExt;
+
+use crate::{load_plugin, LoadedPlugin, PluginError, PluginManifest};
+
+/// How long a replaced instance may keep serving requests before it is
+/// set aside instead of unloaded.
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+const DRAIN_POLL: Duration = Duration::from_millis(50);
+
+/// A loaded plugin and the router built from it.
+pub struct PluginInstance {
+    // Dropped before `plugin`, whose library the handlers live in
+    router: AxumRouter,
+    plugin: LoadedPlugin,
+}
+
+impl PluginInstance {
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.plugin.manifest
+    }
+}
+
+pub struct PluginHost {
+    dir: PathBuf,
+    registry: LocalServiceRegistry,
+    slots: RwLock<HashMap<String, Arc<PluginInstance>>>,
+    /// Modification time of every library file the watcher has tried.
+    seen: Mutex<HashMap<PathBuf, SystemTime>>,
+    /// Replaced instances still held by requests after [`DRAIN_TIMEOUT`].
+    retired: Arc<Mutex<Vec<Arc<PluginInstance>>>>,
+}
+
+impl PluginHost {
+    pub fn new(dir: impl Into<PathBuf>, registry: LocalServiceRegistry) -> Self {
+        Self {
+            dir: dir.into(),
+            registry,
+            slots: RwLock::new(HashMap::new()),
+            seen: Mutex::new(HashMap::new()),
+            retired: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// A host for `FINALVERSE_PLUGIN_DIR`, if it is set.
+    pub fn from_env(registry: LocalServiceRegistry) -> Option<Self> {
+        std::env::var("FINALVERSE_PLUGIN_DIR").ok().map(|dir| Self::new(dir, registry))
+    }
+
+    /// Lease the current instance of a plugin. It stays loaded until the
+    /// lease is dropped, even if the plugin is reloaded meanwhile.
+    pub fn get(&self, name: &str) -> Option<Arc<PluginInstance>> {
+        self.slots.read().unwrap().get(name).cloned()
+    }
+
+    pub fn manifests(&self) -> Vec<PluginManifest> {
+        let mut manifests: Vec<_> = self
+            .slots
+            .read()
+            .unwrap()
+            .values()
+            .map(|instance| instance.manifest().clone())
+            .collect();
+        manifests.sort_by(|a, b| a.name.cmp(&b.name));
+        manifests
+    }
+
+    /// A router serving whichever instance of `name` is current when each
+    /// request arrives. Responds 503 while the plugin isn't loaded.
+    pub fn router(self: &Arc<Self>, name: impl Into<String>) -> AxumRouter {
+        let host = self.clone();
+        let name = name.into();
+        AxumRouter::new().fallback(move |request: Request| {
+            let host = host.clone();
+            let name = name.clone();
+            async move {
+                let Some(lease) = host.get(&name) else {
+                    return StatusCode::SERVICE_UNAVAILABLE.into_response();
+                };
+                let Ok(response) = lease.router.clone().oneshot(request).await;
+                // Bodies can be streamed by plugin code after the handler
+                // returns, so the lease goes with the body
+                let (parts, body) = response.into_parts();
+                let body = body.into_data_stream().map(move |chunk| {
+                    let _lease = &lease;
+                    chunk
+                });
+                Response::from_parts(parts, Body::from_stream(body))
+            }
+        })
+    }
+
+    /// Run a command on the current instance of a plugin.
+    pub async fn handle_command(&self, name: &str, command: &str, args: Value) -> Result<Value, PluginError> {
+        let lease = self.get(name).ok_or_else(|| PluginError::NotLoaded(name.to_string()))?;
+        lease
+            .plugin
+            .instance
+            .handle_command(command, args)
+            .await
+            .map_err(|e| match e.downcast::<PluginError>() {
+                Ok(e) => e,
+                Err(e) => PluginError::Command(e.to_string()),
+            })
+    }
+
+    /// Load the current version of a plugin from the file it was loaded from.
+    pub async fn reload(&self, name: &str) -> Result<PluginManifest, PluginError> {
+        let path = self
+            .get(name)
+            .map(|instance| instance.plugin.path.clone())
+            .ok_or_else(|| PluginError::NotLoaded(name.to_string()))?;
+        let modified = modified(&path)?;
+        let manifest = self.load_file(&path, Some(name)).await?;
+        self.seen.lock().unwrap().insert(path, modified);
+        Ok(manifest)
+    }
+
+    /// Load libraries that are new or changed since the last scan, and log
+    /// the ones that fail. Removing a library leaves its plugin running.
+    pub async fn scan(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !is_library(&path) {
+                continue;
+            }
+            let Ok(modified) = modified(&path) else {
+                continue;
+            };
+            if self.seen.lock().unwrap().insert(path.clone(), modified) == Some(modified) {
+                continue;
+            }
+            let loaded_as = self
+                .slots
+                .read()
+                .unwrap()
+                .iter()
+                .find(|(_, instance)| instance.plugin.path == path)
+                .map(|(name, _)| name.clone());
+            match self.load_file(&path, loaded_as.as_deref()).await {
+                Ok(manifest) => tracing::info!(
+                    "Loaded plugin {} {} from {:?}",
+                    manifest.name,
+                    manifest.version,
+                    path
+                ),
+                Err(e) => tracing::warn!("Rejected plugin {:?}: {}", path, e),
+            }
+        }
+    }
+
+    /// Scan the plugin directory every `interval`, reloading plugins whose
+    /// library changes. Replace libraries with a rename so a half-written
+    /// file is never picked up.
+    pub fn watch(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.scan().await;
+            }
+        })
+    }
+
+    /// Load a library, expecting it to be the plugin `expected` if given.
+    async fn load_file(&self, path: &Path, expected: Option<&str>) -> Result<PluginManifest, PluginError> {
+        // The dynamic loader hands back the already loaded library for a
+        // path it has seen, so each version is loaded from its own copy
+        let copy = unique_copy(path)?;
+        let loaded = unsafe { load_plugin(&copy) };
+        // Still mapped after the file is gone, except on Windows where this fails
+        let _ = std::fs::remove_file(&copy);
+        let mut plugin = loaded?;
+        plugin.path = path.to_path_buf();
+
+        if let Some(expected) = expected {
+            if plugin.manifest.name != expected {
+                return Err(PluginError::Library(format!(
+                    "{:?} now contains plugin {} instead of {}",
+                    path, plugin.manifest.name, expected
+                )));
+            }
+        }
+        self.install(plugin).await
+    }
+
+    /// Start a loaded plugin and make it the current instance.
+    async fn install(&self, plugin: LoadedPlugin) -> Result<PluginManifest, PluginError> {
+        let manifest = plugin.manifest.clone();
+        if !manifest.capabilities.grpc_services.is_empty() {
+            return Err(PluginError::NotReloadable(manifest.name));
+        }
+        plugin
+            .instance
+            .init(&self.registry)
+            .await
+            .map_err(|e| PluginError::Init(e.to_string()))?;
+        let router = plugin.instance.routes().await;
+
+        let replaced = self
+            .slots
+            .write()
+            .unwrap()
+            .insert(manifest.name.clone(), Arc::new(PluginInstance { router, plugin }));
+        if let Some(old) = replaced {
+            tracing::info!("Draining previous instance of plugin {}", manifest.name);
+            tokio::spawn(drain(old, self.retired.clone()));
+        }
+        Ok(manifest)
+    }
+}
+
+/// Unload a replaced instance once no request holds it. Instances still in
+/// use after [`DRAIN_TIMEOUT`] are kept until a later drain finds them free.
+async fn drain(old: Arc<PluginInstance>, retired: Arc<Mutex<Vec<Arc<PluginInstance>>>>) {
+    let deadline = Instant::now() + DRAIN_TIMEOUT;
+    while Arc::strong_count(&old) > 1 && Instant::now() < deadline {
+        tokio::time::sleep(DRAIN_POLL).await;
+    }
+
+    let mut retired = retired.lock().unwrap();
+    retired.retain(|instance| Arc::strong_count(instance) > 1);
+    if Arc::strong_count(&old) > 1 {
+        tracing::warn!(
+            "Plugin {} {} still has requests in flight; keeping it loaded",
+            old.manifest().name,
+            old.manifest().version
+        );
+        retired.push(old);
+    }
+}
+
+fn is_library(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("so" | "dll" | "dylib")
+    )
+}
+
+fn modified(path: &Path) -> Result<SystemTime, PluginError> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| PluginError::Library(format!("{:?}: {}", path, e)))
+}
+
+fn unique_copy(path: &Path) -> Result<PathBuf, PluginError> {
+    static COPIES: AtomicU64 = AtomicU64::new(0);
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("plugin");
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("so");
+    let copy = std::env::temp_dir().join(format!(
+        "{}-{}-{}.{}",
+        stem,
+        std::process::id(),
+        COPIES.fetch_add(1, Ordering::Relaxed),
+        ext
+    ));
+    std::fs::copy(path, &copy).map_err(|e| PluginError::Library(format!("{:?}: {}", path, e)))?;
+    Ok(copy)
+}
+
+#[cfg(all(test, not(feature = "dynamic")))]
+mod tests {
+    use super::*;
+    use crate::ServicePlugin;
+    use std::sync::atomic::AtomicBool;
+
+    struct Versioned {
+        version: &'static str,
+        unloaded: Arc<AtomicBool>,
+    }
+
+    impl Drop for Versioned {
+        fn drop(&mut self) {
+            self.unloaded.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ServicePlugin for Versioned {
+        fn name(&self) -> &'static str { "versioned" }
+        fn manifest(&self) -> PluginManifest { PluginManifest::new("versioned", self.version) }
+        async fn routes(&self) -> AxumRouter {
+            let version = self.version;
+            AxumRouter::new().route("/", axum::routing::get(move || async move { version }))
+        }
+        async fn handle_command(&self, command: &str, args: Value) -> anyhow::Result<Value> {
+            match command {
+                "version" => Ok(Value::from(self.version)),
+                "echo" => Ok(args),
+                _ => Err(PluginError::UnknownCommand(command.to_string()).into()),
+            }
+        }
+    }
+
+    fn plugin(version: &'static str, unloaded: &Arc<AtomicBool>) -> LoadedPlugin {
+        let instance = Box::new(Versioned { version, unloaded: unloaded.clone() });
+        LoadedPlugin {
+            manifest: instance.manifest(),
+            instance,
+            path: PathBuf::from("versioned.so"),
+        }
+    }
+
+    async fn version_served(router: &AxumRouter) -> String {
+        let response = router.clone().oneshot(Request::new(Body::empty())).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reloads_drain_the_old_instance_before_unloading_it() {
+        let host = Arc::new(PluginHost::new("plugins", LocalServiceRegistry::new()));
+        let router = host.router("versioned");
+        let (v1_unloaded, v2_unloaded) = (Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)));
+
+        host.install(plugin("1.0.0", &v1_unloaded)).await.unwrap();
+        assert_eq!(version_served(&router).await, "1.0.0");
+
+        // A request still in flight on the old version keeps it loaded
+        let in_flight = host.get("versioned").unwrap();
+        host.install(plugin("2.0.0", &v2_unloaded)).await.unwrap();
+        assert_eq!(version_served(&router).await, "2.0.0");
+        tokio::time::sleep(DRAIN_POLL * 3).await;
+        assert!(!v1_unloaded.load(Ordering::SeqCst));
+
+        drop(in_flight);
+        tokio::time::sleep(DRAIN_POLL * 3).await;
+        assert!(v1_unloaded.load(Ordering::SeqCst));
+        assert!(!v2_unloaded.load(Ordering::SeqCst));
+        assert_eq!(host.manifests()[0].version, "2.0.0");
+
+        // Commands go to the current instance too
+        let version = host.handle_command("versioned", "version", Value::Null).await.unwrap();
+        assert_eq!(version, "2.0.0");
+        assert!(matches!(
+            host.handle_command("versioned", "explode", Value::Null).await,
+            Err(PluginError::UnknownCommand(command)) if command == "explode"
+        ));
+        assert!(matches!(
+            host.handle_command("missing", "version", Value::Null).await,
+            Err(PluginError::NotLoaded(_))
+        ));
+    }
+}
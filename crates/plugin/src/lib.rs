@@ -13,6 +13,44 @@ use libloading::{Library, Symbol};
 use service_registry::LocalServiceRegistry;
 // Use anyhow's Result for convenience in async plugin APIs
 use anyhow::Result;
+use serde_json::Value;
+use thiserror::Error;
+
+pub mod host;
+pub mod manifest;
+
+pub use host::{PluginHost, PluginInstance};
+pub use manifest::{negotiate_abi, PluginCapabilities, PluginManifest, PLUGIN_ABI_VERSION};
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Plugin ABI version {found} is not supported (host supports {min} to {max})")]
+    IncompatibleAbi { found: u32, min: u32, max: u32 },
+
+    #[error("Plugin manifest declares ABI version {declared} but the library exports {exported}")]
+    ManifestMismatch { declared: u32, exported: u32 },
+
+    #[error("Failed to load plugin library: {0}")]
+    Library(String),
+
+    #[error("Plugin {0} is not loaded")]
+    NotLoaded(String),
+
+    #[error("Plugin {0} registers gRPC services and can't be hot-reloaded")]
+    NotReloadable(String),
+
+    #[error("Plugin failed to initialise: {0}")]
+    Init(String),
+
+    #[error("Plugin has no command {0}")]
+    UnknownCommand(String),
+
+    #[error("Plugin command failed: {0}")]
+    Command(String),
+
+    #[error("Dynamic plugin loading disabled")]
+    DynamicLoadingDisabled,
+}
 
 /// Trait implemented by optional service plugins.
 /// Each plugin registers its own routes under the unified server.
@@ -21,6 +59,10 @@ pub trait ServicePlugin: Send + Sync {
     /// Name of the plugin/service
     fn name(&self) -> &'static str;
 
+    /// What the plugin provides and needs. Checked by the host after the
+    /// plugin's ABI version has been accepted.
+    fn manifest(&self) -> PluginManifest;
+
     /// Build the router for this plugin.
     async fn routes(&self) -> AxumRouter;
 
@@ -30,6 +72,12 @@ pub trait ServicePlugin: Send + Sync {
         Ok(())
     }
 
+    /// Run a named command, for operators and other services. Plugins
+    /// without commands keep the default, which knows none.
+    async fn handle_command(&self, command: &str, _args: Value) -> Result<Value> {
+        Err(PluginError::UnknownCommand(command.to_string()).into())
+    }
+
     /// Optionally register gRPC services on the given `Server` builder.
     /// Implementations can add their own gRPC service definitions and return
     /// the updated builder. The default implementation simply returns the
@@ -45,14 +93,39 @@ pub struct NoopPlugin;
 #[async_trait::async_trait]
 impl ServicePlugin for NoopPlugin {
     fn name(&self) -> &'static str { "noop" }
+    fn manifest(&self) -> PluginManifest { PluginManifest::new("noop", env!("CARGO_PKG_VERSION")) }
     async fn routes(&self) -> AxumRouter { AxumRouter::new() }
     fn register_grpc(self: Box<Self>, server: GrpcRouter) -> GrpcRouter { server }
 }
 
-/// Discover available plugins on the filesystem at runtime.
-/// Currently returns an empty list as a placeholder.
+/// Export a plugin from a `cdylib`: its ABI version and a constructor
+/// returning a new instance.
+///
+/// ```ignore
+/// finalverse_plugin::declare_plugin!(GreeterPlugin::new());
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:expr) => {
+        #[no_mangle]
+        pub extern "C" fn finalverse_plugin_abi_version() -> u32 {
+            $crate::PLUGIN_ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn finalverse_plugin_entry() -> *mut dyn $crate::ServicePlugin {
+            let plugin: Box<dyn $crate::ServicePlugin> = Box::new($constructor);
+            Box::into_raw(plugin)
+        }
+    };
+}
+
+/// A plugin loaded from a library in `FINALVERSE_PLUGIN_DIR`.
 pub struct LoadedPlugin {
     pub instance: Box<dyn ServicePlugin>,
+    pub manifest: PluginManifest,
+    pub path: PathBuf,
+    // Dropped after `instance`, whose code lives in the library
     #[cfg(feature = "dynamic")]
     _lib: Library,
 }
@@ -76,8 +149,17 @@ pub fn discover_plugins() -> Vec<LoadedPlugin> {
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
                     if ext == "so" || ext == "dll" || ext == "dylib" {
                         tracing::info!("Discovered plugin candidate: {:?}", path);
-                        if let Ok(plugin) = unsafe { load_plugin(&path) } {
-                            plugins.push(plugin);
+                        match unsafe { load_plugin(&path) } {
+                            Ok(plugin) => {
+                                tracing::info!(
+                                    "Loaded plugin {} {} (ABI {})",
+                                    plugin.manifest.name,
+                                    plugin.manifest.version,
+                                    plugin.manifest.abi_version
+                                );
+                                plugins.push(plugin);
+                            }
+                            Err(e) => tracing::warn!("Rejected plugin {:?}: {}", path, e),
                         }
                     }
                 }
@@ -87,19 +169,37 @@ pub fn discover_plugins() -> Vec<LoadedPlugin> {
     plugins
 }
 
-unsafe fn load_plugin(path: &Path) -> Result<LoadedPlugin> {
+/// Load one plugin library. Its ABI version is checked before anything
+/// else in the library is touched.
+///
+/// # Safety
+/// Runs the library's initialisers and constructor, which must be a plugin
+/// built with [`declare_plugin`].
+pub unsafe fn load_plugin(path: &Path) -> std::result::Result<LoadedPlugin, PluginError> {
     #[cfg(feature = "dynamic")]
     unsafe {
-        let lib = Library::new(path)?;
-        let constructor: Symbol<unsafe extern "C" fn() -> *mut dyn ServicePlugin> = lib.get(b"finalverse_plugin_entry")?;
-        let boxed_raw = constructor();
-        let instance = Box::from_raw(boxed_raw);
-        Ok(LoadedPlugin { instance, _lib: lib })
+        let library_error = |e: libloading::Error| PluginError::Library(e.to_string());
+        let lib = Library::new(path).map_err(library_error)?;
+        let abi_version: Symbol<unsafe extern "C" fn() -> u32> =
+            lib.get(manifest::ABI_VERSION_SYMBOL).map_err(library_error)?;
+        let abi_version = negotiate_abi(abi_version())?;
+
+        let constructor: Symbol<unsafe extern "C" fn() -> *mut dyn ServicePlugin> =
+            lib.get(manifest::ENTRY_SYMBOL).map_err(library_error)?;
+        let instance = Box::from_raw(constructor());
+        let manifest = instance.manifest();
+        if manifest.abi_version != abi_version {
+            return Err(PluginError::ManifestMismatch {
+                declared: manifest.abi_version,
+                exported: abi_version,
+            });
+        }
+        Ok(LoadedPlugin { instance, manifest, path: path.to_path_buf(), _lib: lib })
     }
 
     #[cfg(not(feature = "dynamic"))]
     {
         let _ = path;
-        Err(anyhow::anyhow!("dynamic plugin loading disabled"))
+        Err(PluginError::DynamicLoadingDisabled)
     }
 }
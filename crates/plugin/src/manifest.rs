@@ -0,0 +1,121 @@
+// crates/plugin/src/manifest.rs
+//! What a plugin declares about itself, and the ABI versions the host
+//! can load.
+//!
+//! A plugin library exports its ABI version as a plain C function next to
+//! its entry point (see [`crate::declare_plugin`]). The host reads it
+//! before calling into the plugin at all: a `dyn ServicePlugin` built
+//! against a different trait layout would crash on first use, but the
+//! version function has the same signature in every release.
+
+use serde::{Deserialize, Serialize};
+
+use crate::PluginError;
+
+/// The `ServicePlugin` ABI this build of the host and plugins implement.
+/// Bump it whenever the trait or the types it passes change.
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/// Oldest plugin ABI the host still loads. ABI 1 predates
+/// `ServicePlugin::handle_command`.
+pub const MIN_SUPPORTED_ABI_VERSION: u32 = 2;
+
+/// Symbol a plugin library exports its ABI version under.
+pub const ABI_VERSION_SYMBOL: &[u8] = b"finalverse_plugin_abi_version";
+
+/// Symbol a plugin library exports its constructor under.
+pub const ENTRY_SYMBOL: &[u8] = b"finalverse_plugin_entry";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    /// The plugin's own release.
+    pub version: String,
+    pub abi_version: u32,
+    pub capabilities: PluginCapabilities,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCapabilities {
+    /// Path prefixes the plugin's router serves.
+    pub http_routes: Vec<String>,
+    /// Fully qualified names of the gRPC services it registers.
+    pub grpc_services: Vec<String>,
+    /// Config keys that must be set for the plugin to start.
+    pub required_config: Vec<String>,
+}
+
+impl PluginManifest {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            abi_version: PLUGIN_ABI_VERSION,
+            capabilities: PluginCapabilities::default(),
+        }
+    }
+
+    pub fn with_http_route(mut self, route: impl Into<String>) -> Self {
+        self.capabilities.http_routes.push(route.into());
+        self
+    }
+
+    pub fn with_grpc_service(mut self, service: impl Into<String>) -> Self {
+        self.capabilities.grpc_services.push(service.into());
+        self
+    }
+
+    pub fn with_required_config(mut self, key: impl Into<String>) -> Self {
+        self.capabilities.required_config.push(key.into());
+        self
+    }
+
+    /// Required config keys `lookup` has no value for.
+    pub fn missing_config(&self, lookup: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        self.capabilities
+            .required_config
+            .iter()
+            .filter(|key| lookup(key).is_none())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Accept a plugin built against ABI `found` if the host still supports it.
+pub fn negotiate_abi(found: u32) -> Result<u32, PluginError> {
+    if (MIN_SUPPORTED_ABI_VERSION..=PLUGIN_ABI_VERSION).contains(&found) {
+        Ok(found)
+    } else {
+        Err(PluginError::IncompatibleAbi {
+            found,
+            min: MIN_SUPPORTED_ABI_VERSION,
+            max: PLUGIN_ABI_VERSION,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifests_declare_capabilities_and_abi_is_negotiated() {
+        let manifest = PluginManifest::new("greeter", "0.1.3")
+            .with_http_route("/greeter")
+            .with_grpc_service("finalverse.greeter.Greeter")
+            .with_required_config("GREETER_LANGUAGE")
+            .with_required_config("GREETER_STYLE");
+        assert_eq!(manifest.abi_version, PLUGIN_ABI_VERSION);
+        assert_eq!(manifest.capabilities.http_routes, vec!["/greeter"]);
+
+        let missing = manifest.missing_config(|key| (key == "GREETER_STYLE").then(|| "epic".to_string()));
+        assert_eq!(missing, vec!["GREETER_LANGUAGE"]);
+
+        assert_eq!(negotiate_abi(PLUGIN_ABI_VERSION).unwrap(), PLUGIN_ABI_VERSION);
+        assert!(matches!(
+            negotiate_abi(PLUGIN_ABI_VERSION + 1),
+            Err(PluginError::IncompatibleAbi { found, .. }) if found == PLUGIN_ABI_VERSION + 1
+        ));
+        assert!(negotiate_abi(0).is_err());
+    }
+}
@@ -17,6 +17,7 @@ fn main() {
                 proto_root.join("common.proto").to_str().unwrap(),
                 proto_root.join("world.proto").to_str().unwrap(),
                 proto_root.join("story.proto").to_str().unwrap(),
+                proto_root.join("world3d.proto").to_str().unwrap(),
             ],
             &[proto_root.to_str().unwrap()],
         )
@@ -9,4 +9,8 @@ pub mod world {
 
 pub mod story {
     tonic::include_proto!("finalverse.story");
-}
\ No newline at end of file
+}
+
+pub mod world3d {
+    tonic::include_proto!("finalverse.world3d");
+}
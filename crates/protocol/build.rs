@@ -0,0 +1,22 @@
+// crates/protocol/build.rs
+use std::path::PathBuf;
+
+#[allow(dead_code)]
+#[path = "src/schema.rs"]
+mod schema;
+
+fn main() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let schema_path = manifest_dir.join("schema").join("realtime.json");
+    println!("cargo:rerun-if-changed={}", schema_path.display());
+    println!("cargo:rerun-if-changed=src/schema.rs");
+
+    let json = std::fs::read_to_string(&schema_path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", schema_path.display(), e));
+    let rust = schema::Schema::parse(&json)
+        .and_then(|schema| schema.rust())
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("realtime.rs");
+    std::fs::write(&out, rust).unwrap_or_else(|e| panic!("Failed to write {}: {}", out.display(), e));
+}
@@ -0,0 +1,21 @@
+// crates/protocol/src/bin/protocol-codegen.rs
+//! Writes the realtime protocol artifacts for clients outside the Rust
+//! workspace: `realtime.schema.json` and `realtime.ts`.
+//!
+//! Usage: protocol-codegen [output dir, default target/protocol]
+
+use finalverse_protocol::realtime;
+use finalverse_protocol::schema::Schema;
+use std::path::PathBuf;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = std::env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("target/protocol"));
+    std::fs::create_dir_all(&out_dir)?;
+
+    let typescript = Schema::parse(realtime::SCHEMA)?.typescript()?;
+    std::fs::write(out_dir.join("realtime.schema.json"), realtime::SCHEMA)?;
+    std::fs::write(out_dir.join("realtime.ts"), typescript)?;
+
+    println!("Wrote realtime protocol artifacts to {}", out_dir.display());
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! Websocket framing. Clients pick a wire format when they connect
+//! (`?format=msgpack`); JSON text stays the default, and MessagePack
+//! binary frames cut the size of high-frequency position and world
+//! updates. Both go through serde, so any message type works with either.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CodecError {
+    #[error("Unknown wire format '{0}'; expected json or msgpack")]
+    UnknownFormat(String),
+    #[error("Failed to encode message: {0}")]
+    Encode(String),
+    #[error("Failed to decode message: {0}")]
+    Decode(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+/// An encoded message, ready to become a websocket text or binary frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodedFrame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl WireFormat {
+    /// The format asked for by a connect request; JSON when none is given.
+    pub fn negotiate(requested: Option<&str>) -> Result<Self, CodecError> {
+        requested.map_or(Ok(Self::Json), str::parse)
+    }
+
+    pub fn encode<T: Serialize>(&self, message: &T) -> Result<EncodedFrame, CodecError> {
+        match self {
+            Self::Json => serde_json::to_string(message)
+                .map(EncodedFrame::Text)
+                .map_err(|e| CodecError::Encode(e.to_string())),
+            // Named fields keep `#[serde(default)]` and skipped fields working
+            Self::MessagePack => rmp_serde::to_vec_named(message)
+                .map(EncodedFrame::Binary)
+                .map_err(|e| CodecError::Encode(e.to_string())),
+        }
+    }
+
+    /// Decodes a binary frame. Text frames are always JSON, whatever was
+    /// negotiated, so decode those with `decode_text`.
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| CodecError::Decode(e.to_string())),
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| CodecError::Decode(e.to_string())),
+        }
+    }
+
+    pub fn decode_text<T: DeserializeOwned>(text: &str) -> Result<T, CodecError> {
+        serde_json::from_str(text).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
+impl FromStr for WireFormat {
+    type Err = CodecError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "msgpack" | "messagepack" => Ok(Self::MessagePack),
+            other => Err(CodecError::UnknownFormat(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for WireFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Update {
+        Position { entity: String, x: f32, y: f32, z: f32 },
+        Harmony { level: f32 },
+    }
+
+    #[test]
+    fn both_formats_round_trip_and_msgpack_is_smaller() {
+        let update = Update::Position { entity: "echo-lumi".to_string(), x: 12.5, y: 0.0, z: -3.25 };
+
+        let EncodedFrame::Text(json) = WireFormat::Json.encode(&update).unwrap() else {
+            panic!("JSON should encode to text");
+        };
+        assert_eq!(WireFormat::decode_text::<Update>(&json).unwrap(), update);
+
+        let EncodedFrame::Binary(packed) = WireFormat::MessagePack.encode(&update).unwrap() else {
+            panic!("MessagePack should encode to binary");
+        };
+        assert_eq!(WireFormat::MessagePack.decode::<Update>(&packed).unwrap(), update);
+        assert!(packed.len() < json.len());
+        assert!(WireFormat::MessagePack.decode::<Update>(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn formats_are_negotiated_by_name() {
+        assert_eq!(WireFormat::negotiate(None).unwrap(), WireFormat::Json);
+        assert_eq!(WireFormat::negotiate(Some("MsgPack")).unwrap(), WireFormat::MessagePack);
+        assert_eq!(WireFormat::MessagePack.to_string().parse::<WireFormat>().unwrap(), WireFormat::MessagePack);
+        assert!(matches!(WireFormat::negotiate(Some("protobuf")), Err(CodecError::UnknownFormat(_))));
+    }
+}
@@ -1,7 +1,11 @@
 pub mod agent;
 pub mod reasoning;
 pub mod action;
+pub mod codec;
+pub mod realtime;
+pub mod schema;
 
 pub use agent::*;
 pub use reasoning::*;
 pub use action::*;
+pub use codec::{CodecError, EncodedFrame, WireFormat};
@@ -0,0 +1,66 @@
+// crates/protocol/src/realtime.rs
+//! The messages both gateways speak, generated at build time from
+//! `schema/realtime.json`. websocket-gateway uses [`WSMessage`];
+//! realtime-gateway uses [`ClientMessage`] and [`ServerMessage`].
+
+use finalverse_core::types::{ClientCapabilities, ContentFeature};
+
+include!(concat!(env!("OUT_DIR"), "/realtime.rs"));
+
+/// The schema the types above were generated from.
+pub const SCHEMA: &str = include_str!("../schema/realtime.json");
+
+impl WSMessage {
+    /// Content feature the client must support to receive this message as-is.
+    pub fn required_feature(&self) -> Option<ContentFeature> {
+        match self {
+            WSMessage::AudioCue { .. } => Some(ContentFeature::Audio),
+            WSMessage::EntityUpdate { .. } => Some(ContentFeature::Streaming3d),
+            _ => None,
+        }
+    }
+
+    /// Adapt the message to what the client can handle, replacing
+    /// feature-specific payloads with a text description where possible.
+    pub fn downgrade_for(self, capabilities: &ClientCapabilities) -> Option<WSMessage> {
+        match self.required_feature() {
+            Some(feature) if !capabilities.supports(feature) => match self {
+                WSMessage::AudioCue { description, .. } | WSMessage::EntityUpdate { description, .. } => {
+                    (!description.is_empty()).then_some(WSMessage::TextDescription { text: description })
+                }
+                _ => None,
+            },
+            _ => Some(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use finalverse_core::RegionId;
+
+    #[test]
+    fn messages_serialize_under_the_variant_names_in_the_schema() {
+        let variants = Schema::parse(SCHEMA).unwrap().variants("WSMessage");
+        let cue = WSMessage::AudioCue {
+            region: RegionId(Default::default()),
+            cue: "wind-chimes".to_string(),
+            description: "Chimes ring out over the grove.".to_string(),
+        };
+        let json = serde_json::to_value(&cue).unwrap();
+        let tag = json.as_object().unwrap().keys().next().unwrap();
+        assert!(variants.contains(tag));
+        assert_eq!(variants.len(), 14);
+
+        let Some(WSMessage::TextDescription { text }) = cue.downgrade_for(&ClientCapabilities::text_only()) else {
+            panic!("text-only clients should get the description");
+        };
+        assert_eq!(text, "Chimes ring out over the grove.");
+
+        let reply: ServerMessage =
+            serde_json::from_str(r#"{"id":"7","event":"chat.message","payload":{"text":"hello"}}"#).unwrap();
+        assert_eq!(reply.payload["text"], "hello");
+    }
+}
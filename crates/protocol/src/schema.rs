@@ -0,0 +1,312 @@
+// crates/protocol/src/schema.rs
+//! Code generation from the JSON schema in `schema/realtime.json`. The
+//! build script turns it into the Rust types in [`crate::realtime`], and
+//! `protocol-codegen` into TypeScript for clients outside this repo.
+//!
+//! Only the parts of JSON schema the protocol uses are understood:
+//! objects become structs, a `oneOf` of single-key objects becomes a
+//! serde externally tagged enum, and a definition carrying `x-rust-type`
+//! names an existing Rust type instead of generating one.
+
+use serde_json::{Map, Value};
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub struct SchemaError(String);
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid protocol schema: {}", self.0)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+fn invalid(message: impl Into<String>) -> SchemaError {
+    SchemaError(message.into())
+}
+
+pub struct Schema {
+    definitions: Map<String, Value>,
+}
+
+/// One field of a struct or enum variant.
+struct Field<'a> {
+    name: &'a str,
+    schema: &'a Value,
+    required: bool,
+}
+
+impl Schema {
+    pub fn parse(json: &str) -> Result<Self, SchemaError> {
+        let document: Value = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        let definitions = document
+            .get("definitions")
+            .and_then(Value::as_object)
+            .cloned()
+            .ok_or_else(|| invalid("no definitions"))?;
+        Ok(Self { definitions })
+    }
+
+    /// Names of the variants of a `oneOf` definition, in schema order.
+    pub fn variants(&self, definition: &str) -> Vec<String> {
+        self.definitions
+            .get(definition)
+            .and_then(|schema| schema.get("oneOf"))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|variant| variant_body(variant).ok().map(|(name, _)| name.to_string()))
+            .collect()
+    }
+
+    /// Rust source for every definition without an `x-rust-type`.
+    pub fn rust(&self) -> Result<String, SchemaError> {
+        let mut out = String::from("// @generated from schema/realtime.json; edit the schema, not this file.\n");
+        for (name, schema) in self.generated() {
+            out.push('\n');
+            rust_doc(&mut out, schema, "");
+            out.push_str("#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]\n");
+            if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+                writeln!(out, "pub enum {} {{", name).unwrap();
+                for variant in variants {
+                    let (variant_name, body) = variant_body(variant)?;
+                    rust_doc(&mut out, variant, "    ");
+                    writeln!(out, "    {} {{", variant_name).unwrap();
+                    for field in fields(body)? {
+                        self.rust_field(&mut out, &field, "        ", "")?;
+                    }
+                    out.push_str("    },\n");
+                }
+            } else {
+                writeln!(out, "pub struct {} {{", name).unwrap();
+                for field in fields(schema)? {
+                    self.rust_field(&mut out, &field, "    ", "pub ")?;
+                }
+            }
+            out.push_str("}\n");
+        }
+        Ok(out)
+    }
+
+    /// TypeScript declarations for every definition, including those
+    /// backed by existing Rust types.
+    pub fn typescript(&self) -> Result<String, SchemaError> {
+        let mut out = String::from("// Generated from schema/realtime.json by protocol-codegen; do not edit.\n");
+        for (name, schema) in &self.definitions {
+            out.push('\n');
+            ts_doc(&mut out, schema, "");
+            if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+                writeln!(out, "export type {} =", name).unwrap();
+                for variant in variants {
+                    let (variant_name, body) = variant_body(variant)?;
+                    ts_doc(&mut out, variant, "  ");
+                    writeln!(out, "  | {{ {}: {} }}", variant_name, self.ts_object(body)?).unwrap();
+                }
+                out.truncate(out.trim_end().len());
+                out.push_str(";\n");
+            } else if schema.get("type").and_then(Value::as_str) == Some("object") && schema.get("properties").is_some() {
+                writeln!(out, "export interface {} {}", name, self.ts_object(schema)?).unwrap();
+            } else {
+                writeln!(out, "export type {} = {};", name, self.ts_type(schema)?).unwrap();
+            }
+        }
+        Ok(out)
+    }
+
+    fn generated(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.definitions.iter().filter(|(_, schema)| schema.get("x-rust-type").is_none())
+    }
+
+    fn rust_field(&self, out: &mut String, field: &Field, indent: &str, visibility: &str) -> Result<(), SchemaError> {
+        if !is_identifier(field.name) {
+            return Err(invalid(format!("'{}' is not a valid Rust field name", field.name)));
+        }
+        let ty = self.rust_type(field.schema)?;
+        rust_doc(out, field.schema, indent);
+        if field.required {
+            writeln!(out, "{}{}{}: {},", indent, visibility, field.name, ty).unwrap();
+        } else {
+            writeln!(out, "{}#[serde(default, skip_serializing_if = \"Option::is_none\")]", indent).unwrap();
+            writeln!(out, "{}{}{}: Option<{}>,", indent, visibility, field.name, ty).unwrap();
+        }
+        Ok(())
+    }
+
+    fn rust_type(&self, schema: &Value) -> Result<String, SchemaError> {
+        if let Some(name) = self.reference(schema)? {
+            let definition = &self.definitions[name];
+            return Ok(match definition.get("x-rust-type").and_then(Value::as_str) {
+                Some(path) => path.to_string(),
+                None => name.to_string(),
+            });
+        }
+        let format = schema.get("format").and_then(Value::as_str);
+        Ok(match schema.get("type").and_then(Value::as_str) {
+            Some("string") => "String".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("integer") => match format {
+                Some("uint64") => "u64",
+                Some("uint32") => "u32",
+                Some("int32") => "i32",
+                _ => "i64",
+            }
+            .to_string(),
+            Some("number") if format == Some("float") => "f32".to_string(),
+            Some("number") => "f64".to_string(),
+            Some("array") => format!("Vec<{}>", self.rust_type(items(schema)?)?),
+            Some("object") | None => "serde_json::Value".to_string(),
+            Some(other) => return Err(invalid(format!("unsupported type '{}'", other))),
+        })
+    }
+
+    fn ts_object(&self, schema: &Value) -> Result<String, SchemaError> {
+        let fields = fields(schema)?;
+        if fields.is_empty() {
+            return Ok("{}".to_string());
+        }
+        let members = fields
+            .iter()
+            .map(|field| {
+                let name = if is_identifier(field.name) { field.name.to_string() } else { format!("\"{}\"", field.name) };
+                let optional = if field.required { "" } else { "?" };
+                Ok(format!("{}{}: {}", name, optional, self.ts_type(field.schema)?))
+            })
+            .collect::<Result<Vec<_>, SchemaError>>()?;
+        Ok(format!("{{ {} }}", members.join("; ")))
+    }
+
+    fn ts_type(&self, schema: &Value) -> Result<String, SchemaError> {
+        if let Some(name) = self.reference(schema)? {
+            return Ok(name.to_string());
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            return Ok(values.iter().map(Value::to_string).collect::<Vec<_>>().join(" | "));
+        }
+        Ok(match schema.get("type").and_then(Value::as_str) {
+            Some("string") => "string".to_string(),
+            Some("boolean") => "boolean".to_string(),
+            Some("integer") | Some("number") => "number".to_string(),
+            Some("array") => format!("{}[]", self.ts_type(items(schema)?)?),
+            Some("object") if schema.get("properties").is_some() => self.ts_object(schema)?,
+            Some("object") => "Record<string, unknown>".to_string(),
+            None => "unknown".to_string(),
+            Some(other) => return Err(invalid(format!("unsupported type '{}'", other))),
+        })
+    }
+
+    /// The definition a `$ref` points at, checked to exist.
+    fn reference<'a>(&self, schema: &'a Value) -> Result<Option<&'a str>, SchemaError> {
+        let Some(reference) = schema.get("$ref").and_then(Value::as_str) else {
+            return Ok(None);
+        };
+        let name = reference
+            .strip_prefix("#/definitions/")
+            .ok_or_else(|| invalid(format!("only local references are supported, got '{}'", reference)))?;
+        if !self.definitions.contains_key(name) {
+            return Err(invalid(format!("'{}' is not defined", name)));
+        }
+        Ok(Some(name))
+    }
+}
+
+/// An externally tagged variant: an object whose only property is the
+/// variant name, holding the variant's fields.
+fn variant_body(variant: &Value) -> Result<(&str, &Value), SchemaError> {
+    let properties = variant.get("properties").and_then(Value::as_object);
+    match properties.map(|properties| properties.iter().collect::<Vec<_>>()).as_deref() {
+        Some([(name, body)]) => Ok((name.as_str(), *body)),
+        _ => Err(invalid("each oneOf variant must have exactly one property naming it")),
+    }
+}
+
+fn fields(schema: &Value) -> Result<Vec<Field<'_>>, SchemaError> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let properties = match schema.get("properties") {
+        Some(properties) => properties.as_object().ok_or_else(|| invalid("properties must be an object"))?,
+        None => return Ok(Vec::new()),
+    };
+    Ok(properties
+        .iter()
+        .map(|(name, schema)| Field { name, schema, required: required.contains(&name.as_str()) })
+        .collect())
+}
+
+fn items(schema: &Value) -> Result<&Value, SchemaError> {
+    schema.get("items").ok_or_else(|| invalid("arrays need items"))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn description(schema: &Value) -> Option<&str> {
+    schema.get("description").and_then(Value::as_str)
+}
+
+fn rust_doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(text) = description(schema) {
+        writeln!(out, "{}/// {}", indent, text).unwrap();
+    }
+}
+
+fn ts_doc(out: &mut String, schema: &Value, indent: &str) {
+    if let Some(text) = description(schema) {
+        writeln!(out, "{}/** {} */", indent, text).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r##"{
+        "definitions": {
+            "Ping": {
+                "description": "Keeps the socket open.",
+                "type": "object",
+                "properties": { "sent_at": { "type": "integer", "format": "uint64" }, "note": { "type": "string" } },
+                "required": ["sent_at"]
+            },
+            "Frame": {
+                "oneOf": [
+                    { "type": "object", "properties": { "Ping": { "type": "object", "properties": { "ping": { "$ref": "#/definitions/Ping" } }, "required": ["ping"] } } },
+                    { "type": "object", "properties": { "Moved": { "type": "object", "properties": { "to": { "$ref": "#/definitions/Place" } }, "required": ["to"] } } }
+                ]
+            },
+            "Place": { "x-rust-type": "crate::Place", "type": "object", "properties": { "3d-x": { "type": "number" } } }
+        }
+    }"##;
+
+    #[test]
+    fn generates_rust_and_typescript_from_one_schema() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        assert_eq!(schema.variants("Frame"), vec!["Ping", "Moved"]);
+
+        let rust = schema.rust().unwrap();
+        assert!(rust.contains("/// Keeps the socket open.\n#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]\npub struct Ping {"));
+        assert!(rust.contains("    pub sent_at: u64,"));
+        assert!(rust.contains("    pub note: Option<String>,"));
+        assert!(rust.contains("pub enum Frame {\n    Ping {\n        ping: Ping,\n    },\n    Moved {\n        to: crate::Place,"));
+        // Types that already exist in Rust are referenced, not redefined
+        assert!(!rust.contains("struct Place"));
+
+        let typescript = schema.typescript().unwrap();
+        assert!(typescript.contains("export interface Ping {"));
+        assert!(typescript.contains("note?: string"));
+        assert!(typescript.contains("sent_at: number"));
+        assert!(typescript.contains("export type Frame =\n  | { Ping: { ping: Ping } }\n  | { Moved: { to: Place } };"));
+        assert!(typescript.contains("export interface Place { \"3d-x\"?: number }"));
+
+        let broken = SCHEMA.replace("#/definitions/Place", "#/definitions/Spot");
+        assert!(Schema::parse(&broken).unwrap().rust().is_err());
+    }
+}
@@ -0,0 +1,51 @@
+// crates/ratelimit/src/axum.rs
+//! Axum middleware. Client addresses come from `ConnectInfo`, so serve the
+//! app with `into_make_service_with_connect_info::<SocketAddr>()`; without
+//! it only the per-player limit applies.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::{RateLimitError, RateLimits};
+
+/// ```ignore
+/// let limits = Arc::new(RateLimits::load());
+/// router.layer(axum::middleware::from_fn_with_state(limits, limit_requests))
+/// ```
+pub async fn limit_requests(
+    State(limits): State<Arc<RateLimits>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, RateLimitError> {
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let player = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| limits.player_for(token.trim()));
+    limits.check(ip, player.as_ref())?;
+    Ok(next.run(request).await)
+}
+
+impl IntoResponse for RateLimitError {
+    fn into_response(self) -> Response {
+        let RateLimitError::Exceeded { retry_after_secs, .. } = &self;
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after_secs.to_string())],
+            Json(serde_json::json!({ "error": self.to_string() })),
+        )
+            .into_response()
+    }
+}
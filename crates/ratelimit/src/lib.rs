@@ -0,0 +1,229 @@
+// crates/ratelimit/src/lib.rs
+//! Token-bucket rate limiting for public endpoints.
+//!
+//! Every client IP and every signed-in player gets a bucket that refills at
+//! the configured rate and holds up to the burst size. A request spends one
+//! token from each bucket that applies to it, so a player can't dodge their
+//! limit by spreading requests over several addresses. Limits come from
+//! `security.rate_limiting` in `FinalverseConfig`; enable the `axum`
+//! feature for the middleware.
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+use finalverse_auth::JwtAuth;
+use finalverse_config::SecurityConfig;
+use finalverse_core::PlayerId;
+use finalverse_metrics::{counter, CounterDef};
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Above this many tracked clients, buckets that have refilled completely
+/// are dropped; they'd be recreated full anyway.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+const RATE_LIMITED: CounterDef<1> = CounterDef::new(
+    "finalverse_rate_limited_requests_total",
+    "Requests refused by rate limits, by the limit that refused them",
+    ["scope"],
+);
+
+pub fn describe_metrics() {
+    RATE_LIMITED.describe();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitScope {
+    Ip,
+    Player,
+}
+
+impl LimitScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitScope::Ip => "ip",
+            LimitScope::Player => "player",
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RateLimitError {
+    #[error("Too many requests per {}; retry in {retry_after_secs}s", scope.as_str())]
+    Exceeded { scope: LimitScope, retry_after_secs: u64 },
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// One token bucket per key.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32, burst_size: u32) -> Self {
+        Self {
+            capacity: burst_size.max(1) as f64,
+            per_second: requests_per_minute.max(1) as f64 / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Spend a token for `key`, or say how long until one is available.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_THRESHOLD {
+            let (capacity, per_second) = (self.capacity, self.per_second);
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.saturating_duration_since(bucket.updated).as_secs_f64() * per_second < capacity
+            });
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        let refill = now.saturating_duration_since(bucket.updated).as_secs_f64() * self.per_second;
+        bucket.tokens = (bucket.tokens + refill).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.per_second))
+        }
+    }
+}
+
+/// The per-IP and per-player limits of one service.
+#[derive(Debug)]
+pub struct RateLimits {
+    enabled: bool,
+    per_ip: RateLimiter,
+    per_player: RateLimiter,
+    exempt_ips: HashSet<IpAddr>,
+    tokens: JwtAuth,
+}
+
+impl RateLimits {
+    /// Whitelist entries that aren't IP addresses are ignored; the config
+    /// validator reports them.
+    pub fn from_config(security: &SecurityConfig) -> Self {
+        let limits = &security.rate_limiting;
+        Self {
+            enabled: limits.enabled,
+            per_ip: RateLimiter::new(limits.requests_per_minute, limits.burst_size),
+            per_player: RateLimiter::new(limits.player_requests_per_minute, limits.player_burst_size),
+            exempt_ips: limits.ip_whitelist.iter().filter_map(|ip| ip.parse().ok()).collect(),
+            tokens: JwtAuth::from_config(security),
+        }
+    }
+
+    pub fn load() -> Self {
+        let security = finalverse_config::load_default_config()
+            .map(|config| config.security)
+            .unwrap_or_default();
+        Self::from_config(&security)
+    }
+
+    /// The player a bearer token was issued to, if it verifies. Requests
+    /// with a bad token are limited by IP only and rejected by the auth
+    /// layer further in.
+    pub fn player_for(&self, token: &str) -> Option<PlayerId> {
+        self.tokens.verify(token).ok().map(|claims| claims.player_id())
+    }
+
+    /// Spend a token for the client address and the player, whichever are known.
+    pub fn check(&self, ip: Option<IpAddr>, player: Option<&PlayerId>) -> Result<(), RateLimitError> {
+        self.check_at(ip, player, Instant::now())
+    }
+
+    fn check_at(&self, ip: Option<IpAddr>, player: Option<&PlayerId>, now: Instant) -> Result<(), RateLimitError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if let Some(ip) = ip.filter(|ip| !self.exempt_ips.contains(ip)) {
+            self.per_ip
+                .check_at(&ip.to_string(), now)
+                .map_err(|retry_after| exceeded(LimitScope::Ip, retry_after))?;
+        }
+        if let Some(player) = player {
+            self.per_player
+                .check_at(&player.0.to_string(), now)
+                .map_err(|retry_after| exceeded(LimitScope::Player, retry_after))?;
+        }
+        Ok(())
+    }
+}
+
+fn exceeded(scope: LimitScope, retry_after: Duration) -> RateLimitError {
+    counter!(RATE_LIMITED, scope = scope.as_str()).increment(1);
+    RateLimitError::Exceeded {
+        scope,
+        retry_after_secs: retry_after.as_secs_f64().ceil().max(1.0) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finalverse_config::RateLimitConfig;
+
+    #[test]
+    fn buckets_allow_bursts_then_refill_at_the_configured_rate() {
+        let limits = RateLimits::from_config(&SecurityConfig {
+            rate_limiting: RateLimitConfig {
+                requests_per_minute: 60,
+                burst_size: 3,
+                player_requests_per_minute: 30,
+                player_burst_size: 4,
+                ip_whitelist: vec!["10.0.0.5".to_string()],
+                ..RateLimitConfig::default()
+            },
+            ..SecurityConfig::default()
+        });
+        let start = Instant::now();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+
+        for _ in 0..3 {
+            assert!(limits.check_at(Some(client), None, start).is_ok());
+        }
+        assert_eq!(
+            limits.check_at(Some(client), None, start),
+            Err(RateLimitError::Exceeded { scope: LimitScope::Ip, retry_after_secs: 1 })
+        );
+        assert!(limits.check_at(Some(client), None, start + Duration::from_secs(1)).is_ok());
+
+        // Only whitelisted addresses are exempt, loopback included
+        let colocated: IpAddr = "10.0.0.5".parse().unwrap();
+        assert!((0..100).all(|_| limits.check_at(Some(colocated), None, start).is_ok()));
+        let local: IpAddr = "127.0.0.1".parse().unwrap();
+        assert!((0..4).any(|_| limits.check_at(Some(local), None, start).is_err()));
+
+        // A player hopping between addresses still spends from one bucket
+        let player = PlayerId(uuid::Uuid::from_u128(7));
+        for i in 0..4 {
+            let ip: IpAddr = format!("198.51.100.{}", i).parse().unwrap();
+            assert!(limits.check_at(Some(ip), Some(&player), start).is_ok());
+        }
+        assert!(matches!(
+            limits.check_at(Some("198.51.100.9".parse().unwrap()), Some(&player), start),
+            Err(RateLimitError::Exceeded { scope: LimitScope::Player, retry_after_secs: 2 })
+        ));
+    }
+}
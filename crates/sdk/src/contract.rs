@@ -0,0 +1,330 @@
+// crates/sdk/src/contract.rs
+//! Golden fixtures of SDK calls, and the check services replay them with.
+//!
+//! A fixture records a request the SDK sends and a sample of the response
+//! it parses. Replays compare shapes, not values: every field the SDK
+//! reads must come back with the same JSON type. Extra fields are fine, so
+//! services can grow responses without breaking older clients.
+
+use crate::song::{CoordinatesRequest, MelodyRequest, NoteRequest, PerformMelodyRequest, PerformMelodyResponse, PERFORM_MELODY_PATH};
+use crate::world::{RegionSummary, WeatherSummary, REGIONS_PATH};
+use crate::SDK_VERSION;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Set to rewrite this version's fixtures from the SDK types.
+pub const UPDATE_FIXTURES_ENV: &str = "FINALVERSE_UPDATE_FIXTURES";
+
+/// One call the SDK makes and the reply it expects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub sdk_version: String,
+    pub name: String,
+    pub service: String,
+    pub method: String,
+    pub path: String,
+    /// Sent with a player's bearer token; replays sign one in.
+    #[serde(default)]
+    pub authenticated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    pub status: u16,
+    /// A sample reply. A null stands for an optional field, and an empty
+    /// array for one whose items the SDK doesn't read.
+    pub response: Value,
+}
+
+impl Fixture {
+    /// Compare a service's reply with what the SDK expects.
+    pub fn check(&self, status: u16, body: &Value) -> Result<(), ContractFailure> {
+        let mut mismatches = Vec::new();
+        if status == self.status {
+            compare("$", &self.response, body, &mut mismatches);
+        } else {
+            mismatches.push(Mismatch {
+                path: "status".to_string(),
+                expected: self.status.to_string(),
+                found: status.to_string(),
+            });
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(ContractFailure {
+                fixture: Box::new(self.clone()),
+                mismatches,
+            })
+        }
+    }
+}
+
+/// A place where a reply differs from the fixture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub path: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: expected {}, found {}", self.path, self.expected, self.found)
+    }
+}
+
+/// A fixture a service no longer honours, with every difference found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractFailure {
+    pub fixture: Box<Fixture>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl fmt::Display for ContractFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fixture = &self.fixture;
+        writeln!(
+            f,
+            "SDK {} `{}` ({} {} on {}) would break:",
+            fixture.sdk_version, fixture.name, fixture.method, fixture.path, fixture.service
+        )?;
+        for mismatch in &self.mismatches {
+            writeln!(f, "  {}", mismatch)?;
+        }
+        write!(f, "Change the service back, or keep serving what SDK {} reads.", fixture.sdk_version)
+    }
+}
+
+fn compare(path: &str, expected: &Value, actual: &Value, mismatches: &mut Vec<Mismatch>) {
+    match (expected, actual) {
+        (Value::Null, _) => {}
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected) in expected {
+                let path = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual) => compare(&path, expected, actual, mismatches),
+                    None if expected.is_null() => {}
+                    None => mismatches.push(Mismatch {
+                        path,
+                        expected: kind(expected).to_string(),
+                        found: "nothing".to_string(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            let Some(sample) = expected.first() else {
+                return;
+            };
+            // An empty reply would pass without checking anything
+            if actual.is_empty() {
+                mismatches.push(Mismatch {
+                    path: path.to_string(),
+                    expected: "some items".to_string(),
+                    found: "an empty array".to_string(),
+                });
+            }
+            for (i, actual) in actual.iter().enumerate() {
+                compare(&format!("{}[{}]", path, i), sample, actual, mismatches);
+            }
+        }
+        (expected, actual) if kind(expected) == kind(actual) => {}
+        (expected, actual) => mismatches.push(Mismatch {
+            path: path.to_string(),
+            expected: kind(expected).to_string(),
+            found: kind(actual).to_string(),
+        }),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// This SDK version's fixtures, built from its types.
+pub fn fixtures() -> Vec<Fixture> {
+    vec![
+        Fixture {
+            sdk_version: SDK_VERSION.to_string(),
+            name: "list-regions".to_string(),
+            service: "world-engine".to_string(),
+            method: "GET".to_string(),
+            path: REGIONS_PATH.to_string(),
+            authenticated: false,
+            request: None,
+            status: 200,
+            response: sample(vec![RegionSummary {
+                id: Uuid::nil(),
+                harmony_level: 0.5,
+                weather: WeatherSummary {
+                    weather_type: "Clear".to_string(),
+                    intensity: 0.5,
+                },
+            }]),
+        },
+        Fixture {
+            sdk_version: SDK_VERSION.to_string(),
+            name: "perform-melody".to_string(),
+            service: "song-engine".to_string(),
+            method: "POST".to_string(),
+            path: PERFORM_MELODY_PATH.to_string(),
+            authenticated: true,
+            request: Some(sample(PerformMelodyRequest {
+                melody: MelodyRequest {
+                    notes: vec![NoteRequest {
+                        frequency: 440.0,
+                        duration: 1.0,
+                        intensity: 1.0,
+                    }],
+                    tempo: 120.0,
+                    harmony_type: "creative".to_string(),
+                },
+                target_location: CoordinatesRequest { x: 0.0, y: 0.0, z: 0.0 },
+                dry_run: false,
+            })),
+            status: 200,
+            response: sample(PerformMelodyResponse {
+                success: true,
+                resonance_gained: 1.0,
+                harmony_impact: 1.0,
+                message: "The melody resonates".to_string(),
+                effects: Vec::new(),
+            }),
+        },
+    ]
+}
+
+fn sample(value: impl Serialize) -> Value {
+    serde_json::to_value(value).expect("SDK types serialize to JSON")
+}
+
+/// Where fixtures live, one directory per SDK version.
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// Read the fixtures saved in `dir`.
+pub fn load(dir: &Path) -> io::Result<Vec<Fixture>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "json"));
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            serde_json::from_slice(&fs::read(path)?)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))
+        })
+        .collect()
+}
+
+/// Save fixtures into `dir`, one file each.
+pub fn save(dir: &Path, fixtures: &[Fixture]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for fixture in fixtures {
+        let json = serde_json::to_string_pretty(fixture).map_err(io::Error::other)?;
+        fs::write(dir.join(format!("{}.json", fixture.name)), json + "\n")?;
+    }
+    Ok(())
+}
+
+/// The fixtures for `service` from the newest SDK and the release before
+/// it, which players may still be running.
+pub fn skew_matrix(service: &str) -> io::Result<Vec<Fixture>> {
+    let root = fixtures_dir();
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(&root)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            versions.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+
+    let mut fixtures = Vec::new();
+    for version in newest_two(versions) {
+        fixtures.extend(load(&root.join(version))?.into_iter().filter(|fixture| fixture.service == service));
+    }
+    Ok(fixtures)
+}
+
+fn newest_two(versions: Vec<String>) -> Vec<String> {
+    let mut versions: Vec<(Vec<u64>, String)> = versions
+        .into_iter()
+        .filter_map(|name| {
+            let parts = name.split('.').map(|part| part.parse().ok()).collect::<Option<Vec<u64>>>()?;
+            Some((parts, name))
+        })
+        .collect();
+    versions.sort_by(|a, b| b.0.cmp(&a.0));
+    versions.into_iter().take(2).map(|(_, name)| name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fixtures_for_this_version_are_up_to_date() {
+        let dir = fixtures_dir().join(SDK_VERSION);
+        if std::env::var_os(UPDATE_FIXTURES_ENV).is_some() {
+            save(&dir, &fixtures()).unwrap();
+        }
+
+        let saved = load(&dir).unwrap_or_default();
+        let mut generated = fixtures();
+        generated.sort_by(|a, b| a.name.cmp(&b.name));
+        assert!(
+            saved == generated,
+            "fixtures in {} differ from the SDK types; rerun with {}=1 to regenerate them",
+            dir.display(),
+            UPDATE_FIXTURES_ENV
+        );
+    }
+
+    #[test]
+    fn replies_need_every_field_the_sdk_reads() {
+        let fixture = Fixture {
+            response: json!({ "id": "r1", "weather": [{ "intensity": 0.5 }], "note": null }),
+            ..fixtures().remove(0)
+        };
+
+        assert!(fixture.check(200, &json!({ "id": "r2", "weather": [{ "intensity": 1 }], "extra": true })).is_ok());
+
+        let failure = fixture.check(200, &json!({ "weather": [{ "intensity": "high" }] })).unwrap_err();
+        assert_eq!(
+            failure.mismatches.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "$.id: expected a string, found nothing",
+                "$.weather[0].intensity: expected a number, found a string",
+            ]
+        );
+        assert!(failure.to_string().contains("`list-regions` (GET /regions on world-engine)"));
+
+        let failure = fixture.check(200, &json!({ "id": "r2", "weather": [] })).unwrap_err();
+        assert_eq!(failure.mismatches[0].to_string(), "$.weather: expected some items, found an empty array");
+
+        let failure = fixture.check(404, &Value::Null).unwrap_err();
+        assert_eq!(failure.mismatches[0].to_string(), "status: expected 200, found 404");
+    }
+
+    #[test]
+    fn the_skew_matrix_is_the_newest_two_versions() {
+        let versions = ["0.1.2", "0.1.10", "notes", "0.1.3"].map(String::from).to_vec();
+        assert_eq!(newest_two(versions), ["0.1.10", "0.1.3"]);
+    }
+}
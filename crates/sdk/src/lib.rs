@@ -0,0 +1,15 @@
+// crates/sdk/src/lib.rs
+//! Requests clients send to the services and the responses they read back.
+//!
+//! Clients build their calls from these types instead of ad hoc JSON. Each
+//! SDK version records golden fixtures of those calls under `fixtures/`,
+//! and services replay the current and previous version's fixtures in
+//! their tests with [`contract`], so a service change that would break a
+//! released client fails the test suite instead of the client.
+
+pub mod contract;
+pub mod song;
+pub mod world;
+
+/// The SDK version fixtures are generated for; it follows the workspace.
+pub const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,48 @@
+// crates/sdk/src/song.rs
+//! song-engine calls.
+
+use serde::{Deserialize, Serialize};
+
+/// Performs a melody as the player named by the bearer token.
+pub const PERFORM_MELODY_PATH: &str = "/api/melody/perform";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteRequest {
+    pub frequency: f32,
+    pub duration: f32,
+    pub intensity: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MelodyRequest {
+    pub notes: Vec<NoteRequest>,
+    pub tempo: f32,
+    /// creative, restoration, exploration or protection
+    pub harmony_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoordinatesRequest {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformMelodyRequest {
+    pub melody: MelodyRequest,
+    pub target_location: CoordinatesRequest,
+    /// Compute the outcome without applying it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// The parts of a performance clients show; the service may send more.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformMelodyResponse {
+    pub success: bool,
+    pub resonance_gained: f32,
+    pub harmony_impact: f32,
+    pub message: String,
+    pub effects: Vec<String>,
+}
@@ -0,0 +1,22 @@
+// crates/sdk/src/world.rs
+//! world-engine calls.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Lists every region as a bare array of [`RegionSummary`].
+pub const REGIONS_PATH: &str = "/regions";
+
+/// The parts of a region clients show; the service may send more.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionSummary {
+    pub id: Uuid,
+    pub harmony_level: f64,
+    pub weather: WeatherSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherSummary {
+    pub weather_type: String,
+    pub intensity: f64,
+}
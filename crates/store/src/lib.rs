@@ -0,0 +1,336 @@
+// crates/store/src/lib.rs
+//! Embedded key-value storage for services that need to keep a little state
+//! across restarts without running Postgres.
+//!
+//! A [`Store`] is one sled database on local disk, opened once per service.
+//! It's split into namespaced [`Tree`]s, each holding JSON values by key,
+//! in key order. Values can be given a time to live; expired ones read as
+//! missing and are removed by [`Store::purge_expired`] or lazily on read.
+//! Disk work runs on tokio's blocking pool, so the API is async.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Bytes before each stored value holding when it expires.
+const HEADER_LEN: usize = 8;
+
+/// Expiry stamp of values that never expire.
+const NEVER: u64 = 0;
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("Storage error: {0}")]
+    Backend(#[from] sled::Error),
+
+    #[error("Can't encode or decode a stored value: {0}")]
+    Codec(#[from] serde_json::Error),
+
+    #[error("Corrupt entry in '{0}'")]
+    Corrupt(String),
+
+    #[error("Storage task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+pub type Result<T> = std::result::Result<T, StoreError>;
+
+/// Run sled work off the async runtime.
+async fn blocking<R: Send + 'static>(work: impl FnOnce() -> Result<R> + Send + 'static) -> Result<R> {
+    tokio::task::spawn_blocking(work).await?
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_millis() as u64)
+}
+
+/// One service's database.
+#[derive(Clone)]
+pub struct Store {
+    db: sled::Db,
+}
+
+impl Store {
+    /// The database at `path`, created if missing.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        blocking(move || Ok(Self { db: sled::open(path)? })).await
+    }
+
+    /// A database that lives in memory and is gone when dropped, for tests.
+    pub async fn temporary() -> Result<Self> {
+        blocking(|| {
+            Ok(Self {
+                db: sled::Config::new().temporary(true).open()?,
+            })
+        })
+        .await
+    }
+
+    /// The tree for `namespace`, created if missing.
+    pub async fn tree(&self, namespace: &str) -> Result<Tree> {
+        let db = self.db.clone();
+        let name = namespace.to_string();
+        blocking(move || {
+            Ok(Tree {
+                tree: db.open_tree(&name)?,
+                db,
+                name,
+            })
+        })
+        .await
+    }
+
+    /// Remove expired values from every tree. Returns how many went.
+    pub async fn purge_expired(&self) -> Result<usize> {
+        let mut purged = 0;
+        for name in self.db.tree_names() {
+            let name = String::from_utf8_lossy(&name).into_owned();
+            purged += self.tree(&name).await?.purge_expired().await?;
+        }
+        Ok(purged)
+    }
+
+    /// Purge expired values every `interval` for as long as the service runs.
+    pub fn spawn_purge(&self, interval: Duration) -> JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = store.purge_expired().await {
+                    warn!("Failed to purge expired values: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Write everything to disk now rather than on sled's own schedule.
+    pub async fn flush(&self) -> Result<()> {
+        let db = self.db.clone();
+        blocking(move || {
+            db.flush()?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// JSON values by key within one namespace of a [`Store`].
+#[derive(Clone)]
+pub struct Tree {
+    db: sled::Db,
+    tree: sled::Tree,
+    name: String,
+}
+
+impl Tree {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A fresh id, increasing over the life of the store, for keying
+    /// values in the order they were added. Use `to_be_bytes` so keys sort
+    /// the same way.
+    pub async fn next_id(&self) -> Result<u64> {
+        let db = self.db.clone();
+        blocking(move || Ok(db.generate_id()?)).await
+    }
+
+    pub async fn get<T: DeserializeOwned + Send + 'static>(&self, key: impl AsRef<[u8]>) -> Result<Option<T>> {
+        let tree = self.clone();
+        let key = key.as_ref().to_vec();
+        blocking(move || {
+            let Some(raw) = tree.tree.get(&key)? else {
+                return Ok(None);
+            };
+            match tree.decode(&raw, now_millis())? {
+                Some(value) => Ok(Some(value)),
+                None => {
+                    tree.tree.remove(&key)?;
+                    Ok(None)
+                }
+            }
+        })
+        .await
+    }
+
+    pub async fn insert<T: Serialize>(&self, key: impl AsRef<[u8]>, value: &T) -> Result<()> {
+        self.put(key.as_ref().to_vec(), NEVER, value).await
+    }
+
+    /// Insert a value that reads as missing once `ttl` has passed.
+    pub async fn insert_with_ttl<T: Serialize>(&self, key: impl AsRef<[u8]>, value: &T, ttl: Duration) -> Result<()> {
+        let expires = now_millis().saturating_add(ttl.as_millis() as u64).max(1);
+        self.put(key.as_ref().to_vec(), expires, value).await
+    }
+
+    async fn put<T: Serialize>(&self, key: Vec<u8>, expires: u64, value: &T) -> Result<()> {
+        let mut raw = expires.to_be_bytes().to_vec();
+        serde_json::to_writer(&mut raw, value)?;
+        let tree = self.tree.clone();
+        blocking(move || {
+            tree.insert(key, raw)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove `key`, saying whether anything was there.
+    pub async fn remove(&self, key: impl AsRef<[u8]>) -> Result<bool> {
+        let tree = self.tree.clone();
+        let key = key.as_ref().to_vec();
+        blocking(move || Ok(tree.remove(key)?.is_some())).await
+    }
+
+    /// Every live value, in key order.
+    pub async fn values<T: DeserializeOwned + Send + 'static>(&self) -> Result<Vec<T>> {
+        let tree = self.clone();
+        blocking(move || {
+            let now = now_millis();
+            let mut values = Vec::new();
+            for item in tree.tree.iter() {
+                let (_, raw) = item?;
+                values.extend(tree.decode(&raw, now)?);
+            }
+            Ok(values)
+        })
+        .await
+    }
+
+    /// Up to `limit` live values with the highest keys, highest first.
+    pub async fn newest<T: DeserializeOwned + Send + 'static>(&self, limit: usize) -> Result<Vec<T>> {
+        let tree = self.clone();
+        blocking(move || {
+            let now = now_millis();
+            let mut values = Vec::new();
+            for item in tree.tree.iter().rev() {
+                if values.len() == limit {
+                    break;
+                }
+                let (_, raw) = item?;
+                values.extend(tree.decode(&raw, now)?);
+            }
+            Ok(values)
+        })
+        .await
+    }
+
+    /// How many live values there are.
+    pub async fn len(&self) -> Result<usize> {
+        let tree = self.tree.clone();
+        blocking(move || {
+            let now = now_millis();
+            let mut live = 0;
+            for item in tree.iter() {
+                let (_, raw) = item?;
+                if !expired(&raw, now) {
+                    live += 1;
+                }
+            }
+            Ok(live)
+        })
+        .await
+    }
+
+    pub async fn is_empty(&self) -> Result<bool> {
+        Ok(self.len().await? == 0)
+    }
+
+    /// Remove all but the `keep` values with the highest keys. Returns how
+    /// many went.
+    pub async fn retain_newest(&self, keep: usize) -> Result<usize> {
+        let tree = self.tree.clone();
+        blocking(move || {
+            let mut removed = 0;
+            while tree.len() > keep {
+                if tree.pop_min()?.is_none() {
+                    break;
+                }
+                removed += 1;
+            }
+            Ok(removed)
+        })
+        .await
+    }
+
+    /// Remove every expired value. Returns how many went.
+    pub async fn purge_expired(&self) -> Result<usize> {
+        let tree = self.tree.clone();
+        blocking(move || {
+            let now = now_millis();
+            let mut purged = 0;
+            for item in tree.iter() {
+                let (key, raw) = item?;
+                if expired(&raw, now) && tree.compare_and_swap(&key, Some(raw), None::<&[u8]>)?.is_ok() {
+                    purged += 1;
+                }
+            }
+            Ok(purged)
+        })
+        .await
+    }
+
+    /// The value in `raw`, or `None` if it has expired.
+    fn decode<T: DeserializeOwned>(&self, raw: &[u8], now: u64) -> Result<Option<T>> {
+        if raw.len() < HEADER_LEN {
+            return Err(StoreError::Corrupt(self.name.clone()));
+        }
+        if expired(raw, now) {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&raw[HEADER_LEN..])?))
+    }
+}
+
+fn expired(raw: &[u8], now: u64) -> bool {
+    let Some(header) = raw.get(..HEADER_LEN) else {
+        return false;
+    };
+    let expires = u64::from_be_bytes(header.try_into().expect("header is eight bytes"));
+    expires != NEVER && expires <= now
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn trees_keep_values_apart_until_they_expire() {
+        let store = Store::temporary().await.unwrap();
+        let bonds = store.tree("bonds").await.unwrap();
+        let history = store.tree("history").await.unwrap();
+
+        bonds.insert("lumi", &0.5f32).await.unwrap();
+        history.insert("lumi", &"hello".to_string()).await.unwrap();
+        history.insert_with_ttl("gone", &"bye".to_string(), Duration::ZERO).await.unwrap();
+        assert_eq!(bonds.get::<f32>("lumi").await.unwrap(), Some(0.5));
+        assert_eq!(history.get::<String>("lumi").await.unwrap().as_deref(), Some("hello"));
+        assert_eq!(history.len().await.unwrap(), 1);
+        assert_eq!(history.get::<String>("gone").await.unwrap(), None);
+
+        history.insert_with_ttl("later", &"soon".to_string(), Duration::ZERO).await.unwrap();
+        assert_eq!(store.purge_expired().await.unwrap(), 1);
+        assert!(bonds.remove("lumi").await.unwrap());
+        assert!(bonds.is_empty().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn ids_keep_values_in_the_order_they_were_added() {
+        let store = Store::temporary().await.unwrap();
+        let log = store.tree("log").await.unwrap();
+        for n in 0..5u32 {
+            let id = log.next_id().await.unwrap();
+            log.insert(id.to_be_bytes(), &n).await.unwrap();
+        }
+
+        assert_eq!(log.values::<u32>().await.unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(log.newest::<u32>(2).await.unwrap(), vec![4, 3]);
+        assert_eq!(log.retain_newest(3).await.unwrap(), 2);
+        assert_eq!(log.values::<u32>().await.unwrap(), vec![2, 3, 4]);
+    }
+}
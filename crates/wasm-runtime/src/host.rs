@@ -0,0 +1,186 @@
+// crates/wasm-runtime/src/host.rs
+//! Host functions imported by plugins from the `finalverse` module.
+//!
+//! Strings and payloads are passed as a pointer and length into the
+//! plugin's memory. Values returned to the plugin are written into memory
+//! it allocates through its exported `alloc(len) -> ptr`, and come back as
+//! an `i64` with the pointer in the high 32 bits and the length in the low
+//! 32 bits. Status results are `0` on success and `-1` on failure.
+//!
+//! | import | signature |
+//! |---|---|
+//! | `log` | `(msg_ptr, msg_len)` |
+//! | `register_route` | `(method_ptr, method_len, path_ptr, path_len, handler) -> status` |
+//! | `publish_event` | `(topic_ptr, topic_len, payload_ptr, payload_len) -> status` |
+//! | `kv_get` | `(key_ptr, key_len) -> packed`, `-1` if the key is unset |
+//! | `kv_set` | `(key_ptr, key_len, value_ptr, value_len) -> status` |
+//! | `kv_delete` | `(key_ptr, key_len) -> status`, `-1` if the key was unset |
+
+use anyhow::{Context, Result};
+use wasmtime::{Caller, Linker, Memory, StoreLimits};
+
+use crate::storage::KvStore;
+
+/// Largest string or payload moved between host and plugin in one call.
+pub const MAX_GUEST_BYTES: usize = 1024 * 1024;
+/// Routes and events a plugin can hold before further ones are refused.
+const MAX_ROUTES: usize = 64;
+const MAX_PENDING_EVENTS: usize = 1024;
+
+/// An HTTP route a plugin registered, dispatched to its `handle_http`
+/// export with `handler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub method: String,
+    pub path: String,
+    pub handler: i32,
+}
+
+/// An event a plugin published, waiting to be sent to the event bus.
+#[derive(Debug, Clone)]
+pub struct OutboundEvent {
+    pub topic: String,
+    pub payload: Vec<u8>,
+}
+
+/// What the host functions of one plugin instance work on.
+pub struct HostState {
+    pub(crate) routes: Vec<Route>,
+    pub(crate) outbox: Vec<OutboundEvent>,
+    pub(crate) storage: KvStore,
+    pub(crate) limits: StoreLimits,
+}
+
+impl HostState {
+    pub fn new(storage: KvStore) -> Self {
+        Self {
+            routes: Vec::new(),
+            outbox: Vec::new(),
+            storage,
+            limits: StoreLimits::default(),
+        }
+    }
+}
+
+pub(crate) fn pack(ptr: i32, len: usize) -> i64 {
+    ((ptr as u32 as i64) << 32) | len as u32 as i64
+}
+
+pub(crate) fn unpack(packed: i64) -> (usize, usize) {
+    ((packed as u64 >> 32) as usize, packed as u32 as usize)
+}
+
+fn memory(caller: &mut Caller<'_, HostState>) -> Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+        .context("plugin does not export `memory`")
+}
+
+fn read_bytes(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Result<Vec<u8>> {
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| *len <= MAX_GUEST_BYTES)
+        .context("plugin passed an invalid length")?;
+    let memory = memory(caller)?;
+    let mut buf = vec![0u8; len];
+    memory.read(&mut *caller, ptr as u32 as usize, &mut buf)?;
+    Ok(buf)
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Result<String> {
+    String::from_utf8(read_bytes(caller, ptr, len)?).context("plugin passed a string that isn't UTF-8")
+}
+
+/// Copy `bytes` into memory allocated by the plugin.
+fn write_bytes(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> Result<i64> {
+    let alloc = caller
+        .get_export("alloc")
+        .and_then(|export| export.into_func())
+        .context("plugin does not export `alloc`")?
+        .typed::<i32, i32>(&mut *caller)?;
+    let ptr = alloc.call(&mut *caller, bytes.len() as i32)?;
+    let memory = memory(caller)?;
+    memory.write(&mut *caller, ptr as u32 as usize, bytes)?;
+    Ok(pack(ptr, bytes.len()))
+}
+
+fn status(ok: bool) -> i32 {
+    if ok {
+        0
+    } else {
+        -1
+    }
+}
+
+/// Define the `finalverse` host functions on `linker`.
+pub fn add_to_linker(linker: &mut Linker<HostState>) -> Result<()> {
+    linker.func_wrap("finalverse", "log", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<()> {
+        println!("[wasm] {}", read_string(&mut caller, ptr, len)?);
+        Ok(())
+    })?;
+
+    linker.func_wrap(
+        "finalverse",
+        "register_route",
+        |mut caller: Caller<'_, HostState>, method_ptr: i32, method_len: i32, path_ptr: i32, path_len: i32, handler: i32| -> Result<i32> {
+            let method = read_string(&mut caller, method_ptr, method_len)?.to_ascii_uppercase();
+            let path = read_string(&mut caller, path_ptr, path_len)?;
+            let routes = &mut caller.data_mut().routes;
+            if !path.starts_with('/') || routes.len() >= MAX_ROUTES {
+                return Ok(status(false));
+            }
+            routes.retain(|route| route.method != method || route.path != path);
+            routes.push(Route { method, path, handler });
+            Ok(status(true))
+        },
+    )?;
+
+    linker.func_wrap(
+        "finalverse",
+        "publish_event",
+        |mut caller: Caller<'_, HostState>, topic_ptr: i32, topic_len: i32, payload_ptr: i32, payload_len: i32| -> Result<i32> {
+            let topic = read_string(&mut caller, topic_ptr, topic_len)?;
+            let payload = read_bytes(&mut caller, payload_ptr, payload_len)?;
+            let outbox = &mut caller.data_mut().outbox;
+            if topic.is_empty() || outbox.len() >= MAX_PENDING_EVENTS {
+                return Ok(status(false));
+            }
+            outbox.push(OutboundEvent { topic, payload });
+            Ok(status(true))
+        },
+    )?;
+
+    linker.func_wrap(
+        "finalverse",
+        "kv_get",
+        |mut caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32| -> Result<i64> {
+            let key = read_string(&mut caller, key_ptr, key_len)?;
+            match caller.data().storage.get(&key) {
+                Some(value) => write_bytes(&mut caller, &value),
+                None => Ok(-1),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "finalverse",
+        "kv_set",
+        |mut caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32, value_ptr: i32, value_len: i32| -> Result<i32> {
+            let key = read_string(&mut caller, key_ptr, key_len)?;
+            let value = read_bytes(&mut caller, value_ptr, value_len)?;
+            Ok(status(caller.data().storage.set(key, value)))
+        },
+    )?;
+
+    linker.func_wrap(
+        "finalverse",
+        "kv_delete",
+        |mut caller: Caller<'_, HostState>, key_ptr: i32, key_len: i32| -> Result<i32> {
+            let key = read_string(&mut caller, key_ptr, key_len)?;
+            Ok(status(caller.data().storage.delete(&key)))
+        },
+    )?;
+
+    Ok(())
+}
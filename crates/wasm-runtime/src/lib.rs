@@ -1,8 +1,24 @@
 // crates/wasm-runtime/src/lib.rs
 // Runtime for loading and executing Wasm plugins safely
+use std::collections::BTreeMap;
 use std::path::Path;
-use anyhow::{Context, Result};
-use wasmtime::{Engine, Func, Instance, Linker, Module, Store, Caller, Memory};
+use std::sync::{Arc, Mutex};
+use anyhow::{bail, Context, Result};
+use axum::extract::Request;
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router as AxumRouter;
+use finalverse_events::GameEventBus;
+use serde::{Deserialize, Serialize};
+use wasmtime::{Func, Instance, Linker, Module, Store, Caller};
+
+pub mod host;
+pub mod sandbox;
+pub mod storage;
+
+pub use host::{HostState, OutboundEvent, Route, MAX_GUEST_BYTES};
+pub use sandbox::SandboxLimits;
+pub use storage::KvStore;
 
 /// Context passed to Wasm plugins on events
 #[repr(C)]
@@ -13,24 +29,89 @@ pub struct EventContext {
     pub payload_len: usize,
 }
 
+/// Request passed, as JSON, to a plugin's `handle_http` export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub body: String,
+}
+
+/// Response a plugin's `handle_http` export returns as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl HttpResponse {
+    fn not_found() -> Self {
+        Self {
+            status: 404,
+            headers: BTreeMap::new(),
+            body: String::new(),
+        }
+    }
+}
+
+impl IntoResponse for HttpResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, self.body).into_response();
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                response.headers_mut().insert(name, value);
+            }
+        }
+        response
+    }
+}
+
+/// A loaded plugin. Besides `on_event`, plugins may export `init()`, called
+/// once after loading to register routes, and
+/// `handle_http(handler, request_ptr, request_len) -> response` to serve
+/// them; see [`host`] for the functions they can import. Every call runs
+/// under the plugin's [`SandboxLimits`].
 pub struct WasmPlugin {
     instance: Instance,
-    store: Store<()>,
+    store: Store<HostState>,
     call_on_event: Func,
+    limits: SandboxLimits,
 }
 
 impl WasmPlugin {
     /// Load a Wasm module from the given path and prepare it for execution
     pub fn load(path: &Path) -> Result<Self> {
-        let engine = Engine::default();
-        let module = Module::from_file(&engine, path)
+        Self::load_with(path, KvStore::new(), SandboxLimits::default())
+    }
+
+    /// Load a module whose key-value storage is `storage`.
+    pub fn load_with(path: &Path, storage: KvStore, limits: SandboxLimits) -> Result<Self> {
+        let bytes = std::fs::read(path)
             .with_context(|| format!("Failed to load module at {:?}", path))?;
+        Self::from_module(&bytes, storage, limits)
+    }
+
+    /// Prepare a module from its binary or text form.
+    pub fn from_module(bytes: &[u8], storage: KvStore, limits: SandboxLimits) -> Result<Self> {
+        let engine = sandbox::engine();
+        let module = Module::new(engine, bytes).context("Failed to compile module")?;
 
-        let mut store = Store::new(&engine, ());
-        let mut linker = Linker::new(&engine);
+        let state = HostState {
+            limits: limits.store_limits(),
+            ..HostState::new(storage)
+        };
+        let mut store = Store::new(engine, state);
+        store.limiter(|state| &mut state.limits);
+        let mut linker = Linker::new(engine);
+        host::add_to_linker(&mut linker)?;
 
         // Basic host functions for plugins
-        linker.func_wrap("env", "log", |mut caller: Caller<'_, ()>, ptr: i32, len: i32| {
+        linker.func_wrap("env", "log", |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
             if let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) {
                 let mut buf = vec![0u8; len as usize];
                 if memory.read(&mut caller, ptr as usize, &mut buf).is_ok() {
@@ -41,7 +122,7 @@ impl WasmPlugin {
             }
         })?;
 
-        linker.func_wrap("env", "read_u8", |mut caller: Caller<'_, ()>, ptr: i32| -> i32 {
+        linker.func_wrap("env", "read_u8", |mut caller: Caller<'_, HostState>, ptr: i32| -> i32 {
             if let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) {
                 let mut byte = [0u8];
                 if memory.read(&mut caller, ptr as usize, &mut byte).is_ok() {
@@ -51,30 +132,155 @@ impl WasmPlugin {
             0
         })?;
 
-        linker.func_wrap("env", "write_u8", |mut caller: Caller<'_, ()>, ptr: i32, val: i32| {
+        linker.func_wrap("env", "write_u8", |mut caller: Caller<'_, HostState>, ptr: i32, val: i32| {
             if let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) {
                 let _ = memory.write(&mut caller, ptr as usize, &[val as u8]);
             }
         })?;
 
-        let instance = linker.instantiate(&mut store, &module)?;
+        limits.arm(&mut store)?;
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(sandbox::explain)?;
         let call_on_event = instance
             .get_func(&mut store, "on_event")
             .context("Missing `on_event` function")?;
 
+        if let Some(init) = instance.get_func(&mut store, "init") {
+            let init = init.typed::<(), ()>(&store)?;
+            limits.arm(&mut store)?;
+            init.call(&mut store, ())
+                .map_err(sandbox::explain)
+                .context("Failed to invoke init")?;
+        }
+
         Ok(Self {
             instance,
             store,
             call_on_event,
+            limits,
         })
     }
 
     /// Invoke the plugin's `on_event` function with the given `EventContext`
     pub fn call_on_event(&mut self, ctx: &EventContext) -> Result<()> {
         let ptr = ctx as *const EventContext as i64;
+        self.limits.arm(&mut self.store)?;
         self.call_on_event
             .call(&mut self.store, &[ptr.into()], &mut [])
+            .map_err(sandbox::explain)
             .context("Failed to invoke on_event")?;
         Ok(())
     }
+
+    /// Routes the plugin registered.
+    pub fn routes(&self) -> &[Route] {
+        &self.store.data().routes
+    }
+
+    pub fn storage(&self) -> &KvStore {
+        &self.store.data().storage
+    }
+
+    /// Serve a request with the handler registered for its method and
+    /// path, or 404 if there is none.
+    pub fn handle_http(&mut self, request: &HttpRequest) -> Result<HttpResponse> {
+        let Some(handler) = self
+            .routes()
+            .iter()
+            .find(|route| route.method.eq_ignore_ascii_case(&request.method) && route.path == request.path)
+            .map(|route| route.handler)
+        else {
+            return Ok(HttpResponse::not_found());
+        };
+
+        let request = serde_json::to_vec(request)?;
+        if request.len() > MAX_GUEST_BYTES {
+            bail!("Request of {} bytes is too large for the plugin", request.len());
+        }
+        let alloc = self
+            .instance
+            .get_typed_func::<i32, i32>(&mut self.store, "alloc")
+            .context("Missing `alloc` function")?;
+        let handle_http = self
+            .instance
+            .get_typed_func::<(i32, i32, i32), i64>(&mut self.store, "handle_http")
+            .context("Missing `handle_http` function")?;
+        let memory = self
+            .instance
+            .get_memory(&mut self.store, "memory")
+            .context("Missing `memory` export")?;
+
+        // The allocation and the handler share one budget
+        self.limits.arm(&mut self.store)?;
+        let request_ptr = alloc
+            .call(&mut self.store, request.len() as i32)
+            .map_err(sandbox::explain)?;
+        memory.write(&mut self.store, request_ptr as u32 as usize, &request)?;
+        let packed = handle_http
+            .call(&mut self.store, (handler, request_ptr, request.len() as i32))
+            .map_err(sandbox::explain)
+            .context("Failed to invoke handle_http")?;
+
+        let (response_ptr, response_len) = host::unpack(packed);
+        if response_len > MAX_GUEST_BYTES {
+            bail!("Plugin returned a {} byte response", response_len);
+        }
+        let mut response = vec![0u8; response_len];
+        memory.read(&self.store, response_ptr, &mut response)?;
+        serde_json::from_slice(&response).context("Plugin returned an invalid response")
+    }
+
+    /// Events the plugin published since the last call.
+    pub fn take_events(&mut self) -> Vec<OutboundEvent> {
+        std::mem::take(&mut self.store.data_mut().outbox)
+    }
+
+    /// Send the events the plugin published to `bus`. Returns how many.
+    pub async fn publish_events(&mut self, bus: &dyn GameEventBus) -> Result<usize> {
+        let events = self.take_events();
+        for event in &events {
+            bus.publish_raw(&event.topic, event.payload.clone()).await?;
+        }
+        Ok(events.len())
+    }
+
+    /// Serve the plugin's routes, like a native plugin's `routes()`. Events
+    /// it publishes while handling requests go to `bus`, if given.
+    pub fn into_router(self, bus: Option<Arc<dyn GameEventBus>>) -> AxumRouter {
+        let plugin = Arc::new(Mutex::new(self));
+        AxumRouter::new().fallback(move |request: Request| {
+            let plugin = plugin.clone();
+            let bus = bus.clone();
+            async move {
+                let (parts, body) = request.into_parts();
+                let Ok(body) = axum::body::to_bytes(body, MAX_GUEST_BYTES).await else {
+                    return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+                };
+                let request = HttpRequest {
+                    method: parts.method.to_string(),
+                    path: parts.uri.path().to_string(),
+                    query: parts.uri.query().map(str::to_string),
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                };
+
+                let (response, events) = {
+                    let mut plugin = plugin.lock().unwrap();
+                    (plugin.handle_http(&request), plugin.take_events())
+                };
+                if let Some(bus) = &bus {
+                    for event in events {
+                        if let Err(e) = bus.publish_raw(&event.topic, event.payload).await {
+                            eprintln!("[wasm] failed to publish {}: {}", event.topic, e);
+                        }
+                    }
+                }
+
+                match response {
+                    Ok(response) => response.into_response(),
+                    Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+                }
+            }
+        })
+    }
 }
@@ -0,0 +1,101 @@
+// crates/wasm-runtime/src/sandbox.rs
+//! Resource limits for plugins. Every call into a plugin gets a fresh fuel
+//! budget and deadline, so a plugin stuck in a loop traps instead of
+//! stalling the caller, and its memory can't grow past its limit.
+//!
+//! All plugins share one engine, whose epoch is advanced by a background
+//! thread every [`EPOCH_TICK`]; deadlines are measured in those ticks.
+
+use anyhow::Result;
+use std::sync::OnceLock;
+use std::time::Duration;
+use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+use crate::host::HostState;
+
+/// Resolution of plugin timeouts.
+pub const EPOCH_TICK: Duration = Duration::from_millis(5);
+
+/// Limits applied to one plugin.
+#[derive(Debug, Clone, Copy)]
+pub struct SandboxLimits {
+    /// Fuel, roughly Wasm instructions, available to each call.
+    pub fuel_per_call: u64,
+    /// Largest a plugin's linear memory may grow to.
+    pub max_memory_bytes: usize,
+    /// Longest a single call may run.
+    pub timeout: Duration,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self {
+            fuel_per_call: 50_000_000,
+            max_memory_bytes: 64 * 1024 * 1024,
+            timeout: Duration::from_millis(50),
+        }
+    }
+}
+
+impl SandboxLimits {
+    pub fn with_fuel_per_call(self, fuel_per_call: u64) -> Self {
+        Self { fuel_per_call, ..self }
+    }
+
+    pub fn with_max_memory_bytes(self, max_memory_bytes: usize) -> Self {
+        Self { max_memory_bytes, ..self }
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub(crate) fn store_limits(&self) -> StoreLimits {
+        StoreLimitsBuilder::new()
+            .memory_size(self.max_memory_bytes)
+            .instances(1)
+            .build()
+    }
+
+    /// Give the store this call's fuel and deadline.
+    pub(crate) fn arm(&self, store: &mut Store<HostState>) -> Result<()> {
+        let remaining = store.consume_fuel(0)?;
+        if remaining < self.fuel_per_call {
+            store.add_fuel(self.fuel_per_call - remaining)?;
+        } else {
+            store.consume_fuel(remaining - self.fuel_per_call)?;
+        }
+        let ticks = self.timeout.as_micros().div_ceil(EPOCH_TICK.as_micros()).max(1);
+        store.set_epoch_deadline(ticks as u64);
+        Ok(())
+    }
+}
+
+/// The engine all plugins run on.
+pub(crate) fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config.consume_fuel(true).epoch_interruption(true);
+        let engine = Engine::new(&config).expect("sandbox engine configuration is valid");
+
+        let ticking = engine.clone();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                ticking.increment_epoch();
+            })
+            .expect("failed to start the Wasm epoch thread");
+        engine
+    })
+}
+
+/// Say which limit a failed call ran into, if any.
+pub(crate) fn explain(error: anyhow::Error) -> anyhow::Error {
+    match error.downcast_ref::<Trap>() {
+        Some(Trap::OutOfFuel) => error.context("Plugin exceeded its fuel budget"),
+        Some(Trap::Interrupt) => error.context("Plugin exceeded its time limit"),
+        _ => error,
+    }
+}
@@ -0,0 +1,46 @@
+// crates/wasm-runtime/src/storage.rs
+// Key-value storage exposed to Wasm plugins
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Largest value a plugin may store under one key.
+pub const MAX_VALUE_BYTES: usize = 64 * 1024;
+
+/// A plugin's key-value store. Clones share the same entries, so a store
+/// kept by the host outlives reloads of the plugin that writes to it.
+#[derive(Clone, Default)]
+pub struct KvStore {
+    entries: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl KvStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.read().unwrap().get(key).cloned()
+    }
+
+    /// Store `value` under `key`. Returns `false` if the value is too large.
+    pub fn set(&self, key: impl Into<String>, value: Vec<u8>) -> bool {
+        if value.len() > MAX_VALUE_BYTES {
+            return false;
+        }
+        self.entries.write().unwrap().insert(key.into(), value);
+        true
+    }
+
+    /// Returns `true` if the key was present.
+    pub fn delete(&self, key: &str) -> bool {
+        self.entries.write().unwrap().remove(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
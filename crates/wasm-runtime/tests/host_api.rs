@@ -0,0 +1,59 @@
+use finalverse_wasm_runtime::{HttpRequest, KvStore, Route, SandboxLimits, WasmPlugin};
+
+/// Counts visits to `GET /visits`, announcing each one.
+const VISIT_COUNTER: &str = r#"
+(module
+  (import "finalverse" "register_route" (func $register_route (param i32 i32 i32 i32 i32) (result i32)))
+  (import "finalverse" "publish_event" (func $publish_event (param i32 i32 i32 i32) (result i32)))
+  (import "finalverse" "kv_set" (func $kv_set (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (data (i32.const 0) "GET")
+  (data (i32.const 16) "/visits")
+  (data (i32.const 32) "visits")
+  (data (i32.const 48) "1")
+  (data (i32.const 64) "plugin.visited")
+  (data (i32.const 96) "{\"status\":200,\"body\":\"counted\"}")
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+    (local.get $ptr))
+  (func (export "init")
+    (drop (call $register_route (i32.const 0) (i32.const 3) (i32.const 16) (i32.const 7) (i32.const 1))))
+  (func (export "handle_http") (param $handler i32) (param $ptr i32) (param $len i32) (result i64)
+    (drop (call $kv_set (i32.const 32) (i32.const 6) (i32.const 48) (i32.const 1)))
+    (drop (call $publish_event (i32.const 64) (i32.const 14) (local.get $ptr) (local.get $len)))
+    (i64.or (i64.shl (i64.const 96) (i64.const 32)) (i64.const 31)))
+  (func (export "on_event") (param i64)))
+"#;
+
+#[test]
+fn plugins_serve_routes_store_values_and_publish_events() -> anyhow::Result<()> {
+    let storage = KvStore::new();
+    let mut plugin = WasmPlugin::from_module(VISIT_COUNTER.as_bytes(), storage.clone(), SandboxLimits::default())?;
+    assert_eq!(
+        plugin.routes(),
+        &[Route { method: "GET".to_string(), path: "/visits".to_string(), handler: 1 }]
+    );
+
+    let request = |method: &str, path: &str| HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: None,
+        body: String::new(),
+    };
+    let response = plugin.handle_http(&request("GET", "/visits"))?;
+    assert_eq!((response.status, response.body.as_str()), (200, "counted"));
+    assert_eq!(storage.get("visits"), Some(b"1".to_vec()));
+
+    let events = plugin.take_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].topic, "plugin.visited");
+    let published: HttpRequest = serde_json::from_slice(&events[0].payload)?;
+    assert_eq!(published.path, "/visits");
+
+    assert_eq!(plugin.handle_http(&request("POST", "/visits"))?.status, 404);
+    assert!(plugin.take_events().is_empty());
+    Ok(())
+}
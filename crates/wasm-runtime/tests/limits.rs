@@ -0,0 +1,55 @@
+use finalverse_wasm_runtime::{EventContext, KvStore, SandboxLimits, WasmPlugin};
+use std::time::{Duration, Instant};
+
+/// Spins forever on every event.
+const SPINNER: &str = r#"
+(module
+  (func (export "on_event") (param i64)
+    (loop $spin (br $spin))))
+"#;
+
+/// Tries to grow its memory to 6.5 MiB at start-up and traps on events
+/// if that worked.
+const GROWER: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $grew (mut i32) (i32.const 0))
+  (func (export "init")
+    (global.set $grew (memory.grow (i32.const 100))))
+  (func (export "on_event") (param i64)
+    (if (i32.ne (global.get $grew) (i32.const -1))
+      (then unreachable))))
+"#;
+
+fn event() -> EventContext {
+    EventContext {
+        entity_id: 1,
+        event_type: 0,
+        payload_ptr: std::ptr::null(),
+        payload_len: 0,
+    }
+}
+
+#[test]
+fn runaway_plugins_are_stopped_by_their_limits() -> anyhow::Result<()> {
+    let limits = SandboxLimits::default().with_fuel_per_call(100_000);
+    let mut spinner = WasmPlugin::from_module(SPINNER.as_bytes(), KvStore::new(), limits)?;
+    let error = spinner.call_on_event(&event()).unwrap_err();
+    assert!(format!("{:#}", error).contains("fuel budget"));
+    // Each call gets a fresh budget rather than inheriting an empty one
+    assert!(format!("{:#}", spinner.call_on_event(&event()).unwrap_err()).contains("fuel budget"));
+
+    let limits = SandboxLimits::default()
+        .with_fuel_per_call(1 << 40)
+        .with_timeout(Duration::from_millis(20));
+    let mut spinner = WasmPlugin::from_module(SPINNER.as_bytes(), KvStore::new(), limits)?;
+    let started = Instant::now();
+    let error = spinner.call_on_event(&event()).unwrap_err();
+    assert!(format!("{:#}", error).contains("time limit"));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    let limits = SandboxLimits::default().with_max_memory_bytes(1024 * 1024);
+    let mut grower = WasmPlugin::from_module(GROWER.as_bytes(), KvStore::new(), limits)?;
+    grower.call_on_event(&event())?;
+    Ok(())
+}
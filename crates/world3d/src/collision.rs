@@ -0,0 +1,485 @@
+// crates/world3d/src/collision.rs
+//! Server-side collision: the ground as a heightfield plus box and
+//! upright capsule colliders for entities. Movement is checked by
+//! sweeping the mover's capsule, and spawns look for a spot that is on
+//! the ground and clear of every collider. Heights are along `z`.
+
+use crate::terrain::{TerrainPatch, GRID_SIZE};
+use crate::{EntityId, GridCoordinate, Position3D};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Distance stepped along a ray between terrain samples.
+const TERRAIN_STEP: f32 = 0.5;
+/// Sweeps stop this far short of contact, so the mover isn't left
+/// touching the collider and free to pass through it on the next move.
+const SKIN: f32 = 1e-3;
+/// Rings of candidate spots tried around a blocked spawn position.
+const PLACEMENT_RINGS: usize = 8;
+
+/// Anything that can say how high the ground is.
+pub trait Ground: Send + Sync {
+    fn height_at(&self, x: f32, y: f32) -> f32;
+}
+
+impl<F: Fn(f32, f32) -> f32 + Send + Sync> Ground for F {
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        self(x, y)
+    }
+}
+
+/// Static terrain sampled on a regular grid, interpolated between samples
+/// and clamped at the edges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Heightfield {
+    pub origin_x: f32,
+    pub origin_y: f32,
+    /// World units between samples.
+    pub spacing: f32,
+    /// Samples per side.
+    pub resolution: usize,
+    /// Row-major, `resolution * resolution` heights.
+    pub heights: Vec<f32>,
+}
+
+impl Heightfield {
+    /// The heightfield of a generated grid.
+    pub fn from_patch(grid: GridCoordinate, patch: &TerrainPatch) -> Self {
+        let resolution = patch.heightmap.len();
+        Self {
+            origin_x: grid.x as f32 * GRID_SIZE,
+            origin_y: grid.y as f32 * GRID_SIZE,
+            spacing: GRID_SIZE / resolution.max(1) as f32,
+            resolution,
+            heights: patch.heightmap.iter().flatten().copied().collect(),
+        }
+    }
+
+    fn sample(&self, col: usize, row: usize) -> f32 {
+        self.heights[row * self.resolution + col]
+    }
+}
+
+impl Ground for Heightfield {
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        if self.resolution == 0 {
+            return 0.0;
+        }
+        let last = (self.resolution - 1) as f32;
+        let fx = ((x - self.origin_x) / self.spacing).clamp(0.0, last);
+        let fy = ((y - self.origin_y) / self.spacing).clamp(0.0, last);
+        let (col, row) = (fx.floor() as usize, fy.floor() as usize);
+        let (next_col, next_row) = ((col + 1).min(self.resolution - 1), (row + 1).min(self.resolution - 1));
+        let (tx, ty) = (fx - col as f32, fy - row as f32);
+        let top = self.sample(col, row) * (1.0 - tx) + self.sample(next_col, row) * tx;
+        let bottom = self.sample(col, next_row) * (1.0 - tx) + self.sample(next_col, next_row) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Collider {
+    Aabb { min: Position3D, max: Position3D },
+    /// Upright, standing on `base`; `height` includes both rounded ends.
+    Capsule { base: Position3D, radius: f32, height: f32 },
+}
+
+impl Collider {
+    pub fn capsule(base: Position3D, radius: f32, height: f32) -> Self {
+        Collider::Capsule { base, radius, height: height.max(2.0 * radius) }
+    }
+
+    /// A box `width` by `depth` by `height` standing on `base`.
+    pub fn cuboid(base: Position3D, width: f32, depth: f32, height: f32) -> Self {
+        Collider::Aabb {
+            min: Position3D::new(base.x - width / 2.0, base.y - depth / 2.0, base.z),
+            max: Position3D::new(base.x + width / 2.0, base.y + depth / 2.0, base.z + height),
+        }
+    }
+
+    /// The point the collider stands on.
+    pub fn base(&self) -> Position3D {
+        match *self {
+            Collider::Aabb { min, max } => Position3D::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0, min.z),
+            Collider::Capsule { base, .. } => base,
+        }
+    }
+
+    /// The same collider standing on `base` instead.
+    pub fn with_base(&self, base: Position3D) -> Self {
+        let current = self.base();
+        let (dx, dy, dz) = (base.x - current.x, base.y - current.y, base.z - current.z);
+        match *self {
+            Collider::Aabb { min, max } => Collider::Aabb {
+                min: Position3D::new(min.x + dx, min.y + dy, min.z + dz),
+                max: Position3D::new(max.x + dx, max.y + dy, max.z + dz),
+            },
+            Collider::Capsule { radius, height, .. } => Collider::Capsule { base, radius, height },
+        }
+    }
+
+    /// A capsule that fits around this collider, for finding it room.
+    pub fn shape(&self) -> Shape {
+        match *self {
+            Collider::Aabb { min, max } => {
+                let radius = (max.x - min.x).hypot(max.y - min.y) / 2.0;
+                Shape::capsule(radius, max.z - min.z)
+            }
+            Collider::Capsule { radius, height, .. } => Shape::capsule(radius, height),
+        }
+    }
+
+    /// Where `shape`'s base point can't go without overlapping this
+    /// collider. Box corners are treated as square rather than rounded,
+    /// which errs on the side of a collision.
+    fn expanded(&self, shape: Shape) -> Volume {
+        let (low, high) = shape.segment();
+        match *self {
+            Collider::Aabb { min, max } => Volume::Aabb {
+                min: Position3D::new(min.x - shape.radius, min.y - shape.radius, min.z - shape.height),
+                max: Position3D::new(max.x + shape.radius, max.y + shape.radius, max.z),
+            },
+            Collider::Capsule { base, radius, height } => {
+                let (bottom, top) = Shape::capsule(radius, height).segment();
+                Volume::Capsule {
+                    x: base.x,
+                    y: base.y,
+                    z0: base.z + bottom - high,
+                    z1: base.z + top - low,
+                    radius: radius + shape.radius,
+                }
+            }
+        }
+    }
+}
+
+/// The size of something moving or being placed: an upright capsule.
+/// [`Shape::POINT`] makes queries test the colliders themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shape {
+    pub radius: f32,
+    pub height: f32,
+}
+
+impl Shape {
+    pub const POINT: Shape = Shape { radius: 0.0, height: 0.0 };
+
+    pub fn capsule(radius: f32, height: f32) -> Self {
+        Self { radius, height: height.max(2.0 * radius) }
+    }
+
+    /// Heights above the base of the capsule's core segment.
+    fn segment(&self) -> (f32, f32) {
+        (self.radius, self.height - self.radius)
+    }
+}
+
+/// A collider grown by the querying shape, so queries reduce to a point.
+#[derive(Debug, Clone, Copy)]
+enum Volume {
+    Aabb { min: Position3D, max: Position3D },
+    Capsule { x: f32, y: f32, z0: f32, z1: f32, radius: f32 },
+}
+
+impl Volume {
+    fn contains(&self, p: Position3D) -> bool {
+        match *self {
+            Volume::Aabb { min, max } => {
+                (min.x..=max.x).contains(&p.x) && (min.y..=max.y).contains(&p.y) && (min.z..=max.z).contains(&p.z)
+            }
+            Volume::Capsule { x, y, z0, z1, radius } => {
+                let z = p.z.clamp(z0, z1);
+                let (dx, dy, dz) = (p.x - x, p.y - y, p.z - z);
+                dx * dx + dy * dy + dz * dz <= radius * radius
+            }
+        }
+    }
+
+    /// Where along `origin + t * direction`, t >= 0, the ray enters.
+    fn entry(&self, origin: Position3D, direction: [f32; 3]) -> Option<f32> {
+        match *self {
+            Volume::Aabb { min, max } => {
+                let (mut enter, mut exit) = (0.0f32, f32::INFINITY);
+                for (o, d, lo, hi) in [
+                    (origin.x, direction[0], min.x, max.x),
+                    (origin.y, direction[1], min.y, max.y),
+                    (origin.z, direction[2], min.z, max.z),
+                ] {
+                    if d.abs() < f32::EPSILON {
+                        if o < lo || o > hi {
+                            return None;
+                        }
+                        continue;
+                    }
+                    let (t0, t1) = ((lo - o) / d, (hi - o) / d);
+                    enter = enter.max(t0.min(t1));
+                    exit = exit.min(t0.max(t1));
+                }
+                (enter <= exit).then_some(enter)
+            }
+            Volume::Capsule { x, y, z0, z1, radius } => {
+                let mut first: Option<f32> = None;
+                let mut consider = |t: Option<f32>| {
+                    if let Some(t) = t.filter(|t| *t >= 0.0) {
+                        first = Some(first.map_or(t, |first| first.min(t)));
+                    }
+                };
+                // The side, where it lies between the end caps
+                let (fx, fy) = (origin.x - x, origin.y - y);
+                let a = direction[0] * direction[0] + direction[1] * direction[1];
+                if a > 0.0 {
+                    let b = fx * direction[0] + fy * direction[1];
+                    let c = fx * fx + fy * fy - radius * radius;
+                    let discriminant = b * b - a * c;
+                    if discriminant >= 0.0 {
+                        let t = (-b - discriminant.sqrt()) / a;
+                        let z = origin.z + direction[2] * t;
+                        consider(Some(t).filter(|_| (z0..=z1).contains(&z)));
+                    }
+                }
+                for z in [z0, z1] {
+                    consider(sphere_entry(origin, direction, [x, y, z], radius));
+                }
+                first
+            }
+        }
+    }
+}
+
+fn sphere_entry(origin: Position3D, d: [f32; 3], center: [f32; 3], radius: f32) -> Option<f32> {
+    let o = [origin.x - center[0], origin.y - center[1], origin.z - center[2]];
+    let a = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+    let b = o[0] * d[0] + o[1] * d[1] + o[2] * d[2];
+    let c = o[0] * o[0] + o[1] * o[1] + o[2] * o[2] - radius * radius;
+    let discriminant = b * b - a * c;
+    (a > 0.0 && discriminant >= 0.0).then(|| (-b - discriminant.sqrt()) / a)
+}
+
+fn along(origin: Position3D, direction: [f32; 3], t: f32) -> Position3D {
+    Position3D::new(origin.x + direction[0] * t, origin.y + direction[1] * t, origin.z + direction[2] * t)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "entity_id", rename_all = "snake_case")]
+pub enum HitTarget {
+    Terrain,
+    Collider(EntityId),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub target: HitTarget,
+    pub distance: f32,
+    pub point: Position3D,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SweepHit {
+    pub entity_id: EntityId,
+    /// Fraction of the move made before contact, 0.0..=1.0.
+    pub fraction: f32,
+    /// Where the shape's base stops.
+    pub position: Position3D,
+}
+
+pub struct CollisionWorld {
+    ground: Arc<dyn Ground>,
+    colliders: DashMap<EntityId, Collider>,
+}
+
+impl CollisionWorld {
+    pub fn new(ground: Arc<dyn Ground>) -> Self {
+        Self { ground, colliders: DashMap::new() }
+    }
+
+    pub fn insert(&self, id: EntityId, collider: Collider) {
+        self.colliders.insert(id, collider);
+    }
+
+    pub fn remove(&self, id: EntityId) -> Option<Collider> {
+        self.colliders.remove(&id).map(|(_, collider)| collider)
+    }
+
+    /// Stand an entity's collider on `base`; false if it has none.
+    pub fn move_to(&self, id: EntityId, base: Position3D) -> bool {
+        match self.colliders.get_mut(&id) {
+            Some(mut collider) => {
+                *collider = collider.with_base(base);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn ground_height(&self, x: f32, y: f32) -> f32 {
+        self.ground.height_at(x, y)
+    }
+
+    /// The first terrain or collider along a ray. Colliders the ray
+    /// starts inside are ignored.
+    pub fn raycast(&self, origin: Position3D, direction: [f32; 3], max_distance: f32) -> Option<RayHit> {
+        let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+        if length == 0.0 {
+            return None;
+        }
+        let unit = [direction[0] / length, direction[1] / length, direction[2] / length];
+
+        let mut nearest = self
+            .colliders
+            .iter()
+            .filter_map(|entry| {
+                let volume = entry.value().expanded(Shape::POINT);
+                if volume.contains(origin) {
+                    return None;
+                }
+                let distance = volume.entry(origin, unit).filter(|t| *t <= max_distance)?;
+                Some(RayHit { target: HitTarget::Collider(*entry.key()), distance, point: along(origin, unit, distance) })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        let limit = nearest.map_or(max_distance, |hit| hit.distance);
+        if let Some(distance) = self.terrain_entry(origin, unit, limit) {
+            nearest = Some(RayHit { target: HitTarget::Terrain, distance, point: along(origin, unit, distance) });
+        }
+        nearest
+    }
+
+    /// Marches the ray until it passes below the ground, then narrows
+    /// down where it crossed.
+    fn terrain_entry(&self, origin: Position3D, unit: [f32; 3], max_distance: f32) -> Option<f32> {
+        let below = |t: f32| {
+            let p = along(origin, unit, t);
+            p.z < self.ground.height_at(p.x, p.y)
+        };
+        if below(0.0) {
+            return Some(0.0);
+        }
+        let mut previous = 0.0;
+        while previous < max_distance {
+            let t = (previous + TERRAIN_STEP).min(max_distance);
+            if below(t) {
+                let (mut above, mut under) = (previous, t);
+                for _ in 0..16 {
+                    let mid = (above + under) / 2.0;
+                    if below(mid) {
+                        under = mid;
+                    } else {
+                        above = mid;
+                    }
+                }
+                return Some(under);
+            }
+            previous = t;
+        }
+        None
+    }
+
+    /// Move `shape` in a straight line and report the first collider it
+    /// would run into. Terrain is left to the caller, and colliders the
+    /// shape already overlaps don't stop it walking out of them.
+    pub fn sweep(&self, shape: Shape, from: Position3D, to: Position3D) -> Option<SweepHit> {
+        let delta = [to.x - from.x, to.y - from.y, to.z - from.z];
+        let length = (delta[0] * delta[0] + delta[1] * delta[1] + delta[2] * delta[2]).sqrt();
+        if length == 0.0 {
+            return None;
+        }
+
+        let (entity_id, fraction) = self
+            .colliders
+            .iter()
+            .filter_map(|entry| {
+                let volume = entry.value().expanded(shape);
+                if volume.contains(from) {
+                    return None;
+                }
+                let t = volume.entry(from, delta).filter(|t| *t <= 1.0)?;
+                Some((*entry.key(), t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        let fraction = (fraction - SKIN / length).max(0.0);
+        Some(SweepHit { entity_id, fraction, position: along(from, delta, fraction) })
+    }
+
+    /// Colliders `shape` would overlap standing at `at`.
+    pub fn overlapping(&self, shape: Shape, at: Position3D) -> Vec<EntityId> {
+        self.colliders
+            .iter()
+            .filter(|entry| entry.value().expanded(shape).contains(at))
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
+    /// A spot on the ground for `shape` clear of every collider: `near`
+    /// itself if possible, otherwise the closest found within
+    /// `max_distance`.
+    pub fn find_clear_spot(&self, shape: Shape, near: Position3D, max_distance: f32) -> Option<Position3D> {
+        let grounded = |x: f32, y: f32| Position3D::new(x, y, self.ground.height_at(x, y));
+        let start = grounded(near.x, near.y);
+        if self.overlapping(shape, start).is_empty() {
+            return Some(start);
+        }
+
+        let step = (max_distance / PLACEMENT_RINGS as f32).max(shape.radius.max(0.25) * 2.0);
+        let mut ring = 1;
+        while ring as f32 * step <= max_distance {
+            let distance = ring as f32 * step;
+            // Roughly one candidate per `step` of circumference
+            let candidates = ((std::f32::consts::TAU * distance / step).ceil() as usize).max(6);
+            for i in 0..candidates {
+                let angle = std::f32::consts::TAU * i as f32 / candidates as f32;
+                let spot = grounded(near.x + distance * angle.cos(), near.y + distance * angle.sin());
+                if self.overlapping(shape, spot).is_empty() {
+                    return Some(spot);
+                }
+            }
+            ring += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn rays_sweeps_and_placement_respect_terrain_and_colliders() {
+        // Flat ground at 0 rising into a slope past x = 50
+        let world = CollisionWorld::new(Arc::new(|x: f32, _y: f32| (x - 50.0).max(0.0)));
+        let pillar = EntityId(Uuid::new_v4());
+        let crate_box = EntityId(Uuid::new_v4());
+        world.insert(pillar, Collider::capsule(Position3D::new(10.0, 0.0, 0.0), 1.0, 6.0));
+        world.insert(crate_box, Collider::cuboid(Position3D::new(20.0, 0.0, 0.0), 2.0, 2.0, 2.0));
+
+        let eye = Position3D::new(0.0, 0.0, 1.5);
+        let hit = world.raycast(eye, [1.0, 0.0, 0.0], 100.0).unwrap();
+        assert_eq!(hit.target, HitTarget::Collider(pillar));
+        assert!((hit.distance - 9.0).abs() < 1e-4);
+        // Past both colliders and on to the slope
+        let hit = world.raycast(Position3D::new(0.0, 3.0, 1.0), [1.0, 0.0, 0.0], 100.0).unwrap();
+        assert_eq!(hit.target, HitTarget::Terrain);
+        assert!((hit.point.x - 51.0).abs() < 1e-2);
+        assert!(world.raycast(eye, [0.0, 1.0, 0.0], 100.0).is_none());
+
+        let player = Shape::capsule(0.5, 1.8);
+        let stop = world.sweep(player, Position3D::new(14.0, 0.0, 0.0), Position3D::new(30.0, 0.0, 0.0)).unwrap();
+        assert_eq!(stop.entity_id, crate_box);
+        assert!((stop.position.x - 18.5).abs() < 1e-2);
+        // Stepping over the box clears it
+        assert!(world.sweep(player, Position3D::new(14.0, 0.0, 2.5), Position3D::new(30.0, 0.0, 2.5)).is_none());
+
+        // A spawn on the pillar is moved just clear of it, onto the ground
+        assert_eq!(world.overlapping(player, Position3D::new(10.0, 0.0, 0.0)), vec![pillar]);
+        let spot = world.find_clear_spot(player, Position3D::new(10.0, 0.0, 5.0), 8.0).unwrap();
+        assert!(world.overlapping(player, spot).is_empty());
+        assert_eq!(spot.z, 0.0);
+        assert!(spot.distance_to(&Position3D::new(10.0, 0.0, 0.0)) <= 3.0);
+
+        assert!(world.move_to(crate_box, Position3D::new(40.0, 0.0, 0.0)));
+        assert!(world.sweep(player, Position3D::new(14.0, 0.0, 0.0), Position3D::new(30.0, 0.0, 0.0)).is_none());
+    }
+}
@@ -8,6 +8,7 @@ pub mod spatial;
 pub mod interactive_objects;
 pub mod echo_entities;
 pub mod assets;
+pub mod collision;
 mod terrain_generator;
 
 use serde::{Deserialize, Serialize};
@@ -30,8 +31,7 @@ pub struct GridCoordinate {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EntityId(pub Uuid);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct PlayerId(pub Uuid);
+pub use finalverse_core::PlayerId;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Position3D {
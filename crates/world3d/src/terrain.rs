@@ -76,38 +76,7 @@ impl TerrainGenerator {
             for x in 0..GRID_RESOLUTION {
                 let world_x = grid_coord.x as f64 * GRID_SIZE as f64 + x as f64;
                 let world_y = grid_coord.y as f64 * GRID_SIZE as f64 + y as f64;
-
-                // Multi-octave noise for base terrain
-                let base_height = self.base_noise.get([world_x * 0.001, world_y * 0.001]) as f32;
-
-                // Add detail noise
-                let detail = self.detail_noise.get([world_x * 0.01, world_y * 0.01]) as f32;
-
-                // Biome-specific modifications
-                let biome_modifier: f32 = match biome {
-                    Biome::WeaversLanding => {
-                        // Gentle rolling hills with river valley
-                        let river_distance = ((world_x - world_y).abs() / 100.0).min(1.0) as f32;
-                        1.0 - (river_distance * 0.3)
-                    }
-                    Biome::WhisperwoodGrove => {
-                        // More varied terrain for forest
-                        1.2 + (detail * 0.3)
-                    }
-                    Biome::MemoryGrotto => {
-                        // Bowl-shaped depression
-                        let center_dist = ((world_x - grid_coord.x as f64 * GRID_SIZE as f64 - 128.0).powi(2)
-                            + (world_y - grid_coord.y as f64 * GRID_SIZE as f64 - 128.0).powi(2))
-                            .sqrt() as f32 / 128.0;
-                        1.0 - (center_dist * 0.5).min(0.5)
-                    }
-                    _ => 1.0,
-                };
-
-                // Apply harmony modifications
-                let harmony_modifier = 1.0f32 + (harmony_level - 0.5) * 0.2;
-
-                heightmap[y][x] = (base_height * 30.0_f32 + detail * 5.0_f32) * biome_modifier * harmony_modifier + 50.0_f32;
+                heightmap[y][x] = self.height_at(grid_coord, world_x, world_y, harmony_level, biome);
             }
         }
 
@@ -128,6 +97,49 @@ impl TerrainGenerator {
         }
     }
 
+    /// Terrain height at a world position, shaped by the biome of the grid
+    /// it belongs to.
+    pub fn height_at(
+        &self,
+        grid_coord: GridCoordinate,
+        world_x: f64,
+        world_y: f64,
+        harmony_level: f32,
+        biome: Biome,
+    ) -> f32 {
+        // Multi-octave noise for base terrain
+        let base_height = self.base_noise.get([world_x * 0.001, world_y * 0.001]) as f32;
+
+        // Add detail noise
+        let detail = self.detail_noise.get([world_x * 0.01, world_y * 0.01]) as f32;
+
+        // Biome-specific modifications
+        let biome_modifier: f32 = match biome {
+            Biome::WeaversLanding => {
+                // Gentle rolling hills with river valley
+                let river_distance = ((world_x - world_y).abs() / 100.0).min(1.0) as f32;
+                1.0 - (river_distance * 0.3)
+            }
+            Biome::WhisperwoodGrove => {
+                // More varied terrain for forest
+                1.2 + (detail * 0.3)
+            }
+            Biome::MemoryGrotto => {
+                // Bowl-shaped depression
+                let center_dist = ((world_x - grid_coord.x as f64 * GRID_SIZE as f64 - 128.0).powi(2)
+                    + (world_y - grid_coord.y as f64 * GRID_SIZE as f64 - 128.0).powi(2))
+                    .sqrt() as f32 / 128.0;
+                1.0 - (center_dist * 0.5).min(0.5)
+            }
+            _ => 1.0,
+        };
+
+        // Apply harmony modifications
+        let harmony_modifier = 1.0f32 + (harmony_level - 0.5) * 0.2;
+
+        (base_height * 30.0_f32 + detail * 5.0_f32) * biome_modifier * harmony_modifier + 50.0_f32
+    }
+
     fn generate_texture_layers(&self, heightmap: &Vec<Vec<f32>>, biome: Biome) -> Vec<TerrainLayer> {
         let mut layers = Vec::new();
 
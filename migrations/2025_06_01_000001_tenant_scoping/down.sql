@@ -0,0 +1,25 @@
+-- File: migrations/2025_06_01_000001_tenant_scoping/down.sql
+-- Path: finalverse/migrations/2025_06_01_000001_tenant_scoping/down.sql
+-- Description: Rollback migration for tenant scoping.
+--              Fails if non-default tenants created clashing names or accounts.
+
+DROP INDEX IF EXISTS idx_npc_memories_tenant;
+DROP INDEX IF EXISTS idx_events_tenant;
+DROP INDEX IF EXISTS idx_players_tenant;
+DROP INDEX IF EXISTS idx_entities_tenant;
+DROP INDEX IF EXISTS idx_worlds_tenant;
+
+ALTER TABLE players DROP CONSTRAINT unique_account_character;
+ALTER TABLE players ADD CONSTRAINT unique_account_character UNIQUE (account_id);
+
+ALTER TABLE players DROP CONSTRAINT unique_character_name_per_tenant;
+ALTER TABLE players ADD CONSTRAINT players_character_name_key UNIQUE (character_name);
+
+ALTER TABLE worlds DROP CONSTRAINT unique_world_name_per_tenant;
+ALTER TABLE worlds ADD CONSTRAINT worlds_name_key UNIQUE (name);
+
+ALTER TABLE npc_memories DROP COLUMN tenant_id;
+ALTER TABLE events DROP COLUMN tenant_id;
+ALTER TABLE players DROP COLUMN tenant_id;
+ALTER TABLE entities DROP COLUMN tenant_id;
+ALTER TABLE worlds DROP COLUMN tenant_id;
@@ -0,0 +1,29 @@
+-- File: migrations/2025_06_01_000001_tenant_scoping/up.sql
+-- Path: finalverse/migrations/2025_06_01_000001_tenant_scoping/up.sql
+-- Description: Scopes persistent data to a tenant (an isolated game world).
+--              Existing rows belong to the 'default' tenant. Tables owned by
+--              a world, player or region inherit the tenant through it.
+
+-- Top-level rows carry the tenant directly
+ALTER TABLE worlds ADD COLUMN tenant_id VARCHAR(32) NOT NULL DEFAULT 'default';
+ALTER TABLE entities ADD COLUMN tenant_id VARCHAR(32) NOT NULL DEFAULT 'default';
+ALTER TABLE players ADD COLUMN tenant_id VARCHAR(32) NOT NULL DEFAULT 'default';
+ALTER TABLE events ADD COLUMN tenant_id VARCHAR(32) NOT NULL DEFAULT 'default';
+ALTER TABLE npc_memories ADD COLUMN tenant_id VARCHAR(32) NOT NULL DEFAULT 'default';
+
+-- Names and accounts are only unique within a tenant
+ALTER TABLE worlds DROP CONSTRAINT worlds_name_key;
+ALTER TABLE worlds ADD CONSTRAINT unique_world_name_per_tenant UNIQUE (tenant_id, name);
+
+ALTER TABLE players DROP CONSTRAINT players_character_name_key;
+ALTER TABLE players ADD CONSTRAINT unique_character_name_per_tenant UNIQUE (tenant_id, character_name);
+
+ALTER TABLE players DROP CONSTRAINT unique_account_character;
+ALTER TABLE players ADD CONSTRAINT unique_account_character UNIQUE (tenant_id, account_id);
+
+-- Every tenant-scoped query filters on tenant first
+CREATE INDEX idx_worlds_tenant ON worlds(tenant_id);
+CREATE INDEX idx_entities_tenant ON entities(tenant_id, entity_type);
+CREATE INDEX idx_players_tenant ON players(tenant_id, account_id);
+CREATE INDEX idx_events_tenant ON events(tenant_id, timestamp);
+CREATE INDEX idx_npc_memories_tenant ON npc_memories(tenant_id, npc_id);
@@ -0,0 +1,6 @@
+-- File: migrations/2025_06_15_000001_harmony_progress/down.sql
+-- Path: finalverse/migrations/2025_06_15_000001_harmony_progress/down.sql
+-- Description: Rollback migration for harmony progress.
+
+DROP TRIGGER IF EXISTS update_harmony_progress_updated_at ON harmony_progress;
+DROP TABLE IF EXISTS harmony_progress;
@@ -0,0 +1,23 @@
+-- File: migrations/2025_06_15_000001_harmony_progress/up.sql
+-- Path: finalverse/migrations/2025_06_15_000001_harmony_progress/up.sql
+-- Description: Resonance and attunement progress owned by harmony-service.
+--              Player ids are the service's own string ids, so there is no
+--              foreign key into players.
+
+CREATE TABLE harmony_progress (
+                                  tenant_id VARCHAR(32) NOT NULL DEFAULT 'default',
+                                  player_id VARCHAR(255) NOT NULL,
+                                  creative_resonance DOUBLE PRECISION NOT NULL DEFAULT 0,
+                                  exploration_resonance DOUBLE PRECISION NOT NULL DEFAULT 0,
+                                  restoration_resonance DOUBLE PRECISION NOT NULL DEFAULT 0,
+                                  attunement_tier INTEGER NOT NULL DEFAULT 0,
+                                  unlocked_melodies TEXT[] NOT NULL DEFAULT '{}',
+                                  unlocked_harmonies TEXT[] NOT NULL DEFAULT '{}',
+                                  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+                                  PRIMARY KEY (tenant_id, player_id)
+);
+
+CREATE TRIGGER update_harmony_progress_updated_at BEFORE UPDATE ON harmony_progress
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON TABLE harmony_progress IS 'Per-player resonance and attunement, written through by harmony-service';
@@ -0,0 +1,6 @@
+-- File: migrations/2025_07_01_000001_accounts/down.sql
+-- Path: finalverse/migrations/2025_07_01_000001_accounts/down.sql
+-- Description: Rollback migration for accounts.
+
+DROP TRIGGER IF EXISTS update_accounts_updated_at ON accounts;
+DROP TABLE IF EXISTS accounts;
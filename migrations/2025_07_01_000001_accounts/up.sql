@@ -0,0 +1,23 @@
+-- File: migrations/2025_07_01_000001_accounts/up.sql
+-- Path: finalverse/migrations/2025_07_01_000001_accounts/up.sql
+-- Description: Sign-in credentials owned by api-gateway. The account id is
+--              the player's id everywhere else, and players.account_id
+--              points here.
+
+CREATE TABLE accounts (
+                          id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+                          tenant_id VARCHAR(32) NOT NULL DEFAULT 'default',
+                          username VARCHAR(64) NOT NULL,
+                          password_hash TEXT NOT NULL,
+                          recovery_token_hash TEXT,
+                          recovery_expires_at TIMESTAMPTZ,
+                          created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                          updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+                          CONSTRAINT unique_username_per_tenant UNIQUE (tenant_id, username)
+);
+
+CREATE TRIGGER update_accounts_updated_at BEFORE UPDATE ON accounts
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+COMMENT ON TABLE accounts IS 'Usernames and Argon2 password hashes, per tenant';
@@ -0,0 +1,5 @@
+-- File: migrations/2025_07_15_000001_harmony_gifts/down.sql
+-- Path: finalverse/migrations/2025_07_15_000001_harmony_gifts/down.sql
+-- Description: Rollback migration for the harmony gift ledger.
+
+DROP TABLE IF EXISTS harmony_gifts;
@@ -0,0 +1,22 @@
+-- File: migrations/2025_07_15_000001_harmony_gifts/up.sql
+-- Path: finalverse/migrations/2025_07_15_000001_harmony_gifts/up.sql
+-- Description: Audit ledger of resonance gifts, written through by
+--              harmony-service. Daily gifting caps are counted from it, so
+--              it must survive restarts.
+
+CREATE TABLE harmony_gifts (
+                               tenant_id VARCHAR(32) NOT NULL DEFAULT 'default',
+                               id UUID NOT NULL,
+                               from_player_id VARCHAR(255) NOT NULL,
+                               to_player_id VARCHAR(255) NOT NULL,
+                               resonance_type VARCHAR(16) NOT NULL,
+                               amount DOUBLE PRECISION NOT NULL,
+                               tax DOUBLE PRECISION NOT NULL,
+                               received DOUBLE PRECISION NOT NULL,
+                               given_at TIMESTAMPTZ NOT NULL,
+                               PRIMARY KEY (tenant_id, id)
+);
+
+CREATE INDEX idx_harmony_gifts_given_at ON harmony_gifts(tenant_id, given_at);
+
+COMMENT ON TABLE harmony_gifts IS 'Accepted resonance gifts between players, append-only';
@@ -1,20 +1,38 @@
 // plugins/greeter-plugin/src/lib.rs
 use async_trait::async_trait;
-use finalverse_plugin::ServicePlugin;
+use finalverse_plugin::{PluginError, PluginManifest, ServicePlugin};
+use finalverse_store::{Store, Tree};
 use service_registry::LocalServiceRegistry;
 use axum::Router as AxumRouter;
 use tonic::transport::server::Router as GrpcRouter;
 use serde_json::Value;
-use serde::de::Error as SerdeError;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, OnceCell};
+
+/// Greetings kept in the history.
+const HISTORY_LIMIT: usize = 100;
+
+/// How long a greeting stays in the history.
+const HISTORY_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Key of the greeting count in the stats tree.
+const COUNT_KEY: &str = "greeting_count";
 
 pub struct GreeterPlugin {
-    greeting_count: Arc<RwLock<u64>>,
-    greeting_history: Arc<RwLock<Vec<GreetingRecord>>>,
+    /// Opened in `init`, so loading the plugin doesn't touch disk.
+    storage: OnceCell<Storage>,
 }
 
-#[derive(Clone, Debug, serde::Serialize)]
+/// The greeter's state, kept across restarts in a store.
+struct Storage {
+    history: Tree,
+    stats: Tree,
+    /// Held while the count is written, so concurrent greetings each get
+    /// their own number.
+    greeting_count: Mutex<u64>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 struct GreetingRecord {
     timestamp: chrono::DateTime<chrono::Utc>,
     name: String,
@@ -24,26 +42,36 @@ struct GreetingRecord {
 impl GreeterPlugin {
     pub fn new() -> Self {
         Self {
-            greeting_count: Arc::new(RwLock::new(0)),
-            greeting_history: Arc::new(RwLock::new(Vec::new())),
+            storage: OnceCell::new(),
         }
     }
 
-    async fn record_greeting(&self, name: String, message: String) {
+    fn storage(&self) -> anyhow::Result<&Storage> {
+        self.storage
+            .get()
+            .ok_or_else(|| anyhow::anyhow!("greeter plugin has not been initialized"))
+    }
+
+    async fn count_greeting(&self) -> anyhow::Result<u64> {
+        let storage = self.storage()?;
+        let mut count = storage.greeting_count.lock().await;
+        storage.stats.insert(COUNT_KEY, &(*count + 1)).await?;
+        *count += 1;
+        Ok(*count)
+    }
+
+    async fn record_greeting(&self, name: String, message: String) -> anyhow::Result<()> {
         let record = GreetingRecord {
             timestamp: chrono::Utc::now(),
             name,
-            message: message.clone(),
+            message,
         };
 
-        self.greeting_history.write().await.push(record);
-
-        // Keep only last 100 greetings
-        let mut history = self.greeting_history.write().await;
-        let len = history.len();
-        if len > 100 {
-            history.drain(0..len - 100);
-        }
+        let history = &self.storage()?.history;
+        let id = history.next_id().await?;
+        history.insert_with_ttl(id.to_be_bytes(), &record, HISTORY_TTL).await?;
+        history.retain_newest(HISTORY_LIMIT).await?;
+        Ok(())
     }
 }
 
@@ -59,23 +87,33 @@ impl ServicePlugin for GreeterPlugin {
         "greeter"
     }
 
+    fn manifest(&self) -> PluginManifest {
+        PluginManifest::new(self.name(), env!("CARGO_PKG_VERSION"))
+    }
+
     async fn routes(&self) -> AxumRouter {
         // In a real plugin we would expose HTTP routes here.
         AxumRouter::new()
     }
 
     async fn init(&self, _registry: &LocalServiceRegistry) -> anyhow::Result<()> {
+        let path = std::env::var("GREETER_STORE").unwrap_or_else(|_| "data/greeter".to_string());
+        let store = Store::open(&path).await?;
+        let stats = store.tree("stats").await?;
+        let greeting_count = stats.get::<u64>(COUNT_KEY).await?.unwrap_or(0);
+        let storage = Storage {
+            history: store.tree("greetings").await?,
+            stats,
+            greeting_count: Mutex::new(greeting_count),
+        };
+        if self.storage.set(storage).is_err() {
+            anyhow::bail!("greeter plugin is already initialized");
+        }
         println!("🎉 greeter plugin initialized");
         Ok(())
     }
 
-    fn register_grpc(self: Box<Self>, server: GrpcRouter) -> GrpcRouter {
-        server
-    }
-}
-
-impl GreeterPlugin {
-    async fn handle_command_internal(&self, command: &str, args: Value) -> serde_json::Result<Value> {
+    async fn handle_command(&self, command: &str, args: Value) -> anyhow::Result<Value> {
         match command {
             "greet" => {
                 let name = args.get("name")
@@ -111,12 +149,10 @@ impl GreeterPlugin {
                 };
 
                 // Increment greeting count
-                let mut count = self.greeting_count.write().await;
-                *count += 1;
-                let greeting_number = *count;
+                let greeting_number = self.count_greeting().await?;
 
                 // Record greeting
-                self.record_greeting(name.to_string(), greeting.clone()).await;
+                self.record_greeting(name.to_string(), greeting.clone()).await?;
 
                 Ok(serde_json::json!({
                     "message": greeting,
@@ -146,7 +182,7 @@ impl GreeterPlugin {
                     _ => format!("See you later, {}!", name),
                 };
 
-                self.record_greeting(name.to_string(), farewell.clone()).await;
+                self.record_greeting(name.to_string(), farewell.clone()).await?;
 
                 Ok(serde_json::json!({
                     "message": farewell,
@@ -155,8 +191,9 @@ impl GreeterPlugin {
             }
 
             "stats" => {
-                let count = *self.greeting_count.read().await;
-                let history_count = self.greeting_history.read().await.len();
+                let storage = self.storage()?;
+                let count = *storage.greeting_count.lock().await;
+                let history_count = storage.history.len().await?;
 
                 Ok(serde_json::json!({
                     "total_greetings": count,
@@ -170,10 +207,9 @@ impl GreeterPlugin {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(10) as usize;
 
-                let history = self.greeting_history.read().await;
-                let recent: Vec<_> = history.iter()
-                    .rev()
-                    .take(limit)
+                let history = &self.storage()?.history;
+                let recent: Vec<_> = history.newest::<GreetingRecord>(limit).await?
+                    .iter()
                     .map(|record| serde_json::json!({
                         "timestamp": record.timestamp.to_rfc3339(),
                         "name": record.name,
@@ -183,21 +219,21 @@ impl GreeterPlugin {
 
                 Ok(serde_json::json!({
                     "recent_greetings": recent,
-                    "total_in_history": history.len(),
+                    "total_in_history": history.len().await?,
                 }))
             }
-            _ => Err(SerdeError::custom("invalid command")),
+            _ => Err(PluginError::UnknownCommand(command.to_string()).into()),
         }
     }
-}
 
-// Plugin entry point for dynamic loading
-#[no_mangle]
-pub extern "C" fn finalverse_plugin_entry() -> *mut dyn ServicePlugin {
-    let plugin = GreeterPlugin::new();
-    Box::into_raw(Box::new(plugin) as Box<dyn ServicePlugin>)
+    fn register_grpc(self: Box<Self>, server: GrpcRouter) -> GrpcRouter {
+        server
+    }
 }
 
+// Plugin entry points for dynamic loading
+finalverse_plugin::declare_plugin!(GreeterPlugin::new());
+
 // Cargo.toml for greeter-plugin:
 /*
 [package]
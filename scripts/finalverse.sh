@@ -14,6 +14,13 @@ LOG_DIR="$PROJECT_ROOT/logs"
 DATA_DIR="$PROJECT_ROOT/data"
 CONFIG_DIR="$PROJECT_ROOT/config"
 BACKUP_DIR="$PROJECT_ROOT/backups"
+SUPPORT_DIR="$PROJECT_ROOT/support"
+CRASH_DIR="${CRASH_DIR:-$LOG_DIR/crashes}"
+
+# Support bundle limits
+SUPPORT_MAX_LOG_BYTES="${SUPPORT_MAX_LOG_BYTES:-1048576}"
+SUPPORT_MAX_BUNDLE_MB="${SUPPORT_MAX_BUNDLE_MB:-50}"
+SUPPORT_HEALTH_SAMPLES="${SUPPORT_HEALTH_SAMPLES:-3}"
 
 # Colors for output
 RED='\033[0;31m'
@@ -547,6 +554,156 @@ backup_data() {
     success "Backup created at $backup_path"
 }
 
+# Support bundles
+collect_support_files() {
+    local staging=$1
+    local log_bytes=$2
+
+    mkdir -p "$staging"/{status,health,logs,config,crashes}
+
+    # Version info
+    {
+        echo "bundle_created: $(date -u '+%Y-%m-%dT%H:%M:%SZ')"
+        echo "finalverse_version: $(grep -m1 '^version' Cargo.toml | cut -d'"' -f2)"
+        echo "git_commit: $(git rev-parse HEAD 2>/dev/null || echo unknown)"
+        echo "git_dirty: $([ -n "$(git status --porcelain 2>/dev/null)" ] && echo yes || echo no)"
+        echo "rustc: $(rustc -V 2>/dev/null || echo unavailable)"
+        echo "docker: $(docker --version 2>/dev/null || echo unavailable)"
+        echo "os: $(uname -a)"
+        echo "use_docker: $USE_DOCKER"
+    } > "$staging/version.txt"
+
+    # Service statuses
+    {
+        for service_port in $DATA_SERVICES; do
+            local service=$(echo "$service_port" | cut -d: -f1)
+            if is_data_service_healthy "$service"; then
+                echo "$service healthy"
+            else
+                echo "$service unhealthy"
+            fi
+        done
+        for service in $(get_all_services); do
+            local port=$(get_service_port "$service")
+            if is_service_running "$service"; then
+                echo "$service running port=$port pid=$(get_service_pid "$service" | tr '\n' ' ')"
+            else
+                echo "$service stopped port=$port"
+            fi
+        done
+    } > "$staging/status/services.txt" 2>/dev/null
+    local server_url=${FINALVERSE_SERVER_URL:-"http://localhost:8080"}
+    timeout 3 curl -s "$server_url/admin/maintenance" > "$staging/status/maintenance.json" 2>/dev/null || true
+
+    # Health history: a few samples per service across the collection window
+    local sample=1
+    while [ "$sample" -le "$SUPPORT_HEALTH_SAMPLES" ]; do
+        for service in $(get_all_services); do
+            local port=$(get_service_port "$service")
+            local body=$(timeout 3 curl -s "http://localhost:$port/health" 2>/dev/null || true)
+            echo "{\"sampled_at\": \"$(date -u '+%Y-%m-%dT%H:%M:%SZ')\", \"health\": ${body:-null}}" \
+                >> "$staging/health/${service}.jsonl"
+        done
+        [ "$sample" -lt "$SUPPORT_HEALTH_SAMPLES" ] && sleep 2
+        sample=$((sample + 1))
+    done
+
+    # Recent logs, capped per file
+    if [ -d "$LOG_DIR" ]; then
+        for log_file in "$LOG_DIR"/*.log; do
+            [ -f "$log_file" ] || continue
+            tail -c "$log_bytes" "$log_file" > "$staging/logs/$(basename "$log_file")"
+        done
+    fi
+
+    # Crash reports, plus panics pulled out of the service logs
+    if [ -d "$CRASH_DIR" ]; then
+        for report in "$CRASH_DIR"/*; do
+            [ -f "$report" ] || continue
+            tail -c "$log_bytes" "$report" > "$staging/crashes/$(basename "$report")"
+        done
+    fi
+    if [ -d "$LOG_DIR" ]; then
+        grep -H -A 8 "panicked at" "$LOG_DIR"/*.log 2>/dev/null | tail -c "$log_bytes" > "$staging/crashes/panics.txt" || true
+    fi
+
+    # Statuses with health history, recent logs and redacted configuration,
+    # as the server manager sees them
+    local management_url="http://${FINALVERSE_MANAGEMENT_ADDR:-127.0.0.1:8081}"
+    if ! timeout 10 curl -fsS -H "x-operator-token: $FINALVERSE_OPERATOR_TOKEN" \
+        "$management_url/support/bundle" > "$staging/server.json" 2>/dev/null; then
+        rm -f "$staging/server.json"
+        echo "Server manager unreachable at $management_url; config was not collected" \
+            > "$staging/config/UNAVAILABLE.txt"
+    fi
+    if [ -n "$FINALVERSE_OPERATOR_TOKEN" ]; then
+        # Running services already redact their effective config
+        timeout 3 curl -s -H "x-operator-token: $FINALVERSE_OPERATOR_TOKEN" \
+            "http://localhost:$(get_service_port api-gateway)/debug/config" \
+            > "$staging/config/effective.json" 2>/dev/null || true
+    fi
+}
+
+support_bundle() {
+    local upload_url=""
+    while [ $# -gt 0 ]; do
+        case "$1" in
+            --upload)
+                upload_url=$2
+                shift 2
+                ;;
+            "")
+                shift
+                ;;
+            *)
+                error "Usage: $0 support-bundle [--upload <url>]"
+                exit 1
+                ;;
+        esac
+    done
+
+    local timestamp=$(date +%Y%m%d_%H%M%S)
+    local name="finalverse-support-$timestamp"
+    local bundle="$SUPPORT_DIR/$name.tar.gz"
+    local max_bytes=$((SUPPORT_MAX_BUNDLE_MB * 1024 * 1024))
+    local log_bytes=$SUPPORT_MAX_LOG_BYTES
+    local work_dir=$(mktemp -d)
+    trap 'rm -rf "$work_dir"' RETURN
+
+    mkdir -p "$SUPPORT_DIR"
+    info "Collecting support bundle..."
+
+    # Shrink the per-file cap until the archive fits
+    local attempt=1
+    while true; do
+        rm -rf "$work_dir/$name"
+        collect_support_files "$work_dir/$name" "$log_bytes"
+        tar -czf "$bundle" -C "$work_dir" "$name"
+        local size=$(wc -c < "$bundle" | tr -d ' ')
+        [ "$size" -le "$max_bytes" ] && break
+        if [ "$attempt" -ge 4 ]; then
+            rm -f "$bundle"
+            error "Support bundle is still ${size} bytes, over the ${SUPPORT_MAX_BUNDLE_MB}MB limit"
+            return 1
+        fi
+        warn "Bundle is ${size} bytes; retrying with smaller log excerpts"
+        log_bytes=$((log_bytes / 4))
+        attempt=$((attempt + 1))
+    done
+
+    success "Support bundle written to $bundle ($(du -h "$bundle" | cut -f1))"
+
+    if [ -n "$upload_url" ]; then
+        info "Uploading support bundle..."
+        if curl -fsS -X PUT -H "Content-Type: application/gzip" --upload-file "$bundle" "$upload_url" >/dev/null; then
+            success "Support bundle uploaded"
+        else
+            error "Upload failed; the bundle is still available at $bundle"
+            return 1
+        fi
+    fi
+}
+
 # Monitor mode
 monitor_services() {
     info "Starting service monitor (Press Ctrl+C to exit)..."
@@ -605,6 +762,83 @@ monitor_services() {
     done
 }
 
+# Request bodies are built with jq so quotes in arguments can't break them
+require_jq() {
+    if ! command -v jq >/dev/null 2>&1; then
+        error "jq is required for this command"
+        exit 1
+    fi
+}
+
+# World snapshot tooling
+world_command() {
+    local subcommand=$1
+    local base_url="http://localhost:$(get_service_port world-engine)"
+
+    case "$subcommand" in
+        "snapshot")
+            local label=${2:-""}
+            require_jq
+            curl -s -X POST "$base_url/snapshots" \
+                -H "Content-Type: application/json" \
+                -d "$(jq -n --arg label "$label" '{label: $label}')"
+            echo ""
+            ;;
+        "snapshots")
+            curl -s "$base_url/snapshots"
+            echo ""
+            ;;
+        "diff")
+            if [ -z "$2" ] || [ -z "$3" ]; then
+                error "Usage: $0 world diff <a> <b> [min_harmony_delta]"
+                exit 1
+            fi
+            local query=""
+            [ -n "$4" ] && query="?min_harmony_delta=$4"
+            curl -s "$base_url/snapshots/diff/$2/$3$query"
+            echo ""
+            ;;
+        *)
+            error "Usage: $0 world <snapshot [label]|snapshots|diff <a> <b>>"
+            exit 1
+            ;;
+    esac
+}
+
+maintenance_command() {
+    local subcommand=$1
+    local base_url=${FINALVERSE_SERVER_URL:-"http://localhost:8080"}
+
+    case "$subcommand" in
+        "schedule")
+            if [ -z "$2" ]; then
+                error "Usage: $0 maintenance schedule <minutes> [reason]"
+                exit 1
+            fi
+            local reason=${3:-"Scheduled maintenance"}
+            require_jq
+            curl -s -X POST "$base_url/admin/maintenance" \
+                -H "Content-Type: application/json" \
+                -H "x-operator-token: $FINALVERSE_OPERATOR_TOKEN" \
+                -d "$(jq -n --argjson starts_in_secs "$(($2 * 60))" --arg reason "$reason" \
+                    '{starts_in_secs: $starts_in_secs, reason: $reason}')"
+            echo ""
+            ;;
+        "status")
+            curl -s "$base_url/admin/maintenance"
+            echo ""
+            ;;
+        "cancel")
+            curl -s -X DELETE -H "x-operator-token: $FINALVERSE_OPERATOR_TOKEN" "$base_url/admin/maintenance"
+            echo ""
+            ;;
+        *)
+            error "Usage: $0 maintenance <schedule <minutes> [reason]|status|cancel>"
+            exit 1
+            ;;
+    esac
+}
+
 # Help system
 show_help() {
     show_banner
@@ -628,6 +862,15 @@ show_help() {
     echo -e "${CYAN}📜 Logs & Debugging:${NC}"
     echo "  logs [service] [n]    Show last n lines of logs"
     echo "  follow [service]      Follow logs in real-time"
+    echo "  support-bundle [--upload <url>]  Archive statuses, logs, redacted config and crashes"
+    echo ""
+    echo -e "${CYAN}🌍 World Tools:${NC}"
+    echo "  world snapshot [label]   Capture a world snapshot"
+    echo "  world snapshots          List stored snapshots"
+    echo "  world diff <a> <b>       Diff two snapshots (use 'current' for live state)"
+    echo "  maintenance schedule <minutes> [reason]  Schedule maintenance with countdown"
+    echo "  maintenance status       Show maintenance phase and health report"
+    echo "  maintenance cancel       Cancel pending maintenance"
     echo ""
     echo -e "${CYAN}🧹 Maintenance:${NC}"
     echo "  clean-ports           Kill processes on Finalverse ports"
@@ -697,6 +940,15 @@ case "${1:-help}" in
     "backup")
         backup_data
         ;;
+    "support-bundle")
+        support_bundle "$2" "$3"
+        ;;
+    "world")
+        world_command "$2" "$3" "$4" "$5"
+        ;;
+    "maintenance")
+        maintenance_command "$2" "$3" "$4"
+        ;;
     "help"|*)
         show_help
         ;;
@@ -1,4 +1,4 @@
-// finalverse-cli/src/main.rs
+// server/src/cli.rs
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use colored::*;
@@ -11,10 +11,11 @@ use futures_util::stream::SplitSink;
 use tokio_tungstenite::{WebSocketStream, MaybeTlsStream};
 use tokio::net::TcpStream;
 
-use finalverse_server::{ServerCommand, ServerResponse, ServiceInfo, LogEntry};
+use chrono::{DateTime, Utc};
+use finalverse_server::{ServerCommand, LogFilter, LogLevel};
 
 #[derive(Parser)]
-#[command(name = "finalverse-cli")]
+#[command(name = "finalverse-admin")]
 #[command(about = "Finalverse CLI - Remote management for Finalverse Server")]
 #[command(version = "1.0")]
 struct Cli {
@@ -57,9 +58,21 @@ enum Commands {
         /// Number of lines to show
         #[arg(short, long, default_value = "50")]
         lines: usize,
-        /// Follow logs in real-time
-        #[arg(short, long)]
-        follow: bool,
+        /// Least severe level to show (error, warn, info, debug, trace)
+        #[arg(long)]
+        level: Option<LogLevel>,
+        /// Only show messages containing this text, ignoring case
+        #[arg(long)]
+        search: Option<String>,
+        /// Only show messages matching this regular expression
+        #[arg(long)]
+        regex: Option<String>,
+        /// Only show entries at or after this RFC 3339 time
+        #[arg(long)]
+        since: Option<DateTime<Utc>>,
+        /// Only show entries at or before this RFC 3339 time
+        #[arg(long)]
+        until: Option<DateTime<Utc>>,
     },
     /// Run health check on all services
     Health,
@@ -68,6 +81,11 @@ enum Commands {
         /// Command to execute
         command: String,
     },
+    /// Reload a dynamic plugin from its library
+    ReloadPlugin {
+        /// Plugin name
+        name: String,
+    },
     /// Start conversational chat mode
     Chat,
     /// Start interactive mode
@@ -175,6 +193,16 @@ impl FinalverseCli {
         self.send_command(&command.to_string()).await
     }
 
+    pub async fn reload_plugin(&mut self, name: String) -> Result<()> {
+        let command = serde_json::to_string(&ServerCommand::ReloadPlugin(name))?;
+        self.send_command(&command).await
+    }
+
+    pub async fn get_logs(&mut self, lines: usize, filter: LogFilter) -> Result<()> {
+        let command = serde_json::to_string(&ServerCommand::GetLogs { lines, filter })?;
+        self.send_command(&command).await
+    }
+
     pub async fn chat_mode(&mut self) -> Result<()> {
         println!("Entering AI chat mode. Type 'exit' to quit.");
         let mut rl = DefaultEditor::new()?;
@@ -261,6 +289,20 @@ impl FinalverseCli {
                                 println!("Usage: event <type> [params]");
                             }
                         }
+                        Some(&"reload") => {
+                            if let Some(name) = parts.get(1) {
+                                self.reload_plugin(name.to_string()).await?;
+                            } else {
+                                println!("Usage: reload <plugin>");
+                            }
+                        }
+                        Some(&"logs") => {
+                            let filter = LogFilter {
+                                services: parts.get(1).map(|name| name.to_string()).into_iter().collect(),
+                                ..LogFilter::default()
+                            };
+                            self.get_logs(50, filter).await?;
+                        }
                         Some(&"raw") => {
                             if parts.len() > 1 {
                                 let command = parts[1..].join(" ");
@@ -300,6 +342,8 @@ fn print_help(&self) {
         println!("  npc <name> <loc>  - Create an NPC");
         println!("  quest <type> <n>  - Generate a quest");
         println!("  event <type>      - Trigger an event");
+        println!("  reload <plugin>   - Reload a dynamic plugin");
+        println!("  logs [service]    - Show recent logs");
         println!("  raw <json>        - Send raw JSON command");
         println!("  chat              - Enter AI chat mode");
     }
@@ -322,6 +366,20 @@ async fn main() -> Result<()> {
         Some(Commands::Exec { command }) => {
             client.send_command(&command).await?;
         }
+        Some(Commands::Logs { service, lines, level, search, regex, since, until }) => {
+            let filter = LogFilter {
+                level,
+                services: service.into_iter().collect(),
+                contains: search,
+                pattern: regex,
+                since,
+                until,
+            };
+            client.get_logs(lines, filter).await?;
+        }
+        Some(Commands::ReloadPlugin { name }) => {
+            client.reload_plugin(name).await?;
+        }
         Some(Commands::Shutdown) => {
             client
                 .send_command(&serde_json::json!({"type": "shutdown"}).to_string())
@@ -0,0 +1,252 @@
+// server/src/dashboard.rs
+//! Data for the web dashboard. `GET /api/metrics/summary` gathers every
+//! managed service's status, CPU, memory and recent errors into one
+//! document. A sampler records them every few seconds into fixed-size
+//! histories, so the dashboard can draw sparklines without a metrics store.
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use finalverse_server::{LogFilter, LogLevel, ManagedService, ServiceStatus};
+use serde::{Serialize, Serializer};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::sync::RwLock;
+
+use crate::server_manager::ServerManager;
+
+/// How often services are sampled.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+/// Samples kept per service: ten minutes at [`SAMPLE_INTERVAL`].
+pub const HISTORY_LEN: usize = 120;
+
+type Manager = Arc<RwLock<ServerManager>>;
+
+/// The latest `capacity` values, oldest first. Serializes as a list.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+}
+
+impl<T: Serialize> Serialize for RingBuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.items)
+    }
+}
+
+/// One service at one moment.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Sample {
+    pub at: DateTime<Utc>,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub healthy: bool,
+    /// Errors logged since the previous sample.
+    pub errors: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceSummary {
+    pub name: String,
+    pub status: ServiceStatus,
+    pub healthy: bool,
+    pub pid: Option<u32>,
+    pub uptime_seconds: u64,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    /// Errors logged over the span of `history`.
+    pub recent_errors: usize,
+    pub history: RingBuffer<Sample>,
+}
+
+/// Body of `GET /api/metrics/summary`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSummary {
+    pub generated_at: DateTime<Utc>,
+    pub sample_interval_seconds: u64,
+    pub healthy: usize,
+    pub unhealthy: usize,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub recent_errors: usize,
+    pub services: Vec<ServiceSummary>,
+}
+
+#[derive(Default)]
+struct History {
+    sampled_at: Option<DateTime<Utc>>,
+    services: HashMap<String, RingBuffer<Sample>>,
+}
+
+impl History {
+    /// Add a sample for each service. `usage` is CPU percent and memory
+    /// bytes by process id; `errors` counts errors by service since the
+    /// last sample.
+    fn record(
+        &mut self,
+        at: DateTime<Utc>,
+        services: &[ManagedService],
+        usage: &HashMap<u32, (f32, u64)>,
+        errors: &HashMap<String, usize>,
+    ) {
+        for service in services {
+            let (cpu_percent, memory_bytes) = service
+                .pid
+                .and_then(|pid| usage.get(&pid))
+                .copied()
+                .unwrap_or((0.0, 0));
+            self.services
+                .entry(service.name.clone())
+                .or_insert_with(|| RingBuffer::new(HISTORY_LEN))
+                .push(Sample {
+                    at,
+                    cpu_percent,
+                    memory_bytes,
+                    healthy: service.health_status,
+                    errors: errors.get(&service.name).copied().unwrap_or(0),
+                });
+        }
+        self.services.retain(|name, _| services.iter().any(|service| &service.name == name));
+        self.sampled_at = Some(at);
+    }
+
+    /// Current status from `services`, usage from the latest sample.
+    fn summary(&self, services: Vec<ManagedService>, now: DateTime<Utc>) -> MetricsSummary {
+        let services: Vec<ServiceSummary> = services
+            .into_iter()
+            .map(|service| {
+                let history = self
+                    .services
+                    .get(&service.name)
+                    .cloned()
+                    .unwrap_or_else(|| RingBuffer::new(HISTORY_LEN));
+                let latest = history.latest().copied();
+                ServiceSummary {
+                    healthy: service.health_status,
+                    pid: service.pid,
+                    uptime_seconds: service.uptime.as_secs(),
+                    cpu_percent: latest.map_or(0.0, |sample| sample.cpu_percent),
+                    memory_bytes: latest.map_or(0, |sample| sample.memory_bytes),
+                    recent_errors: history.iter().map(|sample| sample.errors).sum(),
+                    name: service.name,
+                    status: service.status,
+                    history,
+                }
+            })
+            .collect();
+        let healthy = services.iter().filter(|service| service.healthy).count();
+        MetricsSummary {
+            generated_at: now,
+            sample_interval_seconds: SAMPLE_INTERVAL.as_secs(),
+            healthy,
+            unhealthy: services.len() - healthy,
+            cpu_percent: services.iter().map(|service| service.cpu_percent).sum(),
+            memory_bytes: services.iter().map(|service| service.memory_bytes).sum(),
+            recent_errors: services.iter().map(|service| service.recent_errors).sum(),
+            services,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Dashboard {
+    manager: Manager,
+    history: Arc<RwLock<History>>,
+}
+
+impl Dashboard {
+    pub fn new(manager: Manager) -> Self {
+        Self {
+            manager,
+            history: Arc::new(RwLock::new(History::default())),
+        }
+    }
+
+    /// Sample every managed service's process usage and errors.
+    pub async fn sample(&self, system: &mut System) {
+        let mut history = self.history.write().await;
+        let now = Utc::now();
+        let (services, errors) = {
+            let manager = self.manager.read().await;
+            let filter = LogFilter {
+                level: Some(LogLevel::Error),
+                since: history.sampled_at,
+                ..LogFilter::default()
+            };
+            let mut errors: HashMap<String, usize> = HashMap::new();
+            for entry in manager.search_logs(&filter, usize::MAX).unwrap_or_default() {
+                *errors.entry(entry.service).or_default() += 1;
+            }
+            (manager.all_services(), errors)
+        };
+
+        let pids: Vec<Pid> = services.iter().filter_map(|service| service.pid).map(Pid::from_u32).collect();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        let usage = pids
+            .iter()
+            .filter_map(|pid| system.process(*pid).map(|process| (pid.as_u32(), (process.cpu_usage(), process.memory()))))
+            .collect();
+
+        history.record(now, &services, &usage, &errors);
+    }
+
+    pub async fn summary(&self) -> MetricsSummary {
+        let services = self.manager.read().await.all_services();
+        self.history.read().await.summary(services, Utc::now())
+    }
+}
+
+/// Sample services every [`SAMPLE_INTERVAL`]. CPU usage is measured
+/// between samples, so the first reads zero.
+pub fn spawn_sampler(dashboard: Dashboard) {
+    tokio::spawn(async move {
+        let mut system = System::new();
+        let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            dashboard.sample(&mut system).await;
+        }
+    });
+}
+
+pub fn router(dashboard: Dashboard) -> Router {
+    Router::new()
+        .route("/api/metrics/summary", get(metrics_summary))
+        .with_state(dashboard)
+}
+
+async fn metrics_summary(State(dashboard): State<Dashboard>) -> Json<MetricsSummary> {
+    Json(dashboard.summary().await)
+}
@@ -1,8 +1,10 @@
 // plugin module removed - plugins are now managed directly via the `finalverse-plugin` crate
 
 use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::str::FromStr;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,7 +24,8 @@ pub struct LogEntry {
     pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Most severe first, so `level <= LogLevel::Warn` means "warning or worse".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LogLevel {
     Error,
     Warn,
@@ -31,8 +34,72 @@ pub enum LogLevel {
     Trace,
 }
 
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            other => Err(format!("unknown log level {}", other)),
+        }
+    }
+}
+
+/// Which log entries `GetLogs` returns. Empty fields don't filter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Least severe level included.
+    pub level: Option<LogLevel>,
+    pub services: Vec<String>,
+    /// Case-insensitive text the message must contain.
+    pub contains: Option<String>,
+    /// Regular expression the message must match.
+    pub pattern: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl LogFilter {
+    pub fn matcher(&self) -> Result<LogMatcher<'_>, regex::Error> {
+        Ok(LogMatcher {
+            filter: self,
+            contains: self.contains.as_ref().map(|text| text.to_lowercase()),
+            pattern: self.pattern.as_deref().map(Regex::new).transpose()?,
+        })
+    }
+}
+
+/// A [`LogFilter`] ready to test entries against.
+pub struct LogMatcher<'a> {
+    filter: &'a LogFilter,
+    contains: Option<String>,
+    pattern: Option<Regex>,
+}
+
+impl LogMatcher<'_> {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let filter = self.filter;
+        filter.level.is_none_or(|level| entry.level <= level)
+            && (filter.services.is_empty() || filter.services.contains(&entry.service))
+            && filter.since.is_none_or(|since| entry.timestamp >= since)
+            && filter.until.is_none_or(|until| entry.timestamp <= until)
+            && self
+                .contains
+                .as_ref()
+                .is_none_or(|text| entry.message.to_lowercase().contains(text.as_str()))
+            && self.pattern.as_ref().is_none_or(|pattern| pattern.is_match(&entry.message))
+    }
+}
+
+/// A service process the server manager runs. What a service reports
+/// about itself is `finalverse_core::ServiceInfo`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ServiceInfo {
+pub struct ManagedService {
     pub name: String,
     pub port: u16,
     pub status: ServiceStatus,
@@ -52,17 +119,56 @@ pub enum ServerCommand {
     RestartService(String),
     GetServiceStatus(String),
     GetAllServices,
-    GetLogs { service: Option<String>, lines: usize },
+    /// The latest `lines` entries passing `filter`, oldest first.
+    GetLogs {
+        lines: usize,
+        #[serde(default)]
+        filter: LogFilter,
+    },
     ExecuteCommand(String),
+    /// Swap a dynamic plugin for the current build of its library.
+    ReloadPlugin(String),
     Shutdown,
 }
 
+/// Body of `POST /plugins/{name}/command`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerResponse {
-    ServiceStatus(ServiceInfo),
-    AllServices(Vec<ServiceInfo>),
+    ServiceStatus(ManagedService),
+    AllServices(Vec<ManagedService>),
     Logs(Vec<LogEntry>),
     CommandResult(String),
     Error(String),
     Ok,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_at(timestamp: DateTime<Utc>) -> LogEntry {
+        LogEntry {
+            timestamp,
+            level: LogLevel::Info,
+            service: "world-engine".to_string(),
+            message: "tick".to_string(),
+        }
+    }
+
+    #[test]
+    fn until_includes_entries_at_that_time() {
+        let until = Utc::now();
+        let filter = LogFilter { until: Some(until), ..LogFilter::default() };
+        let matcher = filter.matcher().unwrap();
+
+        assert!(matcher.matches(&entry_at(until)));
+        assert!(!matcher.matches(&entry_at(until + chrono::Duration::milliseconds(1))));
+    }
+}
@@ -0,0 +1,101 @@
+// server/src/log_files.rs
+//! Per-service log files. Entries are appended as JSON lines to
+//! `{dir}/{service}.log`; when a file reaches its size limit it becomes
+//! `{service}.log.1`, older files shift up, and the oldest is deleted.
+
+use finalverse_config::LogFilesConfig;
+use finalverse_server::LogEntry;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+struct OpenLog {
+    file: File,
+    size: u64,
+}
+
+pub struct LogFiles {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    open: HashMap<String, OpenLog>,
+}
+
+impl LogFiles {
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+            max_files,
+            open: HashMap::new(),
+        }
+    }
+
+    pub fn from_config(config: &LogFilesConfig) -> Option<Self> {
+        config
+            .enabled
+            .then(|| Self::new(&config.dir, config.max_bytes, config.max_files))
+    }
+
+    pub fn append(&mut self, entry: &LogEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let name = file_name(&entry.service);
+        let needs_rotation = self
+            .open
+            .get(&name)
+            .is_some_and(|log| log.size > 0 && log.size + line.len() as u64 > self.max_bytes);
+        if needs_rotation {
+            self.open.remove(&name);
+            self.rotate(&name)?;
+        }
+
+        let log = match self.open.entry(name) {
+            Entry::Occupied(log) => log.into_mut(),
+            Entry::Vacant(slot) => {
+                std::fs::create_dir_all(&self.dir)?;
+                let path = self.dir.join(slot.key());
+                let file = OpenOptions::new().create(true).append(true).open(&path)?;
+                let size = file.metadata()?.len();
+                slot.insert(OpenLog { file, size })
+            }
+        };
+        log.file.write_all(&line)?;
+        log.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&self, name: &str) -> io::Result<()> {
+        let rotated = |n: usize| self.dir.join(format!("{}.{}", name, n));
+        remove_if_present(&rotated(self.max_files))?;
+        for n in (1..self.max_files).rev() {
+            if rotated(n).exists() {
+                std::fs::rename(rotated(n), rotated(n + 1))?;
+            }
+        }
+        if self.max_files == 0 {
+            remove_if_present(&self.dir.join(name))
+        } else {
+            std::fs::rename(self.dir.join(name), rotated(1))
+        }
+    }
+}
+
+/// A file name for a service's log that can't escape the log directory.
+fn file_name(service: &str) -> String {
+    let stem: String = service
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}.log", stem)
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -1,14 +1,33 @@
 // server/src/main.rs
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use warp::Filter;
 use world_engine::{WorldEngine, WorldState};
 
+mod dashboard;
 mod handlers;
+mod log_files;
+mod maintenance;
+mod management_api;
+mod metrics_console;
 mod server_manager;
+mod support;
 
+use crate::dashboard::Dashboard;
+use crate::log_files::LogFiles;
+use crate::maintenance::{MaintenanceCoordinator, MaintenanceRequest};
+use crate::metrics_console::{MetricsConsole, MAX_PUSH_BYTES};
 use crate::server_manager::ServerManager;
+use finalverse_plugin::{PluginError, PluginHost};
+use finalverse_server::{PluginCommand, ServerCommand, ServerResponse};
+
+/// Where the management REST API listens unless
+/// `FINALVERSE_MANAGEMENT_ADDR` says otherwise.
+const DEFAULT_MANAGEMENT_ADDR: &str = "127.0.0.1:8081";
+/// How often `FINALVERSE_PLUGIN_DIR` is checked for changed plugins.
+const PLUGIN_WATCH_INTERVAL: Duration = Duration::from_secs(2);
 
 #[tokio::main]
 async fn main() {
@@ -18,10 +37,64 @@ async fn main() {
     let world_engine = Arc::new(WorldEngine::new());
 
     // Initialize server manager
-    let mut server_manager = ServerManager::new();
+    let config = finalverse_config::load_default_config().unwrap_or_default();
+    let server_manager = Arc::new(RwLock::new(
+        ServerManager::new()
+            .with_log_files(LogFiles::from_config(&config.monitoring.log_files))
+            .with_processes(config.services.processes.clone()),
+    ));
+
+    // Start services and restart the ones that exit
+    server_manager.write().await.start_services().await;
+    server_manager::spawn_supervisor(server_manager.clone());
+
+    // Rehearse outages when built with chaos support
+    #[cfg(feature = "chaos")]
+    if let Some(injector) = finalverse_chaos::FaultInjector::from_finalverse_config(&config) {
+        println!("⚠️  Chaos scenarios active: {}", injector.scenarios().len());
+        server_manager::spawn_chaos_kills(server_manager.clone(), &injector);
+    }
 
-    // Start services
-    server_manager.start_services().await;
+    // Sample services for the dashboard
+    let dashboard = Dashboard::new(server_manager.clone());
+    dashboard::spawn_sampler(dashboard.clone());
+
+    // Serve the management REST API; it starts and stops services, so every
+    // route needs the operator token
+    let operator = finalverse_health::OperatorGuard::from_config(&config);
+    let management_addr =
+        std::env::var("FINALVERSE_MANAGEMENT_ADDR").unwrap_or_else(|_| DEFAULT_MANAGEMENT_ADDR.to_string());
+    match tokio::net::TcpListener::bind(&management_addr).await {
+        Ok(listener) => {
+            println!("Management API listening on {}", management_addr);
+            let api = management_api::router(server_manager.clone())
+                .merge(dashboard::router(dashboard.clone()))
+                .merge(support::router(server_manager.clone(), dashboard))
+                .route_layer(axum::middleware::from_fn_with_state(
+                    operator.clone(),
+                    finalverse_health::require_operator,
+                ));
+            tokio::spawn(async move {
+                if let Err(e) = axum::serve(listener, api).await {
+                    eprintln!("Management API stopped: {}", e);
+                }
+            });
+        }
+        Err(e) => eprintln!("⚠️  Management API unavailable on {}: {}", management_addr, e),
+    }
+
+    let maintenance = MaintenanceCoordinator::new(
+        server_manager.clone(),
+        service_registry::LocalServiceRegistry::new(),
+        &operator,
+    );
+
+    // Load dynamic plugins and reload them when their libraries change
+    let plugin_host = PluginHost::from_env(service_registry::LocalServiceRegistry::new()).map(Arc::new);
+    if let Some(host) = &plugin_host {
+        host.scan().await;
+        host.clone().watch(PLUGIN_WATCH_INTERVAL);
+    }
 
     // Clone for the update task
     let world_engine_clone = world_engine.clone();
@@ -52,7 +125,138 @@ async fn main() {
             })
     };
 
-    let routes = health.or(world_state);
+    // Routes that change live state need the operator token
+    let operator = operator.warp_filter();
+    let maintenance_filter = warp::any().map(move || maintenance.clone());
+
+    let schedule_maintenance = warp::path!("admin" / "maintenance")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(maintenance_filter.clone())
+        .and_then(|request: MaintenanceRequest, maintenance: MaintenanceCoordinator| async move {
+            let reply = match maintenance.schedule(request).await {
+                Ok(status) => warp::reply::with_status(warp::reply::json(&status), warp::http::StatusCode::ACCEPTED),
+                Err(e) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({ "error": e })),
+                    warp::http::StatusCode::CONFLICT,
+                ),
+            };
+            Ok::<_, warp::Rejection>(reply)
+        });
+
+    let maintenance_status = warp::path!("admin" / "maintenance")
+        .and(warp::get())
+        .and(maintenance_filter.clone())
+        .and_then(|maintenance: MaintenanceCoordinator| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&maintenance.status().await))
+        });
+
+    let cancel_maintenance = warp::path!("admin" / "maintenance")
+        .and(warp::delete())
+        .and(operator.clone())
+        .and(maintenance_filter)
+        .and_then(|maintenance: MaintenanceCoordinator| async move {
+            let cancelled = maintenance.cancel().await;
+            Ok::<_, warp::Rejection>(warp::reply::json(&serde_json::json!({ "cancelled": cancelled })))
+        });
+
+    let plugin_command = {
+        let plugin_host = plugin_host.clone();
+        warp::path!("plugins" / String / "command")
+            .and(warp::post())
+            .and(operator.clone())
+            .and(warp::body::json())
+            .and_then(move |name: String, request: PluginCommand| {
+                let plugin_host = plugin_host.clone();
+                async move {
+                    let result = match &plugin_host {
+                        Some(host) => host.handle_command(&name, &request.command, request.args).await,
+                        None => Err(PluginError::NotLoaded(name)),
+                    };
+                    let reply = match result {
+                        Ok(value) => warp::reply::with_status(warp::reply::json(&value), warp::http::StatusCode::OK),
+                        Err(e) => {
+                            let status = match e {
+                                PluginError::NotLoaded(_) => warp::http::StatusCode::NOT_FOUND,
+                                PluginError::UnknownCommand(_) => warp::http::StatusCode::BAD_REQUEST,
+                                _ => warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                            };
+                            warp::reply::with_status(warp::reply::json(&serde_json::json!({ "error": e.to_string() })), status)
+                        }
+                    };
+                    Ok::<_, warp::Rejection>(reply)
+                }
+            })
+    };
+
+    let command = warp::path!("admin" / "command")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and_then(move |command: ServerCommand| {
+            let plugin_host = plugin_host.clone();
+            async move {
+                let response = match command {
+                    ServerCommand::ReloadPlugin(name) => match &plugin_host {
+                        Some(host) => match host.reload(&name).await {
+                            Ok(manifest) => ServerResponse::CommandResult(format!(
+                                "Reloaded plugin {} {}",
+                                manifest.name, manifest.version
+                            )),
+                            Err(e) => ServerResponse::Error(e.to_string()),
+                        },
+                        None => ServerResponse::Error("FINALVERSE_PLUGIN_DIR is not set".to_string()),
+                    },
+                    other => ServerResponse::Error(format!("{:?} is not handled by this server", other)),
+                };
+                Ok::<_, warp::Rejection>(warp::reply::json(&response))
+            }
+        });
+
+    let metrics_console = MetricsConsole::new();
+    let metrics_filter = warp::any().map(move || metrics_console.clone());
+
+    let push_metrics = warp::path!("admin" / "metrics" / String)
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::content_length_limit(MAX_PUSH_BYTES))
+        .and(warp::body::bytes())
+        .and(metrics_filter.clone())
+        .and_then(|service: String, body: warp::hyper::body::Bytes, console: MetricsConsole| async move {
+            console.record(service, String::from_utf8_lossy(&body).into_owned()).await;
+            Ok::<_, warp::Rejection>(warp::http::StatusCode::NO_CONTENT)
+        });
+
+    let metrics_summary = warp::path!("admin" / "metrics")
+        .and(warp::get())
+        .and(metrics_filter.clone())
+        .and_then(|console: MetricsConsole| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&console.summaries().await))
+        });
+
+    let service_metrics = warp::path!("admin" / "metrics" / String)
+        .and(warp::get())
+        .and(metrics_filter)
+        .and_then(|service: String, console: MetricsConsole| async move {
+            match console.latest(&service).await {
+                Some(body) => Ok(body),
+                None => Err(warp::reject::not_found()),
+            }
+        });
+
+    let routes = health
+        .or(world_state)
+        .or(schedule_maintenance)
+        .or(maintenance_status)
+        .or(cancel_maintenance)
+        .or(command)
+        .or(plugin_command)
+        .or(push_metrics)
+        .or(metrics_summary)
+        .or(service_metrics)
+        .or(Arc::new(finalverse_health::DebugEndpoints::load("finalverse-server")).warp_routes())
+        .recover(finalverse_core::warp::recover);
 
     // Start server
     let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
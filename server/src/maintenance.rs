@@ -0,0 +1,286 @@
+// server/src/maintenance.rs
+//! Scheduled maintenance: countdown notices, login blocking, session drain
+//! and an ordered restart with a health verification report.
+
+use crate::server_manager::ServerManager;
+use chrono::{DateTime, Utc};
+use finalverse_health::{OperatorGuard, OPERATOR_TOKEN_HEADER};
+use serde::{Deserialize, Serialize};
+use service_registry::{LocalServiceRegistry, ServiceClient};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// Services in the order they come back up; each one only depends on
+/// services listed before it. The gateway goes last so players can't
+/// reconnect into a half-started cluster.
+const RESTORE_ORDER: &[&str] = &[
+    "world-engine",
+    "song-engine",
+    "harmony-service",
+    "echo-engine",
+    "silence-service",
+    "ai-orchestra",
+    "story-engine",
+    "procedural-gen",
+    "behavior-ai",
+    "asset-service",
+    "community",
+    "websocket-gateway",
+];
+
+/// Seconds before the start at which players get a countdown notice.
+const COUNTDOWN_MARKS: &[u64] = &[3600, 1800, 900, 600, 300, 120, 60, 30, 10];
+
+const GATEWAY: &str = "websocket-gateway";
+
+fn default_login_block_secs() -> u64 {
+    300
+}
+
+fn default_drain_grace_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceRequest {
+    pub starts_in_secs: u64,
+    pub reason: String,
+    /// New logins are refused during this final window.
+    #[serde(default = "default_login_block_secs")]
+    pub login_block_secs: u64,
+    /// Time given to clients to disconnect before services stop.
+    #[serde(default = "default_drain_grace_secs")]
+    pub drain_grace_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenancePhase {
+    Scheduled,
+    LoginsBlocked,
+    Draining,
+    Down,
+    Restoring,
+    Complete,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceHealth {
+    pub name: String,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub completed_at: DateTime<Utc>,
+    pub all_healthy: bool,
+    pub services: Vec<ServiceHealth>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceStatus {
+    pub reason: String,
+    pub starts_at: DateTime<Utc>,
+    pub phase: MaintenancePhase,
+    pub report: Option<HealthReport>,
+}
+
+#[derive(Clone)]
+pub struct MaintenanceCoordinator {
+    manager: Arc<RwLock<ServerManager>>,
+    services: ServiceClient,
+    status: Arc<RwLock<Option<MaintenanceStatus>>>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl MaintenanceCoordinator {
+    /// The gateway's maintenance routes need `operator`'s token.
+    pub fn new(manager: Arc<RwLock<ServerManager>>, registry: LocalServiceRegistry, operator: &OperatorGuard) -> Self {
+        let services = ServiceClient::new(registry);
+        let services = match operator.token() {
+            Some(token) => services.with_header(OPERATOR_TOKEN_HEADER, token),
+            None => {
+                println!("⚠️  No operator token configured; the gateway will refuse maintenance notices");
+                services
+            }
+        };
+        Self {
+            manager,
+            services,
+            status: Arc::new(RwLock::new(None)),
+            task: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn status(&self) -> Option<MaintenanceStatus> {
+        self.status.read().await.clone()
+    }
+
+    /// Schedule a maintenance window. Fails if one is already in progress.
+    pub async fn schedule(&self, request: MaintenanceRequest) -> Result<MaintenanceStatus, String> {
+        let mut task = self.task.lock().await;
+        if task.as_ref().is_some_and(|handle| !handle.is_finished()) {
+            return Err("Maintenance is already scheduled".to_string());
+        }
+
+        let status = MaintenanceStatus {
+            reason: request.reason.clone(),
+            starts_at: Utc::now() + chrono::Duration::seconds(request.starts_in_secs as i64),
+            phase: MaintenancePhase::Scheduled,
+            report: None,
+        };
+        *self.status.write().await = Some(status.clone());
+
+        let coordinator = self.clone();
+        *task = Some(tokio::spawn(async move { coordinator.run(request).await }));
+
+        Ok(status)
+    }
+
+    /// Cancel a pending window. Services that were already stopped are not
+    /// restarted; cancelling is only meant for the countdown phase.
+    pub async fn cancel(&self) -> bool {
+        let Some(handle) = self.task.lock().await.take() else {
+            return false;
+        };
+        handle.abort();
+        *self.status.write().await = None;
+        self.gateway_post("/maintenance/clear", &serde_json::json!({})).await;
+        true
+    }
+
+    async fn run(&self, request: MaintenanceRequest) {
+        let start = Instant::now() + Duration::from_secs(request.starts_in_secs);
+
+        for mark in countdown_marks(request.starts_in_secs, request.login_block_secs) {
+            tokio::time::sleep_until((start - Duration::from_secs(mark)).into()).await;
+            let block_logins = mark <= request.login_block_secs;
+            if block_logins {
+                self.set_phase(MaintenancePhase::LoginsBlocked).await;
+            }
+            self.broadcast_countdown(&request.reason, mark, block_logins).await;
+        }
+        tokio::time::sleep_until(start.into()).await;
+
+        self.set_phase(MaintenancePhase::Draining).await;
+        self.gateway_post("/maintenance/drain", &serde_json::json!({ "reason": request.reason }))
+            .await;
+        tokio::time::sleep(Duration::from_secs(request.drain_grace_secs)).await;
+
+        self.set_phase(MaintenancePhase::Down).await;
+        {
+            let mut manager = self.manager.write().await;
+            for service in RESTORE_ORDER.iter().rev() {
+                manager.stop_service(service);
+            }
+        }
+        println!("🔧 Services stopped for maintenance: {}", request.reason);
+
+        self.set_phase(MaintenancePhase::Restoring).await;
+        let report = self.restore().await;
+        println!(
+            "🔧 Maintenance complete, {} of {} services healthy",
+            report.services.iter().filter(|s| s.healthy).count(),
+            report.services.len()
+        );
+
+        self.gateway_post("/maintenance/clear", &serde_json::json!({})).await;
+        if let Some(status) = self.status.write().await.as_mut() {
+            status.phase = MaintenancePhase::Complete;
+            status.report = Some(report);
+        }
+    }
+
+    /// Start services in dependency order, checking each one's health
+    /// before moving on to its dependents.
+    async fn restore(&self) -> HealthReport {
+        let mut services = Vec::with_capacity(RESTORE_ORDER.len());
+        for &name in RESTORE_ORDER {
+            self.manager.write().await.start_service(name);
+
+            let started = Instant::now();
+            let result = tokio::time::timeout(
+                Duration::from_secs(5),
+                self.services.get_json::<serde_json::Value>(name, "/health"),
+            )
+            .await;
+            let error = match result {
+                Ok(Ok(_)) => None,
+                Ok(Err(e)) => Some(e.to_string()),
+                Err(_) => Some("health check timed out".to_string()),
+            };
+            services.push(ServiceHealth {
+                name: name.to_string(),
+                healthy: error.is_none(),
+                latency_ms: started.elapsed().as_millis() as u64,
+                error,
+            });
+        }
+
+        HealthReport {
+            completed_at: Utc::now(),
+            all_healthy: services.iter().all(|s| s.healthy),
+            services,
+        }
+    }
+
+    async fn broadcast_countdown(&self, reason: &str, seconds_remaining: u64, block_logins: bool) {
+        let message = format!(
+            "Scheduled maintenance in {}: {}",
+            format_countdown(seconds_remaining),
+            reason
+        );
+        self.gateway_post(
+            "/maintenance/notice",
+            &serde_json::json!({
+                "message": message,
+                "seconds_remaining": seconds_remaining,
+                "block_logins": block_logins,
+            }),
+        )
+        .await;
+    }
+
+    async fn gateway_post(&self, path: &str, body: &serde_json::Value) {
+        if let Err(e) = self
+            .services
+            .post_json::<_, serde_json::Value>(GATEWAY, path, body)
+            .await
+        {
+            println!("⚠️  Gateway call {} failed: {}", path, e);
+        }
+    }
+
+    async fn set_phase(&self, phase: MaintenancePhase) {
+        if let Some(status) = self.status.write().await.as_mut() {
+            status.phase = phase;
+        }
+    }
+}
+
+/// Seconds before the start to send notices at: the countdown marks that
+/// fit before the start, plus the moment logins get blocked so the block
+/// starts on time rather than at the next mark.
+fn countdown_marks(starts_in_secs: u64, login_block_secs: u64) -> Vec<u64> {
+    let mut marks: Vec<u64> = COUNTDOWN_MARKS
+        .iter()
+        .copied()
+        .chain([login_block_secs.min(starts_in_secs)])
+        .filter(|&mark| mark > 0 && mark <= starts_in_secs)
+        .collect();
+    marks.sort_unstable_by(|a, b| b.cmp(a));
+    marks.dedup();
+    marks
+}
+
+fn format_countdown(seconds: u64) -> String {
+    match seconds {
+        s if s >= 3600 && s % 3600 == 0 => format!("{} hour(s)", s / 3600),
+        s if s >= 60 => format!("{} minute(s)", s / 60),
+        s => format!("{} second(s)", s),
+    }
+}
@@ -0,0 +1,144 @@
+// server/src/management_api.rs
+//! REST API over the service manager for deployment tooling and the
+//! dashboard. Each endpoint runs the matching `ServerCommand` and answers
+//! with its `ServerResponse` as JSON; `/services/events` streams status
+//! changes as server-sent events. The server only serves it behind the
+//! operator token.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use finalverse_server::{LogFilter, LogLevel, ServerCommand, ServerResponse};
+use futures::Stream;
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+
+use crate::server_manager::{ManagerError, ServerManager};
+
+/// Log lines returned when a request doesn't say how many.
+const DEFAULT_LOG_LINES: usize = 100;
+
+type Manager = Arc<RwLock<ServerManager>>;
+
+impl IntoResponse for ManagerError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ManagerError::UnknownService(_) => StatusCode::NOT_FOUND,
+            ManagerError::Unsupported(_) | ManagerError::InvalidFilter(_) => StatusCode::BAD_REQUEST,
+        };
+        (status, Json(ServerResponse::Error(self.to_string()))).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsQuery {
+    lines: Option<usize>,
+    level: Option<String>,
+    /// Comma-separated service names.
+    services: Option<String>,
+    contains: Option<String>,
+    pattern: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+}
+
+pub fn router(manager: Manager) -> Router {
+    Router::new()
+        .route("/services", get(all_services))
+        .route("/services/events", get(service_events))
+        .route("/services/:name", get(service_status))
+        .route("/services/:name/start", post(start_service))
+        .route("/services/:name/stop", post(stop_service))
+        .route("/services/:name/restart", post(restart_service))
+        .route("/logs", get(logs))
+        .with_state(manager)
+}
+
+async fn execute(manager: &Manager, command: ServerCommand) -> Result<Json<ServerResponse>, ManagerError> {
+    manager.write().await.execute(command).map(Json)
+}
+
+async fn all_services(State(manager): State<Manager>) -> Result<Json<ServerResponse>, ManagerError> {
+    execute(&manager, ServerCommand::GetAllServices).await
+}
+
+/// Server-sent `ServiceStatus` responses as services change state.
+async fn service_events(State(manager): State<Manager>) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let updates = manager.read().await.subscribe();
+    let events = futures::stream::unfold(updates, |mut updates| async move {
+        loop {
+            match updates.recv().await {
+                Ok(update) => return Some((Event::default().json_data(&update), updates)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn service_status(
+    State(manager): State<Manager>,
+    Path(name): Path<String>,
+) -> Result<Json<ServerResponse>, ManagerError> {
+    execute(&manager, ServerCommand::GetServiceStatus(name)).await
+}
+
+async fn start_service(
+    State(manager): State<Manager>,
+    Path(name): Path<String>,
+) -> Result<Json<ServerResponse>, ManagerError> {
+    execute(&manager, ServerCommand::StartService(name)).await
+}
+
+async fn stop_service(
+    State(manager): State<Manager>,
+    Path(name): Path<String>,
+) -> Result<Json<ServerResponse>, ManagerError> {
+    execute(&manager, ServerCommand::StopService(name)).await
+}
+
+async fn restart_service(
+    State(manager): State<Manager>,
+    Path(name): Path<String>,
+) -> Result<Json<ServerResponse>, ManagerError> {
+    execute(&manager, ServerCommand::RestartService(name)).await
+}
+
+async fn logs(
+    State(manager): State<Manager>,
+    Query(query): Query<LogsQuery>,
+) -> Result<Json<ServerResponse>, ManagerError> {
+    let level = query
+        .level
+        .as_deref()
+        .map(str::parse::<LogLevel>)
+        .transpose()
+        .map_err(ManagerError::InvalidFilter)?;
+    let services = query
+        .services
+        .iter()
+        .flat_map(|services| services.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    let command = ServerCommand::GetLogs {
+        lines: query.lines.unwrap_or(DEFAULT_LOG_LINES),
+        filter: LogFilter {
+            level,
+            services,
+            contains: query.contains,
+            pattern: query.pattern,
+            since: query.since,
+            until: query.until,
+        },
+    };
+    execute(&manager, command).await
+}
@@ -0,0 +1,67 @@
+// server/src/metrics_console.rs
+//! Metrics pushed by services (see `finalverse_metrics::MetricsHandle::push`),
+//! so the console can show them without a Prometheus server.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Largest push body accepted.
+pub const MAX_PUSH_BYTES: u64 = 4 * 1024 * 1024;
+
+struct Push {
+    received_at: DateTime<Utc>,
+    body: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PushSummary {
+    pub service: String,
+    pub received_at: DateTime<Utc>,
+    /// Number of samples in the latest push.
+    pub series: usize,
+}
+
+/// Latest push per service.
+#[derive(Clone, Default)]
+pub struct MetricsConsole {
+    pushes: Arc<RwLock<HashMap<String, Push>>>,
+}
+
+impl MetricsConsole {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record(&self, service: String, body: String) {
+        let push = Push {
+            received_at: Utc::now(),
+            body,
+        };
+        self.pushes.write().await.insert(service, push);
+    }
+
+    pub async fn summaries(&self) -> Vec<PushSummary> {
+        let pushes = self.pushes.read().await;
+        let mut summaries: Vec<PushSummary> = pushes
+            .iter()
+            .map(|(service, push)| PushSummary {
+                service: service.clone(),
+                received_at: push.received_at,
+                series: push
+                    .body
+                    .lines()
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .count(),
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.service.cmp(&b.service));
+        summaries
+    }
+
+    pub async fn latest(&self, service: &str) -> Option<String> {
+        self.pushes.read().await.get(service).map(|push| push.body.clone())
+    }
+}
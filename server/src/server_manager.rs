@@ -1,27 +1,515 @@
 // server/src/server_manager.rs
+use chrono::Utc;
+use finalverse_config::{ManagedProcessConfig, RestartMode, RestartPolicyConfig};
+use finalverse_server::{LogEntry, LogFilter, LogLevel, ManagedService, ServerCommand, ServerResponse, ServiceStatus as RunState};
+use std::process::{ExitStatus, Stdio};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{broadcast, mpsc, RwLock};
+
+use crate::log_files::LogFiles;
+use std::collections::{HashMap, VecDeque};
+use thiserror::Error;
+
+/// Log entries kept in memory, across all services, for `GetLogs`.
+const MAX_LOG_ENTRIES: usize = 10_000;
+/// Recent log lines included with a service's status.
+const STATUS_LOG_LINES: usize = 20;
+/// Status updates a slow subscriber can fall behind by.
+const UPDATE_BUFFER: usize = 256;
+/// How often child processes are checked.
+const SUPERVISE_INTERVAL: Duration = Duration::from_millis(500);
+/// How long a restarted process must stay up to reset its retry count.
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Error)]
+pub enum ManagerError {
+    #[error("Unknown service {0}")]
+    UnknownService(String),
+
+    #[error("{0} is not handled by the service manager")]
+    Unsupported(String),
+
+    #[error("Invalid log filter: {0}")]
+    InvalidFilter(String),
+}
 
 pub struct ServerManager {
     services: HashMap<String, ServiceStatus>,
+    logs: VecDeque<LogEntry>,
+    log_files: Option<LogFiles>,
+    updates: broadcast::Sender<ServerResponse>,
+    /// Lines written by child processes, recorded on the next supervise.
+    output_tx: mpsc::UnboundedSender<LogEntry>,
+    output_rx: mpsc::UnboundedReceiver<LogEntry>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct ServiceStatus {
     pub name: String,
     pub is_running: bool,
+    pub started_at: Option<Instant>,
+    /// Set for services run as child processes.
+    process: Option<ManagedProcess>,
+}
+
+#[derive(Debug)]
+struct ManagedProcess {
+    config: ManagedProcessConfig,
+    child: Option<Child>,
+    /// Restarts since the process last stayed up for [`STABLE_RUN`].
+    restarts: u32,
+    restart_at: Option<Instant>,
+    /// Why the process is down, once it won't be restarted.
+    failure: Option<String>,
+}
+
+impl ManagedProcess {
+    /// Launch the process, sending what it prints to `output`.
+    fn spawn(&mut self, service: &str, output: &mpsc::UnboundedSender<LogEntry>) -> std::io::Result<()> {
+        let mut child = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(capture(service.to_string(), stdout, output.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(capture(service.to_string(), stderr, output.clone()));
+        }
+        self.child = Some(child);
+        self.failure = None;
+        Ok(())
+    }
+}
+
+async fn capture(service: String, stream: impl AsyncRead + Unpin, output: mpsc::UnboundedSender<LogEntry>) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let entry = LogEntry {
+            timestamp: Utc::now(),
+            level: line_level(&line),
+            service: service.clone(),
+            message: line,
+        };
+        if output.send(entry).is_err() {
+            break;
+        }
+    }
+}
+
+/// The level a log line names, going by the first upper-case level word
+/// in it, as `tracing` and `env_logger` print them.
+fn line_level(line: &str) -> LogLevel {
+    line.split(|c: char| !c.is_ascii_alphabetic())
+        .find_map(|word| match word {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" | "WARNING" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            "TRACE" => Some(LogLevel::Trace),
+            _ => None,
+        })
+        .unwrap_or(LogLevel::Info)
+}
+
+/// Delay before restart number `attempt` (from 0).
+fn backoff(policy: &RestartPolicyConfig, attempt: u32) -> Duration {
+    let delay = policy.backoff_ms.saturating_mul(1 << attempt.min(16));
+    Duration::from_millis(delay.min(policy.max_backoff_ms))
 }
 
 impl ServerManager {
     pub fn new() -> Self {
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
         Self {
             services: HashMap::new(),
+            logs: VecDeque::new(),
+            log_files: None,
+            updates: broadcast::channel(UPDATE_BUFFER).0,
+            output_tx,
+            output_rx,
         }
     }
 
+    /// Also write log entries to per-service files.
+    pub fn with_log_files(self, log_files: Option<LogFiles>) -> Self {
+        Self { log_files, ..self }
+    }
+
+    /// Manage these services as child processes.
+    pub fn with_processes(mut self, processes: HashMap<String, ManagedProcessConfig>) -> Self {
+        for (name, config) in processes {
+            let process = ManagedProcess {
+                config,
+                child: None,
+                restarts: 0,
+                restart_at: None,
+                failure: None,
+            };
+            self.services.insert(name.clone(), ServiceStatus {
+                name,
+                is_running: false,
+                started_at: None,
+                process: Some(process),
+            });
+        }
+        self
+    }
+
+    /// Status of a service each time it starts, stops, crashes or is
+    /// scheduled for a restart.
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerResponse> {
+        self.updates.subscribe()
+    }
+
     pub async fn start_services(&mut self) {
         // Initialize services
         println!("Starting Finalverse services...");
+        let mut processes: Vec<String> = self
+            .services
+            .values()
+            .filter(|status| status.process.is_some())
+            .map(|status| status.name.clone())
+            .collect();
+        processes.sort();
+        for name in processes {
+            self.start_service(&name);
+        }
+    }
+
+    /// Mark a managed service as running, registering it if needed.
+    /// Services configured as processes are launched.
+    pub fn start_service(&mut self, name: &str) {
+        let status = self
+            .services
+            .entry(name.to_string())
+            .or_insert_with(|| ServiceStatus {
+                name: name.to_string(),
+                is_running: false,
+                started_at: None,
+                process: None,
+            });
+        if status.is_running {
+            return;
+        }
+        if let Some(process) = &mut status.process {
+            process.restart_at = None;
+            if let Err(e) = process.spawn(name, &self.output_tx) {
+                let message = format!("Failed to launch {}: {}", process.config.command, e);
+                process.failure = Some(message.clone());
+                eprintln!("❌ {}: {}", name, message);
+                self.log(name, LogLevel::Error, &message);
+                self.emit(name);
+                return;
+            }
+        }
+        status.is_running = true;
+        status.started_at = Some(Instant::now());
+        println!("▶️  Started service {}", status.name);
+        self.log(name, LogLevel::Info, "Service started");
+        self.emit(name);
+    }
+
+    /// Stop a managed service, cancelling any pending restart. Returns
+    /// `true` if it was running.
+    pub fn stop_service(&mut self, name: &str) -> bool {
+        let Some(status) = self.services.get_mut(name) else {
+            return false;
+        };
+        if let Some(process) = &mut status.process {
+            process.restart_at = None;
+            process.restarts = 0;
+            if let Some(mut child) = process.child.take() {
+                let _ = child.start_kill();
+            }
+        }
+        if !status.is_running {
+            return false;
+        }
+        status.is_running = false;
+        status.started_at = None;
+        self.log(name, LogLevel::Info, "Service stopped");
+        self.emit(name);
+        true
+    }
+
+    /// Notice processes that have exited and restart those whose backoff
+    /// has elapsed.
+    pub fn supervise(&mut self, now: Instant) {
+        while let Ok(entry) = self.output_rx.try_recv() {
+            self.record(entry);
+        }
+
+        let mut exited = Vec::new();
+        let mut due = Vec::new();
+        for status in self.services.values_mut() {
+            let Some(process) = &mut status.process else {
+                continue;
+            };
+            match process.child.as_mut().map(|child| child.try_wait()) {
+                Some(Ok(Some(exit))) => {
+                    process.child = None;
+                    exited.push((status.name.clone(), exit));
+                }
+                Some(Ok(None)) => {
+                    if status.started_at.is_some_and(|at| now.duration_since(at) >= STABLE_RUN) {
+                        process.restarts = 0;
+                    }
+                }
+                Some(Err(e)) => eprintln!("⚠️  Can't check process for {}: {}", status.name, e),
+                None => {
+                    if process.restart_at.is_some_and(|at| at <= now) {
+                        due.push(status.name.clone());
+                    }
+                }
+            }
+        }
+
+        for (name, exit) in exited {
+            self.handle_exit(&name, exit, now);
+        }
+        for name in due {
+            self.start_service(&name);
+        }
+    }
+
+    fn handle_exit(&mut self, name: &str, exit: ExitStatus, now: Instant) {
+        let Some(status) = self.services.get_mut(name) else {
+            return;
+        };
+        let Some(process) = &mut status.process else {
+            return;
+        };
+        status.is_running = false;
+        status.started_at = None;
+
+        let policy = process.config.restart.clone();
+        let restart = match policy.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => !exit.success(),
+            RestartMode::Always => true,
+        };
+        let (level, message) = if !restart {
+            process.failure = (!exit.success()).then(|| format!("Exited with {}", exit));
+            let level = if exit.success() { LogLevel::Info } else { LogLevel::Error };
+            (level, format!("Process exited with {}", exit))
+        } else if process.restarts < policy.max_retries {
+            let delay = backoff(&policy, process.restarts);
+            process.restarts += 1;
+            process.restart_at = Some(now + delay);
+            let message = format!(
+                "Process exited with {}; restarting in {:?} (attempt {} of {})",
+                exit, delay, process.restarts, policy.max_retries
+            );
+            (LogLevel::Warn, message)
+        } else {
+            let message = format!("Process exited with {}; gave up after {} restarts", exit, policy.max_retries);
+            process.failure = Some(message.clone());
+            (LogLevel::Error, message)
+        };
+
+        println!("💀 {}: {}", name, message);
+        self.log(name, level, &message);
+        self.emit(name);
+    }
+
+    /// Carry out a management command. Only services the manager knows can
+    /// be stopped, restarted or inspected; starting one registers it.
+    pub fn execute(&mut self, command: ServerCommand) -> Result<ServerResponse, ManagerError> {
+        match command {
+            ServerCommand::StartService(name) => {
+                self.start_service(&name);
+                self.service_info(&name).map(ServerResponse::ServiceStatus)
+            }
+            ServerCommand::StopService(name) => {
+                self.require(&name)?;
+                self.stop_service(&name);
+                self.service_info(&name).map(ServerResponse::ServiceStatus)
+            }
+            ServerCommand::RestartService(name) => {
+                self.require(&name)?;
+                self.stop_service(&name);
+                self.start_service(&name);
+                self.service_info(&name).map(ServerResponse::ServiceStatus)
+            }
+            ServerCommand::GetServiceStatus(name) => self.service_info(&name).map(ServerResponse::ServiceStatus),
+            ServerCommand::GetAllServices => Ok(ServerResponse::AllServices(self.all_services())),
+            ServerCommand::GetLogs { lines, filter } => {
+                for name in &filter.services {
+                    self.require(name)?;
+                }
+                self.search_logs(&filter, lines).map(ServerResponse::Logs)
+            }
+            other => Err(ManagerError::Unsupported(format!("{:?}", other))),
+        }
+    }
+
+    /// Every managed service, by name.
+    pub fn all_services(&self) -> Vec<ManagedService> {
+        let mut names: Vec<&String> = self.services.keys().collect();
+        names.sort();
+        names.into_iter().filter_map(|name| self.service_info(name).ok()).collect()
+    }
+
+    /// The latest `lines` entries in memory passing `filter`, oldest first.
+    pub fn search_logs(&self, filter: &LogFilter, lines: usize) -> Result<Vec<LogEntry>, ManagerError> {
+        let matcher = filter.matcher().map_err(|e| ManagerError::InvalidFilter(e.to_string()))?;
+        let mut entries: Vec<LogEntry> = self
+            .logs
+            .iter()
+            .rev()
+            .filter(|entry| matcher.matches(entry))
+            .take(lines)
+            .cloned()
+            .collect();
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// The latest `lines` entries for one service, oldest first.
+    fn service_logs(&self, name: &str, lines: usize) -> Vec<LogEntry> {
+        let mut entries: Vec<LogEntry> = self
+            .logs
+            .iter()
+            .rev()
+            .filter(|entry| entry.service == name)
+            .take(lines)
+            .cloned()
+            .collect();
+        entries.reverse();
+        entries
+    }
+
+    fn require(&self, name: &str) -> Result<(), ManagerError> {
+        if self.services.contains_key(name) {
+            Ok(())
+        } else {
+            Err(ManagerError::UnknownService(name.to_string()))
+        }
+    }
+
+    fn service_info(&self, name: &str) -> Result<ManagedService, ManagerError> {
+        let status = self
+            .services
+            .get(name)
+            .ok_or_else(|| ManagerError::UnknownService(name.to_string()))?;
+        let process = status.process.as_ref();
+        let state = if status.is_running {
+            RunState::Running
+        } else if let Some(failure) = process.and_then(|process| process.failure.clone()) {
+            RunState::Error(failure)
+        } else if process.is_some_and(|process| process.restart_at.is_some()) {
+            RunState::Starting
+        } else {
+            RunState::Stopped
+        };
+        Ok(ManagedService {
+            name: status.name.clone(),
+            port: 0,
+            status: state,
+            pid: process.and_then(|process| process.child.as_ref()).and_then(|child| child.id()),
+            uptime: status.started_at.map(|at| at.elapsed()).unwrap_or(Duration::ZERO),
+            last_health_check: None,
+            health_status: status.is_running,
+            cpu_usage: 0.0,
+            memory_usage: 0,
+            log_lines: self.service_logs(name, STATUS_LOG_LINES).into(),
+        })
     }
-}
\ No newline at end of file
+
+    fn emit(&self, name: &str) {
+        if let Ok(info) = self.service_info(name) {
+            let _ = self.updates.send(ServerResponse::ServiceStatus(info));
+        }
+    }
+
+    fn log(&mut self, service: &str, level: LogLevel, message: &str) {
+        self.record(LogEntry {
+            timestamp: Utc::now(),
+            level,
+            service: service.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn record(&mut self, entry: LogEntry) {
+        if let Some(files) = &mut self.log_files {
+            if let Err(e) = files.append(&entry) {
+                eprintln!("⚠️  Can't write log file for {}: {}", entry.service, e);
+            }
+        }
+        if self.logs.len() == MAX_LOG_ENTRIES {
+            self.logs.pop_front();
+        }
+        self.logs.push_back(entry);
+    }
+
+    #[cfg(feature = "chaos")]
+    pub fn service_names(&self) -> Vec<String> {
+        self.services.keys().cloned().collect()
+    }
+
+    /// Kill a service's process as if it crashed, leaving restart state
+    /// alone so [`ServerManager::supervise`] applies its restart policy.
+    /// Returns `true` if a process was signalled.
+    #[cfg(feature = "chaos")]
+    pub fn kill_process(&mut self, name: &str) -> bool {
+        let Some(child) = self
+            .services
+            .get_mut(name)
+            .and_then(|status| status.process.as_mut())
+            .and_then(|process| process.child.as_mut())
+        else {
+            return false;
+        };
+        if child.start_kill().is_err() {
+            return false;
+        }
+        self.log(name, LogLevel::Warn, "Process killed by chaos scenario");
+        true
+    }
+}
+
+/// Watch child processes and apply their restart policies.
+pub fn spawn_supervisor(manager: Arc<RwLock<ServerManager>>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SUPERVISE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            manager.write().await.supervise(Instant::now());
+        }
+    });
+}
+
+/// Periodically kill managed services' processes as described by the chaos
+/// scenarios; the supervisor sees a crash and restarts them. A scenario
+/// targeting `*` picks every managed service.
+#[cfg(feature = "chaos")]
+pub fn spawn_chaos_kills(manager: Arc<RwLock<ServerManager>>, injector: &finalverse_chaos::FaultInjector) {
+    for (target, period) in injector.kill_schedule() {
+        let manager = manager.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let mut manager = manager.write().await;
+                let victims = if target == "*" {
+                    manager.service_names()
+                } else {
+                    vec![target.clone()]
+                };
+                for victim in victims {
+                    if manager.kill_process(&victim) {
+                        println!("💥 Chaos killed service {}", victim);
+                    }
+                }
+            }
+        });
+    }
+}
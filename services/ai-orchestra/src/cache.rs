@@ -0,0 +1,287 @@
+// services/ai-orchestra/src/cache.rs
+//! Generated text, remembered so that identical prompts don't go back to
+//! the model. Entries are keyed on a hash of everything that shapes the
+//! output and expire after a TTL, with the least recently used dropped
+//! once the cache is full. Identical requests arriving together wait for
+//! the first of them instead of each calling the model.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use finalverse_metrics::CounterDef;
+use sha2::{Digest, Sha256};
+
+use crate::llm_integration::{GenerationRequest, GenerationResponse};
+
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(3600);
+pub const DEFAULT_CACHE_CAPACITY: usize = 1000;
+
+pub const CACHE_LOOKUPS: CounterDef<2> = CounterDef::new(
+    "finalverse_ai_cache_lookups_total",
+    "Generation requests, by whether a cached response answered them",
+    ["model", "outcome"],
+);
+
+/// The cache key for `request` sent to `model`. Prompts and context that
+/// differ only in whitespace share a key.
+pub fn cache_key(model: &str, request: &GenerationRequest) -> String {
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hasher = Sha256::new();
+    for part in [
+        model.to_string(),
+        normalize(&request.prompt),
+        request.context.as_deref().map(normalize).unwrap_or_default(),
+        format!("{:?}", request.temperature),
+        format!("{:?}", request.max_tokens),
+    ] {
+        // Length-prefixed so parts can't run into each other
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: GenerationResponse,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_key: HashMap<String, Entry>,
+    /// Keys by when they were last used, oldest first.
+    by_use: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl Entries {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.by_key.remove(key) {
+            self.by_use.remove(&entry.last_used);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<Entries>,
+    /// One lock per key being generated, held by whoever is calling the model.
+    in_flight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY, DEFAULT_CACHE_TTL)
+    }
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(Entries::default()),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sized by `AI_CACHE_CAPACITY` entries and `AI_CACHE_TTL_SECS`, when set.
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().and_then(|value| value.parse().ok());
+        Self::new(
+            env("AI_CACHE_CAPACITY").map(|capacity: u64| capacity as usize).unwrap_or(DEFAULT_CACHE_CAPACITY),
+            env("AI_CACHE_TTL_SECS").map(Duration::from_secs).unwrap_or(DEFAULT_CACHE_TTL),
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<GenerationResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        let expired = entries.by_key.get(key)?.stored_at.elapsed() > self.ttl;
+        if expired {
+            entries.remove(key);
+            return None;
+        }
+        let now = entries.tick();
+        let entry = entries.by_key.get_mut(key)?;
+        let previous = std::mem::replace(&mut entry.last_used, now);
+        let response = entry.response.clone();
+        entries.by_use.remove(&previous);
+        entries.by_use.insert(now, key.to_string());
+        Some(response)
+    }
+
+    pub fn insert(&self, key: String, response: GenerationResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.remove(&key);
+        let now = entries.tick();
+        entries.by_use.insert(now, key.clone());
+        entries.by_key.insert(
+            key,
+            Entry {
+                response,
+                stored_at: Instant::now(),
+                last_used: now,
+            },
+        );
+        while entries.by_key.len() > self.capacity {
+            let Some((_, oldest)) = entries.by_use.pop_first() else { break };
+            entries.by_key.remove(&oldest);
+        }
+    }
+
+    /// The cached response for `key`, or else the one `generate` makes,
+    /// which is cached if it succeeds. Also says whether the cache answered.
+    pub async fn get_or_generate<F, Fut, E>(&self, key: String, generate: F) -> Result<(GenerationResponse, bool), E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<GenerationResponse, E>>,
+    {
+        if let Some(response) = self.get(&key) {
+            return Ok((response, true));
+        }
+
+        let slot = InFlight {
+            cache: self,
+            lock: self.in_flight.lock().unwrap().entry(key.clone()).or_default().clone(),
+            key,
+        };
+        let _guard = slot.lock.lock().await;
+        // Whoever held the lock before us may have just filled the cache
+        match self.get(&slot.key) {
+            Some(response) => Ok((response, true)),
+            None => generate().await.map(|response| {
+                self.insert(slot.key.clone(), response.clone());
+                (response, false)
+            }),
+        }
+    }
+}
+
+/// A caller's share of a key's generation lock. Dropping it, on return
+/// or when the caller is cancelled, removes the lock once no one else
+/// holds it.
+struct InFlight<'a> {
+    cache: &'a ResponseCache,
+    key: String,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.cache.in_flight.lock().unwrap();
+        let current = in_flight.get(&self.key).is_some_and(|current| Arc::ptr_eq(current, &self.lock));
+        if current && Arc::strong_count(&self.lock) == 2 {
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn response(text: &str) -> GenerationResponse {
+        GenerationResponse {
+            text: text.to_string(),
+            model_used: "test".to_string(),
+            tokens_used: 1,
+        }
+    }
+
+    fn request(prompt: &str) -> GenerationRequest {
+        GenerationRequest {
+            prompt: prompt.to_string(),
+            context: None,
+            player_id: None,
+            temperature: Some(0.7),
+            max_tokens: None,
+            bypass_cache: false,
+        }
+    }
+
+    #[test]
+    fn keys_ignore_whitespace_but_not_settings() {
+        let key = cache_key("llama2", &request("Describe  the\nforest"));
+        assert_eq!(key, cache_key("llama2", &request("Describe the forest")));
+        assert_ne!(key, cache_key("gpt-4", &request("Describe the forest")));
+        let mut warmer = request("Describe the forest");
+        warmer.temperature = Some(0.9);
+        assert_ne!(key, cache_key("llama2", &warmer));
+    }
+
+    #[test]
+    fn least_recently_used_goes_first() {
+        let cache = ResponseCache::new(2, DEFAULT_CACHE_TTL);
+        cache.insert("a".to_string(), response("a"));
+        cache.insert("b".to_string(), response("b"));
+        assert!(cache.get("a").is_some());
+        cache.insert("c".to_string(), response("c"));
+
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().text, "a");
+        assert_eq!(cache.get("c").unwrap().text, "c");
+    }
+
+    #[test]
+    fn entries_expire_after_the_ttl() {
+        let cache = ResponseCache::new(10, Duration::from_millis(20));
+        cache.insert("a".to_string(), response("a"));
+        assert!(cache.get("a").is_some());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(cache.get("a").is_none());
+        assert!(cache.entries.lock().unwrap().by_use.is_empty());
+    }
+
+    #[tokio::test]
+    async fn identical_requests_share_one_generation() {
+        let cache = Arc::new(ResponseCache::default());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let generate = |cache: Arc<ResponseCache>, calls: Arc<AtomicUsize>| async move {
+            cache
+                .get_or_generate("k".to_string(), || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Ok::<_, ()>(response("shared"))
+                })
+                .await
+                .unwrap()
+        };
+
+        let (first, second) = tokio::join!(
+            generate(cache.clone(), calls.clone()),
+            generate(cache.clone(), calls.clone())
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!((first.1, second.1), (false, true));
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_generations_release_their_lock() {
+        let cache = ResponseCache::default();
+        let stalled = cache.get_or_generate("k".to_string(), std::future::pending::<Result<_, ()>>);
+        assert!(tokio::time::timeout(Duration::from_millis(10), stalled).await.is_err());
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+
+        let (answer, hit) = cache
+            .get_or_generate("k".to_string(), || async { Ok::<_, ()>(response("fresh")) })
+            .await
+            .unwrap();
+        assert_eq!((answer.text.as_str(), hit), ("fresh", false));
+    }
+}
@@ -1,3 +1,5 @@
+mod cache;
+pub mod prompts;
 mod llm_integration;
 
 pub use llm_integration::{LLMOrchestra, GenerationRequest, GenerationResponse};
@@ -2,12 +2,21 @@ use finalverse_core::types::PlayerId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use ort::{Environment, SessionBuilder};
 
+use crate::cache::{cache_key, ResponseCache, CACHE_LOOKUPS};
+use crate::prompts::{self, PromptLibrary, WorldContext};
+
 #[derive(Debug, Clone)]
 pub struct LLMOrchestra {
     models: HashMap<String, LLMProvider>,
     default_model: String,
+    cache: Arc<ResponseCache>,
+    client: reqwest::Client,
+    /// Bounds the provider calls in flight; see `with_limits`.
+    permits: Arc<Semaphore>,
 }
 
 #[derive(Debug, Clone)]
@@ -20,6 +29,19 @@ pub enum LLMProvider {
     Mistral(MistralProvider),
 }
 
+impl LLMProvider {
+    pub fn model_name(&self) -> &str {
+        match self {
+            LLMProvider::Ollama(p) => &p.model_name,
+            LLMProvider::OpenAI(p) => &p.model_name,
+            LLMProvider::Local(p) => &p.model_path,
+            LLMProvider::Claude(p) => &p.model_name,
+            LLMProvider::Gemini(p) => &p.model_name,
+            LLMProvider::Mistral(p) => &p.model_name,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OllamaProvider {
     base_url: String,
@@ -78,9 +100,14 @@ pub struct GenerationRequest {
     pub player_id: Option<PlayerId>,
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
+    /// Always ask the model, even if an identical request was answered
+    /// recently. The fresh answer still replaces the cached one. Over
+    /// `/api/generate` only operators may set this.
+    #[serde(default)]
+    pub bypass_cache: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationResponse {
     pub text: String,
     pub model_used: String,
@@ -230,9 +257,23 @@ impl LLMOrchestra {
         Self {
             models,
             default_model,
+            cache: Arc::new(ResponseCache::from_env()),
+            client: reqwest::Client::new(),
+            permits: Arc::new(Semaphore::new(Semaphore::MAX_PERMITS)),
         }
     }
 
+    /// Give up on a provider call after `timeout`, and make calls beyond
+    /// `max_concurrent` wait for one in flight to finish.
+    pub fn with_limits(mut self, timeout: Duration, max_concurrent: usize) -> Self {
+        self.client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("HTTP client with a timeout builds");
+        self.permits = Arc::new(Semaphore::new(max_concurrent));
+        self
+    }
+
     pub fn add_provider(&mut self, name: String, provider: LLMProvider) {
         self.models.insert(name, provider);
     }
@@ -240,7 +281,28 @@ impl LLMOrchestra {
     pub async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
         let provider = self.models.get(&self.default_model)
             .ok_or("Default model not found")?;
+        let model = provider.model_name().to_string();
+        let key = cache_key(&model, &request);
+
+        if request.bypass_cache {
+            finalverse_metrics::counter!(CACHE_LOOKUPS, model = model, outcome = "bypass").increment(1);
+            let response = self.generate_with(provider, request).await?;
+            self.cache.insert(key, response.clone());
+            return Ok(response);
+        }
 
+        let (response, hit) = self.cache.get_or_generate(key, || self.generate_with(provider, request)).await?;
+        let outcome = if hit { "hit" } else { "miss" };
+        finalverse_metrics::counter!(CACHE_LOOKUPS, model = model, outcome = outcome).increment(1);
+        Ok(response)
+    }
+
+    async fn generate_with(
+        &self,
+        provider: &LLMProvider,
+        request: GenerationRequest,
+    ) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let _permit = self.permits.acquire().await?;
         match provider {
             LLMProvider::Ollama(ollama) => self.generate_ollama(ollama, request).await,
             LLMProvider::OpenAI(openai) => self.generate_openai(openai, request).await,
@@ -256,8 +318,8 @@ impl LLMOrchestra {
         provider: &OllamaProvider,
         request: GenerationRequest,
     ) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
-        let client = reqwest::Client::new();
-        
+        let client = &self.client;
+
         let ollama_request = OllamaRequest {
             model: provider.model_name.clone(),
             prompt: request.prompt,
@@ -291,8 +353,8 @@ impl LLMOrchestra {
         provider: &OpenAIProvider,
         request: GenerationRequest,
     ) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
-        let client = reqwest::Client::new();
-        
+        let client = &self.client;
+
         let messages = vec![OpenAIMessage {
             role: "user".to_string(),
             content: request.prompt,
@@ -334,7 +396,7 @@ impl LLMOrchestra {
         provider: &ClaudeProvider,
         request: GenerationRequest,
     ) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
-        let client = reqwest::Client::new();
+        let client = &self.client;
 
         let messages = vec![OpenAIMessage {
             role: "user".to_string(),
@@ -376,7 +438,7 @@ impl LLMOrchestra {
         provider: &GeminiProvider,
         request: GenerationRequest,
     ) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
-        let client = reqwest::Client::new();
+        let client = &self.client;
 
         let messages = vec![OpenAIMessage {
             role: "user".to_string(),
@@ -418,7 +480,7 @@ impl LLMOrchestra {
         provider: &MistralProvider,
         request: GenerationRequest,
     ) -> Result<GenerationResponse, Box<dyn std::error::Error + Send + Sync>> {
-        let client = reqwest::Client::new();
+        let client = &self.client;
 
         let messages = vec![OpenAIMessage {
             role: "user".to_string(),
@@ -472,28 +534,31 @@ impl LLMOrchestra {
     }
 }
 
-// Narrative AI functions
+// Narrative AI functions, prompted from the templates in `prompts`
 pub async fn generate_quest_narrative(
     orchestra: &LLMOrchestra,
+    prompts: &PromptLibrary,
+    world: &WorldContext,
     player_context: &str,
     world_state: &str,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let prompt = format!(
-        "Generate a quest narrative for Finalverse based on the following context:\n\
-        Player Context: {}\n\
-        World State: {}\n\n\
-        The quest should involve the Song of Creation and align with the principles of \
-        Symbiotic Creation, Empathetic Exploration, or Living Wonder. \
-        Keep it engaging and age-appropriate.",
-        player_context, world_state
-    );
+    let prompt = prompts.render(
+        prompts::QUEST_NARRATIVE,
+        None,
+        world,
+        HashMap::from([
+            ("player_context".to_string(), player_context.to_string()),
+            ("world_state".to_string(), world_state.to_string()),
+        ]),
+    )?;
 
     let request = GenerationRequest {
-        prompt,
+        prompt: prompt.text,
         context: None,
         player_id: None,
         temperature: Some(0.8),
         max_tokens: Some(1024),
+        bypass_cache: false,
     };
 
     let response = orchestra.generate(request).await?;
@@ -502,63 +567,63 @@ pub async fn generate_quest_narrative(
 
 pub async fn generate_npc_dialogue(
     orchestra: &LLMOrchestra,
+    prompts: &PromptLibrary,
+    world: &WorldContext,
     npc_personality: &str,
     conversation_context: &str,
     player_history: &str,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let prompt = format!(
-        "Generate dialogue for an NPC in Finalverse with the following personality: {}\n\
-        Conversation Context: {}\n\
-        Player History: {}\n\n\
-        The dialogue should be consistent with the character's personality and \
-        acknowledge the player's past actions. Keep it natural and engaging.",
-        npc_personality, conversation_context, player_history
-    );
+    let prompt = prompts.render(
+        prompts::NPC_DIALOGUE,
+        None,
+        world,
+        HashMap::from([
+            ("personality".to_string(), npc_personality.to_string()),
+            ("conversation_context".to_string(), conversation_context.to_string()),
+            ("player_history".to_string(), player_history.to_string()),
+        ]),
+    )?;
 
     let request = GenerationRequest {
-        prompt,
+        prompt: prompt.text,
         context: None,
         player_id: None,
         temperature: Some(0.7),
         max_tokens: Some(512),
+        bypass_cache: false,
     };
 
     let response = orchestra.generate(request).await?;
     Ok(response.text)
 }
 
+/// `world.harmony_level` is the region's, which decides how it is described.
 pub async fn generate_world_description(
     orchestra: &LLMOrchestra,
+    prompts: &PromptLibrary,
+    world: &WorldContext,
     region_name: &str,
-    harmony_level: f32,
     time_of_day: &str,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let harmony_description = if harmony_level > 0.8 {
-        "high harmony with vibrant colors and flourishing life"
-    } else if harmony_level > 0.5 {
-        "moderate harmony with gentle signs of the Song's presence"
-    } else if harmony_level > 0.2 {
-        "low harmony with muted colors and signs of the Silence's influence"
-    } else {
-        "very low harmony with corruption and decay from the Silence"
-    };
-
-    let prompt = format!(
-        "Describe the region '{}' in Finalverse during {} with {}. \
-        The description should capture the visual beauty or corruption, \
-        the sounds of the Song or Silence, and the overall atmosphere. \
-        Make it immersive and poetic, suitable for all ages.",
-        region_name, time_of_day, harmony_description
-    );
+    let prompt = prompts.render(
+        prompts::WORLD_DESCRIPTION,
+        None,
+        world,
+        HashMap::from([
+            ("region_name".to_string(), region_name.to_string()),
+            ("time_of_day".to_string(), time_of_day.to_string()),
+        ]),
+    )?;
 
     let request = GenerationRequest {
-        prompt,
+        prompt: prompt.text,
         context: None,
         player_id: None,
         temperature: Some(0.9),
         max_tokens: Some(768),
+        bypass_cache: false,
     };
 
     let response = orchestra.generate(request).await?;
     Ok(response.text)
-}
\ No newline at end of file
+}
@@ -1,47 +1,49 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::post,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
     Router,
 };
-use finalverse_health::HealthMonitor;
+use finalverse_health::{require_operator, DebugEndpoints, HealthMonitor, OperatorGuard};
 use service_registry::LocalServiceRegistry;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use finalverse_logging as logging;
 use std::{
+    collections::HashMap,
     net::SocketAddr,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tokio;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 
+mod cache;
+mod prompts;
 mod llm_integration;
 pub use llm_integration::{LLMOrchestra, GenerationRequest, GenerationResponse};
+use prompts::{PromptError, PromptLibrary, PromptTemplate, RenderedPrompt, WorldContext};
 
 #[derive(Debug, Clone)]
 pub struct AIState {
     orchestra: LLMOrchestra,
+    prompts: Arc<PromptLibrary>,
+    /// Checks callers asking to skip the response cache.
+    operator: OperatorGuard,
     active_sessions: u32,
 }
 
 type SharedAIState = Arc<RwLock<AIState>>;
 
-#[derive(Serialize)]
-struct ServiceInfo {
-    name: String,
-    version: String,
-    status: String,
-    active_sessions: u32,
-}
-
 #[derive(Deserialize)]
 struct QuestGenerationRequest {
     player_context: String,
     world_state: String,
     quest_type: Option<String>,
+    #[serde(default)]
+    world: WorldContext,
 }
 
 #[derive(Serialize)]
@@ -57,6 +59,8 @@ struct DialogueRequest {
     personality: String,
     conversation_context: String,
     player_history: String,
+    #[serde(default)]
+    world: WorldContext,
 }
 
 #[derive(Serialize)]
@@ -72,6 +76,19 @@ struct WorldDescriptionRequest {
     harmony_level: f32,
     time_of_day: String,
     weather: Option<String>,
+    /// Harmony here is taken from `harmony_level`.
+    #[serde(default)]
+    world: WorldContext,
+}
+
+/// A prompt to render without sending it to a model.
+#[derive(Deserialize)]
+struct PromptPreviewRequest {
+    version: Option<u32>,
+    #[serde(default)]
+    variables: HashMap<String, String>,
+    #[serde(default)]
+    world: WorldContext,
 }
 
 #[derive(Serialize)]
@@ -82,9 +99,11 @@ struct WorldDescriptionResponse {
 }
 
 impl AIState {
-    pub fn new() -> Self {
+    pub fn new(prompts: PromptLibrary, orchestra: LLMOrchestra, operator: OperatorGuard) -> Self {
         Self {
-            orchestra: LLMOrchestra::new(),
+            orchestra,
+            prompts: Arc::new(prompts),
+            operator,
             active_sessions: 0,
         }
     }
@@ -93,15 +112,22 @@ impl AIState {
 
 async fn generate_text(
     State(state): State<SharedAIState>,
+    headers: HeaderMap,
     Json(request): Json<GenerationRequest>,
-) -> impl IntoResponse {
-    let orchestra = {
+) -> Response {
+    let (orchestra, operator) = {
         let ai_state = state.read().unwrap();
-        ai_state.orchestra.clone()
+        (ai_state.orchestra.clone(), ai_state.operator.clone())
     };
+    // Skipping the cache costs a model call every time, so only operators may
+    if request.bypass_cache {
+        if let Err(e) = operator.check_headers(&headers) {
+            return e.into_response();
+        }
+    }
 
     match orchestra.generate(request).await {
-        Ok(response) => (StatusCode::OK, Json(response)),
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(GenerationResponse {
@@ -109,7 +135,8 @@ async fn generate_text(
                 model_used: "error".to_string(),
                 tokens_used: 0,
             }),
-        ),
+        )
+            .into_response(),
     }
 }
 
@@ -117,13 +144,15 @@ async fn generate_quest(
     State(state): State<SharedAIState>,
     Json(request): Json<QuestGenerationRequest>,
 ) -> impl IntoResponse {
-    let orchestra = {
+    let (orchestra, prompts) = {
         let ai_state = state.read().unwrap();
-        ai_state.orchestra.clone()
+        (ai_state.orchestra.clone(), ai_state.prompts.clone())
     };
 
     match llm_integration::generate_quest_narrative(
         &orchestra,
+        &prompts,
+        &request.world,
         &request.player_context,
         &request.world_state,
     ).await {
@@ -153,13 +182,15 @@ async fn generate_dialogue(
     State(state): State<SharedAIState>,
     Json(request): Json<DialogueRequest>,
 ) -> impl IntoResponse {
-    let orchestra = {
+    let (orchestra, prompts) = {
         let ai_state = state.read().unwrap();
-        ai_state.orchestra.clone()
+        (ai_state.orchestra.clone(), ai_state.prompts.clone())
     };
 
     match llm_integration::generate_npc_dialogue(
         &orchestra,
+        &prompts,
+        &request.world,
         &request.personality,
         &request.conversation_context,
         &request.player_history,
@@ -191,15 +222,20 @@ async fn generate_world_description(
     State(state): State<SharedAIState>,
     Json(request): Json<WorldDescriptionRequest>,
 ) -> impl IntoResponse {
-    let orchestra = {
+    let (orchestra, prompts) = {
         let ai_state = state.read().unwrap();
-        ai_state.orchestra.clone()
+        (ai_state.orchestra.clone(), ai_state.prompts.clone())
     };
 
+    let world = WorldContext {
+        harmony_level: Some(request.harmony_level),
+        ..request.world
+    };
     match llm_integration::generate_world_description(
         &orchestra,
+        &prompts,
+        &world,
         &request.region_name,
-        request.harmony_level,
         &request.time_of_day,
     ).await {
         Ok(description) => (
@@ -229,30 +265,88 @@ async fn generate_world_description(
     }
 }
 
+fn prompt_library(state: &SharedAIState) -> Arc<PromptLibrary> {
+    state.read().unwrap().prompts.clone()
+}
+
+/// The latest version of every prompt template.
+async fn list_prompts(State(state): State<SharedAIState>) -> Json<Vec<PromptTemplate>> {
+    Json(prompt_library(&state).list())
+}
+
+async fn prompt_versions(
+    State(state): State<SharedAIState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<PromptTemplate>>, PromptError> {
+    prompt_library(&state).versions(&id).map(Json)
+}
+
+/// Store a new version of a template; prompts use it from then on.
+async fn save_prompt(
+    State(state): State<SharedAIState>,
+    Json(template): Json<PromptTemplate>,
+) -> Result<(StatusCode, Json<PromptTemplate>), PromptError> {
+    let saved = prompt_library(&state).save(template)?;
+    info!("Saved prompt template {} version {}", saved.id, saved.version);
+    Ok((StatusCode::CREATED, Json(saved)))
+}
+
+async fn preview_prompt(
+    State(state): State<SharedAIState>,
+    Path(id): Path<String>,
+    Json(request): Json<PromptPreviewRequest>,
+) -> Result<Json<RenderedPrompt>, PromptError> {
+    prompt_library(&state)
+        .render(&id, request.version, &request.world, request.variables)
+        .map(Json)
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
-    let state = Arc::new(RwLock::new(AIState::new()));
+    let metrics = finalverse_metrics::init("ai-orchestra")?;
+    cache::CACHE_LOOKUPS.describe();
+    let settings = finalverse_config::load_default_config()
+        .map(|config| config.ai_orchestra)
+        .unwrap_or_default();
+    let orchestra = LLMOrchestra::new().with_limits(
+        Duration::from_secs(settings.request_timeout_secs),
+        settings.max_concurrent_requests,
+    );
+    let prompts = PromptLibrary::load(settings.prompts_dir.as_deref())?;
+    let operator = OperatorGuard::load();
+    let state = Arc::new(RwLock::new(AIState::new(prompts, orchestra, operator.clone())));
     let monitor = Arc::new(HealthMonitor::new("ai-orchestra", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
-        .register_service("ai-orchestra".to_string(), "http://localhost:3004".to_string())
+        .register_service("ai-orchestra".to_string(), format!("http://localhost:{}", settings.http_port))
         .await;
 
+    // Prompt templates steer every generation, so only operators see and
+    // edit them
+    let admin_routes = Router::new()
+        .route("/admin/prompts", get(list_prompts).post(save_prompt))
+        .route("/admin/prompts/:id", get(prompt_versions))
+        .route("/admin/prompts/:id/preview", post(preview_prompt))
+        .route_layer(axum::middleware::from_fn_with_state(operator, require_operator));
+
     let app = Router::new()
         .route("/api/generate", post(generate_text))
         .route("/api/quest", post(generate_quest))
         .route("/api/dialogue", post(generate_dialogue))
         .route("/api/world-description", post(generate_world_description))
+        .merge(admin_routes)
         .with_state(state.clone())
         .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("ai-orchestra")).axum_routes())
+        .merge(metrics.axum_routes())
         .layer(
             ServiceBuilder::new()
                 .layer(CorsLayer::permissive())
                 .into_inner(),
         );
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3004));
+    let addr = SocketAddr::from(([0, 0, 0, 0], settings.http_port));
     info!("AI Orchestra listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -0,0 +1,349 @@
+// services/ai-orchestra/src/prompts.rs
+//! Prompt templates. A template is text with `{{variable}}` placeholders,
+//! filled from the request and from the state of the world around it:
+//! harmony, region and the player's Echo bonds. Every change to a template
+//! is kept as a new version, so edits can be previewed and rolled back.
+//! Templates come built in and from JSON files in the configured prompts
+//! directory (`[ai_orchestra] prompts_dir`), where new versions are also
+//! written.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use thiserror::Error;
+
+pub const QUEST_NARRATIVE: &str = "quest_narrative";
+pub const NPC_DIALOGUE: &str = "npc_dialogue";
+pub const WORLD_DESCRIPTION: &str = "world_description";
+
+#[derive(Debug, Error)]
+pub enum PromptError {
+    #[error("Unknown prompt template: {0}")]
+    UnknownTemplate(String),
+
+    #[error("Prompt template {id} has no version {version}")]
+    UnknownVersion { id: String, version: u32 },
+
+    #[error("Prompt template {id} needs a value for {variable}")]
+    MissingVariable { id: String, variable: String },
+
+    #[error("Invalid template id {0:?}: use lowercase letters, digits, '_' and '-'")]
+    InvalidId(String),
+
+    #[error("Can't store prompt template: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid prompt template file: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl IntoResponse for PromptError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PromptError::UnknownTemplate(_) | PromptError::UnknownVersion { .. } => StatusCode::NOT_FOUND,
+            PromptError::MissingVariable { .. } | PromptError::InvalidId(_) => StatusCode::BAD_REQUEST,
+            PromptError::Io(_) | PromptError::Parse(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    /// Assigned when the template is saved.
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub description: String,
+    pub text: String,
+    /// Values for variables a request may leave out.
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+}
+
+impl PromptTemplate {
+    fn new(id: &str, description: &str, text: &str, optional: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            version: 1,
+            description: description.to_string(),
+            text: text.to_string(),
+            defaults: optional.iter().map(|name| (name.to_string(), String::new())).collect(),
+        }
+    }
+
+    /// Fill in the placeholders, from `variables` or else the defaults.
+    pub fn render(&self, variables: &HashMap<String, String>) -> Result<String, PromptError> {
+        let mut rendered = String::with_capacity(self.text.len());
+        let mut rest = self.text.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some((name, after)) = next_placeholder(rest) else { break };
+            let value = variables
+                .get(name)
+                .or_else(|| self.defaults.get(name))
+                .ok_or_else(|| PromptError::MissingVariable {
+                    id: self.id.clone(),
+                    variable: name.to_string(),
+                })?;
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(value);
+            rest = after;
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+/// The name in the first `{{name}}` of `text`, and the text after it.
+fn next_placeholder(text: &str) -> Option<(&str, &str)> {
+    let start = text.find("{{")?;
+    let end = start + text[start..].find("}}")?;
+    Some((text[start + 2..end].trim(), &text[end + 2..]))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EchoBond {
+    pub echo: String,
+    pub level: u32,
+}
+
+/// What a prompt should know about the world it is set in. Everything is
+/// optional; whatever is known becomes template variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorldContext {
+    /// From 0 to 1.
+    pub harmony_level: Option<f32>,
+    pub region: Option<String>,
+    #[serde(default)]
+    pub echo_bonds: Vec<EchoBond>,
+}
+
+pub fn harmony_description(harmony_level: f32) -> &'static str {
+    if harmony_level > 0.8 {
+        "high harmony with vibrant colors and flourishing life"
+    } else if harmony_level > 0.5 {
+        "moderate harmony with gentle signs of the Song's presence"
+    } else if harmony_level > 0.2 {
+        "low harmony with muted colors and signs of the Silence's influence"
+    } else {
+        "very low harmony with corruption and decay from the Silence"
+    }
+}
+
+impl WorldContext {
+    /// `region`, `harmony_level`, `harmony_description` and `echo_bonds`
+    /// for whatever is known, and `world_context`, a few sentences
+    /// summing it all up.
+    pub fn variables(&self) -> HashMap<String, String> {
+        let mut variables = HashMap::new();
+        let mut summary = Vec::new();
+        if let Some(region) = &self.region {
+            variables.insert("region".to_string(), region.clone());
+            summary.push(format!("This takes place in {}.", region));
+        }
+        if let Some(level) = self.harmony_level {
+            let description = harmony_description(level);
+            variables.insert("harmony_level".to_string(), format!("{:.0}%", level * 100.0));
+            variables.insert("harmony_description".to_string(), description.to_string());
+            summary.push(format!("The land shows {}.", description));
+        }
+        if !self.echo_bonds.is_empty() {
+            let bonds = self
+                .echo_bonds
+                .iter()
+                .map(|bond| format!("{} (bond level {})", bond.echo, bond.level))
+                .collect::<Vec<_>>()
+                .join(", ");
+            summary.push(format!("The player is bonded with {}.", bonds));
+            variables.insert("echo_bonds".to_string(), bonds);
+        }
+        variables.insert("world_context".to_string(), summary.join(" "));
+        variables
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedPrompt {
+    pub id: String,
+    pub version: u32,
+    pub text: String,
+}
+
+/// Every version of every template.
+#[derive(Debug)]
+pub struct PromptLibrary {
+    /// Oldest version first.
+    templates: RwLock<HashMap<String, Vec<PromptTemplate>>>,
+    dir: Option<PathBuf>,
+}
+
+impl PromptLibrary {
+    /// The prompts the orchestra has always used.
+    pub fn builtin() -> Self {
+        let templates = [
+            PromptTemplate::new(
+                QUEST_NARRATIVE,
+                "Quest narratives for /api/quest",
+                "Generate a quest narrative for Finalverse based on the following context:\n\
+                 Player Context: {{player_context}}\n\
+                 World State: {{world_state}}\n\
+                 {{world_context}}\n\n\
+                 The quest should involve the Song of Creation and align with the principles of \
+                 Symbiotic Creation, Empathetic Exploration, or Living Wonder. \
+                 Keep it engaging and age-appropriate.\n\n\
+                 Start with the quest's title as a '# ' heading, then a short paragraph of story, \
+                 then two to four objectives as a '- ' list. Mark any objective the player may skip \
+                 with (optional).",
+                &["world_context"],
+            ),
+            PromptTemplate::new(
+                NPC_DIALOGUE,
+                "NPC lines for /api/dialogue",
+                "Generate dialogue for an NPC in Finalverse with the following personality: {{personality}}\n\
+                 Conversation Context: {{conversation_context}}\n\
+                 Player History: {{player_history}}\n\
+                 {{world_context}}\n\n\
+                 The dialogue should be consistent with the character's personality and \
+                 acknowledge the player's past actions. Keep it natural and engaging.",
+                &["world_context"],
+            ),
+            PromptTemplate::new(
+                WORLD_DESCRIPTION,
+                "Region descriptions for /api/world-description",
+                "Describe the region '{{region_name}}' in Finalverse during {{time_of_day}} with {{harmony_description}}. \
+                 {{world_context}}\n\
+                 The description should capture the visual beauty or corruption, \
+                 the sounds of the Song or Silence, and the overall atmosphere. \
+                 Make it immersive and poetic, suitable for all ages.",
+                &["world_context"],
+            ),
+        ];
+        Self {
+            templates: RwLock::new(templates.into_iter().map(|template| (template.id.clone(), vec![template])).collect()),
+            dir: None,
+        }
+    }
+
+    /// Load the versions stored in `dir` over the built-in ones, and store
+    /// new versions there.
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Result<Self, PromptError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                let template: PromptTemplate = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+                self.insert(template);
+            }
+        }
+        self.dir = Some(dir);
+        Ok(self)
+    }
+
+    /// Built-in templates, plus those in `dir` when one is configured.
+    pub fn load(dir: Option<&str>) -> Result<Self, PromptError> {
+        match dir {
+            Some(dir) => Self::builtin().with_dir(dir),
+            None => Ok(Self::builtin()),
+        }
+    }
+
+    fn insert(&mut self, template: PromptTemplate) {
+        let versions = self.templates.get_mut().unwrap().entry(template.id.clone()).or_default();
+        versions.retain(|existing| existing.version != template.version);
+        versions.push(template);
+        versions.sort_by_key(|template| template.version);
+    }
+
+    /// The latest version of each template.
+    pub fn list(&self) -> Vec<PromptTemplate> {
+        let mut latest: Vec<PromptTemplate> = self
+            .templates
+            .read()
+            .unwrap()
+            .values()
+            .filter_map(|versions| versions.last().cloned())
+            .collect();
+        latest.sort_by(|a, b| a.id.cmp(&b.id));
+        latest
+    }
+
+    pub fn versions(&self, id: &str) -> Result<Vec<PromptTemplate>, PromptError> {
+        self.templates
+            .read()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| PromptError::UnknownTemplate(id.to_string()))
+    }
+
+    /// A version of a template, the latest if `version` is None.
+    pub fn get(&self, id: &str, version: Option<u32>) -> Result<PromptTemplate, PromptError> {
+        let templates = self.templates.read().unwrap();
+        let versions = templates.get(id).ok_or_else(|| PromptError::UnknownTemplate(id.to_string()))?;
+        match version {
+            None => versions.last().cloned().ok_or_else(|| PromptError::UnknownTemplate(id.to_string())),
+            Some(version) => versions
+                .iter()
+                .find(|template| template.version == version)
+                .cloned()
+                .ok_or_else(|| PromptError::UnknownVersion {
+                    id: id.to_string(),
+                    version,
+                }),
+        }
+    }
+
+    /// Store `template` as the next version of its id, which becomes the
+    /// one prompts are rendered from.
+    pub fn save(&self, mut template: PromptTemplate) -> Result<PromptTemplate, PromptError> {
+        let valid_id = !template.id.is_empty()
+            && template
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid_id {
+            return Err(PromptError::InvalidId(template.id));
+        }
+
+        let mut templates = self.templates.write().unwrap();
+        let versions = templates.entry(template.id.clone()).or_default();
+        template.version = versions.last().map_or(1, |latest| latest.version + 1);
+        if let Some(dir) = &self.dir {
+            write_template(dir, &template)?;
+        }
+        versions.push(template.clone());
+        Ok(template)
+    }
+
+    /// Render a template with the world's variables, overridden by the
+    /// request's own.
+    pub fn render(
+        &self,
+        id: &str,
+        version: Option<u32>,
+        world: &WorldContext,
+        variables: HashMap<String, String>,
+    ) -> Result<RenderedPrompt, PromptError> {
+        let template = self.get(id, version)?;
+        let mut all = world.variables();
+        all.extend(variables);
+        Ok(RenderedPrompt {
+            text: template.render(&all)?,
+            id: template.id,
+            version: template.version,
+        })
+    }
+}
+
+fn write_template(dir: &Path, template: &PromptTemplate) -> Result<(), PromptError> {
+    let path = dir.join(format!("{}.v{}.json", template.id, template.version));
+    std::fs::write(path, serde_json::to_string_pretty(template)?)?;
+    Ok(())
+}
@@ -0,0 +1,360 @@
+// services/api-gateway/src/accounts.rs
+//! Account linking: the devices a player signs in from, their refresh
+//! tokens and the sessions currently open on them.
+
+use chrono::{DateTime, Duration, Utc};
+use finalverse_auth::JwtAuth;
+use finalverse_config::{SessionLimitAction, SessionPolicyConfig};
+use finalverse_core::{PlayerId, TenantId};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+const TOKEN_LENGTH: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    TextViewer,
+    Desktop3d,
+    Mobile,
+    Web,
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::TextViewer => "text_viewer",
+            Platform::Desktop3d => "desktop3d",
+            Platform::Mobile => "mobile",
+            Platform::Web => "web",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AccountError {
+    #[error("Invalid or expired token")]
+    InvalidToken,
+
+    #[error("Unknown device")]
+    UnknownDevice,
+
+    #[error("Unknown session")]
+    UnknownSession,
+
+    #[error("Account already has {0} linked devices")]
+    TooManyDevices(usize),
+
+    #[error("Account already has {0} active sessions")]
+    TooManySessions(usize),
+}
+
+/// What a client tells us about itself when signing in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceRegistration {
+    /// Previously issued device id; a new device is linked when unset.
+    pub device_id: Option<Uuid>,
+    pub platform: Platform,
+    #[serde(default)]
+    pub name: String,
+}
+
+impl Default for DeviceRegistration {
+    fn default() -> Self {
+        Self {
+            device_id: None,
+            platform: Platform::Web,
+            name: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Device {
+    pub id: Uuid,
+    pub platform: Platform,
+    pub name: String,
+    pub linked_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    #[serde(skip)]
+    refresh_token: Option<String>,
+    pub refresh_expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub device_id: Uuid,
+    pub platform: Platform,
+    pub started_at: DateTime<Utc>,
+    pub last_active: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct Account {
+    id: Uuid,
+    devices: Vec<Device>,
+    sessions: Vec<Session>,
+}
+
+/// Tokens handed to a client after a login or refresh.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionGrant {
+    pub account_id: Uuid,
+    pub device_id: Uuid,
+    pub session_id: Uuid,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+    /// Sessions ended to make room for this one.
+    pub kicked_sessions: Vec<Uuid>,
+}
+
+pub struct AccountStore {
+    policy: SessionPolicyConfig,
+    tokens: JwtAuth,
+    accounts: HashMap<Uuid, Account>,
+}
+
+impl AccountStore {
+    pub fn new(policy: SessionPolicyConfig, tokens: JwtAuth) -> Self {
+        Self {
+            policy,
+            tokens,
+            accounts: HashMap::new(),
+        }
+    }
+
+    /// Sign in from a device, linking it to the account if it is new.
+    /// Signing in again from a linked device replaces its session and
+    /// refresh token. The account's password is checked by the caller.
+    pub fn login(
+        &mut self,
+        account_id: Uuid,
+        registration: DeviceRegistration,
+        tenant: &TenantId,
+    ) -> Result<SessionGrant, AccountError> {
+        let account = self.accounts.entry(account_id).or_insert_with(|| Account {
+            id: account_id,
+            devices: Vec::new(),
+            sessions: Vec::new(),
+        });
+        let now = Utc::now();
+
+        let device_id = match registration
+            .device_id
+            .filter(|id| account.devices.iter().any(|device| device.id == *id))
+        {
+            Some(id) => id,
+            None => {
+                if account.devices.len() >= self.policy.max_devices_per_account {
+                    return Err(AccountError::TooManyDevices(account.devices.len()));
+                }
+                let device = Device {
+                    id: Uuid::new_v4(),
+                    platform: registration.platform,
+                    name: registration.name,
+                    linked_at: now,
+                    last_seen: now,
+                    refresh_token: None,
+                    refresh_expires_at: None,
+                };
+                let id = device.id;
+                account.devices.push(device);
+                id
+            }
+        };
+
+        open_session(account, device_id, &self.policy, &self.tokens, tenant, now)
+    }
+
+    /// Exchange a device's refresh token for a new session. The refresh
+    /// token is rotated; the old one stops working.
+    pub fn refresh(&mut self, refresh_token: &str, tenant: &TenantId) -> Result<SessionGrant, AccountError> {
+        let now = Utc::now();
+        let (account, device_id) = self
+            .accounts
+            .values_mut()
+            .find_map(|account| {
+                let device_id = account
+                    .devices
+                    .iter()
+                    .find(|device| device.refresh_token.as_deref() == Some(refresh_token))
+                    .map(|device| device.id)?;
+                Some((account, device_id))
+            })
+            .ok_or(AccountError::InvalidToken)?;
+
+        let device = account
+            .devices
+            .iter()
+            .find(|device| device.id == device_id)
+            .ok_or(AccountError::UnknownDevice)?;
+        if device.refresh_expires_at.is_none_or(|expires| expires <= now) {
+            return Err(AccountError::InvalidToken);
+        }
+
+        open_session(account, device_id, &self.policy, &self.tokens, tenant, now)
+    }
+
+    /// Resolve an access token to its account and session. The token
+    /// must verify and its session must still be open, so ending a session
+    /// or revoking a device takes effect here immediately; other services
+    /// accept the token until it expires.
+    pub fn authenticate(&mut self, access_token: &str) -> Result<(Uuid, Uuid), AccountError> {
+        let claims = self.tokens.verify(access_token).map_err(|_| AccountError::InvalidToken)?;
+        let now = Utc::now();
+        let account = self.accounts.get_mut(&claims.sub).ok_or(AccountError::InvalidToken)?;
+        account.sessions.retain(|session| session.expires_at > now);
+        let session = account
+            .sessions
+            .iter_mut()
+            .find(|session| session.id == claims.sid)
+            .ok_or(AccountError::InvalidToken)?;
+        session.last_active = now;
+        let device_id = session.device_id;
+        if let Some(device) = account.devices.iter_mut().find(|device| device.id == device_id) {
+            device.last_seen = now;
+        }
+        Ok((account.id, session.id))
+    }
+
+    pub fn devices(&self, account_id: Uuid) -> Vec<Device> {
+        self.accounts
+            .get(&account_id)
+            .map(|account| account.devices.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn sessions(&self, account_id: Uuid) -> Vec<Session> {
+        let now = Utc::now();
+        self.accounts
+            .get(&account_id)
+            .map(|account| {
+                account
+                    .sessions
+                    .iter()
+                    .filter(|session| session.expires_at > now)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn terminate_session(&mut self, account_id: Uuid, session_id: Uuid) -> Result<Session, AccountError> {
+        let account = self.accounts.get_mut(&account_id).ok_or(AccountError::UnknownSession)?;
+        let index = account
+            .sessions
+            .iter()
+            .position(|session| session.id == session_id)
+            .ok_or(AccountError::UnknownSession)?;
+        Ok(account.sessions.remove(index))
+    }
+
+    /// Revoke a device's refresh token and end its session without
+    /// affecting the account's other devices.
+    pub fn revoke_device(&mut self, account_id: Uuid, device_id: Uuid) -> Result<(), AccountError> {
+        let account = self.accounts.get_mut(&account_id).ok_or(AccountError::UnknownDevice)?;
+        let device = account
+            .devices
+            .iter_mut()
+            .find(|device| device.id == device_id)
+            .ok_or(AccountError::UnknownDevice)?;
+        device.refresh_token = None;
+        device.refresh_expires_at = None;
+        account.sessions.retain(|session| session.device_id != device_id);
+        Ok(())
+    }
+
+    /// Revoke every device's refresh token and end every session, after
+    /// a password reset say. Devices stay linked.
+    pub fn revoke_all(&mut self, account_id: Uuid) {
+        if let Some(account) = self.accounts.get_mut(&account_id) {
+            for device in &mut account.devices {
+                device.refresh_token = None;
+                device.refresh_expires_at = None;
+            }
+            account.sessions.clear();
+        }
+    }
+
+    /// Revoke a device and remove it from the account entirely.
+    pub fn unlink_device(&mut self, account_id: Uuid, device_id: Uuid) -> Result<(), AccountError> {
+        self.revoke_device(account_id, device_id)?;
+        if let Some(account) = self.accounts.get_mut(&account_id) {
+            account.devices.retain(|device| device.id != device_id);
+        }
+        Ok(())
+    }
+}
+
+/// Start a session on a device, applying the concurrent-session policy.
+fn open_session(
+    account: &mut Account,
+    device_id: Uuid,
+    policy: &SessionPolicyConfig,
+    tokens: &JwtAuth,
+    tenant: &TenantId,
+    now: DateTime<Utc>,
+) -> Result<SessionGrant, AccountError> {
+    account.sessions.retain(|session| session.expires_at > now && session.device_id != device_id);
+
+    let mut kicked_sessions = Vec::new();
+    while account.sessions.len() >= policy.max_concurrent_sessions {
+        if policy.on_limit == SessionLimitAction::Reject {
+            return Err(AccountError::TooManySessions(account.sessions.len()));
+        }
+        let oldest = account
+            .sessions
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, session)| session.last_active)
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+        kicked_sessions.push(account.sessions.remove(oldest).id);
+    }
+
+    let device = account
+        .devices
+        .iter_mut()
+        .find(|device| device.id == device_id)
+        .ok_or(AccountError::UnknownDevice)?;
+    let refresh_token = generate_token();
+    device.refresh_token = Some(refresh_token.clone());
+    device.refresh_expires_at = Some(now + Duration::days(policy.refresh_token_ttl_days as i64));
+    device.last_seen = now;
+
+    let session_id = Uuid::new_v4();
+    let (access_token, claims) = tokens.issue(PlayerId(account.id), session_id, tenant);
+    let session = Session {
+        id: session_id,
+        device_id,
+        platform: device.platform,
+        started_at: now,
+        last_active: now,
+        expires_at: claims.expires_at(),
+    };
+    let grant = SessionGrant {
+        account_id: account.id,
+        device_id,
+        session_id: session.id,
+        access_token,
+        refresh_token,
+        expires_at: session.expires_at,
+        kicked_sessions,
+    };
+    account.sessions.push(session);
+    Ok(grant)
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,349 @@
+// services/api-gateway/src/credentials.rs
+//! Usernames and passwords. Registering gives a player the account id
+//! they keep from then on; passwords and recovery tokens are stored only
+//! as Argon2 hashes. Accounts live in Postgres when `DATABASE_URL` is set.
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::TenantId;
+use rand::{distributions::Alphanumeric, Rng};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+const MAX_USERNAME_LENGTH: usize = 64;
+const RECOVERY_TOKEN_LENGTH: usize = 32;
+const RECOVERY_TOKEN_TTL_MINUTES: i64 = 30;
+
+#[derive(Debug, Error)]
+pub enum CredentialError {
+    #[error("Usernames are 1-{MAX_USERNAME_LENGTH} letters, digits, '-', '_' or '.'")]
+    InvalidUsername,
+
+    #[error("Passwords need at least {MIN_PASSWORD_LENGTH} characters")]
+    WeakPassword,
+
+    #[error("Username {0} is taken")]
+    UsernameTaken(String),
+
+    #[error("Wrong username or password")]
+    InvalidCredentials,
+
+    #[error("Invalid or expired recovery token")]
+    InvalidRecoveryToken,
+
+    #[error("Account store unavailable: {0}")]
+    Store(String),
+}
+
+impl From<anyhow::Error> for CredentialError {
+    fn from(e: anyhow::Error) -> Self {
+        CredentialError::Store(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredAccount {
+    pub id: Uuid,
+    pub password_hash: String,
+    pub recovery_token_hash: Option<String>,
+    pub recovery_expires_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait CredentialStore: Send + Sync {
+    /// None when the username is taken.
+    async fn create(&self, tenant: &TenantId, username: &str, password_hash: &str) -> anyhow::Result<Option<Uuid>>;
+    async fn find(&self, tenant: &TenantId, username: &str) -> anyhow::Result<Option<StoredAccount>>;
+    async fn set_recovery(
+        &self,
+        tenant: &TenantId,
+        id: Uuid,
+        token_hash: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()>;
+    /// Replaces the password and clears any recovery token.
+    async fn set_password(&self, tenant: &TenantId, id: Uuid, password_hash: &str) -> anyhow::Result<()>;
+}
+
+/// Rows in `accounts`.
+pub struct PgCredentialStore {
+    pool: PgPool,
+}
+
+type AccountRow = (Uuid, String, Option<String>, Option<DateTime<Utc>>);
+
+impl PgCredentialStore {
+    pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
+        let pool = PgPoolOptions::new().max_connections(5).connect(database_url).await?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl CredentialStore for PgCredentialStore {
+    async fn create(&self, tenant: &TenantId, username: &str, password_hash: &str) -> anyhow::Result<Option<Uuid>> {
+        let id: Option<(Uuid,)> = sqlx::query_as(
+            "INSERT INTO accounts (tenant_id, username, password_hash) VALUES ($1, $2, $3) \
+             ON CONFLICT (tenant_id, username) DO NOTHING RETURNING id",
+        )
+        .bind(tenant.as_str())
+        .bind(username)
+        .bind(password_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(id.map(|(id,)| id))
+    }
+
+    async fn find(&self, tenant: &TenantId, username: &str) -> anyhow::Result<Option<StoredAccount>> {
+        let row: Option<AccountRow> = sqlx::query_as(
+            "SELECT id, password_hash, recovery_token_hash, recovery_expires_at \
+             FROM accounts WHERE tenant_id = $1 AND username = $2",
+        )
+        .bind(tenant.as_str())
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(id, password_hash, recovery_token_hash, recovery_expires_at)| StoredAccount {
+            id,
+            password_hash,
+            recovery_token_hash,
+            recovery_expires_at,
+        }))
+    }
+
+    async fn set_recovery(
+        &self,
+        tenant: &TenantId,
+        id: Uuid,
+        token_hash: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE accounts SET recovery_token_hash = $3, recovery_expires_at = $4 \
+             WHERE tenant_id = $1 AND id = $2",
+        )
+        .bind(tenant.as_str())
+        .bind(id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_password(&self, tenant: &TenantId, id: Uuid, password_hash: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE accounts SET password_hash = $3, recovery_token_hash = NULL, recovery_expires_at = NULL \
+             WHERE tenant_id = $1 AND id = $2",
+        )
+        .bind(tenant.as_str())
+        .bind(id)
+        .bind(password_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Accounts that last until the gateway restarts, for local development.
+#[derive(Default)]
+pub struct MemoryCredentialStore {
+    accounts: Mutex<HashMap<(TenantId, String), StoredAccount>>,
+}
+
+#[async_trait]
+impl CredentialStore for MemoryCredentialStore {
+    async fn create(&self, tenant: &TenantId, username: &str, password_hash: &str) -> anyhow::Result<Option<Uuid>> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let key = (tenant.clone(), username.to_string());
+        if accounts.contains_key(&key) {
+            return Ok(None);
+        }
+        let id = Uuid::new_v4();
+        accounts.insert(key, StoredAccount {
+            id,
+            password_hash: password_hash.to_string(),
+            recovery_token_hash: None,
+            recovery_expires_at: None,
+        });
+        Ok(Some(id))
+    }
+
+    async fn find(&self, tenant: &TenantId, username: &str) -> anyhow::Result<Option<StoredAccount>> {
+        Ok(self.accounts.lock().unwrap().get(&(tenant.clone(), username.to_string())).cloned())
+    }
+
+    async fn set_recovery(
+        &self,
+        tenant: &TenantId,
+        id: Uuid,
+        token_hash: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        let mut accounts = self.accounts.lock().unwrap();
+        if let Some(account) = accounts.iter_mut().find(|((t, _), a)| t == tenant && a.id == id).map(|(_, a)| a) {
+            account.recovery_token_hash = token_hash.map(str::to_string);
+            account.recovery_expires_at = expires_at;
+        }
+        Ok(())
+    }
+
+    async fn set_password(&self, tenant: &TenantId, id: Uuid, password_hash: &str) -> anyhow::Result<()> {
+        let mut accounts = self.accounts.lock().unwrap();
+        if let Some(account) = accounts.iter_mut().find(|((t, _), a)| t == tenant && a.id == id).map(|(_, a)| a) {
+            account.password_hash = password_hash.to_string();
+            account.recovery_token_hash = None;
+            account.recovery_expires_at = None;
+        }
+        Ok(())
+    }
+}
+
+/// A token an operator hands a player who lost their password.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecoveryGrant {
+    pub recovery_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+pub struct Credentials {
+    store: Box<dyn CredentialStore>,
+}
+
+impl Credentials {
+    pub fn new(store: Box<dyn CredentialStore>) -> Self {
+        Self { store }
+    }
+
+    pub async fn register(&self, tenant: &TenantId, username: &str, password: &str) -> Result<Uuid, CredentialError> {
+        validate_username(username)?;
+        let hash = hash_secret(password_strength(password)?)?;
+        self.store
+            .create(tenant, username, &hash)
+            .await?
+            .ok_or_else(|| CredentialError::UsernameTaken(username.to_string()))
+    }
+
+    /// The account id, if the password is right.
+    pub async fn verify(&self, tenant: &TenantId, username: &str, password: &str) -> Result<Uuid, CredentialError> {
+        let account = self.store.find(tenant, username).await?.ok_or(CredentialError::InvalidCredentials)?;
+        match verify_secret(password, &account.password_hash) {
+            true => Ok(account.id),
+            false => Err(CredentialError::InvalidCredentials),
+        }
+    }
+
+    /// Issue a recovery token, replacing any earlier one. None for
+    /// unknown usernames.
+    pub async fn start_recovery(&self, tenant: &TenantId, username: &str) -> Result<Option<RecoveryGrant>, CredentialError> {
+        let Some(account) = self.store.find(tenant, username).await? else {
+            return Ok(None);
+        };
+        let recovery_token: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(RECOVERY_TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+        let expires_at = Utc::now() + Duration::minutes(RECOVERY_TOKEN_TTL_MINUTES);
+        let hash = hash_secret(&recovery_token)?;
+        self.store.set_recovery(tenant, account.id, Some(&hash), Some(expires_at)).await?;
+        Ok(Some(RecoveryGrant { recovery_token, expires_at }))
+    }
+
+    /// Set a new password with a recovery token, which is then used up.
+    pub async fn recover(
+        &self,
+        tenant: &TenantId,
+        username: &str,
+        recovery_token: &str,
+        new_password: &str,
+    ) -> Result<Uuid, CredentialError> {
+        let account = self.store.find(tenant, username).await?.ok_or(CredentialError::InvalidRecoveryToken)?;
+        let valid = account.recovery_expires_at.is_some_and(|expires| expires > Utc::now())
+            && account.recovery_token_hash.is_some_and(|hash| verify_secret(recovery_token, &hash));
+        if !valid {
+            return Err(CredentialError::InvalidRecoveryToken);
+        }
+        let hash = hash_secret(password_strength(new_password)?)?;
+        self.store.set_password(tenant, account.id, &hash).await?;
+        Ok(account.id)
+    }
+}
+
+fn validate_username(username: &str) -> Result<(), CredentialError> {
+    let valid = !username.is_empty()
+        && username.len() <= MAX_USERNAME_LENGTH
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(CredentialError::InvalidUsername)
+    }
+}
+
+fn password_strength(password: &str) -> Result<&str, CredentialError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(CredentialError::WeakPassword);
+    }
+    Ok(password)
+}
+
+fn hash_secret(secret: &str) -> Result<String, CredentialError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| CredentialError::Store(e.to_string()))
+}
+
+fn verify_secret(secret: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|hash| Argon2::default().verify_password(secret.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn registered_players_keep_their_id_and_can_recover_it() {
+        let credentials = Credentials::new(Box::new(MemoryCredentialStore::default()));
+        let tenant = TenantId::default();
+
+        let id = credentials.register(&tenant, "kael", "songweaver").await.unwrap();
+        assert!(matches!(
+            credentials.register(&tenant, "kael", "another-song").await,
+            Err(CredentialError::UsernameTaken(_))
+        ));
+        assert!(matches!(credentials.register(&tenant, "lyra", "short").await, Err(CredentialError::WeakPassword)));
+        // Another tenant's world has its own usernames
+        let partner = TenantId::parse("partner").unwrap();
+        assert_ne!(credentials.register(&partner, "kael", "songweaver").await.unwrap(), id);
+
+        assert_eq!(credentials.verify(&tenant, "kael", "songweaver").await.unwrap(), id);
+        assert!(matches!(
+            credentials.verify(&tenant, "kael", "wrong-song").await,
+            Err(CredentialError::InvalidCredentials)
+        ));
+
+        let grant = credentials.start_recovery(&tenant, "kael").await.unwrap().unwrap();
+        assert!(credentials.recover(&tenant, "kael", "not-the-token", "new-melody").await.is_err());
+        assert_eq!(
+            credentials.recover(&tenant, "kael", &grant.recovery_token, "new-melody").await.unwrap(),
+            id
+        );
+        // The token is spent and the old password gone
+        assert!(credentials.recover(&tenant, "kael", &grant.recovery_token, "newer-melody").await.is_err());
+        assert!(credentials.verify(&tenant, "kael", "songweaver").await.is_err());
+        assert_eq!(credentials.verify(&tenant, "kael", "new-melody").await.unwrap(), id);
+        assert!(credentials.start_recovery(&tenant, "nobody").await.unwrap().is_none());
+    }
+}
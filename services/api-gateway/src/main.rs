@@ -1,43 +1,595 @@
-use axum::{routing::{get, post}, Router, Json};
+use axum::{
+    body::Bytes,
+    extract::{FromRef, Path, Query, RawQuery, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{any, delete, get, post},
+    Router, Json,
+};
+use finalverse_auth::JwtAuth;
+use finalverse_core::PlayerId;
 use serde::{Deserialize, Serialize};
-use finalverse_health::HealthMonitor;
-use service_registry::LocalServiceRegistry;
+use finalverse_health::{DebugEndpoints, HealthMonitor, OperatorGuard};
+use finalverse_metrics::{counter, histogram, CounterDef, HistogramDef};
+use finalverse_ratelimit::{axum::limit_requests, RateLimits};
+use service_registry::{HttpPolicy, LocalServiceRegistry, Locality, ServiceRegistry};
 use std::{net::SocketAddr, sync::Arc};
+use tokio::sync::RwLock;
 use tracing::info;
 use finalverse_logging as logging;
+use uuid::Uuid;
+
+mod accounts;
+mod credentials;
+mod proxy;
+mod registry;
+mod telemetry;
+mod tenancy;
+
+use accounts::{AccountError, AccountStore, DeviceRegistration, SessionGrant};
+use credentials::{CredentialError, Credentials, MemoryCredentialStore, PgCredentialStore};
+use telemetry::{SamplingRates, TelemetryError, TelemetryIngest, TelemetryRecord};
+use proxy::Upstreams;
+use tenancy::{TenantError, TenantRouter};
+
+type SharedAccounts = Arc<RwLock<AccountStore>>;
+
+/// Sign-in routes need the tenant to put in the tokens they issue.
+#[derive(Clone)]
+struct AccountState {
+    accounts: SharedAccounts,
+    credentials: Arc<Credentials>,
+    tenants: Arc<TenantRouter>,
+    /// Required to issue recovery tokens; unset disables issuing them.
+    operator: OperatorGuard,
+}
+
+impl FromRef<AccountState> for SharedAccounts {
+    fn from_ref(state: &AccountState) -> Self {
+        state.accounts.clone()
+    }
+}
+
+#[derive(Clone)]
+struct TelemetryState {
+    ingest: Arc<RwLock<TelemetryIngest>>,
+    accounts: SharedAccounts,
+    /// Required to change sampling rates.
+    operator: OperatorGuard,
+}
+
+#[derive(Clone)]
+struct ProxyState {
+    tenants: Arc<TenantRouter>,
+    upstreams: Arc<Upstreams>,
+}
+
+/// Internal port serving the service registry, unless
+/// `GATEWAY_REGISTRY_PORT` says otherwise.
+const DEFAULT_REGISTRY_PORT: u16 = 8082;
+
+/// The registry has no authentication, since whoever can register decides
+/// where `/api/{service}` goes, so it only listens on loopback unless
+/// `GATEWAY_REGISTRY_HOST` names a private interface to serve it on.
+const DEFAULT_REGISTRY_HOST: [u8; 4] = [127, 0, 0, 1];
+
+const LOGINS: CounterDef<1> = CounterDef::new(
+    "finalverse_gateway_logins_total",
+    "Login attempts by outcome",
+    ["outcome"],
+);
+
+const KICKED_SESSIONS: CounterDef<0> = CounterDef::new(
+    "finalverse_gateway_kicked_sessions_total",
+    "Sessions ended because a newer login exceeded the session limit",
+    [],
+);
+
+const TELEMETRY_RECORDS: CounterDef<2> = CounterDef::new(
+    "finalverse_gateway_telemetry_records_total",
+    "Client telemetry records by metric and outcome",
+    ["metric", "outcome"],
+);
+
+const CLIENT_FPS: HistogramDef<1> = HistogramDef::new(
+    "finalverse_client_fps",
+    "Frame rates reported by clients",
+    ["platform"],
+);
+
+const CLIENT_LOAD_SECONDS: HistogramDef<1> = HistogramDef::new(
+    "finalverse_client_load_seconds",
+    "Load times reported by clients",
+    ["phase"],
+);
+
+const PROXIED_REQUESTS: CounterDef<3> = CounterDef::new(
+    "finalverse_gateway_proxied_requests_total",
+    "Requests routed to backend services by tenant, service and outcome",
+    ["tenant", "service", "outcome"],
+);
+
+const PROXY_RETRIES: CounterDef<1> = CounterDef::new(
+    "finalverse_gateway_proxy_retries_total",
+    "Proxied requests moved to another instance after one failed",
+    ["service"],
+);
+
+const CLIENT_ERRORS: CounterDef<1> = CounterDef::new(
+    "finalverse_client_errors_total",
+    "Errors reported by clients",
+    ["platform"],
+);
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
+    let metrics = finalverse_metrics::init("api-gateway")?;
+    LOGINS.describe();
+    KICKED_SESSIONS.describe();
+    TELEMETRY_RECORDS.describe();
+    CLIENT_FPS.describe();
+    CLIENT_LOAD_SECONDS.describe();
+    CLIENT_ERRORS.describe();
+    PROXIED_REQUESTS.describe();
+    PROXY_RETRIES.describe();
+    service_registry::describe_metrics();
+    finalverse_ratelimit::describe_metrics();
     let monitor = Arc::new(HealthMonitor::new("api-gateway", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
         .register_service("api-gateway".to_string(), "http://localhost:8080".to_string())
         .await;
 
+    let security = finalverse_config::load_default_config()
+        .map(|config| config.security)
+        .unwrap_or_default();
+    let accounts: SharedAccounts = Arc::new(RwLock::new(AccountStore::new(
+        security.sessions.clone(),
+        JwtAuth::from_config(&security),
+    )));
+    let credentials = match std::env::var("DATABASE_URL") {
+        Ok(database_url) => Credentials::new(Box::new(PgCredentialStore::connect(&database_url).await?)),
+        Err(_) => {
+            tracing::warn!("DATABASE_URL not set; accounts last only until the gateway restarts");
+            Credentials::new(Box::new(MemoryCredentialStore::default()))
+        }
+    };
+
+    let forward = std::env::var("FINALVERSE_ANALYTICS_URL").ok().map(telemetry::spawn_forwarder);
+    let telemetry_state = TelemetryState {
+        ingest: Arc::new(RwLock::new(TelemetryIngest::new(forward))),
+        accounts: accounts.clone(),
+        operator: OperatorGuard::new(security.operator_token.clone()),
+    };
+    let telemetry_routes = Router::new()
+        .route("/telemetry", post(ingest_telemetry))
+        .route("/telemetry/diagnostics", get(telemetry_diagnostics))
+        .route("/admin/telemetry/sampling", get(get_sampling).put(set_sampling))
+        .with_state(telemetry_state);
+
+    let tenancy = finalverse_config::load_default_config()
+        .map(|config| config.tenancy)
+        .unwrap_or_default();
+    TenantRouter::register_services(&tenancy, &registry).await;
+    let tenants = Arc::new(TenantRouter::new(&tenancy));
+
+    // Services register here; /api/{service}/... routes by what they report
+    let service_registry = ServiceRegistry::new();
+    service_registry.start_cleanup_task();
+    let registry_port = std::env::var("GATEWAY_REGISTRY_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DEFAULT_REGISTRY_PORT);
+    let registry_host = std::env::var("GATEWAY_REGISTRY_HOST")
+        .ok()
+        .and_then(|host| host.parse().ok())
+        .unwrap_or(std::net::IpAddr::from(DEFAULT_REGISTRY_HOST));
+    let registry_addr = SocketAddr::new(registry_host, registry_port);
+    let registry_listener = tokio::net::TcpListener::bind(registry_addr).await?;
+    let registry_app = registry::routes(service_registry.clone());
+    info!("Service registry listening on {}", registry_addr);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(registry_listener, registry_app).await {
+            tracing::error!("Service registry stopped: {}", e);
+        }
+    });
+
+    let proxy_routes = Router::new()
+        .route("/api/:service/*path", any(proxy_request))
+        .with_state(ProxyState {
+            tenants: tenants.clone(),
+            upstreams: Arc::new(
+                Upstreams::new(service_registry, registry.clone(), HttpPolicy::load()).with_locality(Locality::from_env()),
+            ),
+        });
+
     let app = Router::new()
+        .route("/register", post(register_handler))
+        .route("/login", post(login_handler))
+        .route("/account/recover", post(recover_handler))
+        .route("/admin/accounts/recovery", post(start_recovery_handler))
+        .route("/token/refresh", post(refresh_handler))
+        .route("/account/devices", get(list_devices))
+        .route("/account/devices/:device_id", delete(unlink_device))
+        .route("/account/devices/:device_id/revoke", post(revoke_device))
+        .route("/account/sessions", get(list_sessions))
+        .route("/account/sessions/:session_id", delete(terminate_session))
+        .with_state(AccountState {
+            accounts,
+            credentials: Arc::new(credentials),
+            tenants,
+            operator: OperatorGuard::new(security.operator_token.clone()),
+        })
+        .merge(telemetry_routes)
+        .merge(proxy_routes)
+        .layer(axum::middleware::from_fn_with_state(Arc::new(RateLimits::load()), limit_requests))
         .merge(monitor.clone().axum_routes())
-        .route("/login", post(login_handler));
+        .merge(Arc::new(DebugEndpoints::load("api-gateway")).axum_routes())
+        .merge(metrics.axum_routes())
+        .layer(axum::middleware::from_fn(finalverse_metrics::http::track_requests));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
     info!("API Gateway listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     Ok(())
 }
 
+impl IntoResponse for AccountError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AccountError::InvalidToken => StatusCode::UNAUTHORIZED,
+            AccountError::UnknownDevice | AccountError::UnknownSession => StatusCode::NOT_FOUND,
+            AccountError::TooManyDevices(_) | AccountError::TooManySessions(_) => StatusCode::CONFLICT,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl IntoResponse for CredentialError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            CredentialError::InvalidUsername | CredentialError::WeakPassword => StatusCode::BAD_REQUEST,
+            CredentialError::UsernameTaken(_) => StatusCode::CONFLICT,
+            CredentialError::InvalidCredentials | CredentialError::InvalidRecoveryToken => StatusCode::UNAUTHORIZED,
+            CredentialError::Store(_) => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl IntoResponse for TenantError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            TenantError::Invalid(_) => StatusCode::BAD_REQUEST,
+            TenantError::HostMismatch { .. } => StatusCode::FORBIDDEN,
+            TenantError::Unknown(_) | TenantError::NoDeployment { .. } => StatusCode::NOT_FOUND,
+            TenantError::Upstream(_) => StatusCode::BAD_GATEWAY,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+/// Resolve the caller's account from their bearer access token.
+async fn authenticate(accounts: &SharedAccounts, headers: &HeaderMap) -> Result<(Uuid, Uuid), AccountError> {
+    let token = headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(AccountError::InvalidToken)?;
+    accounts.write().await.authenticate(token)
+}
+
+#[derive(Deserialize)]
+struct RegisterRequest {
+    username: String,
+    password: String,
+}
+
 #[derive(Deserialize)]
 struct LoginRequest {
     username: String,
     password: String,
+    #[serde(default)]
+    device: DeviceRegistration,
 }
 
 #[derive(Serialize)]
 struct LoginResponse {
     token: String,
+    /// The account id, which is the player's id in every service.
+    player_id: PlayerId,
+    #[serde(flatten)]
+    grant: SessionGrant,
+}
+
+#[derive(Deserialize)]
+struct RecoveryRequest {
+    username: String,
 }
 
-async fn login_handler(Json(payload): Json<LoginRequest>) -> Json<LoginResponse> {
-    let token = format!("token-{}", payload.username);
-    Json(LoginResponse { token })
+#[derive(Deserialize)]
+struct RecoverRequest {
+    username: String,
+    recovery_token: String,
+    new_password: String,
+}
+
+#[derive(Deserialize)]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+async fn register_handler(
+    State(state): State<AccountState>,
+    headers: HeaderMap,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<impl IntoResponse, Response> {
+    let tenant = state.tenants.resolve(&headers).map_err(IntoResponse::into_response)?;
+    let account_id = state
+        .credentials
+        .register(&tenant, &payload.username, &payload.password)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    info!("Registered {} as player {}", payload.username, account_id);
+    Ok((StatusCode::CREATED, Json(serde_json::json!({ "player_id": PlayerId(account_id) }))))
+}
+
+async fn login_handler(
+    State(state): State<AccountState>,
+    headers: HeaderMap,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Json<LoginResponse>, Response> {
+    let tenant = state.tenants.resolve(&headers).map_err(IntoResponse::into_response)?;
+    let account_id = state
+        .credentials
+        .verify(&tenant, &payload.username, &payload.password)
+        .await
+        .inspect_err(|_| counter!(LOGINS, outcome = "rejected").increment(1))
+        .map_err(IntoResponse::into_response)?;
+    let grant = state
+        .accounts
+        .write()
+        .await
+        .login(account_id, payload.device, &tenant)
+        .inspect_err(|_| counter!(LOGINS, outcome = "rejected").increment(1))
+        .map_err(IntoResponse::into_response)?;
+    counter!(LOGINS, outcome = "success").increment(1);
+    if !grant.kicked_sessions.is_empty() {
+        counter!(KICKED_SESSIONS).increment(grant.kicked_sessions.len() as u64);
+        info!("Login for {} ended {} older sessions", payload.username, grant.kicked_sessions.len());
+    }
+    Ok(Json(LoginResponse {
+        token: grant.access_token.clone(),
+        player_id: PlayerId(grant.account_id),
+        grant,
+    }))
+}
+
+async fn refresh_handler(
+    State(state): State<AccountState>,
+    headers: HeaderMap,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<Json<LoginResponse>, Response> {
+    let tenant = state.tenants.resolve(&headers).map_err(IntoResponse::into_response)?;
+    let grant = state
+        .accounts
+        .write()
+        .await
+        .refresh(&payload.refresh_token, &tenant)
+        .map_err(IntoResponse::into_response)?;
+    Ok(Json(LoginResponse {
+        token: grant.access_token.clone(),
+        player_id: PlayerId(grant.account_id),
+        grant,
+    }))
+}
+
+/// Issue a recovery token for support staff to hand to a player who
+/// lost their password. Needs the operator token.
+async fn start_recovery_handler(
+    State(state): State<AccountState>,
+    headers: HeaderMap,
+    Json(payload): Json<RecoveryRequest>,
+) -> Result<impl IntoResponse, Response> {
+    state.operator.check_headers(&headers).map_err(IntoResponse::into_response)?;
+    let tenant = state.tenants.resolve(&headers).map_err(IntoResponse::into_response)?;
+    match state.credentials.start_recovery(&tenant, &payload.username).await {
+        Ok(Some(grant)) => Ok(Json(grant)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Unknown account" }))).into_response()),
+        Err(e) => Err(e.into_response()),
+    }
+}
+
+/// Set a new password with a recovery token. Every session the account
+/// had is ended.
+async fn recover_handler(
+    State(state): State<AccountState>,
+    headers: HeaderMap,
+    Json(payload): Json<RecoverRequest>,
+) -> Result<impl IntoResponse, Response> {
+    let tenant = state.tenants.resolve(&headers).map_err(IntoResponse::into_response)?;
+    let account_id = state
+        .credentials
+        .recover(&tenant, &payload.username, &payload.recovery_token, &payload.new_password)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    state.accounts.write().await.revoke_all(account_id);
+    info!("Password for {} reset with a recovery token", payload.username);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_devices(
+    State(accounts): State<SharedAccounts>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AccountError> {
+    let (account_id, _) = authenticate(&accounts, &headers).await?;
+    Ok(Json(accounts.read().await.devices(account_id)))
+}
+
+async fn revoke_device(
+    State(accounts): State<SharedAccounts>,
+    headers: HeaderMap,
+    Path(device_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AccountError> {
+    let (account_id, _) = authenticate(&accounts, &headers).await?;
+    accounts.write().await.revoke_device(account_id, device_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn unlink_device(
+    State(accounts): State<SharedAccounts>,
+    headers: HeaderMap,
+    Path(device_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AccountError> {
+    let (account_id, _) = authenticate(&accounts, &headers).await?;
+    accounts.write().await.unlink_device(account_id, device_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_sessions(
+    State(accounts): State<SharedAccounts>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AccountError> {
+    let (account_id, current) = authenticate(&accounts, &headers).await?;
+    let sessions: Vec<_> = accounts
+        .read()
+        .await
+        .sessions(account_id)
+        .into_iter()
+        .map(|session| serde_json::json!({ "current": session.id == current, "session": session }))
+        .collect();
+    Ok(Json(sessions))
+}
+
+async fn terminate_session(
+    State(accounts): State<SharedAccounts>,
+    headers: HeaderMap,
+    Path(session_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AccountError> {
+    let (account_id, _) = authenticate(&accounts, &headers).await?;
+    let session = accounts.write().await.terminate_session(account_id, session_id)?;
+    info!("Session {} on device {} terminated", session.id, session.device_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+impl IntoResponse for TelemetryError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            TelemetryError::TooLarge(_) | TelemetryError::TooManyRecords(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            TelemetryError::Malformed(_) | TelemetryError::InvalidSampling(_) => StatusCode::BAD_REQUEST,
+            TelemetryError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        };
+        let body = Json(serde_json::json!({ "error": self.to_string() }));
+        match self {
+            TelemetryError::RateLimited(seconds) => {
+                (status, [(header::RETRY_AFTER, seconds.to_string())], body).into_response()
+            }
+            _ => (status, body).into_response(),
+        }
+    }
+}
+
+/// Accept a batch of client telemetry. Signing in is optional; when a
+/// bearer token is present the records are attributed to the account.
+async fn ingest_telemetry(
+    State(state): State<TelemetryState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, TelemetryError> {
+    let account_id = match headers.contains_key("authorization") {
+        true => authenticate(&state.accounts, &headers).await.ok().map(|(account_id, _)| account_id),
+        false => None,
+    };
+    let (report, accepted) = state.ingest.write().await.ingest(&body, account_id)?;
+
+    for record in &accepted {
+        counter!(TELEMETRY_RECORDS, metric = record.record.metric(), outcome = "accepted").increment(1);
+        let platform = record.platform.as_str();
+        match &record.record {
+            TelemetryRecord::Fps { value, .. } => histogram!(CLIENT_FPS, platform = platform).record(*value),
+            TelemetryRecord::LoadTime { phase, millis, .. } => {
+                histogram!(CLIENT_LOAD_SECONDS, phase = phase.clone()).record(millis / 1000.0)
+            }
+            TelemetryRecord::ClientError { .. } => counter!(CLIENT_ERRORS, platform = platform).increment(1),
+        }
+    }
+    for rejection in &report.rejected {
+        let metric = rejection.metric.clone().unwrap_or_else(|| "unknown".to_string());
+        counter!(TELEMETRY_RECORDS, metric = metric, outcome = "rejected").increment(1);
+    }
+    if report.sampled_out > 0 {
+        counter!(TELEMETRY_RECORDS, metric = "any", outcome = "sampled_out").increment(report.sampled_out as u64);
+    }
+
+    let status = if report.accepted == 0 && !report.rejected.is_empty() {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else {
+        StatusCode::ACCEPTED
+    };
+    Ok((status, Json(report)))
+}
+
+#[derive(Deserialize)]
+struct DiagnosticsQuery {
+    client_id: Uuid,
+}
+
+async fn telemetry_diagnostics(
+    State(state): State<TelemetryState>,
+    Query(query): Query<DiagnosticsQuery>,
+) -> impl IntoResponse {
+    Json(state.ingest.read().await.diagnostics(query.client_id))
+}
+
+async fn get_sampling(State(state): State<TelemetryState>) -> impl IntoResponse {
+    Json(state.ingest.read().await.sampling().clone())
+}
+
+/// Change client sampling rates. Needs the operator token.
+async fn set_sampling(
+    State(state): State<TelemetryState>,
+    headers: HeaderMap,
+    Json(sampling): Json<SamplingRates>,
+) -> Result<impl IntoResponse, Response> {
+    state.operator.check_headers(&headers).map_err(IntoResponse::into_response)?;
+    state
+        .ingest
+        .write()
+        .await
+        .set_sampling(sampling.clone())
+        .map_err(IntoResponse::into_response)?;
+    info!("Client telemetry sampling set to {:?}", sampling);
+    Ok(Json(sampling))
+}
+
+/// Forward `/api/{service}/...` to one of the tenant's instances of the
+/// service. The tenant header sent upstream is always the one resolved here.
+async fn proxy_request(
+    State(state): State<ProxyState>,
+    Path((service, path)): Path<(String, String)>,
+    RawQuery(query): RawQuery,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, TenantError> {
+    let tenant = state.tenants.resolve(&headers)?;
+    let path = match query {
+        Some(query) => format!("{}?{}", path, query),
+        None => path,
+    };
+    let forwarded = state.upstreams.forward(&tenant, &service, &path, method, &headers, body).await;
+    let outcome = if forwarded.is_ok() { "ok" } else { "error" };
+    counter!(PROXIED_REQUESTS, tenant = tenant.to_string(), service = service.clone(), outcome = outcome).increment(1);
+
+    let forwarded = forwarded?;
+    if forwarded.retries > 0 {
+        counter!(PROXY_RETRIES, service = service.clone()).increment(forwarded.retries as u64);
+    }
+    let mut response = (forwarded.status, forwarded.body).into_response();
+    if let Some(content_type) = forwarded.content_type {
+        response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+    }
+    Ok(response)
 }
@@ -0,0 +1,179 @@
+// services/api-gateway/src/proxy.rs
+//! Where `/api/{service}/...` requests go. Instances come from the
+//! service registry the gateway hosts (see [`crate::registry`]); when one
+//! fails the request moves on to the next healthy instance, nearest to the
+//! gateway's own zone first. Services with no registered instances fall
+//! back to their configured URL.
+
+use crate::tenancy::TenantError;
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use finalverse_core::{TenantId, TENANT_HEADER};
+use service_registry::{HttpPolicy, LocalServiceRegistry, Locality, ServiceInstance, ServiceRegistry};
+
+/// A response worth trying another instance for: the instance, not the
+/// request, is the problem.
+fn instance_failed(status: StatusCode) -> bool {
+    matches!(status, StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT)
+}
+
+fn base_url(instance: &ServiceInstance) -> String {
+    format!("http://{}:{}", instance.host, instance.port)
+}
+
+/// What came back from the instance that answered.
+pub struct Forwarded {
+    pub status: StatusCode,
+    pub content_type: Option<header::HeaderValue>,
+    pub body: Bytes,
+    /// Instances tried before this one answered.
+    pub retries: usize,
+}
+
+pub struct Upstreams {
+    registry: ServiceRegistry,
+    fallback: LocalServiceRegistry,
+    client: reqwest::Client,
+    policy: HttpPolicy,
+    locality: Locality,
+}
+
+impl Upstreams {
+    pub fn new(registry: ServiceRegistry, fallback: LocalServiceRegistry, policy: HttpPolicy) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(policy.connect_timeout)
+            .build()
+            .unwrap_or_default();
+        Self { registry, fallback, client, policy, locality: Locality::default() }
+    }
+
+    /// Prefer instances in `locality`, failing over to farther ones.
+    pub fn with_locality(mut self, locality: Locality) -> Self {
+        self.locality = locality;
+        self
+    }
+
+    /// Base URLs to try in order: the registry's pick among the nearest
+    /// first, then the tenant's other healthy instances, nearest first.
+    pub async fn candidates(&self, tenant: &TenantId, service: &str) -> Vec<String> {
+        let Some(first) = self.registry.discover_for_tenant_near(service, tenant, &self.locality).await else {
+            return self.fallback.get_tenant_service_url(tenant, service).await.into_iter().collect();
+        };
+        let mut others: Vec<_> = self
+            .registry
+            .discover_all(service)
+            .await
+            .into_iter()
+            .filter(|instance| instance.tenant() == *tenant && instance.id != first.id)
+            .collect();
+        others.sort_by_key(|instance| self.locality.distance(instance));
+        std::iter::once(first).chain(others).map(|instance| base_url(&instance)).collect()
+    }
+
+    /// Send the request to the service, moving to another instance when
+    /// one can't be reached or reports itself unavailable. Requests that
+    /// may not be safe to repeat only move on if they never got through.
+    pub async fn forward(
+        &self,
+        tenant: &TenantId,
+        service: &str,
+        path_and_query: &str,
+        method: Method,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> Result<Forwarded, TenantError> {
+        let candidates = self.candidates(tenant, service).await;
+        if candidates.is_empty() {
+            return Err(TenantError::NoDeployment { tenant: tenant.clone(), service: service.to_string() });
+        }
+        let target = self.policy.for_target(service);
+        let attempts = candidates.len().min(target.max_retries as usize + 1);
+        let idempotent = !matches!(method, Method::POST | Method::PATCH);
+
+        let mut last_error = String::new();
+        for (retries, base) in candidates.iter().take(attempts).enumerate() {
+            let last_attempt = retries + 1 == attempts;
+            let mut request = self
+                .client
+                .request(method.clone(), format!("{}/{}", base, path_and_query))
+                .timeout(target.timeout)
+                .header(TENANT_HEADER, tenant.as_str())
+                .body(body.clone());
+            for name in [header::AUTHORIZATION, header::CONTENT_TYPE, header::ACCEPT] {
+                if let Some(value) = headers.get(&name) {
+                    request = request.header(name, value);
+                }
+            }
+
+            match request.send().await {
+                Ok(response) if last_attempt || !idempotent || !instance_failed(response.status()) => {
+                    let status = response.status();
+                    let content_type = response.headers().get(header::CONTENT_TYPE).cloned();
+                    let body = response.bytes().await.map_err(|e| TenantError::Upstream(e.to_string()))?;
+                    return Ok(Forwarded { status, content_type, body, retries });
+                }
+                Ok(response) => last_error = format!("{} answered {}", base, response.status()),
+                Err(e) if e.is_connect() || (idempotent && e.is_timeout()) => last_error = e.to_string(),
+                Err(e) => return Err(TenantError::Upstream(e.to_string())),
+            }
+            tracing::debug!("{} {} failed on {}: {}", method, service, base, last_error);
+        }
+        Err(TenantError::Upstream(last_error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use service_registry::ServiceRegistration;
+    use std::collections::HashMap;
+
+    fn registration(port: u16) -> ServiceRegistration {
+        ServiceRegistration {
+            name: "story-engine".to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            health_check_path: "/health".to_string(),
+            metadata: HashMap::new(),
+            load_balancing: Default::default(),
+            heartbeat_ttl_seconds: None,
+            zone: None,
+            shard: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_instances_are_skipped_for_the_next_healthy_one() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let live = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route("/chronicle/kael", get(|| async { "the tale so far" }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        // Nothing listens on a port just released
+        let dead = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+
+        let registry = ServiceRegistry::new();
+        registry.register(registration(dead)).await;
+        registry.register(registration(live)).await;
+        let mut policy = HttpPolicy::default();
+        policy.default.max_retries = 1;
+        let upstreams = Upstreams::new(registry, LocalServiceRegistry::new(), policy);
+
+        let tenant = TenantId::default();
+        assert_eq!(upstreams.candidates(&tenant, "story-engine").await.len(), 2);
+        // Either instance may be picked first; both orders must succeed
+        for _ in 0..4 {
+            let forwarded = upstreams
+                .forward(&tenant, "story-engine", "chronicle/kael", Method::GET, &HeaderMap::new(), Bytes::new())
+                .await
+                .unwrap();
+            assert_eq!(forwarded.status, StatusCode::OK);
+            assert_eq!(forwarded.body, "the tale so far");
+        }
+
+        // Unregistered services still reach their configured URL
+        let fallback = upstreams.candidates(&tenant, "harmony-service").await;
+        assert_eq!(fallback, vec!["http://localhost:3006".to_string()]);
+        assert!(upstreams.candidates(&TenantId::parse("partner").unwrap(), "harmony-service").await.is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+// services/api-gateway/src/registry.rs
+//! The service registry the gateway routes by, served on an internal
+//! port with the endpoints `service_registry::RegistryClient` calls:
+//! point a service's client at `http://<gateway>:<port>` and its
+//! registration and heartbeats decide where `/api/{service}/...` goes.
+//! Anyone who can reach the port can register, so it listens on loopback
+//! unless `GATEWAY_REGISTRY_HOST` is set to a private interface.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use service_registry::{HeartbeatBatch, HeartbeatBatchResult, Locality, ServiceRegistration, ServiceRegistry};
+
+#[derive(Deserialize)]
+struct DiscoverQuery {
+    key: Option<String>,
+    #[serde(flatten)]
+    locality: Locality,
+}
+
+pub fn routes(registry: ServiceRegistry) -> Router {
+    Router::new()
+        .route("/register", post(register))
+        .route("/register/batch", post(register_batch))
+        .route("/heartbeat/batch", post(heartbeat_batch))
+        .route("/services", get(list_services))
+        .route("/services/:id", delete(deregister))
+        .route("/services/:id/heartbeat", put(heartbeat))
+        .route("/discover/:name", get(discover))
+        .with_state(registry)
+}
+
+async fn register(
+    State(registry): State<ServiceRegistry>,
+    Json(registration): Json<ServiceRegistration>,
+) -> impl IntoResponse {
+    Json(registry.register(registration).await)
+}
+
+async fn register_batch(
+    State(registry): State<ServiceRegistry>,
+    Json(registrations): Json<Vec<ServiceRegistration>>,
+) -> impl IntoResponse {
+    Json(registry.register_batch(registrations).await)
+}
+
+async fn heartbeat_batch(
+    State(registry): State<ServiceRegistry>,
+    Json(batch): Json<HeartbeatBatch>,
+) -> Json<HeartbeatBatchResult> {
+    let unknown = registry.heartbeat_batch(&batch.ids).await;
+    Json(HeartbeatBatchResult { unknown })
+}
+
+async fn list_services(State(registry): State<ServiceRegistry>) -> impl IntoResponse {
+    Json(registry.list_services().await)
+}
+
+async fn deregister(State(registry): State<ServiceRegistry>, Path(id): Path<String>) -> StatusCode {
+    registry.deregister(&id).await;
+    StatusCode::NO_CONTENT
+}
+
+async fn heartbeat(State(registry): State<ServiceRegistry>, Path(id): Path<String>) -> StatusCode {
+    match registry.heartbeat(&id).await {
+        true => StatusCode::NO_CONTENT,
+        false => StatusCode::NOT_FOUND,
+    }
+}
+
+async fn discover(
+    State(registry): State<ServiceRegistry>,
+    Path(name): Path<String>,
+    Query(query): Query<DiscoverQuery>,
+) -> impl IntoResponse {
+    Json(registry.discover_near(&name, &query.locality, query.key.as_deref()).await)
+}
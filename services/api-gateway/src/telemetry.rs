@@ -0,0 +1,479 @@
+// services/api-gateway/src/telemetry.rs
+//! Client telemetry: batches of performance samples and error reports are
+//! validated record by record, sampled per metric and forwarded to the
+//! analytics pipeline. Rejections are kept per client so developers can
+//! see why their payloads were refused.
+
+use crate::accounts::Platform;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Largest accepted request body.
+pub const MAX_BATCH_BYTES: usize = 256 * 1024;
+
+pub const MAX_RECORDS_PER_BATCH: usize = 500;
+
+/// Batches each client may send per minute.
+pub const BATCHES_PER_MINUTE: u32 = 30;
+
+/// Rejections remembered per client for the diagnostics endpoint.
+pub const DIAGNOSTICS_PER_CLIENT: usize = 50;
+
+/// Clients whose rejections are remembered. Client ids are whatever the
+/// sender says, so past this the least recently rejected client is
+/// forgotten.
+pub const DIAGNOSTICS_CLIENTS: usize = 10_000;
+
+/// How long a client's rejections are remembered after its last one.
+const DIAGNOSTICS_TTL: chrono::Duration = chrono::Duration::hours(1);
+
+const MAX_MESSAGE_LEN: usize = 2_000;
+const MAX_STACK_LEN: usize = 16 * 1024;
+const MAX_LABEL_LEN: usize = 128;
+
+/// Records forwarded to analytics in one request.
+const FORWARD_BATCH: usize = 500;
+const FORWARD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum TelemetryError {
+    #[error("Batch is {0} bytes; the limit is {MAX_BATCH_BYTES}")]
+    TooLarge(usize),
+
+    #[error("Batch has {0} records; the limit is {MAX_RECORDS_PER_BATCH}")]
+    TooManyRecords(usize),
+
+    #[error("Malformed batch: {0}")]
+    Malformed(String),
+
+    #[error("Too many batches; retry in {0} seconds")]
+    RateLimited(u64),
+
+    #[error("Invalid sampling rate for {0}: must be between 0 and 1")]
+    InvalidSampling(String),
+}
+
+/// The envelope a client sends. Records are validated individually so one
+/// bad record doesn't sink the batch.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryBatch {
+    /// Stable per-install id, used for rate limiting and diagnostics.
+    pub client_id: Uuid,
+    pub client_version: String,
+    pub platform: Platform,
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+    pub records: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "metric", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TelemetryRecord {
+    Fps {
+        value: f64,
+        #[serde(default)]
+        scene: Option<String>,
+        recorded_at: DateTime<Utc>,
+    },
+    LoadTime {
+        /// What was loading, e.g. "world_enter" or "region_assets".
+        phase: String,
+        millis: f64,
+        recorded_at: DateTime<Utc>,
+    },
+    ClientError {
+        message: String,
+        #[serde(default)]
+        code: Option<String>,
+        #[serde(default)]
+        stack: Option<String>,
+        recorded_at: DateTime<Utc>,
+    },
+}
+
+impl TelemetryRecord {
+    pub fn metric(&self) -> &'static str {
+        match self {
+            TelemetryRecord::Fps { .. } => "fps",
+            TelemetryRecord::LoadTime { .. } => "load_time",
+            TelemetryRecord::ClientError { .. } => "client_error",
+        }
+    }
+
+    fn validate(&self, now: DateTime<Utc>) -> Result<(), String> {
+        let recorded_at = match self {
+            TelemetryRecord::Fps { value, scene, recorded_at } => {
+                if !value.is_finite() || !(0.0..=1000.0).contains(value) {
+                    return Err("value must be between 0 and 1000".to_string());
+                }
+                check_len("scene", scene.as_deref(), MAX_LABEL_LEN)?;
+                recorded_at
+            }
+            TelemetryRecord::LoadTime { phase, millis, recorded_at } => {
+                if !millis.is_finite() || !(0.0..=600_000.0).contains(millis) {
+                    return Err("millis must be between 0 and 600000".to_string());
+                }
+                if phase.is_empty() {
+                    return Err("phase must not be empty".to_string());
+                }
+                check_len("phase", Some(phase), MAX_LABEL_LEN)?;
+                recorded_at
+            }
+            TelemetryRecord::ClientError { message, code, stack, recorded_at } => {
+                if message.is_empty() {
+                    return Err("message must not be empty".to_string());
+                }
+                check_len("message", Some(message), MAX_MESSAGE_LEN)?;
+                check_len("code", code.as_deref(), MAX_LABEL_LEN)?;
+                check_len("stack", stack.as_deref(), MAX_STACK_LEN)?;
+                recorded_at
+            }
+        };
+        if *recorded_at > now + Duration::minutes(5) {
+            return Err("recorded_at is in the future".to_string());
+        }
+        if *recorded_at < now - Duration::hours(24) {
+            return Err("recorded_at is more than 24 hours old".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn check_len(field: &str, value: Option<&str>, max: usize) -> Result<(), String> {
+    match value {
+        Some(value) if value.len() > max => Err(format!("{} is longer than {} bytes", field, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Fraction of records kept for each metric. Clients are told the current
+/// rates so they can sample before sending.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingRates {
+    pub fps: f64,
+    pub load_time: f64,
+    pub client_error: f64,
+}
+
+impl Default for SamplingRates {
+    fn default() -> Self {
+        Self {
+            fps: 0.1,
+            load_time: 1.0,
+            client_error: 1.0,
+        }
+    }
+}
+
+impl SamplingRates {
+    fn rate(&self, metric: &str) -> f64 {
+        match metric {
+            "fps" => self.fps,
+            "load_time" => self.load_time,
+            _ => self.client_error,
+        }
+    }
+
+    fn validate(&self) -> Result<(), TelemetryError> {
+        for (metric, rate) in [("fps", self.fps), ("load_time", self.load_time), ("client_error", self.client_error)] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(TelemetryError::InvalidSampling(metric.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a record was refused.
+#[derive(Debug, Clone, Serialize)]
+pub struct Rejection {
+    pub index: usize,
+    pub metric: Option<String>,
+    pub error: String,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestReport {
+    pub accepted: usize,
+    pub sampled_out: usize,
+    pub rejected: Vec<Rejection>,
+    pub sampling: SamplingRates,
+}
+
+/// A record on its way to analytics, with the envelope it came in.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsRecord {
+    pub client_id: Uuid,
+    pub client_version: String,
+    pub platform: Platform,
+    pub session_id: Option<Uuid>,
+    /// Set when the sender was signed in.
+    pub account_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub record: TelemetryRecord,
+}
+
+pub struct TelemetryIngest {
+    sampling: SamplingRates,
+    /// Batches seen per client in the current minute.
+    windows: HashMap<Uuid, (Instant, u32)>,
+    diagnostics: HashMap<Uuid, VecDeque<Rejection>>,
+    forward: Option<mpsc::UnboundedSender<AnalyticsRecord>>,
+}
+
+impl TelemetryIngest {
+    pub fn new(forward: Option<mpsc::UnboundedSender<AnalyticsRecord>>) -> Self {
+        Self {
+            sampling: SamplingRates::default(),
+            windows: HashMap::new(),
+            diagnostics: HashMap::new(),
+            forward,
+        }
+    }
+
+    pub fn sampling(&self) -> &SamplingRates {
+        &self.sampling
+    }
+
+    pub fn set_sampling(&mut self, sampling: SamplingRates) -> Result<(), TelemetryError> {
+        sampling.validate()?;
+        self.sampling = sampling;
+        Ok(())
+    }
+
+    /// Recent rejections for a client, newest last.
+    pub fn diagnostics(&self, client_id: Uuid) -> Vec<Rejection> {
+        self.diagnostics
+            .get(&client_id)
+            .map(|rejections| rejections.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Validate, sample and forward a raw batch.
+    pub fn ingest(&mut self, body: &[u8], account_id: Option<Uuid>) -> Result<(IngestReport, Vec<AnalyticsRecord>), TelemetryError> {
+        if body.len() > MAX_BATCH_BYTES {
+            return Err(TelemetryError::TooLarge(body.len()));
+        }
+        let batch: TelemetryBatch =
+            serde_json::from_slice(body).map_err(|e| TelemetryError::Malformed(e.to_string()))?;
+        if batch.records.len() > MAX_RECORDS_PER_BATCH {
+            return Err(TelemetryError::TooManyRecords(batch.records.len()));
+        }
+        self.check_rate(batch.client_id)?;
+
+        let now = Utc::now();
+        let mut report = IngestReport {
+            accepted: 0,
+            sampled_out: 0,
+            rejected: Vec::new(),
+            sampling: self.sampling.clone(),
+        };
+        let mut accepted = Vec::new();
+        for (index, value) in batch.records.into_iter().enumerate() {
+            let metric = value.get("metric").and_then(|m| m.as_str()).map(str::to_string);
+            let record = serde_json::from_value::<TelemetryRecord>(value)
+                .map_err(|e| e.to_string())
+                .and_then(|record| record.validate(now).map(|_| record));
+            match record {
+                Ok(record) => {
+                    if rand::random::<f64>() >= self.sampling.rate(record.metric()) {
+                        report.sampled_out += 1;
+                        continue;
+                    }
+                    report.accepted += 1;
+                    accepted.push(AnalyticsRecord {
+                        client_id: batch.client_id,
+                        client_version: batch.client_version.clone(),
+                        platform: batch.platform,
+                        session_id: batch.session_id,
+                        account_id,
+                        record,
+                    });
+                }
+                Err(error) => report.rejected.push(Rejection {
+                    index,
+                    metric,
+                    error,
+                    received_at: now,
+                }),
+            }
+        }
+
+        self.remember_rejections(batch.client_id, &report.rejected, now);
+        if let Some(forward) = &self.forward {
+            for record in &accepted {
+                let _ = forward.send(record.clone());
+            }
+        }
+        Ok((report, accepted))
+    }
+
+    fn remember_rejections(&mut self, client_id: Uuid, rejected: &[Rejection], now: DateTime<Utc>) {
+        if rejected.is_empty() {
+            return;
+        }
+        if !self.diagnostics.contains_key(&client_id) && self.diagnostics.len() >= DIAGNOSTICS_CLIENTS {
+            let last_rejected = |log: &VecDeque<Rejection>| log.back().map(|rejection| rejection.received_at);
+            self.diagnostics
+                .retain(|_, log| last_rejected(log).is_some_and(|at| now - at < DIAGNOSTICS_TTL));
+            if self.diagnostics.len() >= DIAGNOSTICS_CLIENTS {
+                let stalest = self
+                    .diagnostics
+                    .iter()
+                    .min_by_key(|(_, log)| last_rejected(log))
+                    .map(|(client_id, _)| *client_id);
+                if let Some(stalest) = stalest {
+                    self.diagnostics.remove(&stalest);
+                }
+            }
+        }
+        let log = self.diagnostics.entry(client_id).or_default();
+        log.extend(rejected.iter().cloned());
+        while log.len() > DIAGNOSTICS_PER_CLIENT {
+            log.pop_front();
+        }
+    }
+
+    fn check_rate(&mut self, client_id: Uuid) -> Result<(), TelemetryError> {
+        let now = Instant::now();
+        let minute = std::time::Duration::from_secs(60);
+        if self.windows.len() > 10_000 {
+            self.windows.retain(|_, (start, _)| now.duration_since(*start) < minute);
+        }
+        let (start, count) = self.windows.entry(client_id).or_insert((now, 0));
+        if now.duration_since(*start) >= minute {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= BATCHES_PER_MINUTE {
+            let retry = minute.saturating_sub(now.duration_since(*start)).as_secs().max(1);
+            return Err(TelemetryError::RateLimited(retry));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Forward accepted records to `url` in batches. Returns the sender to
+/// hand to [`TelemetryIngest::new`].
+pub fn spawn_forwarder(url: String) -> mpsc::UnboundedSender<AnalyticsRecord> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<AnalyticsRecord>();
+    info!("Forwarding client telemetry to {}", url);
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+        let mut buffer = Vec::with_capacity(FORWARD_BATCH);
+        let mut interval = tokio::time::interval(FORWARD_INTERVAL);
+        loop {
+            tokio::select! {
+                record = receiver.recv() => match record {
+                    Some(record) => {
+                        buffer.push(record);
+                        if buffer.len() < FORWARD_BATCH {
+                            continue;
+                        }
+                    }
+                    None => break,
+                },
+                _ = interval.tick() => {}
+            }
+            if buffer.is_empty() {
+                continue;
+            }
+            let records = std::mem::take(&mut buffer);
+            let result = client
+                .post(&url)
+                .json(&records)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                warn!("Failed to forward {} telemetry records: {}", records.len(), e);
+            }
+        }
+    });
+    sender
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_records_are_reported_without_failing_the_batch() {
+        let mut ingest = TelemetryIngest::new(None);
+        ingest
+            .set_sampling(SamplingRates { fps: 1.0, load_time: 1.0, client_error: 1.0 })
+            .unwrap();
+        let client_id = Uuid::new_v4();
+        let now = Utc::now();
+        let body = serde_json::json!({
+            "client_id": client_id,
+            "client_version": "0.4.2",
+            "platform": "web",
+            "records": [
+                { "metric": "fps", "value": 58.5, "recorded_at": now },
+                { "metric": "fps", "value": -3.0, "recorded_at": now },
+                { "metric": "load_time", "phase": "world_enter", "recorded_at": now },
+                { "metric": "memory", "value": 1, "recorded_at": now },
+                { "metric": "client_error", "message": "shader failed", "recorded_at": now },
+            ],
+        });
+
+        let (report, accepted) = ingest.ingest(body.to_string().as_bytes(), None).unwrap();
+        assert_eq!(report.accepted, 2);
+        assert_eq!(accepted.len(), 2);
+        let indices: Vec<usize> = report.rejected.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![1, 2, 3]);
+        assert!(report.rejected[1].error.contains("millis"));
+        assert_eq!(ingest.diagnostics(client_id).len(), 3);
+
+        assert!(matches!(ingest.ingest(b"{\"client_id\": 1}", None), Err(TelemetryError::Malformed(_))));
+        for _ in 1..BATCHES_PER_MINUTE {
+            ingest.ingest(body.to_string().as_bytes(), None).unwrap();
+        }
+        assert!(matches!(
+            ingest.ingest(body.to_string().as_bytes(), None),
+            Err(TelemetryError::RateLimited(_))
+        ));
+    }
+
+    #[test]
+    fn diagnostics_forget_the_stalest_client_when_full() {
+        let mut ingest = TelemetryIngest::new(None);
+        let now = Utc::now();
+        let rejection = |received_at| Rejection {
+            index: 0,
+            metric: None,
+            error: "bad".to_string(),
+            received_at,
+        };
+        let stalest = Uuid::new_v4();
+        ingest.remember_rejections(stalest, &[rejection(now - chrono::Duration::minutes(5))], now);
+        for _ in 1..DIAGNOSTICS_CLIENTS {
+            ingest.remember_rejections(Uuid::new_v4(), &[rejection(now)], now);
+        }
+        assert_eq!(ingest.diagnostics.len(), DIAGNOSTICS_CLIENTS);
+
+        let newest = Uuid::new_v4();
+        ingest.remember_rejections(newest, &[rejection(now)], now);
+        assert_eq!(ingest.diagnostics.len(), DIAGNOSTICS_CLIENTS);
+        assert!(ingest.diagnostics(stalest).is_empty());
+        assert_eq!(ingest.diagnostics(newest).len(), 1);
+
+        // Everything is forgotten once it has gone quiet for long enough
+        let later = now + DIAGNOSTICS_TTL + chrono::Duration::seconds(1);
+        ingest.remember_rejections(Uuid::new_v4(), &[rejection(later)], later);
+        assert_eq!(ingest.diagnostics.len(), 1);
+    }
+}
@@ -0,0 +1,123 @@
+// services/api-gateway/src/tenancy.rs
+//! Works out which world a request is for. Partner shards and staging
+//! events get their own host names; the tenant header can narrow a
+//! request further but never cross from one tenant's host to another.
+
+use axum::http::{header, HeaderMap};
+use finalverse_config::TenancyConfig;
+use finalverse_core::{TenantId, TENANT_HEADER};
+use service_registry::LocalServiceRegistry;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TenantError {
+    #[error("Invalid tenant header: {0}")]
+    Invalid(String),
+
+    #[error("Unknown tenant: {0}")]
+    Unknown(TenantId),
+
+    #[error("Host belongs to tenant {host}, not {requested}")]
+    HostMismatch { host: TenantId, requested: TenantId },
+
+    #[error("Tenant {tenant} has no {service} deployment")]
+    NoDeployment { tenant: TenantId, service: String },
+
+    #[error("Upstream request failed: {0}")]
+    Upstream(String),
+}
+
+pub struct TenantRouter {
+    tenants: HashSet<TenantId>,
+    hosts: HashMap<String, TenantId>,
+}
+
+impl TenantRouter {
+    /// Tenants with invalid ids are skipped; the config validator reports them.
+    pub fn new(config: &TenancyConfig) -> Self {
+        let mut tenants = HashSet::from([TenantId::default()]);
+        let mut hosts = HashMap::new();
+        for tenant in &config.tenants {
+            let Ok(id) = TenantId::parse(&tenant.id) else { continue };
+            for host in &tenant.hosts {
+                hosts.insert(host.to_ascii_lowercase(), id.clone());
+            }
+            tenants.insert(id);
+        }
+        Self { tenants, hosts }
+    }
+
+    /// Register every tenant's own service deployments.
+    pub async fn register_services(config: &TenancyConfig, registry: &LocalServiceRegistry) {
+        for tenant in &config.tenants {
+            let Ok(id) = TenantId::parse(&tenant.id) else { continue };
+            for (service, url) in &tenant.services {
+                registry.register_tenant_service(id.clone(), service.clone(), url.clone()).await;
+            }
+        }
+    }
+
+    pub fn resolve(&self, headers: &HeaderMap) -> Result<TenantId, TenantError> {
+        let host = headers
+            .get(header::HOST)
+            .and_then(|value| value.to_str().ok())
+            .map(|host| host.split(':').next().unwrap_or(host).to_ascii_lowercase())
+            .and_then(|host| self.hosts.get(&host).cloned());
+
+        let requested = match headers.get(TENANT_HEADER) {
+            Some(value) => {
+                let value = value.to_str().map_err(|_| TenantError::Invalid("not ASCII".to_string()))?;
+                let id = TenantId::parse(value).map_err(|_| TenantError::Invalid(value.to_string()))?;
+                if !self.tenants.contains(&id) {
+                    return Err(TenantError::Unknown(id));
+                }
+                Some(id)
+            }
+            None => None,
+        };
+
+        match (host, requested) {
+            (Some(host), Some(requested)) if host != requested => Err(TenantError::HostMismatch { host, requested }),
+            (Some(tenant), _) | (None, Some(tenant)) => Ok(tenant),
+            (None, None) => Ok(TenantId::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finalverse_config::TenantConfig;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn requests_cannot_cross_tenants() {
+        let router = TenantRouter::new(&TenancyConfig {
+            tenants: vec![TenantConfig {
+                id: "partner-eu".to_string(),
+                hosts: vec!["eu.partner.example".to_string()],
+                services: HashMap::new(),
+            }],
+        });
+        let partner = TenantId::parse("partner-eu").unwrap();
+
+        assert_eq!(router.resolve(&headers(&[])).unwrap(), TenantId::default());
+        assert_eq!(router.resolve(&headers(&[("host", "EU.partner.example:443")])).unwrap(), partner);
+        assert_eq!(router.resolve(&headers(&[(TENANT_HEADER, "partner-eu")])).unwrap(), partner);
+
+        assert!(matches!(
+            router.resolve(&headers(&[("host", "eu.partner.example"), (TENANT_HEADER, "default")])),
+            Err(TenantError::HostMismatch { .. })
+        ));
+        assert!(matches!(router.resolve(&headers(&[(TENANT_HEADER, "staging")])), Err(TenantError::Unknown(_))));
+        assert!(matches!(router.resolve(&headers(&[(TENANT_HEADER, "../admin")])), Err(TenantError::Invalid(_))));
+    }
+}
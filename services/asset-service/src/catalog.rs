@@ -0,0 +1,318 @@
+// services/asset-service/src/catalog.rs
+// Content-addressed assets: bytes stored once under their SHA-256, with
+// metadata and tags kept beside them
+
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::storage::{Storage, StorageError};
+
+const BLOB_PREFIX: &str = "blobs/";
+const META_PREFIX: &str = "meta/";
+
+#[derive(Debug, Error)]
+pub enum AssetError {
+    #[error("Asset not found: {0}")]
+    NotFound(String),
+
+    #[error("Not a SHA-256 hash: {0}")]
+    InvalidHash(String),
+
+    #[error("Asset is empty")]
+    Empty,
+
+    #[error("Range not satisfiable for an asset of {0} bytes")]
+    RangeNotSatisfiable(u64),
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+
+    #[error("Corrupt asset metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+}
+
+impl IntoResponse for AssetError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            AssetError::NotFound(_) => StatusCode::NOT_FOUND,
+            AssetError::InvalidHash(_) | AssetError::Empty => StatusCode::BAD_REQUEST,
+            AssetError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+            AssetError::Storage(_) | AssetError::Metadata(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Texture,
+    Mesh,
+    Audio,
+    Other,
+}
+
+impl AssetKind {
+    /// The kind a content type suggests.
+    pub fn of_content_type(content_type: &str) -> Self {
+        match content_type.split('/').next() {
+            Some("image") => AssetKind::Texture,
+            Some("model") => AssetKind::Mesh,
+            Some("audio") => AssetKind::Audio,
+            _ => AssetKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetMetadata {
+    /// Hex SHA-256 of the content, which is also the asset's id.
+    pub hash: String,
+    pub kind: AssetKind,
+    pub content_type: String,
+    pub size: u64,
+    /// Name it was first uploaded under, for people browsing assets.
+    pub name: Option<String>,
+    pub tags: BTreeSet<String>,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// What an uploader says about the bytes it sends.
+#[derive(Debug, Default)]
+pub struct Upload {
+    pub name: Option<String>,
+    pub kind: Option<AssetKind>,
+    pub content_type: Option<String>,
+    pub tags: BTreeSet<String>,
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn check_hash(hash: &str) -> Result<(), AssetError> {
+    if hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        Ok(())
+    } else {
+        Err(AssetError::InvalidHash(hash.to_string()))
+    }
+}
+
+/// Blobs are spread over directories by their first two hex digits.
+fn blob_key(hash: &str) -> String {
+    format!("{}{}/{}", BLOB_PREFIX, &hash[..2], hash)
+}
+
+fn meta_key(hash: &str) -> String {
+    format!("{}{}.json", META_PREFIX, hash)
+}
+
+/// The byte range a `Range` header asks for in an asset of `size` bytes.
+/// Only single ranges are supported; anything else serves the whole asset,
+/// as RFC 9110 allows.
+pub fn parse_range(header: &str, size: u64) -> Result<Option<Range<u64>>, AssetError> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return Ok(None),
+        // The last `suffix` bytes
+        ("", suffix) => {
+            let Ok(suffix) = suffix.parse::<u64>() else {
+                return Ok(None);
+            };
+            (size.saturating_sub(suffix), size)
+        }
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return Ok(None);
+            };
+            let end = match end {
+                "" => size,
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => (end + 1).min(size),
+                    _ => return Ok(None),
+                },
+            };
+            (start, end)
+        }
+    };
+    if start >= end {
+        return Err(AssetError::RangeNotSatisfiable(size));
+    }
+    Ok(Some(start..end))
+}
+
+/// Every asset's metadata, indexed in memory over a [`Storage`].
+pub struct Catalog {
+    storage: Box<dyn Storage>,
+    index: RwLock<HashMap<String, AssetMetadata>>,
+}
+
+impl Catalog {
+    /// Index the metadata already in `storage`. Unreadable entries are
+    /// skipped with a warning rather than keeping the service down.
+    pub async fn open(storage: Box<dyn Storage>) -> Result<Self, AssetError> {
+        let mut index = HashMap::new();
+        for key in storage.list(META_PREFIX).await? {
+            let Some(bytes) = storage.get(&key, None).await? else {
+                continue;
+            };
+            match serde_json::from_slice::<AssetMetadata>(&bytes) {
+                Ok(metadata) => {
+                    index.insert(metadata.hash.clone(), metadata);
+                }
+                Err(e) => warn!("Skipping asset metadata {}: {}", key, e),
+            }
+        }
+        Ok(Self {
+            storage,
+            index: RwLock::new(index),
+        })
+    }
+
+    /// Store `bytes`, returning its metadata and whether it is new. Uploading
+    /// content that is already stored only adds the upload's tags.
+    pub async fn upload(&self, bytes: Bytes, upload: Upload) -> Result<(AssetMetadata, bool), AssetError> {
+        if bytes.is_empty() {
+            return Err(AssetError::Empty);
+        }
+        let hash = content_hash(&bytes);
+
+        let existing = self.index.read().await.get(&hash).cloned();
+        if let Some(mut metadata) = existing {
+            if !upload.tags.is_subset(&metadata.tags) {
+                metadata.tags.extend(upload.tags);
+                self.save_metadata(&metadata).await?;
+            }
+            return Ok((metadata, false));
+        }
+
+        let content_type = upload
+            .content_type
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        let metadata = AssetMetadata {
+            kind: upload
+                .kind
+                .unwrap_or_else(|| AssetKind::of_content_type(&content_type)),
+            hash: hash.clone(),
+            content_type,
+            size: bytes.len() as u64,
+            name: upload.name,
+            tags: upload.tags,
+            uploaded_at: Utc::now(),
+        };
+        // The blob goes first, so metadata never points at missing content
+        self.storage.put(&blob_key(&hash), bytes).await?;
+        self.save_metadata(&metadata).await?;
+        Ok((metadata, true))
+    }
+
+    pub async fn metadata(&self, hash: &str) -> Result<AssetMetadata, AssetError> {
+        check_hash(hash)?;
+        self.index
+            .read()
+            .await
+            .get(hash)
+            .cloned()
+            .ok_or_else(|| AssetError::NotFound(hash.to_string()))
+    }
+
+    /// Assets of `kind` carrying every one of `tags`, newest first.
+    pub async fn list(&self, kind: Option<AssetKind>, tags: &BTreeSet<String>) -> Vec<AssetMetadata> {
+        let mut assets: Vec<AssetMetadata> = self
+            .index
+            .read()
+            .await
+            .values()
+            .filter(|asset| kind.is_none_or(|kind| asset.kind == kind))
+            .filter(|asset| tags.is_subset(&asset.tags))
+            .cloned()
+            .collect();
+        assets.sort_by_key(|asset| std::cmp::Reverse(asset.uploaded_at));
+        assets
+    }
+
+    pub async fn set_tags(&self, hash: &str, tags: BTreeSet<String>) -> Result<AssetMetadata, AssetError> {
+        let mut metadata = self.metadata(hash).await?;
+        metadata.tags = tags;
+        self.save_metadata(&metadata).await?;
+        Ok(metadata)
+    }
+
+    /// An asset's content, or the part of it in `range`.
+    pub async fn read(&self, hash: &str, range: Option<Range<u64>>) -> Result<Bytes, AssetError> {
+        check_hash(hash)?;
+        self.storage
+            .get(&blob_key(hash), range)
+            .await?
+            .ok_or_else(|| AssetError::NotFound(hash.to_string()))
+    }
+
+    async fn save_metadata(&self, metadata: &AssetMetadata) -> Result<(), AssetError> {
+        let json = serde_json::to_vec(metadata)?;
+        self.storage.put(&meta_key(&metadata.hash), json.into()).await?;
+        self.index
+            .write()
+            .await
+            .insert(metadata.hash.clone(), metadata.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FsStorage;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000).unwrap(), Some(0..100));
+        assert_eq!(parse_range("bytes=900-", 1000).unwrap(), Some(900..1000));
+        assert_eq!(parse_range("bytes=-100", 1000).unwrap(), Some(900..1000));
+        assert_eq!(parse_range("bytes=990-2000", 1000).unwrap(), Some(990..1000));
+        assert_eq!(parse_range("bytes=0-1,5-9", 1000).unwrap(), None);
+        assert!(matches!(parse_range("bytes=1000-", 1000), Err(AssetError::RangeNotSatisfiable(1000))));
+    }
+
+    #[tokio::test]
+    async fn test_uploads_are_deduplicated_and_survive_reopening() {
+        let root = std::env::temp_dir().join(format!("asset-catalog-{}", content_hash(Utc::now().to_rfc3339().as_bytes())));
+        let catalog = Catalog::open(Box::new(FsStorage::new(&root))).await.unwrap();
+
+        let bytes = Bytes::from_static(b"a tiny texture");
+        let upload = |tag: &str| Upload {
+            content_type: Some("image/png".to_string()),
+            tags: BTreeSet::from([tag.to_string()]),
+            ..Default::default()
+        };
+        let (first, created) = catalog.upload(bytes.clone(), upload("forest")).await.unwrap();
+        assert!(created);
+        assert_eq!(first.kind, AssetKind::Texture);
+        let (second, created) = catalog.upload(bytes, upload("moss")).await.unwrap();
+        assert!(!created);
+        assert_eq!(second.hash, first.hash);
+        assert_eq!(second.tags.len(), 2);
+
+        let reopened = Catalog::open(Box::new(FsStorage::new(&root))).await.unwrap();
+        assert_eq!(reopened.list(None, &BTreeSet::from(["moss".to_string()])).await.len(), 1);
+        assert_eq!(&reopened.read(&first.hash, Some(2..6)).await.unwrap()[..], b"tiny");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,10 +1,54 @@
-use axum::Router;
-use finalverse_health::HealthMonitor;
+mod catalog;
+mod storage;
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use catalog::{AssetError, AssetKind, AssetMetadata, Catalog, Upload};
+use finalverse_health::{DebugEndpoints, HealthMonitor};
+use serde::Deserialize;
 use service_registry::LocalServiceRegistry;
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
 use tracing::info;
 use finalverse_logging as logging;
 
+/// Largest upload accepted when `ASSET_MAX_UPLOAD_BYTES` isn't set.
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct UploadQuery {
+    name: Option<String>,
+    kind: Option<AssetKind>,
+    /// Comma-separated.
+    tags: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    kind: Option<AssetKind>,
+    /// Comma-separated; assets must carry all of them.
+    tags: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TagsRequest {
+    tags: BTreeSet<String>,
+}
+
+fn split_tags(tags: Option<&str>) -> BTreeSet<String> {
+    tags.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
@@ -14,7 +58,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .register_service("asset-service".to_string(), "http://localhost:3007".to_string())
         .await;
 
-    let app = Router::new().merge(monitor.clone().axum_routes());
+    let catalog = Arc::new(Catalog::open(storage::from_env().await?).await?);
+    let max_upload = std::env::var("ASSET_MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES);
+
+    let app = Router::new()
+        .route("/assets", get(list_assets).post(upload_asset))
+        .route("/assets/:hash", get(download_asset))
+        .route("/assets/:hash/metadata", get(asset_metadata))
+        .route("/assets/:hash/tags", put(set_tags))
+        .layer(DefaultBodyLimit::max(max_upload))
+        .with_state(catalog)
+        .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("asset-service")).axum_routes());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3007));
     info!("Asset Service listening on {}", addr);
@@ -22,3 +80,109 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     axum::serve(listener, app).await?;
     Ok(())
 }
+
+/// Store the request body; 201 if it is new, 200 if the same content was
+/// already stored.
+async fn upload_asset(
+    State(catalog): State<Arc<Catalog>>,
+    Query(query): Query<UploadQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<AssetMetadata>), AssetError> {
+    let upload = Upload {
+        tags: split_tags(query.tags.as_deref()),
+        name: query.name,
+        kind: query.kind,
+        content_type: headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+    };
+    let (metadata, created) = catalog.upload(body, upload).await?;
+    if created {
+        info!("Stored {:?} asset {} ({} bytes)", metadata.kind, metadata.hash, metadata.size);
+    }
+    let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+    Ok((status, Json(metadata)))
+}
+
+async fn list_assets(State(catalog): State<Arc<Catalog>>, Query(query): Query<ListQuery>) -> Json<Vec<AssetMetadata>> {
+    Json(catalog.list(query.kind, &split_tags(query.tags.as_deref())).await)
+}
+
+async fn asset_metadata(
+    State(catalog): State<Arc<Catalog>>,
+    Path(hash): Path<String>,
+) -> Result<Json<AssetMetadata>, AssetError> {
+    Ok(Json(catalog.metadata(&hash).await?))
+}
+
+async fn set_tags(
+    State(catalog): State<Arc<Catalog>>,
+    Path(hash): Path<String>,
+    Json(request): Json<TagsRequest>,
+) -> Result<Json<AssetMetadata>, AssetError> {
+    Ok(Json(catalog.set_tags(&hash, request.tags).await?))
+}
+
+/// An asset's content. Honours single `Range` requests so clients can
+/// stream large meshes and audio, and `If-None-Match`, since content never
+/// changes under a hash.
+async fn download_asset(
+    State(catalog): State<Arc<Catalog>>,
+    Path(hash): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AssetError> {
+    let metadata = catalog.metadata(&hash).await?;
+    let etag = format!("\"{}\"", metadata.hash);
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::ETAG, HeaderValue::from_str(&etag).expect("hex is a valid header"));
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    response_headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=31536000, immutable"),
+    );
+
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+    {
+        return Ok((StatusCode::NOT_MODIFIED, response_headers).into_response());
+    }
+
+    if let Ok(content_type) = HeaderValue::from_str(&metadata.content_type) {
+        response_headers.insert(header::CONTENT_TYPE, content_type);
+    }
+
+    let range = match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(range) => match catalog::parse_range(range, metadata.size) {
+            Ok(range) => range,
+            Err(e) => {
+                let unsatisfied = format!("bytes */{}", metadata.size);
+                response_headers.insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&unsatisfied).expect("digits are a valid header"),
+                );
+                return Ok((response_headers, e).into_response());
+            }
+        },
+        None => None,
+    };
+
+    match range {
+        Some(range) => {
+            let content_range = format!("bytes {}-{}/{}", range.start, range.end - 1, metadata.size);
+            response_headers.insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&content_range).expect("digits are a valid header"),
+            );
+            let bytes = catalog.read(&hash, Some(range)).await?;
+            Ok((StatusCode::PARTIAL_CONTENT, response_headers, bytes).into_response())
+        }
+        None => {
+            let bytes = catalog.read(&hash, None).await?;
+            Ok((response_headers, bytes).into_response())
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// services/asset-service/src/storage.rs
+// Where asset bytes and metadata live: a local directory, or an S3 bucket
+// when built with the `s3` feature
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use std::ops::Range;
+use std::path::PathBuf;
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Storage I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Storage backend failed: {0}")]
+    Backend(String),
+
+    #[error("Unknown ASSET_STORAGE backend: {0}")]
+    UnknownBackend(String),
+}
+
+/// Flat key/value object storage. Keys are `/`-separated paths.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Store `bytes` under `key`, replacing anything already there.
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError>;
+
+    /// The bytes under `key`, or only those in `range`; None if there is
+    /// no such key.
+    async fn get(&self, key: &str, range: Option<Range<u64>>) -> Result<Option<Bytes>, StorageError>;
+
+    /// Every key starting with `prefix`.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError>;
+}
+
+/// The backend `ASSET_STORAGE` names: `fs` (the default) stores under
+/// `ASSET_DIR`, `s3` in `ASSET_S3_BUCKET` under `ASSET_S3_PREFIX`.
+pub async fn from_env() -> Result<Box<dyn Storage>, StorageError> {
+    match std::env::var("ASSET_STORAGE").as_deref() {
+        Err(_) | Ok("fs") => {
+            let root = std::env::var("ASSET_DIR").unwrap_or_else(|_| "data/assets".to_string());
+            Ok(Box::new(FsStorage::new(root)))
+        }
+        #[cfg(feature = "s3")]
+        Ok("s3") => {
+            let bucket = std::env::var("ASSET_S3_BUCKET")
+                .map_err(|_| StorageError::Backend("ASSET_S3_BUCKET is not set".to_string()))?;
+            let prefix = std::env::var("ASSET_S3_PREFIX").unwrap_or_default();
+            Ok(Box::new(S3Storage::from_env(bucket, prefix).await))
+        }
+        #[cfg(not(feature = "s3"))]
+        Ok("s3") => Err(StorageError::Backend(
+            "asset-service was built without the s3 feature".to_string(),
+        )),
+        Ok(other) => Err(StorageError::UnknownBackend(other.to_string())),
+    }
+}
+
+/// Objects as files under a root directory.
+pub struct FsStorage {
+    root: PathBuf,
+}
+
+impl FsStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl Storage for FsStorage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        let path = self.path(key);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        // Written aside and renamed, so a reader never sees half an object
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, &bytes).await?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, range: Option<Range<u64>>) -> Result<Option<Bytes>, StorageError> {
+        let mut file = match tokio::fs::File::open(self.path(key)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut bytes = Vec::new();
+        match range {
+            Some(range) => {
+                file.seek(std::io::SeekFrom::Start(range.start)).await?;
+                file.take(range.end - range.start).read_to_end(&mut bytes).await?;
+            }
+            None => {
+                file.read_to_end(&mut bytes).await?;
+            }
+        }
+        Ok(Some(bytes.into()))
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut keys = Vec::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "partial") {
+                    continue;
+                } else if let Ok(relative) = path.strip_prefix(&self.root) {
+                    let key = relative
+                        .components()
+                        .map(|part| part.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if key.starts_with(prefix) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// Objects in an S3 bucket, with credentials and region from the usual AWS
+/// environment.
+#[cfg(feature = "s3")]
+pub struct S3Storage {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+    prefix: String,
+}
+
+#[cfg(feature = "s3")]
+impl S3Storage {
+    pub async fn from_env(bucket: String, prefix: String) -> Self {
+        let config = aws_config::load_from_env().await;
+        Self {
+            client: aws_sdk_s3::Client::new(&config),
+            bucket,
+            prefix,
+        }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[cfg(feature = "s3")]
+fn backend_error(e: impl std::fmt::Display) -> StorageError {
+    StorageError::Backend(e.to_string())
+}
+
+#[cfg(feature = "s3")]
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key))
+            .body(bytes.into())
+            .send()
+            .await
+            .map_err(backend_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, range: Option<Range<u64>>) -> Result<Option<Bytes>, StorageError> {
+        let mut request = self.client.get_object().bucket(&self.bucket).key(self.object_key(key));
+        if let Some(range) = range {
+            request = request.range(format!("bytes={}-{}", range.start, range.end - 1));
+        }
+        let object = match request.send().await {
+            Ok(object) => object,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => return Ok(None),
+            Err(e) => return Err(backend_error(e)),
+        };
+        let body = object.body.collect().await.map_err(backend_error)?;
+        Ok(Some(body.into_bytes()))
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut keys = Vec::new();
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(self.object_key(prefix))
+            .into_paginator()
+            .send();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(backend_error)?;
+            keys.extend(
+                page.contents()
+                    .iter()
+                    .filter_map(|object| object.key())
+                    .filter_map(|key| key.strip_prefix(&self.prefix))
+                    .map(str::to_string),
+            );
+        }
+        Ok(keys)
+    }
+}
@@ -1,29 +1,41 @@
 use axum::{
     extract::{Path, State},
-    routing::post,
+    http::StatusCode,
+    routing::{delete, get, post},
     Json, Router,
 };
-use finalverse_health::HealthMonitor;
+use finalverse_health::{DebugEndpoints, HealthMonitor};
 use service_registry::LocalServiceRegistry;
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
-use tracing::info;
+use tracing::{info, warn};
 use finalverse_logging as logging;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
-use mapleai_agent::Agent;
+use mapleai_agent::{Agent, BehaviorTree};
+use finalverse_core::TenantId;
 use finalverse_protocol::{BehaviorAction, ReasoningContext};
 
+mod memory;
+mod tick;
+use memory::{Episode, MemoryStore, RECALL_LIMIT};
+
 type Agents = Arc<RwLock<HashMap<String, Agent>>>;
 
 #[derive(Clone)]
 struct AppState {
     agents: Agents,
+    memory: Arc<MemoryStore>,
+    /// Behavior trees agents can be spawned with, by name.
+    behaviors: Arc<HashMap<String, Arc<BehaviorTree>>>,
 }
 
 #[derive(Deserialize)]
 struct SpawnRequest {
     id: String,
     region: String,
+    /// A behavior tree from `BEHAVIOR_TREES_DIR`; without one the agent
+    /// uses the built-in heuristics.
+    behavior: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -34,10 +46,25 @@ struct SpawnResponse {
 async fn spawn_agent(
     State(state): State<AppState>,
     Json(req): Json<SpawnRequest>,
-) -> Json<SpawnResponse> {
+) -> Result<Json<SpawnResponse>, StatusCode> {
+    let mut agent = Agent::new(req.id.clone(), req.region);
+    if let Some(name) = &req.behavior {
+        let tree = state.behaviors.get(name).ok_or(StatusCode::BAD_REQUEST)?;
+        agent = agent.with_behavior(tree.clone());
+    }
+    let mut agents = state.agents.write().await;
+    agents.insert(req.id.clone(), agent);
+    Ok(Json(SpawnResponse { id: req.id }))
+}
+
+/// Drop an agent, e.g. a background NPC no player is near any more.
+async fn despawn_agent(
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SpawnResponse>, StatusCode> {
     let mut agents = state.agents.write().await;
-    agents.insert(req.id.clone(), Agent::new(req.id.clone(), req.region));
-    Json(SpawnResponse { id: req.id })
+    agents.remove(&id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(SpawnResponse { id }))
 }
 
 #[derive(Deserialize)]
@@ -46,6 +73,8 @@ struct ActRequest {
     nearby_entities: Vec<String>,
     harmony_level: f32,
     tension: f32,
+    /// Short-term memory from the caller; the agent's own long-term
+    /// memories are recalled ahead of it.
     memory: Vec<String>,
 }
 
@@ -74,58 +103,125 @@ fn to_dto(action: BehaviorAction) -> ActionDto {
     }
 }
 
-async fn act_agent(
-    Path(id): Path<String>,
-    State(state): State<AppState>,
-    Json(req): Json<ActRequest>,
-) -> Option<Json<ActResponse>> {
+/// Advance one agent a step. Without a context the agent carries on from
+/// its last one, less the caller's short-term memory. None if there is no
+/// such agent, or it is being advanced already.
+async fn advance(state: &AppState, id: &str, req: Option<ActRequest>) -> Option<BehaviorAction> {
     // Remove the agent from the map so the lock isn't held across `.await`
     let mut agent = {
         let mut agents = state.agents.write().await;
-        agents.remove(&id)?
+        agents.remove(id)?
     };
+    let req = req.unwrap_or_else(|| {
+        let context = &agent.state().context;
+        ActRequest {
+            location: context.location.clone(),
+            nearby_entities: context.nearby_entities.clone(),
+            harmony_level: context.harmony_level,
+            tension: context.tension,
+            memory: Vec::new(),
+        }
+    });
+
+    let recalled = match state.memory.recall(id, &req.nearby_entities, RECALL_LIMIT).await {
+        Ok(episodes) => episodes,
+        Err(e) => {
+            warn!("Failed to recall memories of {}: {}", id, e);
+            Vec::new()
+        }
+    };
+    let mut memory: Vec<String> = recalled.into_iter().map(|episode| episode.text).collect();
+    memory.extend(req.memory);
 
     let ctx = ReasoningContext {
-        location: req.location,
-        nearby_entities: req.nearby_entities,
+        location: req.location.clone(),
+        nearby_entities: req.nearby_entities.clone(),
         harmony_level: req.harmony_level,
         tension: req.tension,
-        memory: req.memory,
+        memory,
     };
     agent.update_context(ctx);
     agent.step().await;
     let last_action = agent.state().last_action.clone();
 
+    if let Some(episode) = last_action
+        .as_ref()
+        .and_then(|action| Episode::from_action(action, &req.location, &req.nearby_entities, req.tension))
+    {
+        if let Err(e) = state.memory.remember(id, &episode).await {
+            warn!("Failed to remember for {}: {}", id, e);
+        }
+    }
+
     // Put the agent back into the map after the async call completes
     {
         let mut agents = state.agents.write().await;
-        agents.insert(id, agent);
+        agents.insert(id.to_string(), agent);
     }
 
-    if let Some(action) = last_action {
-        Some(Json(ActResponse { action: to_dto(action) }))
-    } else {
-        None
-    }
+    last_action
+}
+
+/// Advance one agent with the caller's context. 404 if there is no such
+/// agent or it is mid-step already.
+async fn act_agent(
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+    Json(req): Json<ActRequest>,
+) -> Result<Json<ActResponse>, StatusCode> {
+    let action = advance(&state, &id, Some(req)).await.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(ActResponse { action: to_dto(action) }))
+}
+
+/// What an agent remembers, newest first. Survives despawning.
+async fn agent_memories(
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Episode>>, StatusCode> {
+    state.memory.episodes(&id).await.map(Json).map_err(|e| {
+        warn!("Failed to read memories of {}: {}", id, e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
+    let metrics = finalverse_metrics::init("behavior-ai")?;
+    tick::TICK_BATCH_DURATION.describe();
+    tick::TICK_BATCH_SIZE.describe();
     let monitor = Arc::new(HealthMonitor::new("behavior-ai", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
         .register_service("behavior-ai".to_string(), "http://localhost:3011".to_string())
         .await;
 
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let behaviors = match std::env::var("BEHAVIOR_TREES_DIR") {
+        Ok(dir) => BehaviorTree::load_dir(dir)?,
+        Err(_) => HashMap::new(),
+    };
+    info!("Loaded {} behavior trees", behaviors.len());
     let state = AppState {
         agents: Arc::new(RwLock::new(HashMap::new())),
+        memory: Arc::new(MemoryStore::new(redis::Client::open(redis_url)?, TenantId::from_env())),
+        behaviors: Arc::new(behaviors.into_iter().map(|(name, tree)| (name, Arc::new(tree))).collect()),
     };
     let app = Router::new()
         .route("/agent/spawn", post(spawn_agent))
+        .route("/agent/:id", delete(despawn_agent))
         .route("/agent/:id/act", post(act_agent))
-        .with_state(state)
-        .merge(monitor.clone().axum_routes());
+        .route("/agent/:id/memories", get(agent_memories))
+        .route("/agents/tick", post(tick::tick_agents))
+        .with_state(state.clone())
+        .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("behavior-ai")).axum_routes())
+        .merge(metrics.axum_routes());
+
+    if let Some(interval) = tick::scheduler_interval() {
+        info!("Ticking every agent every {:?}", interval);
+        tick::spawn_scheduler(state, interval);
+    }
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3011));
     info!("Behavior AI listening on {}", addr);
@@ -0,0 +1,219 @@
+// services/behavior-ai/src/memory.rs
+// Long-term memory for NPCs: what each agent did and who was around when
+// it did, kept in Redis so agents remember players across sessions
+
+use chrono::{DateTime, Utc};
+use finalverse_core::TenantId;
+use finalverse_protocol::BehaviorAction;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+/// Episodes kept per agent; the oldest are forgotten first.
+pub const MAX_EPISODES: usize = 200;
+/// Episodes brought to mind before each step.
+pub const RECALL_LIMIT: usize = 5;
+/// Hours for an episode's recency to halve.
+const RECENCY_HALF_LIFE_HOURS: f64 = 24.0;
+/// Idle moments with no one around are below this and not remembered.
+const MIN_IMPORTANCE: f32 = 0.3;
+/// Attempts to reach Redis after the first before a recall gives up.
+const CONNECT_RETRIES: usize = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Episode {
+    pub id: Uuid,
+    pub recorded_at: DateTime<Utc>,
+    pub location: String,
+    pub text: String,
+    /// Players and creatures that were nearby.
+    pub entities: Vec<String>,
+    /// From 0 to 1.
+    pub importance: f32,
+}
+
+impl Episode {
+    /// The episode an agent's action makes, if it is worth remembering.
+    pub fn from_action(action: &BehaviorAction, location: &str, nearby: &[String], tension: f32) -> Option<Self> {
+        let importance = importance(action, tension);
+        if nearby.is_empty() && importance < MIN_IMPORTANCE {
+            return None;
+        }
+        Some(Self {
+            id: Uuid::new_v4(),
+            recorded_at: Utc::now(),
+            location: location.to_string(),
+            text: describe(action, location, nearby),
+            entities: nearby.to_vec(),
+            importance,
+        })
+    }
+
+    /// How readily the episode comes to mind at `now` with `nearby` around:
+    /// recent, important episodes about someone present score highest.
+    pub fn score(&self, now: DateTime<Utc>, nearby: &[String]) -> f64 {
+        let age_hours = (now - self.recorded_at).num_seconds().max(0) as f64 / 3600.0;
+        let recency = 0.5f64.powf(age_hours / RECENCY_HALF_LIFE_HOURS);
+        let relevance = if self.entities.iter().any(|entity| nearby.contains(entity)) {
+            1.0
+        } else {
+            0.0
+        };
+        recency + self.importance as f64 + relevance
+    }
+}
+
+fn importance(action: &BehaviorAction, tension: f32) -> f32 {
+    let base = match action {
+        BehaviorAction::Interact { .. } => 0.6,
+        BehaviorAction::Flee(_) => 0.5,
+        BehaviorAction::Migrate { .. } => 0.4,
+        BehaviorAction::Wander | BehaviorAction::Rest => 0.1,
+    };
+    (base + tension.clamp(0.0, 1.0) * 0.4).min(1.0)
+}
+
+fn describe(action: &BehaviorAction, location: &str, nearby: &[String]) -> String {
+    let what = match action {
+        BehaviorAction::Wander => "Wandered".to_string(),
+        BehaviorAction::Rest => "Rested".to_string(),
+        BehaviorAction::Flee(reason) => format!("Fled ({})", reason),
+        BehaviorAction::Migrate { target_region } => format!("Set off for {}", target_region),
+        BehaviorAction::Interact { entity_id, action } => format!("Did \"{}\" with {}", action, entity_id),
+    };
+    let place = if location.is_empty() { String::new() } else { format!(" at {}", location) };
+    if nearby.is_empty() {
+        format!("{}{}", what, place)
+    } else {
+        format!("{}{} while {} were near", what, place, nearby.join(", "))
+    }
+}
+
+/// Episodes in Redis, one list per agent with the newest first, scoped to
+/// the tenant this process serves.
+pub struct MemoryStore {
+    client: redis::Client,
+    /// Made on first use so behavior-ai starts without Redis.
+    connection: OnceCell<ConnectionManager>,
+    tenant: TenantId,
+}
+
+impl MemoryStore {
+    pub fn new(client: redis::Client, tenant: TenantId) -> Self {
+        Self {
+            client,
+            connection: OnceCell::new(),
+            tenant,
+        }
+    }
+
+    fn key(&self, agent_id: &str) -> String {
+        self.tenant.scoped_key(&format!("npc_memory:{}", agent_id))
+    }
+
+    async fn connection(&self) -> redis::RedisResult<ConnectionManager> {
+        Ok(self
+            .connection
+            .get_or_try_init(|| {
+                // Memory is best-effort: a step shouldn't wait out the
+                // default half-dozen retries while Redis is down
+                ConnectionManager::new_with_backoff(self.client.clone(), 2, 100, CONNECT_RETRIES)
+            })
+            .await?
+            .clone())
+    }
+
+    pub async fn remember(&self, agent_id: &str, episode: &Episode) -> redis::RedisResult<()> {
+        let json = serde_json::to_string(episode).map_err(|e| {
+            redis::RedisError::from((redis::ErrorKind::TypeError, "unserializable episode", e.to_string()))
+        })?;
+        let key = self.key(agent_id);
+        let mut connection = self.connection().await?;
+        redis::pipe()
+            .atomic()
+            .lpush(&key, json)
+            .ignore()
+            .ltrim(&key, 0, MAX_EPISODES as isize - 1)
+            .ignore()
+            .query_async(&mut connection)
+            .await
+    }
+
+    /// Everything the agent remembers, newest first.
+    pub async fn episodes(&self, agent_id: &str) -> redis::RedisResult<Vec<Episode>> {
+        let mut connection = self.connection().await?;
+        let stored: Vec<String> = connection.lrange(self.key(agent_id), 0, -1).await?;
+        Ok(stored
+            .iter()
+            .filter_map(|json| match serde_json::from_str(json) {
+                Ok(episode) => Some(episode),
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable memory of {}: {}", agent_id, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// The `limit` episodes that come to mind most readily with `nearby`
+    /// around, best first.
+    pub async fn recall(&self, agent_id: &str, nearby: &[String], limit: usize) -> redis::RedisResult<Vec<Episode>> {
+        let now = Utc::now();
+        let mut scored: Vec<(f64, Episode)> = self
+            .episodes(agent_id)
+            .await?
+            .into_iter()
+            .map(|episode| (episode.score(now, nearby), episode))
+            .collect();
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        Ok(scored.into_iter().take(limit).map(|(_, episode)| episode).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn nearby(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn idle_moments_alone_are_forgotten() {
+        assert!(Episode::from_action(&BehaviorAction::Rest, "glade", &[], 0.0).is_none());
+        // Tension makes even idling memorable
+        assert!(Episode::from_action(&BehaviorAction::Wander, "glade", &[], 1.0).is_some());
+
+        let episode = Episode::from_action(&BehaviorAction::Rest, "glade", &nearby(&["ayla"]), 0.0).unwrap();
+        assert_eq!(episode.text, "Rested at glade while ayla were near");
+        assert_eq!(episode.entities, ["ayla"]);
+    }
+
+    #[test]
+    fn interactions_outrank_idling_and_importance_is_capped() {
+        let greet = BehaviorAction::Interact { entity_id: "ayla".to_string(), action: "greet".to_string() };
+        let episode = Episode::from_action(&greet, "", &nearby(&["ayla"]), 1.0).unwrap();
+        assert_eq!(episode.text, "Did \"greet\" with ayla while ayla were near");
+        assert_eq!(episode.importance, 1.0);
+        assert!(importance(&greet, 0.0) > importance(&BehaviorAction::Wander, 0.0));
+    }
+
+    #[test]
+    fn recent_episodes_about_someone_present_come_to_mind_first() {
+        let now = Utc::now();
+        let flee = BehaviorAction::Flee("wolves".to_string());
+        let mut episode = Episode::from_action(&flee, "ford", &nearby(&["kael"]), 0.0).unwrap();
+        episode.recorded_at = now;
+
+        let with_kael = episode.score(now, &nearby(&["kael"]));
+        let alone = episode.score(now, &[]);
+        assert!((with_kael - alone - 1.0).abs() < 1e-9);
+
+        let mut old = episode.clone();
+        old.recorded_at = now - Duration::hours(RECENCY_HALF_LIFE_HOURS as i64);
+        assert!((episode.score(now, &[]) - old.score(now, &[]) - 0.5).abs() < 1e-6);
+    }
+}
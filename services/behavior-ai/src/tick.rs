@@ -0,0 +1,191 @@
+// services/behavior-ai/src/tick.rs
+// Advancing many agents at once: the batch endpoint regions are simulated
+// through, and a scheduler that ticks every agent on an interval
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, Json};
+use finalverse_metrics::{histogram, HistogramDef};
+use finalverse_protocol::BehaviorAction;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+
+use crate::{advance, to_dto, ActRequest, ActionDto, AppState};
+
+/// Agents advanced at once when a batch doesn't say.
+pub const DEFAULT_CONCURRENCY: usize = 32;
+/// Most agents one batch may advance at once.
+pub const MAX_CONCURRENCY: usize = 256;
+
+pub const TICK_BATCH_DURATION: HistogramDef<1> = HistogramDef::new(
+    "finalverse_behavior_tick_batch_seconds",
+    "Time taken to advance a batch of agents",
+    ["trigger"],
+);
+
+pub const TICK_BATCH_SIZE: HistogramDef<1> = HistogramDef::new(
+    "finalverse_behavior_tick_batch_agents",
+    "Agents advanced per batch",
+    ["trigger"],
+);
+
+#[derive(Deserialize)]
+pub struct TickRequest {
+    agents: Vec<AgentTick>,
+    /// Agents to advance at once, up to [`MAX_CONCURRENCY`].
+    concurrency: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct AgentTick {
+    id: String,
+    #[serde(flatten)]
+    context: ActRequest,
+}
+
+#[derive(Serialize)]
+pub struct TickResponse {
+    actions: Vec<AgentAction>,
+    /// Agents that weren't found, or were busy with another tick.
+    unknown: Vec<String>,
+    elapsed_ms: u64,
+}
+
+#[derive(Serialize)]
+struct AgentAction {
+    id: String,
+    action: ActionDto,
+}
+
+/// Advance each agent once, at most `concurrency` at a time, and return
+/// what each chose in the order given; None for agents that weren't
+/// advanced. An agent listed twice is advanced once, with its first
+/// context.
+async fn tick(
+    state: &AppState,
+    ticks: Vec<(String, Option<ActRequest>)>,
+    concurrency: usize,
+    trigger: &'static str,
+) -> Vec<(String, Option<BehaviorAction>)> {
+    let started = Instant::now();
+    let mut seen = HashSet::new();
+    let ticks: Vec<_> = ticks.into_iter().filter(|(id, _)| seen.insert(id.clone())).collect();
+    let count = ticks.len();
+
+    let permits = Arc::new(Semaphore::new(concurrency.clamp(1, MAX_CONCURRENCY)));
+    let mut tasks = JoinSet::new();
+    for (index, (id, context)) in ticks.into_iter().enumerate() {
+        let state = state.clone();
+        let permits = permits.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await.expect("tick semaphore is never closed");
+            let action = advance(&state, &id, context).await;
+            (index, id, action)
+        });
+    }
+
+    let mut results = vec![None; count];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, id, action)) => results[index] = Some((id, action)),
+            Err(e) => warn!("Agent tick failed: {}", e),
+        }
+    }
+
+    histogram!(TICK_BATCH_DURATION, trigger = trigger).record(started.elapsed().as_secs_f64());
+    histogram!(TICK_BATCH_SIZE, trigger = trigger).record(count as f64);
+    results.into_iter().flatten().collect()
+}
+
+/// `POST /agents/tick`: advance a batch of agents, each with its own context.
+pub async fn tick_agents(State(state): State<AppState>, Json(req): Json<TickRequest>) -> Json<TickResponse> {
+    let started = Instant::now();
+    let ticks = req.agents.into_iter().map(|tick| (tick.id, Some(tick.context))).collect();
+    let concurrency = req.concurrency.unwrap_or(DEFAULT_CONCURRENCY);
+
+    let mut actions = Vec::new();
+    let mut unknown = Vec::new();
+    for (id, action) in tick(&state, ticks, concurrency, "request").await {
+        match action {
+            Some(action) => actions.push(AgentAction { id, action: to_dto(action) }),
+            None => unknown.push(id),
+        }
+    }
+    Json(TickResponse {
+        actions,
+        unknown,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// How often the scheduler ticks every agent, from `AGENT_TICK_INTERVAL_MS`.
+/// None leaves agents to be advanced by requests alone.
+pub fn scheduler_interval() -> Option<Duration> {
+    std::env::var("AGENT_TICK_INTERVAL_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+}
+
+/// Tick every agent every `interval`, each carrying on from its last
+/// context. A tick that runs long delays the next rather than overlapping it.
+pub fn spawn_scheduler(state: AppState, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let ids: Vec<String> = state.agents.read().await.keys().cloned().collect();
+            if ids.is_empty() {
+                continue;
+            }
+            tick(&state, ids.into_iter().map(|id| (id, None)).collect(), DEFAULT_CONCURRENCY, "scheduler").await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStore;
+    use finalverse_core::TenantId;
+    use mapleai_agent::Agent;
+    use std::collections::HashMap;
+    use tokio::sync::RwLock;
+
+    fn state(agents: &[&str]) -> AppState {
+        let agents = agents
+            .iter()
+            .map(|id| (id.to_string(), Agent::new(id.to_string(), "grove".to_string())))
+            .collect();
+        AppState {
+            agents: Arc::new(RwLock::new(agents)),
+            // Nothing listens here: recall and remember fail and are skipped
+            memory: Arc::new(MemoryStore::new(
+                redis::Client::open("redis://127.0.0.1:1").unwrap(),
+                TenantId::default(),
+            )),
+            behaviors: Arc::new(HashMap::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn each_agent_is_advanced_once_in_the_order_given() {
+        let state = state(&["fox", "owl"]);
+        let ticks = ["owl", "ghost", "fox", "owl"].iter().map(|id| (id.to_string(), None)).collect();
+
+        let results = tick(&state, ticks, 1, "request").await;
+        let ids: Vec<_> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["owl", "ghost", "fox"]);
+        assert!(results[0].1.is_some() && results[2].1.is_some());
+        assert!(results[1].1.is_none());
+        // Advanced agents go back for the next tick
+        assert_eq!(state.agents.read().await.len(), 2);
+    }
+}
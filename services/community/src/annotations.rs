@@ -0,0 +1,399 @@
+// services/community/src/annotations.rs
+//! Persistent player annotations. Each community shard holds a replica of
+//! every grid document; local changes produce CRDT deltas that are shipped
+//! to the other shards over the event bus and merged there.
+
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::{
+    Annotation, AnnotationDoc, AnnotationGrid, AnnotationKind, AnnotationVisibility, Coordinates, RegionId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationPolicy {
+    pub max_text_len: usize,
+    /// Annotations one player may place per hour.
+    pub hourly_limit: usize,
+    /// Annotations one player may keep in a single grid cell.
+    pub per_player_per_grid: usize,
+    pub max_per_grid: usize,
+    /// Distinct reports that take an annotation down.
+    pub report_threshold: usize,
+}
+
+impl Default for AnnotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_text_len: 280,
+            hourly_limit: 20,
+            per_player_per_grid: 5,
+            max_per_grid: 200,
+            report_threshold: 3,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AnnotationError {
+    #[error("Unknown annotation {0}")]
+    UnknownAnnotation(Uuid),
+
+    #[error("Only the author or the region's stewards may remove this annotation")]
+    NotPermitted,
+
+    #[error("Annotations are disabled in this region")]
+    Disabled,
+
+    #[error("Public annotations are not allowed in this region")]
+    PublicNotAllowed,
+
+    #[error("Invalid annotation: {0}")]
+    Invalid(String),
+
+    #[error("Annotation rejected by moderation: {0}")]
+    Rejected(String),
+
+    #[error("Annotation limit reached; try again in {0} seconds")]
+    RateLimited(i64),
+
+    #[error("This area already holds as many annotations as it can")]
+    GridFull,
+
+    #[error("Player {0} has already reported this annotation")]
+    AlreadyReported(Uuid),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionAnnotationSettings {
+    pub enabled: bool,
+    pub allow_public: bool,
+}
+
+impl Default for RegionAnnotationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allow_public: true,
+        }
+    }
+}
+
+/// What a player chooses to see.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerAnnotationSettings {
+    #[serde(default = "default_true")]
+    pub show_annotations: bool,
+    #[serde(default)]
+    pub muted_authors: HashSet<Uuid>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PlayerAnnotationSettings {
+    fn default() -> Self {
+        Self {
+            show_annotations: true,
+            muted_authors: HashSet::new(),
+        }
+    }
+}
+
+pub enum ModerationVerdict {
+    Allow,
+    Reject(String),
+}
+
+/// Reviews annotations before they are placed.
+pub trait ModerationHook: Send + Sync {
+    fn review(&self, annotation: &Annotation) -> ModerationVerdict;
+}
+
+/// Rejects text containing any of the listed terms, ignoring case.
+pub struct BlockedTerms(pub Vec<String>);
+
+impl ModerationHook for BlockedTerms {
+    fn review(&self, annotation: &Annotation) -> ModerationVerdict {
+        let text = annotation.text.to_lowercase();
+        match self.0.iter().find(|term| text.contains(&term.to_lowercase())) {
+            Some(_) => ModerationVerdict::Reject("contains blocked language".to_string()),
+            None => ModerationVerdict::Allow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewAnnotation {
+    pub position: Coordinates,
+    pub kind: AnnotationKind,
+    pub text: String,
+    pub visibility: AnnotationVisibility,
+}
+
+/// A local change for the other shards.
+pub struct AnnotationChange {
+    pub grid: AnnotationGrid,
+    pub delta: AnnotationDoc,
+}
+
+pub struct AnnotationRegistry {
+    shard_id: Uuid,
+    policy: AnnotationPolicy,
+    docs: HashMap<AnnotationGrid, AnnotationDoc>,
+    regions: HashMap<RegionId, RegionAnnotationSettings>,
+    players: HashMap<Uuid, PlayerAnnotationSettings>,
+    /// When each player last placed annotations, for the hourly limit.
+    placed: HashMap<Uuid, VecDeque<DateTime<Utc>>>,
+    reports: HashMap<Uuid, HashSet<Uuid>>,
+    hooks: Vec<Box<dyn ModerationHook>>,
+}
+
+impl AnnotationRegistry {
+    pub fn new(shard_id: Uuid, policy: AnnotationPolicy) -> Self {
+        Self {
+            shard_id,
+            policy,
+            docs: HashMap::new(),
+            regions: HashMap::new(),
+            players: HashMap::new(),
+            placed: HashMap::new(),
+            reports: HashMap::new(),
+            hooks: Vec::new(),
+        }
+    }
+
+    pub fn with_hook(mut self, hook: impl ModerationHook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    pub fn shard_id(&self) -> Uuid {
+        self.shard_id
+    }
+
+    pub fn region_settings(&self, region_id: &RegionId) -> RegionAnnotationSettings {
+        self.regions.get(region_id).cloned().unwrap_or_default()
+    }
+
+    pub fn set_region_settings(&mut self, region_id: RegionId, settings: RegionAnnotationSettings) {
+        self.regions.insert(region_id, settings);
+    }
+
+    pub fn player_settings(&self, player_id: Uuid) -> PlayerAnnotationSettings {
+        self.players.get(&player_id).cloned().unwrap_or_default()
+    }
+
+    pub fn set_player_settings(&mut self, player_id: Uuid, settings: PlayerAnnotationSettings) {
+        self.players.insert(player_id, settings);
+    }
+
+    pub fn get(&self, annotation_id: Uuid) -> Option<&Annotation> {
+        self.docs.values().find_map(|doc| doc.get(&annotation_id))
+    }
+
+    pub fn place(
+        &mut self,
+        region_id: RegionId,
+        author_id: Uuid,
+        request: NewAnnotation,
+        now: DateTime<Utc>,
+    ) -> Result<(Annotation, AnnotationChange), AnnotationError> {
+        let settings = self.region_settings(&region_id);
+        if !settings.enabled {
+            return Err(AnnotationError::Disabled);
+        }
+        if request.visibility == AnnotationVisibility::Public && !settings.allow_public {
+            return Err(AnnotationError::PublicNotAllowed);
+        }
+        let text = request.text.trim().to_string();
+        if text.is_empty() && request.kind == AnnotationKind::Note {
+            return Err(AnnotationError::Invalid("notes need text".to_string()));
+        }
+        if text.chars().count() > self.policy.max_text_len {
+            return Err(AnnotationError::Invalid(format!(
+                "text is longer than {} characters",
+                self.policy.max_text_len
+            )));
+        }
+
+        let annotation = Annotation {
+            id: Uuid::new_v4(),
+            author_id,
+            grid: AnnotationGrid::containing(region_id, &request.position),
+            position: request.position,
+            kind: request.kind,
+            text,
+            visibility: request.visibility,
+            created_at: now,
+        };
+        for hook in &self.hooks {
+            if let ModerationVerdict::Reject(reason) = hook.review(&annotation) {
+                return Err(AnnotationError::Rejected(reason));
+            }
+        }
+
+        let placed = self.placed.entry(author_id).or_default();
+        while placed.front().is_some_and(|at| *at <= now - Duration::hours(1)) {
+            placed.pop_front();
+        }
+        if placed.len() >= self.policy.hourly_limit {
+            let retry = placed.front().map(|at| (*at + Duration::hours(1) - now).num_seconds()).unwrap_or(0);
+            return Err(AnnotationError::RateLimited(retry.max(1)));
+        }
+
+        let doc = self.docs.entry(annotation.grid.clone()).or_default();
+        if doc.len() >= self.policy.max_per_grid {
+            return Err(AnnotationError::GridFull);
+        }
+        let own = doc.iter().filter(|(_, existing)| existing.author_id == author_id).count();
+        if own >= self.policy.per_player_per_grid {
+            return Err(AnnotationError::GridFull);
+        }
+
+        placed.push_back(now);
+        let delta = doc.insert(self.shard_id, annotation.id, annotation.clone());
+        let change = AnnotationChange {
+            grid: annotation.grid.clone(),
+            delta,
+        };
+        Ok((annotation, change))
+    }
+
+    /// Remove an annotation on behalf of its author or, with `moderator`,
+    /// a steward of its region.
+    pub fn remove(
+        &mut self,
+        annotation_id: Uuid,
+        player_id: Uuid,
+        moderator: bool,
+    ) -> Result<AnnotationChange, AnnotationError> {
+        let annotation = self
+            .get(annotation_id)
+            .ok_or(AnnotationError::UnknownAnnotation(annotation_id))?;
+        if annotation.author_id != player_id && !moderator {
+            return Err(AnnotationError::NotPermitted);
+        }
+        let grid = annotation.grid.clone();
+        Ok(self.take_down(grid, annotation_id))
+    }
+
+    /// Record a report. Returns the removal once enough players have
+    /// reported the annotation.
+    pub fn report(&mut self, annotation_id: Uuid, reporter: Uuid) -> Result<Option<AnnotationChange>, AnnotationError> {
+        let grid = self
+            .get(annotation_id)
+            .ok_or(AnnotationError::UnknownAnnotation(annotation_id))?
+            .grid
+            .clone();
+        let reporters = self.reports.entry(annotation_id).or_default();
+        if !reporters.insert(reporter) {
+            return Err(AnnotationError::AlreadyReported(reporter));
+        }
+        if reporters.len() < self.policy.report_threshold {
+            return Ok(None);
+        }
+        Ok(Some(self.take_down(grid, annotation_id)))
+    }
+
+    fn take_down(&mut self, grid: AnnotationGrid, annotation_id: Uuid) -> AnnotationChange {
+        self.reports.remove(&annotation_id);
+        let delta = self
+            .docs
+            .get_mut(&grid)
+            .and_then(|doc| doc.remove(&annotation_id))
+            .unwrap_or_default();
+        AnnotationChange { grid, delta }
+    }
+
+    /// Merge a change from another shard. Our own changes echoed back by
+    /// the bus are already applied.
+    pub fn merge_remote(&mut self, shard_id: Uuid, grid: AnnotationGrid, delta: &AnnotationDoc) {
+        if shard_id != self.shard_id {
+            self.docs.entry(grid).or_default().merge(delta);
+        }
+    }
+
+    /// Every document, for anti-entropy broadcasts.
+    pub fn documents(&self) -> Vec<(AnnotationGrid, AnnotationDoc)> {
+        self.docs.iter().map(|(grid, doc)| (grid.clone(), doc.clone())).collect()
+    }
+
+    /// Annotations in a region the viewer may and wants to see, optionally
+    /// limited to one grid cell.
+    pub fn visible(
+        &self,
+        region_id: &RegionId,
+        cell: Option<(i32, i32)>,
+        viewer: Uuid,
+        ensemble_id: Option<&str>,
+    ) -> Vec<Annotation> {
+        let settings = self.player_settings(viewer);
+        if !settings.show_annotations || !self.region_settings(region_id).enabled {
+            return Vec::new();
+        }
+        let mut annotations: Vec<Annotation> = self
+            .docs
+            .iter()
+            .filter(|(grid, _)| &grid.region_id == region_id && cell.is_none_or(|(x, y)| grid.x == x && grid.y == y))
+            .flat_map(|(_, doc)| doc.iter().map(|(_, annotation)| annotation))
+            .filter(|annotation| annotation.visible_to(viewer, ensemble_id))
+            .filter(|annotation| !settings.muted_authors.contains(&annotation.author_id))
+            .cloned()
+            .collect();
+        annotations.sort_by_key(|annotation| annotation.created_at);
+        annotations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(text: &str) -> NewAnnotation {
+        NewAnnotation {
+            position: Coordinates { x: 150.0, y: 40.0, z: 0.0 },
+            kind: AnnotationKind::Note,
+            text: text.to_string(),
+            visibility: AnnotationVisibility::Public,
+        }
+    }
+
+    #[test]
+    fn shards_converge_and_moderation_applies() {
+        let region = RegionId(Uuid::new_v4());
+        let mut east = AnnotationRegistry::new(Uuid::new_v4(), AnnotationPolicy::default())
+            .with_hook(BlockedTerms(vec!["spoiler".into()]));
+        let mut west = AnnotationRegistry::new(Uuid::new_v4(), AnnotationPolicy::default());
+        let (author, viewer) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+
+        assert!(matches!(
+            east.place(region.clone(), author, note("Big SPOILER ahead"), now),
+            Err(AnnotationError::Rejected(_))
+        ));
+        let (placed, change) = east.place(region.clone(), author, note("Echoes gather here at dusk"), now).unwrap();
+        assert_eq!((placed.grid.x, placed.grid.y), (1, 0));
+        west.merge_remote(east.shard_id(), change.grid, &change.delta);
+        assert_eq!(west.visible(&region, Some((1, 0)), viewer, None).len(), 1);
+
+        west.set_player_settings(viewer, PlayerAnnotationSettings {
+            show_annotations: true,
+            muted_authors: HashSet::from([author]),
+        });
+        assert!(west.visible(&region, None, viewer, None).is_empty());
+
+        assert!(matches!(west.remove(placed.id, viewer, false), Err(AnnotationError::NotPermitted)));
+        for reporter in 0..3 {
+            let removal = west.report(placed.id, Uuid::from_u128(reporter)).unwrap();
+            if let Some(change) = removal {
+                east.merge_remote(west.shard_id(), change.grid, &change.delta);
+            }
+        }
+        assert!(east.get(placed.id).is_none());
+        assert!(west.get(placed.id).is_none());
+    }
+}
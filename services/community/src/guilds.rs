@@ -0,0 +1,228 @@
+// services/community/src/guilds.rs
+//! Guilds: lasting, named groups with ranked roles. Officers and the leader
+//! bring in new members; only the leader changes roles.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum GuildError {
+    #[error("Unknown guild {0}")]
+    UnknownGuild(Uuid),
+
+    #[error("A guild named {0} already exists")]
+    NameTaken(String),
+
+    #[error("Invalid guild name: {0}")]
+    InvalidName(String),
+
+    #[error("Player {0} is already in a guild")]
+    AlreadyInGuild(Uuid),
+
+    #[error("Player {0} is not in this guild")]
+    NotMember(Uuid),
+
+    #[error("Requires the {0:?} role")]
+    NotPermitted(GuildRole),
+
+    #[error("Player {0} has not been invited to this guild")]
+    NotInvited(Uuid),
+
+    #[error("The leader must hand over leadership before leaving")]
+    LeaderMustHandOver,
+}
+
+/// Roles in rising rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuildRole {
+    Member,
+    Officer,
+    Leader,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Guild {
+    pub id: Uuid,
+    pub name: String,
+    pub founded_at: DateTime<Utc>,
+    pub members: HashMap<Uuid, GuildRole>,
+    pub invites: HashSet<Uuid>,
+}
+
+impl Guild {
+    pub fn role_of(&self, player_id: Uuid) -> Option<GuildRole> {
+        self.members.get(&player_id).copied()
+    }
+
+    fn require(&self, player_id: Uuid, role: GuildRole) -> Result<(), GuildError> {
+        match self.role_of(player_id) {
+            Some(held) if held >= role => Ok(()),
+            Some(_) => Err(GuildError::NotPermitted(role)),
+            None => Err(GuildError::NotMember(player_id)),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GuildRegistry {
+    guilds: HashMap<Uuid, Guild>,
+    by_player: HashMap<Uuid, Uuid>,
+}
+
+impl GuildRegistry {
+    /// A registry holding previously saved guilds.
+    pub fn restore(guilds: Vec<Guild>) -> Self {
+        let by_player = guilds
+            .iter()
+            .flat_map(|guild| guild.members.keys().map(|member| (*member, guild.id)))
+            .collect();
+        Self {
+            guilds: guilds.into_iter().map(|guild| (guild.id, guild)).collect(),
+            by_player,
+        }
+    }
+
+    pub fn list(&self) -> Vec<Guild> {
+        let mut guilds: Vec<_> = self.guilds.values().cloned().collect();
+        guilds.sort_by_key(|guild| guild.founded_at);
+        guilds
+    }
+
+    pub fn get(&self, guild_id: Uuid) -> Result<&Guild, GuildError> {
+        self.guilds.get(&guild_id).ok_or(GuildError::UnknownGuild(guild_id))
+    }
+
+    pub fn guild_of(&self, player_id: Uuid) -> Option<&Guild> {
+        self.by_player.get(&player_id).and_then(|guild_id| self.guilds.get(guild_id))
+    }
+
+    fn check_free(&self, player_id: Uuid) -> Result<(), GuildError> {
+        if self.by_player.contains_key(&player_id) {
+            return Err(GuildError::AlreadyInGuild(player_id));
+        }
+        Ok(())
+    }
+
+    fn guild_mut(&mut self, guild_id: Uuid) -> Result<&mut Guild, GuildError> {
+        self.guilds.get_mut(&guild_id).ok_or(GuildError::UnknownGuild(guild_id))
+    }
+
+    /// Found a guild led by `founder_id`. Names are unique, ignoring case.
+    pub fn create(&mut self, founder_id: Uuid, name: &str, now: DateTime<Utc>) -> Result<Guild, GuildError> {
+        let name = name.trim();
+        if name.len() < 3 || name.len() > 32 {
+            return Err(GuildError::InvalidName("must be 3-32 characters".to_string()));
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '\'' || c == '-') {
+            return Err(GuildError::InvalidName("contains unsupported characters".to_string()));
+        }
+        if self.guilds.values().any(|guild| guild.name.eq_ignore_ascii_case(name)) {
+            return Err(GuildError::NameTaken(name.to_string()));
+        }
+        self.check_free(founder_id)?;
+
+        let guild = Guild {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            founded_at: now,
+            members: HashMap::from([(founder_id, GuildRole::Leader)]),
+            invites: HashSet::new(),
+        };
+        self.by_player.insert(founder_id, guild.id);
+        self.guilds.insert(guild.id, guild.clone());
+        Ok(guild)
+    }
+
+    pub fn invite(&mut self, guild_id: Uuid, by: Uuid, invitee_id: Uuid) -> Result<Guild, GuildError> {
+        self.check_free(invitee_id)?;
+        let guild = self.guild_mut(guild_id)?;
+        guild.require(by, GuildRole::Officer)?;
+        guild.invites.insert(invitee_id);
+        Ok(guild.clone())
+    }
+
+    /// Accept an invitation, joining as a member.
+    pub fn join(&mut self, guild_id: Uuid, player_id: Uuid) -> Result<Guild, GuildError> {
+        self.check_free(player_id)?;
+        let guild = self.guild_mut(guild_id)?;
+        if !guild.invites.remove(&player_id) {
+            return Err(GuildError::NotInvited(player_id));
+        }
+        guild.members.insert(player_id, GuildRole::Member);
+        let guild = guild.clone();
+        self.by_player.insert(player_id, guild_id);
+        Ok(guild)
+    }
+
+    /// Leave a guild; None if it was disbanded because no one is left.
+    pub fn leave(&mut self, guild_id: Uuid, player_id: Uuid) -> Result<Option<Guild>, GuildError> {
+        let guild = self.guild_mut(guild_id)?;
+        let role = guild.role_of(player_id).ok_or(GuildError::NotMember(player_id))?;
+        if role == GuildRole::Leader && guild.members.len() > 1 {
+            return Err(GuildError::LeaderMustHandOver);
+        }
+        guild.members.remove(&player_id);
+        let remaining = (!guild.members.is_empty()).then(|| guild.clone());
+        self.by_player.remove(&player_id);
+        if remaining.is_none() {
+            self.guilds.remove(&guild_id);
+        }
+        Ok(remaining)
+    }
+
+    /// Change a member's role. Making someone leader steps the current
+    /// leader down to officer.
+    pub fn set_role(&mut self, guild_id: Uuid, by: Uuid, player_id: Uuid, role: GuildRole) -> Result<Guild, GuildError> {
+        let guild = self.guild_mut(guild_id)?;
+        guild.require(by, GuildRole::Leader)?;
+        if guild.role_of(player_id).is_none() {
+            return Err(GuildError::NotMember(player_id));
+        }
+        if by == player_id {
+            // The leader can only step down by naming a successor
+            return Err(GuildError::LeaderMustHandOver);
+        }
+        if role == GuildRole::Leader {
+            guild.members.insert(by, GuildRole::Officer);
+        }
+        guild.members.insert(player_id, role);
+        Ok(guild.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_govern_invitations_and_leadership() {
+        let mut registry = GuildRegistry::default();
+        let (founder, officer, member, outsider) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+
+        let guild = registry.create(founder, "Keepers of the Song", now).unwrap();
+        assert!(matches!(registry.create(outsider, "keepers of the song", now), Err(GuildError::NameTaken(_))));
+
+        registry.invite(guild.id, founder, officer).unwrap();
+        registry.join(guild.id, officer).unwrap();
+        assert!(matches!(
+            registry.invite(guild.id, officer, member),
+            Err(GuildError::NotPermitted(GuildRole::Officer))
+        ));
+        registry.set_role(guild.id, founder, officer, GuildRole::Officer).unwrap();
+        registry.invite(guild.id, officer, member).unwrap();
+        registry.join(guild.id, member).unwrap();
+        assert!(matches!(registry.join(guild.id, outsider), Err(GuildError::NotInvited(_))));
+
+        assert!(matches!(registry.leave(guild.id, founder), Err(GuildError::LeaderMustHandOver)));
+        let guild = registry.set_role(guild.id, founder, officer, GuildRole::Leader).unwrap();
+        assert_eq!(guild.role_of(founder), Some(GuildRole::Officer));
+        assert_eq!(guild.role_of(officer), Some(GuildRole::Leader));
+        assert!(registry.leave(guild.id, founder).unwrap().is_some());
+        assert!(registry.guild_of(founder).is_none());
+    }
+}
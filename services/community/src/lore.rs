@@ -0,0 +1,98 @@
+// services/community/src/lore.rs
+//! Registry of community-authored lore, such as names given to regions by
+//! their stewards, and the pool of names the world's people are drawn from.
+
+use chrono::{DateTime, Utc};
+use finalverse_core::RegionId;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoreEntry {
+    pub id: Uuid,
+    pub region_id: RegionId,
+    pub title: String,
+    pub text: String,
+    pub author_ensemble: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Names used for procedurally generated inhabitants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamePool {
+    pub given: Vec<String>,
+    pub family: Vec<String>,
+}
+
+impl Default for NamePool {
+    fn default() -> Self {
+        let given = [
+            "Aria", "Bram", "Cerys", "Doran", "Elowen", "Fenn", "Galen", "Hesper", "Ilya", "Joren", "Kestrel",
+            "Liora", "Maren", "Niall", "Oriel", "Perrin", "Quilla", "Rowan", "Seren", "Tamsin", "Ulric", "Vesna",
+            "Wren", "Yara",
+        ];
+        let family = [
+            "Ashgrove", "Brightwater", "Cantor", "Dawnmere", "Emberlyn", "Fairsong", "Greymantle", "Hollowreed",
+            "Ironvale", "Larkspur", "Mossbank", "Novastride", "Oakenshaw", "Stonebrook", "Thistledown", "Windsong",
+        ];
+        Self {
+            given: given.iter().map(|name| name.to_string()).collect(),
+            family: family.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LoreRegistry {
+    entries: Vec<LoreEntry>,
+    names: NamePool,
+}
+
+impl LoreRegistry {
+    pub fn record(
+        &mut self,
+        region_id: RegionId,
+        title: String,
+        text: String,
+        author_ensemble: Option<String>,
+    ) -> LoreEntry {
+        let entry = LoreEntry {
+            id: Uuid::new_v4(),
+            region_id,
+            title,
+            text,
+            author_ensemble,
+            recorded_at: Utc::now(),
+        };
+        self.entries.push(entry.clone());
+        entry
+    }
+
+    pub fn names(&self) -> &NamePool {
+        &self.names
+    }
+
+    /// Add names to the pool, skipping blanks and ones already known.
+    pub fn add_names(&mut self, given: Vec<String>, family: Vec<String>) -> usize {
+        fn merge(pool: &mut Vec<String>, names: Vec<String>) -> usize {
+            let before = pool.len();
+            for name in names {
+                let name = name.trim().to_string();
+                if !name.is_empty() && !pool.contains(&name) {
+                    pool.push(name);
+                }
+            }
+            pool.len() - before
+        }
+        merge(&mut self.names.given, given) + merge(&mut self.names.family, family)
+    }
+
+    /// Lore for a region, oldest first.
+    pub fn for_region(&self, region_id: &RegionId) -> Vec<LoreEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| &entry.region_id == region_id)
+            .cloned()
+            .collect()
+    }
+}
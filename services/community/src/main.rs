@@ -1,20 +1,1126 @@
-use axum::Router;
-use finalverse_health::HealthMonitor;
-use service_registry::LocalServiceRegistry;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post, put},
+    Router,
+};
+use chrono::Utc;
+use finalverse_auth::{axum::require_player, AuthenticatedPlayer, PlayerAuth};
+use finalverse_core::{RegionId, TerraformKind};
+use finalverse_events::{
+    CommunityEvent, Event, EventMetadata, EventType, GameEventBus, GroupKind, LocalEventBus, NatsEventBus, PlayerId,
+    TenantEventBus,
+};
+use finalverse_health::{DebugEndpoints, HealthMonitor, OperatorGuard, OPERATOR_TOKEN_HEADER};
+use serde::Deserialize;
+use service_registry::{LocalServiceRegistry, ServiceClient};
 use std::{net::SocketAddr, sync::Arc};
-use tracing::info;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
 use finalverse_logging as logging;
+use uuid::Uuid;
+
+mod annotations;
+mod guilds;
+mod lore;
+mod membership;
+mod parties;
+mod stewardship;
+mod terraforming;
+
+use annotations::{
+    AnnotationChange, AnnotationError, AnnotationPolicy, AnnotationRegistry, BlockedTerms, NewAnnotation,
+    PlayerAnnotationSettings, RegionAnnotationSettings,
+};
+use guilds::{GuildError, GuildRegistry, GuildRole};
+use lore::{LoreRegistry, NamePool};
+use membership::{Membership, MembershipFile};
+use parties::{Departure, PartyError, PartyPolicy, PartyRegistry};
+use stewardship::{StewardshipError, StewardshipPolicy, StewardshipRegistry};
+use terraforming::{ProposalStatus, TerraformError, TerraformPolicy, TerraformProposal, TerraformRegistry, Voter};
+
+#[derive(Clone)]
+struct AppState {
+    stewardship: Arc<RwLock<StewardshipRegistry>>,
+    lore: Arc<RwLock<LoreRegistry>>,
+    terraform: Arc<RwLock<TerraformRegistry>>,
+    annotations: Arc<RwLock<AnnotationRegistry>>,
+    parties: Arc<RwLock<PartyRegistry>>,
+    guilds: Arc<RwLock<GuildRegistry>>,
+    membership: Arc<Mutex<MembershipFile>>,
+    event_bus: Arc<dyn GameEventBus>,
+    /// Sends the operator token, for world-engine's admin routes.
+    admin_services: ServiceClient,
+}
+
+impl AppState {
+    async fn publish(&self, community_event: CommunityEvent) {
+        let tag = match community_event {
+            CommunityEvent::TerraformProposed { .. } | CommunityEvent::TerraformDecided { .. } => "terraforming",
+            CommunityEvent::AnnotationsChanged { .. } | CommunityEvent::AnnotationSettingsChanged { .. } => "annotations",
+            CommunityEvent::PartyFormed { .. }
+            | CommunityEvent::PartyDisbanded { .. }
+            | CommunityEvent::GuildFounded { .. }
+            | CommunityEvent::MemberInvited { .. }
+            | CommunityEvent::MemberJoined { .. }
+            | CommunityEvent::MemberLeft { .. } => "membership",
+            _ => "stewardship",
+        };
+        let event = Event::new(EventType::Community(community_event)).with_metadata(EventMetadata {
+            source: Some("community".to_string()),
+            tags: vec![tag.to_string()],
+            ..Default::default()
+        });
+        if let Err(e) = self.event_bus.publish(event).await {
+            warn!("Failed to publish community event: {}", e);
+        }
+    }
+
+    /// Save party and guild membership. The file lock is held across the
+    /// snapshot so saves land in the order the changes were made.
+    async fn save_membership(&self) {
+        let file = self.membership.lock().await;
+        let membership = Membership {
+            parties: self.parties.read().await.list(),
+            guilds: self.guilds.read().await.list(),
+        };
+        if let Err(e) = file.save(&membership).await {
+            warn!("Failed to save party and guild membership: {}", e);
+        }
+    }
+
+    /// Save membership and announce the change that caused it.
+    async fn membership_changed(&self, events: Vec<CommunityEvent>) {
+        self.save_membership().await;
+        for event in events {
+            self.publish(event).await;
+        }
+    }
+
+    /// Ship a local annotation change to the other shards.
+    async fn publish_annotations(&self, change: AnnotationChange) {
+        let shard_id = self.annotations.read().await.shard_id();
+        self.publish(CommunityEvent::AnnotationsChanged {
+            shard_id,
+            grid: change.grid,
+            delta: change.delta,
+        })
+        .await;
+    }
+
+    /// Whether the player acts for the ensemble that stewards the region.
+    async fn is_steward(&self, player: &AuthenticatedPlayer, region_id: &RegionId, ensemble_id: Option<&str>) -> bool {
+        let Some(ensemble_id) = ensemble_id else {
+            return false;
+        };
+        let stewards = self
+            .stewardship
+            .read()
+            .await
+            .get(region_id)
+            .is_some_and(|claim| claim.ensemble_id == ensemble_id);
+        stewards && self.acts_for(player, ensemble_id).await
+    }
+
+    /// Whether the player may act for the ensemble. Ensembles are guilds,
+    /// so this means belonging to the guild.
+    async fn acts_for(&self, player: &AuthenticatedPlayer, ensemble_id: &str) -> bool {
+        let Ok(guild_id) = ensemble_id.parse::<Uuid>() else {
+            return false;
+        };
+        self.guilds
+            .read()
+            .await
+            .get(guild_id)
+            .is_ok_and(|guild| guild.role_of(player.player_id.0).is_some())
+    }
+
+    /// Reject a stewardship action by a player outside the ensemble.
+    async fn require_member(&self, player: &AuthenticatedPlayer, ensemble_id: &str) -> Result<(), StewardshipError> {
+        if self.acts_for(player, ensemble_id).await {
+            Ok(())
+        } else {
+            Err(StewardshipError::NotMember(ensemble_id.to_string()))
+        }
+    }
+
+    /// Push a region's stewardship yield bonus to the world engine.
+    async fn sync_yield_bonus(&self, region_id: &RegionId, bonus: f64) {
+        let path = format!("/region/{}/yield", region_id.0);
+        if let Err(e) = self
+            .admin_services
+            .put_json::<_, serde_json::Value>("world-engine", &path, &serde_json::json!({ "bonus": bonus }))
+            .await
+        {
+            warn!("Failed to update yield bonus for region {}: {}", region_id.0, e);
+        }
+    }
+
+    /// Hand an approved proposal to the world engine to carry out.
+    async fn start_terraforming(&self, proposal: &TerraformProposal, stage_hours: i64) {
+        let path = format!("/region/{}/terraform", proposal.region_id.0);
+        let body = serde_json::json!({
+            "proposal_id": proposal.id,
+            "kind": proposal.kind,
+            "stage_hours": stage_hours,
+        });
+        if let Err(e) = self.admin_services.post_json::<_, serde_json::Value>("world-engine", &path, &body).await {
+            warn!("Failed to start terraforming of region {}: {}", proposal.region_id.0, e);
+        }
+    }
+
+    /// Close terraforming votes whose window has ended.
+    async fn close_terraform_votes(&self) {
+        let (closed, stage_hours) = {
+            let mut registry = self.terraform.write().await;
+            (registry.close_due(Utc::now()), registry.policy().stage_hours)
+        };
+        for proposal in closed {
+            info!("🗳️ Terraforming proposal {} for region {}: {}", proposal.id, proposal.region_id.0, proposal.status.as_str());
+            if proposal.status == ProposalStatus::Approved {
+                self.start_terraforming(&proposal, stage_hours).await;
+            }
+            self.publish(CommunityEvent::TerraformDecided {
+                proposal_id: proposal.id,
+                region_id: proposal.region_id,
+                kind: proposal.kind,
+                outcome: proposal.status.as_str().to_string(),
+            })
+            .await;
+        }
+    }
+
+    /// Resolve contests and lapse neglected claims.
+    async fn tick(&self) {
+        self.close_terraform_votes().await;
+        let now = Utc::now();
+        let (results, lapsed, bonus) = {
+            let mut registry = self.stewardship.write().await;
+            let results = registry.resolve_contests(now);
+            let lapsed = registry.lapse_overdue(now);
+            (results, lapsed, registry.policy().yield_bonus)
+        };
+
+        for result in results {
+            if result.challenger_won {
+                info!("🏳️ Ensemble {} won stewardship of region {}", result.challenger_id, result.region_id.0);
+                self.publish(CommunityEvent::StewardshipTransferred {
+                    region_id: result.region_id.clone(),
+                    from: result.steward_id,
+                    to: result.challenger_id,
+                })
+                .await;
+                self.sync_yield_bonus(&result.region_id, bonus).await;
+            } else {
+                info!("🛡️ Ensemble {} held stewardship of region {}", result.steward_id, result.region_id.0);
+            }
+        }
+
+        for claim in lapsed {
+            info!("🍂 Stewardship of region {} by {} lapsed", claim.region_id.0, claim.ensemble_id);
+            self.sync_yield_bonus(&claim.region_id, 0.0).await;
+            self.publish(CommunityEvent::StewardshipLapsed {
+                region_id: claim.region_id,
+                ensemble_id: claim.ensemble_id,
+            })
+            .await;
+        }
+    }
+}
+
+impl IntoResponse for StewardshipError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            StewardshipError::Unclaimed | StewardshipError::NoContest => StatusCode::NOT_FOUND,
+            StewardshipError::NotSteward(_) | StewardshipError::NotContestant(_) | StewardshipError::NotMember(_) => {
+                StatusCode::FORBIDDEN
+            }
+            StewardshipError::InvalidName(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::CONFLICT,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl IntoResponse for TerraformError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            TerraformError::UnknownProposal(_) => StatusCode::NOT_FOUND,
+            TerraformError::NotEligible(_) | TerraformError::NotMember(_) => StatusCode::FORBIDDEN,
+            TerraformError::InvalidPledge(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::CONFLICT,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl IntoResponse for AnnotationError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AnnotationError::UnknownAnnotation(_) => StatusCode::NOT_FOUND,
+            AnnotationError::NotPermitted | AnnotationError::Disabled | AnnotationError::PublicNotAllowed => {
+                StatusCode::FORBIDDEN
+            }
+            AnnotationError::Invalid(_) => StatusCode::BAD_REQUEST,
+            AnnotationError::Rejected(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AnnotationError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::CONFLICT,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl IntoResponse for PartyError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PartyError::UnknownParty(_) => StatusCode::NOT_FOUND,
+            PartyError::NotMember(_) | PartyError::NotLeader | PartyError::NotInvited(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::CONFLICT,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl IntoResponse for GuildError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            GuildError::UnknownGuild(_) => StatusCode::NOT_FOUND,
+            GuildError::NotMember(_) | GuildError::NotPermitted(_) | GuildError::NotInvited(_) => StatusCode::FORBIDDEN,
+            GuildError::InvalidName(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::CONFLICT,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EnsembleRequest {
+    ensemble_id: String,
+    ensemble_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpkeepRequest {
+    ensemble_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContributionRequest {
+    ensemble_id: String,
+    amount: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProposalRequest {
+    region_id: Uuid,
+    kind: TerraformKind,
+    ensemble_id: String,
+    ensemble_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VoteRequest {
+    ensemble_id: String,
+    support: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameRequest {
+    ensemble_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnnotationQuery {
+    ensemble_id: Option<String>,
+    x: Option<i32>,
+    y: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnnotationActor {
+    /// Set when acting as a steward of the annotation's region.
+    ensemble_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InviteRequest {
+    invitee_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+struct GuildRequest {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RoleRequest {
+    role: GuildRole,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegionAnnotationRequest {
+    ensemble_id: String,
+    #[serde(flatten)]
+    settings: RegionAnnotationSettings,
+}
+
+async fn list_stewardships(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.stewardship.read().await.list())
+}
+
+async fn get_stewardship(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+) -> Result<impl IntoResponse, StewardshipError> {
+    let registry = state.stewardship.read().await;
+    let claim = registry.get(&RegionId(region_id)).ok_or(StewardshipError::Unclaimed)?;
+    Ok(Json(serde_json::json!({
+        "stewardship": claim,
+        "upkeep_due": claim.upkeep_due(registry.policy()),
+        "yield_bonus": registry.policy().yield_bonus,
+    })))
+}
+
+async fn claim_region(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<EnsembleRequest>,
+) -> Result<impl IntoResponse, StewardshipError> {
+    state.require_member(&player, &request.ensemble_id).await?;
+    let region_id = RegionId(region_id);
+    let (claim, bonus) = {
+        let mut registry = state.stewardship.write().await;
+        let claim = registry.claim(region_id.clone(), request.ensemble_id, request.ensemble_name, Utc::now())?;
+        (claim, registry.policy().yield_bonus)
+    };
+
+    state.sync_yield_bonus(&region_id, bonus).await;
+    state
+        .publish(CommunityEvent::StewardshipClaimed {
+            region_id,
+            ensemble_id: claim.ensemble_id.clone(),
+        })
+        .await;
+
+    Ok((StatusCode::CREATED, Json(claim)))
+}
+
+async fn perform_upkeep(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<UpkeepRequest>,
+) -> Result<impl IntoResponse, StewardshipError> {
+    state.require_member(&player, &request.ensemble_id).await?;
+    let region_id = RegionId(region_id);
+    let (claim, harmony) = {
+        let mut registry = state.stewardship.write().await;
+        let harmony = registry.policy().ritual_harmony;
+        let claim = registry.perform_upkeep(&region_id, &request.ensemble_id, harmony as f64, Utc::now())?;
+        (claim, harmony)
+    };
+
+    // Ritual harmony is in points; the world engine keeps region harmony on a 0-1 scale
+    let path = format!("/region/{}/harmony", region_id.0);
+    let delta = harmony / 100.0;
+    if let Err(e) = state
+        .admin_services
+        .post_json::<_, serde_json::Value>("world-engine", &path, &serde_json::json!({ "delta": delta }))
+        .await
+    {
+        warn!("Failed to apply restoration ritual to region {}: {}", region_id.0, e);
+    }
+
+    state
+        .publish(CommunityEvent::UpkeepPerformed {
+            region_id,
+            ensemble_id: request.ensemble_id,
+            harmony_restored: harmony as f64,
+        })
+        .await;
+
+    Ok(Json(claim))
+}
+
+async fn contest_region(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<EnsembleRequest>,
+) -> Result<impl IntoResponse, StewardshipError> {
+    state.require_member(&player, &request.ensemble_id).await?;
+    let region_id = RegionId(region_id);
+    let (contest, steward_id) = {
+        let mut registry = state.stewardship.write().await;
+        let contest = registry.contest(&region_id, request.ensemble_id, request.ensemble_name, Utc::now())?;
+        let steward_id = registry
+            .get(&region_id)
+            .map(|claim| claim.ensemble_id.clone())
+            .unwrap_or_default();
+        (contest, steward_id)
+    };
+
+    state
+        .publish(CommunityEvent::StewardshipContested {
+            region_id,
+            steward_id,
+            challenger_id: contest.challenger_id.clone(),
+            resolves_at: contest.resolves_at,
+        })
+        .await;
+
+    Ok((StatusCode::CREATED, Json(contest)))
+}
+
+async fn contribute_to_contest(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<ContributionRequest>,
+) -> Result<impl IntoResponse, StewardshipError> {
+    state.require_member(&player, &request.ensemble_id).await?;
+    let contest = state
+        .stewardship
+        .write()
+        .await
+        .contribute(&RegionId(region_id), &request.ensemble_id, request.amount.max(0.0))?;
+    Ok(Json(contest))
+}
+
+async fn name_region(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<NameRequest>,
+) -> Result<impl IntoResponse, StewardshipError> {
+    state.require_member(&player, &request.ensemble_id).await?;
+    let region_id = RegionId(region_id);
+    let (previous, ensemble_name) = {
+        let mut registry = state.stewardship.write().await;
+        let previous = registry.name_region(&region_id, &request.ensemble_id, &request.name)?;
+        let ensemble_name = registry
+            .get(&region_id)
+            .map(|claim| claim.ensemble_name.clone())
+            .unwrap_or_default();
+        (previous, ensemble_name)
+    };
+
+    let name = request.name.trim().to_string();
+    let text = match previous {
+        Some(previous) => format!("The stewards of {} renamed {} to {}.", ensemble_name, previous, name),
+        None => format!("The stewards of {} named this land {}.", ensemble_name, name),
+    };
+    let entry = state
+        .lore
+        .write()
+        .await
+        .record(region_id.clone(), name.clone(), text, Some(request.ensemble_id.clone()));
+
+    state
+        .publish(CommunityEvent::RegionNamed {
+            region_id,
+            ensemble_id: request.ensemble_id,
+            name,
+        })
+        .await;
+
+    Ok(Json(entry))
+}
+
+async fn region_lore(State(state): State<AppState>, Path(region_id): Path<Uuid>) -> impl IntoResponse {
+    Json(state.lore.read().await.for_region(&RegionId(region_id)))
+}
+
+async fn list_proposals(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.terraform.read().await.list())
+}
+
+async fn get_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+) -> Result<impl IntoResponse, TerraformError> {
+    let registry = state.terraform.read().await;
+    let proposal = registry.get(proposal_id)?;
+    let (support, oppose) = proposal.tally();
+    Ok(Json(serde_json::json!({
+        "proposal": proposal,
+        "pledged": proposal.pledged(),
+        "support": support,
+        "oppose": oppose,
+    })))
+}
+
+async fn propose_terraform(
+    State(state): State<AppState>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<ProposalRequest>,
+) -> Result<impl IntoResponse, TerraformError> {
+    if !state.acts_for(&player, &request.ensemble_id).await {
+        return Err(TerraformError::NotMember(request.ensemble_id));
+    }
+    let region_id = RegionId(request.region_id);
+    let proposal = state.terraform.write().await.propose(
+        region_id.clone(),
+        request.kind,
+        request.ensemble_id,
+        request.ensemble_name,
+        Utc::now(),
+    )?;
+
+    state
+        .publish(CommunityEvent::TerraformProposed {
+            proposal_id: proposal.id,
+            region_id,
+            ensemble_id: proposal.proposer_id.clone(),
+            kind: proposal.kind,
+            closes_at: proposal.closes_at,
+        })
+        .await;
+
+    Ok((StatusCode::CREATED, Json(proposal)))
+}
+
+async fn pledge_to_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<ContributionRequest>,
+) -> Result<impl IntoResponse, TerraformError> {
+    if !state.acts_for(&player, &request.ensemble_id).await {
+        return Err(TerraformError::NotMember(request.ensemble_id));
+    }
+    let proposal = state
+        .terraform
+        .write()
+        .await
+        .pledge(proposal_id, &request.ensemble_id, request.amount, Utc::now())?;
+    Ok(Json(proposal))
+}
+
+async fn vote_on_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<VoteRequest>,
+) -> Result<impl IntoResponse, TerraformError> {
+    if !state.acts_for(&player, &request.ensemble_id).await {
+        return Err(TerraformError::NotMember(request.ensemble_id));
+    }
+    let region_id = state.terraform.read().await.get(proposal_id)?.region_id.clone();
+    // Eligibility comes from stewardship standing
+    let voter = {
+        let stewardship = state.stewardship.read().await;
+        Voter {
+            stewards_region: stewardship
+                .get(&region_id)
+                .is_some_and(|claim| claim.ensemble_id == request.ensemble_id),
+            is_steward: stewardship.list().iter().any(|claim| claim.ensemble_id == request.ensemble_id),
+            ensemble_id: request.ensemble_id,
+        }
+    };
+    let proposal = state
+        .terraform
+        .write()
+        .await
+        .vote(proposal_id, voter, request.support, Utc::now())?;
+    Ok(Json(proposal))
+}
+
+async fn lore_names(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.lore.read().await.names().clone())
+}
+
+async fn add_lore_names(State(state): State<AppState>, Json(request): Json<NamePool>) -> impl IntoResponse {
+    let added = state.lore.write().await.add_names(request.given, request.family);
+    Json(serde_json::json!({ "added": added }))
+}
+
+async fn list_annotations(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    viewer: AuthenticatedPlayer,
+    Query(query): Query<AnnotationQuery>,
+) -> impl IntoResponse {
+    let cell = query.x.zip(query.y);
+    // Ensemble notes are only shown to the ensemble's members
+    let ensemble_id = match query.ensemble_id {
+        Some(ensemble_id) if state.acts_for(&viewer, &ensemble_id).await => Some(ensemble_id),
+        _ => None,
+    };
+    Json(
+        state
+            .annotations
+            .read()
+            .await
+            .visible(&RegionId(region_id), cell, viewer.player_id.0, ensemble_id.as_deref()),
+    )
+}
+
+async fn place_annotation(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    author: AuthenticatedPlayer,
+    Json(request): Json<NewAnnotation>,
+) -> Result<impl IntoResponse, AnnotationError> {
+    let (annotation, change) = state
+        .annotations
+        .write()
+        .await
+        .place(RegionId(region_id), author.player_id.0, request, Utc::now())?;
+    state.publish_annotations(change).await;
+    Ok((StatusCode::CREATED, Json(annotation)))
+}
+
+async fn remove_annotation(
+    State(state): State<AppState>,
+    Path(annotation_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+    Json(request): Json<AnnotationActor>,
+) -> Result<impl IntoResponse, AnnotationError> {
+    let region_id = state
+        .annotations
+        .read()
+        .await
+        .get(annotation_id)
+        .ok_or(AnnotationError::UnknownAnnotation(annotation_id))?
+        .grid
+        .region_id
+        .clone();
+    let moderator = state.is_steward(&caller, &region_id, request.ensemble_id.as_deref()).await;
+    let change = state
+        .annotations
+        .write()
+        .await
+        .remove(annotation_id, caller.player_id.0, moderator)?;
+    if moderator {
+        info!("🧹 Annotation {} removed by stewards of region {}", annotation_id, region_id.0);
+    }
+    state.publish_annotations(change).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn report_annotation(
+    State(state): State<AppState>,
+    Path(annotation_id): Path<Uuid>,
+    reporter: AuthenticatedPlayer,
+) -> Result<impl IntoResponse, AnnotationError> {
+    let removal = state.annotations.write().await.report(annotation_id, reporter.player_id.0)?;
+    let removed = removal.is_some();
+    if let Some(change) = removal {
+        info!("🧹 Annotation {} taken down after reports", annotation_id);
+        state.publish_annotations(change).await;
+    }
+    Ok(Json(serde_json::json!({ "removed": removed })))
+}
+
+async fn get_region_annotation_settings(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+) -> impl IntoResponse {
+    Json(state.annotations.read().await.region_settings(&RegionId(region_id)))
+}
+
+async fn set_region_annotation_settings(
+    State(state): State<AppState>,
+    Path(region_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+    Json(request): Json<RegionAnnotationRequest>,
+) -> Result<impl IntoResponse, AnnotationError> {
+    let region_id = RegionId(region_id);
+    if !state.is_steward(&caller, &region_id, Some(&request.ensemble_id)).await {
+        return Err(AnnotationError::NotPermitted);
+    }
+    let shard_id = {
+        let mut annotations = state.annotations.write().await;
+        annotations.set_region_settings(region_id.clone(), request.settings.clone());
+        annotations.shard_id()
+    };
+    state
+        .publish(CommunityEvent::AnnotationSettingsChanged {
+            shard_id,
+            region_id,
+            enabled: request.settings.enabled,
+            allow_public: request.settings.allow_public,
+        })
+        .await;
+    Ok(Json(request.settings))
+}
+
+async fn get_player_annotation_settings(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+) -> impl IntoResponse {
+    Json(state.annotations.read().await.player_settings(player_id))
+}
+
+async fn set_player_annotation_settings(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+    Json(settings): Json<PlayerAnnotationSettings>,
+) -> Result<impl IntoResponse, AnnotationError> {
+    if caller.player_id.0 != player_id {
+        return Err(AnnotationError::NotPermitted);
+    }
+    state.annotations.write().await.set_player_settings(player_id, settings.clone());
+    Ok(Json(settings))
+}
+
+async fn list_parties(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.parties.read().await.list())
+}
+
+async fn get_party(State(state): State<AppState>, Path(party_id): Path<Uuid>) -> Result<impl IntoResponse, PartyError> {
+    Ok(Json(state.parties.read().await.get(party_id)?.clone()))
+}
+
+/// The party a player is in, for services that act on parties such as
+/// symphony matchmaking and party chat.
+async fn player_party(State(state): State<AppState>, Path(player_id): Path<Uuid>) -> impl IntoResponse {
+    match state.parties.read().await.party_of(player_id) {
+        Some(party) => Json(party.clone()).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Player is not in a party" }))).into_response(),
+    }
+}
+
+async fn form_party(
+    State(state): State<AppState>,
+    caller: AuthenticatedPlayer,
+) -> Result<impl IntoResponse, PartyError> {
+    let party = state.parties.write().await.create(caller.player_id.0, Utc::now())?;
+    info!("🎒 Player {} formed party {}", caller.player_id.0, party.id);
+    state
+        .membership_changed(vec![CommunityEvent::PartyFormed {
+            party_id: party.id,
+            leader_id: PlayerId(party.leader_id),
+        }])
+        .await;
+    Ok((StatusCode::CREATED, Json(party)))
+}
+
+async fn invite_to_party(
+    State(state): State<AppState>,
+    Path(party_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+    Json(request): Json<InviteRequest>,
+) -> Result<impl IntoResponse, PartyError> {
+    let party = state
+        .parties
+        .write()
+        .await
+        .invite(party_id, caller.player_id.0, request.invitee_id, Utc::now())?;
+    state
+        .membership_changed(vec![CommunityEvent::MemberInvited {
+            group: GroupKind::Party,
+            group_id: party_id,
+            player_id: PlayerId(request.invitee_id),
+            invited_by: caller.player_id.clone(),
+        }])
+        .await;
+    Ok(Json(party))
+}
+
+async fn join_party(
+    State(state): State<AppState>,
+    Path(party_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+) -> Result<impl IntoResponse, PartyError> {
+    let party = state.parties.write().await.join(party_id, caller.player_id.0, Utc::now())?;
+    state
+        .membership_changed(vec![CommunityEvent::MemberJoined {
+            group: GroupKind::Party,
+            group_id: party_id,
+            player_id: caller.player_id.clone(),
+        }])
+        .await;
+    Ok(Json(party))
+}
+
+async fn leave_party(
+    State(state): State<AppState>,
+    Path(party_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+) -> Result<impl IntoResponse, PartyError> {
+    let departure = state.parties.write().await.leave(party_id, caller.player_id.0)?;
+    let mut events = vec![CommunityEvent::MemberLeft {
+        group: GroupKind::Party,
+        group_id: party_id,
+        player_id: caller.player_id.clone(),
+    }];
+    if let Departure::Disbanded = departure {
+        info!("🎒 Party {} disbanded", party_id);
+        events.push(CommunityEvent::PartyDisbanded { party_id });
+    }
+    state.membership_changed(events).await;
+    Ok(match departure {
+        Departure::Left { party, new_leader } => Json(serde_json::json!({ "party": party, "new_leader": new_leader })),
+        Departure::Disbanded => Json(serde_json::json!({ "party": null, "disbanded": true })),
+    })
+}
+
+async fn list_guilds(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.guilds.read().await.list())
+}
+
+async fn get_guild(State(state): State<AppState>, Path(guild_id): Path<Uuid>) -> Result<impl IntoResponse, GuildError> {
+    Ok(Json(state.guilds.read().await.get(guild_id)?.clone()))
+}
+
+async fn player_guild(State(state): State<AppState>, Path(player_id): Path<Uuid>) -> impl IntoResponse {
+    match state.guilds.read().await.guild_of(player_id) {
+        Some(guild) => Json(guild.clone()).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Player is not in a guild" }))).into_response(),
+    }
+}
+
+async fn found_guild(
+    State(state): State<AppState>,
+    caller: AuthenticatedPlayer,
+    Json(request): Json<GuildRequest>,
+) -> Result<impl IntoResponse, GuildError> {
+    let guild = state.guilds.write().await.create(caller.player_id.0, &request.name, Utc::now())?;
+    info!("🏰 Player {} founded guild {}", caller.player_id.0, guild.name);
+    state
+        .membership_changed(vec![CommunityEvent::GuildFounded {
+            guild_id: guild.id,
+            name: guild.name.clone(),
+            founder_id: caller.player_id.clone(),
+        }])
+        .await;
+    Ok((StatusCode::CREATED, Json(guild)))
+}
+
+async fn invite_to_guild(
+    State(state): State<AppState>,
+    Path(guild_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+    Json(request): Json<InviteRequest>,
+) -> Result<impl IntoResponse, GuildError> {
+    let guild = state
+        .guilds
+        .write()
+        .await
+        .invite(guild_id, caller.player_id.0, request.invitee_id)?;
+    state
+        .membership_changed(vec![CommunityEvent::MemberInvited {
+            group: GroupKind::Guild,
+            group_id: guild_id,
+            player_id: PlayerId(request.invitee_id),
+            invited_by: caller.player_id.clone(),
+        }])
+        .await;
+    Ok(Json(guild))
+}
+
+async fn join_guild(
+    State(state): State<AppState>,
+    Path(guild_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+) -> Result<impl IntoResponse, GuildError> {
+    let guild = state.guilds.write().await.join(guild_id, caller.player_id.0)?;
+    state
+        .membership_changed(vec![CommunityEvent::MemberJoined {
+            group: GroupKind::Guild,
+            group_id: guild_id,
+            player_id: caller.player_id.clone(),
+        }])
+        .await;
+    Ok(Json(guild))
+}
+
+async fn leave_guild(
+    State(state): State<AppState>,
+    Path(guild_id): Path<Uuid>,
+    caller: AuthenticatedPlayer,
+) -> Result<impl IntoResponse, GuildError> {
+    let guild = state.guilds.write().await.leave(guild_id, caller.player_id.0)?;
+    if guild.is_none() {
+        info!("🏰 Guild {} disbanded", guild_id);
+    }
+    state
+        .membership_changed(vec![CommunityEvent::MemberLeft {
+            group: GroupKind::Guild,
+            group_id: guild_id,
+            player_id: caller.player_id.clone(),
+        }])
+        .await;
+    Ok(Json(serde_json::json!({ "guild": guild })))
+}
+
+async fn set_guild_role(
+    State(state): State<AppState>,
+    Path((guild_id, player_id)): Path<(Uuid, Uuid)>,
+    caller: AuthenticatedPlayer,
+    Json(request): Json<RoleRequest>,
+) -> Result<impl IntoResponse, GuildError> {
+    let guild = state
+        .guilds
+        .write()
+        .await
+        .set_role(guild_id, caller.player_id.0, player_id, request.role)?;
+    state.save_membership().await;
+    Ok(Json(guild))
+}
+
+/// Merge annotation changes published by other shards.
+async fn apply_remote_annotations(annotations: &RwLock<AnnotationRegistry>, event: CommunityEvent) {
+    match event {
+        CommunityEvent::AnnotationsChanged { shard_id, grid, delta } => {
+            annotations.write().await.merge_remote(shard_id, grid, &delta);
+        }
+        CommunityEvent::AnnotationSettingsChanged { shard_id, region_id, enabled, allow_public } => {
+            let mut annotations = annotations.write().await;
+            if shard_id != annotations.shard_id() {
+                annotations.set_region_settings(region_id, RegionAnnotationSettings { enabled, allow_public });
+            }
+        }
+        _ => {}
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
+    // Mirror world-engine's clock so events carry world time
+    finalverse_core::world_clock::start_from_env();
     let monitor = Arc::new(HealthMonitor::new("community", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
         .register_service("community".to_string(), "http://localhost:3008".to_string())
         .await;
 
-    let app = Router::new().merge(monitor.clone().axum_routes());
+    let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
+        info!("📡 Connecting to NATS at {}", nats_url);
+        Arc::new(NatsEventBus::new(&nats_url).await?)
+    } else {
+        info!("📦 Using local event bus");
+        Arc::new(LocalEventBus::new())
+    };
+    let event_bus = TenantEventBus::from_env(event_bus);
+
+    let shard_id = std::env::var("FINALVERSE_SHARD_ID")
+        .ok()
+        .and_then(|id| Uuid::parse_str(&id).ok())
+        .unwrap_or_else(Uuid::new_v4);
+    let mut annotations = AnnotationRegistry::new(shard_id, AnnotationPolicy::default());
+    if let Ok(terms) = std::env::var("FINALVERSE_ANNOTATION_BLOCKLIST") {
+        let terms = terms.split(',').map(str::trim).filter(|term| !term.is_empty()).map(String::from);
+        annotations = annotations.with_hook(BlockedTerms(terms.collect()));
+    }
+    info!("📝 Annotation shard {}", shard_id);
+
+    let membership_file = MembershipFile::new(
+        std::env::var("COMMUNITY_MEMBERSHIP_FILE").unwrap_or_else(|_| "data/community-membership.json".to_string()),
+    );
+    let membership = membership_file.load().await?;
+    info!("🎒 Restored {} parties and {} guilds", membership.parties.len(), membership.guilds.len());
+
+    let services = ServiceClient::new(registry);
+    let admin_services = match OperatorGuard::load().token() {
+        Some(token) => services.with_header(OPERATOR_TOKEN_HEADER, token),
+        None => {
+            warn!("No operator token configured; world-engine will refuse rituals, yield bonuses and terraforming");
+            services
+        }
+    };
+    let state = AppState {
+        stewardship: Arc::new(RwLock::new(StewardshipRegistry::new(StewardshipPolicy::default()))),
+        lore: Arc::new(RwLock::new(LoreRegistry::default())),
+        terraform: Arc::new(RwLock::new(TerraformRegistry::new(TerraformPolicy::default()))),
+        annotations: Arc::new(RwLock::new(annotations)),
+        parties: Arc::new(RwLock::new(PartyRegistry::restore(PartyPolicy::default(), membership.parties))),
+        guilds: Arc::new(RwLock::new(GuildRegistry::restore(membership.guilds))),
+        membership: Arc::new(Mutex::new(membership_file)),
+        event_bus: event_bus.clone(),
+        admin_services,
+    };
+
+    // Resolve scheduled contests, lapse neglected claims and close votes
+    let tick_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            tick_state.tick().await;
+        }
+    });
+
+    let remote = state.annotations.clone();
+    event_bus
+        .subscribe(
+            "events.community",
+            Box::new(move |event| {
+                if let EventType::Community(event) = event.event_type {
+                    let annotations = remote.clone();
+                    tokio::spawn(async move { apply_remote_annotations(&annotations, event).await });
+                }
+            }),
+        )
+        .await?;
+
+    // Deltas can be lost in transit and new shards start empty, so each
+    // shard periodically rebroadcasts its full documents
+    let sync_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            let documents = sync_state.annotations.read().await.documents();
+            for (grid, delta) in documents {
+                sync_state.publish_annotations(AnnotationChange { grid, delta }).await;
+            }
+        }
+    });
+
+    // Stewardship and terraforming act on behalf of the signed-in
+    // player's ensemble; annotations, parties and guilds act as that player
+    let player_routes = Router::new()
+        .route("/stewardship/:region_id/claim", post(claim_region))
+        .route("/stewardship/:region_id/upkeep", post(perform_upkeep))
+        .route("/stewardship/:region_id/contest", post(contest_region))
+        .route("/stewardship/:region_id/contest/contribute", post(contribute_to_contest))
+        .route("/stewardship/:region_id/name", post(name_region))
+        .route("/terraform/proposals", post(propose_terraform))
+        .route("/terraform/proposals/:proposal_id/pledge", post(pledge_to_proposal))
+        .route("/terraform/proposals/:proposal_id/vote", post(vote_on_proposal))
+        .route("/annotations/regions/:region_id", get(list_annotations).post(place_annotation))
+        .route("/annotations/regions/:region_id/settings", put(set_region_annotation_settings))
+        .route("/annotations/players/:player_id/settings", put(set_player_annotation_settings))
+        .route("/annotations/:annotation_id", delete(remove_annotation))
+        .route("/annotations/:annotation_id/report", post(report_annotation))
+        .route("/parties", post(form_party))
+        .route("/parties/:party_id/invite", post(invite_to_party))
+        .route("/parties/:party_id/join", post(join_party))
+        .route("/parties/:party_id/leave", post(leave_party))
+        .route("/guilds", post(found_guild))
+        .route("/guilds/:guild_id/invite", post(invite_to_guild))
+        .route("/guilds/:guild_id/join", post(join_guild))
+        .route("/guilds/:guild_id/leave", post(leave_guild))
+        .route("/guilds/:guild_id/members/:player_id/role", put(set_guild_role))
+        .route_layer(axum::middleware::from_fn_with_state(PlayerAuth::load(), require_player));
+
+    let app = Router::new()
+        .route("/stewardship", get(list_stewardships))
+        .route("/stewardship/:region_id", get(get_stewardship))
+        .route("/terraform/proposals", get(list_proposals))
+        .route("/terraform/proposals/:proposal_id", get(get_proposal))
+        .route("/lore/regions/:region_id", get(region_lore))
+        .route("/lore/names", get(lore_names).post(add_lore_names))
+        .route("/annotations/regions/:region_id/settings", get(get_region_annotation_settings))
+        .route("/annotations/players/:player_id/settings", get(get_player_annotation_settings))
+        .route("/parties", get(list_parties))
+        .route("/parties/:party_id", get(get_party))
+        .route("/guilds", get(list_guilds))
+        .route("/guilds/:guild_id", get(get_guild))
+        .route("/players/:player_id/party", get(player_party))
+        .route("/players/:player_id/guild", get(player_guild))
+        .merge(player_routes)
+        .with_state(state)
+        .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("community").with_event_bus(event_bus.clone())).axum_routes());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3008));
     info!("Community Service listening on {}", addr);
@@ -0,0 +1,47 @@
+// services/community/src/membership.rs
+//! Party and guild membership saved to a JSON file after every change, so
+//! groups survive a restart.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::guilds::Guild;
+use crate::parties::Party;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Membership {
+    #[serde(default)]
+    pub parties: Vec<Party>,
+    #[serde(default)]
+    pub guilds: Vec<Guild>,
+}
+
+pub struct MembershipFile {
+    path: PathBuf,
+}
+
+impl MembershipFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The saved membership; empty if nothing has been saved yet.
+    pub async fn load(&self) -> std::io::Result<Membership> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(std::io::Error::other),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Membership::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Replace the saved membership. The file is written aside and renamed
+    /// into place, so a crash mid-save leaves the previous copy intact.
+    pub async fn save(&self, membership: &Membership) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let partial = self.path.with_extension("partial");
+        tokio::fs::write(&partial, serde_json::to_vec_pretty(membership)?).await?;
+        tokio::fs::rename(&partial, &self.path).await
+    }
+}
@@ -0,0 +1,230 @@
+// services/community/src/parties.rs
+//! Parties: small groups that travel together, formed by invitation. A
+//! player is in at most one party at a time.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartyPolicy {
+    pub max_size: usize,
+    /// How long an invitation stays open.
+    pub invite_minutes: i64,
+}
+
+impl Default for PartyPolicy {
+    fn default() -> Self {
+        Self {
+            max_size: 6,
+            invite_minutes: 10,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PartyError {
+    #[error("Unknown party {0}")]
+    UnknownParty(Uuid),
+
+    #[error("Player {0} is already in a party")]
+    AlreadyInParty(Uuid),
+
+    #[error("Player {0} is not in this party")]
+    NotMember(Uuid),
+
+    #[error("Only the party leader can do that")]
+    NotLeader,
+
+    #[error("Party is full ({0} members)")]
+    Full(usize),
+
+    #[error("Player {0} has no open invitation to this party")]
+    NotInvited(Uuid),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Party {
+    pub id: Uuid,
+    pub leader_id: Uuid,
+    /// In joining order; leadership passes down this list.
+    pub members: Vec<Uuid>,
+    pub formed_at: DateTime<Utc>,
+    /// Open invitations and when they expire.
+    pub invites: HashMap<Uuid, DateTime<Utc>>,
+}
+
+impl Party {
+    pub fn is_member(&self, player_id: Uuid) -> bool {
+        self.members.contains(&player_id)
+    }
+}
+
+/// What leaving did to the party.
+#[derive(Debug, Clone)]
+pub enum Departure {
+    /// The party carries on, possibly under a new leader.
+    Left { party: Party, new_leader: Option<Uuid> },
+    /// The player was the last member.
+    Disbanded,
+}
+
+#[derive(Debug)]
+pub struct PartyRegistry {
+    policy: PartyPolicy,
+    parties: HashMap<Uuid, Party>,
+    by_player: HashMap<Uuid, Uuid>,
+}
+
+impl PartyRegistry {
+    /// A registry holding previously saved parties.
+    pub fn restore(policy: PartyPolicy, parties: Vec<Party>) -> Self {
+        let by_player = parties
+            .iter()
+            .flat_map(|party| party.members.iter().map(|member| (*member, party.id)))
+            .collect();
+        Self {
+            policy,
+            parties: parties.into_iter().map(|party| (party.id, party)).collect(),
+            by_player,
+        }
+    }
+
+    pub fn list(&self) -> Vec<Party> {
+        let mut parties: Vec<_> = self.parties.values().cloned().collect();
+        parties.sort_by_key(|party| party.formed_at);
+        parties
+    }
+
+    pub fn get(&self, party_id: Uuid) -> Result<&Party, PartyError> {
+        self.parties.get(&party_id).ok_or(PartyError::UnknownParty(party_id))
+    }
+
+    pub fn party_of(&self, player_id: Uuid) -> Option<&Party> {
+        self.by_player.get(&player_id).and_then(|party_id| self.parties.get(party_id))
+    }
+
+    fn check_free(&self, player_id: Uuid) -> Result<(), PartyError> {
+        if self.by_player.contains_key(&player_id) {
+            return Err(PartyError::AlreadyInParty(player_id));
+        }
+        Ok(())
+    }
+
+    /// Form a party led by `leader_id`.
+    pub fn create(&mut self, leader_id: Uuid, now: DateTime<Utc>) -> Result<Party, PartyError> {
+        self.check_free(leader_id)?;
+        let party = Party {
+            id: Uuid::new_v4(),
+            leader_id,
+            members: vec![leader_id],
+            formed_at: now,
+            invites: HashMap::new(),
+        };
+        self.by_player.insert(leader_id, party.id);
+        self.parties.insert(party.id, party.clone());
+        Ok(party)
+    }
+
+    pub fn invite(
+        &mut self,
+        party_id: Uuid,
+        leader_id: Uuid,
+        invitee_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Party, PartyError> {
+        self.check_free(invitee_id)?;
+        let expires_at = now + Duration::minutes(self.policy.invite_minutes);
+        let max_size = self.policy.max_size;
+        let party = self.parties.get_mut(&party_id).ok_or(PartyError::UnknownParty(party_id))?;
+        if party.leader_id != leader_id {
+            return Err(PartyError::NotLeader);
+        }
+        if party.members.len() >= max_size {
+            return Err(PartyError::Full(party.members.len()));
+        }
+        party.invites.retain(|_, expires| *expires > now);
+        party.invites.insert(invitee_id, expires_at);
+        Ok(party.clone())
+    }
+
+    /// Accept an open invitation.
+    pub fn join(&mut self, party_id: Uuid, player_id: Uuid, now: DateTime<Utc>) -> Result<Party, PartyError> {
+        self.check_free(player_id)?;
+        let max_size = self.policy.max_size;
+        let party = self.parties.get_mut(&party_id).ok_or(PartyError::UnknownParty(party_id))?;
+        match party.invites.remove(&player_id) {
+            Some(expires_at) if expires_at > now => {}
+            _ => return Err(PartyError::NotInvited(player_id)),
+        }
+        if party.members.len() >= max_size {
+            return Err(PartyError::Full(party.members.len()));
+        }
+        party.members.push(player_id);
+        let party = party.clone();
+        self.by_player.insert(player_id, party_id);
+        Ok(party)
+    }
+
+    /// Leave a party. A departing leader hands over to the longest-standing
+    /// member.
+    pub fn leave(&mut self, party_id: Uuid, player_id: Uuid) -> Result<Departure, PartyError> {
+        let party = self.parties.get_mut(&party_id).ok_or(PartyError::UnknownParty(party_id))?;
+        if !party.is_member(player_id) {
+            return Err(PartyError::NotMember(player_id));
+        }
+        party.members.retain(|member| *member != player_id);
+        self.by_player.remove(&player_id);
+
+        let Some(&successor) = party.members.first() else {
+            self.parties.remove(&party_id);
+            return Ok(Departure::Disbanded);
+        };
+        let new_leader = (party.leader_id == player_id).then(|| {
+            party.leader_id = successor;
+            successor
+        });
+        Ok(Departure::Left {
+            party: party.clone(),
+            new_leader,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invitations_gate_joining_and_leadership_passes_on() {
+        let policy = PartyPolicy {
+            max_size: 2,
+            ..Default::default()
+        };
+        let mut registry = PartyRegistry::restore(policy, Vec::new());
+        let (leader, friend, stranger) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+
+        let party = registry.create(leader, now).unwrap();
+        assert!(matches!(registry.join(party.id, friend, now), Err(PartyError::NotInvited(_))));
+        assert!(matches!(registry.invite(party.id, friend, stranger, now), Err(PartyError::NotLeader)));
+
+        registry.invite(party.id, leader, friend, now).unwrap();
+        let late = now + Duration::minutes(11);
+        assert!(matches!(registry.join(party.id, friend, late), Err(PartyError::NotInvited(_))));
+        registry.invite(party.id, leader, friend, now).unwrap();
+        registry.join(party.id, friend, now).unwrap();
+        assert!(matches!(registry.invite(party.id, leader, stranger, now), Err(PartyError::Full(2))));
+        assert!(matches!(registry.create(friend, now), Err(PartyError::AlreadyInParty(_))));
+
+        let Departure::Left { party, new_leader } = registry.leave(party.id, leader).unwrap() else {
+            panic!("party should carry on");
+        };
+        assert_eq!(new_leader, Some(friend));
+        assert_eq!(party.members, vec![friend]);
+        assert!(matches!(registry.leave(party.id, friend).unwrap(), Departure::Disbanded));
+        assert!(registry.party_of(friend).is_none());
+    }
+}
@@ -0,0 +1,312 @@
+// services/community/src/stewardship.rs
+//! Ensemble stewardship of regions: claims, upkeep rituals, benefits and
+//! scheduled contests.
+
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::RegionId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StewardshipPolicy {
+    /// How often a steward must perform a restoration ritual.
+    pub upkeep_interval_hours: i64,
+    /// Extra time after a missed ritual before the claim lapses.
+    pub grace_hours: i64,
+    /// Delay between a contest being declared and its resolution.
+    pub contest_window_hours: i64,
+    pub max_regions_per_ensemble: usize,
+    /// Resource yield bonus granted to stewarded regions.
+    pub yield_bonus: f64,
+    /// Harmony restored to the region by each upkeep ritual.
+    pub ritual_harmony: f32,
+}
+
+impl Default for StewardshipPolicy {
+    fn default() -> Self {
+        Self {
+            upkeep_interval_hours: 72,
+            grace_hours: 24,
+            contest_window_hours: 48,
+            max_regions_per_ensemble: 3,
+            yield_bonus: 0.15,
+            ritual_harmony: 5.0,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StewardshipError {
+    #[error("Region is already stewarded by {0}")]
+    AlreadyClaimed(String),
+
+    #[error("Region has no steward")]
+    Unclaimed,
+
+    #[error("Ensemble {0} is not the steward of this region")]
+    NotSteward(String),
+
+    #[error("Player is not a member of ensemble {0}")]
+    NotMember(String),
+
+    #[error("Ensemble already stewards {0} regions")]
+    TooManyRegions(usize),
+
+    #[error("A contest is already scheduled for this region")]
+    ContestInProgress,
+
+    #[error("No contest is scheduled for this region")]
+    NoContest,
+
+    #[error("Ensemble {0} is not part of the contest")]
+    NotContestant(String),
+
+    #[error("Invalid region name: {0}")]
+    InvalidName(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contest {
+    pub challenger_id: String,
+    pub challenger_name: String,
+    pub declared_at: DateTime<Utc>,
+    pub resolves_at: DateTime<Utc>,
+    /// Restoration contributed by each side during the contest window.
+    pub steward_score: f64,
+    pub challenger_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stewardship {
+    pub region_id: RegionId,
+    pub ensemble_id: String,
+    pub ensemble_name: String,
+    pub claimed_at: DateTime<Utc>,
+    pub last_upkeep: DateTime<Utc>,
+    pub region_name: Option<String>,
+    pub contest: Option<Contest>,
+}
+
+impl Stewardship {
+    pub fn upkeep_due(&self, policy: &StewardshipPolicy) -> DateTime<Utc> {
+        self.last_upkeep + Duration::hours(policy.upkeep_interval_hours)
+    }
+
+    fn lapses_at(&self, policy: &StewardshipPolicy) -> DateTime<Utc> {
+        self.upkeep_due(policy) + Duration::hours(policy.grace_hours)
+    }
+}
+
+/// Outcome of a contest that reached its scheduled resolution time.
+#[derive(Debug, Clone)]
+pub struct ContestResult {
+    pub region_id: RegionId,
+    pub steward_id: String,
+    pub challenger_id: String,
+    pub challenger_won: bool,
+}
+
+#[derive(Debug)]
+pub struct StewardshipRegistry {
+    policy: StewardshipPolicy,
+    claims: HashMap<RegionId, Stewardship>,
+}
+
+impl StewardshipRegistry {
+    pub fn new(policy: StewardshipPolicy) -> Self {
+        Self {
+            policy,
+            claims: HashMap::new(),
+        }
+    }
+
+    pub fn policy(&self) -> &StewardshipPolicy {
+        &self.policy
+    }
+
+    pub fn get(&self, region_id: &RegionId) -> Option<&Stewardship> {
+        self.claims.get(region_id)
+    }
+
+    pub fn list(&self) -> Vec<Stewardship> {
+        let mut claims: Vec<_> = self.claims.values().cloned().collect();
+        claims.sort_by_key(|claim| claim.claimed_at);
+        claims
+    }
+
+    fn regions_held(&self, ensemble_id: &str) -> usize {
+        self.claims.values().filter(|claim| claim.ensemble_id == ensemble_id).count()
+    }
+
+    pub fn claim(
+        &mut self,
+        region_id: RegionId,
+        ensemble_id: String,
+        ensemble_name: String,
+        now: DateTime<Utc>,
+    ) -> Result<Stewardship, StewardshipError> {
+        if let Some(existing) = self.claims.get(&region_id) {
+            return Err(StewardshipError::AlreadyClaimed(existing.ensemble_name.clone()));
+        }
+        let held = self.regions_held(&ensemble_id);
+        if held >= self.policy.max_regions_per_ensemble {
+            return Err(StewardshipError::TooManyRegions(held));
+        }
+
+        let stewardship = Stewardship {
+            region_id: region_id.clone(),
+            ensemble_id,
+            ensemble_name,
+            claimed_at: now,
+            last_upkeep: now,
+            region_name: None,
+            contest: None,
+        };
+        self.claims.insert(region_id, stewardship.clone());
+        Ok(stewardship)
+    }
+
+    /// Record a restoration ritual. During a contest the ritual also counts
+    /// towards the steward's score.
+    pub fn perform_upkeep(
+        &mut self,
+        region_id: &RegionId,
+        ensemble_id: &str,
+        contribution: f64,
+        now: DateTime<Utc>,
+    ) -> Result<Stewardship, StewardshipError> {
+        let claim = self.claims.get_mut(region_id).ok_or(StewardshipError::Unclaimed)?;
+        if claim.ensemble_id != ensemble_id {
+            return Err(StewardshipError::NotSteward(ensemble_id.to_string()));
+        }
+        claim.last_upkeep = now;
+        if let Some(contest) = claim.contest.as_mut() {
+            contest.steward_score += contribution;
+        }
+        Ok(claim.clone())
+    }
+
+    pub fn contest(
+        &mut self,
+        region_id: &RegionId,
+        challenger_id: String,
+        challenger_name: String,
+        now: DateTime<Utc>,
+    ) -> Result<Contest, StewardshipError> {
+        let held = self.regions_held(&challenger_id);
+        let window = Duration::hours(self.policy.contest_window_hours);
+        let max_regions = self.policy.max_regions_per_ensemble;
+
+        let claim = self.claims.get_mut(region_id).ok_or(StewardshipError::Unclaimed)?;
+        if claim.ensemble_id == challenger_id {
+            return Err(StewardshipError::AlreadyClaimed(claim.ensemble_name.clone()));
+        }
+        if claim.contest.is_some() {
+            return Err(StewardshipError::ContestInProgress);
+        }
+        if held >= max_regions {
+            return Err(StewardshipError::TooManyRegions(held));
+        }
+
+        let contest = Contest {
+            challenger_id,
+            challenger_name,
+            declared_at: now,
+            resolves_at: now + window,
+            steward_score: 0.0,
+            challenger_score: 0.0,
+        };
+        claim.contest = Some(contest.clone());
+        Ok(contest)
+    }
+
+    /// Add restoration work by either side of a scheduled contest.
+    pub fn contribute(
+        &mut self,
+        region_id: &RegionId,
+        ensemble_id: &str,
+        amount: f64,
+    ) -> Result<Contest, StewardshipError> {
+        let claim = self.claims.get_mut(region_id).ok_or(StewardshipError::Unclaimed)?;
+        let steward_id = claim.ensemble_id.clone();
+        let contest = claim.contest.as_mut().ok_or(StewardshipError::NoContest)?;
+
+        if ensemble_id == steward_id {
+            contest.steward_score += amount;
+        } else if ensemble_id == contest.challenger_id {
+            contest.challenger_score += amount;
+        } else {
+            return Err(StewardshipError::NotContestant(ensemble_id.to_string()));
+        }
+        Ok(contest.clone())
+    }
+
+    /// Name a stewarded region. Returns the previous name, if any.
+    pub fn name_region(
+        &mut self,
+        region_id: &RegionId,
+        ensemble_id: &str,
+        name: &str,
+    ) -> Result<Option<String>, StewardshipError> {
+        let name = name.trim();
+        if name.len() < 3 || name.len() > 40 {
+            return Err(StewardshipError::InvalidName("must be 3-40 characters".to_string()));
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '\'' || c == '-') {
+            return Err(StewardshipError::InvalidName("contains unsupported characters".to_string()));
+        }
+
+        let claim = self.claims.get_mut(region_id).ok_or(StewardshipError::Unclaimed)?;
+        if claim.ensemble_id != ensemble_id {
+            return Err(StewardshipError::NotSteward(ensemble_id.to_string()));
+        }
+        Ok(claim.region_name.replace(name.to_string()))
+    }
+
+    /// Resolve contests whose window has closed. Ties go to the steward.
+    pub fn resolve_contests(&mut self, now: DateTime<Utc>) -> Vec<ContestResult> {
+        let mut results = Vec::new();
+        for claim in self.claims.values_mut() {
+            let due = claim.contest.as_ref().is_some_and(|contest| contest.resolves_at <= now);
+            if !due {
+                continue;
+            }
+            let Some(contest) = claim.contest.take() else {
+                continue;
+            };
+
+            let challenger_won = contest.challenger_score > contest.steward_score;
+            results.push(ContestResult {
+                region_id: claim.region_id.clone(),
+                steward_id: claim.ensemble_id.clone(),
+                challenger_id: contest.challenger_id.clone(),
+                challenger_won,
+            });
+
+            if challenger_won {
+                claim.ensemble_id = contest.challenger_id;
+                claim.ensemble_name = contest.challenger_name;
+                claim.claimed_at = now;
+                claim.last_upkeep = now;
+            }
+        }
+        results
+    }
+
+    /// Drop claims whose upkeep is overdue past the grace period.
+    pub fn lapse_overdue(&mut self, now: DateTime<Utc>) -> Vec<Stewardship> {
+        let policy = self.policy.clone();
+        let overdue: Vec<RegionId> = self
+            .claims
+            .values()
+            .filter(|claim| claim.lapses_at(&policy) <= now)
+            .map(|claim| claim.region_id.clone())
+            .collect();
+
+        overdue
+            .into_iter()
+            .filter_map(|region_id| self.claims.remove(&region_id))
+            .collect()
+    }
+}
@@ -0,0 +1,317 @@
+// services/community/src/terraforming.rs
+//! Terraforming proposals: an ensemble proposes a transformation of a
+//! region, resources are pledged towards its cost, and eligible ensembles
+//! vote. Approved proposals are handed to the world engine to carry out.
+
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::{RegionId, TerraformKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerraformPolicy {
+    pub voting_window_hours: i64,
+    /// Minimum number of ensembles that must vote.
+    pub quorum: usize,
+    /// Share of weighted votes in favour needed to pass.
+    pub approval_ratio: f64,
+    /// Vote weight of the ensemble stewarding the region.
+    pub steward_weight: u32,
+    /// How long the world engine spends on each visible stage.
+    pub stage_hours: i64,
+}
+
+impl Default for TerraformPolicy {
+    fn default() -> Self {
+        Self {
+            voting_window_hours: 48,
+            quorum: 3,
+            approval_ratio: 0.6,
+            steward_weight: 3,
+            stage_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TerraformError {
+    #[error("Unknown proposal {0}")]
+    UnknownProposal(Uuid),
+
+    #[error("Region already has an open proposal")]
+    AlreadyProposed,
+
+    #[error("Voting on this proposal has closed")]
+    VotingClosed,
+
+    #[error("Ensemble {0} has already voted")]
+    AlreadyVoted(String),
+
+    #[error("Ensemble {0} is not eligible to vote: it must steward a region or have pledged to the proposal")]
+    NotEligible(String),
+
+    #[error("Invalid pledge: {0}")]
+    InvalidPledge(String),
+
+    #[error("Player is not a member of ensemble {0}")]
+    NotMember(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Voting,
+    Approved,
+    Rejected,
+    /// The vote closed before enough resources were pledged.
+    Underfunded,
+}
+
+impl ProposalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalStatus::Voting => "voting",
+            ProposalStatus::Approved => "approved",
+            ProposalStatus::Rejected => "rejected",
+            ProposalStatus::Underfunded => "underfunded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vote {
+    pub ensemble_id: String,
+    pub support: bool,
+    pub weight: u32,
+    pub cast_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerraformProposal {
+    pub id: Uuid,
+    pub region_id: RegionId,
+    pub kind: TerraformKind,
+    pub proposer_id: String,
+    pub proposer_name: String,
+    pub cost: f64,
+    /// Resources pledged by each ensemble.
+    pub pledges: HashMap<String, f64>,
+    pub votes: Vec<Vote>,
+    pub opened_at: DateTime<Utc>,
+    pub closes_at: DateTime<Utc>,
+    pub status: ProposalStatus,
+}
+
+impl TerraformProposal {
+    pub fn pledged(&self) -> f64 {
+        self.pledges.values().sum()
+    }
+
+    /// (weight in favour, weight against)
+    pub fn tally(&self) -> (u32, u32) {
+        self.votes.iter().fold((0, 0), |(yes, no), vote| {
+            if vote.support {
+                (yes + vote.weight, no)
+            } else {
+                (yes, no + vote.weight)
+            }
+        })
+    }
+}
+
+/// What the caller knows about a voting ensemble from the stewardship registry.
+#[derive(Debug, Clone)]
+pub struct Voter {
+    pub ensemble_id: String,
+    /// Stewards the region the proposal would transform.
+    pub stewards_region: bool,
+    /// Stewards any region.
+    pub is_steward: bool,
+}
+
+#[derive(Debug)]
+pub struct TerraformRegistry {
+    policy: TerraformPolicy,
+    proposals: HashMap<Uuid, TerraformProposal>,
+}
+
+impl TerraformRegistry {
+    pub fn new(policy: TerraformPolicy) -> Self {
+        Self {
+            policy,
+            proposals: HashMap::new(),
+        }
+    }
+
+    pub fn policy(&self) -> &TerraformPolicy {
+        &self.policy
+    }
+
+    pub fn get(&self, proposal_id: Uuid) -> Result<&TerraformProposal, TerraformError> {
+        self.proposals.get(&proposal_id).ok_or(TerraformError::UnknownProposal(proposal_id))
+    }
+
+    /// Newest first.
+    pub fn list(&self) -> Vec<TerraformProposal> {
+        let mut proposals: Vec<TerraformProposal> = self.proposals.values().cloned().collect();
+        proposals.sort_by_key(|proposal| std::cmp::Reverse(proposal.opened_at));
+        proposals
+    }
+
+    pub fn propose(
+        &mut self,
+        region_id: RegionId,
+        kind: TerraformKind,
+        ensemble_id: String,
+        ensemble_name: String,
+        now: DateTime<Utc>,
+    ) -> Result<TerraformProposal, TerraformError> {
+        let open = self
+            .proposals
+            .values()
+            .any(|proposal| proposal.region_id == region_id && proposal.status == ProposalStatus::Voting);
+        if open {
+            return Err(TerraformError::AlreadyProposed);
+        }
+
+        let proposal = TerraformProposal {
+            id: Uuid::new_v4(),
+            region_id,
+            kind,
+            proposer_id: ensemble_id,
+            proposer_name: ensemble_name,
+            cost: kind.resource_cost(),
+            pledges: HashMap::new(),
+            votes: Vec::new(),
+            opened_at: now,
+            closes_at: now + Duration::hours(self.policy.voting_window_hours),
+            status: ProposalStatus::Voting,
+        };
+        self.proposals.insert(proposal.id, proposal.clone());
+        Ok(proposal)
+    }
+
+    pub fn pledge(
+        &mut self,
+        proposal_id: Uuid,
+        ensemble_id: &str,
+        amount: f64,
+        now: DateTime<Utc>,
+    ) -> Result<TerraformProposal, TerraformError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(TerraformError::InvalidPledge("amount must be positive".to_string()));
+        }
+        let proposal = self.open_proposal(proposal_id, now)?;
+        *proposal.pledges.entry(ensemble_id.to_string()).or_insert(0.0) += amount;
+        Ok(proposal.clone())
+    }
+
+    /// Cast a vote. Stewards of any region and ensembles who pledged
+    /// resources may vote; the region's own steward carries extra weight.
+    pub fn vote(
+        &mut self,
+        proposal_id: Uuid,
+        voter: Voter,
+        support: bool,
+        now: DateTime<Utc>,
+    ) -> Result<TerraformProposal, TerraformError> {
+        let steward_weight = self.policy.steward_weight;
+        let proposal = self.open_proposal(proposal_id, now)?;
+        if proposal.votes.iter().any(|vote| vote.ensemble_id == voter.ensemble_id) {
+            return Err(TerraformError::AlreadyVoted(voter.ensemble_id));
+        }
+        let pledged = proposal.pledges.get(&voter.ensemble_id).is_some_and(|amount| *amount > 0.0);
+        if !(voter.is_steward || voter.stewards_region || pledged) {
+            return Err(TerraformError::NotEligible(voter.ensemble_id));
+        }
+
+        proposal.votes.push(Vote {
+            ensemble_id: voter.ensemble_id,
+            support,
+            weight: if voter.stewards_region { steward_weight } else { 1 },
+            cast_at: now,
+        });
+        Ok(proposal.clone())
+    }
+
+    /// Close every proposal whose voting window has ended.
+    pub fn close_due(&mut self, now: DateTime<Utc>) -> Vec<TerraformProposal> {
+        let policy = &self.policy;
+        let mut closed = Vec::new();
+        for proposal in self.proposals.values_mut() {
+            if proposal.status != ProposalStatus::Voting || now < proposal.closes_at {
+                continue;
+            }
+            let (yes, no) = proposal.tally();
+            proposal.status = if proposal.pledged() < proposal.cost {
+                ProposalStatus::Underfunded
+            } else if proposal.votes.len() < policy.quorum || (yes as f64) < (yes + no) as f64 * policy.approval_ratio {
+                ProposalStatus::Rejected
+            } else {
+                ProposalStatus::Approved
+            };
+            closed.push(proposal.clone());
+        }
+        closed
+    }
+
+    fn open_proposal(&mut self, proposal_id: Uuid, now: DateTime<Utc>) -> Result<&mut TerraformProposal, TerraformError> {
+        let proposal = self
+            .proposals
+            .get_mut(&proposal_id)
+            .ok_or(TerraformError::UnknownProposal(proposal_id))?;
+        if proposal.status != ProposalStatus::Voting || now >= proposal.closes_at {
+            return Err(TerraformError::VotingClosed);
+        }
+        Ok(proposal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voter(id: &str, stewards_region: bool) -> Voter {
+        Voter {
+            ensemble_id: id.to_string(),
+            stewards_region,
+            is_steward: stewards_region,
+        }
+    }
+
+    #[test]
+    fn funded_proposal_passes_with_weighted_quorum() {
+        let mut registry = TerraformRegistry::new(TerraformPolicy::default());
+        let now = Utc::now();
+        let region = RegionId(Uuid::new_v4());
+        let proposal = registry
+            .propose(region.clone(), TerraformKind::Reforest, "grove".into(), "The Grove".into(), now)
+            .unwrap();
+        assert!(matches!(
+            registry.propose(region, TerraformKind::Purify, "other".into(), "Other".into(), now),
+            Err(TerraformError::AlreadyProposed)
+        ));
+
+        assert!(matches!(
+            registry.vote(proposal.id, voter("drifters", false), true, now),
+            Err(TerraformError::NotEligible(_))
+        ));
+        registry.pledge(proposal.id, "grove", 300.0, now).unwrap();
+        registry.pledge(proposal.id, "drifters", 200.0, now).unwrap();
+
+        registry.vote(proposal.id, voter("steward", true), true, now).unwrap();
+        registry.vote(proposal.id, voter("grove", false), true, now).unwrap();
+        registry.vote(proposal.id, voter("drifters", false), false, now).unwrap();
+        assert!(registry.close_due(now).is_empty());
+
+        let closed = registry.close_due(proposal.closes_at);
+        assert_eq!(closed[0].status, ProposalStatus::Approved);
+        assert_eq!(closed[0].tally(), (4, 1));
+        assert!(matches!(
+            registry.pledge(proposal.id, "grove", 1.0, proposal.closes_at),
+            Err(TerraformError::VotingClosed)
+        ));
+    }
+}
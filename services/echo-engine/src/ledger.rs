@@ -0,0 +1,389 @@
+// services/echo-engine/src/ledger.rs
+//! Append-only ledger of echo bond changes. Bond levels are derived from the
+//! ledger instead of being mutated in place, so every change can be audited
+//! and bad ones undone with compensating entries. The ledger can be
+//! journaled to a store tree, keyed by sequence number, and replayed on
+//! startup.
+
+use chrono::{DateTime, Utc};
+use finalverse_store::{StoreError, Tree};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::warn;
+use uuid::Uuid;
+
+/// A snapshot of all bond levels is kept every this many entries, so
+/// deriving a level only replays the tail of the ledger.
+pub const SNAPSHOT_INTERVAL: u64 = 100;
+
+/// Source recorded on entries appended by a rollback.
+pub const ROLLBACK_SOURCE: &str = "admin_rollback";
+
+/// (echo, player)
+type BondKey = (Uuid, Uuid);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondEntry {
+    /// Position in the ledger, starting at 1.
+    pub seq: u64,
+    pub echo_id: Uuid,
+    pub player_id: Uuid,
+    pub source: String,
+    pub reason: String,
+    /// Change actually applied, after clamping to 0.0..=1.0.
+    pub delta: f32,
+    pub resulting_level: f32,
+    pub recorded_at: DateTime<Utc>,
+    /// Set on compensating entries: the sequence number the bond was
+    /// restored to.
+    pub rollback_to: Option<u64>,
+}
+
+/// Bond levels after applying every entry up to and including `seq`.
+struct Snapshot {
+    seq: u64,
+    levels: HashMap<BondKey, f32>,
+}
+
+/// Writes new entries to the store in the background, so recording a bond
+/// never waits on disk.
+struct Journal {
+    entries: mpsc::UnboundedSender<BondEntry>,
+    writer: JoinHandle<()>,
+}
+
+impl Journal {
+    fn spawn(tree: Tree) -> Self {
+        let (entries, mut pending) = mpsc::unbounded_channel::<BondEntry>();
+        let writer = tokio::spawn(async move {
+            while let Some(entry) = pending.recv().await {
+                if let Err(e) = tree.insert(entry.seq.to_be_bytes(), &entry).await {
+                    // The ledger in memory stays authoritative until restart
+                    warn!("Failed to journal bond entry {}: {}", entry.seq, e);
+                }
+            }
+        });
+        Self { entries, writer }
+    }
+}
+
+#[derive(Default)]
+pub struct BondLedger {
+    entries: Vec<BondEntry>,
+    snapshots: Vec<Snapshot>,
+    /// Where every new entry is kept.
+    journal: Option<Journal>,
+}
+
+impl BondLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A ledger replayed from `tree`, which new entries are journaled to.
+    pub async fn load(tree: Tree) -> Result<Self, StoreError> {
+        let mut ledger = Self::new();
+        for entry in tree.values::<BondEntry>().await? {
+            if entry.seq != ledger.last_seq() + 1 {
+                warn!("Bond journal entry {} follows entry {}", entry.seq, ledger.last_seq());
+                return Err(StoreError::Corrupt(tree.name().to_string()));
+            }
+            ledger.push(entry);
+        }
+        ledger.journal = Some(Journal::spawn(tree));
+        Ok(ledger)
+    }
+
+    /// Take over the entries of a ledger journaled one per line to the file
+    /// at `path`, as echo-engine did before it had a store. Only an empty
+    /// ledger can import. Returns how many entries came across.
+    pub fn import_journal(&mut self, path: impl AsRef<Path>) -> std::io::Result<u64> {
+        if self.last_seq() > 0 {
+            return Err(std::io::Error::other("only an empty ledger can import a journal"));
+        }
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: BondEntry = serde_json::from_str(&line)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            if entry.seq != self.last_seq() + 1 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("journal entry {} follows entry {}", entry.seq, self.last_seq()),
+                ));
+            }
+            self.journal_entry(&entry);
+            self.push(entry);
+        }
+        Ok(self.last_seq())
+    }
+
+    /// Wait until every entry recorded so far is in the store.
+    pub async fn close(mut self) {
+        if let Some(Journal { entries, writer }) = self.journal.take() {
+            drop(entries);
+            if let Err(e) = writer.await {
+                warn!("Bond journal writer failed: {}", e);
+            }
+        }
+    }
+
+    pub fn last_seq(&self) -> u64 {
+        self.entries.len() as u64
+    }
+
+    /// Current bond level between an echo and a player.
+    pub fn level(&self, echo_id: Uuid, player_id: Uuid) -> f32 {
+        self.level_at((echo_id, player_id), self.last_seq())
+    }
+
+    /// Current bond levels of every echo `player_id` is bonded with.
+    pub fn levels_for_player(&self, player_id: Uuid) -> HashMap<Uuid, f32> {
+        self.levels_at(self.last_seq())
+            .into_iter()
+            .filter(|((_, player), _)| *player == player_id)
+            .map(|((echo, _), level)| (echo, level))
+            .collect()
+    }
+
+    /// The newest change to a bond from `source`.
+    pub fn latest(&self, echo_id: Uuid, player_id: Uuid, source: &str) -> Option<&BondEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.echo_id == echo_id && entry.player_id == player_id && entry.source == source)
+    }
+
+    /// How many changes to a bond came from `source`.
+    pub fn count(&self, echo_id: Uuid, player_id: Uuid, source: &str) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.echo_id == echo_id && entry.player_id == player_id && entry.source == source)
+            .count()
+    }
+
+    /// Current bond levels of every player bonded with `echo_id`.
+    pub fn levels_for_echo(&self, echo_id: Uuid) -> HashMap<Uuid, f32> {
+        self.levels_at(self.last_seq())
+            .into_iter()
+            .filter(|((echo, _), _)| *echo == echo_id)
+            .map(|((_, player), level)| (player, level))
+            .collect()
+    }
+
+    /// Append a change of `delta`. The stored delta is what was actually
+    /// applied once the level is clamped.
+    pub fn record(
+        &mut self,
+        echo_id: Uuid,
+        player_id: Uuid,
+        source: impl Into<String>,
+        reason: impl Into<String>,
+        delta: f32,
+    ) -> Result<BondEntry, String> {
+        if !delta.is_finite() {
+            return Err("delta must be a finite number".to_string());
+        }
+        let resulting_level = (self.level(echo_id, player_id) + delta).clamp(0.0, 1.0);
+        Ok(self.append(echo_id, player_id, source.into(), reason.into(), resulting_level, None))
+    }
+
+    /// Newest-first changes for an echo, optionally for one player only.
+    pub fn history(&self, echo_id: Uuid, player_id: Option<Uuid>, limit: usize) -> Vec<BondEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.echo_id == echo_id && player_id.is_none_or(|p| entry.player_id == p))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Restore bonds of `echo_id` (and optionally a single player) to their
+    /// levels as of `to_seq`. Nothing is removed: each restored bond gets a
+    /// compensating entry, which is returned.
+    pub fn rollback(
+        &mut self,
+        echo_id: Uuid,
+        player_id: Option<Uuid>,
+        to_seq: u64,
+        reason: &str,
+    ) -> Result<Vec<BondEntry>, String> {
+        if to_seq > self.last_seq() {
+            return Err(format!("ledger only has {} entries", self.last_seq()));
+        }
+
+        let mut keys: Vec<BondKey> = Vec::new();
+        let mut seen = HashSet::new();
+        for entry in &self.entries[to_seq as usize..] {
+            let key = (entry.echo_id, entry.player_id);
+            if entry.echo_id == echo_id && player_id.is_none_or(|p| entry.player_id == p) && seen.insert(key) {
+                keys.push(key);
+            }
+        }
+
+        let mut compensations = Vec::new();
+        for key in keys {
+            let target = self.level_at(key, to_seq);
+            let current = self.level(key.0, key.1);
+            if (target - current).abs() > f32::EPSILON {
+                compensations.push(self.append(
+                    key.0,
+                    key.1,
+                    ROLLBACK_SOURCE.to_string(),
+                    reason.to_string(),
+                    target,
+                    Some(to_seq),
+                ));
+            }
+        }
+        Ok(compensations)
+    }
+
+    fn append(
+        &mut self,
+        echo_id: Uuid,
+        player_id: Uuid,
+        source: String,
+        reason: String,
+        resulting_level: f32,
+        rollback_to: Option<u64>,
+    ) -> BondEntry {
+        let current = self.level(echo_id, player_id);
+        let entry = BondEntry {
+            seq: self.last_seq() + 1,
+            echo_id,
+            player_id,
+            source,
+            reason,
+            delta: resulting_level - current,
+            resulting_level,
+            recorded_at: Utc::now(),
+            rollback_to,
+        };
+        self.journal_entry(&entry);
+        self.push(entry.clone());
+        entry
+    }
+
+    fn journal_entry(&self, entry: &BondEntry) {
+        if let Some(journal) = &self.journal {
+            if journal.entries.send(entry.clone()).is_err() {
+                warn!("Bond journal writer has stopped; entry {} is only in memory", entry.seq);
+            }
+        }
+    }
+
+    fn push(&mut self, entry: BondEntry) {
+        let seq = entry.seq;
+        self.entries.push(entry);
+        if seq.is_multiple_of(SNAPSHOT_INTERVAL) {
+            let levels = self.levels_at(seq);
+            self.snapshots.push(Snapshot { seq, levels });
+        }
+    }
+
+    /// Latest snapshot taken at or before `seq`.
+    fn snapshot_before(&self, seq: u64) -> Option<&Snapshot> {
+        self.snapshots.iter().rev().find(|snapshot| snapshot.seq <= seq)
+    }
+
+    fn level_at(&self, key: BondKey, seq: u64) -> f32 {
+        let snapshot = self.snapshot_before(seq);
+        let from = snapshot.map_or(0, |s| s.seq) as usize;
+        self.entries[from..seq as usize]
+            .iter()
+            .rev()
+            .find(|entry| (entry.echo_id, entry.player_id) == key)
+            .map(|entry| entry.resulting_level)
+            .or_else(|| snapshot.and_then(|s| s.levels.get(&key).copied()))
+            .unwrap_or(0.0)
+    }
+
+    fn levels_at(&self, seq: u64) -> HashMap<BondKey, f32> {
+        let snapshot = self.snapshot_before(seq);
+        let mut levels = snapshot.map(|s| s.levels.clone()).unwrap_or_default();
+        let from = snapshot.map_or(0, |s| s.seq) as usize;
+        for entry in &self.entries[from..seq as usize] {
+            levels.insert((entry.echo_id, entry.player_id), entry.resulting_level);
+        }
+        levels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_appends_compensating_entries() {
+        let mut ledger = BondLedger::new();
+        let echo = Uuid::new_v4();
+        let player = Uuid::new_v4();
+
+        for _ in 0..SNAPSHOT_INTERVAL + 5 {
+            ledger.record(echo, player, "interaction", "chat", 0.001).unwrap();
+        }
+        let checkpoint = ledger.last_seq();
+        let before = ledger.level(echo, player);
+
+        let bad = ledger.record(echo, player, "quest", "duplicate reward", 2.0).unwrap();
+        assert_eq!(bad.resulting_level, 1.0);
+        assert!((bad.delta - (1.0 - before)).abs() < 1e-6);
+
+        let compensations = ledger.rollback(echo, None, checkpoint, "double-granted reward").unwrap();
+        assert_eq!(compensations.len(), 1);
+        assert_eq!(compensations[0].rollback_to, Some(checkpoint));
+        assert_eq!(ledger.level(echo, player), before);
+        // History keeps the bad change alongside its compensation
+        assert_eq!(ledger.history(echo, Some(player), 2).len(), 2);
+        assert!(ledger.rollback(echo, None, ledger.last_seq() + 1, "").is_err());
+    }
+
+    #[tokio::test]
+    async fn journal_replays_on_load() {
+        let store = finalverse_store::Store::temporary().await.unwrap();
+        let echo = Uuid::new_v4();
+        let player = Uuid::new_v4();
+
+        let mut ledger = BondLedger::load(store.tree("bonds").await.unwrap()).await.unwrap();
+        ledger.record(echo, player, "interaction", "chat", 0.25).unwrap();
+        ledger.record(echo, player, "quest", "rescue", 0.5).unwrap();
+        ledger.close().await;
+
+        let reloaded = BondLedger::load(store.tree("bonds").await.unwrap()).await.unwrap();
+        assert_eq!(reloaded.last_seq(), 2);
+        assert_eq!(reloaded.level(echo, player), 0.75);
+        assert_eq!(reloaded.count(echo, player, "interaction"), 1);
+        assert_eq!(reloaded.latest(echo, player, "quest").map(|entry| entry.seq), Some(2));
+    }
+
+    #[tokio::test]
+    async fn file_journals_import_into_the_store() {
+        let path = std::env::temp_dir().join(format!("echo-bonds-{}.jsonl", Uuid::new_v4()));
+        let echo = Uuid::new_v4();
+        let player = Uuid::new_v4();
+        let mut old = BondLedger::new();
+        old.record(echo, player, "interaction", "chat", 0.25).unwrap();
+        old.record(echo, player, "quest", "rescue", 0.5).unwrap();
+        let lines: Vec<String> = old.entries.iter().map(|entry| serde_json::to_string(entry).unwrap()).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let store = finalverse_store::Store::temporary().await.unwrap();
+        let mut ledger = BondLedger::load(store.tree("bonds").await.unwrap()).await.unwrap();
+        assert_eq!(ledger.import_journal(&path).unwrap(), 2);
+        assert!(ledger.import_journal(&path).is_err());
+        ledger.close().await;
+
+        let reloaded = BondLedger::load(store.tree("bonds").await.unwrap()).await.unwrap();
+        assert_eq!(reloaded.level(echo, player), 0.75);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
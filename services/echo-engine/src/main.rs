@@ -1,6 +1,11 @@
 // services/echo-engine/src/main.rs
+mod ledger;
+mod presence;
+mod progression;
+
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
@@ -9,6 +14,12 @@ use finalverse_core::{
     echo::{Echo, EchoPersonality, EchoState},
     types::{EchoType, Coordinates as Position},
 };
+use chrono::Utc;
+use finalverse_auth::{axum::require_player, AuthenticatedPlayer, PlayerAuth};
+use finalverse_events::{
+    self as events, EchoEvent, Event, EventMetadata, EventType, GameEventBus, LocalEventBus, NatsEventBus, PlayerEvent,
+    PlayerId, TenantEventBus, WorldEvent,
+};
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -17,12 +28,170 @@ use std::{
     sync::{Arc, Mutex},
 };
 use tower_http::trace::TraceLayer;
-use tracing::{info, Level};
+use tracing::{info, warn};
 use finalverse_logging as logging;
+use finalverse_health::{require_operator, DebugEndpoints, OperatorGuard};
+use finalverse_store::Store;
+use ledger::{BondEntry, BondLedger};
+use presence::{PresenceTracker, Wanderer, WorldSync};
+use progression::{BondTier, INTERACTION_BOND_GAIN, INTERACTION_SOURCE};
+
+/// History entries returned when no limit is given.
+const DEFAULT_HISTORY_LIMIT: usize = 50;
 
 #[derive(Clone)]
 struct AppState {
     echoes: Arc<Mutex<HashMap<Uuid, Echo>>>,
+    bonds: Arc<Mutex<BondLedger>>,
+    event_bus: Arc<dyn GameEventBus>,
+    presence: Arc<Mutex<PresenceTracker>>,
+}
+
+impl AppState {
+    /// The only way bonds change: append to the ledger, then mirror the
+    /// derived level onto the echo.
+    fn record_bond(
+        &self,
+        echo_id: Uuid,
+        player_id: Uuid,
+        source: &str,
+        reason: &str,
+        delta: f32,
+    ) -> Result<BondEntry, (StatusCode, String)> {
+        let mut bonds = self.bonds.lock().unwrap();
+        let mut echoes = self.echoes.lock().unwrap();
+        let echo = echoes
+            .get_mut(&echo_id)
+            .ok_or((StatusCode::NOT_FOUND, "Echo not found".to_string()))?;
+        let entry = bonds
+            .record(echo_id, player_id, source, reason, delta)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        echo.bond_levels.insert(player_id, entry.resulting_level);
+        Ok(entry)
+    }
+
+    /// An echo by id, or by name ignoring case.
+    fn find_echo(&self, key: &str) -> Option<Uuid> {
+        let echoes = self.echoes.lock().unwrap();
+        match Uuid::parse_str(key) {
+            Ok(id) => echoes.contains_key(&id).then_some(id),
+            Err(_) => echoes.values().find(|echo| echo.name.eq_ignore_ascii_case(key)).map(|echo| echo.id),
+        }
+    }
+
+    /// A player interacts with an echo, which answers according to their
+    /// bond. Outside the cooldown the bond grows; the ledger entry for that
+    /// is returned too.
+    fn interact(&self, echo_id: Uuid, player_id: Uuid) -> Result<(InteractResponse, Option<BondEntry>), (StatusCode, String)> {
+        let mut bonds = self.bonds.lock().unwrap();
+        let mut echoes = self.echoes.lock().unwrap();
+        let echo = echoes
+            .get_mut(&echo_id)
+            .ok_or((StatusCode::NOT_FOUND, "Echo not found".to_string()))?;
+
+        let before = bonds.level(echo_id, player_id);
+        let cooldown = progression::cooldown_remaining(&bonds, echo_id, player_id, Utc::now());
+        let entry = match cooldown {
+            Some(_) => None,
+            None => {
+                let entry = bonds
+                    .record(echo_id, player_id, INTERACTION_SOURCE, "echo interaction", INTERACTION_BOND_GAIN)
+                    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+                echo.bond_levels.insert(player_id, entry.resulting_level);
+                Some(entry)
+            }
+        };
+
+        let level = bonds.level(echo_id, player_id);
+        let tier = BondTier::of(level);
+        let mut response = echo.get_dialogue_for_context(player_id, "interaction");
+        if tier > BondTier::of(before) {
+            if let Some(line) = progression::tier_reached_line(&echo.name, tier) {
+                response = format!("{} {}", response, line);
+            }
+        }
+
+        let interaction = InteractResponse {
+            echo_id,
+            echo_name: echo.name.clone(),
+            response,
+            bond_level: progression::percent(level),
+            tier,
+            gained: entry.as_ref().map_or(0.0, |entry| entry.delta),
+            interactions: bonds.count(echo_id, player_id, INTERACTION_SOURCE),
+            cooldown_secs: cooldown.map_or(0, |remaining| remaining.num_seconds().max(1) as u64),
+        };
+        Ok((interaction, entry))
+    }
+
+    fn echo_positions(&self) -> Vec<(Uuid, Position)> {
+        self.echoes.lock().unwrap().values().map(|echo| (echo.id, echo.position)).collect()
+    }
+
+    /// A player is at `position`: echoes they have just come near appear
+    /// to them.
+    async fn player_moved(&self, player_id: Uuid, position: Position) {
+        let appeared = self.presence.lock().unwrap().update(player_id, &position, self.echo_positions());
+        for echo_id in appeared {
+            let Some((echo_name, location)) = self
+                .echoes
+                .lock()
+                .unwrap()
+                .get(&echo_id)
+                .map(|echo| (echo.name.clone(), echo.position))
+            else {
+                continue;
+            };
+            let (grid_x, grid_y) = presence::grid_of(&location);
+            let event = Event::new(EventType::World(WorldEvent::EchoAppeared {
+                echo_id,
+                echo_name,
+                player_id: PlayerId(player_id),
+                location: events::Coordinates {
+                    x: location.x as f64,
+                    y: location.y as f64,
+                    z: location.z as f64,
+                },
+                grid_x,
+                grid_y,
+            }))
+            .with_metadata(EventMetadata {
+                source: Some("echo-engine".to_string()),
+                tags: vec!["presence".to_string()],
+                ..Default::default()
+            });
+            if let Err(e) = self.event_bus.publish(event).await {
+                warn!("Failed to publish echo appearance: {}", e);
+            }
+        }
+    }
+
+    /// Tell the world a bond grew. Changes that lowered or kept a bond, such
+    /// as rollbacks, aren't announced.
+    async fn publish_bond_change(&self, entry: &BondEntry) {
+        if entry.delta <= 0.0 {
+            return;
+        }
+        let Some(echo_name) = self.echoes.lock().unwrap().get(&entry.echo_id).map(|echo| echo.name.clone()) else {
+            return;
+        };
+        let event = Event::new(EventType::Echo(EchoEvent::EchoBondIncreased {
+            player_id: PlayerId(entry.player_id),
+            echo_id: entry.echo_id,
+            echo_name,
+            gained: entry.delta,
+            new_level: entry.resulting_level,
+            tier: BondTier::of(entry.resulting_level).name().to_string(),
+        }))
+        .with_metadata(EventMetadata {
+            source: Some("echo-engine".to_string()),
+            tags: vec!["bond".to_string()],
+            ..Default::default()
+        });
+        if let Err(e) = self.event_bus.publish(event).await {
+            warn!("Failed to publish bond change: {}", e);
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -40,6 +209,97 @@ struct EchoResponse {
     position: Position,
 }
 
+#[derive(Deserialize)]
+struct InteractRequest {
+    player_id: Uuid,
+}
+
+/// Interaction the way the text client asks for it, naming the echo.
+#[derive(Deserialize)]
+struct NamedInteractRequest {
+    player_id: Uuid,
+    /// Id or name of the echo.
+    echo_id: String,
+}
+
+#[derive(Serialize)]
+struct InteractResponse {
+    echo_id: Uuid,
+    echo_name: String,
+    /// What the echo says, which depends on the bond.
+    response: String,
+    /// 0 to 100.
+    bond_level: u32,
+    tier: BondTier,
+    /// Bond level this interaction added, from 0 to 1.
+    gained: f32,
+    /// Interactions that have added to the bond.
+    interactions: usize,
+    /// Seconds until interacting adds to the bond again.
+    cooldown_secs: u64,
+}
+
+#[derive(Deserialize)]
+struct NearbyQuery {
+    x: f32,
+    y: f32,
+    z: f32,
+    radius: Option<f32>,
+    /// The player standing here, to whom echoes they've come near appear.
+    player_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+struct NearbyEcho {
+    id: Uuid,
+    echo_type: EchoType,
+    name: String,
+    position: Position,
+    distance: f32,
+    grid_x: i32,
+    grid_y: i32,
+}
+
+#[derive(Serialize)]
+struct PlayerBond {
+    echo_id: Uuid,
+    echo_type: EchoType,
+    name: String,
+    /// 0 to 100.
+    bond_level: u32,
+    tier: BondTier,
+    interactions: usize,
+}
+
+#[derive(Serialize)]
+struct PlayerBonds {
+    player_id: Uuid,
+    bonds: Vec<PlayerBond>,
+}
+
+/// Recorded for the signed-in player.
+#[derive(Deserialize)]
+struct RecordBondRequest {
+    source: String,
+    reason: String,
+    delta: f32,
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    player_id: Option<Uuid>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct RollbackRequest {
+    echo_id: Uuid,
+    player_id: Option<Uuid>,
+    /// Ledger sequence number to restore bonds to.
+    to_seq: u64,
+    reason: String,
+}
+
 impl From<&Echo> for EchoResponse {
     fn from(echo: &Echo) -> Self {
         EchoResponse {
@@ -53,25 +313,69 @@ impl From<&Echo> for EchoResponse {
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
     logging::init(Some("info"));
 
+    let store_path = std::env::var("ECHO_STORE").unwrap_or_else(|_| "data/echo-engine".to_string());
+    let store = Store::open(&store_path).await?;
+    let mut bonds = BondLedger::load(store.tree("bonds").await?).await?;
+    info!("Replayed {} bond entries from {}", bonds.last_seq(), store_path);
+    // Bonds journaled to a file before echo-engine had a store come across once
+    let legacy_path = std::env::var("ECHO_BOND_LEDGER").unwrap_or_else(|_| "data/echo-bonds.jsonl".to_string());
+    if bonds.last_seq() == 0 && std::path::Path::new(&legacy_path).exists() {
+        let imported = bonds.import_journal(&legacy_path)?;
+        info!("Imported {} bond entries from {}", imported, legacy_path);
+    }
+
+    let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
+        info!("📡 Connecting to NATS at {}", nats_url);
+        Arc::new(NatsEventBus::new(&nats_url).await?)
+    } else {
+        info!("📦 Using local event bus");
+        Arc::new(LocalEventBus::new())
+    };
+    let event_bus = TenantEventBus::from_env(event_bus);
+
     let state = AppState {
         echoes: Arc::new(Mutex::new(HashMap::new())),
+        bonds: Arc::new(Mutex::new(bonds)),
+        event_bus: event_bus.clone(),
+        presence: Arc::new(Mutex::new(PresenceTracker::default())),
     };
 
     // Initialize the First Echoes
     initialize_first_echoes(&state);
 
+    let world3d_url = std::env::var("WORLD3D_URL").unwrap_or_else(|_| "http://localhost:3012".to_string());
+    spawn_echo_movement(state.clone(), WorldSync::new(&world3d_url)?);
+    subscribe_player_movement(&state).await?;
+
+    // Recording a bond acts as the signed-in player; rolling bonds back is
+    // for operators only
+    let player_routes = Router::new()
+        .route("/echoes/:id/bonds", post(record_bond))
+        .route_layer(axum::middleware::from_fn_with_state(PlayerAuth::load(), require_player));
+    let operator_routes = Router::new()
+        .route("/admin/bonds/rollback", post(rollback_bonds))
+        .route_layer(axum::middleware::from_fn_with_state(OperatorGuard::load(), require_operator));
+
     // Build our application with routes
     let app = Router::new()
         .route("/echoes", get(list_echoes))
         .route("/echoes", post(create_echo))
+        .route("/echoes/nearby", get(nearby_echoes))
         .route("/echoes/:id", get(get_echo))
         .route("/echoes/:id/interact", post(interact_with_echo))
+        .route("/interact", post(interact_by_name))
+        .route("/bonds/:player_id", get(player_bonds))
+        .route("/echoes/:id/bonds", get(get_bonds))
+        .route("/echoes/:id/bonds/history", get(bond_history))
+        .merge(player_routes)
+        .merge(operator_routes)
         .layer(TraceLayer::new_for_http())
-        .with_state(state);
+        .with_state(state.clone())
+        .merge(Arc::new(DebugEndpoints::load("echo-engine").with_event_bus(event_bus)).axum_routes());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3004));
     info!("Echo Engine listening on {}", addr);
@@ -79,47 +383,145 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("Failed to bind");
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .unwrap();
+
+    // Finish journaling bonds recorded before the signal
+    info!("🛑 Shutting down Echo Engine...");
+    let bonds = std::mem::take(&mut *state.bonds.lock().unwrap());
+    bonds.close().await;
+    Ok(())
+}
+
+/// Resolves on ctrl+c or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Failed to listen for ctrl+c");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Move every echo a step every [`presence::MOVE_INTERVAL`] and send the
+/// new positions to world3d-service, placing echoes it doesn't know yet.
+fn spawn_echo_movement(state: AppState, world: WorldSync) {
+    tokio::spawn(async move {
+        let mut wanderer = Wanderer::default();
+        let mut interval = tokio::time::interval(presence::MOVE_INTERVAL);
+        let mut world_reachable = true;
+        loop {
+            interval.tick().await;
+            let moved: Vec<(Uuid, String, Position)> = {
+                let mut echoes = state.echoes.lock().unwrap();
+                echoes
+                    .values_mut()
+                    .map(|echo| {
+                        echo.position = wanderer.step(echo.id, echo.position);
+                        (echo.id, echo.name.clone(), echo.position)
+                    })
+                    .collect()
+            };
+
+            for (echo_id, name, position) in moved {
+                let synced = match world.move_to(echo_id, position).await {
+                    Ok(true) => Ok(()),
+                    Ok(false) => world.place(echo_id, &name, position).await,
+                    Err(e) => Err(e),
+                };
+                match synced {
+                    Ok(()) if !world_reachable => {
+                        info!("Echo positions are syncing to world3d-service again");
+                        world_reachable = true;
+                    }
+                    Err(e) if world_reachable => {
+                        // Logged once per outage rather than once per echo per step
+                        warn!("Can't sync echo positions to world3d-service: {}", e);
+                        world_reachable = false;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+}
+
+/// Echoes appear to players as their moves bring them near.
+async fn subscribe_player_movement(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
+    let handler_state = state.clone();
+    state
+        .event_bus
+        .subscribe(
+            "events.player",
+            Box::new(move |event| {
+                let (player_id, position) = match &event.event_type {
+                    EventType::Player(PlayerEvent::Moved { player_id, to, .. }) => {
+                        (player_id, Some(Position::new(to.x as f32, to.y as f32, to.z as f32)))
+                    }
+                    EventType::Player(PlayerEvent::Disconnected { player_id }) => (player_id, None),
+                    _ => return,
+                };
+                let player_id = player_id.0;
+                match position {
+                    Some(position) => {
+                        let state = handler_state.clone();
+                        tokio::spawn(async move { state.player_moved(player_id, position).await });
+                    }
+                    None => handler_state.presence.lock().unwrap().forget(player_id),
+                }
+            }),
+        )
+        .await?;
+    Ok(())
+}
+
+/// The First Echoes keep their ids across restarts, so journaled bonds
+/// still find them.
+fn first_echo(echo_type: EchoType, name: &str, position: Position) -> Echo {
+    let mut echo = Echo::new(echo_type, name.to_string(), position);
+    echo.id = Uuid::new_v5(&Uuid::NAMESPACE_OID, format!("finalverse.echo.{}", name.to_lowercase()).as_bytes());
+    echo
 }
 
 fn initialize_first_echoes(state: &AppState) {
     let mut echoes = state.echoes.lock().unwrap();
 
     // Lumi - Echo of Hope and Discovery
-    let lumi = Echo::new(
-        EchoType::Lumi,
-        "Lumi".to_string(),
-        Position::new(0.0, 0.0, 0.0),
-    );
+    let lumi = first_echo(EchoType::Lumi, "Lumi", Position::new(0.0, 0.0, 0.0));
     echoes.insert(lumi.id, lumi);
     info!("Initialized Lumi - Echo of Hope and Discovery");
 
     // KAI - Echo of Logic and Understanding
-    let kai = Echo::new(
-        EchoType::KAI,
-        "KAI".to_string(),
-        Position::new(100.0, 0.0, 0.0),
-    );
+    let kai = first_echo(EchoType::KAI, "KAI", Position::new(100.0, 0.0, 0.0));
     echoes.insert(kai.id, kai);
     info!("Initialized KAI - Echo of Logic and Understanding");
 
     // Terra - Echo of Resilience and Growth
-    let terra = Echo::new(
-        EchoType::Terra,
-        "Terra".to_string(),
-        Position::new(0.0, 100.0, 0.0),
-    );
+    let terra = first_echo(EchoType::Terra, "Terra", Position::new(0.0, 100.0, 0.0));
     echoes.insert(terra.id, terra);
     info!("Initialized Terra - Echo of Resilience and Growth");
 
     // Ignis - Echo of Courage and Creation
-    let ignis = Echo::new(
-        EchoType::Ignis,
-        "Ignis".to_string(),
-        Position::new(100.0, 100.0, 0.0),
-    );
+    let ignis = first_echo(EchoType::Ignis, "Ignis", Position::new(100.0, 100.0, 0.0));
     echoes.insert(ignis.id, ignis);
     info!("Initialized Ignis - Echo of Courage and Creation");
+
+    let bonds = state.bonds.lock().unwrap();
+    for echo in echoes.values_mut() {
+        echo.bond_levels = bonds.levels_for_echo(echo.id);
+    }
 }
 
 async fn list_echoes(State(state): State<AppState>) -> Json<Vec<EchoResponse>> {
@@ -146,6 +548,46 @@ async fn create_echo(
     Json(response)
 }
 
+/// Echoes within `radius` of a point, nearest first.
+async fn nearby_echoes(
+    State(state): State<AppState>,
+    Query(query): Query<NearbyQuery>,
+) -> Result<Json<Vec<NearbyEcho>>, (StatusCode, String)> {
+    let radius = query.radius.unwrap_or(presence::DEFAULT_NEARBY_RADIUS);
+    if !(0.0..=presence::MAX_NEARBY_RADIUS).contains(&radius) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("radius must be between 0 and {}", presence::MAX_NEARBY_RADIUS),
+        ));
+    }
+    let here = Position::new(query.x, query.y, query.z);
+    if let Some(player_id) = query.player_id {
+        state.player_moved(player_id, here).await;
+    }
+
+    let mut nearby: Vec<NearbyEcho> = state
+        .echoes
+        .lock()
+        .unwrap()
+        .values()
+        .filter_map(|echo| {
+            let distance = echo.position.distance_to(&here);
+            let (grid_x, grid_y) = presence::grid_of(&echo.position);
+            (distance <= radius).then(|| NearbyEcho {
+                id: echo.id,
+                echo_type: echo.echo_type,
+                name: echo.name.clone(),
+                position: echo.position,
+                distance,
+                grid_x,
+                grid_y,
+            })
+        })
+        .collect();
+    nearby.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    Ok(Json(nearby))
+}
+
 async fn get_echo(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -157,17 +599,92 @@ async fn get_echo(
 async fn interact_with_echo(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Json<String> {
+    Json(request): Json<InteractRequest>,
+) -> Result<Json<InteractResponse>, (StatusCode, String)> {
+    let (response, entry) = state.interact(id, request.player_id)?;
+    if let Some(entry) = entry {
+        state.publish_bond_change(&entry).await;
+    }
+    Ok(Json(response))
+}
+
+async fn interact_by_name(
+    State(state): State<AppState>,
+    Json(request): Json<NamedInteractRequest>,
+) -> Result<Json<InteractResponse>, (StatusCode, String)> {
+    let id = state
+        .find_echo(&request.echo_id)
+        .ok_or((StatusCode::NOT_FOUND, "Echo not found".to_string()))?;
+    interact_with_echo(State(state), Path(id), Json(InteractRequest { player_id: request.player_id })).await
+}
+
+/// Every bond a player has formed, strongest first.
+async fn player_bonds(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+) -> Json<PlayerBonds> {
+    let bonds = state.bonds.lock().unwrap();
     let echoes = state.echoes.lock().unwrap();
+    let mut player_bonds: Vec<PlayerBond> = bonds
+        .levels_for_player(player_id)
+        .into_iter()
+        .filter_map(|(echo_id, level)| {
+            let echo = echoes.get(&echo_id)?;
+            Some(PlayerBond {
+                echo_id,
+                echo_type: echo.echo_type,
+                name: echo.name.clone(),
+                bond_level: progression::percent(level),
+                tier: BondTier::of(level),
+                interactions: bonds.count(echo_id, player_id, INTERACTION_SOURCE),
+            })
+        })
+        .collect();
+    player_bonds.sort_by(|a, b| b.bond_level.cmp(&a.bond_level).then_with(|| a.name.cmp(&b.name)));
+    Json(PlayerBonds { player_id, bonds: player_bonds })
+}
+
+async fn get_bonds(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Json<HashMap<Uuid, f32>> {
+    Json(state.bonds.lock().unwrap().levels_for_echo(id))
+}
 
-    if let Some(echo) = echoes.get(&id) {
-        match echo.echo_type {
-            EchoType::Lumi => Json("Lumi's light brightens, filling you with hope!".to_string()),
-            EchoType::KAI => Json("KAI analyzes the situation, revealing hidden patterns.".to_string()),
-            EchoType::Terra => Json("Terra's presence strengthens your resolve.".to_string()),
-            EchoType::Ignis => Json("Ignis ignites your courage!".to_string()),
+async fn record_bond(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    player: AuthenticatedPlayer,
+    Json(request): Json<RecordBondRequest>,
+) -> Result<Json<BondEntry>, (StatusCode, String)> {
+    let entry = state.record_bond(id, player.player_id.0, &request.source, &request.reason, request.delta)?;
+    state.publish_bond_change(&entry).await;
+    Ok(Json(entry))
+}
+
+async fn bond_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Json<Vec<BondEntry>> {
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    Json(state.bonds.lock().unwrap().history(id, query.player_id, limit))
+}
+
+async fn rollback_bonds(
+    State(state): State<AppState>,
+    Json(request): Json<RollbackRequest>,
+) -> Result<Json<Vec<BondEntry>>, (StatusCode, String)> {
+    let mut bonds = state.bonds.lock().unwrap();
+    let mut echoes = state.echoes.lock().unwrap();
+    let compensations = bonds
+        .rollback(request.echo_id, request.player_id, request.to_seq, &request.reason)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    if let Some(echo) = echoes.get_mut(&request.echo_id) {
+        for entry in &compensations {
+            echo.bond_levels.insert(entry.player_id, entry.resulting_level);
         }
-    } else {
-        Json("Echo not found".to_string())
     }
+    info!("Rolled back {} bond(s) of echo {} to entry {}", compensations.len(), request.echo_id, request.to_seq);
+    Ok(Json(compensations))
 }
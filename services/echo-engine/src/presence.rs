@@ -0,0 +1,208 @@
+// services/echo-engine/src/presence.rs
+//! Where echoes are in the world. Echoes wander near their homes, keep their
+//! place in world3d-service's grids up to date, and appear to players who
+//! come near them.
+
+use finalverse_core::types::Coordinates as Position;
+use finalverse_proto::world3d as proto;
+use finalverse_proto::world3d::world3_d_service_client::World3DServiceClient;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+use uuid::Uuid;
+
+/// Side of a world3d grid, as in `Position3D::to_grid_coordinate`.
+pub const GRID_SIZE: f32 = 256.0;
+
+/// How far an echo strays from where it was placed.
+pub const WANDER_RADIUS: f32 = 40.0;
+
+/// Furthest an echo moves in one step.
+pub const WANDER_STEP: f32 = 5.0;
+
+/// Time between echo movement steps.
+pub const MOVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A player this close to an echo sees it appear.
+pub const APPEARANCE_RADIUS: f32 = 50.0;
+
+/// Radius of a nearby query that doesn't give one.
+pub const DEFAULT_NEARBY_RADIUS: f32 = 100.0;
+
+/// Largest radius a nearby query may use.
+pub const MAX_NEARBY_RADIUS: f32 = 1000.0;
+
+/// Entity type echoes are spawned in world3d-service as.
+const ECHO_ENTITY_TYPE: &str = "echo";
+
+/// The world3d grid `position` lies in.
+pub fn grid_of(position: &Position) -> (i32, i32) {
+    ((position.x / GRID_SIZE).floor() as i32, (position.y / GRID_SIZE).floor() as i32)
+}
+
+/// Moves echoes about their homes: each walks towards a point near home,
+/// and picks another once it gets there.
+#[derive(Default)]
+pub struct Wanderer {
+    homes: HashMap<Uuid, Position>,
+    targets: HashMap<Uuid, Position>,
+}
+
+impl Wanderer {
+    /// Where `echo_id`, now at `current`, moves next. An echo's home is
+    /// wherever it was first seen.
+    pub fn step(&mut self, echo_id: Uuid, current: Position) -> Position {
+        let home = *self.homes.entry(echo_id).or_insert(current);
+        let target = match self.targets.get(&echo_id) {
+            Some(target) if target.distance_to(&current) > f32::EPSILON => *target,
+            _ => {
+                let mut rng = rand::thread_rng();
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let distance = rng.gen_range(0.0..WANDER_RADIUS);
+                let target = Position::new(home.x + angle.cos() * distance, home.y + angle.sin() * distance, home.z);
+                self.targets.insert(echo_id, target);
+                target
+            }
+        };
+
+        let remaining = current.distance_to(&target);
+        if remaining <= WANDER_STEP {
+            return target;
+        }
+        let scale = WANDER_STEP / remaining;
+        Position::new(
+            current.x + (target.x - current.x) * scale,
+            current.y + (target.y - current.y) * scale,
+            current.z + (target.z - current.z) * scale,
+        )
+    }
+}
+
+/// Which echoes each player is near, so an echo appears once per visit
+/// rather than on every move.
+#[derive(Default)]
+pub struct PresenceTracker {
+    near: HashMap<Uuid, HashSet<Uuid>>,
+}
+
+impl PresenceTracker {
+    /// Note that `player_id` is at `position` and return the echoes it has
+    /// just come within [`APPEARANCE_RADIUS`] of.
+    pub fn update(
+        &mut self,
+        player_id: Uuid,
+        position: &Position,
+        echoes: impl IntoIterator<Item = (Uuid, Position)>,
+    ) -> Vec<Uuid> {
+        let now_near: HashSet<Uuid> = echoes
+            .into_iter()
+            .filter(|(_, echo)| echo.distance_to(position) <= APPEARANCE_RADIUS)
+            .map(|(id, _)| id)
+            .collect();
+        let before = self.near.remove(&player_id).unwrap_or_default();
+        let appeared = now_near.difference(&before).copied().collect();
+        if !now_near.is_empty() {
+            self.near.insert(player_id, now_near);
+        }
+        appeared
+    }
+
+    pub fn forget(&mut self, player_id: Uuid) {
+        self.near.remove(&player_id);
+    }
+}
+
+/// Echo placement in world3d-service. Calls are best effort: echo-engine
+/// keeps the authoritative positions and resends them as echoes move.
+#[derive(Clone)]
+pub struct WorldSync {
+    client: World3DServiceClient<Channel>,
+}
+
+impl WorldSync {
+    /// Connects on first use, so world3d-service needn't be up yet.
+    pub fn new(url: &str) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(url.to_string())?
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(10))
+            .connect_lazy();
+        Ok(Self {
+            client: World3DServiceClient::new(channel),
+        })
+    }
+
+    /// Place an echo under its own id; placing it again replaces it.
+    pub async fn place(&self, echo_id: Uuid, name: &str, position: Position) -> Result<(), tonic::Status> {
+        let (grid_x, grid_y) = grid_of(&position);
+        let properties = HashMap::from([
+            ("entity_id".to_string(), echo_id.to_string()),
+            ("name".to_string(), name.to_string()),
+        ]);
+        self.client
+            .clone()
+            .spawn_entity(proto::SpawnEntityRequest {
+                entity_type: ECHO_ENTITY_TYPE.to_string(),
+                position: Some(to_proto(position)),
+                grid_x,
+                grid_y,
+                properties,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Move a placed echo. Returns false if world3d-service doesn't know it,
+    /// e.g. because it restarted.
+    pub async fn move_to(&self, echo_id: Uuid, position: Position) -> Result<bool, tonic::Status> {
+        let response = self
+            .client
+            .clone()
+            .move_entity(proto::MoveEntityRequest {
+                entity_id: echo_id.to_string(),
+                position: Some(to_proto(position)),
+            })
+            .await?;
+        Ok(response.into_inner().success)
+    }
+}
+
+fn to_proto(position: Position) -> proto::Position3D {
+    proto::Position3D {
+        x: position.x,
+        y: position.y,
+        z: position.z,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echoes_appear_once_per_visit() {
+        let mut tracker = PresenceTracker::default();
+        let player = Uuid::new_v4();
+        let echo = Uuid::new_v4();
+        let echoes = [(echo, Position::new(0.0, 0.0, 0.0))];
+
+        assert_eq!(tracker.update(player, &Position::new(30.0, 0.0, 0.0), echoes), vec![echo]);
+        assert!(tracker.update(player, &Position::new(10.0, 0.0, 0.0), echoes).is_empty());
+        assert!(tracker.update(player, &Position::new(500.0, 0.0, 0.0), echoes).is_empty());
+        assert_eq!(tracker.update(player, &Position::new(0.0, 20.0, 0.0), echoes), vec![echo]);
+    }
+
+    #[test]
+    fn wandering_stays_near_home() {
+        let mut wanderer = Wanderer::default();
+        let echo = Uuid::new_v4();
+        let home = Position::new(100.0, 100.0, 0.0);
+        let mut position = home;
+        for _ in 0..200 {
+            let next = wanderer.step(echo, position);
+            assert!(next.distance_to(&position) <= WANDER_STEP + 1e-3);
+            assert!(next.distance_to(&home) <= WANDER_RADIUS + 1e-3);
+            position = next;
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// services/echo-engine/src/progression.rs
+//! How bonds grow through interaction: the tiers an echo's regard for a
+//! player passes through, and the cooldown between interactions that count.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::ledger::BondLedger;
+
+/// Ledger source of bond gained by interacting with an echo.
+pub const INTERACTION_SOURCE: &str = "interaction";
+
+/// Bond gained from a single interaction with an echo.
+pub const INTERACTION_BOND_GAIN: f32 = 0.02;
+
+/// Time after an interaction before the next one adds to the bond.
+pub const INTERACTION_COOLDOWN_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BondTier {
+    Stranger,
+    Companion,
+    Kindred,
+}
+
+impl BondTier {
+    /// The tier of a bond level; the thresholds are the ones echo dialogue
+    /// changes at.
+    pub fn of(level: f32) -> Self {
+        if level < 0.3 {
+            BondTier::Stranger
+        } else if level < 0.7 {
+            BondTier::Companion
+        } else {
+            BondTier::Kindred
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BondTier::Stranger => "stranger",
+            BondTier::Companion => "companion",
+            BondTier::Kindred => "kindred",
+        }
+    }
+}
+
+/// A bond level as the 0–100 the client shows.
+pub fn percent(level: f32) -> u32 {
+    (level * 100.0).round() as u32
+}
+
+/// How long until interacting with `echo_id` adds to `player_id`'s bond
+/// again; None if it would now.
+pub fn cooldown_remaining(ledger: &BondLedger, echo_id: Uuid, player_id: Uuid, now: DateTime<Utc>) -> Option<Duration> {
+    let last = ledger.latest(echo_id, player_id, INTERACTION_SOURCE)?;
+    let remaining = last.recorded_at + Duration::seconds(INTERACTION_COOLDOWN_SECS) - now;
+    (remaining > Duration::zero()).then_some(remaining)
+}
+
+/// What an echo adds to its usual words when a bond reaches a new tier.
+pub fn tier_reached_line(echo_name: &str, tier: BondTier) -> Option<String> {
+    match tier {
+        BondTier::Stranger => None,
+        BondTier::Companion => Some(format!("{} now counts you among its companions.", echo_name)),
+        BondTier::Kindred => Some(format!("{} and you are kindred now; its song and yours are one.", echo_name)),
+    }
+}
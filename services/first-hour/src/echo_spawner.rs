@@ -14,9 +14,11 @@ struct PreparedSpawn {
     trigger_condition: TriggerCondition,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EchoType {
     Lumi,
+    #[serde(rename = "kai")]
     KAI,
     Terra,
     Ignis,
@@ -36,38 +38,31 @@ impl EchoSpawner {
         }
     }
 
-    pub async fn prepare_lumi_spawn(
+    /// Hold an echo back until `trigger_spawn(spawn_id)`, which a scene
+    /// trigger calls when `event` arrives.
+    pub async fn prepare_spawn(
         &mut self,
+        spawn_id: &str,
+        echo_type: EchoType,
         grid: GridCoordinate,
         position: Position3D,
+        event: &str,
     ) -> anyhow::Result<()> {
         self.prepared_spawns.insert(
-            "lumi_first_appearance".to_string(),
+            spawn_id.to_string(),
             PreparedSpawn {
                 grid,
                 position,
-                echo_type: EchoType::Lumi,
-                trigger_condition: TriggerCondition::OnEvent("character_creation_complete".to_string()),
+                echo_type,
+                trigger_condition: TriggerCondition::OnEvent(event.to_string()),
             },
         );
         Ok(())
     }
 
-    pub async fn prepare_ignis_spawn(
-        &mut self,
-        grid: GridCoordinate,
-        position: Position3D,
-    ) -> anyhow::Result<()> {
-        self.prepared_spawns.insert(
-            "ignis_arrival".to_string(),
-            PreparedSpawn {
-                grid,
-                position,
-                echo_type: EchoType::Ignis,
-                trigger_condition: TriggerCondition::OnEvent("gloom_shade_encounter".to_string()),
-            },
-        );
-        Ok(())
+    /// Forget every prepared spawn, for reloading scenes.
+    pub fn clear(&mut self) {
+        self.prepared_spawns.clear();
     }
 
     pub async fn trigger_spawn(&mut self, spawn_id: &str) -> anyhow::Result<Option<EntityId>> {
@@ -1,18 +1,20 @@
 // services/first-hour/src/first_hour_manager.rs
-use finalverse_world3d::{Position3D, GridCoordinate};
-use crate::echo_spawner::{EchoSpawner, EchoType};
-use crate::interactive_objects::{InteractiveObjectManager, InteractiveType, ObjectState, NPCState};
-use crate::scenes::SceneDefinitions;
+use crate::echo_spawner::EchoSpawner;
+use crate::interactive_objects::InteractiveObjectManager;
+use crate::scenes::{SceneLibrary, SceneScript, Spawn, SpawnKind};
 use crate::PlayerEvent;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct FirstHourSceneManager {
     echo_spawner: EchoSpawner,
     object_manager: InteractiveObjectManager,
+    library: SceneLibrary,
     scene_states: HashMap<String, SceneState>,
+    /// Completion events each player has sent, by scene then player.
+    progress: HashMap<String, HashMap<String, HashSet<String>>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SceneState {
     Uninitialized,
     Initialized,
@@ -20,102 +22,210 @@ pub enum SceneState {
     Completed,
 }
 
+/// What a player event set off, in the order it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneOutcome {
+    Spawned { scene: String, spawn: String },
+    Dialogue { scene: String, speaker: String, line: String },
+    Completed { scene: String, player_id: String },
+}
+
 impl FirstHourSceneManager {
     pub fn new() -> Self {
+        Self::with_library(SceneLibrary::builtin())
+    }
+
+    pub fn with_library(library: SceneLibrary) -> Self {
         Self {
             echo_spawner: EchoSpawner::new(),
             object_manager: InteractiveObjectManager::new(),
-            scene_states: HashMap::new(),
+            scene_states: library
+                .scenes()
+                .iter()
+                .map(|scene| (scene.id.clone(), SceneState::Uninitialized))
+                .collect(),
+            library,
+            progress: HashMap::new(),
         }
     }
 
-    pub async fn setup_memory_grotto(&mut self) -> anyhow::Result<()> {
-        let layout = SceneDefinitions::memory_grotto_layout();
+    pub fn scene_state(&self, scene_id: &str) -> Option<&SceneState> {
+        self.scene_states.get(scene_id)
+    }
 
-        // Add memory crystals for character creation
-        for (name, pos) in &layout.key_positions {
-            if name.starts_with("crystal_") {
-                self.object_manager.spawn_memory_crystal(layout.grid, *pos).await?;
-            }
+    pub async fn setup_scenes(&mut self) -> anyhow::Result<()> {
+        for scene in self.library.scenes().to_vec() {
+            self.setup_scene(&scene).await?;
         }
+        Ok(())
+    }
 
-        // Lumi spawns here after character creation
-        self.echo_spawner.prepare_lumi_spawn(
-            layout.grid,
-            Position3D::new(130.0, 130.0, 51.0)
-        ).await?;
+    /// Spawn everything no trigger is holding back and prepare the rest.
+    async fn setup_scene(&mut self, scene: &SceneScript) -> anyhow::Result<()> {
+        for spawn in &scene.spawns {
+            if !scene.is_deferred(&spawn.id) {
+                self.spawn(scene, spawn).await?;
+                continue;
+            }
+            if let SpawnKind::Echo { echo } = &spawn.kind {
+                let event = scene
+                    .triggers
+                    .iter()
+                    .find(|trigger| trigger.spawn.contains(&spawn.id))
+                    .map(|trigger| trigger.on.as_str())
+                    .unwrap_or_default();
+                let position = scene.position(&spawn.at)?;
+                self.echo_spawner
+                    .prepare_spawn(&spawn_key(scene, spawn), *echo, scene.grid, position, event)
+                    .await?;
+            }
+        }
 
-        self.scene_states.insert("memory_grotto".to_string(), SceneState::Initialized);
-        tracing::info!("Memory Grotto scene initialized");
+        self.scene_states.insert(scene.id.clone(), SceneState::Initialized);
+        tracing::info!("{} scene initialized", scene.name);
         Ok(())
     }
 
-    pub async fn setup_weavers_landing(&mut self) -> anyhow::Result<()> {
-        let layout = SceneDefinitions::weavers_landing_layout();
-
-        // Spawn Anya NPC
-        self.object_manager.spawn_npc(
-            layout.grid,
-            "anya",
-            Position3D::new(182.0, 142.0, 52.0),
-            NPCState::InitialSadness
-        ).await?;
-
-        // Spawn the faded Star Whale statue
-        self.object_manager.spawn_interactive(
-            layout.grid,
-            InteractiveType::AnyaStatue,
-            Position3D::new(185.0, 145.0, 52.5),
-            ObjectState::Faded
-        ).await?;
-
-        // Prepare Ignis spawn (triggered later)
-        self.echo_spawner.prepare_ignis_spawn(
-            layout.grid,
-            Position3D::new(150.0, 150.0, 51.5)
-        ).await?;
-
-        self.scene_states.insert("weavers_landing".to_string(), SceneState::Initialized);
-        tracing::info!("Weaver's Landing scene initialized");
+    async fn spawn(&mut self, scene: &SceneScript, spawn: &Spawn) -> anyhow::Result<()> {
+        let position = scene.position(&spawn.at)?;
+        match &spawn.kind {
+            SpawnKind::Echo { echo } => {
+                let key = spawn_key(scene, spawn);
+                self.echo_spawner.prepare_spawn(&key, *echo, scene.grid, position, "").await?;
+                if let Some(entity_id) = self.echo_spawner.trigger_spawn(&key).await? {
+                    tracing::info!("{:?} spawned in {}: {:?}", echo, scene.name, entity_id);
+                }
+            }
+            SpawnKind::Npc { name, state } => {
+                self.object_manager.spawn_npc(scene.grid, name, position, state.clone()).await?;
+            }
+            SpawnKind::Object { object, state } => {
+                self.object_manager
+                    .spawn_interactive(scene.grid, object.clone(), position, state.clone())
+                    .await?;
+            }
+        }
         Ok(())
     }
 
-    pub async fn setup_whisperwood_grove(&mut self) -> anyhow::Result<()> {
-        let layout = SceneDefinitions::whisperwood_grove_layout();
-
-        // Spawn the Resonant Blossom
-        self.object_manager.spawn_interactive(
-            layout.grid,
-            InteractiveType::ResonantBlossom,
-            Position3D::new(210.0, 190.0, 56.0),
-            ObjectState::Dormant
-        ).await?;
+    /// Swap in edited scenes: everything spawned is cleared and the new
+    /// scenes set up. Players keep their progress toward completion.
+    pub async fn reload(&mut self, library: SceneLibrary) -> anyhow::Result<()> {
+        self.echo_spawner.clear();
+        self.object_manager.clear();
+        self.scene_states = library
+            .scenes()
+            .iter()
+            .map(|scene| (scene.id.clone(), SceneState::Uninitialized))
+            .collect();
+        self.library = library;
+        self.setup_scenes().await
+    }
 
-        self.scene_states.insert("whisperwood_grove".to_string(), SceneState::Initialized);
-        tracing::info!("Whisperwood Grove scene initialized");
+    pub async fn handle_player_event(&mut self, event: PlayerEvent) -> anyhow::Result<()> {
+        let outcomes = self.apply(&event).await?;
+        if outcomes.is_empty() {
+            tracing::debug!("Unhandled event type: {}", event.event_type);
+        }
+        for outcome in outcomes {
+            match outcome {
+                SceneOutcome::Spawned { scene, spawn } => tracing::info!("Spawned {} in {}", spawn, scene),
+                SceneOutcome::Dialogue { speaker, line, .. } => tracing::info!("{}: {}", speaker, line),
+                SceneOutcome::Completed { scene, player_id } => {
+                    tracing::info!("Player {} completed {}", player_id, scene)
+                }
+            }
+        }
         Ok(())
     }
 
-    pub async fn handle_player_event(&mut self, event: PlayerEvent) -> anyhow::Result<()> {
-        match event.event_type.as_str() {
-            "character_creation_complete" => {
-                if let Some(entity_id) = self.echo_spawner.trigger_spawn("lumi_first_appearance").await? {
-                    tracing::info!("Lumi spawned: {:?}", entity_id);
+    /// Run every trigger listening for `event` and record it toward scene
+    /// completion.
+    pub async fn apply(&mut self, event: &PlayerEvent) -> anyhow::Result<Vec<SceneOutcome>> {
+        let mut outcomes = Vec::new();
+        for scene in self.library.scenes().to_vec() {
+            for trigger in scene.triggers.iter().filter(|trigger| trigger.on == event.event_type) {
+                for spawn_id in &trigger.spawn {
+                    let Some(spawn) = scene.spawn(spawn_id) else { continue };
+                    let spawned = match &spawn.kind {
+                        // Echoes appear once, for whoever gets there first
+                        SpawnKind::Echo { .. } => self
+                            .echo_spawner
+                            .trigger_spawn(&spawn_key(&scene, spawn))
+                            .await?
+                            .is_some(),
+                        _ => {
+                            self.spawn(&scene, spawn).await?;
+                            true
+                        }
+                    };
+                    if spawned {
+                        outcomes.push(SceneOutcome::Spawned { scene: scene.id.clone(), spawn: spawn_id.clone() });
+                    }
                 }
-            },
-            "statue_restored" => {
-                // Trigger Gloom Shade appearance
-                tracing::info!("Statue restored, preparing for Gloom Shade encounter");
-            },
-            "gloom_shade_defeated" => {
-                if let Some(entity_id) = self.echo_spawner.trigger_spawn("ignis_arrival").await? {
-                    tracing::info!("Ignis has arrived: {:?}", entity_id);
+                for beat in trigger.say.iter().filter_map(|id| scene.beat(id)) {
+                    outcomes.push(SceneOutcome::Dialogue {
+                        scene: scene.id.clone(),
+                        speaker: beat.speaker.clone(),
+                        line: beat.line.clone(),
+                    });
                 }
-            },
-            _ => {
-                tracing::debug!("Unhandled event type: {}", event.event_type);
+            }
+
+            let required = &scene.completion.events;
+            if required.is_empty() || !required.contains(&event.event_type) {
+                continue;
+            }
+            let seen = self
+                .progress
+                .entry(scene.id.clone())
+                .or_default()
+                .entry(event.player_id.clone())
+                .or_default();
+            if seen.insert(event.event_type.clone()) && required.iter().all(|required| seen.contains(required)) {
+                self.scene_states.insert(scene.id.clone(), SceneState::Completed);
+                outcomes.push(SceneOutcome::Completed { scene: scene.id.clone(), player_id: event.player_id.clone() });
+            } else if self.scene_states.get(&scene.id) == Some(&SceneState::Initialized) {
+                self.scene_states.insert(scene.id.clone(), SceneState::Active);
             }
         }
-        Ok(())
+        Ok(outcomes)
+    }
+}
+
+fn spawn_key(scene: &SceneScript, spawn: &Spawn) -> String {
+    format!("{}:{}", scene.id, spawn.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str) -> PlayerEvent {
+        PlayerEvent {
+            event_type: event_type.to_string(),
+            player_id: "kael".to_string(),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    #[tokio::test]
+    async fn triggers_spawn_speak_and_complete_scenes() {
+        let mut manager = FirstHourSceneManager::new();
+        manager.setup_scenes().await.unwrap();
+
+        let outcomes = manager.apply(&event("statue_restored")).await.unwrap();
+        assert_eq!(outcomes[0], SceneOutcome::Spawned { scene: "weavers_landing".into(), spawn: "gloom_shade".into() });
+        assert!(matches!(&outcomes[1], SceneOutcome::Dialogue { speaker, .. } if speaker == "Anya"));
+        assert_eq!(manager.scene_state("weavers_landing"), Some(&SceneState::Active));
+
+        let outcomes = manager.apply(&event("gloom_shade_defeated")).await.unwrap();
+        assert!(outcomes.contains(&SceneOutcome::Spawned { scene: "weavers_landing".into(), spawn: "ignis".into() }));
+        assert!(outcomes.contains(&SceneOutcome::Completed { scene: "weavers_landing".into(), player_id: "kael".into() }));
+
+        // Ignis only arrives once
+        let again = manager.apply(&event("gloom_shade_defeated")).await.unwrap();
+        assert!(!again.iter().any(|outcome| matches!(outcome, SceneOutcome::Spawned { .. })));
+        assert!(manager.apply(&event("waved")).await.unwrap().is_empty());
     }
-}
\ No newline at end of file
+}
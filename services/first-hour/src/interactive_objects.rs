@@ -17,7 +17,8 @@ struct InteractiveObject {
     state: ObjectState,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InteractiveType {
     MemoryCrystal,
     AnyaStatue,
@@ -25,7 +26,8 @@ pub enum InteractiveType {
     GloomShade,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ObjectState {
     Active,
     Dormant,
@@ -43,7 +45,8 @@ struct NPCData {
     state: NPCState,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NPCState {
     InitialSadness,
     Hopeful,
@@ -128,4 +131,10 @@ impl InteractiveObjectManager {
         }
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Remove every object and NPC, for reloading scenes.
+    pub fn clear(&mut self) {
+        self.objects.clear();
+        self.npcs.clear();
+    }
+}
@@ -6,19 +6,29 @@ pub mod interactive_objects;
 pub mod world_client;
 pub mod asset_generator;
 
+use finalverse_core::TenantId;
 use finalverse_world3d::{Position3D, GridCoordinate};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tonic::codegen::tokio_stream::StreamExt;
 // Re-export for easier access
 pub use first_hour_manager::FirstHourSceneManager;
+pub use scenes::{SceneLibrary, SceneScript};
 pub use world_client::WorldEngineClient;
 
+/// How often a scene directory is checked for edits.
+const SCENE_POLL: Duration = Duration::from_secs(2);
+
 #[derive(Clone)]
 pub struct FirstHourConfig {
     pub redis_url: String,
     pub world_engine_url: String,
     pub starting_grid: GridCoordinate,
+    pub tenant: TenantId,
+    /// Scene files to run and watch; the built-in scenes when unset.
+    pub scene_dir: Option<PathBuf>,
 }
 
 impl FirstHourConfig {
@@ -29,6 +39,8 @@ impl FirstHourConfig {
             world_engine_url: std::env::var("WORLD_ENGINE_URL")
                 .unwrap_or_else(|_| "http://localhost:50051".to_string()),
             starting_grid: GridCoordinate::new(100, 100),
+            tenant: TenantId::from_env(),
+            scene_dir: std::env::var("FIRST_HOUR_SCENES").ok().map(PathBuf::from),
         }
     }
 }
@@ -43,7 +55,11 @@ pub struct FirstHourService {
 impl FirstHourService {
     pub async fn new(config: FirstHourConfig) -> anyhow::Result<Self> {
         let world_client = WorldEngineClient::connect(&config.world_engine_url).await?;
-        let scene_manager = Arc::new(RwLock::new(FirstHourSceneManager::new()));
+        let library = match &config.scene_dir {
+            Some(dir) => SceneLibrary::load_dir(dir)?,
+            None => SceneLibrary::builtin(),
+        };
+        let scene_manager = Arc::new(RwLock::new(FirstHourSceneManager::with_library(library)));
         let redis_client = redis::Client::open(config.redis_url.clone())?;
 
         Ok(Self {
@@ -61,6 +77,11 @@ impl FirstHourService {
         // Start event listeners
         self.start_event_listeners().await?;
 
+        // Pick up scene edits without a restart
+        if let Some(dir) = self.config.scene_dir.clone() {
+            tokio::spawn(Self::watch_scenes(dir, self.scene_manager.clone()));
+        }
+
         // Keep service running
         tokio::signal::ctrl_c().await?;
         Ok(())
@@ -82,21 +103,46 @@ impl FirstHourService {
             tracing::info!("Preparing grid {:?}", grid);
         }
 
-        // Set up specific first hour entities
-        manager.setup_memory_grotto().await?;
-        manager.setup_weavers_landing().await?;
-        manager.setup_whisperwood_grove().await?;
+        // Set up the entities each scene script places
+        manager.setup_scenes().await?;
 
         Ok(())
     }
 
+    /// Reload the scene directory whenever a file in it is added, removed
+    /// or changed. Scenes that fail to load are logged and the running
+    /// ones kept.
+    async fn watch_scenes(dir: PathBuf, scene_manager: Arc<RwLock<FirstHourSceneManager>>) {
+        let mut seen = scenes::scene_files(&dir).ok();
+        let mut ticker = tokio::time::interval(SCENE_POLL);
+        loop {
+            ticker.tick().await;
+            let files = scenes::scene_files(&dir).ok();
+            if files == seen {
+                continue;
+            }
+            seen = files;
+            match SceneLibrary::load_dir(&dir) {
+                Ok(library) => {
+                    let count = library.scenes().len();
+                    match scene_manager.write().await.reload(library).await {
+                        Ok(()) => tracing::info!("Reloaded {} scenes from {}", count, dir.display()),
+                        Err(e) => tracing::error!("Failed to set up reloaded scenes: {}", e),
+                    }
+                }
+                Err(e) => tracing::warn!("Keeping the running scenes; {} didn't load: {:#}", dir.display(), e),
+            }
+        }
+    }
+
     async fn start_event_listeners(&self) -> anyhow::Result<()> {
         // Start Redis event listener for player actions
         let scene_manager = self.scene_manager.clone();
         let redis_client = self.redis_client.clone();
+        let channel = self.config.tenant.scoped_key("first_hour:events");
 
         tokio::spawn(async move {
-            if let Err(e) = Self::listen_for_events(redis_client, scene_manager).await {
+            if let Err(e) = Self::listen_for_events(redis_client, &channel, scene_manager).await {
                 tracing::error!("Event listener error: {}", e);
             }
         });
@@ -106,6 +152,7 @@ impl FirstHourService {
 
     async fn listen_for_events(
         redis_client: redis::Client,
+        channel: &str,
         scene_manager: Arc<RwLock<FirstHourSceneManager>>,
     ) -> anyhow::Result<()> {
         use redis::AsyncCommands;
@@ -113,7 +160,7 @@ impl FirstHourService {
         let mut con = redis_client.get_async_connection().await?;
         let mut pubsub = con.into_pubsub();
 
-        pubsub.subscribe("first_hour:events").await?;
+        pubsub.subscribe(channel).await?;
 
         let mut stream = pubsub.into_on_message();
 
@@ -1,65 +1,247 @@
 // services/first-hour/src/scenes.rs
-use finalverse_world3d::{Position3D, GridCoordinate};
-
-pub struct SceneDefinitions;
-
-impl SceneDefinitions {
-    pub fn memory_grotto_layout() -> SceneLayout {
-        SceneLayout {
-            name: "Memory Grotto".to_string(),
-            grid: GridCoordinate::new(100, 100),
-            key_positions: vec![
-                ("grotto_center", Position3D::new(128.0, 128.0, 50.0)),
-                ("crystal_north", Position3D::new(128.0, 110.0, 52.0)),
-                ("crystal_east", Position3D::new(146.0, 128.0, 52.0)),
-                ("crystal_south", Position3D::new(128.0, 146.0, 52.0)),
-                ("crystal_west", Position3D::new(110.0, 128.0, 52.0)),
-            ],
-            ambient_effects: vec![
-                ("grotto_mist", Position3D::new(128.0, 128.0, 50.0), 30.0),
-                ("light_motes", Position3D::new(128.0, 128.0, 51.0), 50.0),
-            ],
+//! First-hour scenes as data. Each scene is a YAML file naming its grid,
+//! key positions, what to spawn, dialogue beats, the player events that
+//! trigger spawns and beats, and the events that complete it. The files
+//! under `services/first-hour/scenes` are built in; point
+//! `FIRST_HOUR_SCENES` at a directory of scene files to use those instead
+//! and pick up edits while the service runs.
+
+use crate::echo_spawner::EchoType;
+use crate::interactive_objects::{InteractiveType, NPCState, ObjectState};
+use anyhow::{bail, Context};
+use finalverse_world3d::{GridCoordinate, Position3D};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const BUILTIN_SCENES: [(&str, &str); 3] = [
+    ("memory_grotto.yaml", include_str!("../scenes/memory_grotto.yaml")),
+    ("weavers_landing.yaml", include_str!("../scenes/weavers_landing.yaml")),
+    ("whisperwood_grove.yaml", include_str!("../scenes/whisperwood_grove.yaml")),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SceneScript {
+    pub id: String,
+    pub name: String,
+    pub grid: GridCoordinate,
+    #[serde(default)]
+    pub key_positions: BTreeMap<String, Position3D>,
+    #[serde(default)]
+    pub ambient_effects: Vec<AmbientEffect>,
+    #[serde(default)]
+    pub spawns: Vec<Spawn>,
+    #[serde(default)]
+    pub beats: Vec<DialogueBeat>,
+    #[serde(default)]
+    pub triggers: Vec<SceneTrigger>,
+    #[serde(default)]
+    pub completion: Completion,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AmbientEffect {
+    pub name: String,
+    pub at: Placement,
+    pub radius: f32,
+}
+
+/// A key position by name, or coordinates in the scene's grid.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Placement {
+    Named(String),
+    At(Position3D),
+}
+
+/// Something placed in the scene. Spawns a trigger names wait for it;
+/// the rest appear when the scene is set up.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Spawn {
+    pub id: String,
+    pub at: Placement,
+    #[serde(flatten)]
+    pub kind: SpawnKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpawnKind {
+    Echo { echo: EchoType },
+    Npc { name: String, state: NPCState },
+    Object { object: InteractiveType, state: ObjectState },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogueBeat {
+    pub id: String,
+    pub speaker: String,
+    pub line: String,
+}
+
+/// What happens when a player sends the `on` event.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SceneTrigger {
+    pub on: String,
+    #[serde(default)]
+    pub spawn: Vec<String>,
+    #[serde(default)]
+    pub say: Vec<String>,
+}
+
+/// The scene is complete for a player once they have sent every event in
+/// `events`. Scenes with none never complete.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Completion {
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl SceneScript {
+    pub fn parse(yaml: &str) -> anyhow::Result<Self> {
+        let scene: Self = serde_yaml::from_str(yaml)?;
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    pub fn position(&self, placement: &Placement) -> anyhow::Result<Position3D> {
+        match placement {
+            Placement::At(position) => Ok(*position),
+            Placement::Named(name) => match self.key_positions.get(name) {
+                Some(position) => Ok(*position),
+                None => bail!("scene '{}' has no key position '{}'", self.id, name),
+            },
+        }
+    }
+
+    pub fn spawn(&self, id: &str) -> Option<&Spawn> {
+        self.spawns.iter().find(|spawn| spawn.id == id)
+    }
+
+    pub fn beat(&self, id: &str) -> Option<&DialogueBeat> {
+        self.beats.iter().find(|beat| beat.id == id)
+    }
+
+    /// Whether a trigger holds this spawn back until its event.
+    pub fn is_deferred(&self, spawn_id: &str) -> bool {
+        self.triggers.iter().any(|trigger| trigger.spawn.iter().any(|id| id == spawn_id))
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut spawn_ids = HashSet::new();
+        for spawn in &self.spawns {
+            if !spawn_ids.insert(spawn.id.as_str()) {
+                bail!("scene '{}' has two spawns named '{}'", self.id, spawn.id);
+            }
+            self.position(&spawn.at)?;
+        }
+        for effect in &self.ambient_effects {
+            self.position(&effect.at)?;
         }
+        let mut beat_ids = HashSet::new();
+        for beat in &self.beats {
+            if !beat_ids.insert(beat.id.as_str()) {
+                bail!("scene '{}' has two beats named '{}'", self.id, beat.id);
+            }
+        }
+        for trigger in &self.triggers {
+            if let Some(id) = trigger.spawn.iter().find(|id| !spawn_ids.contains(id.as_str())) {
+                bail!("trigger on '{}' in scene '{}' spawns unknown '{}'", trigger.on, self.id, id);
+            }
+            if let Some(id) = trigger.say.iter().find(|id| !beat_ids.contains(id.as_str())) {
+                bail!("trigger on '{}' in scene '{}' says unknown beat '{}'", trigger.on, self.id, id);
+            }
+        }
+        Ok(())
     }
+}
 
-    pub fn weavers_landing_layout() -> SceneLayout {
-        SceneLayout {
-            name: "Weaver's Landing".to_string(),
-            grid: GridCoordinate::new(101, 101),
-            key_positions: vec![
-                ("anyas_workshop", Position3D::new(180.0, 140.0, 52.0)),
-                ("plaza_center", Position3D::new(150.0, 150.0, 51.0)),
-                ("bridge_north", Position3D::new(140.0, 120.0, 50.5)),
-                ("bridge_south", Position3D::new(160.0, 180.0, 50.5)),
-            ],
-            ambient_effects: vec![
-                ("river_sounds", Position3D::new(150.0, 100.0, 50.0), 100.0),
-                ("town_ambience", Position3D::new(150.0, 150.0, 51.0), 80.0),
-            ],
+/// Every scene the first hour runs, in the order they are set up.
+#[derive(Debug, Clone, Default)]
+pub struct SceneLibrary {
+    scenes: Vec<SceneScript>,
+}
+
+impl SceneLibrary {
+    pub fn new(scenes: Vec<SceneScript>) -> anyhow::Result<Self> {
+        let mut ids = HashSet::new();
+        if let Some(scene) = scenes.iter().find(|scene| !ids.insert(scene.id.clone())) {
+            bail!("scene '{}' is defined twice", scene.id);
         }
+        Ok(Self { scenes })
+    }
+
+    pub fn builtin() -> Self {
+        let scenes = BUILTIN_SCENES
+            .iter()
+            .map(|(file, yaml)| SceneScript::parse(yaml).unwrap_or_else(|e| panic!("built-in scene {}: {}", file, e)))
+            .collect();
+        Self::new(scenes).expect("built-in scenes have unique ids")
     }
 
-    pub fn whisperwood_grove_layout() -> SceneLayout {
-        SceneLayout {
-            name: "Whisperwood Grove".to_string(),
-            grid: GridCoordinate::new(102, 101),
-            key_positions: vec![
-                ("grove_entrance", Position3D::new(20.0, 128.0, 54.0)),
-                ("resonant_blossom", Position3D::new(210.0, 190.0, 56.0)),
-                ("ancient_tree_1", Position3D::new(100.0, 100.0, 56.0)),
-                ("ancient_tree_2", Position3D::new(150.0, 90.0, 55.5)),
-            ],
-            ambient_effects: vec![
-                ("whisperwood_fog", Position3D::new(128.0, 128.0, 55.0), 100.0),
-                ("forest_whispers", Position3D::new(150.0, 150.0, 55.0), 120.0),
-            ],
+    /// Read every `.yaml` or `.yml` file in `dir`, in file name order.
+    pub fn load_dir(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let scenes = scene_files(dir.as_ref())?
+            .into_iter()
+            .map(|(path, _)| {
+                let yaml = std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+                SceneScript::parse(&yaml).with_context(|| format!("in {}", path.display()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Self::new(scenes)
+    }
+
+    pub fn scenes(&self) -> &[SceneScript] {
+        &self.scenes
+    }
+}
+
+/// Scene files in `dir` with when each last changed, so a watcher can
+/// tell when to reload.
+pub fn scene_files(dir: &Path) -> anyhow::Result<Vec<(PathBuf, SystemTime)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml")) {
+            let modified = std::fs::metadata(&path)?.modified()?;
+            files.push((path, modified));
         }
     }
+    files.sort();
+    Ok(files)
 }
 
-pub struct SceneLayout {
-    pub name: String,
-    pub grid: GridCoordinate,
-    pub key_positions: Vec<(&'static str, Position3D)>,
-    pub ambient_effects: Vec<(&'static str, Position3D, f32)>, // (name, position, radius)
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_scenes_parse_and_bad_references_are_refused() {
+        let library = SceneLibrary::builtin();
+        let ids: Vec<_> = library.scenes().iter().map(|scene| scene.id.as_str()).collect();
+        assert_eq!(ids, vec!["memory_grotto", "weavers_landing", "whisperwood_grove"]);
+
+        let landing = &library.scenes()[1];
+        assert!(landing.is_deferred("ignis"));
+        assert!(!landing.is_deferred("anya"));
+
+        let dangling = r#"
+id: broken
+name: Broken
+grid: { x: 1, y: 1 }
+spawns:
+  - { id: lumi, kind: echo, echo: lumi, at: nowhere }
+"#;
+        assert!(SceneScript::parse(dangling).is_err());
+
+        let unknown_beat = r#"
+id: broken
+name: Broken
+grid: { x: 1, y: 1 }
+triggers:
+  - { on: arrived, say: [hello] }
+"#;
+        assert!(SceneScript::parse(unknown_beat).is_err());
+    }
+}
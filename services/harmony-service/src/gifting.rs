@@ -0,0 +1,211 @@
+// services/harmony-service/src/gifting.rs
+//! Resonance gifting between players.
+//!
+//! Gifts are capped per day for both sender and receiver, limited by the
+//! sender's attunement tier and taxed so that alt accounts cannot be used to
+//! farm resonance. Every accepted gift is kept in an audit ledger, which is
+//! persisted alongside player progress when the service has a store.
+
+use chrono::{DateTime, Utc};
+use finalverse_core::FinalverseError;
+use finalverse_events::{PlayerId, ResonanceType};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct GiftPolicy {
+    /// Minimum attunement tier required before a player may gift.
+    pub min_sender_tier: u32,
+    /// Maximum single gift at tier 1; grows by the same amount per tier.
+    pub amount_per_tier: f64,
+    /// Maximum total a player may send per UTC day.
+    pub daily_send_cap: f64,
+    /// Maximum total a player may receive per UTC day.
+    pub daily_receive_cap: f64,
+    /// Share of each gift removed from circulation.
+    pub tax_rate: f64,
+}
+
+impl Default for GiftPolicy {
+    fn default() -> Self {
+        Self {
+            min_sender_tier: 1,
+            amount_per_tier: 10.0,
+            daily_send_cap: 50.0,
+            daily_receive_cap: 75.0,
+            tax_rate: 0.2,
+        }
+    }
+}
+
+impl GiftPolicy {
+    pub fn max_gift_for_tier(&self, tier: u32) -> f64 {
+        self.amount_per_tier * tier as f64
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GiftError {
+    #[error("Gift amount must be positive")]
+    InvalidAmount,
+
+    #[error("Players cannot gift resonance to themselves")]
+    SelfGift,
+
+    #[error("Player not found: {0}")]
+    PlayerNotFound(String),
+
+    #[error("Attunement tier {tier} is below the required tier {required}")]
+    TierTooLow { tier: u32, required: u32 },
+
+    #[error("Gift of {amount} exceeds the tier limit of {limit}")]
+    ExceedsTierLimit { amount: f64, limit: f64 },
+
+    #[error("Insufficient resonance: required {required}, available {available}")]
+    InsufficientResonance { required: f64, available: f64 },
+
+    #[error("Daily {direction} cap reached: {remaining} remaining today")]
+    DailyCapExceeded { direction: &'static str, remaining: f64 },
+
+    #[error("Gift id {0} was already used by another player")]
+    IdInUse(Uuid),
+}
+
+impl From<GiftError> for FinalverseError {
+    fn from(error: GiftError) -> Self {
+        match error {
+            GiftError::PlayerNotFound(_) => FinalverseError::NotFound(error.to_string()),
+            GiftError::DailyCapExceeded { .. } => FinalverseError::RateLimited {
+                message: error.to_string(),
+                retry_after_secs: None,
+            },
+            _ => FinalverseError::BadRequest(error.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiftRecord {
+    /// Chosen by the sender's client when it has one, so a resent request
+    /// finds the gift it already made.
+    pub id: Uuid,
+    pub from: PlayerId,
+    pub to: PlayerId,
+    pub resonance_type: ResonanceType,
+    pub amount: f64,
+    pub tax: f64,
+    pub received: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Append-only audit log of accepted gifts.
+#[derive(Debug, Default)]
+pub struct GiftLedger {
+    records: Vec<GiftRecord>,
+}
+
+impl GiftLedger {
+    /// A ledger holding `records`, oldest first, as saved by earlier runs.
+    pub fn from_records(records: Vec<GiftRecord>) -> Self {
+        Self { records }
+    }
+
+    pub fn record(&mut self, record: GiftRecord) {
+        self.records.push(record);
+    }
+
+    pub fn find(&self, id: Uuid) -> Option<&GiftRecord> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    pub fn sent_today(&self, player_id: &PlayerId, now: DateTime<Utc>) -> f64 {
+        self.today(now)
+            .filter(|r| &r.from == player_id)
+            .map(|r| r.amount)
+            .sum()
+    }
+
+    pub fn received_today(&self, player_id: &PlayerId, now: DateTime<Utc>) -> f64 {
+        self.today(now)
+            .filter(|r| &r.to == player_id)
+            .map(|r| r.received)
+            .sum()
+    }
+
+    /// All gifts a player sent or received, newest first.
+    pub fn history(&self, player_id: &PlayerId) -> Vec<GiftRecord> {
+        self.records
+            .iter()
+            .rev()
+            .filter(|r| &r.from == player_id || &r.to == player_id)
+            .cloned()
+            .collect()
+    }
+
+    fn today(&self, now: DateTime<Utc>) -> impl Iterator<Item = &GiftRecord> {
+        let day = now.date_naive();
+        self.records.iter().filter(move |r| r.timestamp.date_naive() == day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HarmonyService;
+    use chrono::Duration;
+    use finalverse_events::LocalEventBus;
+    use std::sync::Arc;
+
+    fn gift(from: &PlayerId, to: &PlayerId, amount: f64, timestamp: DateTime<Utc>) -> GiftRecord {
+        GiftRecord {
+            id: Uuid::new_v4(),
+            from: from.clone(),
+            to: to.clone(),
+            resonance_type: ResonanceType::Creative,
+            amount,
+            tax: amount * 0.2,
+            received: amount * 0.8,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn daily_caps_count_only_todays_gifts() {
+        let (ana, bo) = (PlayerId::from_legacy("ana"), PlayerId::from_legacy("bo"));
+        let now = "2026-03-02T00:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let ledger = GiftLedger::from_records(vec![
+            gift(&ana, &bo, 30.0, now - Duration::hours(1)),
+            gift(&ana, &bo, 10.0, now),
+        ]);
+
+        assert_eq!(ledger.sent_today(&ana, now), 10.0);
+        assert_eq!(ledger.received_today(&bo, now), 8.0);
+        assert_eq!(ledger.sent_today(&ana, now - Duration::hours(1)), 30.0);
+        assert_eq!(ledger.sent_today(&ana, now + Duration::days(1)), 0.0);
+        assert_eq!(ledger.history(&bo).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn resending_a_gift_id_gives_only_once() {
+        let service = HarmonyService::new(Arc::new(LocalEventBus::new()));
+        let (ana, bo, cy) = (PlayerId::from_legacy("ana"), PlayerId::from_legacy("bo"), PlayerId::from_legacy("cy"));
+        service.add_resonance(ana.clone(), ResonanceType::Creative, 300.0, None).await.unwrap();
+        service.add_resonance(bo.clone(), ResonanceType::Creative, 10.0, None).await.unwrap();
+        service.add_resonance(cy.clone(), ResonanceType::Creative, 300.0, None).await.unwrap();
+
+        let id = Uuid::new_v4();
+        let first = service.gift_resonance(id, ana.clone(), bo.clone(), ResonanceType::Creative, 20.0).await.unwrap();
+        let resent = service.gift_resonance(id, ana.clone(), bo.clone(), ResonanceType::Creative, 20.0).await.unwrap();
+        assert_eq!(resent.timestamp, first.timestamp);
+        assert_eq!(service.get_progress(&ana).await.unwrap().resonance.creative, 280.0);
+        assert_eq!(service.get_progress(&bo).await.unwrap().resonance.creative, 26.0);
+        assert_eq!(service.gift_history(&bo).await.len(), 1);
+
+        assert!(matches!(
+            service.gift_resonance(id, cy.clone(), bo.clone(), ResonanceType::Creative, 20.0).await,
+            Err(GiftError::IdInUse(reused)) if reused == id
+        ));
+        assert_eq!(service.get_progress(&cy).await.unwrap().resonance.creative, 300.0);
+    }
+}
@@ -0,0 +1,182 @@
+// services/harmony-service/src/leaderboard.rs
+//! Resonance and attunement leaderboards, for the whole world and for each
+//! region. Rankings are kept sorted as resonance changes, so reading the
+//! top of a board never sorts all players.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeSet, HashMap};
+
+use finalverse_core::RegionId;
+use finalverse_events::PlayerId;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Entries returned when a request doesn't ask for a number.
+pub const DEFAULT_LEADERBOARD_SIZE: usize = 10;
+/// Most entries one request may ask for.
+pub const MAX_LEADERBOARD_SIZE: usize = 100;
+
+/// Resonance, ordered so it can sit in a sorted set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Score(f64);
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LeaderboardEntry {
+    /// From 1.
+    pub rank: usize,
+    pub player_id: PlayerId,
+    pub resonance: f64,
+    pub attunement_tier: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Leaderboard {
+    /// Everyone ranked, not just those listed.
+    pub players: usize,
+    pub top_resonance: Vec<LeaderboardEntry>,
+    pub highest_tier: Vec<LeaderboardEntry>,
+}
+
+/// One set of rankings. Ties go to the lower player id so that ranks are
+/// stable between requests.
+#[derive(Debug, Default)]
+pub struct Board {
+    players: HashMap<Uuid, (Score, u32)>,
+    by_resonance: BTreeSet<(Reverse<Score>, Uuid)>,
+    by_tier: BTreeSet<(Reverse<u32>, Reverse<Score>, Uuid)>,
+}
+
+impl Board {
+    pub fn set(&mut self, player_id: &PlayerId, resonance: f64, attunement_tier: u32) {
+        let key = player_id.0;
+        if let Some((old_score, old_tier)) = self.players.insert(key, (Score(resonance), attunement_tier)) {
+            self.by_resonance.remove(&(Reverse(old_score), key));
+            self.by_tier.remove(&(Reverse(old_tier), Reverse(old_score), key));
+        }
+        self.by_resonance.insert((Reverse(Score(resonance)), key));
+        self.by_tier.insert((Reverse(attunement_tier), Reverse(Score(resonance)), key));
+    }
+
+    pub fn resonance_of(&self, player_id: &PlayerId) -> Option<f64> {
+        self.players.get(&player_id.0).map(|(score, _)| score.0)
+    }
+
+    pub fn top(&self, limit: usize) -> Leaderboard {
+        Leaderboard {
+            players: self.players.len(),
+            top_resonance: self.entries(self.by_resonance.iter().map(|(_, player)| player), limit),
+            highest_tier: self.entries(self.by_tier.iter().map(|(_, _, player)| player), limit),
+        }
+    }
+
+    fn entries<'a>(&self, players: impl Iterator<Item = &'a Uuid>, limit: usize) -> Vec<LeaderboardEntry> {
+        players
+            .take(limit)
+            .enumerate()
+            .map(|(index, player)| {
+                let (score, tier) = self.players[player];
+                LeaderboardEntry {
+                    rank: index + 1,
+                    player_id: PlayerId(*player),
+                    resonance: score.0,
+                    attunement_tier: tier,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The world board ranks players by their total resonance; each region's
+/// board by the resonance they have gained in that region.
+#[derive(Debug, Default)]
+pub struct Leaderboards {
+    global: Board,
+    regions: HashMap<RegionId, Board>,
+}
+
+impl Leaderboards {
+    /// Record a player's new totals, with `gained` resonance earned in
+    /// `region` if the gain happened in one.
+    pub fn record(
+        &mut self,
+        player_id: &PlayerId,
+        total_resonance: f64,
+        attunement_tier: u32,
+        region: Option<(&RegionId, f64)>,
+    ) {
+        self.global.set(player_id, total_resonance, attunement_tier);
+        if let Some((region_id, gained)) = region {
+            let board = self.regions.entry(region_id.clone()).or_default();
+            let resonance = board.resonance_of(player_id).unwrap_or(0.0) + gained;
+            board.set(player_id, resonance, attunement_tier);
+        }
+    }
+
+    pub fn global(&self) -> &Board {
+        &self.global
+    }
+
+    pub fn region(&self, region_id: &RegionId) -> Option<&Board> {
+        self.regions.get(region_id)
+    }
+
+    pub fn region_count(&self) -> usize {
+        self.regions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANA: u128 = 1;
+    const BO: u128 = 2;
+    const CY: u128 = 3;
+
+    fn player(id: u128) -> PlayerId {
+        PlayerId(Uuid::from_u128(id))
+    }
+
+    fn ids(entries: &[LeaderboardEntry]) -> Vec<u128> {
+        entries.iter().map(|entry| entry.player_id.0.as_u128()).collect()
+    }
+
+    #[test]
+    fn boards_rerank_as_resonance_changes() {
+        let forest = RegionId(Uuid::from_u128(1));
+        let mut boards = Leaderboards::default();
+        boards.record(&player(ANA), 150.0, 1, Some((&forest, 150.0)));
+        boards.record(&player(BO), 90.0, 0, Some((&forest, 40.0)));
+        boards.record(&player(CY), 90.0, 0, None);
+
+        let top = boards.global().top(DEFAULT_LEADERBOARD_SIZE);
+        assert_eq!(ids(&top.top_resonance), [ANA, BO, CY]);
+        assert_eq!(top.top_resonance[2].rank, 3);
+
+        // Bo overtakes Ana everywhere, and in the forest by gains made there
+        boards.record(&player(BO), 250.0, 2, Some((&forest, 120.0)));
+        let top = boards.global().top(2);
+        assert_eq!(ids(&top.top_resonance), [BO, ANA]);
+        assert_eq!(ids(&top.highest_tier), [BO, ANA]);
+        assert_eq!(top.players, 3);
+
+        let forest_board = boards.region(&forest).unwrap().top(DEFAULT_LEADERBOARD_SIZE);
+        assert_eq!(ids(&forest_board.top_resonance), [BO, ANA]);
+        assert_eq!(forest_board.top_resonance[0].resonance, 160.0);
+        assert!(boards.region(&RegionId(Uuid::from_u128(2))).is_none());
+    }
+}
@@ -6,12 +6,25 @@ use serde::{Deserialize, Serialize};
 use warp::Filter;
 use tracing::info;
 use finalverse_logging as logging;
+use finalverse_auth::{AuthenticatedPlayer, PlayerAuth};
+use finalverse_core::warp::reject;
+use finalverse_core::{FinalverseError, RegionId};
+use finalverse_health::{DebugEndpoints, OperatorGuard};
+use finalverse_idempotency::{warp::idempotent, IdempotencyCache};
+use uuid::Uuid;
 use finalverse_events::{
-    GameEventBus, LocalEventBus, NatsEventBus,
+    GameEventBus, LocalEventBus, NatsEventBus, TenantEventBus,
     Event, EventType, HarmonyEvent, ResonanceType, PlayerId,
     PlayerEvent, EventMetadata,
 };
 
+mod gifting;
+mod leaderboard;
+mod progress_store;
+use gifting::{GiftError, GiftLedger, GiftPolicy, GiftRecord};
+use leaderboard::{Leaderboard, Leaderboards, DEFAULT_LEADERBOARD_SIZE, MAX_LEADERBOARD_SIZE};
+use progress_store::{PgProgressStore, ProgressStore};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Resonance {
     pub creative: f64,
@@ -19,6 +32,12 @@ pub struct Resonance {
     pub restoration: f64,
 }
 
+impl Resonance {
+    pub fn total(&self) -> f64 {
+        self.creative + self.exploration + self.restoration
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerProgress {
     pub player_id: PlayerId,
@@ -32,6 +51,22 @@ pub struct HarmonyService {
     player_progress: Arc<RwLock<HashMap<PlayerId, PlayerProgress>>>,
     event_bus: Arc<dyn GameEventBus>,
     subscription_ids: Arc<RwLock<Vec<String>>>,
+    gift_policy: GiftPolicy,
+    gift_ledger: Arc<RwLock<GiftLedger>>,
+    leaderboards: Arc<RwLock<Leaderboards>>,
+    store: Option<Arc<dyn ProgressStore>>,
+}
+
+/// Totals for dashboards.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSummary {
+    pub players: usize,
+    pub total_resonance: Resonance,
+    pub average_attunement_tier: f64,
+    /// Number of players at each tier.
+    pub tier_distribution: std::collections::BTreeMap<u32, usize>,
+    /// Regions where anyone has gained resonance.
+    pub active_regions: usize,
 }
 
 impl HarmonyService {
@@ -40,6 +75,50 @@ impl HarmonyService {
             player_progress: Arc::new(RwLock::new(HashMap::new())),
             event_bus,
             subscription_ids: Arc::new(RwLock::new(Vec::new())),
+            gift_policy: GiftPolicy::default(),
+            gift_ledger: Arc::new(RwLock::new(GiftLedger::default())),
+            leaderboards: Arc::new(RwLock::new(Leaderboards::default())),
+            store: None,
+        }
+    }
+
+    /// Persist progress changes to `store`. Call [`Self::load_progress`]
+    /// before serving requests to pick up what was saved last run.
+    pub fn with_store(mut self, store: Arc<dyn ProgressStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load saved progress and the gift ledger into memory. Returns how many
+    /// players were loaded.
+    pub async fn load_progress(&self) -> anyhow::Result<usize> {
+        let Some(store) = &self.store else { return Ok(0) };
+        let saved = store.load().await?;
+        *self.gift_ledger.write().await = GiftLedger::from_records(store.load_gifts().await?);
+        let mut progress_map = self.player_progress.write().await;
+        let mut leaderboards = self.leaderboards.write().await;
+        for progress in &saved {
+            progress_map.insert(progress.player_id.clone(), progress.clone());
+            leaderboards.record(&progress.player_id, progress.resonance.total(), progress.attunement_tier, None);
+        }
+        Ok(saved.len())
+    }
+
+    /// Write-through save. Failures are logged and never fail the change
+    /// that caused them; the next change to the player saves it again.
+    async fn persist(&self, progress: &PlayerProgress) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save(progress).await {
+                tracing::warn!("Failed to persist progress for player {}: {}", progress.player_id.0, e);
+            }
+        }
+    }
+
+    async fn persist_gift(&self, record: &GiftRecord) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save_gift(record).await {
+                tracing::warn!("Failed to persist gift {}: {}", record.id, e);
+            }
         }
     }
 
@@ -99,7 +178,14 @@ impl HarmonyService {
         Ok(())
     }
 
-    pub async fn add_resonance(&self, player_id: PlayerId, resonance_type: ResonanceType, amount: f64) -> anyhow::Result<()> {
+    /// Add resonance, earned in `region` if it's known where.
+    pub async fn add_resonance(
+        &self,
+        player_id: PlayerId,
+        resonance_type: ResonanceType,
+        amount: f64,
+        region: Option<RegionId>,
+    ) -> anyhow::Result<()> {
         let mut progress_map = self.player_progress.write().await;
 
         let progress = progress_map.entry(player_id.clone()).or_insert_with(|| {
@@ -136,7 +222,7 @@ impl HarmonyService {
         self.event_bus.publish(event).await?;
 
         // Check for attunement tier upgrade
-        let total_resonance = progress.resonance.creative + progress.resonance.exploration + progress.resonance.restoration;
+        let total_resonance = progress.resonance.total();
         let new_tier = (total_resonance / 100.0) as u32;
 
         if new_tier > progress.attunement_tier {
@@ -161,6 +247,17 @@ impl HarmonyService {
             self.unlock_tier_abilities(progress, new_tier).await?;
         }
 
+        self.leaderboards.write().await.record(
+            &player_id,
+            total_resonance,
+            progress.attunement_tier,
+            region.as_ref().map(|region| (region, amount)),
+        );
+
+        let saved = progress.clone();
+        drop(progress_map);
+        self.persist(&saved).await;
+
         Ok(())
     }
 
@@ -222,10 +319,178 @@ impl HarmonyService {
         Ok(())
     }
 
+    /// Transfer resonance from one player to another, applying tier limits,
+    /// daily caps and the gifting tax. Sending a gift `id` again returns the
+    /// gift already made with it instead of giving twice.
+    pub async fn gift_resonance(
+        &self,
+        id: Uuid,
+        from: PlayerId,
+        to: PlayerId,
+        resonance_type: ResonanceType,
+        amount: f64,
+    ) -> Result<GiftRecord, GiftError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(GiftError::InvalidAmount);
+        }
+        if from == to {
+            return Err(GiftError::SelfGift);
+        }
+
+        let policy = &self.gift_policy;
+        let now = chrono::Utc::now();
+        let mut progress_map = self.player_progress.write().await;
+        let mut ledger = self.gift_ledger.write().await;
+
+        if let Some(existing) = ledger.find(id) {
+            return if existing.from == from {
+                Ok(existing.clone())
+            } else {
+                Err(GiftError::IdInUse(id))
+            };
+        }
+        if !progress_map.contains_key(&to) {
+            return Err(GiftError::PlayerNotFound(to.to_string()));
+        }
+        let sender = progress_map
+            .get_mut(&from)
+            .ok_or_else(|| GiftError::PlayerNotFound(from.to_string()))?;
+
+        if sender.attunement_tier < policy.min_sender_tier {
+            return Err(GiftError::TierTooLow {
+                tier: sender.attunement_tier,
+                required: policy.min_sender_tier,
+            });
+        }
+
+        let limit = policy.max_gift_for_tier(sender.attunement_tier);
+        if amount > limit {
+            return Err(GiftError::ExceedsTierLimit { amount, limit });
+        }
+
+        let sent_remaining = policy.daily_send_cap - ledger.sent_today(&from, now);
+        if amount > sent_remaining {
+            return Err(GiftError::DailyCapExceeded {
+                direction: "send",
+                remaining: sent_remaining.max(0.0),
+            });
+        }
+
+        let tax = amount * policy.tax_rate;
+        let received = amount - tax;
+        let receive_remaining = policy.daily_receive_cap - ledger.received_today(&to, now);
+        if received > receive_remaining {
+            return Err(GiftError::DailyCapExceeded {
+                direction: "receive",
+                remaining: receive_remaining.max(0.0),
+            });
+        }
+
+        let balance = resonance_slot(&mut sender.resonance, &resonance_type);
+        if *balance < amount {
+            return Err(GiftError::InsufficientResonance {
+                required: amount,
+                available: *balance,
+            });
+        }
+        *balance -= amount;
+
+        let sender = sender.clone();
+        let receiver = progress_map.get_mut(&to).map(|receiver| {
+            *resonance_slot(&mut receiver.resonance, &resonance_type) += received;
+            receiver.clone()
+        });
+
+        let record = GiftRecord {
+            id,
+            from: from.clone(),
+            to: to.clone(),
+            resonance_type: resonance_type.clone(),
+            amount,
+            tax,
+            received,
+            timestamp: now,
+        };
+        ledger.record(record.clone());
+        drop(ledger);
+        drop(progress_map);
+
+        let mut leaderboards = self.leaderboards.write().await;
+        for progress in std::iter::once(&sender).chain(&receiver) {
+            leaderboards.record(&progress.player_id, progress.resonance.total(), progress.attunement_tier, None);
+        }
+        drop(leaderboards);
+
+        self.persist(&sender).await;
+        if let Some(receiver) = &receiver {
+            self.persist(receiver).await;
+        }
+        self.persist_gift(&record).await;
+
+        info!("🎁 Player {} gifted {:.1} resonance to {} ({:.1} taxed)", from.0, amount, to.0, tax);
+
+        let event = Event::new(EventType::Harmony(HarmonyEvent::ResonanceGifted {
+            from,
+            to,
+            resonance_type,
+            amount,
+            received,
+        })).with_metadata(EventMetadata {
+            source: Some("harmony-service".to_string()),
+            tags: vec!["generosity".to_string()],
+            ..Default::default()
+        });
+
+        if let Err(e) = self.event_bus.publish(event).await {
+            tracing::warn!("Failed to publish gift event: {}", e);
+        }
+
+        Ok(record)
+    }
+
+    pub async fn gift_history(&self, player_id: &PlayerId) -> Vec<GiftRecord> {
+        self.gift_ledger.read().await.history(player_id)
+    }
+
     pub async fn get_progress(&self, player_id: &PlayerId) -> Option<PlayerProgress> {
         self.player_progress.read().await.get(player_id).cloned()
     }
 
+    /// The world's leaderboard, or a region's. None for a region where
+    /// nobody has gained resonance.
+    pub async fn leaderboard(&self, region: Option<&RegionId>, limit: usize) -> Option<Leaderboard> {
+        let leaderboards = self.leaderboards.read().await;
+        match region {
+            Some(region) => leaderboards.region(region).map(|board| board.top(limit)),
+            None => Some(leaderboards.global().top(limit)),
+        }
+    }
+
+    pub async fn stats_summary(&self) -> StatsSummary {
+        let progress_map = self.player_progress.read().await;
+        let mut total_resonance = Resonance {
+            creative: 0.0,
+            exploration: 0.0,
+            restoration: 0.0,
+        };
+        let mut tier_distribution = std::collections::BTreeMap::new();
+        for progress in progress_map.values() {
+            total_resonance.creative += progress.resonance.creative;
+            total_resonance.exploration += progress.resonance.exploration;
+            total_resonance.restoration += progress.resonance.restoration;
+            *tier_distribution.entry(progress.attunement_tier).or_insert(0) += 1;
+        }
+        let players = progress_map.len();
+        let tier_sum: u64 = progress_map.values().map(|progress| progress.attunement_tier as u64).sum();
+        StatsSummary {
+            players,
+            total_resonance,
+            average_attunement_tier: if players == 0 { 0.0 } else { tier_sum as f64 / players as f64 },
+            tier_distribution,
+            active_regions: self.leaderboards.read().await.region_count(),
+        }
+    }
+
     pub async fn shutdown(&self) -> anyhow::Result<()> {
         // Unsubscribe from all events
         let sub_ids = self.subscription_ids.read().await;
@@ -236,24 +501,89 @@ impl HarmonyService {
     }
 }
 
+fn resonance_slot<'a>(resonance: &'a mut Resonance, resonance_type: &ResonanceType) -> &'a mut f64 {
+    match resonance_type {
+        ResonanceType::Creative => &mut resonance.creative,
+        ResonanceType::Exploration => &mut resonance.exploration,
+        ResonanceType::Restoration => &mut resonance.restoration,
+    }
+}
+
 // HTTP API handlers
+#[derive(Debug, Deserialize)]
+struct GiftRequest {
+    /// Lets clients resend a gift safely; a fresh id is used when absent.
+    #[serde(default)]
+    id: Option<Uuid>,
+    to: PlayerId,
+    resonance_type: String,
+    amount: f64,
+}
+
+fn parse_resonance_type(value: &str) -> Option<ResonanceType> {
+    match value {
+        "creative" => Some(ResonanceType::Creative),
+        "exploration" => Some(ResonanceType::Exploration),
+        "restoration" => Some(ResonanceType::Restoration),
+        _ => None,
+    }
+}
+
+/// Gifts always come from the authenticated player.
+async fn gift_handler(
+    player: AuthenticatedPlayer,
+    request: GiftRequest,
+    service: Arc<HarmonyService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let Some(resonance_type) = parse_resonance_type(&request.resonance_type) else {
+        return Err(reject(FinalverseError::BadRequest("Invalid resonance type".to_string())));
+    };
+
+    match service
+        .gift_resonance(
+            request.id.unwrap_or_else(Uuid::new_v4),
+            player.player_id,
+            request.to,
+            resonance_type,
+            request.amount,
+        )
+        .await
+    {
+        Ok(record) => Ok(warp::reply::json(&record)),
+        Err(e) => Err(reject(e.into())),
+    }
+}
+
+async fn gift_history_handler(
+    player_id: PlayerId,
+    service: Arc<HarmonyService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&service.gift_history(&player_id).await))
+}
+
+#[derive(Debug, Deserialize)]
+struct ResonanceQuery {
+    /// Region the resonance was earned in.
+    region: Option<Uuid>,
+}
+
 async fn add_resonance_handler(
-    player_id: String,
+    player_id: PlayerId,
     resonance_type: String,
     amount: f64,
+    query: ResonanceQuery,
     service: Arc<HarmonyService>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let resonance_type = match resonance_type.as_str() {
-        "creative" => ResonanceType::Creative,
-        "exploration" => ResonanceType::Exploration,
-        "restoration" => ResonanceType::Restoration,
-        _ => return Ok(warp::reply::with_status(
+    let resonance_type = match parse_resonance_type(&resonance_type) {
+        Some(resonance_type) => resonance_type,
+        None => return Ok(warp::reply::with_status(
             warp::reply::json(&serde_json::json!({"error": "Invalid resonance type"})),
             warp::http::StatusCode::BAD_REQUEST,
         )),
     };
 
-    match service.add_resonance(PlayerId(player_id), resonance_type, amount).await {
+    let region = query.region.map(RegionId);
+    match service.add_resonance(player_id, resonance_type, amount, region).await {
         Ok(_) => Ok(warp::reply::with_status(
             warp::reply::json(&serde_json::json!({"success": true})),
             warp::http::StatusCode::OK,
@@ -266,16 +596,51 @@ async fn add_resonance_handler(
 }
 
 async fn get_progress_handler(
-    player_id: String,
+    player_id: PlayerId,
     service: Arc<HarmonyService>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    if let Some(progress) = service.get_progress(&PlayerId(player_id)).await {
+    if let Some(progress) = service.get_progress(&player_id).await {
         Ok(warp::reply::json(&progress))
     } else {
         Ok(warp::reply::json(&serde_json::json!({"error": "Player not found"})))
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct LeaderboardQuery {
+    limit: Option<usize>,
+}
+
+impl LeaderboardQuery {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LEADERBOARD_SIZE).min(MAX_LEADERBOARD_SIZE)
+    }
+}
+
+async fn leaderboard_handler(
+    query: LeaderboardQuery,
+    service: Arc<HarmonyService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&service.leaderboard(None, query.limit()).await))
+}
+
+async fn region_leaderboard_handler(
+    region_id: Uuid,
+    query: LeaderboardQuery,
+    service: Arc<HarmonyService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.leaderboard(Some(&RegionId(region_id)), query.limit()).await {
+        Some(leaderboard) => Ok(warp::reply::json(&leaderboard)),
+        None => Err(reject(FinalverseError::NotFound(
+            "No resonance has been gained in this region".to_string(),
+        ))),
+    }
+}
+
+async fn stats_summary_handler(service: Arc<HarmonyService>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&service.stats_summary().await))
+}
+
 async fn health_handler() -> Result<impl warp::Reply, warp::Rejection> {
     Ok(warp::reply::json(&serde_json::json!({
         "status": "healthy",
@@ -287,6 +652,8 @@ async fn health_handler() -> Result<impl warp::Reply, warp::Rejection> {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     logging::init(None);
+    // Mirror world-engine's clock so events carry world time
+    finalverse_core::world_clock::start_from_env();
 
     // Initialize event bus - use NATS if URL provided, otherwise use local
     let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
@@ -296,9 +663,20 @@ async fn main() -> anyhow::Result<()> {
         info!("📦 Using local event bus (no NATS_URL provided)");
         Arc::new(LocalEventBus::new())
     };
-
-    // Create service
-    let service = Arc::new(HarmonyService::new(event_bus));
+    let event_bus = TenantEventBus::from_env(event_bus);
+
+    // Create service, persisting progress to PostgreSQL if DATABASE_URL provided
+    let mut service = HarmonyService::new(event_bus.clone());
+    if let Ok(database_url) = std::env::var("DATABASE_URL") {
+        info!("🗄️ Persisting player progress to PostgreSQL");
+        let store = PgProgressStore::connect(&database_url, finalverse_core::TenantId::from_env()).await?;
+        service = service.with_store(Arc::new(store));
+    }
+    let loaded = service.load_progress().await?;
+    if loaded > 0 {
+        info!("📥 Loaded progress for {} players", loaded);
+    }
+    let service = Arc::new(service);
 
     // Start event listeners
     service.start_event_listeners().await?;
@@ -307,23 +685,71 @@ async fn main() -> anyhow::Result<()> {
     let service_clone = service.clone();
     let service_filter = warp::any().map(move || service_clone.clone());
 
-    let add_resonance = warp::path!("resonance" / String / String / f64)
+    // Grants name their player, so only services may make them. Retried
+    // grants carrying the same Idempotency-Key are only added once
+    let add_resonance = idempotent(
+        IdempotencyCache::default(),
+        warp::path!("resonance" / PlayerId / String / f64)
+            .and(warp::post())
+            .and(OperatorGuard::load().warp_filter())
+            .and(warp::query::<ResonanceQuery>())
+            .and(service_filter.clone())
+            .and_then(add_resonance_handler),
+    );
+
+    let get_progress = warp::path!("progress" / PlayerId)
+        .and(warp::get())
+        .and(service_filter.clone())
+        .and_then(get_progress_handler);
+
+    let auth = PlayerAuth::load();
+
+    let gift = warp::path!("gift")
         .and(warp::post())
+        .and(finalverse_auth::warp::player(auth))
+        .and(warp::body::json())
         .and(service_filter.clone())
-        .and_then(add_resonance_handler);
+        .and_then(gift_handler);
 
-    let get_progress = warp::path!("progress" / String)
+    let gift_history = warp::path!("gift" / "history" / PlayerId)
         .and(warp::get())
         .and(service_filter.clone())
-        .and_then(get_progress_handler);
+        .and_then(gift_history_handler);
+
+    let leaderboard = warp::path!("leaderboards")
+        .and(warp::get())
+        .and(warp::query::<LeaderboardQuery>())
+        .and(service_filter.clone())
+        .and_then(leaderboard_handler);
+
+    let region_leaderboard = warp::path!("leaderboards" / "regions" / Uuid)
+        .and(warp::get())
+        .and(warp::query::<LeaderboardQuery>())
+        .and(service_filter.clone())
+        .and_then(region_leaderboard_handler);
+
+    let stats_summary = warp::path!("stats" / "summary")
+        .and(warp::get())
+        .and(service_filter.clone())
+        .and_then(stats_summary_handler);
 
     let health = warp::path!("health")
         .and(warp::get())
         .and_then(health_handler);
 
+    let debug = Arc::new(DebugEndpoints::load("harmony-service").with_event_bus(event_bus)).warp_routes();
+
     let routes = add_resonance
         .or(get_progress)
-        .or(health);
+        .or(gift)
+        .or(gift_history)
+        .or(leaderboard)
+        .or(region_leaderboard)
+        .or(stats_summary)
+        .or(health)
+        .or(debug)
+        .recover(finalverse_auth::warp::recover)
+        .recover(finalverse_core::warp::recover);
 
     // Handle shutdown gracefully
     let service_shutdown = service.clone();
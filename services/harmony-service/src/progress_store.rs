@@ -0,0 +1,237 @@
+// services/harmony-service/src/progress_store.rs
+// Durable player progress, so a restart doesn't wipe resonance and tiers
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use finalverse_core::TenantId;
+use finalverse_events::{PlayerId, ResonanceType};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use uuid::Uuid;
+
+use crate::gifting::GiftRecord;
+use crate::{parse_resonance_type, PlayerProgress, Resonance};
+
+/// Write-through persistence for [`crate::HarmonyService`]. The in-memory
+/// map stays authoritative while the service runs; the store is read once
+/// at startup.
+#[async_trait]
+pub trait ProgressStore: Send + Sync {
+    async fn load(&self) -> anyhow::Result<Vec<PlayerProgress>>;
+
+    async fn save(&self, progress: &PlayerProgress) -> anyhow::Result<()>;
+
+    /// Every gift saved so far, oldest first.
+    async fn load_gifts(&self) -> anyhow::Result<Vec<GiftRecord>>;
+
+    async fn save_gift(&self, record: &GiftRecord) -> anyhow::Result<()>;
+}
+
+/// Rows in `harmony_progress`, scoped to the tenant this process serves.
+pub struct PgProgressStore {
+    pool: PgPool,
+    tenant: TenantId,
+}
+
+type ProgressRow = (String, f64, f64, f64, i32, Vec<String>, Vec<String>);
+
+type GiftRow = (Uuid, String, String, String, f64, f64, f64, DateTime<Utc>);
+
+fn resonance_type_name(resonance_type: &ResonanceType) -> &'static str {
+    match resonance_type {
+        ResonanceType::Creative => "creative",
+        ResonanceType::Exploration => "exploration",
+        ResonanceType::Restoration => "restoration",
+    }
+}
+
+impl PgProgressStore {
+    pub async fn connect(database_url: &str, tenant: TenantId) -> anyhow::Result<Self> {
+        let pool = PgPoolOptions::new().max_connections(5).connect(database_url).await?;
+        Ok(Self { pool, tenant })
+    }
+}
+
+#[async_trait]
+impl ProgressStore for PgProgressStore {
+    async fn load(&self) -> anyhow::Result<Vec<PlayerProgress>> {
+        let rows: Vec<ProgressRow> = sqlx::query_as(
+            "SELECT player_id, creative_resonance, exploration_resonance, restoration_resonance, \
+             attunement_tier, unlocked_melodies, unlocked_harmonies \
+             FROM harmony_progress WHERE tenant_id = $1",
+        )
+        .bind(self.tenant.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(player_id, creative, exploration, restoration, tier, unlocked_melodies, unlocked_harmonies)| {
+                    PlayerProgress {
+                        player_id: PlayerId::from_legacy(&player_id),
+                        resonance: Resonance {
+                            creative,
+                            exploration,
+                            restoration,
+                        },
+                        attunement_tier: tier.max(0) as u32,
+                        unlocked_melodies,
+                        unlocked_harmonies,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    async fn save(&self, progress: &PlayerProgress) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO harmony_progress (tenant_id, player_id, creative_resonance, exploration_resonance, \
+             restoration_resonance, attunement_tier, unlocked_melodies, unlocked_harmonies) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+             ON CONFLICT (tenant_id, player_id) DO UPDATE SET \
+             creative_resonance = EXCLUDED.creative_resonance, \
+             exploration_resonance = EXCLUDED.exploration_resonance, \
+             restoration_resonance = EXCLUDED.restoration_resonance, \
+             attunement_tier = EXCLUDED.attunement_tier, \
+             unlocked_melodies = EXCLUDED.unlocked_melodies, \
+             unlocked_harmonies = EXCLUDED.unlocked_harmonies",
+        )
+        .bind(self.tenant.as_str())
+        .bind(progress.player_id.to_string())
+        .bind(progress.resonance.creative)
+        .bind(progress.resonance.exploration)
+        .bind(progress.resonance.restoration)
+        .bind(progress.attunement_tier as i32)
+        .bind(&progress.unlocked_melodies)
+        .bind(&progress.unlocked_harmonies)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn load_gifts(&self) -> anyhow::Result<Vec<GiftRecord>> {
+        let rows: Vec<GiftRow> = sqlx::query_as(
+            "SELECT id, from_player_id, to_player_id, resonance_type, amount, tax, received, given_at \
+             FROM harmony_gifts WHERE tenant_id = $1 ORDER BY given_at",
+        )
+        .bind(self.tenant.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(id, from, to, resonance_type, amount, tax, received, timestamp)| {
+                Ok(GiftRecord {
+                    id,
+                    from: PlayerId::from_legacy(&from),
+                    to: PlayerId::from_legacy(&to),
+                    resonance_type: parse_resonance_type(&resonance_type)
+                        .ok_or_else(|| anyhow::anyhow!("gift {} has unknown resonance type {}", id, resonance_type))?,
+                    amount,
+                    tax,
+                    received,
+                    timestamp,
+                })
+            })
+            .collect()
+    }
+
+    async fn save_gift(&self, record: &GiftRecord) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO harmony_gifts (tenant_id, id, from_player_id, to_player_id, resonance_type, \
+             amount, tax, received, given_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+             ON CONFLICT (tenant_id, id) DO NOTHING",
+        )
+        .bind(self.tenant.as_str())
+        .bind(record.id)
+        .bind(record.from.to_string())
+        .bind(record.to.to_string())
+        .bind(resonance_type_name(&record.resonance_type))
+        .bind(record.amount)
+        .bind(record.tax)
+        .bind(record.received)
+        .bind(record.timestamp)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HarmonyService;
+    use crate::gifting::GiftError;
+    use finalverse_events::LocalEventBus;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct MemoryStore {
+        progress: Mutex<HashMap<PlayerId, PlayerProgress>>,
+        gifts: Mutex<Vec<GiftRecord>>,
+    }
+
+    #[async_trait]
+    impl ProgressStore for MemoryStore {
+        async fn load(&self) -> anyhow::Result<Vec<PlayerProgress>> {
+            Ok(self.progress.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn save(&self, progress: &PlayerProgress) -> anyhow::Result<()> {
+            self.progress.lock().unwrap().insert(progress.player_id.clone(), progress.clone());
+            Ok(())
+        }
+
+        async fn load_gifts(&self) -> anyhow::Result<Vec<GiftRecord>> {
+            Ok(self.gifts.lock().unwrap().clone())
+        }
+
+        async fn save_gift(&self, record: &GiftRecord) -> anyhow::Result<()> {
+            self.gifts.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn restarted_service_keeps_resonance_and_tiers() {
+        let store = Arc::new(MemoryStore::default());
+        let service = HarmonyService::new(Arc::new(LocalEventBus::new())).with_store(store.clone());
+        let player = PlayerId::from_legacy("ayla");
+        service.add_resonance(player.clone(), ResonanceType::Creative, 80.0, None).await.unwrap();
+        service.add_resonance(player.clone(), ResonanceType::Restoration, 40.0, None).await.unwrap();
+        drop(service);
+
+        let restarted = HarmonyService::new(Arc::new(LocalEventBus::new())).with_store(store);
+        assert_eq!(restarted.load_progress().await.unwrap(), 1);
+        let progress = restarted.get_progress(&player).await.unwrap();
+        assert_eq!(progress.resonance.creative, 80.0);
+        assert_eq!(progress.resonance.restoration, 40.0);
+        assert_eq!(progress.attunement_tier, 1);
+        assert!(progress.unlocked_melodies.contains(&"Melody of Healing".to_string()));
+    }
+
+    #[tokio::test]
+    async fn restarted_service_keeps_gifts_toward_daily_caps() {
+        let store = Arc::new(MemoryStore::default());
+        let service = HarmonyService::new(Arc::new(LocalEventBus::new())).with_store(store.clone());
+        let (ana, bo) = (PlayerId::from_legacy("ana"), PlayerId::from_legacy("bo"));
+        service.add_resonance(ana.clone(), ResonanceType::Creative, 600.0, None).await.unwrap();
+        service.add_resonance(bo.clone(), ResonanceType::Creative, 10.0, None).await.unwrap();
+        for _ in 0..2 {
+            service
+                .gift_resonance(Uuid::new_v4(), ana.clone(), bo.clone(), ResonanceType::Creative, 25.0)
+                .await
+                .unwrap();
+        }
+        drop(service);
+
+        let restarted = HarmonyService::new(Arc::new(LocalEventBus::new())).with_store(store);
+        restarted.load_progress().await.unwrap();
+        assert_eq!(restarted.gift_history(&ana).await.len(), 2);
+        assert!(matches!(
+            restarted.gift_resonance(Uuid::new_v4(), ana, bo, ResonanceType::Creative, 5.0).await,
+            Err(GiftError::DailyCapExceeded { direction: "send", .. })
+        ));
+    }
+}
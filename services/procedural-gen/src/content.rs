@@ -0,0 +1,409 @@
+// services/procedural-gen/src/content.rs
+//! Seeded content generators: NPC and settlement names, flora and fauna
+//! descriptors, and dungeon layouts. Every generator is a pure function of
+//! its seed, and mixing a grid coordinate into the seed gives each grid its
+//! own content, so world-engine can regenerate a grid and get it back
+//! exactly as before.
+
+use finalverse_world3d::GridCoordinate;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::Serialize;
+use thiserror::Error;
+
+/// Upper bound on names or descriptors returned by one request.
+pub const MAX_COUNT: usize = 100;
+
+pub const MIN_DUNGEON_SIZE: u32 = 16;
+pub const MAX_DUNGEON_SIZE: u32 = 128;
+pub const MAX_DUNGEON_ROOMS: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum ContentError {
+    #[error("Invalid request: {0}")]
+    Invalid(String),
+}
+
+/// The seed for content at `grid`; the world seed itself when there is no
+/// grid. Neighbouring grids get unrelated seeds.
+pub fn grid_seed(seed: u64, grid: Option<GridCoordinate>) -> u64 {
+    let Some(grid) = grid else {
+        return seed;
+    };
+    // SplitMix64 finalizer over the seed and both coordinates
+    let mut z = seed ^ ((grid.x as u32 as u64) << 32 | grid.y as u32 as u64);
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn check_count(count: usize) -> Result<(), ContentError> {
+    if count == 0 || count > MAX_COUNT {
+        return Err(ContentError::Invalid(format!("count must be 1-{}", MAX_COUNT)));
+    }
+    Ok(())
+}
+
+fn pick<'a>(rng: &mut StdRng, options: &[&'a str]) -> &'a str {
+    options.choose(rng).copied().unwrap_or_default()
+}
+
+const NAME_ONSETS: [&str; 16] = [
+    "Ae", "Bra", "Cal", "Dor", "El", "Fen", "Gwy", "Hal", "Is", "Ka", "Lir", "Mae", "Nor", "Ori", "Sel", "Tam",
+];
+const NAME_MIDDLES: [&str; 10] = ["", "", "", "a", "e", "i", "ra", "lo", "ve", "th"];
+const NAME_ENDINGS: [&str; 14] = [
+    "n", "ra", "wyn", "dor", "lia", "ric", "ssa", "mir", "th", "el", "os", "ne", "ys", "an",
+];
+const FAMILY_FIRSTS: [&str; 12] = [
+    "Wind", "Star", "Ash", "Silver", "Thorn", "Echo", "River", "Dawn", "Stone", "Lark", "Moss", "Ember",
+];
+const FAMILY_SECONDS: [&str; 12] = [
+    "song", "weaver", "hollow", "brook", "mantle", "field", "whisper", "ward", "vale", "reed", "light", "croft",
+];
+
+fn given_name(rng: &mut StdRng) -> String {
+    format!(
+        "{}{}{}",
+        pick(rng, &NAME_ONSETS),
+        pick(rng, &NAME_MIDDLES),
+        pick(rng, &NAME_ENDINGS)
+    )
+}
+
+/// Full NPC names, given name then family name.
+pub fn npc_names(seed: u64, count: usize) -> Result<Vec<String>, ContentError> {
+    check_count(count)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    Ok((0..count)
+        .map(|_| {
+            let given = given_name(&mut rng);
+            format!("{} {}{}", given, pick(&mut rng, &FAMILY_FIRSTS), pick(&mut rng, &FAMILY_SECONDS))
+        })
+        .collect())
+}
+
+const SETTLEMENT_ROOTS: [&str; 14] = [
+    "Harmony", "Whisper", "Amber", "Willow", "Lantern", "Silent", "Crystal", "Hollow", "Bright", "Mist", "Echo",
+    "Gilded", "Thistle", "Cinder",
+];
+const SETTLEMENT_SUFFIXES: [&str; 12] = [
+    "'s Rest", "ford", "haven", "mere", "stead", " Crossing", "wick", " Landing", "holt", "fall", "brook", " Reach",
+];
+
+/// Settlement names, either compounds like "Willowmere" or named for a
+/// founder, like "Calwyn's Landing".
+pub fn settlement_names(seed: u64, count: usize) -> Result<Vec<String>, ContentError> {
+    check_count(count)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    Ok((0..count)
+        .map(|_| {
+            if rng.gen_bool(0.25) {
+                let founder = given_name(&mut rng);
+                let place = pick(&mut rng, &["Landing", "Rest", "Hollow", "Watch", "Reach"]);
+                format!("{}'s {}", founder, place)
+            } else {
+                format!("{}{}", pick(&mut rng, &SETTLEMENT_ROOTS), pick(&mut rng, &SETTLEMENT_SUFFIXES))
+            }
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DescriptorKind {
+    Flora,
+    Fauna,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Descriptor {
+    pub kind: DescriptorKind,
+    pub name: String,
+    pub description: String,
+    pub habitat: String,
+    /// 0.0..=1.0, how strongly it resonates with the Song.
+    pub resonance: f32,
+}
+
+const FLORA_PREFIXES: [&str; 10] = [
+    "Whisper", "Dusk", "Glimmer", "Hush", "Chime", "Frost", "Ember", "Moon", "Lull", "Sorrow",
+];
+const FLORA_FORMS: [&str; 8] = ["bloom", "fern", "vine", "cap", "reed", "moss", "thorn", "willow"];
+const FLORA_TRAITS: [&str; 8] = [
+    "petals that ring faintly in the wind",
+    "leaves that fold shut when the Silence is near",
+    "a soft glow that brightens with nearby melodies",
+    "roots that hum underground",
+    "sap that tastes of honey and rain",
+    "seed pods that drift like lanterns",
+    "bark etched with spiral patterns",
+    "blossoms that open only at dusk",
+];
+
+const FAUNA_PREFIXES: [&str; 10] = [
+    "Lyre", "Glass", "Thrum", "Shade", "Quill", "Hollow", "Dapple", "Ash", "Tide", "Gloam",
+];
+const FAUNA_FORMS: [&str; 8] = ["hart", "finch", "moth", "stag", "otter", "wyrm", "hound", "heron"];
+const FAUNA_TRAITS: [&str; 8] = [
+    "answers songs with a call of its own",
+    "hides from anything carrying the Silence",
+    "moves in small, watchful herds",
+    "sheds feathers that chime when they land",
+    "is drawn to places of old harmony",
+    "hunts only by moonlight",
+    "weaves its nest from fallen melody-threads",
+    "is said to remember every traveller it meets",
+];
+
+const HABITATS: [&str; 8] = [
+    "forest glades",
+    "crystal caverns",
+    "riverbanks",
+    "highland meadows",
+    "ruined courtyards",
+    "coastal cliffs",
+    "ashen hollows",
+    "starlit marshes",
+];
+
+fn descriptor(rng: &mut StdRng, kind: DescriptorKind) -> Descriptor {
+    let (prefixes, forms, traits): (&[&str], &[&str], &[&str]) = match kind {
+        DescriptorKind::Flora => (&FLORA_PREFIXES, &FLORA_FORMS, &FLORA_TRAITS),
+        DescriptorKind::Fauna => (&FAUNA_PREFIXES, &FAUNA_FORMS, &FAUNA_TRAITS),
+    };
+    let prefix = pick(rng, prefixes);
+    let form = pick(rng, forms);
+    let habitat = pick(rng, &HABITATS);
+    // Flora traits are noun phrases, fauna traits are verb phrases
+    let joiner = match kind {
+        DescriptorKind::Flora => "with",
+        DescriptorKind::Fauna => "that",
+    };
+    let description = format!("A {} of the {} {} {}.", form, habitat, joiner, pick(rng, traits));
+    Descriptor {
+        kind,
+        name: format!("{}{}", prefix, form),
+        description,
+        habitat: habitat.to_string(),
+        resonance: (rng.gen_range(0.0f32..=1.0) * 100.0).round() / 100.0,
+    }
+}
+
+pub fn descriptors(seed: u64, kind: DescriptorKind, count: usize) -> Result<Vec<Descriptor>, ContentError> {
+    check_count(count)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    Ok((0..count).map(|_| descriptor(&mut rng, kind)).collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomKind {
+    Entrance,
+    Chamber,
+    Treasure,
+    /// The furthest room from the entrance, where the Silence is strongest.
+    Heart,
+}
+
+/// A rectangle of floor tiles; `x` and `y` are its top-left corner.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Room {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub kind: RoomKind,
+}
+
+impl Room {
+    fn center(&self) -> (u32, u32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Overlap test with a one-tile margin so rooms never share walls.
+    fn touches(&self, other: &Room) -> bool {
+        self.x <= other.x + other.width
+            && other.x <= self.x + self.width
+            && self.y <= other.y + other.height
+            && other.y <= self.y + self.height
+    }
+}
+
+/// An L-shaped passage between two rooms, by index into `rooms`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Corridor {
+    pub from: usize,
+    pub to: usize,
+    /// Tiles walked from the centre of `from` to the centre of `to`.
+    pub path: Vec<(u32, u32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DungeonLayout {
+    pub seed: u64,
+    pub width: u32,
+    pub height: u32,
+    pub rooms: Vec<Room>,
+    pub corridors: Vec<Corridor>,
+    /// One string per row: `#` is wall, `.` is floor.
+    pub tiles: Vec<String>,
+}
+
+/// Scatters up to `max_rooms` non-overlapping rooms over a `size` by `size`
+/// map and joins them in placement order, so every room is reachable from
+/// the entrance.
+pub fn dungeon_layout(seed: u64, size: u32, max_rooms: usize) -> Result<DungeonLayout, ContentError> {
+    if !(MIN_DUNGEON_SIZE..=MAX_DUNGEON_SIZE).contains(&size) {
+        return Err(ContentError::Invalid(format!(
+            "size must be {}-{}",
+            MIN_DUNGEON_SIZE, MAX_DUNGEON_SIZE
+        )));
+    }
+    if !(2..=MAX_DUNGEON_ROOMS).contains(&max_rooms) {
+        return Err(ContentError::Invalid(format!("rooms must be 2-{}", MAX_DUNGEON_ROOMS)));
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let max_side = (size / 4).max(4);
+    let mut rooms: Vec<Room> = Vec::new();
+    for _ in 0..max_rooms * 8 {
+        if rooms.len() == max_rooms {
+            break;
+        }
+        let width = rng.gen_range(3..=max_side);
+        let height = rng.gen_range(3..=max_side);
+        // Keep a wall border around the whole map
+        let room = Room {
+            x: rng.gen_range(1..size - width),
+            y: rng.gen_range(1..size - height),
+            width,
+            height,
+            kind: RoomKind::Chamber,
+        };
+        if !rooms.iter().any(|placed| placed.touches(&room)) {
+            rooms.push(room);
+        }
+    }
+
+    let corridors: Vec<Corridor> = (1..rooms.len())
+        .map(|to| Corridor {
+            from: to - 1,
+            to,
+            path: corridor_path(&mut rng, rooms[to - 1].center(), rooms[to].center()),
+        })
+        .collect();
+
+    if let Some(first) = rooms.first_mut() {
+        first.kind = RoomKind::Entrance;
+    }
+    if rooms.len() > 1 {
+        let entrance = rooms[0].center();
+        let heart = (1..rooms.len())
+            .max_by_key(|&i| {
+                let (x, y) = rooms[i].center();
+                x.abs_diff(entrance.0) + y.abs_diff(entrance.1)
+            })
+            .unwrap_or(1);
+        rooms[heart].kind = RoomKind::Heart;
+        for (i, room) in rooms.iter_mut().enumerate().skip(1) {
+            if i != heart && rng.gen_bool(0.2) {
+                room.kind = RoomKind::Treasure;
+            }
+        }
+    }
+
+    let mut grid = vec![vec![b'#'; size as usize]; size as usize];
+    for room in &rooms {
+        for y in room.y..room.y + room.height {
+            for x in room.x..room.x + room.width {
+                grid[y as usize][x as usize] = b'.';
+            }
+        }
+    }
+    for &(x, y) in corridors.iter().flat_map(|corridor| &corridor.path) {
+        grid[y as usize][x as usize] = b'.';
+    }
+
+    Ok(DungeonLayout {
+        seed,
+        width: size,
+        height: size,
+        rooms,
+        corridors,
+        tiles: grid.into_iter().map(|row| String::from_utf8(row).unwrap_or_default()).collect(),
+    })
+}
+
+/// Walks horizontally then vertically, or the other way round.
+fn corridor_path(rng: &mut StdRng, from: (u32, u32), to: (u32, u32)) -> Vec<(u32, u32)> {
+    let horizontal_first = rng.gen_bool(0.5);
+    let corner = if horizontal_first { (to.0, from.1) } else { (from.0, to.1) };
+    let mut path = Vec::new();
+    for (start, end) in [(from, corner), (corner, to)] {
+        let mut at = start;
+        path.push(at);
+        while at != end {
+            at.0 = step_towards(at.0, end.0);
+            at.1 = step_towards(at.1, end.1);
+            path.push(at);
+        }
+    }
+    path.dedup();
+    path
+}
+
+fn step_towards(from: u32, to: u32) -> u32 {
+    match from.cmp(&to) {
+        std::cmp::Ordering::Less => from + 1,
+        std::cmp::Ordering::Greater => from - 1,
+        std::cmp::Ordering::Equal => from,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generators_are_reproducible_per_seed_and_grid() {
+        let grid = Some(GridCoordinate { x: 3, y: -2 });
+        assert_eq!(grid_seed(42, None), 42);
+        assert_eq!(grid_seed(42, grid), grid_seed(42, grid));
+        assert_ne!(grid_seed(42, grid), grid_seed(42, Some(GridCoordinate { x: -2, y: 3 })));
+
+        let seed = grid_seed(42, grid);
+        assert_eq!(npc_names(seed, 10).unwrap(), npc_names(seed, 10).unwrap());
+        assert_ne!(npc_names(seed, 10).unwrap(), npc_names(seed + 1, 10).unwrap());
+        assert_eq!(settlement_names(seed, 5).unwrap(), settlement_names(seed, 5).unwrap());
+
+        let fauna = descriptors(seed, DescriptorKind::Fauna, 4).unwrap();
+        assert_eq!(fauna, descriptors(seed, DescriptorKind::Fauna, 4).unwrap());
+        assert!(fauna.iter().all(|d| d.kind == DescriptorKind::Fauna && d.description.contains(" that ")));
+        assert!((0.0..=1.0).contains(&fauna[0].resonance));
+
+        assert!(matches!(npc_names(seed, 0), Err(ContentError::Invalid(_))));
+        assert!(matches!(settlement_names(seed, MAX_COUNT + 1), Err(ContentError::Invalid(_))));
+    }
+
+    #[test]
+    fn dungeon_rooms_are_connected_and_disjoint() {
+        let layout = dungeon_layout(7, 48, 8).unwrap();
+        assert_eq!(layout, dungeon_layout(7, 48, 8).unwrap());
+        assert!(layout.rooms.len() >= 2);
+        assert_eq!(layout.corridors.len(), layout.rooms.len() - 1);
+        assert_eq!(layout.rooms[0].kind, RoomKind::Entrance);
+        assert_eq!(layout.rooms.iter().filter(|room| room.kind == RoomKind::Heart).count(), 1);
+
+        for (i, room) in layout.rooms.iter().enumerate() {
+            assert!(layout.rooms[i + 1..].iter().all(|other| !room.touches(other)));
+            let (x, y) = room.center();
+            assert_eq!(layout.tiles[y as usize].as_bytes()[x as usize], b'.');
+        }
+        assert!(layout.tiles.iter().all(|row| row.starts_with('#') && row.ends_with('#')));
+        assert!(layout.tiles[0].bytes().all(|tile| tile == b'#'));
+
+        assert!(dungeon_layout(7, 8, 8).is_err());
+        assert!(dungeon_layout(7, 48, 1).is_err());
+    }
+}
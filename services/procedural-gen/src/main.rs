@@ -1,20 +1,295 @@
-use axum::Router;
-use finalverse_health::HealthMonitor;
-use service_registry::LocalServiceRegistry;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use finalverse_core::world_clock::{self, WorldClockClient};
+use finalverse_core::FinalverseError;
+use finalverse_health::{DebugEndpoints, HealthMonitor};
+use finalverse_world3d::{GridCoordinate, Position3D};
+use serde::Deserialize;
+use service_registry::{LocalServiceRegistry, ServiceClient};
 use std::{net::SocketAddr, sync::Arc};
-use tracing::info;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
 use finalverse_logging as logging;
+use uuid::Uuid;
+
+mod content;
+mod npc_population;
+
+use content::{ContentError, DescriptorKind};
+use npc_population::{generate_settlement, NamePool, PopulationError, PopulationRegistry, SettlementSpec};
+
+/// Seconds between full-fidelity updates.
+const FIDELITY_TICK_SECONDS: u64 = 5;
+
+/// How far around a player background NPCs are streamed.
+const VIEW_RADIUS: f32 = 200.0;
+
+/// Harmony reported to behavior-ai for settlement NPCs; towns are calm.
+const SETTLEMENT_HARMONY: f32 = 60.0;
+
+/// Names or descriptors returned when a request doesn't give a count.
+const DEFAULT_CONTENT_COUNT: usize = 10;
+
+const DEFAULT_DUNGEON_SIZE: u32 = 48;
+const DEFAULT_DUNGEON_ROOMS: usize = 8;
+
+#[derive(Clone)]
+struct AppState {
+    population: Arc<RwLock<PopulationRegistry>>,
+    services: ServiceClient,
+    clock: Arc<WorldClockClient>,
+}
+
+#[derive(Deserialize)]
+struct AgentAction {
+    action: serde_json::Value,
+}
+
+impl AppState {
+    fn hour(&self) -> f32 {
+        self.clock.now().hour
+    }
+
+    /// Hand NPCs near players to behavior-ai, take back the rest, and step
+    /// every agent still in play.
+    async fn tick(&self) {
+        let hour = self.hour();
+        let changes = self.population.write().await.update_fidelity(hour);
+
+        for (npc_id, region_id) in changes.promoted {
+            let body = serde_json::json!({ "id": npc_id.to_string(), "region": region_id.to_string() });
+            if let Err(e) = self.services.post_json::<_, serde_json::Value>("behavior-ai", "/agent/spawn", &body).await {
+                warn!("Failed to spawn agent for NPC {}: {}", npc_id, e);
+            }
+        }
+        for npc_id in changes.demoted {
+            let path = format!("/agent/{}", npc_id);
+            if let Err(e) = self.services.delete_json::<serde_json::Value>("behavior-ai", &path).await {
+                warn!("Failed to despawn agent for NPC {}: {}", npc_id, e);
+            }
+        }
+
+        let contexts = self.population.read().await.agent_contexts(hour);
+        for context in contexts {
+            let path = format!("/agent/{}/act", context.npc_id);
+            let body = serde_json::json!({
+                "location": context.settlement_name,
+                "nearby_entities": context.nearby_players.iter().map(Uuid::to_string).collect::<Vec<_>>(),
+                "harmony_level": SETTLEMENT_HARMONY,
+                "tension": 0.0,
+                "memory": [format!("{:?}", context.activity)],
+            });
+            match self.services.post_json::<_, AgentAction>("behavior-ai", &path, &body).await {
+                Ok(AgentAction { action }) => {
+                    if let Some(kind) = action.get("kind").and_then(|kind| kind.as_str()) {
+                        self.population.write().await.set_behavior(context.npc_id, kind.to_string());
+                    }
+                }
+                Err(e) => warn!("Failed to step agent for NPC {}: {}", context.npc_id, e),
+            }
+        }
+    }
+}
+
+impl IntoResponse for PopulationError {
+    fn into_response(self) -> Response {
+        let message = self.to_string();
+        let error = match self {
+            PopulationError::UnknownSettlement(_) => FinalverseError::NotFound(message),
+            PopulationError::NoNames => FinalverseError::Unavailable(message),
+            PopulationError::Invalid(_) => FinalverseError::BadRequest(message),
+        };
+        error.into_response()
+    }
+}
+
+impl IntoResponse for ContentError {
+    fn into_response(self) -> Response {
+        FinalverseError::BadRequest(self.to_string()).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HourQuery {
+    /// Defaults to the current world hour.
+    hour: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObserveRequest {
+    player_id: Uuid,
+    position: Position3D,
+}
+
+/// Shared by every content endpoint. The same `seed` and grid always give
+/// the same content; without a seed one is picked and echoed back.
+#[derive(Debug, Deserialize)]
+struct ContentQuery {
+    seed: Option<u64>,
+    grid_x: Option<i32>,
+    grid_y: Option<i32>,
+    count: Option<usize>,
+}
+
+impl ContentQuery {
+    fn grid(&self) -> Option<GridCoordinate> {
+        self.grid_x.zip(self.grid_y).map(|(x, y)| GridCoordinate { x, y })
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+
+    fn count(&self) -> usize {
+        self.count.unwrap_or(DEFAULT_CONTENT_COUNT)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DungeonQuery {
+    seed: Option<u64>,
+    grid_x: Option<i32>,
+    grid_y: Option<i32>,
+    size: Option<u32>,
+    rooms: Option<usize>,
+}
+
+fn content_response<T: serde::Serialize>(seed: u64, grid: Option<GridCoordinate>, items: T) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "seed": seed, "grid": grid, "items": items }))
+}
+
+async fn npc_names(Query(query): Query<ContentQuery>) -> Result<impl IntoResponse, ContentError> {
+    let (seed, grid) = (query.seed(), query.grid());
+    let names = content::npc_names(content::grid_seed(seed, grid), query.count())?;
+    Ok(content_response(seed, grid, names))
+}
+
+async fn settlement_names(Query(query): Query<ContentQuery>) -> Result<impl IntoResponse, ContentError> {
+    let (seed, grid) = (query.seed(), query.grid());
+    let names = content::settlement_names(content::grid_seed(seed, grid), query.count())?;
+    Ok(content_response(seed, grid, names))
+}
+
+async fn flora(Query(query): Query<ContentQuery>) -> Result<impl IntoResponse, ContentError> {
+    let (seed, grid) = (query.seed(), query.grid());
+    let flora = content::descriptors(content::grid_seed(seed, grid), DescriptorKind::Flora, query.count())?;
+    Ok(content_response(seed, grid, flora))
+}
+
+async fn fauna(Query(query): Query<ContentQuery>) -> Result<impl IntoResponse, ContentError> {
+    let (seed, grid) = (query.seed(), query.grid());
+    let fauna = content::descriptors(content::grid_seed(seed, grid), DescriptorKind::Fauna, query.count())?;
+    Ok(content_response(seed, grid, fauna))
+}
+
+async fn dungeon(Query(query): Query<DungeonQuery>) -> Result<impl IntoResponse, ContentError> {
+    let seed = query.seed.unwrap_or_else(rand::random);
+    let grid = query.grid_x.zip(query.grid_y).map(|(x, y)| GridCoordinate { x, y });
+    let layout = content::dungeon_layout(
+        content::grid_seed(seed, grid),
+        query.size.unwrap_or(DEFAULT_DUNGEON_SIZE),
+        query.rooms.unwrap_or(DEFAULT_DUNGEON_ROOMS),
+    )?;
+    Ok(content_response(seed, grid, layout))
+}
+
+async fn create_settlement(
+    State(state): State<AppState>,
+    Json(spec): Json<SettlementSpec>,
+) -> Result<impl IntoResponse, PopulationError> {
+    let names = match state.services.get_json::<NamePool>("community", "/lore/names").await {
+        Ok(names) => names,
+        Err(e) => {
+            warn!("Failed to fetch names from the lore registry: {}", e);
+            return Err(PopulationError::NoNames);
+        }
+    };
+    let settlement = generate_settlement(spec, &names)?;
+    info!("🏘️ Populated {} with {} inhabitants", settlement.name, settlement.npcs.len());
+    let summary = state.population.write().await.add_settlement(settlement);
+    Ok((StatusCode::CREATED, Json(summary)))
+}
+
+async fn list_settlements(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.population.read().await.settlements())
+}
+
+async fn get_settlement(
+    State(state): State<AppState>,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<impl IntoResponse, PopulationError> {
+    let population = state.population.read().await;
+    Ok(Json(population.settlement(settlement_id)?.clone()))
+}
+
+async fn settlement_presences(
+    State(state): State<AppState>,
+    Path(settlement_id): Path<Uuid>,
+    Query(query): Query<HourQuery>,
+) -> Result<impl IntoResponse, PopulationError> {
+    let hour = query.hour.unwrap_or_else(|| state.hour());
+    Ok(Json(state.population.read().await.presences(settlement_id, hour)?))
+}
+
+/// Called by the gateway as players move: records the observer and returns
+/// the background NPCs around them for streaming.
+async fn observe(State(state): State<AppState>, Json(request): Json<ObserveRequest>) -> impl IntoResponse {
+    let hour = state.hour();
+    let mut population = state.population.write().await;
+    population.observe(request.player_id, request.position);
+    Json(population.presences_near(&request.position, VIEW_RADIUS, hour))
+}
+
+async fn forget_observer(State(state): State<AppState>, Path(player_id): Path<Uuid>) -> impl IntoResponse {
+    state.population.write().await.forget_observer(player_id);
+    StatusCode::NO_CONTENT
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
+    // Schedules run on world time
+    let clock = world_clock::start_from_env();
     let monitor = Arc::new(HealthMonitor::new("procedural-gen", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
         .register_service("procedural-gen".to_string(), "http://localhost:3010".to_string())
         .await;
 
-    let app = Router::new().merge(monitor.clone().axum_routes());
+    let state = AppState {
+        population: Arc::new(RwLock::new(PopulationRegistry::new())),
+        services: ServiceClient::new(registry),
+        clock,
+    };
+
+    let tick_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(FIDELITY_TICK_SECONDS));
+        loop {
+            interval.tick().await;
+            tick_state.tick().await;
+        }
+    });
+
+    let app = Router::new()
+        .route("/settlements", get(list_settlements).post(create_settlement))
+        .route("/settlements/:settlement_id", get(get_settlement))
+        .route("/settlements/:settlement_id/npcs", get(settlement_presences))
+        .route("/population/observe", post(observe))
+        .route("/population/observe/:player_id", axum::routing::delete(forget_observer))
+        .route("/content/names/npc", get(npc_names))
+        .route("/content/names/settlement", get(settlement_names))
+        .route("/content/flora", get(flora))
+        .route("/content/fauna", get(fauna))
+        .route("/content/dungeon", get(dungeon))
+        .with_state(state)
+        .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("procedural-gen")).axum_routes());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3010));
     info!("Procedural Gen listening on {}", addr);
@@ -0,0 +1,530 @@
+// services/procedural-gen/src/npc_population.rs
+//! Background NPC populations for settlements. Everyone is generated from
+//! the settlement's seed and follows a daily schedule that is cheap to
+//! evaluate: their location is a pure function of the time of day. NPCs
+//! near a player are handed to behavior-ai for full-fidelity behaviour.
+
+use finalverse_core::{Coordinates, NPCRole, NpcActivity, NpcPresence};
+use finalverse_world3d::Position3D;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{hash_map::Entry, HashMap};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const MAX_POPULATION: usize = 500;
+
+/// Time spent walking between two scheduled locations.
+pub const COMMUTE_HOURS: f32 = 0.5;
+
+/// NPCs within this distance of a player are simulated by behavior-ai.
+pub const FULL_FIDELITY_RADIUS: f32 = 40.0;
+
+/// Players who haven't reported a position for this long stop keeping
+/// NPCs at full fidelity.
+pub const OBSERVER_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum PopulationError {
+    #[error("Unknown settlement {0}")]
+    UnknownSettlement(Uuid),
+    #[error("The lore registry has no names to give")]
+    NoNames,
+    #[error("Invalid settlement: {0}")]
+    Invalid(String),
+}
+
+/// Names from the community lore registry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamePool {
+    pub given: Vec<String>,
+    pub family: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SettlementSpec {
+    pub name: String,
+    pub region_id: Uuid,
+    pub center: Position3D,
+    pub radius: f32,
+    pub population: usize,
+    /// Regenerating with the same seed yields the same people.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleEntry {
+    pub start_hour: f32,
+    pub activity: NpcActivity,
+    pub location: Position3D,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundNpc {
+    pub id: Uuid,
+    pub name: String,
+    pub role: NPCRole,
+    pub home: Position3D,
+    pub workplace: Position3D,
+    /// Sorted by start hour, the first starting at midnight.
+    pub schedule: Vec<ScheduleEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Settlement {
+    pub id: Uuid,
+    pub name: String,
+    pub region_id: Uuid,
+    pub center: Position3D,
+    pub radius: f32,
+    pub seed: u64,
+    pub npcs: Vec<BackgroundNpc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettlementSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub region_id: Uuid,
+    pub population: usize,
+}
+
+/// Places shared by everyone in a settlement.
+struct Landmarks {
+    center: Position3D,
+    radius: f32,
+    square: Position3D,
+    tavern: Position3D,
+    gate: Position3D,
+}
+
+impl BackgroundNpc {
+    /// What the NPC is doing at `hour` (0.0..24.0) and where. The first
+    /// stretch of every entry is spent walking from the previous location.
+    pub fn activity_at(&self, hour: f32) -> (NpcActivity, Position3D) {
+        let hour = hour.rem_euclid(24.0);
+        let len = self.schedule.len();
+        let current = self
+            .schedule
+            .iter()
+            .rposition(|entry| entry.start_hour <= hour)
+            .unwrap_or(len - 1);
+        let entry = &self.schedule[current];
+        let previous = &self.schedule[(current + len - 1) % len];
+
+        let elapsed = (hour - entry.start_hour).rem_euclid(24.0);
+        if elapsed < COMMUTE_HOURS && previous.location.distance_to(&entry.location) > 1.0 {
+            let t = elapsed / COMMUTE_HOURS;
+            return (NpcActivity::Commuting, lerp(&previous.location, &entry.location, t));
+        }
+        (entry.activity, entry.location)
+    }
+
+    pub fn presence_at(&self, settlement_id: Uuid, hour: f32, behavior: Option<String>) -> NpcPresence {
+        let (activity, position) = self.activity_at(hour);
+        NpcPresence {
+            npc_id: self.id,
+            settlement_id,
+            name: self.name.clone(),
+            role: self.role.clone(),
+            activity,
+            position: Coordinates {
+                x: position.x as f64,
+                y: position.y as f64,
+                z: position.z as f64,
+            },
+            behavior,
+        }
+    }
+}
+
+/// Generate a settlement's inhabitants with names drawn from `names`.
+pub fn generate_settlement(spec: SettlementSpec, names: &NamePool) -> Result<Settlement, PopulationError> {
+    if names.given.is_empty() || names.family.is_empty() {
+        return Err(PopulationError::NoNames);
+    }
+    if !(spec.radius.is_finite() && spec.radius > 0.0) {
+        return Err(PopulationError::Invalid("radius must be positive".to_string()));
+    }
+    if spec.population > MAX_POPULATION {
+        return Err(PopulationError::Invalid(format!("population is limited to {}", MAX_POPULATION)));
+    }
+
+    let seed = spec.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let landmarks = Landmarks {
+        center: spec.center,
+        radius: spec.radius,
+        square: scatter(&mut rng, &spec.center, 0.0, spec.radius * 0.1),
+        tavern: scatter(&mut rng, &spec.center, spec.radius * 0.1, spec.radius * 0.3),
+        gate: scatter(&mut rng, &spec.center, spec.radius, spec.radius),
+    };
+
+    let npcs = (0..spec.population)
+        .map(|_| generate_npc(&mut rng, &landmarks, names))
+        .collect();
+
+    Ok(Settlement {
+        id: Uuid::new_v4(),
+        name: spec.name,
+        region_id: spec.region_id,
+        center: spec.center,
+        radius: spec.radius,
+        seed,
+        npcs,
+    })
+}
+
+fn generate_npc(rng: &mut StdRng, landmarks: &Landmarks, names: &NamePool) -> BackgroundNpc {
+    let id = Uuid::from_u128(rng.gen());
+    let name = format!(
+        "{} {}",
+        names.given[rng.gen_range(0..names.given.len())],
+        names.family[rng.gen_range(0..names.family.len())]
+    );
+    let role = pick_role(rng);
+    let (center, radius) = (&landmarks.center, landmarks.radius);
+    let home = scatter(rng, center, radius * 0.3, radius * 0.9);
+    let workplace = match role {
+        NPCRole::Merchant => scatter(rng, &landmarks.square, 0.0, radius * 0.1),
+        NPCRole::Guard => landmarks.gate,
+        NPCRole::Villager => scatter(rng, center, radius, radius * 1.3),
+        NPCRole::Child | NPCRole::Elder => landmarks.square,
+        _ => scatter(rng, center, radius * 0.1, radius * 0.6),
+    };
+    let schedule = schedule_for(rng, &role, home, workplace, landmarks);
+
+    BackgroundNpc {
+        id,
+        name,
+        role,
+        home,
+        workplace,
+        schedule,
+    }
+}
+
+fn pick_role(rng: &mut StdRng) -> NPCRole {
+    const WEIGHTS: [(u32, NPCRole); 7] = [
+        (40, NPCRole::Villager),
+        (12, NPCRole::Merchant),
+        (15, NPCRole::Artisan),
+        (10, NPCRole::Guard),
+        (5, NPCRole::Scholar),
+        (12, NPCRole::Child),
+        (6, NPCRole::Elder),
+    ];
+    let total: u32 = WEIGHTS.iter().map(|(weight, _)| weight).sum();
+    let mut roll = rng.gen_range(0..total);
+    for (weight, role) in WEIGHTS {
+        if roll < weight {
+            return role;
+        }
+        roll -= weight;
+    }
+    NPCRole::Villager
+}
+
+fn schedule_for(
+    rng: &mut StdRng,
+    role: &NPCRole,
+    home: Position3D,
+    workplace: Position3D,
+    landmarks: &Landmarks,
+) -> Vec<ScheduleEntry> {
+    use NpcActivity::*;
+    let (square, tavern) = (landmarks.square, landmarks.tavern);
+    let plan: Vec<(f32, NpcActivity, Position3D)> = match role {
+        NPCRole::Guard if rng.gen_bool(0.5) => vec![
+            (0.0, Patrolling, workplace),
+            (7.0, Sleeping, home),
+            (15.0, Eating, home),
+            (16.0, Socializing, tavern),
+            (20.0, Patrolling, workplace),
+        ],
+        NPCRole::Guard => vec![
+            (0.0, Sleeping, home),
+            (6.0, Eating, home),
+            (7.0, Patrolling, workplace),
+            (12.0, Eating, tavern),
+            (13.0, Patrolling, workplace),
+            (19.0, Socializing, tavern),
+            (22.0, Sleeping, home),
+        ],
+        NPCRole::Child => vec![
+            (0.0, Sleeping, home),
+            (7.0, Eating, home),
+            (8.0, Socializing, square),
+            (12.0, Eating, home),
+            (13.0, Socializing, square),
+            (18.0, Eating, home),
+            (20.0, Sleeping, home),
+        ],
+        NPCRole::Elder => vec![
+            (0.0, Sleeping, home),
+            (7.0, Eating, home),
+            (9.0, Socializing, square),
+            (12.0, Eating, tavern),
+            (14.0, Socializing, square),
+            (17.0, Eating, home),
+            (20.0, Sleeping, home),
+        ],
+        _ => vec![
+            (0.0, Sleeping, home),
+            (6.0, Eating, home),
+            (7.0, Working, workplace),
+            (12.0, Eating, tavern),
+            (13.0, Working, workplace),
+            (18.0, Socializing, tavern),
+            (22.0, Sleeping, home),
+        ],
+    };
+
+    // Entries are at least an hour apart, so this jitter keeps them ordered
+    plan.into_iter()
+        .map(|(start_hour, activity, location)| ScheduleEntry {
+            start_hour: if start_hour == 0.0 { 0.0 } else { start_hour + rng.gen_range(-0.4..0.4) },
+            activity,
+            location,
+        })
+        .collect()
+}
+
+/// A random point between `min` and `max` away from `center`.
+fn scatter(rng: &mut StdRng, center: &Position3D, min: f32, max: f32) -> Position3D {
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let distance = if max > min { rng.gen_range(min..max) } else { min };
+    Position3D {
+        x: center.x + angle.cos() * distance,
+        y: center.y + angle.sin() * distance,
+        z: center.z,
+    }
+}
+
+fn lerp(from: &Position3D, to: &Position3D, t: f32) -> Position3D {
+    Position3D {
+        x: from.x + (to.x - from.x) * t,
+        y: from.y + (to.y - from.y) * t,
+        z: from.z + (to.z - from.z) * t,
+    }
+}
+
+/// An NPC that should be driven by behavior-ai, with what it needs to act.
+#[derive(Debug, Clone)]
+pub struct AgentContext {
+    pub npc_id: Uuid,
+    pub settlement_name: String,
+    pub activity: NpcActivity,
+    pub nearby_players: Vec<Uuid>,
+}
+
+/// NPCs entering and leaving full-fidelity simulation.
+#[derive(Debug, Default)]
+pub struct FidelityChanges {
+    /// (npc, region)
+    pub promoted: Vec<(Uuid, Uuid)>,
+    pub demoted: Vec<Uuid>,
+}
+
+#[derive(Default)]
+pub struct PopulationRegistry {
+    settlements: HashMap<Uuid, Settlement>,
+    observers: HashMap<Uuid, (Position3D, Instant)>,
+    /// NPCs currently simulated by behavior-ai, with their latest action.
+    full_fidelity: HashMap<Uuid, Option<String>>,
+}
+
+impl PopulationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_settlement(&mut self, settlement: Settlement) -> SettlementSummary {
+        let summary = summarize(&settlement);
+        self.settlements.insert(settlement.id, settlement);
+        summary
+    }
+
+    pub fn settlements(&self) -> Vec<SettlementSummary> {
+        self.settlements.values().map(summarize).collect()
+    }
+
+    pub fn settlement(&self, id: Uuid) -> Result<&Settlement, PopulationError> {
+        self.settlements.get(&id).ok_or(PopulationError::UnknownSettlement(id))
+    }
+
+    /// Every inhabitant of a settlement at `hour`.
+    pub fn presences(&self, settlement_id: Uuid, hour: f32) -> Result<Vec<NpcPresence>, PopulationError> {
+        let settlement = self.settlement(settlement_id)?;
+        Ok(settlement
+            .npcs
+            .iter()
+            .map(|npc| npc.presence_at(settlement.id, hour, self.behavior(npc.id)))
+            .collect())
+    }
+
+    /// Inhabitants of any settlement within `radius` of `position` at `hour`.
+    pub fn presences_near(&self, position: &Position3D, radius: f32, hour: f32) -> Vec<NpcPresence> {
+        self.settlements
+            .values()
+            // A commuter never strays further than this from the settlement
+            .filter(|settlement| settlement.center.distance_to(position) <= settlement.radius * 1.3 + radius)
+            .flat_map(|settlement| {
+                settlement.npcs.iter().filter_map(move |npc| {
+                    let (_, at) = npc.activity_at(hour);
+                    (at.distance_to(position) <= radius).then(|| npc.presence_at(settlement.id, hour, self.behavior(npc.id)))
+                })
+            })
+            .collect()
+    }
+
+    /// Record where a player is; their surroundings are kept at full fidelity.
+    pub fn observe(&mut self, player_id: Uuid, position: Position3D) {
+        self.observers.insert(player_id, (position, Instant::now()));
+    }
+
+    pub fn forget_observer(&mut self, player_id: Uuid) {
+        self.observers.remove(&player_id);
+    }
+
+    /// Work out which NPCs are near a player at `hour`, promoting newcomers
+    /// and demoting those left alone.
+    pub fn update_fidelity(&mut self, hour: f32) -> FidelityChanges {
+        self.observers.retain(|_, (_, seen)| seen.elapsed() < OBSERVER_TTL);
+
+        let mut near: HashMap<Uuid, Uuid> = HashMap::new();
+        for settlement in self.settlements.values() {
+            for npc in &settlement.npcs {
+                let (_, at) = npc.activity_at(hour);
+                if self.observers.values().any(|(position, _)| position.distance_to(&at) <= FULL_FIDELITY_RADIUS) {
+                    near.insert(npc.id, settlement.region_id);
+                }
+            }
+        }
+
+        let mut changes = FidelityChanges::default();
+        self.full_fidelity.retain(|npc_id, _| {
+            let keep = near.contains_key(npc_id);
+            if !keep {
+                changes.demoted.push(*npc_id);
+            }
+            keep
+        });
+        for (npc_id, region_id) in near {
+            if let Entry::Vacant(entry) = self.full_fidelity.entry(npc_id) {
+                entry.insert(None);
+                changes.promoted.push((npc_id, region_id));
+            }
+        }
+        changes
+    }
+
+    /// Context for each full-fidelity NPC's next behavior-ai step.
+    pub fn agent_contexts(&self, hour: f32) -> Vec<AgentContext> {
+        self.settlements
+            .values()
+            .flat_map(|settlement| settlement.npcs.iter().map(move |npc| (settlement, npc)))
+            .filter(|(_, npc)| self.full_fidelity.contains_key(&npc.id))
+            .map(|(settlement, npc)| {
+                let (activity, at) = npc.activity_at(hour);
+                let nearby_players = self
+                    .observers
+                    .iter()
+                    .filter(|(_, (position, _))| position.distance_to(&at) <= FULL_FIDELITY_RADIUS)
+                    .map(|(player_id, _)| *player_id)
+                    .collect();
+                AgentContext {
+                    npc_id: npc.id,
+                    settlement_name: settlement.name.clone(),
+                    activity,
+                    nearby_players,
+                }
+            })
+            .collect()
+    }
+
+    pub fn set_behavior(&mut self, npc_id: Uuid, behavior: String) {
+        if let Some(current) = self.full_fidelity.get_mut(&npc_id) {
+            *current = Some(behavior);
+        }
+    }
+
+    fn behavior(&self, npc_id: Uuid) -> Option<String> {
+        self.full_fidelity.get(&npc_id).cloned().flatten()
+    }
+}
+
+fn summarize(settlement: &Settlement) -> SettlementSummary {
+    SettlementSummary {
+        id: settlement.id,
+        name: settlement.name.clone(),
+        region_id: settlement.region_id,
+        population: settlement.npcs.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> NamePool {
+        NamePool {
+            given: vec!["Wren".to_string(), "Bram".to_string()],
+            family: vec!["Windsong".to_string()],
+        }
+    }
+
+    fn spec(seed: u64) -> SettlementSpec {
+        SettlementSpec {
+            name: "Harmony's Rest".to_string(),
+            region_id: Uuid::new_v4(),
+            center: Position3D { x: 0.0, y: 0.0, z: 0.0 },
+            radius: 100.0,
+            population: 30,
+            seed: Some(seed),
+        }
+    }
+
+    #[test]
+    fn seeded_settlements_are_reproducible_and_follow_schedules() {
+        let first = generate_settlement(spec(7), &names()).unwrap();
+        let again = generate_settlement(spec(7), &names()).unwrap();
+        assert_eq!(first.npcs.len(), 30);
+        assert_eq!(first.npcs[3].id, again.npcs[3].id);
+        assert_eq!(first.npcs[3].name, again.npcs[3].name);
+
+        for npc in &first.npcs {
+            assert!(npc.name.ends_with("Windsong"));
+            let (activity, at) = npc.activity_at(3.0);
+            if activity == NpcActivity::Sleeping {
+                assert!(at.distance_to(&npc.home) < 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn nearby_npcs_are_promoted_and_demoted() {
+        let mut registry = PopulationRegistry::new();
+        let settlement = generate_settlement(spec(11), &names()).unwrap();
+        let (_, at) = settlement.npcs[0].activity_at(10.0);
+        let npc_id = settlement.npcs[0].id;
+        registry.add_settlement(settlement);
+
+        let player = Uuid::new_v4();
+        registry.observe(player, at);
+        let changes = registry.update_fidelity(10.0);
+        assert!(changes.promoted.iter().any(|(id, _)| *id == npc_id));
+        registry.set_behavior(npc_id, "wander".to_string());
+        assert!(registry
+            .presences_near(&at, 1.0, 10.0)
+            .iter()
+            .any(|presence| presence.npc_id == npc_id && presence.behavior.as_deref() == Some("wander")));
+
+        registry.forget_observer(player);
+        assert!(registry.update_fidelity(10.0).demoted.contains(&npc_id));
+        assert!(registry.agent_contexts(10.0).is_empty());
+        assert!(matches!(generate_settlement(spec(1), &NamePool { given: vec![], family: vec![] }), Err(PopulationError::NoNames)));
+    }
+}
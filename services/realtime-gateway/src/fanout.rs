@@ -0,0 +1,162 @@
+// services/realtime-gateway/src/fanout.rs
+// Fan-out between gateway instances so broadcasts reach every client
+
+use crate::ServerMessage;
+use async_trait::async_trait;
+use finalverse_core::TenantId;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+/// Which clients a message is for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum Audience {
+    All,
+    Region { region_id: String },
+    Client { client_id: String },
+}
+
+/// A message on its way to the other gateway instances. `origin` lets
+/// the sender skip its own copy, which it has already delivered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    pub origin: Uuid,
+    pub audience: Audience,
+    pub message: ServerMessage,
+}
+
+/// Carries envelopes between every gateway instance serving a tenant.
+#[async_trait]
+pub trait Fanout: Send + Sync {
+    async fn publish(&self, envelope: &Envelope) -> anyhow::Result<()>;
+    /// Envelopes published by any instance, this one included. The
+    /// stream ends if the connection drops.
+    async fn subscribe(&self) -> anyhow::Result<BoxStream<'static, Envelope>>;
+}
+
+/// Fan-out over a Redis pub/sub channel scoped to the tenant.
+pub struct RedisFanout {
+    client: redis::Client,
+    /// Made on first publish so the gateway starts without Redis.
+    connection: OnceCell<redis::aio::ConnectionManager>,
+    channel: String,
+}
+
+impl RedisFanout {
+    pub fn new(client: redis::Client, tenant: &TenantId) -> Self {
+        Self {
+            client,
+            connection: OnceCell::new(),
+            channel: tenant.scoped_key("gateway:fanout"),
+        }
+    }
+}
+
+#[async_trait]
+impl Fanout for RedisFanout {
+    async fn publish(&self, envelope: &Envelope) -> anyhow::Result<()> {
+        let mut connection = self
+            .connection
+            .get_or_try_init(|| redis::aio::ConnectionManager::new(self.client.clone()))
+            .await?
+            .clone();
+        let _: () = connection.publish(&self.channel, serde_json::to_string(envelope)?).await?;
+        Ok(())
+    }
+
+    async fn subscribe(&self) -> anyhow::Result<BoxStream<'static, Envelope>> {
+        let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+        pubsub.subscribe(&self.channel).await?;
+        let envelopes = pubsub.into_on_message().filter_map(|msg| async move {
+            let payload: String = msg.get_payload().ok()?;
+            match serde_json::from_str(&payload) {
+                Ok(envelope) => Some(envelope),
+                Err(e) => {
+                    tracing::warn!("Ignoring malformed fan-out message: {}", e);
+                    None
+                }
+            }
+        });
+        Ok(envelopes.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionManager;
+    use finalverse_protocol::WireFormat;
+    use std::sync::Arc;
+    use tokio::sync::broadcast;
+    use warp::ws::Message;
+
+    /// Instances sharing one in-process channel.
+    struct SharedFanout(broadcast::Sender<Envelope>);
+
+    #[async_trait]
+    impl Fanout for SharedFanout {
+        async fn publish(&self, envelope: &Envelope) -> anyhow::Result<()> {
+            self.0.send(envelope.clone())?;
+            Ok(())
+        }
+
+        async fn subscribe(&self) -> anyhow::Result<BoxStream<'static, Envelope>> {
+            let rx = self.0.subscribe();
+            Ok(envelopes(rx))
+        }
+    }
+
+    fn envelopes(rx: broadcast::Receiver<Envelope>) -> BoxStream<'static, Envelope> {
+        futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.ok().map(|envelope| (envelope, rx)) }).boxed()
+    }
+
+    fn message(event: &str) -> ServerMessage {
+        ServerMessage {
+            id: "1".to_string(),
+            event: event.to_string(),
+            payload: serde_json::Value::Null,
+        }
+    }
+
+    async fn received(rx: &mut tokio::sync::mpsc::UnboundedReceiver<Message>) -> Vec<String> {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let mut events = Vec::new();
+        while let Ok(frame) = rx.try_recv() {
+            let message: ServerMessage = serde_json::from_str(frame.to_str().unwrap()).unwrap();
+            events.push(message.event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn broadcasts_and_region_messages_reach_clients_on_other_instances() {
+        let (tx, _) = broadcast::channel(16);
+        let fanout: Arc<dyn Fanout> = Arc::new(SharedFanout(tx));
+        let a = Arc::new(ConnectionManager::new().with_fanout(fanout.clone()));
+        let b = Arc::new(ConnectionManager::new().with_fanout(fanout));
+        a.start_fanout();
+        b.start_fanout();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let (a_tx, mut on_a) = tokio::sync::mpsc::unbounded_channel();
+        let (b_tx, mut on_b) = tokio::sync::mpsc::unbounded_channel();
+        let (grove_tx, mut in_grove) = tokio::sync::mpsc::unbounded_channel();
+        a.add_client("on-a".to_string(), a_tx, WireFormat::Json).await;
+        b.add_client("on-b".to_string(), b_tx, WireFormat::Json).await;
+        b.add_client("in-grove".to_string(), grove_tx, WireFormat::Json).await;
+        assert!(b.set_region("in-grove", Some("whisperwood".to_string())).await);
+
+        a.broadcast(message("dawn")).await;
+        a.broadcast_to_region("whisperwood", message("rustle")).await;
+        b.send_to("on-a", message("whisper")).await;
+
+        // Each client hears a message once, however many instances relay it
+        assert_eq!(received(&mut on_a).await, vec!["dawn", "whisper"]);
+        assert_eq!(received(&mut on_b).await, vec!["dawn"]);
+        assert_eq!(received(&mut in_grove).await, vec!["dawn", "rustle"]);
+    }
+}
@@ -5,29 +5,36 @@ use tokio::sync::RwLock;
 use warp::Filter;
 use warp::ws::{WebSocket, Message};
 use futures::{StreamExt, SinkExt};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use uuid::Uuid;
-use tracing::info;
+use tracing::{info, warn};
 use finalverse_logging as logging;
+use finalverse_health::{DebugEndpoints, DebugSource, OperatorGuard};
+use finalverse_config::HeartbeatConfig;
+use finalverse_events::{Event, EventMetadata, EventType, GameEventBus, LocalEventBus, NatsEventBus, PlayerEvent, TenantEventBus};
+use finalverse_protocol::{EncodedFrame, WireFormat};
+pub use finalverse_protocol::realtime::{ClientMessage, ServerMessage};
+use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ClientMessage {
-    pub id: String,
-    pub action: String,
-    pub payload: serde_json::Value,
-}
+/// Wait before resubscribing to the fan-out channel after it drops.
+const FANOUT_RETRY: Duration = Duration::from_secs(5);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ServerMessage {
-    pub id: String,
-    pub event: String,
-    pub payload: serde_json::Value,
-}
+mod fanout;
+mod net_sim;
+mod routing;
+use fanout::{Audience, Envelope, Fanout, RedisFanout};
+use net_sim::{NetSim, NetSimConfig};
+use routing::{ActionRouter, RoutingMetrics};
 
 // WebSocket plugin trait - removed Clone requirement
 #[async_trait::async_trait]
 pub trait WebSocketPlugin: Send + Sync {
     fn name(&self) -> &str;
+    /// Actions this plugin handles, e.g. `chat.*`; see `ActionPattern`.
+    /// Defaults to the namespace named after the plugin.
+    fn action_patterns(&self) -> Vec<String> {
+        vec![format!("{}.*", self.name())]
+    }
     async fn handle_message(&self, client_id: &str, message: ClientMessage) -> Option<ServerMessage>;
     async fn on_connect(&self, client_id: &str);
     async fn on_disconnect(&self, client_id: &str);
@@ -36,57 +43,256 @@ pub trait WebSocketPlugin: Send + Sync {
 // Plugin registry using Arc instead of Box to avoid Clone issues
 pub struct PluginRegistry {
     plugins: HashMap<String, Arc<dyn WebSocketPlugin>>,
+    router: ActionRouter,
 }
 
 impl PluginRegistry {
     pub fn new() -> Self {
         Self {
             plugins: HashMap::new(),
+            router: ActionRouter::new(),
         }
     }
 
-    pub fn register(&mut self, plugin: Arc<dyn WebSocketPlugin>) {
-        self.plugins.insert(plugin.name().to_string(), plugin);
+    /// Fails if the plugin claims an action pattern another plugin owns.
+    pub fn register(&mut self, plugin: Arc<dyn WebSocketPlugin>) -> Result<(), String> {
+        let name = plugin.name().to_string();
+        self.router.unregister(&name);
+        self.router.register(&name, &plugin.action_patterns())?;
+        self.plugins.insert(name, plugin);
+        Ok(())
     }
 
     pub fn get(&self, name: &str) -> Option<Arc<dyn WebSocketPlugin>> {
         self.plugins.get(name).cloned()
     }
+
+    /// The plugin registered for `action`, if any.
+    pub fn route(&self, action: &str) -> Option<(&str, Arc<dyn WebSocketPlugin>)> {
+        let name = self.router.route(action)?;
+        Some((name, self.plugins.get(name)?.clone()))
+    }
+
+    pub fn metrics(&self) -> RoutingMetrics {
+        self.router.metrics()
+    }
 }
 
-// Client connection manager
+struct ConnectedClient {
+    tx: tokio::sync::mpsc::UnboundedSender<Message>,
+    format: WireFormat,
+    region: Option<String>,
+}
+
+// Client connection manager. With a fan-out, broadcasts and region
+// messages also reach clients connected to other gateway instances.
 pub struct ConnectionManager {
-    clients: Arc<RwLock<HashMap<String, tokio::sync::mpsc::UnboundedSender<Message>>>>,
+    clients: Arc<RwLock<HashMap<String, ConnectedClient>>>,
+    instance_id: Uuid,
+    fanout: Option<Arc<dyn Fanout>>,
 }
 
 impl ConnectionManager {
     pub fn new() -> Self {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
+            instance_id: Uuid::new_v4(),
+            fanout: None,
         }
     }
 
-    pub async fn add_client(&self, client_id: String, tx: tokio::sync::mpsc::UnboundedSender<Message>) {
-        self.clients.write().await.insert(client_id, tx);
+    pub fn with_fanout(self, fanout: Arc<dyn Fanout>) -> Self {
+        Self { fanout: Some(fanout), ..self }
+    }
+
+    pub async fn add_client(
+        &self,
+        client_id: String,
+        tx: tokio::sync::mpsc::UnboundedSender<Message>,
+        format: WireFormat,
+    ) {
+        self.clients.write().await.insert(client_id, ConnectedClient { tx, format, region: None });
     }
 
     pub async fn remove_client(&self, client_id: &str) {
         self.clients.write().await.remove(client_id);
     }
 
+    /// Move a client into a region, or out of every region with `None`.
+    /// False if the client isn't connected here.
+    pub async fn set_region(&self, client_id: &str, region: Option<String>) -> bool {
+        match self.clients.write().await.get_mut(client_id) {
+            Some(client) => {
+                client.region = region;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn client_count(&self) -> usize {
+        self.clients.read().await.len()
+    }
+
     pub async fn send_to_client(&self, client_id: &str, message: Message) -> Result<(), String> {
         let clients = self.clients.read().await;
-        if let Some(tx) = clients.get(client_id) {
-            tx.send(message).map_err(|_| "Failed to send message".to_string())
+        if let Some(client) = clients.get(client_id) {
+            client.tx.send(message).map_err(|_| "Failed to send message".to_string())
         } else {
             Err("Client not found".to_string())
         }
     }
 
-    pub async fn broadcast(&self, message: Message) {
+    pub async fn broadcast(&self, message: ServerMessage) {
+        self.send(Audience::All, message).await;
+    }
+
+    pub async fn broadcast_to_region(&self, region_id: &str, message: ServerMessage) {
+        self.send(Audience::Region { region_id: region_id.to_string() }, message).await;
+    }
+
+    /// Message one client, on whichever instance it is connected to.
+    pub async fn send_to(&self, client_id: &str, message: ServerMessage) {
+        self.send(Audience::Client { client_id: client_id.to_string() }, message).await;
+    }
+
+    /// Deliver to matching clients here, then pass the message on to the
+    /// other instances. A client found here needs no passing on.
+    pub async fn send(&self, audience: Audience, message: ServerMessage) -> usize {
+        let delivered = self.deliver(&audience, &message).await;
+        let Some(fanout) = &self.fanout else {
+            return delivered;
+        };
+        if matches!(audience, Audience::Client { .. }) && delivered > 0 {
+            return delivered;
+        }
+        let envelope = Envelope { origin: self.instance_id, audience, message };
+        if let Err(e) = fanout.publish(&envelope).await {
+            warn!("Failed to fan out {} to other gateways: {}", envelope.message.event, e);
+        }
+        delivered
+    }
+
+    /// Send to the matching clients connected to this instance, each in
+    /// its own wire format. Returns how many were sent to.
+    async fn deliver(&self, audience: &Audience, message: &ServerMessage) -> usize {
         let clients = self.clients.read().await;
-        for (_, tx) in clients.iter() {
-            let _ = tx.send(message.clone());
+        let mut delivered = 0;
+        for (client_id, client) in clients.iter() {
+            let wanted = match audience {
+                Audience::All => true,
+                Audience::Region { region_id } => client.region.as_ref() == Some(region_id),
+                Audience::Client { client_id: wanted } => client_id == wanted,
+            };
+            if let Some(frame) = wanted.then(|| encode_frame(client.format, message)).flatten() {
+                if client.tx.send(frame).is_ok() {
+                    delivered += 1;
+                }
+            }
+        }
+        delivered
+    }
+
+    /// Deliver what other instances fan out, resubscribing if the
+    /// connection to them drops.
+    pub fn start_fanout(self: &Arc<Self>) {
+        let Some(fanout) = self.fanout.clone() else {
+            return;
+        };
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match fanout.subscribe().await {
+                    Ok(mut envelopes) => {
+                        while let Some(envelope) = envelopes.next().await {
+                            if envelope.origin != manager.instance_id {
+                                manager.deliver(&envelope.audience, &envelope.message).await;
+                            }
+                        }
+                        warn!("Gateway fan-out subscription ended; resubscribing");
+                    }
+                    Err(e) => warn!("Failed to subscribe to gateway fan-out: {}", e),
+                }
+                tokio::time::sleep(FANOUT_RETRY).await;
+            }
+        });
+    }
+}
+
+/// Joins and leaves regions, so region broadcasts know who to reach.
+pub struct RegionPlugin {
+    clients: Arc<ConnectionManager>,
+}
+
+#[derive(Deserialize)]
+struct JoinRegion {
+    region_id: String,
+}
+
+#[async_trait::async_trait]
+impl WebSocketPlugin for RegionPlugin {
+    fn name(&self) -> &str {
+        "region"
+    }
+
+    async fn handle_message(&self, client_id: &str, message: ClientMessage) -> Option<ServerMessage> {
+        let (event, region_id) = match message.action.as_str() {
+            "region.join" => match serde_json::from_value::<JoinRegion>(message.payload) {
+                Ok(join) => ("region.joined", Some(join.region_id)),
+                Err(e) => {
+                    return Some(ServerMessage {
+                        id: message.id,
+                        event: "error".to_string(),
+                        payload: serde_json::json!({ "code": "bad_payload", "message": e.to_string() }),
+                    })
+                }
+            },
+            "region.leave" => ("region.left", None),
+            _ => return Some(unknown_action(&message)),
+        };
+        self.clients.set_region(client_id, region_id.clone()).await;
+        Some(ServerMessage {
+            id: message.id,
+            event: event.to_string(),
+            payload: serde_json::json!({ "region_id": region_id }),
+        })
+    }
+
+    async fn on_connect(&self, _client_id: &str) {}
+
+    async fn on_disconnect(&self, _client_id: &str) {}
+}
+
+/// A message for clients from another service.
+#[derive(Debug, Deserialize)]
+pub struct BroadcastRequest {
+    /// Only clients in this region; everyone when absent.
+    pub region_id: Option<String>,
+    pub event: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+/// Error reply for an action no plugin has claimed.
+fn unknown_action(message: &ClientMessage) -> ServerMessage {
+    ServerMessage {
+        id: message.id.clone(),
+        event: "error".to_string(),
+        payload: serde_json::json!({
+            "code": "unknown_action",
+            "action": message.action,
+        }),
+    }
+}
+
+/// A websocket frame in the client's negotiated format.
+fn encode_frame(format: WireFormat, message: &ServerMessage) -> Option<Message> {
+    match format.encode(message) {
+        Ok(EncodedFrame::Text(text)) => Some(Message::text(text)),
+        Ok(EncodedFrame::Binary(bytes)) => Some(Message::binary(bytes)),
+        Err(e) => {
+            warn!("Dropping message that failed to encode: {}", e);
+            None
         }
     }
 }
@@ -95,13 +301,16 @@ async fn handle_websocket(
     ws: WebSocket,
     clients: Arc<ConnectionManager>,
     plugins: Arc<RwLock<PluginRegistry>>,
+    format: WireFormat,
+    heartbeat: HeartbeatConfig,
+    event_bus: Arc<dyn GameEventBus>,
 ) {
     let client_id = Uuid::new_v4().to_string();
     let (mut ws_tx, mut ws_rx) = ws.split();
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
 
     // Add client to connection manager
-    clients.add_client(client_id.clone(), tx).await;
+    clients.add_client(client_id.clone(), tx, format).await;
 
     // Notify plugins of new connection
     {
@@ -111,30 +320,61 @@ async fn handle_websocket(
         }
     }
 
-    // Spawn task to handle outgoing messages
-    let client_id_clone = client_id.clone();
+    // Spawn task to handle outgoing messages, pinging between them
+    let mut ping = tokio::time::interval(Duration::from_secs(heartbeat.ping_interval_secs));
+    ping.reset();
     tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
+        loop {
+            let msg = tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = ping.tick() => Message::ping(Vec::new()),
+            };
             if ws_tx.send(msg).await.is_err() {
                 break;
             }
         }
     });
 
-    // Handle incoming messages
-    while let Some(result) = ws_rx.next().await {
+    // Handle incoming messages. Any frame, pongs included, proves the
+    // socket is alive; silence past the idle timeout means it isn't.
+    let idle_timeout = Duration::from_secs(heartbeat.idle_timeout_secs);
+    loop {
+        let result = match tokio::time::timeout(idle_timeout, ws_rx.next()).await {
+            Ok(Some(result)) => result,
+            Ok(None) => break,
+            Err(_) => {
+                info!("Client {} went silent for {:?}; dropping the connection", client_id, idle_timeout);
+                break;
+            }
+        };
         match result {
             Ok(msg) => {
-                if let Ok(text) = msg.to_str() {
-                    if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(text) {
-                        // Route message to appropriate plugin
-                        let registry = plugins.read().await;
-                        for (_, plugin) in &registry.plugins {
-                            if let Some(response) = plugin.handle_message(&client_id, client_msg.clone()).await {
-                                let response_text = serde_json::to_string(&response).unwrap();
-                                let _ = clients.send_to_client(&client_id, Message::text(response_text)).await;
+                // Text frames are always JSON; binary frames use the negotiated format
+                let decoded = if msg.is_text() {
+                    msg.to_str().ok().and_then(|text| WireFormat::decode_text::<ClientMessage>(text).ok())
+                } else if msg.is_binary() {
+                    format.decode::<ClientMessage>(msg.as_bytes()).ok()
+                } else {
+                    None
+                };
+                if let Some(client_msg) = decoded {
+                    // Route message to the plugin owning its action
+                    let registry = plugins.read().await;
+                    let response = match registry.route(&client_msg.action) {
+                        Some((name, plugin)) => {
+                            let response = plugin.handle_message(&client_id, client_msg).await;
+                            if response.is_some() {
+                                registry.router.record_reply(name);
                             }
+                            response
                         }
+                        None => Some(unknown_action(&client_msg)),
+                    };
+                    if let Some(frame) = response.and_then(|response| encode_frame(format, &response)) {
+                        let _ = clients.send_to_client(&client_id, frame).await;
                     }
                 }
             }
@@ -152,29 +392,181 @@ async fn handle_websocket(
             plugin.on_disconnect(&client_id).await;
         }
     }
+
+    // Connections are anonymous here, so the client id stands in for the player
+    let event = Event::new(EventType::Player(PlayerEvent::Disconnected {
+        player_id: finalverse_events::PlayerId::from_legacy(&client_id),
+    }))
+    .with_metadata(EventMetadata {
+        source: Some("realtime-gateway".to_string()),
+        ..Default::default()
+    });
+    if let Err(e) = event_bus.publish(event).await {
+        warn!("Failed to publish disconnect for client {}: {}", client_id, e);
+    }
 }
 
 #[tokio::main]
 async fn main() {
     logging::init(None);
 
-    let clients = Arc::new(ConnectionManager::new());
+    // With REDIS_URL set, instances behind a load balancer share broadcasts
+    let clients = match std::env::var("REDIS_URL").map(redis::Client::open) {
+        Ok(Ok(client)) => {
+            info!("📣 Fanning out broadcasts through Redis");
+            let fanout = RedisFanout::new(client, &finalverse_core::TenantId::from_env());
+            ConnectionManager::new().with_fanout(Arc::new(fanout))
+        }
+        Ok(Err(e)) => {
+            warn!("Invalid REDIS_URL, broadcasts stay on this instance: {}", e);
+            ConnectionManager::new()
+        }
+        Err(_) => ConnectionManager::new(),
+    };
+    let clients = Arc::new(clients);
+    clients.start_fanout();
     let plugins = Arc::new(RwLock::new(PluginRegistry::new()));
+    if let Err(e) = plugins.write().await.register(Arc::new(EchoPlugin)) {
+        warn!("Failed to register echo plugin: {}", e);
+    }
+    if let Err(e) = plugins.write().await.register(Arc::new(RegionPlugin { clients: clients.clone() })) {
+        warn!("Failed to register region plugin: {}", e);
+    }
+    let heartbeat = finalverse_config::load_default_config()
+        .map(|config| config.network.heartbeat)
+        .unwrap_or_default();
+    let event_bus: Arc<dyn GameEventBus> = match std::env::var("NATS_URL") {
+        Ok(nats_url) => match NatsEventBus::new(&nats_url).await {
+            Ok(bus) => {
+                info!("📡 Connected to NATS at {}", nats_url);
+                Arc::new(bus)
+            }
+            Err(e) => {
+                warn!("Failed to connect to NATS at {}, using local event bus: {}", nats_url, e);
+                Arc::new(LocalEventBus::new())
+            }
+        },
+        Err(_) => {
+            info!("📦 Using local event bus");
+            Arc::new(LocalEventBus::new())
+        }
+    };
+    let event_bus = TenantEventBus::from_env(event_bus);
+    let debug = Arc::new(DebugEndpoints::load("realtime-gateway").with_state(clients.clone())).warp_routes();
+
+    // Per-plugin routing counters
+    let metrics_plugins = plugins.clone();
+    let plugin_metrics_route = warp::path!("plugins" / "metrics")
+        .and(warp::get())
+        .and_then(move || {
+            let plugins = metrics_plugins.clone();
+            async move { Ok::<_, warp::Rejection>(warp::reply::json(&plugins.read().await.metrics())) }
+        });
+
+    // Other services reach clients on every instance through here
+    let broadcast_clients = clients.clone();
+    let broadcast_route = warp::path("broadcast")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |request: BroadcastRequest| {
+            let clients = broadcast_clients.clone();
+            async move {
+                let audience = match request.region_id {
+                    Some(region_id) => Audience::Region { region_id },
+                    None => Audience::All,
+                };
+                let message = ServerMessage {
+                    id: Uuid::new_v4().to_string(),
+                    event: request.event,
+                    payload: request.payload,
+                };
+                let delivered = clients.send(audience, message).await;
+                Ok::<_, warp::Rejection>(warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({ "delivered_here": delivered })),
+                    warp::http::StatusCode::ACCEPTED,
+                ))
+            }
+        });
 
     // WebSocket route
     let ws_route = warp::path("ws")
         .and(warp::ws())
         .and(warp::any().map(move || clients.clone()))
         .and(warp::any().map(move || plugins.clone()))
-        .map(|ws: warp::ws::Ws, clients, plugins| {
-            ws.on_upgrade(move |websocket| handle_websocket(websocket, clients, plugins))
+        .and(warp::any().map(move || heartbeat.clone()))
+        .and(warp::any().map(move || event_bus.clone()))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|ws: warp::ws::Ws, clients, plugins, heartbeat, event_bus, query: HashMap<String, String>| {
+            // `?format=msgpack` switches the connection to binary frames
+            match WireFormat::negotiate(query.get("format").map(String::as_str)) {
+                Ok(format) => warp::Reply::into_response(ws.on_upgrade(move |websocket| {
+                    handle_websocket(websocket, clients, plugins, format, heartbeat, event_bus)
+                })),
+                Err(e) => warp::Reply::into_response(warp::reply::with_status(
+                    e.to_string(),
+                    warp::http::StatusCode::BAD_REQUEST,
+                )),
+            }
         });
 
     // Health check endpoint
     let health_route = warp::path("health")
         .map(|| warp::reply::json(&serde_json::json!({"status": "ok"})));
 
-    let routes = ws_route.or(health_route);
+    let routes = ws_route.or(health_route).or(plugin_metrics_route).or(broadcast_route).or(debug);
+
+    // Proxy mode: forward /proxy sessions to an upstream gateway under
+    // simulated network conditions, adjustable through /admin/netsim by
+    // operators.
+    let routes = match std::env::var("NETSIM_UPSTREAM") {
+        Ok(upstream) => {
+            info!("🐢 Network simulation proxy forwarding to {}", upstream);
+            let net_sim = Arc::new(NetSim::new(upstream));
+            let net_sim_filter = warp::any().map(move || net_sim.clone());
+
+            let proxy_route = warp::path("proxy")
+                .and(warp::ws())
+                .and(net_sim_filter.clone())
+                .map(|ws: warp::ws::Ws, net_sim: Arc<NetSim>| {
+                    ws.on_upgrade(move |websocket| net_sim.proxy(websocket))
+                });
+
+            let get_config = warp::path!("admin" / "netsim")
+                .and(warp::get())
+                .and(net_sim_filter.clone())
+                .and_then(|net_sim: Arc<NetSim>| async move {
+                    Ok::<_, warp::Rejection>(warp::reply::json(&net_sim.config().await))
+                });
+
+            let set_config = warp::path!("admin" / "netsim")
+                .and(warp::put())
+                .and(OperatorGuard::load().warp_filter())
+                .and(warp::body::json())
+                .and(net_sim_filter)
+                .and_then(|config: NetSimConfig, net_sim: Arc<NetSim>| async move {
+                    let reply = match net_sim.set_config(config).await {
+                        Ok(()) => warp::reply::with_status(
+                            warp::reply::json(&net_sim.config().await),
+                            warp::http::StatusCode::OK,
+                        ),
+                        Err(e) => warp::reply::with_status(
+                            warp::reply::json(&serde_json::json!({"error": e})),
+                            warp::http::StatusCode::BAD_REQUEST,
+                        ),
+                    };
+                    Ok::<_, warp::Rejection>(reply)
+                });
+
+            routes
+                .or(proxy_route)
+                .or(get_config)
+                .or(set_config)
+                .recover(finalverse_core::warp::recover)
+                .map(warp::Reply::into_response)
+                .boxed()
+        }
+        Err(_) => routes.map(warp::Reply::into_response).boxed(),
+    };
 
     info!("🌐 Realtime Gateway starting on port 3000");
     warp::serve(routes)
@@ -182,6 +574,13 @@ async fn main() {
         .await;
 }
 
+#[async_trait::async_trait]
+impl DebugSource for ConnectionManager {
+    async fn debug_state(&self) -> serde_json::Value {
+        serde_json::json!({ "connected_clients": self.client_count().await })
+    }
+}
+
 // Example plugin implementation
 pub struct EchoPlugin;
 
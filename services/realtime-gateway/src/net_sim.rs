@@ -0,0 +1,230 @@
+// services/realtime-gateway/src/net_sim.rs
+// Bad-network simulation proxy for client development
+
+use futures::{SinkExt, Stream, StreamExt};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message as UpstreamMessage};
+use tracing::{info, warn};
+use warp::ws::{Message, WebSocket};
+
+/// Network conditions applied to traffic flowing in one direction.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectionProfile {
+    pub latency_ms: u64,
+    /// Random delay added or removed from the base latency.
+    pub jitter_ms: u64,
+    /// Chance (0.0 - 1.0) that a message is silently dropped.
+    pub drop_rate: f64,
+    /// Chance (0.0 - 1.0) that a message is held back and overtaken by
+    /// messages sent after it.
+    pub reorder_rate: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetSimConfig {
+    /// When false, traffic is forwarded untouched.
+    pub enabled: bool,
+    pub client_to_server: DirectionProfile,
+    pub server_to_client: DirectionProfile,
+}
+
+impl NetSimConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, profile) in [
+            ("client_to_server", &self.client_to_server),
+            ("server_to_client", &self.server_to_client),
+        ] {
+            if !(0.0..=1.0).contains(&profile.drop_rate) || !(0.0..=1.0).contains(&profile.reorder_rate) {
+                return Err(format!("{}: rates must be between 0.0 and 1.0", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    ClientToServer,
+    ServerToClient,
+}
+
+/// Message shape shared by both sides of the proxy.
+#[derive(Debug, Clone)]
+enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+    Close,
+}
+
+impl Frame {
+    fn from_client(message: Message) -> Option<Self> {
+        if message.is_close() {
+            Some(Frame::Close)
+        } else if let Ok(text) = message.to_str() {
+            Some(Frame::Text(text.to_string()))
+        } else if message.is_binary() {
+            Some(Frame::Binary(message.into_bytes()))
+        } else {
+            None
+        }
+    }
+
+    fn from_upstream(message: UpstreamMessage) -> Option<Self> {
+        match message {
+            UpstreamMessage::Text(text) => Some(Frame::Text(text)),
+            UpstreamMessage::Binary(bytes) => Some(Frame::Binary(bytes)),
+            UpstreamMessage::Close(_) => Some(Frame::Close),
+            _ => None,
+        }
+    }
+
+    fn into_client(self) -> Message {
+        match self {
+            Frame::Text(text) => Message::text(text),
+            Frame::Binary(bytes) => Message::binary(bytes),
+            Frame::Close => Message::close(),
+        }
+    }
+
+    fn into_upstream(self) -> UpstreamMessage {
+        match self {
+            Frame::Text(text) => UpstreamMessage::Text(text),
+            Frame::Binary(bytes) => UpstreamMessage::Binary(bytes),
+            Frame::Close => UpstreamMessage::Close(None),
+        }
+    }
+}
+
+/// Forwards WebSocket sessions to an upstream gateway while applying the
+/// configured latency, jitter, drops and reordering.
+pub struct NetSim {
+    upstream: String,
+    config: RwLock<NetSimConfig>,
+}
+
+impl NetSim {
+    pub fn new(upstream: String) -> Self {
+        Self {
+            upstream,
+            config: RwLock::new(NetSimConfig::default()),
+        }
+    }
+
+    pub async fn config(&self) -> NetSimConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn set_config(&self, config: NetSimConfig) -> Result<(), String> {
+        config.validate()?;
+        info!("🐢 Network simulation updated: {:?}", config);
+        *self.config.write().await = config;
+        Ok(())
+    }
+
+    pub async fn proxy(self: Arc<Self>, client: WebSocket) {
+        let upstream = match connect_async(self.upstream.as_str()).await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!("Proxy failed to reach upstream {}: {}", self.upstream, e);
+                return;
+            }
+        };
+
+        let (mut client_tx, client_rx) = client.split();
+        let (mut upstream_tx, upstream_rx) = upstream.split();
+        let (to_upstream, mut upstream_queue) = mpsc::unbounded_channel::<Frame>();
+        let (to_client, mut client_queue) = mpsc::unbounded_channel::<Frame>();
+
+        tokio::spawn(async move {
+            while let Some(frame) = upstream_queue.recv().await {
+                let closing = matches!(frame, Frame::Close);
+                if upstream_tx.send(frame.into_upstream()).await.is_err() || closing {
+                    break;
+                }
+            }
+        });
+        tokio::spawn(async move {
+            while let Some(frame) = client_queue.recv().await {
+                let closing = matches!(frame, Frame::Close);
+                if client_tx.send(frame.into_client()).await.is_err() || closing {
+                    break;
+                }
+            }
+        });
+
+        let inbound = client_rx.filter_map(|message| async move { message.ok().and_then(Frame::from_client) });
+        let outbound = upstream_rx.filter_map(|message| async move { message.ok().and_then(Frame::from_upstream) });
+
+        // Once either side goes away the other pump is dropped; the writers
+        // finish on their own after any delayed frames have been delivered.
+        tokio::select! {
+            _ = self.clone().pump(inbound, to_upstream, Direction::ClientToServer) => {}
+            _ = self.clone().pump(outbound, to_client, Direction::ServerToClient) => {}
+        }
+    }
+
+    async fn pump(
+        self: Arc<Self>,
+        frames: impl Stream<Item = Frame>,
+        sink: mpsc::UnboundedSender<Frame>,
+        direction: Direction,
+    ) {
+        futures::pin_mut!(frames);
+        // Delivery time of the last in-order message, so that jitter alone
+        // never reorders traffic.
+        let mut last_delivery = Instant::now();
+
+        while let Some(frame) = frames.next().await {
+            let closing = matches!(frame, Frame::Close);
+            let config = self.config.read().await.clone();
+            if !config.enabled {
+                if sink.send(frame).is_err() || closing {
+                    break;
+                }
+                continue;
+            }
+
+            let profile = match direction {
+                Direction::ClientToServer => &config.client_to_server,
+                Direction::ServerToClient => &config.server_to_client,
+            };
+            let (delay, dropped, reordered) = {
+                let mut rng = rand::thread_rng();
+                let jitter = profile.jitter_ms as i64;
+                let offset = if jitter > 0 { rng.gen_range(-jitter..=jitter) } else { 0 };
+                let delay = (profile.latency_ms as i64 + offset).max(0) as u64;
+                let dropped = !closing && rng.gen_bool(profile.drop_rate);
+                let reordered = !closing && rng.gen_bool(profile.reorder_rate);
+                (delay, dropped, reordered)
+            };
+            if dropped {
+                continue;
+            }
+
+            let now = Instant::now();
+            let deliver_at = if reordered {
+                // Hold the message back long enough for later ones to pass it.
+                now + Duration::from_millis(delay + profile.latency_ms + profile.jitter_ms + 50)
+            } else {
+                last_delivery = last_delivery.max(now + Duration::from_millis(delay));
+                last_delivery
+            };
+
+            let sink = sink.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep_until(deliver_at).await;
+                let _ = sink.send(frame);
+            });
+
+            if closing {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// services/realtime-gateway/src/routing.rs
+// Routes client actions to plugins by namespace
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What a plugin asks to receive. Patterns are an exact action
+/// (`echo`), a namespace (`chat.*`, which matches `chat.say` and
+/// `chat.party.say`), or `*` for everything nothing else claims.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ActionPattern {
+    Exact(String),
+    Namespace(String),
+    CatchAll,
+}
+
+impl ActionPattern {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.trim();
+        if pattern == "*" {
+            return Ok(Self::CatchAll);
+        }
+        let (name, namespace) = match pattern.strip_suffix(".*") {
+            Some(prefix) => (prefix, true),
+            None => (pattern, false),
+        };
+        if name.is_empty() || name.split('.').any(|part| part.is_empty() || part.contains('*')) {
+            return Err(format!("invalid action pattern '{}'", pattern));
+        }
+        Ok(if namespace {
+            Self::Namespace(name.to_string())
+        } else {
+            Self::Exact(name.to_string())
+        })
+    }
+
+    fn matches(&self, action: &str) -> bool {
+        match self {
+            Self::Exact(name) => action == name,
+            Self::Namespace(prefix) => action
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.starts_with('.') && rest.len() > 1),
+            Self::CatchAll => true,
+        }
+    }
+
+    /// Higher wins: exact names, then deeper namespaces, then the catch-all.
+    fn specificity(&self) -> usize {
+        match self {
+            Self::Exact(_) => usize::MAX,
+            Self::Namespace(prefix) => 1 + prefix.split('.').count(),
+            Self::CatchAll => 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PluginCounters {
+    received: AtomicU64,
+    replied: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginMetrics {
+    pub plugin: String,
+    pub patterns: Vec<String>,
+    pub received: u64,
+    pub replied: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingMetrics {
+    pub plugins: Vec<PluginMetrics>,
+    /// Messages whose action no plugin claimed.
+    pub unrouted: u64,
+}
+
+/// Maps action patterns to plugin names. Each pattern belongs to one
+/// plugin, so every message goes to at most one plugin.
+#[derive(Debug, Default)]
+pub struct ActionRouter {
+    routes: Vec<(ActionPattern, String)>,
+    patterns: HashMap<String, Vec<String>>,
+    counters: HashMap<String, PluginCounters>,
+    unrouted: AtomicU64,
+}
+
+impl ActionRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claims `patterns` for `plugin`. Nothing is registered if any
+    /// pattern is malformed or already claimed by another plugin.
+    pub fn register(&mut self, plugin: &str, patterns: &[String]) -> Result<(), String> {
+        let mut added = Vec::with_capacity(patterns.len());
+        for raw in patterns {
+            let pattern = ActionPattern::parse(raw)?;
+            if let Some((_, owner)) = self.routes.iter().find(|(existing, _)| *existing == pattern) {
+                if owner != plugin {
+                    return Err(format!("'{}' is already routed to plugin '{}'", raw.trim(), owner));
+                }
+                continue;
+            }
+            added.push((raw.trim().to_string(), pattern));
+        }
+        let claimed = self.patterns.entry(plugin.to_string()).or_default();
+        for (raw, pattern) in added {
+            claimed.push(raw);
+            self.routes.push((pattern, plugin.to_string()));
+        }
+        self.routes.sort_by_key(|(pattern, _)| std::cmp::Reverse(pattern.specificity()));
+        self.counters.entry(plugin.to_string()).or_default();
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, plugin: &str) {
+        self.routes.retain(|(_, owner)| owner != plugin);
+        self.patterns.remove(plugin);
+        self.counters.remove(plugin);
+    }
+
+    /// The plugin that should handle `action`, counting the message
+    /// against it, or `None` after counting it as unrouted.
+    pub fn route(&self, action: &str) -> Option<&str> {
+        match self.routes.iter().find(|(pattern, _)| pattern.matches(action)) {
+            Some((_, plugin)) => {
+                if let Some(counters) = self.counters.get(plugin) {
+                    counters.received.fetch_add(1, Ordering::Relaxed);
+                }
+                Some(plugin)
+            }
+            None => {
+                self.unrouted.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub fn record_reply(&self, plugin: &str) {
+        if let Some(counters) = self.counters.get(plugin) {
+            counters.replied.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn metrics(&self) -> RoutingMetrics {
+        let mut plugins: Vec<PluginMetrics> = self
+            .counters
+            .iter()
+            .map(|(plugin, counters)| PluginMetrics {
+                plugin: plugin.clone(),
+                patterns: self.patterns.get(plugin).cloned().unwrap_or_default(),
+                received: counters.received.load(Ordering::Relaxed),
+                replied: counters.replied.load(Ordering::Relaxed),
+            })
+            .collect();
+        plugins.sort_by(|a, b| a.plugin.cmp(&b.plugin));
+        RoutingMetrics {
+            plugins,
+            unrouted: self.unrouted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|pattern| pattern.to_string()).collect()
+    }
+
+    #[test]
+    fn most_specific_pattern_wins() {
+        let mut router = ActionRouter::new();
+        router.register("chat", &patterns(&["chat.*"])).unwrap();
+        router.register("party", &patterns(&["chat.party.*", "party"])).unwrap();
+        router.register("fallback", &patterns(&["*"])).unwrap();
+
+        assert_eq!(router.route("chat.say"), Some("chat"));
+        assert_eq!(router.route("chat.party.say"), Some("party"));
+        assert_eq!(router.route("party"), Some("party"));
+        assert_eq!(router.route("chat"), Some("fallback"));
+
+        router.unregister("fallback");
+        assert_eq!(router.route("chatter.say"), None);
+        router.record_reply("chat");
+
+        let metrics = router.metrics();
+        assert_eq!(metrics.unrouted, 1);
+        let chat = metrics.plugins.iter().find(|plugin| plugin.plugin == "chat").unwrap();
+        assert_eq!((chat.received, chat.replied), (1, 1));
+    }
+
+    #[test]
+    fn conflicting_or_malformed_patterns_are_rejected() {
+        let mut router = ActionRouter::new();
+        router.register("chat", &patterns(&["chat.*"])).unwrap();
+        assert!(router.register("other", &patterns(&["emote.*", "chat.*"])).is_err());
+        assert_eq!(router.route("emote.wave"), None);
+        assert!(router.register("other", &patterns(&["chat.*.say"])).is_err());
+        assert!(router.register("other", &patterns(&[".*"])).is_err());
+        // Re-registering your own pattern is harmless
+        router.register("chat", &patterns(&["chat.*"])).unwrap();
+    }
+}
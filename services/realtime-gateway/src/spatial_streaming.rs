@@ -1,26 +1,143 @@
 // services/realtime-gateway/src/spatial_streaming.rs
 
 use dashmap::DashMap;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use finalverse_config::InterestConfig;
+use finalverse_core::{MarkerId, NpcPresence, ObjectiveMarker};
 use finalverse_world3d::{GridCoordinate, Position3D, PlayerId, grid::Grid, entities::Entity};
 use finalverse_world3d::EntityId;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
 
+#[derive(Default)]
 pub struct ObjectCache;
 
+/// Which entities each client hears about and how often.
+#[derive(Debug, Clone)]
+pub struct InterestPolicy {
+    pub radius: f32,
+    pub update_interval: Duration,
+}
+
+impl InterestPolicy {
+    pub fn from_config(config: &InterestConfig) -> Self {
+        Self {
+            radius: config.radius,
+            // Validation rejects non-positive rates; the floor only keeps
+            // an unvalidated config from dividing by zero
+            update_interval: Duration::from_secs_f32(1.0 / config.update_rate_hz.max(0.01)),
+        }
+    }
+
+    /// The policy from `network.interest` in `FinalverseConfig`.
+    pub fn load() -> Self {
+        let config = finalverse_config::load_default_config()
+            .map(|config| config.network.interest)
+            .unwrap_or_default();
+        Self::from_config(&config)
+    }
+}
+
+impl Default for InterestPolicy {
+    fn default() -> Self {
+        Self::from_config(&InterestConfig::default())
+    }
+}
+
+/// An entity's fields as a client last received them.
+struct SentEntity {
+    kind: String,
+    fields: Map<String, Value>,
+}
+
+#[derive(Default)]
+struct ClientInterest {
+    sent: HashMap<EntityId, SentEntity>,
+    last_update: Option<Instant>,
+}
+
+/// Changes to the entities around one player since their last update.
+#[derive(Clone, Serialize)]
+pub struct InterestUpdate {
+    pub player_id: PlayerId,
+    /// Entities that came into range, in full.
+    pub entered: Vec<Entity>,
+    /// Entities that went out of range or no longer exist.
+    pub left: Vec<EntityId>,
+    /// Entities still in range that changed.
+    pub changed: Vec<EntityDelta>,
+}
+
+impl InterestUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.entered.is_empty() && self.left.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The fields of an entity that changed; fields it no longer has are null.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityDelta {
+    pub id: EntityId,
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Default)]
 pub struct SpatialStreamManager {
     player_positions: DashMap<PlayerId, Position3D>,
     grid_subscribers: DashMap<GridCoordinate, HashSet<PlayerId>>,
     object_cache: ObjectCache,
+    /// Objective markers by the quest they belong to.
+    quest_markers: DashMap<Uuid, Vec<ObjectiveMarker>>,
+    /// Quests each player currently has active; markers only go to these players.
+    active_quests: DashMap<PlayerId, HashSet<Uuid>>,
+    /// Markers each player's client is currently showing.
+    delivered_markers: DashMap<PlayerId, HashSet<MarkerId>>,
+    /// Background NPCs by the grid they are in, as last reported by
+    /// procedural generation.
+    npc_presences: DashMap<GridCoordinate, Vec<NpcPresence>>,
+    /// Entities the gateway streams, as last reported by the world.
+    entities: DashMap<EntityId, Entity>,
+    /// What each player's client was last sent about nearby entities.
+    interests: DashMap<PlayerId, ClientInterest>,
+    interest: InterestPolicy,
 }
 
 pub struct StreamUpdate {
     pub load_grids: Vec<Grid>,
     pub unload_grids: Vec<GridCoordinate>,
-    pub nearby_entities: Vec<Entity>,
+    /// Nearby entity changes, when the player is due an update.
+    pub interest: Option<InterestUpdate>,
     pub lod_updates: Vec<(EntityId, u8)>,
+    /// Markers the client should start showing.
+    pub markers: Vec<ObjectiveMarker>,
+    /// Markers the client should remove.
+    pub cleared_markers: Vec<MarkerId>,
+    /// Schedule-derived positions of background NPCs in visible grids.
+    pub npcs: Vec<NpcPresence>,
+}
+
+/// Marker changes for one player outside of movement updates.
+pub struct MarkerUpdate {
+    pub player_id: PlayerId,
+    pub markers: Vec<ObjectiveMarker>,
+    pub cleared_markers: Vec<MarkerId>,
 }
 
 impl SpatialStreamManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_interest(self, interest: InterestPolicy) -> Self {
+        Self { interest, ..self }
+    }
+
+    pub fn interest(&self) -> &InterestPolicy {
+        &self.interest
+    }
+
     pub async fn handle_player_movement(
         &self,
         player_id: PlayerId,
@@ -36,16 +153,209 @@ impl SpatialStreamManager {
         let grids_to_unload = old_grids.difference(&new_grids);
 
         // Update subscriptions
-        self.update_grid_subscriptions(player_id, &new_grids).await;
+        self.player_positions.insert(player_id.clone(), new_position);
+        self.update_grid_subscriptions(player_id.clone(), &new_grids).await;
+        let (markers, cleared_markers) = self.sync_markers(player_id.clone(), &new_grids);
 
         StreamUpdate {
             load_grids: self.get_grid_data(grids_to_load).await,
             unload_grids: grids_to_unload.cloned().collect(),
-            nearby_entities: self.get_nearby_entities(new_position).await,
+            interest: self.interest_update(player_id, Instant::now()),
             lod_updates: self.calculate_lod_changes(new_position).await,
+            markers,
+            cleared_markers,
+            npcs: self.npcs_in(&new_grids),
+        }
+    }
+
+    /// Forget a disconnected player.
+    pub fn remove_player(&self, player_id: PlayerId) {
+        self.player_positions.remove(&player_id);
+        self.interests.remove(&player_id);
+        self.active_quests.remove(&player_id);
+        self.delivered_markers.remove(&player_id);
+        for mut subscribers in self.grid_subscribers.iter_mut() {
+            subscribers.remove(&player_id);
+        }
+    }
+
+    /// Add an entity or replace its state. Clients hear about it on their
+    /// next update.
+    pub fn upsert_entity(&self, entity: Entity) {
+        self.entities.insert(entity.get_id(), entity);
+    }
+
+    pub fn remove_entity(&self, entity_id: EntityId) {
+        self.entities.remove(&entity_id);
+    }
+
+    /// Updates for every player due one, to be called each
+    /// `update_interval`.
+    pub fn tick(&self, now: Instant) -> Vec<InterestUpdate> {
+        let players: Vec<PlayerId> = self.player_positions.iter().map(|entry| entry.key().clone()).collect();
+        players
+            .into_iter()
+            .filter_map(|player_id| self.interest_update(player_id, now))
+            .collect()
+    }
+
+    /// Diff the entities in range of a player against what their client
+    /// was last sent. `None` when nothing changed or the player had an
+    /// update less than `update_interval` ago.
+    pub fn interest_update(&self, player_id: PlayerId, now: Instant) -> Option<InterestUpdate> {
+        let position = *self.player_positions.get(&player_id)?;
+        let mut interest = self.interests.entry(player_id.clone()).or_default();
+        if interest
+            .last_update
+            .is_some_and(|last| now.duration_since(last) < self.interest.update_interval)
+        {
+            return None;
+        }
+        interest.last_update = Some(now);
+
+        let mut update = InterestUpdate {
+            player_id,
+            entered: Vec::new(),
+            left: Vec::new(),
+            changed: Vec::new(),
+        };
+        let mut in_range = HashSet::new();
+        for entry in self.entities.iter() {
+            let entity = entry.value();
+            if entity.get_position().distance_to(&position) > self.interest.radius {
+                continue;
+            }
+            let id = *entry.key();
+            in_range.insert(id);
+            let (kind, fields) = entity_fields(entity);
+            match interest.sent.get(&id) {
+                // Same kind of entity: only what changed
+                Some(sent) if sent.kind == kind => {
+                    let delta = field_delta(&sent.fields, &fields);
+                    if !delta.is_empty() {
+                        update.changed.push(EntityDelta { id, fields: delta });
+                    }
+                }
+                _ => update.entered.push(entity.clone()),
+            }
+            interest.sent.insert(id, SentEntity { kind, fields });
+        }
+        interest.sent.retain(|id, _| {
+            let keep = in_range.contains(id);
+            if !keep {
+                update.left.push(*id);
+            }
+            keep
+        });
+
+        (!update.is_empty()).then_some(update)
+    }
+
+    /// Replace the known background NPC positions. NPCs move on their own
+    /// schedule, so this is called periodically rather than on movement.
+    pub fn refresh_npcs(&self, presences: Vec<NpcPresence>) {
+        self.npc_presences.clear();
+        for presence in presences {
+            self.npc_presences.entry(coordinates_grid(&presence.position)).or_default().push(presence);
         }
     }
 
+    /// Background NPCs a player can currently see, for periodic refreshes
+    /// between movement updates.
+    pub fn npcs_for(&self, player_id: PlayerId) -> Vec<NpcPresence> {
+        self.npcs_in(&self.get_visible_grids(self.player_positions.get(&player_id).map(|p| *p)))
+    }
+
+    fn npcs_in(&self, grids: &HashSet<GridCoordinate>) -> Vec<NpcPresence> {
+        grids
+            .iter()
+            .filter_map(|grid| self.npc_presences.get(grid))
+            .flat_map(|presences| presences.value().clone())
+            .collect()
+    }
+
+    /// Track a quest being accepted or dropped by a player.
+    pub fn set_quest_active(&self, player_id: PlayerId, quest_id: Uuid, active: bool) -> MarkerUpdate {
+        if active {
+            self.active_quests.entry(player_id.clone()).or_default().insert(quest_id);
+        } else if let Some(mut quests) = self.active_quests.get_mut(&player_id) {
+            quests.remove(&quest_id);
+        }
+        self.marker_update(player_id)
+    }
+
+    /// Add or replace a marker. Returns updates for every player who has
+    /// the quest active and can see the marker's grid.
+    pub fn place_marker(&self, marker: ObjectiveMarker) -> Vec<MarkerUpdate> {
+        let quest_id = marker.quest_id;
+        {
+            let mut markers = self.quest_markers.entry(quest_id).or_default();
+            markers.retain(|existing| existing.id != marker.id);
+            markers.push(marker);
+        }
+        self.updates_for_quest(quest_id)
+    }
+
+    /// Drop the markers of a completed objective from every client showing them.
+    pub fn complete_objective(&self, quest_id: Uuid, objective_id: Uuid) -> Vec<MarkerUpdate> {
+        if let Some(mut markers) = self.quest_markers.get_mut(&quest_id) {
+            markers.retain(|marker| marker.objective_id != objective_id);
+        }
+        self.updates_for_quest(quest_id)
+    }
+
+    /// Drop every marker of a finished or abandoned quest.
+    pub fn clear_quest(&self, quest_id: Uuid) -> Vec<MarkerUpdate> {
+        self.quest_markers.remove(&quest_id);
+        let updates = self.updates_for_quest(quest_id);
+        for mut quests in self.active_quests.iter_mut() {
+            quests.remove(&quest_id);
+        }
+        updates
+    }
+
+    fn updates_for_quest(&self, quest_id: Uuid) -> Vec<MarkerUpdate> {
+        let players: Vec<PlayerId> = self
+            .active_quests
+            .iter()
+            .filter(|entry| entry.value().contains(&quest_id))
+            .map(|entry| entry.key().clone())
+            .collect();
+        players
+            .into_iter()
+            .map(|player_id| self.marker_update(player_id))
+            .filter(|update| !update.markers.is_empty() || !update.cleared_markers.is_empty())
+            .collect()
+    }
+
+    fn marker_update(&self, player_id: PlayerId) -> MarkerUpdate {
+        let grids = self.get_visible_grids(self.player_positions.get(&player_id).map(|p| *p));
+        let (markers, cleared_markers) = self.sync_markers(player_id.clone(), &grids);
+        MarkerUpdate { player_id, markers, cleared_markers }
+    }
+
+    /// Diff the markers a player should see against what their client
+    /// already shows, and record the new state as delivered.
+    fn sync_markers(&self, player_id: PlayerId, grids: &HashSet<GridCoordinate>) -> (Vec<ObjectiveMarker>, Vec<MarkerId>) {
+        let now = chrono::Utc::now();
+        let visible: Vec<ObjectiveMarker> = match self.active_quests.get(&player_id) {
+            Some(quests) => quests
+                .iter()
+                .filter_map(|quest_id| self.quest_markers.get(quest_id))
+                .flat_map(|markers| markers.value().clone())
+                .filter(|marker| !marker.is_expired(now) && grids.contains(&marker_grid(marker)))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut delivered = self.delivered_markers.entry(player_id).or_default();
+        let visible_ids: HashSet<MarkerId> = visible.iter().map(|marker| marker.id).collect();
+        let cleared = delivered.difference(&visible_ids).copied().collect();
+        let added = visible.into_iter().filter(|marker| !delivered.contains(&marker.id)).collect();
+        *delivered = visible_ids;
+        (added, cleared)
+    }
+
     fn get_visible_grids(&self, position: Option<Position3D>) -> HashSet<GridCoordinate> {
         let mut grids = HashSet::new();
         if let Some(pos) = position {
@@ -58,10 +368,6 @@ impl SpatialStreamManager {
         Vec::new()
     }
 
-    async fn get_nearby_entities(&self, _pos: Position3D) -> Vec<Entity> {
-        Vec::new()
-    }
-
     async fn calculate_lod_changes(&self, _pos: Position3D) -> Vec<(EntityId, u8)> {
         Vec::new()
     }
@@ -69,3 +375,106 @@ impl SpatialStreamManager {
     async fn update_grid_subscriptions(&self, _player: PlayerId, _grids: &HashSet<GridCoordinate>) {
     }
 }
+
+/// An entity's variant name and fields as serialized for clients.
+fn entity_fields(entity: &Entity) -> (String, Map<String, Value>) {
+    match serde_json::to_value(entity) {
+        Ok(Value::Object(tagged)) => match tagged.into_iter().next() {
+            Some((kind, Value::Object(fields))) => (kind, fields),
+            _ => Default::default(),
+        },
+        _ => Default::default(),
+    }
+}
+
+fn field_delta(old: &Map<String, Value>, new: &Map<String, Value>) -> Map<String, Value> {
+    let mut delta: Map<String, Value> = new
+        .iter()
+        .filter(|(key, value)| old.get(*key) != Some(value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        delta.insert(key.clone(), Value::Null);
+    }
+    delta
+}
+
+fn marker_grid(marker: &ObjectiveMarker) -> GridCoordinate {
+    coordinates_grid(&marker.position)
+}
+
+fn coordinates_grid(position: &finalverse_core::Coordinates) -> GridCoordinate {
+    Position3D {
+        x: position.x as f32,
+        y: position.y as f32,
+        z: position.z as f32,
+    }
+    .to_grid_coordinate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finalverse_core::{Coordinates, MarkerIcon};
+    use finalverse_world3d::entities::CreatureEntity;
+
+    #[tokio::test]
+    async fn markers_follow_active_quests_and_clear_on_completion() {
+        let streams = SpatialStreamManager::new();
+        let (quest, objective) = (Uuid::new_v4(), Uuid::new_v4());
+        let on_quest = PlayerId(Uuid::new_v4());
+        let bystander = PlayerId(Uuid::new_v4());
+        let here = Position3D { x: 10.0, y: 10.0, z: 0.0 };
+        streams.handle_player_movement(on_quest.clone(), here).await;
+        streams.handle_player_movement(bystander.clone(), here).await;
+        streams.set_quest_active(on_quest.clone(), quest, true);
+
+        let marker = ObjectiveMarker::new(quest, objective, Coordinates { x: 20.0, y: 20.0, z: 0.0 }, 5.0, MarkerIcon::Destination);
+        let updates = streams.place_marker(marker.clone());
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].player_id, on_quest);
+        assert_eq!(updates[0].markers[0].id, marker.id);
+        assert!(streams.handle_player_movement(bystander, here).await.markers.is_empty());
+
+        let updates = streams.complete_objective(quest, objective);
+        assert_eq!(updates[0].cleared_markers, vec![marker.id]);
+    }
+
+    #[tokio::test]
+    async fn clients_hear_about_nearby_entities_as_deltas_at_the_update_rate() {
+        let streams = SpatialStreamManager::new().with_interest(InterestPolicy {
+            radius: 50.0,
+            update_interval: Duration::from_millis(100),
+        });
+        let creature = |x: f32, behavior_state: &str| {
+            Entity::Creature(CreatureEntity {
+                id: EntityId(Uuid::nil()),
+                creature_type: "lumifox".to_string(),
+                position: Position3D { x, y: 0.0, z: 0.0 },
+                behavior_state: behavior_state.to_string(),
+            })
+        };
+        let player = PlayerId(Uuid::new_v4());
+        streams.upsert_entity(creature(30.0, "grazing"));
+        let first = streams.handle_player_movement(player, Position3D { x: 0.0, y: 0.0, z: 0.0 }).await;
+        let entered = first.interest.unwrap().entered;
+        assert_eq!(entered.len(), 1);
+        assert_eq!(entered[0].get_id(), EntityId(Uuid::nil()));
+
+        // Changes wait for the next update slot, then only changed fields go out
+        let start = Instant::now();
+        streams.upsert_entity(creature(30.0, "fleeing"));
+        assert!(streams.tick(start).is_empty());
+        let later = start + Duration::from_millis(150);
+        let updates = streams.tick(later);
+        assert_eq!(updates[0].changed[0].fields.len(), 1);
+        assert_eq!(updates[0].changed[0].fields["behavior_state"], "fleeing");
+
+        let later = later + Duration::from_millis(150);
+        assert!(streams.tick(later).is_empty());
+
+        streams.upsert_entity(creature(80.0, "fleeing"));
+        let updates = streams.tick(later + Duration::from_millis(150));
+        assert_eq!(updates[0].left, vec![EntityId(Uuid::nil())]);
+    }
+}
@@ -0,0 +1,162 @@
+// services/service-registry/src/balancing.rs
+// Load-balancing strategies for choosing among a service's healthy instances
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::ServiceInstance;
+
+/// Points each instance gets on the hash ring; more points spread keys
+/// more evenly when instances come and go.
+const VIRTUAL_NODES: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalancing {
+    #[default]
+    Random,
+    RoundRobin,
+    /// The instance with the fewest outstanding [`InstanceLease`]s.
+    LeastConnections,
+    /// The same key always lands on the same instance while it stays
+    /// healthy, and only keys owned by a departed instance move. Lookups
+    /// without a key fall back to round-robin.
+    ConsistentHash,
+}
+
+impl LoadBalancing {
+    /// Index into `instances` of the one to use. `cursor` advances the
+    /// round-robin position and is only read by strategies that need it.
+    pub(crate) fn pick(self, instances: &[ServiceInstance], cursor: &AtomicUsize, key: Option<&str>) -> Option<usize> {
+        if instances.is_empty() {
+            return None;
+        }
+        let round_robin = || cursor.fetch_add(1, Ordering::Relaxed) % instances.len();
+        let index = match (self, key) {
+            (LoadBalancing::Random, _) => rand::random::<usize>() % instances.len(),
+            (LoadBalancing::RoundRobin, _) | (LoadBalancing::ConsistentHash, None) => round_robin(),
+            (LoadBalancing::LeastConnections, _) => (0..instances.len())
+                .min_by_key(|&i| instances[i].active_leases())
+                .unwrap_or(0),
+            (LoadBalancing::ConsistentHash, Some(key)) => HashRing::new(instances).owner(key),
+        };
+        Some(index)
+    }
+}
+
+/// Consistent-hash ring over instance ids.
+struct HashRing {
+    points: BTreeMap<u64, usize>,
+}
+
+impl HashRing {
+    fn new(instances: &[ServiceInstance]) -> Self {
+        let mut points = BTreeMap::new();
+        for (index, instance) in instances.iter().enumerate() {
+            for node in 0..VIRTUAL_NODES {
+                points.insert(fnv1a(format!("{}#{}", instance.id, node).as_bytes()), index);
+            }
+        }
+        Self { points }
+    }
+
+    fn owner(&self, key: &str) -> usize {
+        let hash = fnv1a(key.as_bytes());
+        self.points
+            .range(hash..)
+            .next()
+            .or_else(|| self.points.iter().next())
+            .map(|(_, index)| *index)
+            .unwrap_or(0)
+    }
+}
+
+/// FNV-1a, so keys map the same way across registry restarts and builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// An instance handed out by [`crate::ServiceRegistry::acquire`]. It
+/// counts as an open connection for least-connections balancing until
+/// dropped.
+#[derive(Debug)]
+pub struct InstanceLease {
+    instance: ServiceInstance,
+    active: Arc<AtomicUsize>,
+}
+
+impl InstanceLease {
+    pub(crate) fn new(instance: ServiceInstance) -> Self {
+        let active = instance.active.clone();
+        active.fetch_add(1, Ordering::Relaxed);
+        Self { instance, active }
+    }
+
+    pub fn instance(&self) -> &ServiceInstance {
+        &self.instance
+    }
+}
+
+impl Drop for InstanceLease {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Instant;
+
+    fn instance(id: &str) -> ServiceInstance {
+        ServiceInstance {
+            id: id.to_string(),
+            name: "world-engine".to_string(),
+            host: "localhost".to_string(),
+            port: 3002,
+            health_check_url: String::new(),
+            metadata: HashMap::new(),
+            zone: None,
+            shard: None,
+            last_heartbeat: Instant::now(),
+            heartbeat_ttl_seconds: None,
+            active: Arc::default(),
+        }
+    }
+
+    #[test]
+    fn strategies_pick_as_described() {
+        let cursor = AtomicUsize::new(0);
+        let instances: Vec<_> = ["a", "b", "c", "d"].into_iter().map(instance).collect();
+
+        let picks: Vec<_> = (0..5)
+            .map(|_| LoadBalancing::RoundRobin.pick(&instances, &cursor, None).unwrap())
+            .collect();
+        assert_eq!(picks, vec![0, 1, 2, 3, 0]);
+
+        let _busy = [InstanceLease::new(instances[0].clone()), InstanceLease::new(instances[2].clone())];
+        let _also_busy = InstanceLease::new(instances[1].clone());
+        assert_eq!(LoadBalancing::LeastConnections.pick(&instances, &cursor, None), Some(3));
+
+        // Players stay put, and losing an instance only moves its own players
+        let players: Vec<String> = (0..200).map(|i| format!("player-{}", i)).collect();
+        let owner = |instances: &[ServiceInstance], player: &str| {
+            let index = LoadBalancing::ConsistentHash.pick(instances, &cursor, Some(player)).unwrap();
+            instances[index].id.clone()
+        };
+        let before: Vec<_> = players.iter().map(|p| owner(&instances, p)).collect();
+        assert_eq!(before, players.iter().map(|p| owner(&instances, p)).collect::<Vec<_>>());
+
+        let without_b: Vec<_> = instances.iter().filter(|i| i.id != "b").cloned().collect();
+        for (player, previous) in players.iter().zip(&before) {
+            if previous != "b" {
+                assert_eq!(&owner(&without_b, player), previous);
+            }
+        }
+    }
+}
@@ -1,13 +1,38 @@
 // services/service-registry/src/lib.rs
 // Service discovery and registration for Finalverse
 
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tokio::time::interval;
 
+mod balancing;
+mod locality;
+mod metrics;
+mod policy;
+mod storage;
+mod watch;
+
+pub use balancing::{InstanceLease, LoadBalancing};
+pub use locality::Locality;
+pub use metrics::describe_metrics;
+pub use policy::{HttpPolicy, PolicyClient, TargetPolicy};
+#[cfg(feature = "redis")]
+pub use storage::RedisStorage;
+pub use storage::{Storage, StoredInstance};
+pub use watch::RegistryEvent;
+
+use finalverse_core::{TenantId, TENANT_HEADER};
+use finalverse_metrics::{counter, histogram};
+
+/// Registration metadata key naming the tenant an instance serves.
+/// Instances without it serve the default tenant.
+pub const TENANT_METADATA_KEY: &str = "tenant";
+
 fn default_instant() -> Instant {
     Instant::now()
 }
@@ -20,12 +45,36 @@ pub struct ServiceInstance {
     pub port: u16,
     pub health_check_url: String,
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard: Option<String>,
     #[serde(
         skip_serializing,
         skip_deserializing,
         default = "default_instant"
     )]
     pub last_heartbeat: Instant,
+    /// Silence after which this instance counts as unhealthy, overriding
+    /// the registry's heartbeat timeout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_ttl_seconds: Option<u64>,
+    #[serde(skip)]
+    active: Arc<AtomicUsize>,
+}
+
+impl ServiceInstance {
+    /// Leases currently held on this instance.
+    pub fn active_leases(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub fn tenant(&self) -> TenantId {
+        self.metadata
+            .get(TENANT_METADATA_KEY)
+            .and_then(|tenant| TenantId::parse(tenant).ok())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,11 +84,50 @@ pub struct ServiceRegistration {
     pub port: u16,
     pub health_check_path: String,
     pub metadata: HashMap<String, String>,
+    /// How lookups choose among the service's instances. The most recent
+    /// registration decides for the whole service.
+    #[serde(default)]
+    pub load_balancing: LoadBalancing,
+    /// How long the instance may go without a heartbeat before it counts
+    /// as unhealthy; the registry's timeout when unset.
+    #[serde(default)]
+    pub heartbeat_ttl_seconds: Option<u64>,
+    /// Deployment zone (a region or datacenter), for [`Locality`]-aware
+    /// discovery.
+    #[serde(default)]
+    pub zone: Option<String>,
+    /// Shard within the zone, e.g. the slice of the world an instance owns.
+    #[serde(default)]
+    pub shard: Option<String>,
+}
+
+/// Body of `POST /heartbeat/batch`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeartbeatBatch {
+    pub ids: Vec<String>,
+}
+
+/// Reply to `POST /heartbeat/batch`: the ids the registry doesn't know,
+/// which need registering again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeartbeatBatchResult {
+    pub unknown: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Default)]
+struct Balancer {
+    strategy: LoadBalancing,
+    cursor: AtomicUsize,
+}
+
+#[derive(Clone)]
 pub struct ServiceRegistry {
     services: Arc<RwLock<HashMap<String, Vec<ServiceInstance>>>>,
+    balancers: Arc<RwLock<HashMap<String, Balancer>>>,
+    storage: Option<Arc<dyn Storage>>,
+    watchers: Arc<watch::Watchers>,
+    /// Instances past the heartbeat timeout but not yet evicted.
+    unhealthy: Arc<std::sync::Mutex<HashSet<String>>>,
     health_check_interval: Duration,
     heartbeat_timeout: Duration,
 }
@@ -50,14 +138,100 @@ impl Default for ServiceRegistry {
     }
 }
 
+impl std::fmt::Debug for ServiceRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceRegistry")
+            .field("services", &self.services)
+            .field("heartbeat_timeout", &self.heartbeat_timeout)
+            .field("persistent", &self.storage.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
 impl ServiceRegistry {
     pub fn new() -> Self {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
+            balancers: Arc::new(RwLock::new(HashMap::new())),
+            storage: None,
+            watchers: Arc::default(),
+            unhealthy: Arc::default(),
             health_check_interval: Duration::from_secs(10),
             heartbeat_timeout: Duration::from_secs(30),
         }
     }
+
+    pub fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Persist registrations and heartbeats to `storage`. Call
+    /// [`Self::recover`] before serving to pick up what was stored.
+    pub fn with_storage(mut self, storage: Arc<dyn Storage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Reload instances from storage that are still within the heartbeat
+    /// timeout, dropping the rest from storage. Returns how many were
+    /// recovered.
+    pub async fn recover(&self) -> anyhow::Result<usize> {
+        let Some(storage) = &self.storage else { return Ok(0) };
+        let now = Instant::now();
+        let mut recovered = 0;
+        for stored in storage.load().await? {
+            let load_balancing = stored.load_balancing;
+            let instance = stored.into_instance();
+            if now.duration_since(instance.last_heartbeat) >= self.ttl(&instance) {
+                storage.remove(&instance.id).await?;
+                continue;
+            }
+            self.balancers
+                .write()
+                .await
+                .entry(instance.name.clone())
+                .or_default()
+                .strategy = load_balancing;
+            let mut services = self.services.write().await;
+            let instances = services.entry(instance.name.clone()).or_default();
+            if !instances.iter().any(|existing| existing.id == instance.id) {
+                instances.push(instance.clone());
+                self.watchers.notify(RegistryEvent::Added { instance });
+                recovered += 1;
+            }
+        }
+        tracing::info!("Recovered {} service instances from storage", recovered);
+        Ok(recovered)
+    }
+
+    /// Changes to `service_name`'s instances from now on. A watcher that
+    /// falls too far behind gets `RecvError::Lagged` and should re-read
+    /// [`Self::discover_all`].
+    pub fn watch(&self, service_name: &str) -> tokio::sync::broadcast::Receiver<RegistryEvent> {
+        self.watchers.subscribe(service_name)
+    }
+
+    /// Write-through to storage; the in-memory view stays authoritative.
+    async fn persist<F, Fut>(&self, what: &str, write: F)
+    where
+        F: FnOnce(Arc<dyn Storage>) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<()>>,
+    {
+        if let Some(storage) = &self.storage {
+            if let Err(e) = write(storage.clone()).await {
+                tracing::warn!("Failed to persist {}: {}", what, e);
+            }
+        }
+    }
+
+    /// How long `instance` may stay silent before it counts as unhealthy.
+    fn ttl(&self, instance: &ServiceInstance) -> Duration {
+        instance
+            .heartbeat_ttl_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(self.heartbeat_timeout)
+    }
     
     pub async fn register(&self, registration: ServiceRegistration) -> String {
         let id = format!("{}-{}", registration.name, uuid::Uuid::new_v4());
@@ -76,59 +250,165 @@ impl ServiceRegistry {
             port: registration.port,
             health_check_url,
             metadata: registration.metadata,
+            zone: registration.zone,
+            shard: registration.shard,
             last_heartbeat: Instant::now(),
+            heartbeat_ttl_seconds: registration.heartbeat_ttl_seconds,
+            active: Arc::default(),
         };
         
+        counter!(metrics::REGISTRATIONS, service = registration.name).increment(1);
+        let stored = StoredInstance::new(instance.clone(), registration.load_balancing);
+        self.persist("registration", |storage| async move { storage.save(&stored).await }).await;
+        self.balancers
+            .write()
+            .await
+            .entry(registration.name.clone())
+            .or_default()
+            .strategy = registration.load_balancing;
         let mut services = self.services.write().await;
         services
             .entry(registration.name)
             .or_insert_with(Vec::new)
-            .push(instance);
+            .push(instance.clone());
+        self.watchers.notify(RegistryEvent::Added { instance });
         
         id
     }
+
+    /// Register several instances at once, e.g. for a sidecar fronting
+    /// them. Ids come back in the order of `registrations`.
+    pub async fn register_batch(&self, registrations: Vec<ServiceRegistration>) -> Vec<String> {
+        let mut ids = Vec::with_capacity(registrations.len());
+        for registration in registrations {
+            ids.push(self.register(registration).await);
+        }
+        ids
+    }
     
     pub async fn deregister(&self, service_id: &str) {
         let mut services = self.services.write().await;
         
-        for instances in services.values_mut() {
+        for (name, instances) in services.iter_mut() {
+            let before = instances.len();
             instances.retain(|instance| instance.id != service_id);
+            if instances.len() < before {
+                self.watchers.notify(RegistryEvent::Removed { service: name.clone(), id: service_id.to_string() });
+            }
         }
         
         // Remove empty entries
         services.retain(|_, instances| !instances.is_empty());
+        drop(services);
+        self.unhealthy.lock().unwrap().remove(service_id);
+        self.persist("deregistration", |storage| async move { storage.remove(service_id).await }).await;
     }
     
     pub async fn heartbeat(&self, service_id: &str) -> bool {
-        let mut services = self.services.write().await;
-        
-        for instances in services.values_mut() {
-            for instance in instances.iter_mut() {
-                if instance.id == service_id {
-                    instance.last_heartbeat = Instant::now();
-                    return true;
+        self.heartbeat_batch(&[service_id.to_string()]).await.is_empty()
+    }
+
+    /// Record a heartbeat from each of `service_ids` under one lock,
+    /// returning those the registry doesn't know.
+    pub async fn heartbeat_batch(&self, service_ids: &[String]) -> Vec<String> {
+        let now = Instant::now();
+        let wanted: HashSet<&str> = service_ids.iter().map(String::as_str).collect();
+        // (id, service name) of each instance found
+        let mut seen: Vec<(String, String)> = Vec::with_capacity(wanted.len());
+        {
+            let mut services = self.services.write().await;
+            for instance in services.values_mut().flat_map(|instances| instances.iter_mut()) {
+                if wanted.contains(instance.id.as_str()) {
+                    instance.last_heartbeat = now;
+                    seen.push((instance.id.clone(), instance.name.clone()));
                 }
             }
         }
-        
-        false
+        let found: HashSet<&str> = seen.iter().map(|(id, _)| id.as_str()).collect();
+        let unknown: Vec<String> = service_ids
+            .iter()
+            .filter(|id| !found.contains(id.as_str()))
+            .cloned()
+            .collect();
+
+        counter!(metrics::HEARTBEATS, outcome = "ok").increment(seen.len() as u64);
+        counter!(metrics::HEARTBEATS, outcome = "unknown").increment(unknown.len() as u64);
+        {
+            let mut unhealthy = self.unhealthy.lock().unwrap();
+            for (id, service) in &seen {
+                if unhealthy.remove(id) {
+                    self.watchers.notify(RegistryEvent::HealthChanged { service: service.clone(), id: id.clone(), healthy: true });
+                }
+            }
+        }
+        let at_ms = storage::to_unix_ms(now);
+        for (id, _) in &seen {
+            self.persist("heartbeat", |storage| async move { storage.heartbeat(id, at_ms).await }).await;
+        }
+        unknown
     }
     
     pub async fn discover(&self, service_name: &str) -> Option<ServiceInstance> {
-        let services = self.services.read().await;
-        
-        services.get(service_name)
-            .and_then(|instances| {
-                // Find healthy instances
-                let now = Instant::now();
-                instances
-                    .iter()
-                    .filter(|instance| {
-                        now.duration_since(instance.last_heartbeat) < self.heartbeat_timeout
-                    })
-                    .min_by_key(|_| rand::random::<u8>()) // Random load balancing
-                    .cloned()
-            })
+        let instances = self.discover_all(service_name).await;
+        self.select(service_name, instances, None).await
+    }
+
+    /// Like [`Self::discover`], but consistent-hash services route the same
+    /// `key` (a player id, say) to the same instance.
+    pub async fn discover_with_key(&self, service_name: &str, key: &str) -> Option<ServiceInstance> {
+        let instances = self.discover_all(service_name).await;
+        self.select(service_name, instances, Some(key)).await
+    }
+
+    /// Pick an instance and hold a lease on it until the returned guard is
+    /// dropped, so least-connections balancing sees the call in flight.
+    pub async fn acquire(&self, service_name: &str, key: Option<&str>) -> Option<InstanceLease> {
+        let instances = self.discover_all(service_name).await;
+        self.select(service_name, instances, key).await.map(InstanceLease::new)
+    }
+
+    /// Like [`Self::discover_with_key`], but among the healthy instances
+    /// closest to `locality`: same zone and shard first, then the same
+    /// zone, spilling over to other zones only when it has none.
+    pub async fn discover_near(&self, service_name: &str, locality: &Locality, key: Option<&str>) -> Option<ServiceInstance> {
+        let instances = locality.nearest(self.discover_all(service_name).await);
+        self.select(service_name, instances, key).await
+    }
+
+    /// Like [`Self::discover`], but only among instances serving `tenant`.
+    pub async fn discover_for_tenant(&self, service_name: &str, tenant: &TenantId) -> Option<ServiceInstance> {
+        self.discover_for_tenant_near(service_name, tenant, &Locality::default()).await
+    }
+
+    /// Like [`Self::discover_near`], but only among instances serving `tenant`.
+    pub async fn discover_for_tenant_near(
+        &self,
+        service_name: &str,
+        tenant: &TenantId,
+        locality: &Locality,
+    ) -> Option<ServiceInstance> {
+        let instances = self
+            .discover_all(service_name)
+            .await
+            .into_iter()
+            .filter(|instance| instance.tenant() == *tenant)
+            .collect();
+        self.select(service_name, locality.nearest(instances), None).await
+    }
+
+    async fn select(&self, service_name: &str, instances: Vec<ServiceInstance>, key: Option<&str>) -> Option<ServiceInstance> {
+        let found = {
+            let balancers = self.balancers.read().await;
+            let fallback = Balancer::default();
+            let balancer = balancers.get(service_name).unwrap_or(&fallback);
+            balancer
+                .strategy
+                .pick(&instances, &balancer.cursor, key)
+                .map(|index| instances[index].clone())
+        };
+        let outcome = if found.is_some() { "found" } else { "none" };
+        counter!(metrics::DISCOVERIES, service = service_name, outcome = outcome).increment(1);
+        found
     }
     
     pub async fn discover_all(&self, service_name: &str) -> Vec<ServiceInstance> {
@@ -139,9 +419,7 @@ impl ServiceRegistry {
             .map(|instances| {
                 instances
                     .iter()
-                    .filter(|instance| {
-                        now.duration_since(instance.last_heartbeat) < self.heartbeat_timeout
-                    })
+                    .filter(|instance| now.duration_since(instance.last_heartbeat) < self.ttl(instance))
                     .cloned()
                     .collect()
             })
@@ -157,9 +435,7 @@ impl ServiceRegistry {
             .map(|(name, instances)| {
                 let healthy_instances: Vec<ServiceInstance> = instances
                     .iter()
-                    .filter(|instance| {
-                        now.duration_since(instance.last_heartbeat) < self.heartbeat_timeout
-                    })
+                    .filter(|instance| now.duration_since(instance.last_heartbeat) < self.ttl(instance))
                     .cloned()
                     .collect();
                 (name.clone(), healthy_instances)
@@ -168,23 +444,52 @@ impl ServiceRegistry {
             .collect()
     }
     
+    /// Mark instances past their heartbeat TTL unhealthy, and evict
+    /// those that stay silent for twice as long.
     pub async fn cleanup_stale_services(&self) {
         let mut services = self.services.write().await;
         let now = Instant::now();
+        let mut evicted = Vec::new();
         
-        for instances in services.values_mut() {
-            instances.retain(|instance| {
-                now.duration_since(instance.last_heartbeat) < self.heartbeat_timeout
-            });
+        {
+            let mut unhealthy = self.unhealthy.lock().unwrap();
+            for (name, instances) in services.iter_mut() {
+                let before = instances.len();
+                instances.retain(|instance| {
+                    let silent = now.duration_since(instance.last_heartbeat);
+                    let ttl = self.ttl(instance);
+                    if silent >= ttl * 2 {
+                        unhealthy.remove(&instance.id);
+                        self.watchers.notify(RegistryEvent::Removed { service: name.clone(), id: instance.id.clone() });
+                        evicted.push(instance.id.clone());
+                        return false;
+                    }
+                    if silent >= ttl && unhealthy.insert(instance.id.clone()) {
+                        self.watchers.notify(RegistryEvent::HealthChanged {
+                            service: name.clone(),
+                            id: instance.id.clone(),
+                            healthy: false,
+                        });
+                    }
+                    true
+                });
+                if instances.len() < before {
+                    counter!(metrics::STALE_EVICTIONS, service = name).increment((before - instances.len()) as u64);
+                }
+            }
         }
         
         services.retain(|_, instances| !instances.is_empty());
+        drop(services);
+        for id in &evicted {
+            self.persist("eviction", |storage| async move { storage.remove(id).await }).await;
+        }
     }
     
     pub fn start_cleanup_task(&self) {
         let registry = self.clone();
         tokio::spawn(async move {
-            let mut ticker = interval(Duration::from_secs(30));
+            let mut ticker = interval(registry.health_check_interval);
             
             loop {
                 ticker.tick().await;
@@ -198,23 +503,28 @@ impl ServiceRegistry {
 pub struct RegistryClient {
     registry_url: String,
     service_id: Option<String>,
-    client: reqwest::Client,
+    client: PolicyClient,
 }
 
 impl RegistryClient {
+    const TARGET: &'static str = "service-registry";
+
     pub fn new(registry_url: impl Into<String>) -> Self {
+        Self::with_policy(registry_url, HttpPolicy::load())
+    }
+
+    pub fn with_policy(registry_url: impl Into<String>, policy: HttpPolicy) -> Self {
         Self {
             registry_url: registry_url.into(),
             service_id: None,
-            client: reqwest::Client::new(),
+            client: PolicyClient::new(policy),
         }
     }
     
     pub async fn register(&mut self, registration: ServiceRegistration) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&registration)?;
         let response = self.client
-            .post(&format!("{}/register", self.registry_url))
-            .json(&registration)
-            .send()
+            .send(Self::TARGET, Method::POST, &format!("{}/register", self.registry_url), Some(body))
             .await?;
         
         if response.status().is_success() {
@@ -229,8 +539,7 @@ impl RegistryClient {
     pub async fn deregister(&self) -> anyhow::Result<()> {
         if let Some(id) = &self.service_id {
             self.client
-                .delete(&format!("{}/services/{}", self.registry_url, id))
-                .send()
+                .send(Self::TARGET, Method::DELETE, &format!("{}/services/{}", self.registry_url, id), None)
                 .await?;
         }
         Ok(())
@@ -239,13 +548,43 @@ impl RegistryClient {
     pub async fn heartbeat(&self) -> anyhow::Result<()> {
         if let Some(id) = &self.service_id {
             self.client
-                .put(&format!("{}/services/{}/heartbeat", self.registry_url, id))
-                .send()
+                .send(Self::TARGET, Method::PUT, &format!("{}/services/{}/heartbeat", self.registry_url, id), None)
                 .await?;
         }
         Ok(())
     }
     
+    /// Register several instances in one request, returning their ids in
+    /// order. This client's own registration is left as it was.
+    pub async fn register_batch(&self, registrations: &[ServiceRegistration]) -> anyhow::Result<Vec<String>> {
+        let body = serde_json::to_vec(registrations)?;
+        let response = self.client
+            .send(Self::TARGET, Method::POST, &format!("{}/register/batch", self.registry_url), Some(body))
+            .await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Err(anyhow::anyhow!("Batch registration failed: {}", response.status()))
+        }
+    }
+
+    /// Heartbeat for several instances in one request, returning the ids
+    /// the registry no longer knows.
+    pub async fn heartbeat_batch(&self, ids: &[String]) -> anyhow::Result<Vec<String>> {
+        let body = serde_json::to_vec(&HeartbeatBatch { ids: ids.to_vec() })?;
+        let response = self.client
+            .send(Self::TARGET, Method::POST, &format!("{}/heartbeat/batch", self.registry_url), Some(body))
+            .await?;
+
+        if response.status().is_success() {
+            let result: HeartbeatBatchResult = response.json().await?;
+            Ok(result.unknown)
+        } else {
+            Err(anyhow::anyhow!("Batch heartbeat failed: {}", response.status()))
+        }
+    }
+    
     pub fn start_heartbeat_task(&self) {
         if let Some(id) = &self.service_id {
             let client = self.client.clone();
@@ -257,10 +596,8 @@ impl RegistryClient {
                 
                 loop {
                     ticker.tick().await;
-                    let _ = client
-                        .put(&format!("{}/services/{}/heartbeat", registry_url, service_id))
-                        .send()
-                        .await;
+                    let url = format!("{}/services/{}/heartbeat", registry_url, service_id);
+                    let _ = client.send(Self::TARGET, Method::PUT, &url, None).await;
                 }
             });
         }
@@ -268,8 +605,7 @@ impl RegistryClient {
     
     pub async fn discover(&self, service_name: &str) -> anyhow::Result<Option<ServiceInstance>> {
         let response = self.client
-            .get(&format!("{}/discover/{}", self.registry_url, service_name))
-            .send()
+            .send(Self::TARGET, Method::GET, &format!("{}/discover/{}", self.registry_url, service_name), None)
             .await?;
         
         if response.status().is_success() {
@@ -278,12 +614,41 @@ impl RegistryClient {
             Ok(None)
         }
     }
+
+    /// Discover with a routing key for consistent-hash services.
+    pub async fn discover_with_key(&self, service_name: &str, key: &str) -> anyhow::Result<Option<ServiceInstance>> {
+        self.discover_near(service_name, &Locality::default(), Some(key)).await
+    }
+
+    /// Discover the closest instance to `locality`, spilling over to
+    /// other zones when its own has none healthy.
+    pub async fn discover_near(
+        &self,
+        service_name: &str,
+        locality: &Locality,
+        key: Option<&str>,
+    ) -> anyhow::Result<Option<ServiceInstance>> {
+        let params = [("key", key), ("zone", locality.zone.as_deref()), ("shard", locality.shard.as_deref())];
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}/discover/{}", self.registry_url, service_name),
+            params.iter().filter_map(|(name, value)| value.map(|value| (*name, value))),
+        )?;
+        let response = self.client.send(Self::TARGET, Method::GET, url.as_str(), None).await?;
+
+        if response.status().is_success() {
+            Ok(response.json().await?)
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 // For local development without external registry
 #[derive(Clone)]
 pub struct LocalServiceRegistry {
     services: Arc<RwLock<HashMap<String, String>>>,
+    /// Per-tenant deployments of a service, keyed by (tenant, service).
+    tenant_services: Arc<RwLock<HashMap<(TenantId, String), String>>>,
 }
 
 impl Default for LocalServiceRegistry {
@@ -297,6 +662,7 @@ impl LocalServiceRegistry {
         let mut services = HashMap::new();
         
         // Pre-populate with known services for local development
+        services.insert("websocket-gateway".to_string(), "http://localhost:3000".to_string());
         services.insert("song-engine".to_string(), "http://localhost:3001".to_string());
         services.insert("world-engine".to_string(), "http://localhost:3002".to_string());
         services.insert("echo-engine".to_string(), "http://localhost:3003".to_string());
@@ -311,6 +677,7 @@ impl LocalServiceRegistry {
         
         Self {
             services: Arc::new(RwLock::new(services)),
+            tenant_services: Arc::new(RwLock::new(HashMap::new())),
         }
     }
     
@@ -323,4 +690,232 @@ impl LocalServiceRegistry {
         let mut services = self.services.write().await;
         services.insert(name, url);
     }
-}
\ No newline at end of file
+
+    /// The tenant's own deployment of the service, falling back to the
+    /// shared one for the default tenant only.
+    pub async fn get_tenant_service_url(&self, tenant: &TenantId, service_name: &str) -> Option<String> {
+        let key = (tenant.clone(), service_name.to_string());
+        if let Some(url) = self.tenant_services.read().await.get(&key) {
+            return Some(url.clone());
+        }
+        if tenant.is_default() {
+            self.get_service_url(service_name).await
+        } else {
+            None
+        }
+    }
+
+    pub async fn register_tenant_service(&self, tenant: TenantId, name: String, url: String) {
+        let mut services = self.tenant_services.write().await;
+        services.insert((tenant, name), url);
+    }
+}
+
+/// HTTP client for calling other Finalverse services by name, resolved
+/// through the [`LocalServiceRegistry`]. Timeouts, retries and header
+/// propagation follow the shared [`HttpPolicy`], keyed by service name.
+/// Calls stay within the client's tenant, which every request names in
+/// the tenant header.
+#[derive(Clone)]
+pub struct ServiceClient {
+    registry: LocalServiceRegistry,
+    client: PolicyClient,
+    tenant: TenantId,
+    #[cfg(feature = "chaos")]
+    fault_injector: Option<Arc<finalverse_chaos::FaultInjector>>,
+}
+
+impl ServiceClient {
+    pub fn new(registry: LocalServiceRegistry) -> Self {
+        Self::with_policy(registry, HttpPolicy::load())
+    }
+
+    pub fn with_policy(registry: LocalServiceRegistry, policy: HttpPolicy) -> Self {
+        Self {
+            registry,
+            client: PolicyClient::new(policy),
+            tenant: TenantId::default(),
+            #[cfg(feature = "chaos")]
+            fault_injector: None,
+        }
+        .for_tenant(TenantId::from_env())
+    }
+
+    /// A client whose calls go to `tenant`'s deployments.
+    pub fn for_tenant(&self, tenant: TenantId) -> Self {
+        Self {
+            client: self.client.with_header(TENANT_HEADER, tenant.as_str()),
+            tenant,
+            ..self.clone()
+        }
+    }
+
+    pub fn tenant(&self) -> &TenantId {
+        &self.tenant
+    }
+
+    /// Route every call through the given fault injector.
+    #[cfg(feature = "chaos")]
+    pub fn with_fault_injector(mut self, injector: finalverse_chaos::FaultInjector) -> Self {
+        self.fault_injector = Some(Arc::new(injector));
+        self
+    }
+
+    /// A client that also sends `name: value` with every call, such as the
+    /// operator token for another service's admin routes.
+    pub fn with_header(&self, name: &str, value: &str) -> Self {
+        Self {
+            client: self.client.with_header(name, value),
+            ..self.clone()
+        }
+    }
+
+    /// A client that forwards the policy's propagated headers (trace
+    /// context by default) from an inbound request.
+    pub fn propagating<'a>(&self, inbound: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        // The tenant is ours to set, never the caller's
+        Self {
+            client: self.client.propagating(inbound).with_header(TENANT_HEADER, self.tenant.as_str()),
+            ..self.clone()
+        }
+    }
+
+    async fn url_for(&self, service_name: &str, path: &str) -> anyhow::Result<String> {
+        #[cfg(feature = "chaos")]
+        if let Some(injector) = &self.fault_injector {
+            injector.before_call(service_name).await?;
+        }
+
+        let base = self
+            .registry
+            .get_tenant_service_url(&self.tenant, service_name)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Unknown service {} for tenant {}", service_name, self.tenant))?;
+        Ok(format!("{}{}", base, path))
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        service_name: &str,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> anyhow::Result<T> {
+        let started = Instant::now();
+        let result = self.call(service_name, method, path, body).await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        counter!(metrics::CALLS, target = service_name, outcome = outcome).increment(1);
+        histogram!(metrics::CALL_DURATION, target = service_name).record(started.elapsed().as_secs_f64());
+        result
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        service_name: &str,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> anyhow::Result<T> {
+        let url = self.url_for(service_name, path).await?;
+        let response = self
+            .client
+            .send(service_name, method, &url, body)
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, service_name: &str, path: &str) -> anyhow::Result<T> {
+        self.request(service_name, Method::GET, path, None).await
+    }
+
+    pub async fn post_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        service_name: &str,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        let body = serde_json::to_vec(body)?;
+        self.request(service_name, Method::POST, path, Some(body)).await
+    }
+
+    pub async fn put_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        service_name: &str,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        let body = serde_json::to_vec(body)?;
+        self.request(service_name, Method::PUT, path, Some(body)).await
+    }
+
+    pub async fn delete_json<T: DeserializeOwned>(&self, service_name: &str, path: &str) -> anyhow::Result<T> {
+        self.request(service_name, Method::DELETE, path, None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registration(name: &str, heartbeat_ttl_seconds: Option<u64>) -> ServiceRegistration {
+        ServiceRegistration {
+            name: name.to_string(),
+            host: "localhost".to_string(),
+            port: 3003,
+            health_check_path: "/health".to_string(),
+            metadata: HashMap::new(),
+            load_balancing: LoadBalancing::default(),
+            heartbeat_ttl_seconds,
+            zone: None,
+            shard: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_register_and_heartbeat_with_per_instance_ttls() {
+        let registry = ServiceRegistry::new().with_heartbeat_timeout(Duration::from_millis(50));
+        let ids = registry
+            .register_batch(vec![registration("echo-engine", None), registration("echo-engine", Some(60))])
+            .await;
+        assert_eq!(ids.len(), 2);
+
+        // Only the instance on the registry's timeout lapses
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let live: Vec<_> = registry.discover_all("echo-engine").await.into_iter().map(|i| i.id).collect();
+        assert_eq!(live, vec![ids[1].clone()]);
+
+        let batch = vec![ids[0].clone(), "echo-engine-gone".to_string(), ids[1].clone()];
+        assert_eq!(registry.heartbeat_batch(&batch).await, vec!["echo-engine-gone".to_string()]);
+        assert_eq!(registry.discover_all("echo-engine").await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn discovery_stays_in_zone_until_it_runs_dry() {
+        let registry = ServiceRegistry::new();
+        let placed = |zone: &str, shard: &str| ServiceRegistration {
+            zone: Some(zone.to_string()),
+            shard: Some(shard.to_string()),
+            ..registration("world-engine", None)
+        };
+        let east = registry.register(placed("us-east", "1")).await;
+        let east_other_shard = registry.register(placed("us-east", "2")).await;
+        let west = registry.register(placed("us-west", "1")).await;
+
+        let near = |zone: &str, shard: Option<&str>| Locality::new(Some(zone.to_string()), shard.map(str::to_string));
+        let found = |locality: Locality| {
+            let registry = registry.clone();
+            async move { registry.discover_near("world-engine", &locality, None).await.map(|i| i.id) }
+        };
+        assert_eq!(found(near("us-east", Some("2"))).await, Some(east_other_shard.clone()));
+        assert_eq!(found(near("us-west", None)).await, Some(west.clone()));
+        for _ in 0..8 {
+            let id = found(near("us-east", None)).await.unwrap();
+            assert!(id == east || id == east_other_shard);
+        }
+
+        registry.deregister(&east).await;
+        registry.deregister(&east_other_shard).await;
+        assert_eq!(found(near("us-east", Some("1"))).await, Some(west));
+    }
+}
@@ -0,0 +1,54 @@
+// services/service-registry/src/locality.rs
+// Where an instance or caller runs, for keeping traffic close to home
+
+use serde::{Deserialize, Serialize};
+
+use crate::ServiceInstance;
+
+/// The zone and shard a caller would like its instances in. Lookups go to
+/// the closest instances available and only spill over to other zones
+/// when the caller's own has none healthy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Locality {
+    #[serde(default)]
+    pub zone: Option<String>,
+    #[serde(default)]
+    pub shard: Option<String>,
+}
+
+impl Locality {
+    pub fn new(zone: Option<String>, shard: Option<String>) -> Self {
+        Self { zone, shard }
+    }
+
+    /// This process's locality, from `FINALVERSE_ZONE` and `FINALVERSE_SHARD`.
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|value: &String| !value.is_empty());
+        Self::new(var("FINALVERSE_ZONE"), var("FINALVERSE_SHARD"))
+    }
+
+    pub fn is_any(&self) -> bool {
+        self.zone.is_none() && self.shard.is_none()
+    }
+
+    /// How far `instance` is from here: 0 for the same zone and shard, 1
+    /// for the same zone, 2 for the same shard elsewhere, 3 otherwise.
+    /// Whatever the caller leaves unset matches every instance.
+    pub fn distance(&self, instance: &ServiceInstance) -> u8 {
+        let matches = |wanted: &Option<String>, actual: &Option<String>| wanted.is_none() || wanted == actual;
+        let zone = if matches(&self.zone, &instance.zone) { 0 } else { 2 };
+        let shard = if matches(&self.shard, &instance.shard) { 0 } else { 1 };
+        zone + shard
+    }
+
+    /// The instances closest to here, dropping all farther away.
+    pub(crate) fn nearest(&self, instances: Vec<ServiceInstance>) -> Vec<ServiceInstance> {
+        let Some(closest) = instances.iter().map(|instance| self.distance(instance)).min() else {
+            return instances;
+        };
+        instances
+            .into_iter()
+            .filter(|instance| self.distance(instance) == closest)
+            .collect()
+    }
+}
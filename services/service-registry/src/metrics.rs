@@ -0,0 +1,51 @@
+// services/service-registry/src/metrics.rs
+// Registry and service-to-service call metrics
+
+use finalverse_metrics::{CounterDef, HistogramDef};
+
+pub(crate) const REGISTRATIONS: CounterDef<1> = CounterDef::new(
+    "finalverse_registry_registrations_total",
+    "Service instances registered",
+    ["service"],
+);
+
+pub(crate) const HEARTBEATS: CounterDef<1> = CounterDef::new(
+    "finalverse_registry_heartbeats_total",
+    "Heartbeats received, by whether the instance was known",
+    ["outcome"],
+);
+
+pub(crate) const DISCOVERIES: CounterDef<2> = CounterDef::new(
+    "finalverse_registry_discoveries_total",
+    "Discovery lookups, by whether a healthy instance was found",
+    ["service", "outcome"],
+);
+
+pub(crate) const STALE_EVICTIONS: CounterDef<1> = CounterDef::new(
+    "finalverse_registry_stale_evictions_total",
+    "Instances dropped after missing heartbeats",
+    ["service"],
+);
+
+pub(crate) const CALLS: CounterDef<2> = CounterDef::new(
+    "finalverse_registry_calls_total",
+    "Calls made through ServiceClient",
+    ["target", "outcome"],
+);
+
+pub(crate) const CALL_DURATION: HistogramDef<1> = HistogramDef::new(
+    "finalverse_registry_call_duration_seconds",
+    "Latency of calls made through ServiceClient",
+    ["target"],
+);
+
+/// Register help text for the registry's metrics. Call after
+/// `finalverse_metrics::init`.
+pub fn describe_metrics() {
+    REGISTRATIONS.describe();
+    HEARTBEATS.describe();
+    DISCOVERIES.describe();
+    STALE_EVICTIONS.describe();
+    CALLS.describe();
+    CALL_DURATION.describe();
+}
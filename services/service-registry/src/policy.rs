@@ -0,0 +1,380 @@
+// services/service-registry/src/policy.rs
+// Shared outbound HTTP policy: timeouts, retry budgets and header propagation
+
+use finalverse_config::FinalverseConfig;
+use reqwest::{Method, Response};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Retry tokens available before any requests have been made.
+const INITIAL_RETRY_TOKENS: f64 = 10.0;
+const MAX_RETRY_TOKENS: f64 = 100.0;
+
+#[derive(Debug, Clone)]
+pub struct TargetPolicy {
+    pub timeout: Duration,
+    pub max_retries: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpPolicy {
+    pub default: TargetPolicy,
+    pub connect_timeout: Duration,
+    pub retry_backoff: Duration,
+    pub retry_budget_ratio: f64,
+    pub propagate_headers: Vec<String>,
+    pub targets: HashMap<String, TargetPolicy>,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self::from_config(&FinalverseConfig::default())
+    }
+}
+
+impl HttpPolicy {
+    pub fn from_config(config: &FinalverseConfig) -> Self {
+        let internal = &config.services.internal_services;
+        let outbound = &config.services.outbound_http;
+        let default = TargetPolicy {
+            timeout: Duration::from_millis(internal.default_timeout_ms),
+            max_retries: internal.default_retries,
+        };
+
+        let targets = outbound
+            .targets
+            .iter()
+            .map(|(name, target)| {
+                let policy = TargetPolicy {
+                    timeout: target.timeout_ms.map(Duration::from_millis).unwrap_or(default.timeout),
+                    max_retries: target.max_retries.unwrap_or(default.max_retries),
+                };
+                (name.clone(), policy)
+            })
+            .collect();
+
+        Self {
+            default,
+            connect_timeout: Duration::from_millis(outbound.connect_timeout_ms),
+            retry_backoff: Duration::from_millis(outbound.retry_backoff_ms),
+            retry_budget_ratio: outbound.retry_budget_ratio,
+            propagate_headers: outbound.propagate_headers.iter().map(|h| h.to_ascii_lowercase()).collect(),
+            targets,
+        }
+    }
+
+    /// Load the policy from the central configuration, falling back to the
+    /// built-in defaults when no configuration is available.
+    pub fn load() -> Self {
+        finalverse_config::load_default_config()
+            .map(|config| Self::from_config(&config))
+            .unwrap_or_default()
+    }
+
+    pub fn for_target(&self, target: &str) -> &TargetPolicy {
+        self.targets.get(target).unwrap_or(&self.default)
+    }
+}
+
+/// Caps retries to a fraction of the requests sent so that a struggling
+/// service isn't hit with a retry storm.
+#[derive(Debug)]
+struct RetryBudget {
+    ratio: f64,
+    tokens: Mutex<f64>,
+}
+
+impl RetryBudget {
+    fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            tokens: Mutex::new(INITIAL_RETRY_TOKENS),
+        }
+    }
+
+    fn deposit(&self) {
+        let mut tokens = self.tokens.lock().unwrap();
+        *tokens = (*tokens + self.ratio).min(MAX_RETRY_TOKENS);
+    }
+
+    fn withdraw(&self) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// reqwest wrapper that applies the [`HttpPolicy`] to every request.
+#[derive(Clone)]
+pub struct PolicyClient {
+    client: reqwest::Client,
+    policy: Arc<HttpPolicy>,
+    budget: Arc<RetryBudget>,
+    headers: Vec<(String, String)>,
+}
+
+impl PolicyClient {
+    pub fn new(policy: HttpPolicy) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(policy.connect_timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            budget: Arc::new(RetryBudget::new(policy.retry_budget_ratio)),
+            policy: Arc::new(policy),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn policy(&self) -> &HttpPolicy {
+        &self.policy
+    }
+
+    /// A client that forwards the configured headers from an inbound
+    /// request on every outbound call. Only trace context is forwarded by
+    /// default; credentials such as `authorization` must be listed
+    /// explicitly.
+    pub fn propagating<'a>(&self, inbound: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let headers = inbound
+            .into_iter()
+            .filter(|(name, _)| self.policy.propagate_headers.iter().any(|h| h.eq_ignore_ascii_case(name)))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Self {
+            headers,
+            ..self.clone()
+        }
+    }
+
+    /// A client that sends `name: value` on every call, replacing any
+    /// propagated value of the same header.
+    pub fn with_header(&self, name: &str, value: &str) -> Self {
+        let mut headers: Vec<(String, String)> = self
+            .headers
+            .iter()
+            .filter(|(existing, _)| !existing.eq_ignore_ascii_case(name))
+            .cloned()
+            .collect();
+        headers.push((name.to_string(), value.to_string()));
+        Self {
+            headers,
+            ..self.clone()
+        }
+    }
+
+    /// Send a request to `target`, retrying timeouts, connection failures
+    /// and 5xx/429 responses within the target's retry limit and the shared
+    /// retry budget. Non-idempotent methods are only retried when the
+    /// connection could not be established.
+    pub async fn send(
+        &self,
+        target: &str,
+        method: Method,
+        url: &str,
+        body: Option<Vec<u8>>,
+    ) -> anyhow::Result<Response> {
+        let target_policy = self.policy.for_target(target);
+        let idempotent = !matches!(method, Method::POST | Method::PATCH);
+        self.budget.deposit();
+
+        let mut attempt = 0;
+        loop {
+            let mut request = self
+                .client
+                .request(method.clone(), url)
+                .timeout(target_policy.timeout);
+            for (name, value) in &self.headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            if let Some(body) = &body {
+                request = request
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body.clone());
+            }
+
+            let retriable = match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    let retriable_status = idempotent
+                        && (status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS);
+                    if !retriable_status || !self.can_retry(attempt, target_policy) {
+                        return Ok(response);
+                    }
+                    format!("status {}", status)
+                }
+                Err(e) => {
+                    let retriable_error = e.is_connect() || (idempotent && e.is_timeout());
+                    if !retriable_error || !self.can_retry(attempt, target_policy) {
+                        return Err(e.into());
+                    }
+                    e.to_string()
+                }
+            };
+
+            attempt += 1;
+            tracing::debug!("Retrying {} {} (attempt {}): {}", method, url, attempt, retriable);
+            tokio::time::sleep(self.policy.retry_backoff * 2u32.saturating_pow(attempt - 1)).await;
+        }
+    }
+
+    fn can_retry(&self, attempt: u32, target_policy: &TargetPolicy) -> bool {
+        attempt < target_policy.max_retries && self.budget.withdraw()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn policy(max_retries: u32, propagate_headers: &[&str]) -> HttpPolicy {
+        HttpPolicy {
+            default: TargetPolicy {
+                timeout: Duration::from_secs(2),
+                max_retries,
+            },
+            connect_timeout: Duration::from_secs(1),
+            retry_backoff: Duration::from_millis(1),
+            retry_budget_ratio: 1.0,
+            propagate_headers: propagate_headers.iter().map(|h| h.to_string()).collect(),
+            targets: HashMap::new(),
+        }
+    }
+
+    /// An HTTP server answering with `statuses` in turn, then 200. Returns
+    /// its URL, how many requests it has seen and their raw heads.
+    async fn serve(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let count = Arc::new(AtomicUsize::new(0));
+        let heads = Arc::new(Mutex::new(Vec::new()));
+        let (seen, recorded) = (count.clone(), heads.clone());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let n = seen.fetch_add(1, Ordering::SeqCst);
+                recorded.lock().unwrap().push(String::from_utf8_lossy(&head).to_lowercase());
+                let status = statuses.get(n).copied().unwrap_or(200);
+                let response = format!("HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, count, heads)
+    }
+
+    #[tokio::test]
+    async fn retries_idempotent_requests_until_they_succeed() {
+        let (url, count, _) = serve(vec![503, 429]).await;
+        let response = PolicyClient::new(policy(3, &[])).send("world-engine", Method::GET, &url, None).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_targets_retry_limit() {
+        let (url, count, _) = serve(vec![500; 10]).await;
+        let response = PolicyClient::new(policy(2, &[])).send("world-engine", Method::PUT, &url, None).await.unwrap();
+        assert_eq!(response.status(), 500);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn never_retries_non_idempotent_responses() {
+        let (url, count, _) = serve(vec![503]).await;
+        let client = PolicyClient::new(policy(3, &[]));
+        let response = client.send("world-engine", Method::POST, &url, Some(b"{}".to_vec())).await.unwrap();
+        assert_eq!(response.status(), 503);
+        let response = client.send("world-engine", Method::PATCH, &url, Some(b"{}".to_vec())).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn stops_retrying_when_the_budget_runs_out() {
+        let (url, count, _) = serve(vec![503; 100]).await;
+        let client = PolicyClient::new(HttpPolicy {
+            retry_budget_ratio: 0.0,
+            ..policy(50, &[])
+        });
+        let response = client.send("world-engine", Method::GET, &url, None).await.unwrap();
+        assert_eq!(response.status(), 503);
+        // One request plus a retry per initial token
+        assert_eq!(count.load(Ordering::SeqCst), 1 + INITIAL_RETRY_TOKENS as usize);
+        client.send("world-engine", Method::GET, &url, None).await.unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 2 + INITIAL_RETRY_TOKENS as usize);
+    }
+
+    #[test]
+    fn budget_earns_a_share_of_a_retry_per_request() {
+        let budget = RetryBudget::new(0.5);
+        for _ in 0..INITIAL_RETRY_TOKENS as usize {
+            assert!(budget.withdraw());
+        }
+        assert!(!budget.withdraw());
+        budget.deposit();
+        assert!(!budget.withdraw());
+        budget.deposit();
+        assert!(budget.withdraw());
+    }
+
+    #[test]
+    fn budget_is_capped() {
+        let budget = RetryBudget::new(1.0);
+        for _ in 0..1000 {
+            budget.deposit();
+        }
+        assert_eq!(*budget.tokens.lock().unwrap(), MAX_RETRY_TOKENS);
+    }
+
+    #[tokio::test]
+    async fn forwards_only_configured_headers() {
+        let (url, _, heads) = serve(Vec::new()).await;
+        let inbound = [("traceparent", "00-abc-01"), ("Authorization", "Bearer player"), ("cookie", "session=1")];
+        PolicyClient::new(policy(0, &["traceparent"]))
+            .propagating(inbound)
+            .send("world-engine", Method::GET, &url, None)
+            .await
+            .unwrap();
+        let head = heads.lock().unwrap()[0].clone();
+        assert!(head.contains("traceparent: 00-abc-01"));
+        assert!(!head.contains("authorization"));
+        assert!(!head.contains("cookie"));
+    }
+
+    #[test]
+    fn default_policy_does_not_forward_credentials() {
+        let policy = HttpPolicy::default();
+        assert!(policy.propagate_headers.iter().any(|h| h == "traceparent"));
+        assert!(!policy.propagate_headers.iter().any(|h| h == "authorization"));
+    }
+
+    #[tokio::test]
+    async fn set_headers_replace_propagated_ones() {
+        let (url, _, heads) = serve(Vec::new()).await;
+        PolicyClient::new(policy(0, &["authorization"]))
+            .propagating([("authorization", "Bearer player")])
+            .with_header("Authorization", "Bearer service")
+            .send("world-engine", Method::GET, &url, None)
+            .await
+            .unwrap();
+        let head = heads.lock().unwrap()[0].clone();
+        assert!(head.contains("authorization: bearer service"));
+        assert!(!head.contains("bearer player"));
+    }
+}
@@ -0,0 +1,220 @@
+// services/service-registry/src/storage.rs
+// Durable copies of registry state, so a restarted registry recovers its view
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{LoadBalancing, ServiceInstance};
+
+/// An instance as persisted. Heartbeats are wall-clock times because an
+/// `Instant` means nothing to another process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredInstance {
+    #[serde(flatten)]
+    pub instance: ServiceInstance,
+    #[serde(default)]
+    pub load_balancing: LoadBalancing,
+    pub last_heartbeat_ms: u64,
+}
+
+impl StoredInstance {
+    pub fn new(instance: ServiceInstance, load_balancing: LoadBalancing) -> Self {
+        let last_heartbeat_ms = to_unix_ms(instance.last_heartbeat);
+        Self {
+            instance,
+            load_balancing,
+            last_heartbeat_ms,
+        }
+    }
+
+    /// The instance with its heartbeat converted back to this process's clock.
+    pub fn into_instance(self) -> ServiceInstance {
+        ServiceInstance {
+            last_heartbeat: from_unix_ms(self.last_heartbeat_ms),
+            ..self.instance
+        }
+    }
+}
+
+/// Write-through persistence for [`crate::ServiceRegistry`]. Failures are
+/// logged by the registry and never fail the registration or heartbeat
+/// that caused them.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn save(&self, instance: &StoredInstance) -> anyhow::Result<()>;
+
+    async fn heartbeat(&self, service_id: &str, at_ms: u64) -> anyhow::Result<()>;
+
+    async fn remove(&self, service_id: &str) -> anyhow::Result<()>;
+
+    async fn load(&self) -> anyhow::Result<Vec<StoredInstance>>;
+}
+
+pub(crate) fn to_unix_ms(at: Instant) -> u64 {
+    let wall = SystemTime::now() - Instant::now().saturating_duration_since(at);
+    wall.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+fn from_unix_ms(ms: u64) -> Instant {
+    let at = UNIX_EPOCH + Duration::from_millis(ms);
+    let age = SystemTime::now().duration_since(at).unwrap_or_default();
+    Instant::now().checked_sub(age).unwrap_or_else(Instant::now)
+}
+
+/// Instances in a Redis hash keyed by instance id, with heartbeats kept in
+/// a second hash so each heartbeat is a single small write.
+#[cfg(feature = "redis")]
+pub struct RedisStorage {
+    connection: redis::aio::ConnectionManager,
+    instances_key: String,
+    heartbeats_key: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisStorage {
+    pub async fn connect(redis_url: &str, key_prefix: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(redis_url)?;
+        Ok(Self {
+            connection: redis::aio::ConnectionManager::new(client).await?,
+            instances_key: format!("{}:instances", key_prefix),
+            heartbeats_key: format!("{}:heartbeats", key_prefix),
+        })
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl Storage for RedisStorage {
+    async fn save(&self, instance: &StoredInstance) -> anyhow::Result<()> {
+        let json = serde_json::to_string(instance)?;
+        redis::pipe()
+            .atomic()
+            .hset(&self.instances_key, &instance.instance.id, json)
+            .hset(&self.heartbeats_key, &instance.instance.id, instance.last_heartbeat_ms)
+            .query_async::<_, ()>(&mut self.connection.clone())
+            .await?;
+        Ok(())
+    }
+
+    async fn heartbeat(&self, service_id: &str, at_ms: u64) -> anyhow::Result<()> {
+        redis::cmd("HSET")
+            .arg(&self.heartbeats_key)
+            .arg(service_id)
+            .arg(at_ms)
+            .query_async::<_, ()>(&mut self.connection.clone())
+            .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, service_id: &str) -> anyhow::Result<()> {
+        redis::pipe()
+            .atomic()
+            .hdel(&self.instances_key, service_id)
+            .hdel(&self.heartbeats_key, service_id)
+            .query_async::<_, ()>(&mut self.connection.clone())
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self) -> anyhow::Result<Vec<StoredInstance>> {
+        let mut connection = self.connection.clone();
+        let (instances, heartbeats): (
+            std::collections::HashMap<String, String>,
+            std::collections::HashMap<String, u64>,
+        ) = redis::pipe()
+            .hgetall(&self.instances_key)
+            .hgetall(&self.heartbeats_key)
+            .query_async(&mut connection)
+            .await?;
+
+        let mut loaded = Vec::with_capacity(instances.len());
+        for (id, json) in instances {
+            match serde_json::from_str::<StoredInstance>(&json) {
+                Ok(mut stored) => {
+                    if let Some(at_ms) = heartbeats.get(&id) {
+                        stored.last_heartbeat_ms = stored.last_heartbeat_ms.max(*at_ms);
+                    }
+                    loaded.push(stored);
+                }
+                Err(e) => tracing::warn!("Skipping unreadable registry record {}: {}", id, e),
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ServiceRegistration, ServiceRegistry};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct MemoryStorage(Mutex<HashMap<String, StoredInstance>>);
+
+    #[async_trait]
+    impl Storage for MemoryStorage {
+        async fn save(&self, instance: &StoredInstance) -> anyhow::Result<()> {
+            self.0.lock().unwrap().insert(instance.instance.id.clone(), instance.clone());
+            Ok(())
+        }
+
+        async fn heartbeat(&self, service_id: &str, at_ms: u64) -> anyhow::Result<()> {
+            if let Some(stored) = self.0.lock().unwrap().get_mut(service_id) {
+                stored.last_heartbeat_ms = at_ms;
+            }
+            Ok(())
+        }
+
+        async fn remove(&self, service_id: &str) -> anyhow::Result<()> {
+            self.0.lock().unwrap().remove(service_id);
+            Ok(())
+        }
+
+        async fn load(&self) -> anyhow::Result<Vec<StoredInstance>> {
+            Ok(self.0.lock().unwrap().values().cloned().collect())
+        }
+    }
+
+    fn registration(name: &str, port: u16) -> ServiceRegistration {
+        ServiceRegistration {
+            name: name.to_string(),
+            host: "localhost".to_string(),
+            port,
+            health_check_path: "/health".to_string(),
+            metadata: HashMap::new(),
+            load_balancing: LoadBalancing::ConsistentHash,
+            heartbeat_ttl_seconds: None,
+            zone: None,
+            shard: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn restarted_registry_recovers_live_instances() {
+        let storage = Arc::new(MemoryStorage::default());
+        let registry = ServiceRegistry::new().with_storage(storage.clone());
+        let world = registry.register(registration("world-engine", 3002)).await;
+        let gone = registry.register(registration("song-engine", 3001)).await;
+        registry.register(registration("song-engine", 3101)).await;
+        registry.deregister(&gone).await;
+        assert!(registry.heartbeat(&world).await);
+
+        // A record whose heartbeat is long past the timeout stays dead
+        let mut stale = storage.load().await.unwrap().remove(0);
+        stale.instance.id = "echo-engine-stale".to_string();
+        stale.instance.name = "echo-engine".to_string();
+        stale.last_heartbeat_ms -= 10 * 60 * 1000;
+        storage.save(&stale).await.unwrap();
+        drop(registry);
+
+        let recovered = ServiceRegistry::new().with_storage(storage.clone());
+        assert_eq!(recovered.recover().await.unwrap(), 2);
+        assert_eq!(recovered.discover("world-engine").await.unwrap().id, world);
+        assert_eq!(recovered.discover_all("song-engine").await.len(), 1);
+        assert!(recovered.discover("echo-engine").await.is_none());
+        assert_eq!(storage.load().await.unwrap().len(), 2);
+    }
+}
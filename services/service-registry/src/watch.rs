@@ -0,0 +1,104 @@
+// services/service-registry/src/watch.rs
+// Push notifications of instance changes, so consumers needn't poll
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use crate::ServiceInstance;
+
+/// Events buffered per service before slow watchers start lagging.
+const WATCH_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegistryEvent {
+    Added { instance: ServiceInstance },
+    /// Deregistered, or evicted after missing heartbeats for too long.
+    Removed { service: String, id: String },
+    /// Stopped or resumed heartbeating within the timeout. Unhealthy
+    /// instances are left out of discovery until they recover.
+    HealthChanged { service: String, id: String, healthy: bool },
+}
+
+impl RegistryEvent {
+    pub fn service(&self) -> &str {
+        match self {
+            RegistryEvent::Added { instance } => &instance.name,
+            RegistryEvent::Removed { service, .. } | RegistryEvent::HealthChanged { service, .. } => service,
+        }
+    }
+}
+
+/// One broadcast channel per watched service, created on first watch.
+#[derive(Debug, Default)]
+pub(crate) struct Watchers {
+    channels: Mutex<HashMap<String, broadcast::Sender<RegistryEvent>>>,
+}
+
+impl Watchers {
+    pub(crate) fn subscribe(&self, service_name: &str) -> broadcast::Receiver<RegistryEvent> {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(service_name.to_string())
+            .or_insert_with(|| broadcast::channel(WATCH_CAPACITY).0)
+            .subscribe()
+    }
+
+    pub(crate) fn notify(&self, event: RegistryEvent) {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(sender) = channels.get(event.service()) {
+            if sender.send(event.clone()).is_err() {
+                // Every watcher has gone away
+                channels.remove(event.service());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LoadBalancing, ServiceRegistration, ServiceRegistry};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn watchers_see_the_instance_lifecycle() {
+        let registry = ServiceRegistry::new().with_heartbeat_timeout(Duration::from_millis(50));
+        let mut world = registry.watch("world-engine");
+        let mut song = registry.watch("song-engine");
+
+        let id = registry
+            .register(ServiceRegistration {
+                name: "world-engine".to_string(),
+                host: "localhost".to_string(),
+                port: 3002,
+                health_check_path: "/health".to_string(),
+                metadata: HashMap::new(),
+                load_balancing: LoadBalancing::default(),
+                heartbeat_ttl_seconds: None,
+                zone: None,
+                shard: None,
+            })
+            .await;
+        assert!(matches!(world.try_recv(), Ok(RegistryEvent::Added { instance }) if instance.id == id));
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        registry.cleanup_stale_services().await;
+        registry.cleanup_stale_services().await;
+        assert!(matches!(world.try_recv(), Ok(RegistryEvent::HealthChanged { healthy: false, .. })));
+        assert!(world.try_recv().is_err());
+
+        registry.heartbeat(&id).await;
+        assert!(matches!(world.try_recv(), Ok(RegistryEvent::HealthChanged { healthy: true, .. })));
+
+        tokio::time::sleep(Duration::from_millis(110)).await;
+        registry.cleanup_stale_services().await;
+        assert!(matches!(world.try_recv(), Ok(RegistryEvent::Removed { id: removed, .. }) if removed == id));
+        assert!(registry.discover_all("world-engine").await.is_empty());
+
+        assert!(song.try_recv().is_err());
+    }
+}
@@ -0,0 +1,177 @@
+// services/silence-service/src/infection.rs
+//! Tracks Silence corruption per grid and the infection it causes in the
+//! players standing there.
+
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::{SilenceInfection, StageChange};
+use finalverse_world3d::{GridCoordinate, Position3D};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Exposure removed per point of restoration melody power.
+pub const RESTORATION_CLEANSE_PER_POWER: f32 = 2.0;
+
+/// How long a player must wait before the same ritual site cleanses them again.
+pub const RITUAL_COOLDOWN_MINUTES: i64 = 10;
+
+#[derive(Debug, Error)]
+pub enum InfectionError {
+    #[error("Unknown ritual site {0}")]
+    UnknownSite(Uuid),
+    #[error("Player position is unknown")]
+    UnknownPosition,
+    #[error("Player is not within the ritual site")]
+    OutOfRange,
+    #[error("Ritual site is still recovering until {0}")]
+    OnCooldown(DateTime<Utc>),
+    #[error("Invalid value: {0}")]
+    Invalid(String),
+}
+
+/// A place where the Silence can be washed away by performing its ritual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RitualSite {
+    pub id: Uuid,
+    pub name: String,
+    pub position: Position3D,
+    pub radius: f32,
+    /// Exposure removed per completed ritual.
+    pub cleanse_amount: f32,
+}
+
+/// Result of a cleansing, for the caller to report.
+#[derive(Debug, Clone, Serialize)]
+pub struct Cleansing {
+    pub amount: f32,
+    pub remaining: f32,
+    #[serde(skip)]
+    pub change: Option<StageChange>,
+}
+
+#[derive(Default)]
+pub struct InfectionTracker {
+    corruption: HashMap<GridCoordinate, f32>,
+    positions: HashMap<Uuid, Position3D>,
+    infections: HashMap<Uuid, SilenceInfection>,
+    ritual_sites: HashMap<Uuid, RitualSite>,
+    /// (player, site) -> last ritual
+    rituals_performed: HashMap<(Uuid, Uuid), DateTime<Utc>>,
+}
+
+impl InfectionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a grid's corruption (0.0..=1.0). Zero removes it.
+    pub fn set_corruption(&mut self, grid: GridCoordinate, level: f32) -> Result<(), InfectionError> {
+        if !(0.0..=1.0).contains(&level) {
+            return Err(InfectionError::Invalid("corruption must be between 0 and 1".to_string()));
+        }
+        if level == 0.0 {
+            self.corruption.remove(&grid);
+        } else {
+            self.corruption.insert(grid, level);
+        }
+        Ok(())
+    }
+
+    pub fn corruption_at(&self, position: &Position3D) -> f32 {
+        self.corruption.get(&position.to_grid_coordinate()).copied().unwrap_or(0.0)
+    }
+
+    pub fn update_position(&mut self, player_id: Uuid, position: Position3D) {
+        self.positions.insert(player_id, position);
+        self.infections.entry(player_id).or_default();
+    }
+
+    /// Forget a player who has left. Their infection should already be
+    /// saved with the character.
+    pub fn remove_player(&mut self, player_id: Uuid) -> Option<SilenceInfection> {
+        self.positions.remove(&player_id);
+        self.infections.remove(&player_id)
+    }
+
+    pub fn infection(&self, player_id: Uuid) -> SilenceInfection {
+        self.infections.get(&player_id).cloned().unwrap_or_default()
+    }
+
+    /// Restore a character's saved infection when they come back online.
+    pub fn restore(&mut self, player_id: Uuid, infection: SilenceInfection) -> Result<(), InfectionError> {
+        if !infection.exposure.is_finite() {
+            return Err(InfectionError::Invalid("exposure must be a finite number".to_string()));
+        }
+        // Re-derive the stage rather than trusting the stored one
+        self.infections.insert(player_id, infection.normalized());
+        Ok(())
+    }
+
+    /// Advance every tracked player by `seconds` of exposure to the grid
+    /// they stand in. Returns players whose stage changed.
+    pub fn tick(&mut self, seconds: f32) -> Vec<(Uuid, StageChange, f32)> {
+        let mut changes = Vec::new();
+        for (player_id, position) in &self.positions {
+            let corruption = self.corruption.get(&position.to_grid_coordinate()).copied().unwrap_or(0.0);
+            if corruption <= 0.0 {
+                continue;
+            }
+            let infection = self.infections.entry(*player_id).or_default();
+            if let Some(change) = infection.expose(corruption, seconds) {
+                changes.push((*player_id, change, infection.exposure));
+            }
+        }
+        changes
+    }
+
+    /// Cleanse with a restoration melody of the given power.
+    pub fn cleanse_with_melody(&mut self, player_id: Uuid, power: f32) -> Result<Cleansing, InfectionError> {
+        if !power.is_finite() || power < 0.0 {
+            return Err(InfectionError::Invalid("power must be a positive number".to_string()));
+        }
+        Ok(self.cleanse(player_id, power * RESTORATION_CLEANSE_PER_POWER))
+    }
+
+    /// Cleanse by performing the ritual of a site the player is standing in.
+    pub fn cleanse_at_site(&mut self, player_id: Uuid, site_id: Uuid) -> Result<Cleansing, InfectionError> {
+        let site = self.ritual_sites.get(&site_id).ok_or(InfectionError::UnknownSite(site_id))?;
+        let position = self.positions.get(&player_id).ok_or(InfectionError::UnknownPosition)?;
+        if position.distance_to(&site.position) > site.radius {
+            return Err(InfectionError::OutOfRange);
+        }
+        let now = Utc::now();
+        if let Some(last) = self.rituals_performed.get(&(player_id, site_id)) {
+            let ready_at = *last + Duration::minutes(RITUAL_COOLDOWN_MINUTES);
+            if now < ready_at {
+                return Err(InfectionError::OnCooldown(ready_at));
+            }
+        }
+        let amount = site.cleanse_amount;
+        self.rituals_performed.insert((player_id, site_id), now);
+        Ok(self.cleanse(player_id, amount))
+    }
+
+    pub fn add_ritual_site(&mut self, site: RitualSite) -> Result<(), InfectionError> {
+        if site.radius <= 0.0 || site.cleanse_amount <= 0.0 {
+            return Err(InfectionError::Invalid("radius and cleanse_amount must be positive".to_string()));
+        }
+        self.ritual_sites.insert(site.id, site);
+        Ok(())
+    }
+
+    pub fn ritual_sites(&self) -> Vec<RitualSite> {
+        self.ritual_sites.values().cloned().collect()
+    }
+
+    fn cleanse(&mut self, player_id: Uuid, amount: f32) -> Cleansing {
+        let infection = self.infections.entry(player_id).or_default();
+        let before = infection.exposure;
+        let change = infection.cleanse(amount);
+        Cleansing {
+            amount: before - infection.exposure,
+            remaining: infection.exposure,
+            change,
+        }
+    }
+}
@@ -1,9 +1,379 @@
-use axum::Router;
-use finalverse_health::HealthMonitor;
-use service_registry::LocalServiceRegistry;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use chrono::Utc;
+use finalverse_core::{RegionEffect, RegionEffects, RegionId, RegionMap, SilenceInfection, StageChange, REGION_MAP_PATH};
+use finalverse_events::{
+    self as events, Event, EventMetadata, EventType, GameEventBus, LocalEventBus, NatsEventBus, TenantEventBus,
+    PlayerId, SilenceEvent, SongEvent,
+};
+use finalverse_health::{DebugEndpoints, HealthMonitor, OperatorGuard, OPERATOR_TOKEN_HEADER};
+use finalverse_world3d::{GridCoordinate, Position3D};
+use serde::Deserialize;
+use service_registry::{LocalServiceRegistry, ServiceClient};
 use std::{net::SocketAddr, sync::Arc};
-use tracing::info;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
 use finalverse_logging as logging;
+use uuid::Uuid;
+
+mod infection;
+mod outbreak;
+
+use infection::{Cleansing, InfectionError, InfectionTracker, RitualSite};
+use outbreak::{Outbreak, OutbreakDirector, OutbreakPolicy, OutbreakStatus};
+
+/// Seconds between exposure ticks.
+const TICK_SECONDS: u64 = 5;
+
+/// Seconds between outbreak spreads.
+const SPREAD_SECONDS: u64 = 30;
+
+/// Seconds between looks for regions to break out, unless
+/// `SILENCE_OUTBREAK_INTERVAL_SECS` says otherwise.
+const DEFAULT_OUTBREAK_INTERVAL_SECS: u64 = 300;
+
+/// Discord an outbreak brings its region in world-engine, per unit of
+/// intensity. Cleansing the outbreak takes it away again.
+const OUTBREAK_DISCORD: f64 = 0.3;
+
+/// Harmony restored to a region when an outbreak there is cleansed.
+const CLEANSED_HARMONY: f64 = 0.1;
+
+/// The part of a world-engine region the director needs.
+#[derive(Debug, Deserialize)]
+struct RegionHarmony {
+    id: RegionId,
+    harmony_level: f64,
+}
+
+#[derive(Clone)]
+struct AppState {
+    tracker: Arc<RwLock<InfectionTracker>>,
+    outbreaks: Arc<RwLock<OutbreakDirector>>,
+    region_map: Arc<RwLock<RegionMap>>,
+    event_bus: Arc<dyn GameEventBus>,
+    services: ServiceClient,
+    /// Sends the operator token, for world-engine's region effects.
+    admin_services: ServiceClient,
+}
+
+impl AppState {
+    async fn publish(&self, silence_event: SilenceEvent) {
+        let tag = match silence_event {
+            SilenceEvent::SilenceOutbreak { .. }
+            | SilenceEvent::OutbreakSpread { .. }
+            | SilenceEvent::OutbreakCleansed { .. } => "outbreak",
+            _ => "infection",
+        };
+        let event = Event::new(EventType::Silence(silence_event)).with_metadata(EventMetadata {
+            source: Some("silence-service".to_string()),
+            tags: vec![tag.to_string()],
+            ..Default::default()
+        });
+        if let Err(e) = self.event_bus.publish(event).await {
+            warn!("Failed to publish silence event: {}", e);
+        }
+    }
+
+    /// Report what the Silence did to a region to world-engine, which owns
+    /// region state.
+    async fn report_effects(&self, region_id: &RegionId, effects: Vec<RegionEffect>) {
+        let path = format!("/regions/{}/effects", region_id.0);
+        let effects = RegionEffects::new("silence-service", effects);
+        if let Err(e) = self.admin_services.post_json::<_, serde_json::Value>("world-engine", &path, &effects).await {
+            warn!("Failed to report effects on region {} to world-engine: {}", region_id.0, e);
+        }
+    }
+
+    async fn publish_stage_change(&self, player_id: Uuid, change: StageChange, exposure: f32) {
+        info!("🌫️ Player {} infection {:?} -> {:?}", player_id, change.from, change.to);
+        self.publish(SilenceEvent::InfectionStageChanged {
+            player_id: PlayerId(player_id),
+            from: change.from,
+            to: change.to,
+            exposure,
+        })
+        .await;
+    }
+
+    async fn publish_cleansing(&self, player_id: Uuid, source: &str, cleansing: &Cleansing) {
+        if let Some(change) = cleansing.change {
+            self.publish_stage_change(player_id, change, cleansing.remaining).await;
+        }
+        self.publish(SilenceEvent::InfectionCleansed {
+            player_id: PlayerId(player_id),
+            source: source.to_string(),
+            amount: cleansing.amount,
+            remaining: cleansing.remaining,
+        })
+        .await;
+    }
+
+    async fn publish_progress(&self, outbreak: &Outbreak) {
+        match outbreak.status {
+            OutbreakStatus::Active => {
+                self.publish(SilenceEvent::OutbreakSpread {
+                    outbreak_id: outbreak.id,
+                    radius: outbreak.radius as f64,
+                    progress: outbreak.progress() as f64,
+                })
+                .await
+            }
+            OutbreakStatus::Cleansed => {
+                info!("🎶 Outbreak {} in region {} was cleansed", outbreak.id, outbreak.region_id.0);
+                self.report_effects(
+                    &outbreak.region_id,
+                    vec![
+                        RegionEffect::Discord { delta: -OUTBREAK_DISCORD * outbreak.intensity as f64 },
+                        RegionEffect::Harmony { delta: CLEANSED_HARMONY },
+                    ],
+                )
+                .await;
+                self.publish(SilenceEvent::OutbreakCleansed {
+                    outbreak_id: outbreak.id,
+                    region_id: outbreak.region_id.clone(),
+                    cleansers: outbreak.cleansers.iter().cloned().map(PlayerId).collect(),
+                })
+                .await
+            }
+        }
+    }
+
+    /// Break out in the lowest-harmony regions world-engine reports, where
+    /// there is room for more outbreaks.
+    async fn direct_outbreaks(&self) {
+        let regions = match self.services.get_json::<Vec<RegionHarmony>>("world-engine", "/regions").await {
+            Ok(regions) => regions,
+            Err(e) => {
+                warn!("Failed to fetch regions from world-engine: {}", e);
+                return;
+            }
+        };
+        if self.region_map.read().await.is_empty() {
+            match self.services.get_json::<RegionMap>("world-engine", REGION_MAP_PATH).await {
+                Ok(map) => *self.region_map.write().await = map,
+                Err(e) => warn!("Failed to fetch the region map from world-engine: {}", e),
+            }
+        }
+
+        let started = {
+            let map = self.region_map.read().await;
+            let mut director = self.outbreaks.write().await;
+            let candidates = director.candidates(regions.into_iter().map(|region| (region.id, region.harmony_level)));
+            let mut rng = rand::thread_rng();
+            candidates
+                .into_iter()
+                .filter_map(|(region_id, harmony)| {
+                    // Outbreaks need somewhere to start from
+                    let boundary = map.regions().iter().find(|boundary| boundary.id == region_id)?;
+                    let epicenter = outbreak::epicenter_in(&boundary.bounds, &mut rng);
+                    Some(director.start(region_id, harmony, epicenter, Utc::now()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for outbreak in started {
+            info!("🌑 The Silence broke out in region {} (intensity {:.2})", outbreak.region_id.0, outbreak.intensity);
+            self.report_effects(
+                &outbreak.region_id,
+                vec![RegionEffect::Discord { delta: OUTBREAK_DISCORD * outbreak.intensity as f64 }],
+            )
+            .await;
+            self.publish(SilenceEvent::SilenceOutbreak {
+                outbreak_id: outbreak.id,
+                region_id: outbreak.region_id,
+                epicenter: events::Coordinates {
+                    x: outbreak.epicenter.x as f64,
+                    y: outbreak.epicenter.y as f64,
+                    z: outbreak.epicenter.z as f64,
+                },
+                radius: outbreak.radius as f64,
+                intensity: outbreak.intensity as f64,
+            })
+            .await;
+        }
+    }
+
+    async fn spread_outbreaks(&self) {
+        let grown = {
+            let mut director = self.outbreaks.write().await;
+            director.prune(Utc::now());
+            director.spread(SPREAD_SECONDS as f32 / 60.0)
+        };
+        for outbreak in grown {
+            self.publish_progress(&outbreak).await;
+        }
+    }
+
+    /// Restoration melodies performed within an outbreak wear it away.
+    async fn melody_performed(&self, player_id: Uuid, location: &events::Coordinates, power: f64) {
+        let location = finalverse_core::types::Coordinates {
+            x: location.x as f32,
+            y: location.y as f32,
+            z: location.z as f32,
+        };
+        let touched = self
+            .outbreaks
+            .write()
+            .await
+            .cleanse(player_id, &location, power as f32, Utc::now());
+        for outbreak in touched {
+            self.publish_progress(&outbreak).await;
+        }
+    }
+
+    /// Accumulate exposure for everyone standing in a corrupted grid.
+    async fn tick(&self) {
+        let changes = self.tracker.write().await.tick(TICK_SECONDS as f32);
+        for (player_id, change, exposure) in changes {
+            self.publish_stage_change(player_id, change, exposure).await;
+        }
+    }
+}
+
+impl IntoResponse for InfectionError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            InfectionError::UnknownSite(_) | InfectionError::UnknownPosition => StatusCode::NOT_FOUND,
+            InfectionError::OutOfRange => StatusCode::FORBIDDEN,
+            InfectionError::OnCooldown(_) => StatusCode::TOO_MANY_REQUESTS,
+            InfectionError::Invalid(_) => StatusCode::BAD_REQUEST,
+        };
+        (status, Json(serde_json::json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CorruptionRequest {
+    grid: GridCoordinate,
+    level: f32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+enum CleanseRequest {
+    RestorationMelody { power: f32 },
+    RitualSite { site_id: Uuid },
+}
+
+#[derive(Debug, Deserialize)]
+struct RitualSiteRequest {
+    name: String,
+    position: Position3D,
+    radius: f32,
+    cleanse_amount: f32,
+}
+
+async fn set_corruption(
+    State(state): State<AppState>,
+    Json(request): Json<CorruptionRequest>,
+) -> Result<impl IntoResponse, InfectionError> {
+    state.tracker.write().await.set_corruption(request.grid, request.level)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn update_position(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+    Json(position): Json<Position3D>,
+) -> impl IntoResponse {
+    let mut tracker = state.tracker.write().await;
+    tracker.update_position(player_id, position);
+    Json(serde_json::json!({
+        "corruption": tracker.corruption_at(&position),
+        "infection": tracker.infection(player_id),
+    }))
+}
+
+async fn remove_player(State(state): State<AppState>, Path(player_id): Path<Uuid>) -> impl IntoResponse {
+    Json(state.tracker.write().await.remove_player(player_id).unwrap_or_default())
+}
+
+async fn get_infection(State(state): State<AppState>, Path(player_id): Path<Uuid>) -> impl IntoResponse {
+    let infection = state.tracker.read().await.infection(player_id);
+    Json(serde_json::json!({
+        "infection": infection,
+        "melody_power_multiplier": infection.melody_power_multiplier(),
+    }))
+}
+
+async fn restore_infection(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+    Json(infection): Json<SilenceInfection>,
+) -> Result<impl IntoResponse, InfectionError> {
+    state.tracker.write().await.restore(player_id, infection)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn cleanse(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+    Json(request): Json<CleanseRequest>,
+) -> Result<impl IntoResponse, InfectionError> {
+    let (source, cleansing) = {
+        let mut tracker = state.tracker.write().await;
+        match request {
+            CleanseRequest::RestorationMelody { power } => {
+                ("restoration_melody", tracker.cleanse_with_melody(player_id, power)?)
+            }
+            CleanseRequest::RitualSite { site_id } => ("ritual_site", tracker.cleanse_at_site(player_id, site_id)?),
+        }
+    };
+    if cleansing.amount > 0.0 {
+        state.publish_cleansing(player_id, source, &cleansing).await;
+    }
+    Ok(Json(cleansing))
+}
+
+#[derive(Debug, Deserialize)]
+struct OutbreakQuery {
+    #[serde(default)]
+    active: bool,
+}
+
+async fn list_outbreaks(State(state): State<AppState>, Query(query): Query<OutbreakQuery>) -> impl IntoResponse {
+    let mut outbreaks = state.outbreaks.read().await.list();
+    if query.active {
+        outbreaks.retain(|outbreak| outbreak.status == OutbreakStatus::Active);
+    }
+    Json(outbreaks)
+}
+
+async fn get_outbreak(State(state): State<AppState>, Path(outbreak_id): Path<Uuid>) -> impl IntoResponse {
+    match state.outbreaks.read().await.get(outbreak_id) {
+        Some(outbreak) => Json(serde_json::json!({
+            "outbreak": outbreak,
+            "progress": outbreak.progress(),
+        }))
+        .into_response(),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Unknown outbreak" }))).into_response(),
+    }
+}
+
+async fn list_ritual_sites(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.tracker.read().await.ritual_sites())
+}
+
+async fn add_ritual_site(
+    State(state): State<AppState>,
+    Json(request): Json<RitualSiteRequest>,
+) -> Result<impl IntoResponse, InfectionError> {
+    let site = RitualSite {
+        id: Uuid::new_v4(),
+        name: request.name,
+        position: request.position,
+        radius: request.radius,
+        cleanse_amount: request.cleanse_amount,
+    };
+    state.tracker.write().await.add_ritual_site(site.clone())?;
+    Ok((StatusCode::CREATED, Json(site)))
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -14,7 +384,92 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .register_service("silence-service".to_string(), "http://localhost:3009".to_string())
         .await;
 
-    let app = Router::new().merge(monitor.clone().axum_routes());
+    let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
+        info!("📡 Connecting to NATS at {}", nats_url);
+        Arc::new(NatsEventBus::new(&nats_url).await?)
+    } else {
+        info!("📦 Using local event bus");
+        Arc::new(LocalEventBus::new())
+    };
+    let event_bus = TenantEventBus::from_env(event_bus);
+
+    let services = ServiceClient::new(registry);
+    let admin_services = match OperatorGuard::load().token() {
+        Some(token) => services.with_header(OPERATOR_TOKEN_HEADER, token),
+        None => {
+            warn!("No operator token configured; world-engine will refuse outbreak effects");
+            services.clone()
+        }
+    };
+    let state = AppState {
+        tracker: Arc::new(RwLock::new(InfectionTracker::new())),
+        outbreaks: Arc::new(RwLock::new(OutbreakDirector::new(OutbreakPolicy::default()))),
+        region_map: Arc::new(RwLock::new(RegionMap::default())),
+        event_bus: event_bus.clone(),
+        services,
+        admin_services,
+    };
+
+    let tick_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(TICK_SECONDS));
+        loop {
+            interval.tick().await;
+            tick_state.tick().await;
+        }
+    });
+
+    let outbreak_interval = std::env::var("SILENCE_OUTBREAK_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(DEFAULT_OUTBREAK_INTERVAL_SECS);
+    let director_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(outbreak_interval));
+        loop {
+            interval.tick().await;
+            director_state.direct_outbreaks().await;
+        }
+    });
+
+    let spread_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(SPREAD_SECONDS));
+        loop {
+            interval.tick().await;
+            spread_state.spread_outbreaks().await;
+        }
+    });
+
+    let melody_state = state.clone();
+    event_bus
+        .subscribe(
+            "events.song",
+            Box::new(move |event| {
+                if let EventType::Song(SongEvent::MelodyWoven { player_id, harmony_type, power, location, .. }) =
+                    event.event_type
+                {
+                    if harmony_type == "restoration" {
+                        let state = melody_state.clone();
+                        tokio::spawn(async move { state.melody_performed(player_id.0, &location, power).await });
+                    }
+                }
+            }),
+        )
+        .await?;
+
+    let app = Router::new()
+        .route("/corruption", post(set_corruption))
+        .route("/players/:player_id/position", post(update_position))
+        .route("/players/:player_id", axum::routing::delete(remove_player))
+        .route("/players/:player_id/infection", get(get_infection).put(restore_infection))
+        .route("/players/:player_id/cleanse", post(cleanse))
+        .route("/ritual-sites", get(list_ritual_sites).post(add_ritual_site))
+        .route("/outbreaks", get(list_outbreaks))
+        .route("/outbreaks/:outbreak_id", get(get_outbreak))
+        .with_state(state)
+        .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("silence-service").with_event_bus(event_bus.clone())).axum_routes());
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3009));
     info!("Silence Service listening on {}", addr);
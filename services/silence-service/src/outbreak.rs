@@ -0,0 +1,254 @@
+// services/silence-service/src/outbreak.rs
+//! Silence outbreaks: the Silence breaks out in regions whose harmony has
+//! fallen low and spreads from its epicenter until melodies performed
+//! within it wear it away.
+
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::types::Coordinates;
+use finalverse_core::{RegionBounds, RegionId};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutbreakPolicy {
+    /// Regions whose harmony is below this can break out.
+    pub harmony_threshold: f64,
+    pub max_active: usize,
+    pub initial_radius: f32,
+    /// Radius gained per minute by an outbreak no one is cleansing.
+    pub growth_per_minute: f32,
+    pub max_radius: f32,
+    /// Melody power it takes to cleanse a full-intensity outbreak.
+    pub cleanse_required: f32,
+    /// How long cleansed outbreaks stay listed.
+    pub retain_minutes: i64,
+}
+
+impl Default for OutbreakPolicy {
+    fn default() -> Self {
+        Self {
+            harmony_threshold: 0.4,
+            max_active: 3,
+            initial_radius: 32.0,
+            growth_per_minute: 8.0,
+            max_radius: 512.0,
+            cleanse_required: 100.0,
+            retain_minutes: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutbreakStatus {
+    Active,
+    Cleansed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Outbreak {
+    pub id: Uuid,
+    pub region_id: RegionId,
+    pub epicenter: Coordinates,
+    pub radius: f32,
+    /// 0.1..=1.0, by how far the region's harmony had fallen.
+    pub intensity: f32,
+    /// Melody power needed to cleanse it.
+    pub required: f32,
+    pub cleansed: f32,
+    pub status: OutbreakStatus,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Players whose melodies went into cleansing it.
+    pub cleansers: BTreeSet<Uuid>,
+}
+
+impl Outbreak {
+    /// Share cleansed so far, 0.0..=1.0.
+    pub fn progress(&self) -> f32 {
+        (self.cleansed / self.required).min(1.0)
+    }
+
+    /// Whether `location` is within reach, measured on the ground plane.
+    pub fn reaches(&self, location: &Coordinates) -> bool {
+        let (dx, dy) = (location.x - self.epicenter.x, location.y - self.epicenter.y);
+        (dx * dx + dy * dy).sqrt() <= self.radius
+    }
+}
+
+/// A random point within a region, for an outbreak to start from.
+pub fn epicenter_in(bounds: &RegionBounds, rng: &mut impl Rng) -> Coordinates {
+    let at = |x, y| Coordinates { x, y, z: 0.0 };
+    match bounds {
+        RegionBounds::Rect { min, max } => at(rng.gen_range(min[0]..=max[0]), rng.gen_range(min[1]..=max[1])),
+        RegionBounds::Polygon { points } => {
+            let Some(first) = points.first() else {
+                return at(0.0, 0.0);
+            };
+            let (min, max) = points.iter().fold((*first, *first), |(min, max), point| {
+                ([min[0].min(point[0]), min[1].min(point[1])], [max[0].max(point[0]), max[1].max(point[1])])
+            });
+            // Sample the bounding box; thin polygons may need a few tries
+            for _ in 0..32 {
+                let (x, y) = (rng.gen_range(min[0]..=max[0]), rng.gen_range(min[1]..=max[1]));
+                if bounds.contains(x, y) {
+                    return at(x, y);
+                }
+            }
+            at(first[0], first[1])
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OutbreakDirector {
+    policy: OutbreakPolicy,
+    outbreaks: HashMap<Uuid, Outbreak>,
+}
+
+impl OutbreakDirector {
+    pub fn new(policy: OutbreakPolicy) -> Self {
+        Self {
+            policy,
+            outbreaks: HashMap::new(),
+        }
+    }
+
+    /// Every listed outbreak, newest first.
+    pub fn list(&self) -> Vec<Outbreak> {
+        let mut outbreaks: Vec<_> = self.outbreaks.values().cloned().collect();
+        outbreaks.sort_by_key(|outbreak| std::cmp::Reverse(outbreak.started_at));
+        outbreaks
+    }
+
+    pub fn get(&self, outbreak_id: Uuid) -> Option<&Outbreak> {
+        self.outbreaks.get(&outbreak_id)
+    }
+
+    fn active(&self) -> impl Iterator<Item = &Outbreak> {
+        self.outbreaks
+            .values()
+            .filter(|outbreak| outbreak.status == OutbreakStatus::Active)
+    }
+
+    /// Regions that should break out now, lowest harmony first: those under
+    /// the threshold without an active outbreak, up to the active limit.
+    pub fn candidates(&self, regions: impl IntoIterator<Item = (RegionId, f64)>) -> Vec<(RegionId, f64)> {
+        let room = self.policy.max_active.saturating_sub(self.active().count());
+        let mut candidates: Vec<_> = regions
+            .into_iter()
+            .filter(|(_, harmony)| *harmony < self.policy.harmony_threshold)
+            .filter(|(region_id, _)| !self.active().any(|outbreak| &outbreak.region_id == region_id))
+            .collect();
+        candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        candidates.truncate(room);
+        candidates
+    }
+
+    pub fn start(&mut self, region_id: RegionId, harmony: f64, epicenter: Coordinates, now: DateTime<Utc>) -> Outbreak {
+        let threshold = self.policy.harmony_threshold;
+        let intensity = (((threshold - harmony) / threshold) as f32).clamp(0.1, 1.0);
+        let outbreak = Outbreak {
+            id: Uuid::new_v4(),
+            region_id,
+            epicenter,
+            radius: self.policy.initial_radius,
+            intensity,
+            required: self.policy.cleanse_required * intensity,
+            cleansed: 0.0,
+            status: OutbreakStatus::Active,
+            started_at: now,
+            ended_at: None,
+            cleansers: BTreeSet::new(),
+        };
+        self.outbreaks.insert(outbreak.id, outbreak.clone());
+        outbreak
+    }
+
+    /// Grow active outbreaks by `minutes` of spread, returning those that
+    /// grew. Cleansing slows the spread in proportion to its progress.
+    pub fn spread(&mut self, minutes: f32) -> Vec<Outbreak> {
+        let policy = &self.policy;
+        self.outbreaks
+            .values_mut()
+            .filter(|outbreak| outbreak.status == OutbreakStatus::Active && outbreak.radius < policy.max_radius)
+            .filter_map(|outbreak| {
+                let growth = policy.growth_per_minute * minutes * (1.0 - outbreak.progress());
+                (growth > 0.0).then(|| {
+                    outbreak.radius = (outbreak.radius + growth).min(policy.max_radius);
+                    outbreak.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Apply a melody of `power` performed at `location` to every active
+    /// outbreak that reaches it, returning those it touched.
+    pub fn cleanse(&mut self, player_id: Uuid, location: &Coordinates, power: f32, now: DateTime<Utc>) -> Vec<Outbreak> {
+        if power <= 0.0 {
+            return Vec::new();
+        }
+        self.outbreaks
+            .values_mut()
+            .filter(|outbreak| outbreak.status == OutbreakStatus::Active && outbreak.reaches(location))
+            .map(|outbreak| {
+                outbreak.cleansed = (outbreak.cleansed + power).min(outbreak.required);
+                outbreak.cleansers.insert(player_id);
+                if outbreak.cleansed >= outbreak.required {
+                    outbreak.status = OutbreakStatus::Cleansed;
+                    outbreak.ended_at = Some(now);
+                }
+                outbreak.clone()
+            })
+            .collect()
+    }
+
+    /// Stop listing outbreaks cleansed more than the retention period ago.
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let retain = Duration::minutes(self.policy.retain_minutes);
+        self.outbreaks
+            .retain(|_, outbreak| outbreak.ended_at.is_none_or(|ended_at| now - ended_at < retain));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outbreaks_spread_until_melodies_cleanse_them() {
+        let mut director = OutbreakDirector::new(OutbreakPolicy {
+            max_active: 1,
+            ..Default::default()
+        });
+        let (bleak, dim, bright) = (RegionId(Uuid::new_v4()), RegionId(Uuid::new_v4()), RegionId(Uuid::new_v4()));
+        let regions = [(dim.clone(), 0.3), (bleak.clone(), 0.0), (bright, 0.9)];
+        let now = Utc::now();
+
+        assert_eq!(director.candidates(regions.clone()), vec![(bleak.clone(), 0.0)]);
+        let epicenter = Coordinates { x: 100.0, y: 100.0, z: 0.0 };
+        let outbreak = director.start(bleak, 0.0, epicenter, now);
+        assert_eq!(outbreak.required, 100.0);
+        assert!(director.candidates(regions).is_empty());
+
+        let grown = director.spread(2.0);
+        assert_eq!(grown[0].radius, 48.0);
+
+        let outside = Coordinates { x: 200.0, y: 100.0, z: 0.0 };
+        assert!(director.cleanse(Uuid::from_u128(1), &outside, 50.0, now).is_empty());
+        let inside = Coordinates { x: 130.0, y: 100.0, z: 0.0 };
+        director.cleanse(Uuid::from_u128(1), &inside, 50.0, now);
+        // Half cleansed, so it spreads at half speed
+        assert_eq!(director.spread(2.0)[0].radius, 56.0);
+
+        let touched = director.cleanse(Uuid::from_u128(2), &inside, 80.0, now);
+        assert_eq!(touched[0].status, OutbreakStatus::Cleansed);
+        assert_eq!(touched[0].cleansers.len(), 2);
+        assert!(director.spread(2.0).is_empty());
+
+        director.prune(now + Duration::minutes(61));
+        assert!(director.list().is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+// services/song-engine/src/analysis.rs
+//! Musical analysis of performed melodies. A melody's power comes from how
+//! consonant its intervals are, how varied its rhythm is, and how well it
+//! stays within the scale of the region it is performed in.
+
+use finalverse_audio_core::{MoodDescriptor, MusicalTheme, Scale};
+use finalverse_core::types::{Melody, Note};
+
+/// Power each note contributes before the melody's quality is applied.
+const POWER_PER_NOTE: f32 = 0.5;
+/// Beat subdivision that durations and onsets are rounded to.
+const BEAT_GRID: f32 = 0.25;
+/// Distinct note lengths at which a rhythm counts as fully varied.
+const VARIED_DURATIONS: usize = 4;
+
+/// How pleasant each melodic interval sounds, indexed by semitones within
+/// an octave. Unisons are consonant but static, so they score below the
+/// perfect intervals; leaps of whole octaves are scored separately.
+const INTERVAL_CONSONANCE: [f32; 12] = [
+    0.6,  // unison
+    0.1,  // minor second
+    0.4,  // major second
+    0.7,  // minor third
+    0.75, // major third
+    0.8,  // perfect fourth
+    0.0,  // tritone
+    0.9,  // perfect fifth
+    0.65, // minor sixth
+    0.7,  // major sixth
+    0.35, // minor seventh
+    0.15, // major seventh
+];
+const OCTAVE_CONSONANCE: f32 = 0.9;
+
+/// How one melody scores, each score from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MelodyAnalysis {
+    pub notes: usize,
+    /// Average consonance of the intervals between consecutive notes.
+    pub consonance: f32,
+    /// How varied the note lengths are and how often notes start off the beat.
+    pub rhythm_complexity: f32,
+    /// Share of the melody's sounding time spent on notes of the theme's
+    /// scale, in whichever key fits best.
+    pub scale_adherence: f32,
+}
+
+impl MelodyAnalysis {
+    /// Between 0.5 and 1.5: a consonant, in-scale, rhythmically varied
+    /// melody is worth three times a clashing, monotonous one.
+    pub fn quality(&self) -> f32 {
+        0.5 + self.consonance * 0.4 + self.scale_adherence * 0.4 + self.rhythm_complexity * 0.2
+    }
+
+    pub fn power(&self) -> f32 {
+        self.notes as f32 * POWER_PER_NOTE * self.quality()
+    }
+}
+
+pub fn analyze(melody: &Melody, theme: &MusicalTheme) -> MelodyAnalysis {
+    let tempo = if melody.tempo > 0.0 { melody.tempo } else { theme.tempo };
+    MelodyAnalysis {
+        notes: melody.notes.len(),
+        consonance: consonance(&melody.notes),
+        rhythm_complexity: rhythm_complexity(&melody.notes, tempo),
+        scale_adherence: scale_adherence(&melody.notes, &theme.base_scale),
+    }
+}
+
+/// A theme with nothing but a scale and tempo, for regions without one.
+pub fn plain_theme(id: impl Into<String>, base_scale: Scale, tempo: f32) -> MusicalTheme {
+    MusicalTheme {
+        id: id.into(),
+        base_scale,
+        tempo,
+        mood: MoodDescriptor {
+            valence: 0.0,
+            energy: 0.5,
+            tension: 0.0,
+        },
+        instrumentation: Vec::new(),
+    }
+}
+
+/// MIDI note number, fractional for notes between semitones. Notes with no
+/// pitch are rests.
+fn pitch(note: &Note) -> Option<f32> {
+    (note.frequency > 0.0).then(|| 69.0 + 12.0 * (note.frequency / 440.0).log2())
+}
+
+fn consonance(notes: &[Note]) -> f32 {
+    let pitches: Vec<i32> = notes.iter().filter_map(pitch).map(|p| p.round() as i32).collect();
+    let scores: Vec<f32> = pitches
+        .windows(2)
+        .map(|pair| {
+            let interval = (pair[1] - pair[0]).abs();
+            if interval > 0 && interval % 12 == 0 {
+                OCTAVE_CONSONANCE
+            } else {
+                INTERVAL_CONSONANCE[(interval % 12) as usize]
+            }
+        })
+        .collect();
+    if scores.is_empty() {
+        return 0.0;
+    }
+    scores.iter().sum::<f32>() / scores.len() as f32
+}
+
+fn rhythm_complexity(notes: &[Note], tempo: f32) -> f32 {
+    if notes.len() < 2 {
+        return 0.0;
+    }
+    let beats_per_second = tempo / 60.0;
+    let grid = |seconds: f32| (seconds * beats_per_second / BEAT_GRID).round() as i64;
+
+    let mut durations: Vec<i64> = notes.iter().map(|note| grid(note.duration.max(0.0))).collect();
+    let steps_per_beat = (1.0 / BEAT_GRID) as i64;
+    let mut onset = 0;
+    let mut off_beat = 0;
+    for duration in &durations {
+        if onset % steps_per_beat != 0 {
+            off_beat += 1;
+        }
+        onset += duration;
+    }
+    durations.sort_unstable();
+    durations.dedup();
+
+    let variety = ((durations.len() - 1) as f32 / (VARIED_DURATIONS - 1) as f32).min(1.0);
+    let syncopation = off_beat as f32 / notes.len() as f32;
+    (variety + syncopation) / 2.0
+}
+
+fn scale_steps(scale: &Scale) -> &'static [i32] {
+    match scale {
+        Scale::Major => &[0, 2, 4, 5, 7, 9, 11],
+        Scale::Minor => &[0, 2, 3, 5, 7, 8, 10],
+        Scale::Pentatonic => &[0, 2, 4, 7, 9],
+        Scale::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+        Scale::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+        Scale::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+        Scale::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+    }
+}
+
+fn scale_adherence(notes: &[Note], scale: &Scale) -> f32 {
+    let sounding: Vec<(i32, f32)> = notes
+        .iter()
+        .filter_map(|note| pitch(note).map(|p| ((p.round() as i32).rem_euclid(12), note.duration.max(0.0))))
+        .collect();
+    let total: f32 = sounding.iter().map(|(_, duration)| duration).sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let steps = scale_steps(scale);
+    (0..12)
+        .map(|key| {
+            sounding
+                .iter()
+                .filter(|(class, _)| steps.contains(&(class - key).rem_euclid(12)))
+                .map(|(_, duration)| duration)
+                .sum::<f32>()
+        })
+        .fold(0.0, f32::max)
+        / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finalverse_core::types::HarmonyType;
+
+    /// Notes by MIDI number and length in beats, at 60 BPM.
+    fn melody(notes: &[(i32, f32)]) -> Melody {
+        Melody {
+            notes: notes
+                .iter()
+                .map(|&(midi, beats)| Note {
+                    frequency: 440.0 * 2f32.powf((midi - 69) as f32 / 12.0),
+                    duration: beats,
+                    intensity: 0.8,
+                })
+                .collect(),
+            tempo: 60.0,
+            harmony_type: HarmonyType::Creative,
+        }
+    }
+
+    fn ode_to_joy() -> Melody {
+        // E E F G G F E D C C D E E. D D
+        melody(&[
+            (64, 1.0), (64, 1.0), (65, 1.0), (67, 1.0),
+            (67, 1.0), (65, 1.0), (64, 1.0), (62, 1.0),
+            (60, 1.0), (60, 1.0), (62, 1.0), (64, 1.0),
+            (64, 1.5), (62, 0.5), (62, 2.0),
+        ])
+    }
+
+    /// The same length as Ode to Joy, leaping by tritones and semitones.
+    fn clashing() -> Melody {
+        melody(&[
+            (60, 1.0), (66, 1.0), (67, 1.0), (61, 1.0),
+            (62, 1.0), (68, 1.0), (69, 1.0), (63, 1.0),
+            (64, 1.0), (70, 1.0), (71, 1.0), (65, 1.0),
+            (66, 1.0), (72, 1.0), (73, 1.0),
+        ])
+    }
+
+    #[test]
+    fn known_melodies_score_as_expected() {
+        let major = plain_theme("major", Scale::Major, 60.0);
+        let ode = analyze(&ode_to_joy(), &major);
+        let clash = analyze(&clashing(), &major);
+
+        assert_eq!(ode.scale_adherence, 1.0);
+        assert!(clash.scale_adherence < 0.8);
+        assert!(ode.consonance > 0.4);
+        assert!(clash.consonance < 0.2);
+        assert!(ode.rhythm_complexity > clash.rhythm_complexity);
+        assert!(ode.power() > clash.power() * 1.3);
+
+        // No pentatonic key has Ode to Joy's E to F semitone
+        let pentatonic = plain_theme("pentatonic", Scale::Pentatonic, 60.0);
+        assert!(analyze(&ode_to_joy(), &pentatonic).scale_adherence < 1.0);
+        // and nothing leaves the chromatic scale
+        let chromatic = plain_theme("chromatic", Scale::Chromatic, 60.0);
+        assert_eq!(analyze(&clashing(), &chromatic).scale_adherence, 1.0);
+    }
+
+    #[test]
+    fn empty_and_single_note_melodies_have_no_power() {
+        let theme = plain_theme("major", Scale::Major, 60.0);
+        let empty = analyze(&melody(&[]), &theme);
+        assert_eq!(empty.power(), 0.0);
+
+        let single = analyze(&melody(&[(60, 1.0)]), &theme);
+        assert_eq!(single.consonance, 0.0);
+        assert_eq!(single.rhythm_complexity, 0.0);
+        assert!(single.power() > 0.0);
+    }
+}
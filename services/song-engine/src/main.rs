@@ -1,53 +1,250 @@
 use axum::{
-    extract::State,
+    extract::{FromRef, Path, State},
     http::StatusCode,
     response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
+use chrono::Utc;
+use finalverse_auth::{axum::require_player, AuthenticatedPlayer, PlayerAuth};
+use finalverse_config::{MelodyLimitSettings, StaminaSettings};
+use finalverse_idempotency::{axum::idempotent, IdempotencyCache};
+use finalverse_ratelimit::{axum::limit_requests, RateLimits};
 use finalverse_core::{
     events::{SongEvent, HarmonyEvent},
     types::{Coordinates, Melody, PlayerId, RegionId, HarmonyType, Note},
-    FinalverseError, Result,
+    FinalverseError, RegionEffect, RegionEffects, RegionMap, Result, Stamina, StaminaStatus, REGION_MAP_PATH,
 };
 use serde::{Deserialize, Serialize};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::{
-    collections::HashMap,
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
     net::SocketAddr,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex},
 };
 use tokio;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use uuid::Uuid;
-use finalverse_health::HealthMonitor;
-use service_registry::LocalServiceRegistry;
-use tracing::info;
+use arc_swap::ArcSwap;
+use finalverse_audio_core::{MusicalTheme, Scale};
+use finalverse_health::{require_operator, DebugEndpoints, HealthMonitor, OperatorGuard, OPERATOR_TOKEN_HEADER};
+use finalverse_events::{self as events, Event, EventMetadata, EventType, GameEventBus, LocalEventBus, NatsEventBus, TenantEventBus};
+use finalverse_metrics::{counter, histogram, CounterDef, HistogramDef};
+use service_registry::{LocalServiceRegistry, ServiceClient};
+use tracing::{info, warn};
 use finalverse_logging as logging;
 
+mod analysis;
+
 #[derive(Debug, Clone)]
 pub struct SongEngineState {
     global_harmony: f32,
     regional_harmony: HashMap<RegionId, f32>,
     active_melodies: HashMap<String, Melody>,
     silence_corruption: HashMap<RegionId, f32>,
+    stamina_settings: StaminaSettings,
+    stamina: HashMap<Uuid, Stamina>,
+    /// Where each player last wove a melody, for rest locations.
+    positions: HashMap<Uuid, Coordinates>,
+    /// Melodies are judged against the scale of the region they are
+    /// performed in; other regions use a major scale.
+    regional_themes: HashMap<RegionId, MusicalTheme>,
+    region_map: RegionMap,
+    melody_limits: MelodyLimitSettings,
+    /// Each player's recent melodies, oldest first, for cooldowns and
+    /// diminishing returns.
+    melody_history: HashMap<Uuid, VecDeque<PastMelody>>,
+    /// Mixed into preview jitter so players can't work out the seed.
+    preview_salt: u64,
 }
 
-type SharedSongState = Arc<RwLock<SongEngineState>>;
+#[derive(Debug, Clone)]
+struct PastMelody {
+    at: chrono::DateTime<Utc>,
+    region: Option<RegionId>,
+}
 
-#[derive(Serialize)]
-struct ServiceInfo {
-    name: String,
-    version: String,
-    status: String,
+/// What harmony checks read, republished after every write.
+#[derive(Debug, Clone)]
+struct HarmonySnapshot {
     global_harmony: f32,
+    regional_harmony: HashMap<RegionId, f32>,
+    silence_corruption: HashMap<RegionId, f32>,
+    active_melodies_count: usize,
+    dominant_song_fragments: Vec<String>,
+}
+
+/// Song state behind a single writer lock. Each write publishes an
+/// immutable snapshot, so harmony checks never wait on a melody being
+/// performed.
+struct SongEngine {
+    state: Mutex<SongEngineState>,
+    snapshot: ArcSwap<HarmonySnapshot>,
+}
+
+impl SongEngine {
+    fn new(state: SongEngineState) -> Self {
+        Self {
+            snapshot: ArcSwap::from_pointee(state.snapshot()),
+            state: Mutex::new(state),
+        }
+    }
+
+    fn snapshot(&self) -> Arc<HarmonySnapshot> {
+        self.snapshot.load_full()
+    }
+
+    fn read<R>(&self, read: impl FnOnce(&SongEngineState) -> R) -> R {
+        read(&self.state.lock().unwrap())
+    }
+
+    fn write<R>(&self, change: impl FnOnce(&mut SongEngineState) -> R) -> R {
+        let mut state = self.state.lock().unwrap();
+        let result = change(&mut state);
+        self.snapshot.store(Arc::new(state.snapshot()));
+        result
+    }
+}
+
+type SharedSongState = Arc<SongEngine>;
+
+#[derive(Clone)]
+struct AppState {
+    song: SharedSongState,
+    services: ServiceClient,
+    /// Sends the operator token, for world-engine's region effects.
+    admin_services: ServiceClient,
+    event_bus: Arc<dyn GameEventBus>,
+    max_melody_notes: usize,
+}
+
+impl AppState {
+    /// Refuse melodies longer than `[song_engine] max_melody_notes`.
+    fn check_length(&self, notes: usize) -> std::result::Result<(), (StatusCode, Json<serde_json::Value>)> {
+        if notes > self.max_melody_notes {
+            return Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({
+                "error": format!("Melodies can have at most {} notes", self.max_melody_notes)
+            }))));
+        }
+        Ok(())
+    }
+
+    async fn publish(&self, song_event: events::SongEvent) {
+        let event = Event::new(EventType::Song(song_event)).with_metadata(EventMetadata {
+            source: Some("song-engine".to_string()),
+            tags: vec!["melody".to_string()],
+            ..Default::default()
+        });
+        if let Err(e) = self.event_bus.publish(event).await {
+            warn!("Failed to publish song event: {}", e);
+        }
+    }
+
+    /// Report the harmony a melody moved to world-engine, which owns region
+    /// state, and take the region's harmony it answers with as ours.
+    /// `None` outside every region or when world-engine can't be reached.
+    async fn report_harmony(&self, performed: &PerformedMelody) -> Option<f32> {
+        let region = performed.outcome.region.as_ref()?;
+        let effects = RegionEffects::new(
+            "song-engine",
+            vec![RegionEffect::Harmony { delta: (performed.outcome.harmony_modifier / 100.0) as f64 }],
+        );
+        let path = format!("/regions/{}/effects", region.0);
+        match self.admin_services.post_json::<_, RegionHarmony>("world-engine", &path, &effects).await {
+            Ok(reply) => Some(self.song.write(|song| song.reconcile_harmony(region, reply.harmony_level))),
+            Err(e) => {
+                warn!("Failed to report harmony in region {} to world-engine: {}", region.0, e);
+                None
+            }
+        }
+    }
+
+    /// Tell the rest of the world about a melody and the harmony it moved.
+    async fn publish_melody(&self, performed: &PerformedMelody) {
+        let harmony_level = self.report_harmony(performed).await.unwrap_or(performed.harmony_level);
+        let player_id = events::PlayerId(performed.player_id);
+        let region_id = performed.outcome.region.clone();
+        let location = events::Coordinates {
+            x: performed.location.x as f64,
+            y: performed.location.y as f64,
+            z: performed.location.z as f64,
+        };
+        self.publish(events::SongEvent::MelodyWoven {
+            player_id: player_id.clone(),
+            harmony_type: harmony_type_name(&performed.harmony_type).to_string(),
+            power: performed.outcome.power as f64,
+            location,
+            region_id: region_id.clone(),
+        })
+        .await;
+        self.publish(events::SongEvent::HarmonyAchieved {
+            player_id,
+            region_id,
+            harmony_impact: performed.outcome.harmony_modifier as f64,
+            harmony_level: harmony_level as f64,
+        })
+        .await;
+    }
 }
 
+impl FromRef<AppState> for SharedSongState {
+    fn from_ref(state: &AppState) -> Self {
+        state.song.clone()
+    }
+}
+
+/// The part of a world-engine region song-engine keeps.
+#[derive(Deserialize)]
+struct RegionHarmony {
+    /// 0..=1, where song-engine's harmony runs 0..=100.
+    harmony_level: f64,
+}
+
+#[derive(Deserialize)]
+struct InfectionStatus {
+    melody_power_multiplier: f32,
+}
+
+/// Harmony of a region before any melody has been performed in it.
+const STARTING_HARMONY: f32 = 50.0;
+
+/// Relative jitter applied to dry-run numbers so previews can't be used to
+/// pin down exact effect thresholds.
+const PREVIEW_FUZZ: f32 = 0.1;
+
+const MELODIES: CounterDef<2> = CounterDef::new(
+    "finalverse_song_melodies_total",
+    "Melodies performed or previewed",
+    ["harmony_type", "dry_run"],
+);
+
+const COOLDOWN_REJECTIONS: CounterDef<1> = CounterDef::new(
+    "finalverse_song_cooldown_rejections_total",
+    "Melodies refused because the performer's cooldown hadn't passed",
+    ["harmony_type"],
+);
+
+const STAMINA_REJECTIONS: CounterDef<1> = CounterDef::new(
+    "finalverse_song_stamina_rejections_total",
+    "Melodies refused because the performer lacked stamina",
+    ["harmony_type"],
+);
+
+const HARMONY_IMPACT: HistogramDef<1> = HistogramDef::new(
+    "finalverse_song_harmony_impact",
+    "Harmony change caused by performed melodies",
+    ["harmony_type"],
+);
+
 #[derive(Deserialize)]
 struct PerformMelodyRequest {
-    player_id: String,
     melody: MelodyRequest,
     target_location: CoordinatesRequest,
+    /// Compute the outcome against current state without applying it.
+    #[serde(default)]
+    dry_run: bool,
 }
 
 #[derive(Deserialize)]
@@ -78,6 +275,64 @@ struct PerformMelodyResponse {
     harmony_impact: f32,
     message: String,
     effects: Vec<String>,
+    /// True when nothing was applied and the figures are approximate.
+    dry_run: bool,
+    stamina_cost: f32,
+    /// After the cost was paid, or as things stand for a dry run.
+    stamina: StaminaStatus,
+    /// Below 1 when the player has performed in this region recently.
+    repeat_multiplier: f32,
+}
+
+/// Why a melody was refused.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum MelodyRefusal {
+    Stamina(StaminaShortfall),
+    Cooldown(CooldownActive),
+}
+
+impl MelodyRefusal {
+    fn status(&self) -> StatusCode {
+        match self {
+            MelodyRefusal::Stamina(_) => StatusCode::CONFLICT,
+            MelodyRefusal::Cooldown(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StaminaShortfall {
+    error: String,
+    cost: f32,
+    missing: f32,
+    stamina: StaminaStatus,
+}
+
+#[derive(Debug, Serialize)]
+struct CooldownActive {
+    error: String,
+    remaining_seconds: f32,
+}
+
+/// What a melody would do to the world, before it is applied.
+struct MelodyOutcome {
+    /// None outside every mapped region.
+    region: Option<RegionId>,
+    power: f32,
+    harmony_modifier: f32,
+    repeat_multiplier: f32,
+}
+
+/// A melody that was paid for and applied.
+struct PerformedMelody {
+    player_id: Uuid,
+    harmony_type: HarmonyType,
+    location: Coordinates,
+    response: PerformMelodyResponse,
+    outcome: MelodyOutcome,
+    /// The harmony of the melody's region afterwards, or the world's.
+    harmony_level: f32,
 }
 
 #[derive(Deserialize)]
@@ -94,13 +349,20 @@ struct HarmonyCheckResponse {
 }
 
 impl SongEngineState {
-    pub fn new() -> Self {
-        let mut regional_harmony = HashMap::new();
-        regional_harmony.insert(RegionId(Uuid::new_v4()), 75.0);
-        regional_harmony.insert(RegionId(Uuid::new_v4()), 45.0);
-        regional_harmony.insert(RegionId(Uuid::new_v4()), 60.0);
-        regional_harmony.insert(RegionId(Uuid::new_v4()), 80.0);
-        regional_harmony.insert(RegionId(Uuid::new_v4()), 55.0);
+    pub fn new(stamina_settings: StaminaSettings, melody_limits: MelodyLimitSettings, region_map: RegionMap) -> Self {
+        let regional_harmony = region_map
+            .regions()
+            .iter()
+            .map(|region| (region.id.clone(), STARTING_HARMONY))
+            .collect();
+
+        let scales = [Scale::Major, Scale::Dorian, Scale::Pentatonic, Scale::Lydian, Scale::Minor];
+        let regional_themes = region_map
+            .regions()
+            .iter()
+            .zip(scales.into_iter().cycle())
+            .map(|(region, scale)| (region.id.clone(), analysis::plain_theme(format!("{} theme", region.name), scale, 90.0)))
+            .collect();
 
         let mut silence_corruption = HashMap::new();
         silence_corruption.insert(RegionId(Uuid::new_v4()), 25.0);
@@ -111,73 +373,255 @@ impl SongEngineState {
             regional_harmony,
             active_melodies: HashMap::new(),
             silence_corruption,
+            stamina_settings,
+            stamina: HashMap::new(),
+            positions: HashMap::new(),
+            regional_themes,
+            region_map,
+            melody_limits,
+            melody_history: HashMap::new(),
+            preview_salt: rand::random(),
         }
     }
 
-    pub fn perform_melody(&mut self, melody: Melody, location: Coordinates, player_id: PlayerId) -> PerformMelodyResponse {
-        // Calculate melody power based on complexity and harmony
-        let melody_power = self.calculate_melody_power(&melody);
+    /// Seconds until `player` may perform again, if they must wait.
+    fn cooldown_remaining(&self, player: &Uuid, now: chrono::DateTime<Utc>) -> Option<f32> {
+        let last = self.melody_history.get(player)?.back()?;
+        let remaining = self.melody_limits.cooldown_seconds - seconds_between(last.at, now);
+        (remaining > 0.0).then_some(remaining)
+    }
 
-        // Determine region from coordinates (simplified)
-        let region = self.determine_region_from_coordinates(&location);
+    /// Power multiplier for `player`'s next melody in `region`.
+    fn repeat_multiplier(&self, player: &Uuid, region: Option<&RegionId>, now: chrono::DateTime<Utc>) -> f32 {
+        let recent = self.melody_history.get(player).map_or(0, |history| {
+            history
+                .iter()
+                .filter(|past| past.region.as_ref() == region)
+                .filter(|past| seconds_between(past.at, now) < self.melody_limits.repeat_window_seconds)
+                .count()
+        });
+        self.melody_limits.repeat_multiplier(recent)
+    }
 
-        // Apply harmony effects
-        let harmony_impact = self.apply_harmony_effects(&region, melody_power, &melody.harmony_type);
+    fn record_melody(&mut self, player: Uuid, region: Option<RegionId>, now: chrono::DateTime<Utc>) {
+        let horizon = self.melody_limits.repeat_window_seconds.max(self.melody_limits.cooldown_seconds);
+        let history = self.melody_history.entry(player).or_default();
+        while history.front().is_some_and(|past| seconds_between(past.at, now) >= horizon) {
+            history.pop_front();
+        }
+        history.push_back(PastMelody { at: now, region });
+    }
+
+    /// How fast the player's stamina refills where they are, and whether
+    /// they are resting.
+    fn stamina_regen(&self, player: &Uuid) -> (f32, bool) {
+        let position = self.positions.get(player);
+        let resting = position.is_some_and(|p| self.stamina_settings.rest_location_at([p.x, p.y, p.z]).is_some());
+        let harmony = position
+            .and_then(|p| self.determine_region_from_coordinates(p))
+            .and_then(|region| self.regional_harmony.get(&region))
+            .copied()
+            .unwrap_or(self.global_harmony);
+        (self.stamina_settings.regen_rate(harmony, resting), resting)
+    }
 
-        // Calculate resonance gained for the player
-        let resonance_gained = melody_power * 2.0;
+    /// The player's stamina brought up to `now`, without storing it.
+    fn stamina_status(&self, player: &Uuid, now: chrono::DateTime<Utc>) -> StaminaStatus {
+        let (rate, resting) = self.stamina_regen(player);
+        let mut stamina = self
+            .stamina
+            .get(player)
+            .cloned()
+            .unwrap_or_else(|| Stamina::full(self.stamina_settings.max_stamina, now));
+        stamina.regenerate(rate, now);
+        stamina.status(rate, resting)
+    }
 
-        // Generate effects based on harmony type and power
-        let effects = self.generate_melody_effects(&melody.harmony_type, melody_power, &region);
+    /// Bring the player's stamina up to `now` and pay `cost` from it.
+    fn spend_stamina(&mut self, player: Uuid, cost: f32, now: chrono::DateTime<Utc>) -> std::result::Result<StaminaStatus, StaminaShortfall> {
+        let (rate, resting) = self.stamina_regen(&player);
+        let max = self.stamina_settings.max_stamina;
+        let stamina = self.stamina.entry(player).or_insert_with(|| Stamina::full(max, now));
+        stamina.regenerate(rate, now);
+        match stamina.spend(cost) {
+            Ok(()) => Ok(stamina.status(rate, resting)),
+            Err(missing) => Err(StaminaShortfall {
+                error: "Not enough stamina for this melody".to_string(),
+                cost,
+                missing,
+                stamina: stamina.status(rate, resting),
+            }),
+        }
+    }
 
-        // Prepare message description before moving melody
-        let harmony_desc = match melody.harmony_type {
-            HarmonyType::Creative => "creative",
-            HarmonyType::Restoration => "restorative",
-            HarmonyType::Exploration => "exploratory",
-            HarmonyType::Protection => "protective",
-        };
+    fn snapshot(&self) -> HarmonySnapshot {
+        HarmonySnapshot {
+            global_harmony: self.global_harmony,
+            regional_harmony: self.regional_harmony.clone(),
+            silence_corruption: self.silence_corruption.clone(),
+            active_melodies_count: self.active_melodies.len(),
+            dominant_song_fragments: self.active_melodies.keys().take(3).cloned().collect(),
+        }
+    }
+
+    /// `power_multiplier` is the performer's debuff, e.g. from Silence infection.
+    fn evaluate_melody(
+        &self,
+        melody: &Melody,
+        location: &Coordinates,
+        player: &Uuid,
+        power_multiplier: f32,
+        now: chrono::DateTime<Utc>,
+    ) -> MelodyOutcome {
+        let region = self.determine_region_from_coordinates(location);
+
+        // Judge the melody against the region's music, with less effect
+        // each time the player repeats themselves there
+        let repeat_multiplier = self.repeat_multiplier(player, region.as_ref(), now);
+        let power = self.calculate_melody_power(melody, region.as_ref()) * power_multiplier * repeat_multiplier;
+
+        MelodyOutcome {
+            region,
+            power,
+            harmony_modifier: Self::harmony_modifier(power, &melody.harmony_type),
+            repeat_multiplier,
+        }
+    }
+
+    fn perform_melody(
+        &mut self,
+        melody: Melody,
+        location: Coordinates,
+        player_id: PlayerId,
+        power_multiplier: f32,
+    ) -> std::result::Result<PerformedMelody, MelodyRefusal> {
+        let now = Utc::now();
+        if let Some(remaining_seconds) = self.cooldown_remaining(&player_id.0, now) {
+            return Err(MelodyRefusal::Cooldown(CooldownActive {
+                error: "Melody cooldown still active".to_string(),
+                remaining_seconds,
+            }));
+        }
+        let outcome = self.evaluate_melody(&melody, &location, &player_id.0, power_multiplier, now);
+
+        // Pay for the melody before anything is applied
+        self.positions.insert(player_id.0, location);
+        let stamina_cost = self.stamina_settings.melody_cost(outcome.power);
+        let stamina = self
+            .spend_stamina(player_id.0, stamina_cost, now)
+            .map_err(MelodyRefusal::Stamina)?;
+        self.record_melody(player_id.0, outcome.region.clone(), now);
+
+        // Apply harmony effects
+        let harmony_level = self.apply_harmony_effects(outcome.region.as_ref(), outcome.harmony_modifier);
+
+        // Generate effects based on harmony type and power
+        let effects = self.generate_melody_effects(&melody.harmony_type, outcome.power);
+        let message = Self::melody_message(&melody.harmony_type);
 
         // Store the melody
+        let harmony_type = melody.harmony_type.clone();
         let melody_id = uuid::Uuid::new_v4().to_string();
         self.active_melodies.insert(melody_id, melody);
 
-        PerformMelodyResponse {
+        let response = PerformMelodyResponse {
             success: true,
-            resonance_gained,
-            harmony_impact,
-            message: format!(
-                "Your {} melody resonates through the Song of Creation!",
-                harmony_desc
-            ),
+            // Resonance gained by the player
+            resonance_gained: outcome.power * 2.0,
+            harmony_impact: outcome.harmony_modifier,
+            message,
             effects,
+            dry_run: false,
+            stamina_cost,
+            stamina,
+            repeat_multiplier: outcome.repeat_multiplier,
+        };
+        Ok(PerformedMelody {
+            player_id: player_id.0,
+            harmony_type,
+            location,
+            response,
+            outcome,
+            harmony_level,
+        })
+    }
+
+    /// Predict a melody's outcome without touching any state. Figures are
+    /// jittered and rounded, and effects are judged against a jittered power,
+    /// so repeated previews don't reveal exact thresholds.
+    fn preview_melody(&self, melody: &Melody, location: &Coordinates, player: &Uuid, power_multiplier: f32) -> PerformMelodyResponse {
+        let now = Utc::now();
+        let outcome = self.evaluate_melody(melody, location, player, power_multiplier, now);
+        let mut fuzz = self.preview_fuzz(player, melody);
+        let round = |value: f32| (value * 10.0).round() / 10.0;
+
+        PerformMelodyResponse {
+            success: true,
+            resonance_gained: round(outcome.power * 2.0 * fuzz()),
+            harmony_impact: round(outcome.harmony_modifier * fuzz()),
+            message: Self::melody_message(&melody.harmony_type),
+            effects: self.generate_melody_effects(&melody.harmony_type, outcome.power * fuzz()),
+            dry_run: true,
+            stamina_cost: round(self.stamina_settings.melody_cost(outcome.power)),
+            stamina: self.stamina_status(player, now),
+            repeat_multiplier: round(outcome.repeat_multiplier),
         }
     }
 
-    fn calculate_melody_power(&self, melody: &Melody) -> f32 {
-        let base_power = melody.notes.len() as f32 * 0.5;
-        let complexity_bonus = melody.notes.iter()
-            .map(|note| note.intensity * note.duration / note.frequency.max(1.0))
-            .sum::<f32>() / melody.notes.len() as f32;
+    /// Jitter factors for `player`'s previews of `melody`. They are seeded
+    /// from the player, the melody and a per-process salt, so previewing
+    /// again returns the same figures rather than fresh samples to average.
+    fn preview_fuzz(&self, player: &Uuid, melody: &Melody) -> impl FnMut() -> f32 {
+        let mut hasher = DefaultHasher::new();
+        self.preview_salt.hash(&mut hasher);
+        player.hash(&mut hasher);
+        for note in &melody.notes {
+            [note.frequency, note.duration, note.intensity].map(f32::to_bits).hash(&mut hasher);
+        }
+        melody.tempo.to_bits().hash(&mut hasher);
+        std::mem::discriminant(&melody.harmony_type).hash(&mut hasher);
+        let mut rng = StdRng::seed_from_u64(hasher.finish());
+        move || 1.0 + rng.gen_range(-PREVIEW_FUZZ..=PREVIEW_FUZZ)
+    }
+
+    fn melody_message(harmony_type: &HarmonyType) -> String {
+        let harmony_desc = match harmony_type {
+            HarmonyType::Creative => "creative",
+            HarmonyType::Restoration => "restorative",
+            HarmonyType::Exploration => "exploratory",
+            HarmonyType::Protection => "protective",
+        };
+        format!("Your {} melody resonates through the Song of Creation!", harmony_desc)
+    }
 
-        base_power + complexity_bonus.min(10.0)
+    fn calculate_melody_power(&self, melody: &Melody, region: Option<&RegionId>) -> f32 {
+        match region.and_then(|region| self.regional_themes.get(region)) {
+            Some(theme) => analysis::analyze(melody, theme).power(),
+            None => analysis::analyze(melody, &analysis::plain_theme("default", Scale::Major, melody.tempo)).power(),
+        }
     }
 
-    fn determine_region_from_coordinates(&self, _coordinates: &Coordinates) -> RegionId {
-        // Simplified region determination - in a real implementation,
-        // this would use spatial indexing
-        RegionId(Uuid::new_v4())
+    fn determine_region_from_coordinates(&self, coordinates: &Coordinates) -> Option<RegionId> {
+        self.region_map.region_at(coordinates).map(|region| region.id.clone())
     }
 
-    fn apply_harmony_effects(&mut self, region: &RegionId, power: f32, harmony_type: &HarmonyType) -> f32 {
-        let current_harmony = self.regional_harmony.get(region).unwrap_or(&50.0);
-        let harmony_modifier = match harmony_type {
+    fn harmony_modifier(power: f32, harmony_type: &HarmonyType) -> f32 {
+        match harmony_type {
             HarmonyType::Restoration => power * 1.5,
             HarmonyType::Creative => power * 1.2,
             HarmonyType::Protection => power * 1.0,
             HarmonyType::Exploration => power * 0.8,
-        };
+        }
+    }
 
+    /// Outside every mapped region a melody lifts global harmony directly.
+    /// Returns the region's new harmony, or the world's outside every region.
+    fn apply_harmony_effects(&mut self, region: Option<&RegionId>, harmony_modifier: f32) -> f32 {
+        let Some(region) = region else {
+            self.global_harmony = (self.global_harmony + harmony_modifier).min(100.0);
+            return self.global_harmony;
+        };
+        let current_harmony = self.regional_harmony.get(region).unwrap_or(&STARTING_HARMONY);
         let new_harmony = (current_harmony + harmony_modifier).min(100.0);
         self.regional_harmony.insert(region.clone(), new_harmony);
 
@@ -189,11 +633,18 @@ impl SongEngineState {
         if let Some(corruption) = self.silence_corruption.get_mut(region) {
             *corruption = (*corruption - harmony_modifier * 0.5).max(0.0);
         }
+        new_harmony
+    }
 
-        harmony_modifier
+    /// Take world-engine's harmony for a region as this service's.
+    fn reconcile_harmony(&mut self, region: &RegionId, harmony_level: f64) -> f32 {
+        let harmony = (harmony_level * 100.0) as f32;
+        self.regional_harmony.insert(region.clone(), harmony);
+        self.global_harmony = self.regional_harmony.values().sum::<f32>() / self.regional_harmony.len() as f32;
+        harmony
     }
 
-    fn generate_melody_effects(&self, harmony_type: &HarmonyType, power: f32, region: &RegionId) -> Vec<String> {
+    fn generate_melody_effects(&self, harmony_type: &HarmonyType, power: f32) -> Vec<String> {
         let mut effects = Vec::new();
 
         match harmony_type {
@@ -228,19 +679,62 @@ impl SongEngineState {
 }
 
 
+/// The performer's melody power multiplier from their Silence infection.
+/// Falls back to no debuff when the silence service can't be reached.
+async fn infection_multiplier(services: &ServiceClient, player_id: &Uuid) -> f32 {
+    let path = format!("/players/{}/infection", player_id);
+    match services.get_json::<InfectionStatus>("silence-service", &path).await {
+        Ok(status) => status.melody_power_multiplier.clamp(0.0, 1.0),
+        Err(e) => {
+            warn!("Failed to fetch infection for player {}: {}", player_id, e);
+            1.0
+        }
+    }
+}
+
+fn seconds_between(earlier: chrono::DateTime<Utc>, later: chrono::DateTime<Utc>) -> f32 {
+    (later - earlier).num_milliseconds() as f32 / 1000.0
+}
+
+/// The name melody requests use for a harmony type.
+fn harmony_type_name(harmony_type: &HarmonyType) -> &'static str {
+    match harmony_type {
+        HarmonyType::Creative => "creative",
+        HarmonyType::Restoration => "restoration",
+        HarmonyType::Exploration => "exploration",
+        HarmonyType::Protection => "protection",
+    }
+}
+
+/// The region map named in config, or else world-engine's. Without
+/// either, melodies only affect global harmony.
+async fn load_region_map(path: Option<&str>, services: &ServiceClient) -> RegionMap {
+    match path {
+        Some(path) => RegionMap::load(path).unwrap_or_else(|e| {
+            warn!("Not placing regions from {}: {}", path, e);
+            RegionMap::default()
+        }),
+        None => services
+            .get_json::<RegionMap>("world-engine", REGION_MAP_PATH)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to fetch the region map from world-engine: {}", e);
+                RegionMap::default()
+            }),
+    }
+}
+
+/// The performer is always the authenticated player.
 async fn perform_melody(
-    State(state): State<SharedSongState>,
+    State(state): State<AppState>,
+    player: AuthenticatedPlayer,
     Json(request): Json<PerformMelodyRequest>,
 ) -> impl IntoResponse {
-    // Parse and validate player ID
-    let player_uuid = match uuid::Uuid::parse_str(&request.player_id) {
-        Ok(uuid) => uuid,
-        Err(_) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
-            "error": "Invalid player ID format"
-        }))),
-    };
-
-    let player_id = PlayerId(player_uuid);
+    let player_id = player.player_id;
+    let player_uuid = player_id.0;
+    if let Err(refusal) = state.check_length(request.melody.notes.len()) {
+        return refusal;
+    }
 
     // Convert request to internal types
     let harmony_type = match request.melody.harmony_type.as_str() {
@@ -271,9 +765,42 @@ async fn perform_melody(
         z: request.target_location.z,
     };
 
-    // Perform the melody
-    let mut song_state = state.write().unwrap();
-    let response = song_state.perform_melody(melody, coordinates, player_id);
+    let is_restoration = matches!(melody.harmony_type, HarmonyType::Restoration);
+    let power_multiplier = infection_multiplier(&state.services, &player_uuid).await;
+
+    // Dry runs only read state, so nothing is stored or applied
+    let response = if request.dry_run {
+        state.song.read(|song| song.preview_melody(&melody, &coordinates, &player_uuid, power_multiplier))
+    } else {
+        let performed = state
+            .song
+            .write(|song| song.perform_melody(melody, coordinates, player_id, power_multiplier));
+        let performed = match performed {
+            Ok(performed) => performed,
+            Err(refusal) => {
+                let rejections = match refusal {
+                    MelodyRefusal::Stamina(_) => STAMINA_REJECTIONS,
+                    MelodyRefusal::Cooldown(_) => COOLDOWN_REJECTIONS,
+                };
+                counter!(rejections, harmony_type = request.melody.harmony_type).increment(1);
+                return (refusal.status(), Json(serde_json::to_value(refusal).unwrap()));
+            }
+        };
+        // Restoration melodies also wash the Silence out of the performer
+        if is_restoration {
+            let path = format!("/players/{}/cleanse", player_uuid);
+            let body = serde_json::json!({ "source": "restoration_melody", "power": performed.outcome.power });
+            if let Err(e) = state.services.post_json::<_, serde_json::Value>("silence-service", &path, &body).await {
+                warn!("Failed to cleanse player {}: {}", player_uuid, e);
+            }
+        }
+        state.publish_melody(&performed).await;
+        performed.response
+    };
+    counter!(MELODIES, harmony_type = request.melody.harmony_type, dry_run = request.dry_run).increment(1);
+    if !request.dry_run {
+        histogram!(HARMONY_IMPACT, harmony_type = request.melody.harmony_type).record(response.harmony_impact as f64);
+    }
     let json_response = serde_json::to_value(response).unwrap();
 
     (StatusCode::OK, Json(json_response))
@@ -283,7 +810,7 @@ async fn check_harmony(
     State(state): State<SharedSongState>,
     Json(request): Json<HarmonyCheckRequest>,
 ) -> impl IntoResponse {
-    let song_state = state.read().unwrap();
+    let song_state = state.snapshot();
     let region_uuid = match Uuid::parse_str(&request.region_id) {
         Ok(u) => u,
         Err(_) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
@@ -302,18 +829,11 @@ async fn check_harmony(
         .copied()
         .unwrap_or(0.0);
 
-    // Get dominant song fragments (simplified)
-    let dominant_fragments: Vec<String> = song_state.active_melodies
-        .keys()
-        .take(3)
-        .cloned()
-        .collect();
-
     let response = HarmonyCheckResponse {
         region_id: request.region_id,
         harmony_level,
         corruption_level,
-        dominant_song_fragments: dominant_fragments,
+        dominant_song_fragments: song_state.dominant_song_fragments.clone(),
     };
     let json_response = serde_json::to_value(response).unwrap();
 
@@ -321,29 +841,50 @@ async fn check_harmony(
 }
 
 async fn get_global_harmony(State(state): State<SharedSongState>) -> impl IntoResponse {
-    let song_state = state.read().unwrap();
+    let song_state = state.snapshot();
 
     (StatusCode::OK, Json(serde_json::json!({
         "global_harmony": song_state.global_harmony,
         "regional_harmony": song_state.regional_harmony,
-        "active_melodies_count": song_state.active_melodies.len(),
+        "active_melodies_count": song_state.active_melodies_count,
         "corrupted_regions": song_state.silence_corruption.len()
     })))
 }
 
-async fn process_song_event(
+async fn get_stamina(
     State(state): State<SharedSongState>,
-    Json(event): Json<SongEvent>,
+    Path(player_id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let mut song_state = state.write().unwrap();
+    Json(state.read(|song| song.stamina_status(&player_id, Utc::now())))
+}
 
-    match event {
+async fn process_song_event(
+    State(state): State<AppState>,
+    Json(event): Json<SongEvent>,
+) -> impl IntoResponse {
+    if let SongEvent::MelodyWoven { melody, .. } = &event {
+        if let Err(refusal) = state.check_length(melody.notes.len()) {
+            return refusal;
+        }
+    }
+    let power_multiplier = match &event {
+        SongEvent::MelodyWoven { player_id, .. } => infection_multiplier(&state.services, &player_id.0).await,
+        _ => 1.0,
+    };
+    let mut woven = None;
+    let reply = state.song.write(|song_state| match event {
         SongEvent::MelodyWoven { player_id, melody, target } => {
-            let response = song_state.perform_melody(melody, target, player_id);
-            (StatusCode::OK, Json(serde_json::json!({
-                "event_processed": true,
-                "result": response
-            })))
+            match song_state.perform_melody(melody, target, player_id, power_multiplier) {
+                Ok(performed) => {
+                    let reply = (StatusCode::OK, Json(serde_json::json!({
+                        "event_processed": true,
+                        "result": performed.response
+                    })));
+                    woven = Some(performed);
+                    reply
+                }
+                Err(refusal) => (refusal.status(), Json(serde_json::to_value(refusal).unwrap())),
+            }
         },
         SongEvent::HarmonyAchieved { participants, harmony_type, power_level } => {
             // Process collaborative harmony achievement
@@ -360,7 +901,7 @@ async fn process_song_event(
         SongEvent::DissonanceDetected { location, intensity, source } => {
             // Handle dissonance detection
             let region = song_state.determine_region_from_coordinates(&location);
-            if let Some(harmony) = song_state.regional_harmony.get_mut(&region) {
+            if let Some(harmony) = region.and_then(|region| song_state.regional_harmony.get_mut(&region)) {
                 *harmony = (*harmony - intensity).max(0.0);
             }
 
@@ -385,38 +926,220 @@ async fn process_song_event(
                 "affected_entities_count": affected_entities.len()
             })))
         }
+    });
+    if let Some(performed) = woven {
+        state.publish_melody(&performed).await;
     }
+    reply
+}
+
+/// The song API. Routes above the route_layer act on behalf of a
+/// signed-in player.
+fn api_routes(auth: PlayerAuth, operator: OperatorGuard) -> Router<AppState> {
+    // Song events name their player, so only services may send them
+    let service_routes = Router::new()
+        .route("/api/events", post(process_song_event))
+        .route_layer(axum::middleware::from_fn_with_state(operator, require_operator));
+
+    Router::new()
+        .route(
+            "/api/melody/perform",
+            post(perform_melody).layer(axum::middleware::from_fn_with_state(IdempotencyCache::default(), idempotent)),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(auth, require_player))
+        .route("/api/harmony/check", post(check_harmony))
+        .route("/api/harmony/global", get(get_global_harmony))
+        .route("/api/stamina/:player_id", get(get_stamina))
+        .merge(service_routes)
 }
 
 #[tokio::main]
 async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
+    let metrics = finalverse_metrics::init("song-engine")?;
+    MELODIES.describe();
+    HARMONY_IMPACT.describe();
+    STAMINA_REJECTIONS.describe();
+    COOLDOWN_REJECTIONS.describe();
+    service_registry::describe_metrics();
+    finalverse_ratelimit::describe_metrics();
 
-    let state = Arc::new(RwLock::new(SongEngineState::new()));
+    let config = finalverse_config::load_default_config().ok();
+    let section = config.as_ref().map(|config| config.song_engine.clone()).unwrap_or_default();
     let monitor = Arc::new(HealthMonitor::new("song-engine", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
-        .register_service("song-engine".to_string(), "http://localhost:3001".to_string())
+        .register_service("song-engine".to_string(), format!("http://localhost:{}", section.http_port))
         .await;
+    let services = ServiceClient::new(registry.clone());
 
-    let app = Router::new()
-        .route("/api/melody/perform", post(perform_melody))
-        .route("/api/harmony/check", post(check_harmony))
-        .route("/api/harmony/global", get(get_global_harmony))
-        .route("/api/events", post(process_song_event))
-        .with_state(state.clone())
+    // Rehearse outages when built with chaos support
+    #[cfg(feature = "chaos")]
+    let chaos = finalverse_chaos::FaultInjector::from_default_config();
+    #[cfg(feature = "chaos")]
+    let services = match &chaos {
+        Some(injector) => {
+            warn!("⚠️  Chaos scenarios active: {}", injector.scenarios().len());
+            services.with_fault_injector(injector.clone())
+        }
+        None => services,
+    };
+
+    let stamina = config.as_ref().map(|config| config.game.stamina.clone()).unwrap_or_default();
+    let melody_limits = config.as_ref().map(|config| config.game.melody_limits.clone()).unwrap_or_default();
+    let region_map_path = config.as_ref().and_then(|config| config.game.world_settings.region_map.as_deref());
+    let region_map = load_region_map(region_map_path, &services).await;
+    info!("Placing melodies in {} mapped regions", region_map.regions().len());
+
+    let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
+        info!("📡 Connecting to NATS at {}", nats_url);
+        Arc::new(NatsEventBus::new(&nats_url).await?)
+    } else {
+        info!("📦 Using local event bus");
+        Arc::new(LocalEventBus::new())
+    };
+    #[cfg(feature = "chaos")]
+    let event_bus = finalverse_chaos::wrap_event_bus(event_bus, chaos.as_ref());
+    let event_bus = TenantEventBus::from_env(event_bus);
+
+    let admin_services = match OperatorGuard::load().token() {
+        Some(token) => services.with_header(OPERATOR_TOKEN_HEADER, token),
+        None => {
+            warn!("No operator token configured; world-engine will refuse the harmony melodies bring to regions");
+            services.clone()
+        }
+    };
+    let state = AppState {
+        song: Arc::new(SongEngine::new(SongEngineState::new(stamina, melody_limits, region_map))),
+        services,
+        admin_services,
+        event_bus: event_bus.clone(),
+        max_melody_notes: section.max_melody_notes,
+    };
+
+    let app = api_routes(PlayerAuth::load(), OperatorGuard::load())
+        .layer(axum::middleware::from_fn_with_state(Arc::new(RateLimits::load()), limit_requests))
+        .with_state(state)
         .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("song-engine").with_event_bus(event_bus)).axum_routes())
+        .merge(metrics.axum_routes())
+        .layer(axum::middleware::from_fn(finalverse_metrics::http::track_requests))
         .layer(
             ServiceBuilder::new()
                 .layer(CorsLayer::permissive())
                 .into_inner(),
         );
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3001));
+    let addr = SocketAddr::from(([0, 0, 0, 0], section.http_port));
     info!("Song Engine listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use finalverse_auth::JwtAuth;
+    use finalverse_core::TenantId;
+    use finalverse_sdk::contract;
+    use tower::ServiceExt;
+
+    /// Replays the client SDK's golden fixtures against the song API.
+    #[tokio::test]
+    async fn sdk_fixtures_still_hold() {
+        let tokens = JwtAuth::new("a-contract-test-secret-of-32-chars!", chrono::Duration::hours(1));
+        let state = AppState {
+            song: Arc::new(SongEngine::new(SongEngineState::new(
+                StaminaSettings::default(),
+                MelodyLimitSettings::default(),
+                RegionMap::default(),
+            ))),
+            services: ServiceClient::new(LocalServiceRegistry::new()),
+            admin_services: ServiceClient::new(LocalServiceRegistry::new()),
+            event_bus: Arc::new(LocalEventBus::new()),
+            max_melody_notes: finalverse_config::SongEngineSection::default().max_melody_notes,
+        };
+        let app = api_routes(PlayerAuth::new(tokens.clone(), TenantId::default()), OperatorGuard::default()).with_state(state);
+
+        let fixtures = contract::skew_matrix("song-engine").unwrap();
+        assert!(!fixtures.is_empty());
+        let mut failures = Vec::new();
+        for fixture in fixtures {
+            let mut request = Request::builder()
+                .method(fixture.method.as_str())
+                .uri(&fixture.path)
+                .header("content-type", "application/json");
+            if fixture.authenticated {
+                let (token, _) = tokens.issue(PlayerId(Uuid::new_v4()), Uuid::new_v4(), &TenantId::default());
+                request = request.header("authorization", format!("Bearer {}", token));
+            }
+            let body = fixture.request.as_ref().map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+            let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+            let status = response.status().as_u16();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            if let Err(failure) = fixture.check(status, &serde_json::from_slice(&body).unwrap_or_default()) {
+                failures.push(failure.to_string());
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    }
+
+    fn melody(harmony_type: HarmonyType, intensity: f32) -> Melody {
+        Melody {
+            notes: [261.6, 329.6, 392.0]
+                .into_iter()
+                .map(|frequency| Note { frequency, duration: 0.5, intensity })
+                .collect(),
+            tempo: 90.0,
+            harmony_type,
+        }
+    }
+
+    fn song_state() -> SongEngineState {
+        SongEngineState::new(StaminaSettings::default(), MelodyLimitSettings::default(), RegionMap::default())
+    }
+
+    #[test]
+    fn previews_repeat_their_figures() {
+        let state = song_state();
+        let player = Uuid::new_v4();
+        let location = Coordinates { x: 10.0, y: 0.0, z: 10.0 };
+        let melody = melody(HarmonyType::Restoration, 0.8);
+        let first = state.preview_melody(&melody, &location, &player, 1.0);
+        for _ in 0..20 {
+            let again = state.preview_melody(&melody, &location, &player, 1.0);
+            assert_eq!(again.resonance_gained, first.resonance_gained);
+            assert_eq!(again.harmony_impact, first.harmony_impact);
+        }
+    }
+
+    #[test]
+    fn preview_fuzz_is_bounded_and_differs_by_player_and_melody() {
+        let state = song_state();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let soft = melody(HarmonyType::Creative, 0.4);
+        let loud = melody(HarmonyType::Creative, 0.9);
+        let factors = |player: &Uuid, melody: &Melody| {
+            let mut fuzz = state.preview_fuzz(player, melody);
+            (0..50).map(|_| fuzz()).collect::<Vec<f32>>()
+        };
+
+        let alice_soft = factors(&alice, &soft);
+        assert!(alice_soft.iter().all(|factor| (1.0 - PREVIEW_FUZZ..=1.0 + PREVIEW_FUZZ).contains(factor)));
+        assert_eq!(alice_soft, factors(&alice, &soft));
+        assert_ne!(alice_soft, factors(&bob, &soft));
+        assert_ne!(alice_soft, factors(&alice, &loud));
+        // Another process's salt gives other figures
+        assert_ne!(alice_soft, {
+            let other = song_state();
+            let mut fuzz = other.preview_fuzz(&alice, &soft);
+            (0..50).map(|_| fuzz()).collect::<Vec<f32>>()
+        });
+    }
+}
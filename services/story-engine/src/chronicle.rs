@@ -0,0 +1,214 @@
+// services/story-engine/src/chronicle.rs
+// Each player's chronicle: the moments of their story worth retelling,
+// kept in Redis so it outlives restarts
+
+use chrono::{DateTime, Utc};
+use finalverse_core::{TenantId, WorldInstant};
+use finalverse_events::{Event, EventType, HarmonyEvent, PlayerEvent, PlayerId, SilenceEvent, SongEvent};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+/// Entries returned when a request doesn't ask for a number.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+/// Most entries one request may ask for.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChronicleMoment {
+    AttunementGained { tier: u32, total_resonance: f64 },
+    SymphonyCompleted { symphony_type: String, participants: usize },
+    SilenceCleansed { source: String, amount: f32 },
+    ItemCrafted { recipe_id: String, item_id: String, quantity: u32 },
+    ItemConsumed { item_id: String, quantity: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChronicleEntry {
+    pub id: Uuid,
+    /// The event the entry was written from.
+    pub event_id: String,
+    pub recorded_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_time: Option<WorldInstant>,
+    pub text: String,
+    #[serde(flatten)]
+    pub moment: ChronicleMoment,
+}
+
+/// Part of a chronicle, newest entries first.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChroniclePage {
+    pub player_id: String,
+    /// Entries in the whole chronicle.
+    pub total: usize,
+    pub offset: usize,
+    pub entries: Vec<ChronicleEntry>,
+}
+
+/// The chronicle entries an event adds, with the player each belongs to.
+/// Most events aren't significant enough to add any.
+pub fn entries_for(event: &Event) -> Vec<(PlayerId, ChronicleEntry)> {
+    let entry = |text: String, moment: ChronicleMoment| ChronicleEntry {
+        id: Uuid::new_v4(),
+        event_id: event.id.clone(),
+        recorded_at: event.timestamp,
+        world_time: event.world_time,
+        text,
+        moment,
+    };
+
+    match &event.event_type {
+        EventType::Harmony(HarmonyEvent::AttunementAchieved { player_id, tier, total_resonance }) => vec![(
+            player_id.clone(),
+            entry(
+                format!("Attuned to the Song at tier {}", tier),
+                ChronicleMoment::AttunementGained {
+                    tier: *tier,
+                    total_resonance: *total_resonance,
+                },
+            ),
+        )],
+        EventType::Song(SongEvent::SymphonyCompleted { participants, symphony_type, success: true }) => {
+            let text = match participants.len() {
+                1 => format!("Completed the {} symphony", symphony_type),
+                n => format!("Completed the {} symphony with {} others", symphony_type, n - 1),
+            };
+            participants
+                .iter()
+                .map(|player_id| {
+                    (
+                        player_id.clone(),
+                        entry(
+                            text.clone(),
+                            ChronicleMoment::SymphonyCompleted {
+                                symphony_type: symphony_type.clone(),
+                                participants: participants.len(),
+                            },
+                        ),
+                    )
+                })
+                .collect()
+        }
+        EventType::Silence(SilenceEvent::InfectionCleansed { player_id, source, amount, .. }) => vec![(
+            player_id.clone(),
+            entry(
+                format!("Cleansed of the Silence by {}", source.replace('_', " ")),
+                ChronicleMoment::SilenceCleansed {
+                    source: source.clone(),
+                    amount: *amount,
+                },
+            ),
+        )],
+        EventType::Player(PlayerEvent::ItemCrafted { player_id, recipe_id, item_id, item_name, quantity }) => vec![(
+            player_id.clone(),
+            entry(
+                match quantity {
+                    1 => format!("Crafted {}", item_name),
+                    n => format!("Crafted {} {}", n, item_name),
+                },
+                ChronicleMoment::ItemCrafted {
+                    recipe_id: recipe_id.clone(),
+                    item_id: item_id.clone(),
+                    quantity: *quantity,
+                },
+            ),
+        )],
+        EventType::Player(PlayerEvent::ItemConsumed { player_id, item_id, item_name, quantity }) => vec![(
+            player_id.clone(),
+            entry(
+                match quantity {
+                    1 => format!("Used {}", item_name),
+                    n => format!("Used {} {}", n, item_name),
+                },
+                ChronicleMoment::ItemConsumed {
+                    item_id: item_id.clone(),
+                    quantity: *quantity,
+                },
+            ),
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Chronicles in Redis, one list per player with the newest entry first,
+/// scoped to the tenant this process serves.
+pub struct Chronicle {
+    client: redis::Client,
+    /// Multiplexed and reconnecting; made on first use so story-engine
+    /// starts without Redis.
+    connection: OnceCell<ConnectionManager>,
+    tenant: TenantId,
+}
+
+impl Chronicle {
+    pub fn new(client: redis::Client, tenant: TenantId) -> Self {
+        Self {
+            client,
+            connection: OnceCell::new(),
+            tenant,
+        }
+    }
+
+    fn key(&self, player_id: &str) -> String {
+        self.tenant.scoped_key(&format!("chronicle:{}", player_id))
+    }
+
+    async fn connection(&self) -> redis::RedisResult<ConnectionManager> {
+        Ok(self
+            .connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await?
+            .clone())
+    }
+
+    pub async fn append(&self, player_id: &PlayerId, entry: &ChronicleEntry) -> redis::RedisResult<()> {
+        let json = serde_json::to_string(entry).map_err(|e| {
+            redis::RedisError::from((redis::ErrorKind::TypeError, "unserializable chronicle entry", e.to_string()))
+        })?;
+        let mut connection = self.connection().await?;
+        connection.lpush(self.key(&player_id.to_string()), json).await
+    }
+
+    /// Write down whatever `event` adds to anyone's chronicle. Failures are
+    /// logged; the moment is lost from the chronicle but nothing else.
+    pub async fn record(&self, event: &Event) {
+        for (player_id, entry) in entries_for(event) {
+            if let Err(e) = self.append(&player_id, &entry).await {
+                tracing::warn!("Failed to add to the chronicle of {}: {}", player_id.0, e);
+            }
+        }
+    }
+
+    pub async fn page(&self, player_id: &str, offset: usize, limit: usize) -> redis::RedisResult<ChroniclePage> {
+        let key = self.key(player_id);
+        let mut connection = self.connection().await?;
+        let total: usize = connection.llen(&key).await?;
+        let entries = if limit == 0 || offset >= total {
+            Vec::new()
+        } else {
+            let stored: Vec<String> = connection
+                .lrange(&key, offset as isize, (offset + limit - 1) as isize)
+                .await?;
+            stored
+                .iter()
+                .filter_map(|json| match serde_json::from_str(json) {
+                    Ok(entry) => Some(entry),
+                    Err(e) => {
+                        tracing::warn!("Skipping unreadable chronicle entry for {}: {}", player_id, e);
+                        None
+                    }
+                })
+                .collect()
+        };
+        Ok(ChroniclePage {
+            player_id: player_id.to_string(),
+            total,
+            offset,
+            entries,
+        })
+    }
+}
@@ -6,17 +6,33 @@ use serde::{Deserialize, Serialize};
 use warp::Filter;
 use tracing::info;
 use finalverse_logging as logging;
+use finalverse_auth::{AuthenticatedPlayer, PlayerAuth};
+use finalverse_core::{FinalverseError, InfectionStage, RegionId, TenantId};
+use finalverse_health::{DebugEndpoints, OperatorGuard};
 use finalverse_audio_core::{AudioEvent, AudioEventType, AudioSource, EmotionalState};
 use redis::Client as RedisClient;
+use service_registry::{LocalServiceRegistry, ServiceClient};
 use uuid::Uuid;
 use nalgebra::Vector3;
 use serde_json;
 use finalverse_events::{
-    GameEventBus, LocalEventBus, NatsEventBus,
+    GameEventBus, JournaledEventBus, LocalEventBus, NatsEventBus, TenantEventBus,
     Event, EventType, SongEvent, SongType, PlayerId, Coordinates,
-    HarmonyEvent, EventMetadata,
+    HarmonyEvent, EventMetadata, WorldEvent, CommunityEvent, PlayerEvent, SilenceEvent,
 };
 
+mod chronicle;
+mod matchmaking;
+mod personal_quests;
+mod quest_system;
+mod triggers;
+mod visions;
+use chronicle::{Chronicle, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use matchmaking::{Matchmaker, MatchmakingError};
+use personal_quests::PersonalQuests;
+use triggers::{EntityInteraction, InteractionType, SkippedTrigger, TriggerAction, TriggerDefinition, TriggerEngine};
+use visions::{ActiveVision, ReturnPoint, VisionDirector, VisionEndReason, VisionScene};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActiveSong {
     pub id: String,
@@ -33,9 +49,13 @@ pub struct Symphony {
     pub id: String,
     pub symphony_type: String,
     pub participants: Vec<PlayerId>,
+    /// Power each participant has put in, by player id.
+    pub contributions: HashMap<Uuid, f64>,
     pub required_power: f64,
     pub current_power: f64,
     pub started_at: chrono::DateTime<chrono::Utc>,
+    /// When the symphony fails if it is still gathering.
+    pub gathering_deadline: chrono::DateTime<chrono::Utc>,
     pub status: SymphonyStatus,
 }
 
@@ -60,22 +80,45 @@ pub struct DialogueResponse {
     pub audio_stream_id: uuid::Uuid,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct InteractionOutcome {
+    pub fired: Vec<String>,
+    pub skipped: Vec<SkippedTrigger>,
+    pub dialogue: Vec<DialogueResponse>,
+    pub vision: Option<ActiveVision>,
+}
+
 pub struct StoryEngineService {
     active_songs: Arc<RwLock<HashMap<String, ActiveSong>>>,
     symphonies: Arc<RwLock<HashMap<String, Symphony>>>,
     event_bus: Arc<dyn GameEventBus>,
     subscription_ids: Arc<RwLock<Vec<String>>>,
     redis_client: RedisClient,
+    tenant: TenantId,
+    triggers: Arc<TriggerEngine>,
+    visions: Arc<VisionDirector>,
+    chronicle: Arc<Chronicle>,
+    matchmaker: Matchmaker,
+    quests: PersonalQuests,
+    services: ServiceClient,
 }
 
 impl StoryEngineService {
-    pub fn new(event_bus: Arc<dyn GameEventBus>, redis_client: RedisClient) -> Self {
+    pub fn new(event_bus: Arc<dyn GameEventBus>, redis_client: RedisClient, services: ServiceClient) -> Self {
+        let tenant = TenantId::from_env();
         Self {
             active_songs: Arc::new(RwLock::new(HashMap::new())),
             symphonies: Arc::new(RwLock::new(HashMap::new())),
             event_bus,
             subscription_ids: Arc::new(RwLock::new(Vec::new())),
+            chronicle: Arc::new(Chronicle::new(redis_client.clone(), tenant.clone())),
+            quests: PersonalQuests::new(services.clone(), redis_client.clone(), tenant.clone()),
+            matchmaker: Matchmaker::new(services.clone()),
+            services,
             redis_client,
+            tenant,
+            triggers: Arc::new(TriggerEngine::new()),
+            visions: Arc::new(VisionDirector::new()),
         }
     }
 
@@ -134,6 +177,81 @@ impl StoryEngineService {
 
         self.subscription_ids.write().await.push(harmony_sub_id);
 
+        // Stewardship changes become legend beats
+        let community_sub_id = self
+            .event_bus
+            .subscribe("events.community", Box::new(move |event| {
+                if let EventType::Community(community_event) = &event.event_type {
+                    if let Some(beat) = stewardship_legend(community_event) {
+                        info!("📜 {}", beat);
+                    }
+                }
+            }))
+            .await?;
+
+        self.subscription_ids.write().await.push(community_sub_id);
+
+        // Infected players draw narrative reactions
+        let silence_sub_id = self
+            .event_bus
+            .subscribe("events.silence", Box::new(move |event| {
+                if let EventType::Silence(silence_event) = &event.event_type {
+                    if let Some(beat) = silence_reaction(silence_event) {
+                        info!("📜 {}", beat);
+                    }
+                }
+            }))
+            .await?;
+
+        self.subscription_ids.write().await.push(silence_sub_id);
+
+        // Significant moments go into the players' chronicles
+        for topic in ["events.harmony", "events.song", "events.silence", "events.player"] {
+            let chronicle = self.chronicle.clone();
+            let chronicle_sub_id = self
+                .event_bus
+                .subscribe(topic, Box::new(move |event| {
+                    let chronicle = chronicle.clone();
+                    tokio::spawn(async move { chronicle.record(&event).await });
+                }))
+                .await?;
+            self.subscription_ids.write().await.push(chronicle_sub_id);
+        }
+
+        // A player who drops out mid-vision is still returned to where they were
+        let visions = self.visions.clone();
+        let event_bus = self.event_bus.clone();
+        let player_sub_id = self
+            .event_bus
+            .subscribe("events.player", Box::new(move |event| {
+                if let EventType::Player(PlayerEvent::Disconnected { player_id }) = &event.event_type {
+                    let visions = visions.clone();
+                    let event_bus = event_bus.clone();
+                    let player_id = player_id.to_string();
+                    tokio::spawn(async move {
+                        if let Some(vision) = visions.exit(&player_id, None).await {
+                            finish_vision(&event_bus, vision, VisionEndReason::Disconnected).await;
+                        }
+                    });
+                }
+            }))
+            .await?;
+
+        self.subscription_ids.write().await.push(player_sub_id);
+
+        // Force players out of visions that overran their scene
+        let visions = self.visions.clone();
+        let event_bus = self.event_bus.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
+            loop {
+                interval.tick().await;
+                for vision in visions.take_overdue(chrono::Utc::now()).await {
+                    finish_vision(&event_bus, vision, VisionEndReason::TimedOut).await;
+                }
+            }
+        });
+
         // Start cleanup task for expired songs
         let songs = self.active_songs.clone();
         tokio::spawn(async move {
@@ -208,99 +326,138 @@ impl StoryEngineService {
         Ok(song_id)
     }
 
+    /// Start gathering players for a symphony, with the initiator as its
+    /// first participant.
     pub async fn start_symphony(
         &self,
         symphony_type: String,
         initiator: PlayerId,
-        required_power: f64,
-    ) -> anyhow::Result<String> {
+    ) -> Result<Symphony, MatchmakingError> {
+        let rules = self.matchmaker.rules(&symphony_type)?.clone();
+        self.matchmaker.allowance(&symphony_type, &initiator).await?;
+
+        let started_at = chrono::Utc::now();
         let symphony = Symphony {
             id: uuid::Uuid::new_v4().to_string(),
             symphony_type: symphony_type.clone(),
             participants: vec![initiator.clone()],
-            required_power,
+            contributions: HashMap::new(),
+            required_power: rules.required_power,
             current_power: 0.0,
-            started_at: chrono::Utc::now(),
+            started_at,
+            gathering_deadline: started_at + chrono::Duration::seconds(rules.gathering_timeout_secs as i64),
             status: SymphonyStatus::Gathering,
         };
 
         let symphony_id = symphony.id.clone();
-        self.symphonies.write().await.insert(symphony_id.clone(), symphony);
+        self.symphonies.write().await.insert(symphony_id.clone(), symphony.clone());
 
         // Publish symphony started event
         let event = Event::new(EventType::Song(SongEvent::SymphonyStarted {
             participants: vec![initiator],
             symphony_type,
-            required_power,
+            required_power: rules.required_power,
         })).with_metadata(EventMetadata {
             source: Some("story-engine".to_string()),
             correlation_id: Some(symphony_id.clone()),
             ..Default::default()
         });
 
-        self.event_bus.publish(event).await?;
+        if let Err(e) = self.event_bus.publish(event).await {
+            tracing::warn!("Failed to publish symphony start: {}", e);
+        }
+
+        // Symphonies that don't gather enough players in time fail
+        let symphonies = self.symphonies.clone();
+        let event_bus = self.event_bus.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_secs(rules.gathering_timeout_secs)).await;
+            let failed = match symphonies.write().await.get_mut(&symphony_id) {
+                Some(symphony) if symphony.status == SymphonyStatus::Gathering => {
+                    symphony.status = SymphonyStatus::Failed;
+                    symphony.clone()
+                }
+                _ => return,
+            };
+            info!("🎼 Symphony {} failed to gather in time", failed.id);
+            publish_symphony_completed(&event_bus, failed, false).await;
+        });
 
-        Ok(symphony_id)
+        Ok(symphony)
     }
 
+    /// Add a player and their power to a gathering symphony. Players may
+    /// contribute more than once, up to what their attunement allows.
     pub async fn join_symphony(
         &self,
         symphony_id: &str,
         player_id: PlayerId,
         contributed_power: f64,
-    ) -> anyhow::Result<()> {
-        let mut symphonies = self.symphonies.write().await;
-
-        if let Some(symphony) = symphonies.get_mut(symphony_id) {
-            if !symphony.participants.contains(&player_id) {
-                symphony.participants.push(player_id);
-            }
-
-            symphony.current_power += contributed_power;
+    ) -> Result<Symphony, MatchmakingError> {
+        if !contributed_power.is_finite() || contributed_power <= 0.0 {
+            return Err(MatchmakingError::InvalidPower);
+        }
+        let symphony_type = self
+            .symphonies
+            .read()
+            .await
+            .get(symphony_id)
+            .map(|symphony| symphony.symphony_type.clone())
+            .ok_or_else(|| MatchmakingError::NotFound(symphony_id.to_string()))?;
+        let rules = self.matchmaker.rules(&symphony_type)?.clone();
+        let allowance = self.matchmaker.allowance(&symphony_type, &player_id).await?;
 
-            // Check if symphony is ready to complete
-            if symphony.current_power >= symphony.required_power && symphony.status == SymphonyStatus::Gathering {
-                symphony.status = SymphonyStatus::InProgress;
+        let mut symphonies = self.symphonies.write().await;
+        let symphony = symphonies
+            .get_mut(symphony_id)
+            .ok_or_else(|| MatchmakingError::NotFound(symphony_id.to_string()))?;
+        let ready = rules.contribute(symphony, &player_id, contributed_power, allowance)?;
+        let joined = symphony.clone();
+        drop(symphonies);
+
+        let event = Event::new(EventType::Song(SongEvent::SymphonyProgressed {
+            symphony_id: joined.id.clone(),
+            symphony_type: joined.symphony_type.clone(),
+            participants: joined.participants.clone(),
+            current_power: joined.current_power,
+            required_power: joined.required_power,
+        })).with_metadata(EventMetadata {
+            source: Some("story-engine".to_string()),
+            correlation_id: Some(joined.id.clone()),
+            ..Default::default()
+        });
+        if let Err(e) = self.event_bus.publish(event).await {
+            tracing::warn!("Failed to publish symphony progress: {}", e);
+        }
 
-                // Simulate symphony completion after some time
-                let symphony_id = symphony_id.to_string();
-                let participants = symphony.participants.clone();
-                let symphony_type = symphony.symphony_type.clone();
-                let event_bus = self.event_bus.clone();
-                let symphonies_clone = self.symphonies.clone();
+        if ready {
+            // Simulate symphony completion after some time
+            let symphony_id = joined.id.clone();
+            let event_bus = self.event_bus.clone();
+            let symphonies = self.symphonies.clone();
 
-                tokio::spawn(async move {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            tokio::spawn(async move {
+                tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
 
-                    // Complete the symphony
-                    if let Some(symphony) = symphonies_clone.write().await.get_mut(&symphony_id) {
+                let completed = match symphonies.write().await.get_mut(&symphony_id) {
+                    Some(symphony) => {
                         symphony.status = SymphonyStatus::Completed;
+                        symphony.clone()
                     }
-
-                    // Publish completion event
-                    let event = Event::new(EventType::Song(SongEvent::SymphonyCompleted {
-                        participants,
-                        symphony_type,
-                        success: true,
-                    })).with_metadata(EventMetadata {
-                        source: Some("story-engine".to_string()),
-                        correlation_id: Some(symphony_id),
-                        ..Default::default()
-                    });
-
-                    let _ = event_bus.publish(event).await;
-                });
-            }
+                    None => return,
+                };
+                publish_symphony_completed(&event_bus, completed, true).await;
+            });
         }
 
-        Ok(())
+        Ok(joined)
     }
 
     async fn publish_audio_event(&self, event: AudioEvent) {
         if let Ok(mut con) = self.redis_client.get_async_connection().await {
             if let Ok(json) = serde_json::to_string(&event) {
                 let _ : Result<(), _> = redis::cmd("PUBLISH")
-                    .arg("npc:events")
+                    .arg(self.tenant.scoped_key("npc:events"))
                     .arg(json)
                     .query_async(&mut con)
                     .await;
@@ -327,7 +484,10 @@ impl StoryEngineService {
     ) -> DialogueResponse {
         let dialogue_text = self.generate_dialogue_text(npc_id, player_context).await;
         let emotion = self.determine_npc_emotion(npc_id, player_context);
+        self.speak(npc_id, dialogue_text, emotion).await
+    }
 
+    async fn speak(&self, npc_id: &str, dialogue_text: String, emotion: EmotionalState) -> DialogueResponse {
         let audio_event = AudioEvent {
             id: Uuid::new_v4(),
             event_type: AudioEventType::CharacterSpeak {
@@ -349,6 +509,174 @@ impl StoryEngineService {
         }
     }
 
+    /// Run every trigger bound to the interacted entity and execute the
+    /// actions of those that fire.
+    pub async fn handle_interaction(self: &Arc<Self>, interaction: EntityInteraction) -> InteractionOutcome {
+        let (fired, skipped) = self.triggers.evaluate(&interaction).await;
+        let player_context = PlayerContext {
+            player_id: interaction.player_id.clone(),
+            location: interaction.location.clone(),
+        };
+
+        let mut dialogue = Vec::new();
+        let mut vision = None;
+        for trigger in &fired {
+            info!("📜 Trigger {} fired for player {} on {}", trigger.trigger_id, interaction.player_id, interaction.entity_id);
+            for action in &trigger.actions {
+                if let TriggerAction::Vision { vision_id } = action {
+                    let return_point = ReturnPoint {
+                        location: interaction.location.clone(),
+                        state: serde_json::Value::Null,
+                    };
+                    match self.enter_vision(&interaction.player_id, vision_id, return_point).await {
+                        Ok(active) => vision = Some(active),
+                        Err(e) => tracing::warn!("Trigger {} could not start vision: {}", trigger.trigger_id, e),
+                    }
+                    continue;
+                }
+                let response = self
+                    .run_action(&trigger.trigger_id, action, &player_context, &interaction.entity_id)
+                    .await;
+                dialogue.extend(response);
+            }
+        }
+
+        InteractionOutcome {
+            fired: fired.into_iter().map(|t| t.trigger_id).collect(),
+            skipped,
+            dialogue,
+            vision,
+        }
+    }
+
+    /// Execute a scripted action on behalf of a trigger or vision beat.
+    async fn run_action(
+        &self,
+        source_id: &str,
+        action: &TriggerAction,
+        player_context: &PlayerContext,
+        entity_id: &str,
+    ) -> Option<DialogueResponse> {
+        match action {
+            TriggerAction::Dialogue { npc_id, line: Some(line) } => {
+                let emotion = self.determine_npc_emotion(npc_id, player_context);
+                Some(self.speak(npc_id, line.clone(), emotion).await)
+            }
+            TriggerAction::Dialogue { npc_id, line: None } => {
+                Some(self.generate_npc_dialogue(npc_id, player_context).await)
+            }
+            TriggerAction::Spawn { entity_type, location } => {
+                self.publish_trigger_event(source_id, WorldEvent::EntitySpawnRequested {
+                    entity_type: entity_type.clone(),
+                    location: location.clone().unwrap_or_else(|| player_context.location.clone()),
+                    requested_by: source_id.to_string(),
+                }).await;
+                None
+            }
+            TriggerAction::EmitEvent { name, payload } => {
+                self.publish_trigger_event(source_id, WorldEvent::ScriptedEvent {
+                    name: name.clone(),
+                    entity_id: entity_id.to_string(),
+                    player_id: PlayerId::from_legacy(&player_context.player_id),
+                    payload: payload.clone(),
+                }).await;
+                None
+            }
+            // Visions only start from triggers; scenes cannot nest them
+            TriggerAction::Vision { .. } => None,
+        }
+    }
+
+    /// Swap the player into an instance of the vision scene, populate it and
+    /// schedule its beats. The player is returned when the scene finishes.
+    pub async fn enter_vision(
+        self: &Arc<Self>,
+        player_id: &str,
+        vision_id: &str,
+        return_point: ReturnPoint,
+    ) -> Result<ActiveVision, String> {
+        let (vision, scene) = self.visions.enter(player_id, vision_id, return_point).await?;
+        let instance_id = vision.instance_id.to_string();
+        info!("🌙 Player {} entered vision {} ({})", player_id, scene.id, instance_id);
+
+        let event = Event::new(EventType::Player(PlayerEvent::VisionEntered {
+            player_id: PlayerId::from_legacy(player_id),
+            vision_id: scene.id.clone(),
+            instance_id: instance_id.clone(),
+            origin: scene.origin.clone(),
+        })).with_metadata(EventMetadata {
+            source: Some("story-engine".to_string()),
+            correlation_id: Some(instance_id.clone()),
+            tags: vec!["vision".to_string()],
+            ..Default::default()
+        });
+        if let Err(e) = self.event_bus.publish(event).await {
+            tracing::warn!("Failed to publish vision start: {}", e);
+        }
+
+        // Instance entities are tagged with the instance so the world keeps
+        // them out of the shared region
+        for entity in &scene.entities {
+            self.publish_trigger_event(&instance_id, WorldEvent::EntitySpawnRequested {
+                entity_type: entity.entity_type.clone(),
+                location: entity.location.clone(),
+                requested_by: instance_id.clone(),
+            }).await;
+        }
+
+        let service = self.clone();
+        let player_id = player_id.to_string();
+        let instance = vision.instance_id;
+        tokio::spawn(async move { service.play_vision(player_id, instance, scene).await });
+
+        Ok(vision)
+    }
+
+    async fn play_vision(self: Arc<Self>, player_id: String, instance_id: Uuid, scene: VisionScene) {
+        let player_context = PlayerContext {
+            player_id: player_id.clone(),
+            location: scene.origin.clone(),
+        };
+        let mut beats = scene.beats.clone();
+        beats.sort_by_key(|beat| beat.at_secs);
+
+        let started = tokio::time::Instant::now();
+        for beat in beats {
+            tokio::time::sleep_until(started + tokio::time::Duration::from_secs(beat.at_secs)).await;
+            if !self.visions.is_current(&player_id, instance_id).await {
+                return;
+            }
+            self.run_action(&instance_id.to_string(), &beat.action, &player_context, &scene.id).await;
+        }
+
+        tokio::time::sleep_until(started + tokio::time::Duration::from_secs(scene.duration_secs)).await;
+        self.end_vision(&player_id, Some(instance_id), VisionEndReason::Completed).await;
+    }
+
+    pub async fn end_vision(
+        &self,
+        player_id: &str,
+        instance_id: Option<Uuid>,
+        reason: VisionEndReason,
+    ) -> Option<ActiveVision> {
+        let vision = self.visions.exit(player_id, instance_id).await?;
+        finish_vision(&self.event_bus, vision.clone(), reason).await;
+        Some(vision)
+    }
+
+    async fn publish_trigger_event(&self, trigger_id: &str, world_event: WorldEvent) {
+        let event = Event::new(EventType::World(world_event)).with_metadata(EventMetadata {
+            source: Some("story-engine".to_string()),
+            correlation_id: Some(trigger_id.to_string()),
+            tags: vec!["trigger".to_string()],
+            ..Default::default()
+        });
+
+        if let Err(e) = self.event_bus.publish(event).await {
+            tracing::warn!("Failed to publish trigger event: {}", e);
+        }
+    }
+
     pub async fn get_active_songs(&self) -> Vec<ActiveSong> {
         self.active_songs.read().await.values().cloned().collect()
     }
@@ -366,13 +694,110 @@ impl StoryEngineService {
     }
 }
 
+async fn publish_symphony_completed(event_bus: &Arc<dyn GameEventBus>, symphony: Symphony, success: bool) {
+    let event = Event::new(EventType::Song(SongEvent::SymphonyCompleted {
+        participants: symphony.participants,
+        symphony_type: symphony.symphony_type,
+        success,
+    })).with_metadata(EventMetadata {
+        source: Some("story-engine".to_string()),
+        correlation_id: Some(symphony.id),
+        ..Default::default()
+    });
+
+    if let Err(e) = event_bus.publish(event).await {
+        tracing::warn!("Failed to publish symphony completion: {}", e);
+    }
+}
+
+/// Tell the world to put the player back where the vision found them.
+async fn finish_vision(event_bus: &Arc<dyn GameEventBus>, vision: ActiveVision, reason: VisionEndReason) {
+    info!("🌅 Player {} left vision {} ({})", vision.player_id, vision.vision_id, reason);
+    let instance_id = vision.instance_id.to_string();
+    let event = Event::new(EventType::Player(PlayerEvent::VisionEnded {
+        player_id: PlayerId::from_legacy(&vision.player_id),
+        vision_id: vision.vision_id,
+        instance_id: instance_id.clone(),
+        return_to: vision.return_point.location,
+        state: vision.return_point.state,
+        reason: reason.to_string(),
+    })).with_metadata(EventMetadata {
+        source: Some("story-engine".to_string()),
+        correlation_id: Some(instance_id),
+        tags: vec!["vision".to_string()],
+        ..Default::default()
+    });
+
+    if let Err(e) = event_bus.publish(event).await {
+        tracing::warn!("Failed to publish vision end: {}", e);
+    }
+}
+
+fn stewardship_legend(event: &CommunityEvent) -> Option<String> {
+    match event {
+        CommunityEvent::StewardshipClaimed { region_id, ensemble_id } => {
+            Some(format!("Ensemble {} swore to tend region {}", ensemble_id, region_id.0))
+        }
+        CommunityEvent::StewardshipContested { region_id, challenger_id, resolves_at, .. } => Some(format!(
+            "Ensemble {} challenged the stewards of region {}; the Song will decide at {}",
+            challenger_id, region_id.0, resolves_at
+        )),
+        CommunityEvent::StewardshipTransferred { region_id, from, to } => {
+            Some(format!("Region {} passed from {} into the care of {}", region_id.0, from, to))
+        }
+        CommunityEvent::StewardshipLapsed { region_id, ensemble_id } => {
+            Some(format!("Ensemble {} let the rituals of region {} fall silent", ensemble_id, region_id.0))
+        }
+        CommunityEvent::RegionNamed { region_id, name, .. } => {
+            Some(format!("Region {} shall henceforth be known as {}", region_id.0, name))
+        }
+        CommunityEvent::TerraformProposed { region_id, ensemble_id, kind, .. } => Some(format!(
+            "Ensemble {} asked the people to let region {} be remade ({:?})",
+            ensemble_id, region_id.0, kind
+        )),
+        CommunityEvent::TerraformDecided { region_id, kind, outcome, .. } if outcome == "approved" => {
+            Some(format!("The people agreed: region {} will be remade ({:?})", region_id.0, kind))
+        }
+        CommunityEvent::GuildFounded { name, founder_id, .. } => {
+            Some(format!("{} gathered kindred spirits and founded the guild {}", founder_id.0, name))
+        }
+        CommunityEvent::UpkeepPerformed { .. }
+        | CommunityEvent::TerraformDecided { .. }
+        | CommunityEvent::AnnotationsChanged { .. }
+        | CommunityEvent::AnnotationSettingsChanged { .. }
+        | CommunityEvent::PartyFormed { .. }
+        | CommunityEvent::PartyDisbanded { .. }
+        | CommunityEvent::MemberInvited { .. }
+        | CommunityEvent::MemberJoined { .. }
+        | CommunityEvent::MemberLeft { .. } => None,
+    }
+}
+
+fn silence_reaction(event: &SilenceEvent) -> Option<String> {
+    match event {
+        SilenceEvent::InfectionStageChanged { player_id, from, to, .. } if to > from => Some(match to {
+            InfectionStage::Touched => format!("A chill settles in {}; their songs waver at the edges", player_id.0),
+            InfectionStage::Afflicted => format!("The Silence has taken root in {}; the Echoes grow uneasy", player_id.0),
+            _ => format!("{} is all but consumed by the Silence; only a true restoration can call them back", player_id.0),
+        }),
+        SilenceEvent::InfectionStageChanged { player_id, to: InfectionStage::Clear, .. } => {
+            Some(format!("{} sings free of the Silence once more", player_id.0))
+        }
+        SilenceEvent::InfectionCleansed { player_id, source, .. } if source == "ritual_site" => {
+            Some(format!("{} was washed in the old rites, and the Silence loosened its hold", player_id.0))
+        }
+        _ => None,
+    }
+}
+
 // HTTP handlers
 async fn weave_song_handler(
+    player: AuthenticatedPlayer,
     body: WeaveRequest,
     service: Arc<StoryEngineService>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     match service.weave_song(
-        PlayerId(body.player_id),
+        player.player_id,
         body.song_type,
         body.power,
         body.location,
@@ -387,6 +812,231 @@ async fn weave_song_handler(
     }
 }
 
+async fn upsert_trigger_handler(
+    definition: TriggerDefinition,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.triggers.upsert(definition).await {
+        Ok(()) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "success": true })),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": e })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InteractionRequest {
+    /// A world-engine entity id.
+    entity_id: u64,
+    interaction: InteractionType,
+}
+
+/// The parts of a world-engine entity triggers check.
+#[derive(Debug, Deserialize)]
+struct WorldEntity {
+    components: WorldEntityComponents,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorldEntityComponents {
+    #[serde(default)]
+    tags: Vec<String>,
+    placement: Option<WorldPlacement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorldPlacement {
+    position: Coordinates,
+}
+
+/// Interactions act as the authenticated player. The entity's tags and
+/// location come from world-engine, the hour from the shared world clock
+/// and the player's facts from their progress, never from the request.
+async fn interact_handler(
+    player: AuthenticatedPlayer,
+    request: InteractionRequest,
+    service: Arc<StoryEngineService>,
+) -> Result<warp::reply::WithStatus<warp::reply::Json>, warp::Rejection> {
+    let path = format!("/entities/{}", request.entity_id);
+    let entity = match service.services.get_json::<WorldEntity>("world-engine", &path).await {
+        Ok(entity) => entity,
+        Err(e) => return Ok(error_reply(FinalverseError::upstream("world-engine", e))),
+    };
+    let Some(placement) = entity.components.placement else {
+        return Ok(error_reply(FinalverseError::BadRequest(format!(
+            "Entity {} is not placed in the world",
+            request.entity_id
+        ))));
+    };
+    match service.quests.player_facts(player.player_id.0).await {
+        Ok(facts) => {
+            let interaction = EntityInteraction {
+                entity_id: request.entity_id.to_string(),
+                entity_tags: entity.components.tags,
+                player_id: player.player_id.0.to_string(),
+                interaction: request.interaction,
+                location: placement.position,
+                world_hour: None,
+                player: facts,
+            };
+            let outcome = service.handle_interaction(interaction).await;
+            Ok(warp::reply::with_status(warp::reply::json(&outcome), warp::http::StatusCode::OK))
+        }
+        Err(e) => Ok(error_reply(e)),
+    }
+}
+
+async fn upsert_vision_handler(
+    scene: VisionScene,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.visions.upsert(scene).await {
+        Ok(()) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "success": true })),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": e })),
+            warp::http::StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+async fn enter_vision_handler(
+    vision_id: String,
+    player: AuthenticatedPlayer,
+    body: EnterVisionRequest,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let player_id = player.player_id.0.to_string();
+    match service.enter_vision(&player_id, &vision_id, body.return_point).await {
+        Ok(vision) => Ok(warp::reply::with_status(
+            warp::reply::json(&vision),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": e })),
+            warp::http::StatusCode::CONFLICT,
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+struct StartSymphonyRequest {
+    symphony_type: String,
+}
+
+#[derive(Deserialize)]
+struct JoinSymphonyRequest {
+    power: f64,
+}
+
+fn symphony_reply(result: Result<Symphony, MatchmakingError>) -> warp::reply::WithStatus<warp::reply::Json> {
+    match result {
+        Ok(symphony) => warp::reply::with_status(warp::reply::json(&symphony), warp::http::StatusCode::OK),
+        Err(e) => {
+            let status = match e {
+                MatchmakingError::NotFound(_) | MatchmakingError::UnknownSymphony(_) => warp::http::StatusCode::NOT_FOUND,
+                MatchmakingError::NotGathering | MatchmakingError::Full(_) => warp::http::StatusCode::CONFLICT,
+                MatchmakingError::TierTooLow { .. } => warp::http::StatusCode::FORBIDDEN,
+                MatchmakingError::ProgressUnavailable(_) => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                MatchmakingError::InvalidPower | MatchmakingError::ExceedsAllowance { .. } => {
+                    warp::http::StatusCode::BAD_REQUEST
+                }
+            };
+            warp::reply::with_status(warp::reply::json(&serde_json::json!({ "error": e.to_string() })), status)
+        }
+    }
+}
+
+async fn start_symphony_handler(
+    player: AuthenticatedPlayer,
+    body: StartSymphonyRequest,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let initiator = player.player_id;
+    Ok(symphony_reply(service.start_symphony(body.symphony_type, initiator).await))
+}
+
+async fn join_symphony_handler(
+    symphony_id: String,
+    player: AuthenticatedPlayer,
+    body: JoinSymphonyRequest,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let player_id = player.player_id;
+    Ok(symphony_reply(service.join_symphony(&symphony_id, player_id, body.power).await))
+}
+
+#[derive(Deserialize)]
+struct ChronicleQuery {
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+async fn chronicle_handler(
+    player_id: String,
+    query: ChronicleQuery,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    match service.chronicle.page(&player_id, query.offset, limit).await {
+        Ok(page) => Ok(warp::reply::with_status(
+            warp::reply::json(&page),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": format!("Chronicle unavailable: {}", e) })),
+            warp::http::StatusCode::SERVICE_UNAVAILABLE,
+        )),
+    }
+}
+
+/// An error in the JSON body every Finalverse service answers with.
+fn error_reply(error: FinalverseError) -> warp::reply::WithStatus<warp::reply::Json> {
+    let status = warp::http::StatusCode::from_u16(error.status_code().as_u16())
+        .unwrap_or(warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+    warp::reply::with_status(warp::reply::json(&error.body()), status)
+}
+
+#[derive(Deserialize)]
+struct QuestRequest {
+    /// A region id; region names aren't looked up.
+    region: Option<String>,
+}
+
+async fn generate_quest_handler(
+    player: AuthenticatedPlayer,
+    body: QuestRequest,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let region_id = body.region.and_then(|region| Uuid::parse_str(&region).ok()).map(RegionId);
+    match service.quests.generate(player.player_id.0, region_id).await {
+        Ok(quest) => Ok(warp::reply::with_status(warp::reply::json(&quest), warp::http::StatusCode::OK)),
+        Err(e) => Ok(error_reply(e)),
+    }
+}
+
+/// A player's quests, for that player only.
+async fn list_quests_handler(
+    player_id: String,
+    player: AuthenticatedPlayer,
+    service: Arc<StoryEngineService>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if player_id != player.player_id.0.to_string() {
+        return Ok(error_reply(FinalverseError::Forbidden("Quests are only shown to their player".to_string())));
+    }
+    match service.quests.list(&player_id).await {
+        Ok(quests) => Ok(warp::reply::with_status(warp::reply::json(&quests), warp::http::StatusCode::OK)),
+        Err(e) => Ok(error_reply(e)),
+    }
+}
+
 async fn health_handler() -> Result<impl warp::Reply, warp::Rejection> {
     Ok(warp::reply::json(&serde_json::json!({
         "status": "healthy",
@@ -397,15 +1047,21 @@ async fn health_handler() -> Result<impl warp::Reply, warp::Rejection> {
 
 #[derive(Deserialize)]
 struct WeaveRequest {
-    player_id: String,
     song_type: SongType,
     power: f64,
     location: Coordinates,
 }
 
+#[derive(Deserialize)]
+struct EnterVisionRequest {
+    return_point: ReturnPoint,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     logging::init(None);
+    // Mirror world-engine's clock so events carry world time
+    finalverse_core::world_clock::start_from_env();
 
     // Initialize event bus
     let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
@@ -415,20 +1071,50 @@ async fn main() -> anyhow::Result<()> {
         info!("📦 Using local event bus");
         Arc::new(LocalEventBus::new())
     };
+    // Record what we publish when EVENT_JOURNAL_PATH is set
+    let (event_bus, journal) = JournaledEventBus::from_env(event_bus).await?;
+    if journal.is_some() {
+        info!("📓 Journaling published events");
+    }
+    let event_bus = TenantEventBus::from_env(event_bus);
 
     // Create service
     let redis_client = RedisClient::open("redis://127.0.0.1/").unwrap();
-    let service = Arc::new(StoryEngineService::new(event_bus, redis_client));
+    let services = ServiceClient::new(LocalServiceRegistry::new());
+    let service = Arc::new(StoryEngineService::new(event_bus.clone(), redis_client, services));
 
     // Start event listeners
     service.start_event_listeners().await?;
 
-    // Define routes
+    // Load designer-authored triggers
+    let triggers_dir = std::env::var("STORY_TRIGGERS_DIR").unwrap_or_else(|_| "data/triggers".to_string());
+    if std::path::Path::new(&triggers_dir).is_dir() {
+        match service.triggers.load_dir(std::path::Path::new(&triggers_dir)).await {
+            Ok(count) => info!("📜 Loaded {} triggers from {}", count, triggers_dir),
+            Err(e) => tracing::warn!("Failed to load triggers: {}", e),
+        }
+    }
+
+    // Load designer-authored vision scenes
+    let visions_dir = std::env::var("STORY_VISIONS_DIR").unwrap_or_else(|_| "data/visions".to_string());
+    if std::path::Path::new(&visions_dir).is_dir() {
+        match service.visions.load_dir(std::path::Path::new(&visions_dir)).await {
+            Ok(count) => info!("🌙 Loaded {} vision scenes from {}", count, visions_dir),
+            Err(e) => tracing::warn!("Failed to load visions: {}", e),
+        }
+    }
+
+    // Define routes. Player actions act as the authenticated player,
+    // whatever a request body claims; authoring triggers and visions takes
+    // the operator token.
     let service_clone = service.clone();
     let service_filter = warp::any().map(move || service_clone.clone());
+    let player = finalverse_auth::warp::player(PlayerAuth::load());
+    let operator = OperatorGuard::load().warp_filter();
 
     let weave_song = warp::path!("song" / "weave")
         .and(warp::post())
+        .and(player.clone())
         .and(warp::body::json())
         .and(service_filter.clone())
         .and_then(weave_song_handler);
@@ -441,13 +1127,145 @@ async fn main() -> anyhow::Result<()> {
             Ok::<_, warp::Rejection>(warp::reply::json(&songs))
         });
 
+    let start_symphony = warp::path!("symphonies")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(start_symphony_handler);
+
+    let join_symphony = warp::path!("symphonies" / String / "join")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(join_symphony_handler);
+
+    let list_symphonies = warp::path!("symphonies")
+        .and(warp::get())
+        .and(service_filter.clone())
+        .and_then(|service: Arc<StoryEngineService>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&service.get_symphonies().await))
+        });
+
+    let upsert_trigger = warp::path!("triggers")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(upsert_trigger_handler);
+
+    let list_triggers = warp::path!("triggers")
+        .and(warp::get())
+        .and(service_filter.clone())
+        .and_then(|service: Arc<StoryEngineService>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&service.triggers.list().await))
+        });
+
+    let delete_trigger = warp::path!("triggers" / String)
+        .and(warp::delete())
+        .and(operator.clone())
+        .and(service_filter.clone())
+        .and_then(|id: String, service: Arc<StoryEngineService>| async move {
+            let removed = service.triggers.remove(&id).await;
+            Ok::<_, warp::Rejection>(warp::reply::json(&serde_json::json!({ "removed": removed })))
+        });
+
+    let interact = warp::path!("triggers" / "interact")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(interact_handler);
+
+    let upsert_vision = warp::path!("visions")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(upsert_vision_handler);
+
+    let list_visions = warp::path!("visions")
+        .and(warp::get())
+        .and(service_filter.clone())
+        .and_then(|service: Arc<StoryEngineService>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&service.visions.list().await))
+        });
+
+    let enter_vision = warp::path!("visions" / String / "enter")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(enter_vision_handler);
+
+    let exit_vision = warp::path!("visions" / "exit")
+        .and(warp::post())
+        .and(player.clone())
+        .and(service_filter.clone())
+        .and_then(|player: AuthenticatedPlayer, service: Arc<StoryEngineService>| async move {
+            let player_id = player.player_id.0.to_string();
+            let vision = service.end_vision(&player_id, None, VisionEndReason::Left).await;
+            Ok::<_, warp::Rejection>(warp::reply::json(&serde_json::json!({ "ended": vision })))
+        });
+
+    let active_vision = warp::path!("visions" / "active" / String)
+        .and(warp::get())
+        .and(service_filter.clone())
+        .and_then(|player_id: String, service: Arc<StoryEngineService>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&service.visions.active_for(&player_id).await))
+        });
+
+    let chronicle = warp::path!("chronicle" / String)
+        .and(warp::get())
+        .and(warp::query::<ChronicleQuery>())
+        .and(service_filter.clone())
+        .and_then(chronicle_handler);
+
+    let generate_quest = warp::path!("quest" / "generate")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(service_filter.clone())
+        .and_then(generate_quest_handler);
+
+    let list_quests = warp::path!("quests" / String)
+        .and(warp::get())
+        .and(player)
+        .and(service_filter.clone())
+        .and_then(list_quests_handler);
+
     let health = warp::path!("health")
         .and(warp::get())
         .and_then(health_handler);
 
+    let mut debug = DebugEndpoints::load("story-engine").with_event_bus(event_bus);
+    if let Some(journal) = journal {
+        debug = debug.with_journal(journal);
+    }
+    let debug = Arc::new(debug).warp_routes();
+
     let routes = weave_song
         .or(get_songs)
-        .or(health);
+        .or(start_symphony)
+        .or(join_symphony)
+        .or(list_symphonies)
+        .or(interact)
+        .or(upsert_trigger)
+        .or(list_triggers)
+        .or(delete_trigger)
+        .or(exit_vision)
+        .or(active_vision)
+        .or(enter_vision)
+        .or(upsert_vision)
+        .or(list_visions)
+        .or(chronicle)
+        .or(generate_quest)
+        .or(list_quests)
+        .or(health)
+        .or(debug)
+        .recover(finalverse_auth::warp::recover)
+        .recover(finalverse_core::warp::recover);
 
     // Handle shutdown
     let service_shutdown = service.clone();
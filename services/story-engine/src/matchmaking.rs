@@ -0,0 +1,262 @@
+// services/story-engine/src/matchmaking.rs
+// Who may take part in a symphony, and how much power each player can bring
+
+use crate::{Symphony, SymphonyStatus};
+use finalverse_events::PlayerId;
+use serde::{Deserialize, Serialize};
+use service_registry::ServiceClient;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Power a player may contribute to one symphony per attunement tier.
+const POWER_PER_TIER: f64 = 25.0;
+
+/// What a symphony type asks of the players who perform it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SymphonyRules {
+    pub min_participants: usize,
+    pub max_participants: usize,
+    pub required_power: f64,
+    /// Attunement tier needed to start or join.
+    pub min_tier: u32,
+    /// Seconds a symphony may gather before it fails.
+    pub gathering_timeout_secs: u64,
+}
+
+impl SymphonyRules {
+    /// The symphonies players can perform, by type.
+    pub fn standard() -> HashMap<String, SymphonyRules> {
+        HashMap::from([
+            (
+                "harmony_of_balance".to_string(),
+                SymphonyRules {
+                    min_participants: 3,
+                    max_participants: 12,
+                    required_power: 300.0,
+                    min_tier: 2,
+                    gathering_timeout_secs: 600,
+                },
+            ),
+            (
+                "song_of_restoration".to_string(),
+                SymphonyRules {
+                    min_participants: 2,
+                    max_participants: 8,
+                    required_power: 150.0,
+                    min_tier: 1,
+                    gathering_timeout_secs: 300,
+                },
+            ),
+        ])
+    }
+
+    /// The most power a player at attunement `tier` may contribute in total.
+    pub fn allowance_for(&self, tier: u32) -> Result<f64, MatchmakingError> {
+        if tier < self.min_tier {
+            return Err(MatchmakingError::TierTooLow {
+                tier,
+                required: self.min_tier,
+            });
+        }
+        Ok(tier as f64 * POWER_PER_TIER)
+    }
+
+    /// Add `power` from `player_id` to a gathering `symphony`, joining them
+    /// if they aren't a participant yet. Returns whether the symphony now
+    /// has the players and power to begin, and marks it in progress if so.
+    pub fn contribute(
+        &self,
+        symphony: &mut Symphony,
+        player_id: &PlayerId,
+        power: f64,
+        allowance: f64,
+    ) -> Result<bool, MatchmakingError> {
+        if !power.is_finite() || power <= 0.0 {
+            return Err(MatchmakingError::InvalidPower);
+        }
+        if symphony.status != SymphonyStatus::Gathering {
+            return Err(MatchmakingError::NotGathering);
+        }
+        let joining = !symphony.participants.contains(player_id);
+        if joining && symphony.participants.len() >= self.max_participants {
+            return Err(MatchmakingError::Full(self.max_participants));
+        }
+        let contributed = symphony.contributions.get(&player_id.0).copied().unwrap_or(0.0);
+        if contributed + power > allowance {
+            return Err(MatchmakingError::ExceedsAllowance {
+                remaining: (allowance - contributed).max(0.0),
+            });
+        }
+
+        if joining {
+            symphony.participants.push(player_id.clone());
+        }
+        *symphony.contributions.entry(player_id.0).or_insert(0.0) += power;
+        symphony.current_power += power;
+
+        let ready = symphony.current_power >= symphony.required_power
+            && symphony.participants.len() >= self.min_participants;
+        if ready {
+            symphony.status = SymphonyStatus::InProgress;
+        }
+        Ok(ready)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MatchmakingError {
+    #[error("Unknown symphony type: {0}")]
+    UnknownSymphony(String),
+
+    #[error("Symphony not found: {0}")]
+    NotFound(String),
+
+    #[error("Symphony is no longer gathering")]
+    NotGathering,
+
+    #[error("Symphony already has its {0} participants")]
+    Full(usize),
+
+    #[error("Attunement tier {tier} is below the required tier {required}")]
+    TierTooLow { tier: u32, required: u32 },
+
+    #[error("Contributed power must be positive")]
+    InvalidPower,
+
+    #[error("Contribution exceeds what this player can give: {remaining} remaining")]
+    ExceedsAllowance { remaining: f64 },
+
+    #[error("Can't check harmony progress: {0}")]
+    ProgressUnavailable(String),
+}
+
+/// The parts of harmony-service's player progress matchmaking needs.
+#[derive(Debug, Deserialize)]
+struct HarmonyProgress {
+    attunement_tier: u32,
+}
+
+/// Checks players against symphony rules, using harmony-service for their
+/// attunement.
+pub struct Matchmaker {
+    rules: HashMap<String, SymphonyRules>,
+    services: ServiceClient,
+}
+
+impl Matchmaker {
+    pub fn new(services: ServiceClient) -> Self {
+        Self {
+            rules: SymphonyRules::standard(),
+            services,
+        }
+    }
+
+    pub fn rules(&self, symphony_type: &str) -> Result<&SymphonyRules, MatchmakingError> {
+        self.rules
+            .get(symphony_type)
+            .ok_or_else(|| MatchmakingError::UnknownSymphony(symphony_type.to_string()))
+    }
+
+    /// The most power `player_id` may contribute to a symphony of
+    /// `symphony_type` in total. Errors if they may not take part at all.
+    pub async fn allowance(&self, symphony_type: &str, player_id: &PlayerId) -> Result<f64, MatchmakingError> {
+        let rules = self.rules(symphony_type)?;
+        let progress: HarmonyProgress = self
+            .services
+            .get_json("harmony-service", &format!("/progress/{}", player_id.0))
+            .await
+            .map_err(|e| MatchmakingError::ProgressUnavailable(e.to_string()))?;
+        rules.allowance_for(progress.attunement_tier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn restoration() -> SymphonyRules {
+        SymphonyRules::standard().remove("song_of_restoration").unwrap()
+    }
+
+    fn gathering(rules: &SymphonyRules, initiator: &PlayerId) -> Symphony {
+        let now = chrono::Utc::now();
+        Symphony {
+            id: "symphony-1".to_string(),
+            symphony_type: "song_of_restoration".to_string(),
+            participants: vec![initiator.clone()],
+            contributions: HashMap::new(),
+            required_power: rules.required_power,
+            current_power: 0.0,
+            started_at: now,
+            gathering_deadline: now,
+            status: SymphonyStatus::Gathering,
+        }
+    }
+
+    fn player() -> PlayerId {
+        PlayerId(Uuid::new_v4())
+    }
+
+    #[test]
+    fn allowance_grows_with_tier_above_the_minimum() {
+        let rules = SymphonyRules::standard().remove("harmony_of_balance").unwrap();
+        assert!(matches!(rules.allowance_for(1), Err(MatchmakingError::TierTooLow { tier: 1, required: 2 })));
+        assert_eq!(rules.allowance_for(2).unwrap(), 50.0);
+        assert_eq!(rules.allowance_for(4).unwrap(), 100.0);
+    }
+
+    #[test]
+    fn contributions_add_up_to_the_players_allowance() {
+        let rules = restoration();
+        let ana = player();
+        let mut symphony = gathering(&rules, &ana);
+
+        assert!(!rules.contribute(&mut symphony, &ana, 30.0, 50.0).unwrap());
+        assert!(matches!(
+            rules.contribute(&mut symphony, &ana, 30.0, 50.0),
+            Err(MatchmakingError::ExceedsAllowance { remaining }) if remaining == 20.0
+        ));
+        assert!(!rules.contribute(&mut symphony, &ana, 20.0, 50.0).unwrap());
+        assert_eq!(symphony.participants, std::slice::from_ref(&ana));
+        assert_eq!(symphony.contributions[&ana.0], 50.0);
+        assert_eq!(symphony.current_power, 50.0);
+
+        assert!(matches!(rules.contribute(&mut symphony, &ana, 0.0, 50.0), Err(MatchmakingError::InvalidPower)));
+        assert!(matches!(rules.contribute(&mut symphony, &ana, f64::NAN, 50.0), Err(MatchmakingError::InvalidPower)));
+    }
+
+    #[test]
+    fn symphony_begins_once_it_has_both_the_players_and_the_power() {
+        let rules = restoration();
+        let ana = player();
+        let mut symphony = gathering(&rules, &ana);
+
+        // Enough power from one player still needs a second participant
+        assert!(!rules.contribute(&mut symphony, &ana, 150.0, 200.0).unwrap());
+        assert_eq!(symphony.status, SymphonyStatus::Gathering);
+
+        let bo = player();
+        assert!(rules.contribute(&mut symphony, &bo, 10.0, 25.0).unwrap());
+        assert_eq!(symphony.status, SymphonyStatus::InProgress);
+        assert!(matches!(
+            rules.contribute(&mut symphony, &player(), 10.0, 25.0),
+            Err(MatchmakingError::NotGathering)
+        ));
+    }
+
+    #[test]
+    fn full_symphonies_take_more_power_only_from_their_participants() {
+        let rules = restoration();
+        let ana = player();
+        let mut symphony = gathering(&rules, &ana);
+        for _ in 1..rules.max_participants {
+            rules.contribute(&mut symphony, &player(), 1.0, 25.0).unwrap();
+        }
+        assert_eq!(symphony.participants.len(), rules.max_participants);
+
+        assert!(matches!(rules.contribute(&mut symphony, &player(), 1.0, 25.0), Err(MatchmakingError::Full(8))));
+        rules.contribute(&mut symphony, &ana, 1.0, 25.0).unwrap();
+        assert_eq!(symphony.participants.len(), rules.max_participants);
+    }
+}
@@ -0,0 +1,248 @@
+// services/story-engine/src/personal_quests.rs
+// Quests written for one player: their progress from harmony-service and
+// their region from world-engine go to ai-orchestra, and the narrative
+// that comes back is turned into objectives and kept in Redis
+
+use crate::quest_system::{AiQuestPrompt, DynamicQuest, GenerationContext, QuestGenerationEngine, QuestState};
+use crate::triggers::PlayerFacts;
+use finalverse_core::{FinalverseError, RegionId, TenantId};
+use finalverse_ratelimit::RateLimiter;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::Deserialize;
+use service_registry::ServiceClient;
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+/// Quests kept per player; older ones are dropped as new ones are written.
+const MAX_KEPT_QUESTS: isize = 50;
+/// Quests a player may have written per minute, after a burst of
+/// [`QUEST_BURST`]. Each one is an LLM call.
+const QUESTS_PER_MINUTE: u32 = 1;
+const QUEST_BURST: u32 = 3;
+
+/// The parts of harmony-service's player progress a quest is written around.
+#[derive(Debug, Default, Deserialize)]
+struct HarmonyProgress {
+    resonance: HarmonyResonance,
+    attunement_tier: u32,
+    #[serde(default)]
+    unlocked_melodies: Vec<String>,
+    #[serde(default)]
+    unlocked_harmonies: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HarmonyResonance {
+    creative: f64,
+    exploration: f64,
+    restoration: f64,
+}
+
+/// The parts of world-engine's region state a quest is written around.
+#[derive(Debug, Deserialize)]
+struct RegionConditions {
+    harmony_level: f64,
+    discord_level: f64,
+    terrain_type: serde_json::Value,
+    weather: serde_json::Value,
+}
+
+/// Everything gathered about the player before asking for their quest.
+struct QuestSubject {
+    progress: HarmonyProgress,
+    region: Option<RegionConditions>,
+}
+
+impl QuestSubject {
+    fn total_resonance(&self) -> u64 {
+        let resonance = &self.progress.resonance;
+        (resonance.creative + resonance.exploration + resonance.restoration) as u64
+    }
+
+    fn prompt(&self) -> AiQuestPrompt {
+        let progress = &self.progress;
+        let melodies = match progress.unlocked_melodies.as_slice() {
+            [] => "no melodies yet".to_string(),
+            melodies => format!("the melodies {}", melodies.join(", ")),
+        };
+        let player_context = format!(
+            "A Songweaver at attunement tier {} with creative {:.0}, exploration {:.0} and restoration {:.0} resonance, who knows {}",
+            progress.attunement_tier,
+            progress.resonance.creative,
+            progress.resonance.exploration,
+            progress.resonance.restoration,
+            melodies,
+        );
+        let world_state = match &self.region {
+            Some(region) => format!(
+                "A {} region, harmony {:.0}% and discord {:.0}%, weather {}",
+                plain(&region.terrain_type),
+                region.harmony_level * 100.0,
+                region.discord_level * 100.0,
+                plain(&region.weather),
+            ),
+            None => "Somewhere in the wilds of Terra Nova".to_string(),
+        };
+        AiQuestPrompt {
+            player_context,
+            world_state,
+            harmony_level: self.region.as_ref().map(|region| region.harmony_level as f32),
+        }
+    }
+}
+
+/// A JSON value as prose: strings bare, anything else compact.
+fn plain(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Fetch from a service that answers unknown ids with `{"error": ...}`
+/// rather than a status, reading that answer as `None`.
+async fn lookup<T: for<'de> Deserialize<'de>>(
+    services: &ServiceClient,
+    service: &str,
+    path: &str,
+) -> Result<Option<T>, FinalverseError> {
+    let value: serde_json::Value = services
+        .get_json(service, path)
+        .await
+        .map_err(|e| FinalverseError::upstream(service, e))?;
+    if value.get("error").is_some() {
+        return Ok(None);
+    }
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(|e| FinalverseError::upstream(service, e))
+}
+
+/// Writes personal quests and keeps each player's in Redis, newest last,
+/// scoped to the tenant this process serves.
+pub struct PersonalQuests {
+    engine: QuestGenerationEngine,
+    services: ServiceClient,
+    /// Keyed by player id.
+    limiter: RateLimiter,
+    client: redis::Client,
+    /// Made on first use so story-engine starts without Redis.
+    connection: OnceCell<ConnectionManager>,
+    tenant: TenantId,
+}
+
+impl PersonalQuests {
+    pub fn new(services: ServiceClient, client: redis::Client, tenant: TenantId) -> Self {
+        Self {
+            engine: QuestGenerationEngine::new(services.clone()),
+            services,
+            limiter: RateLimiter::new(QUESTS_PER_MINUTE, QUEST_BURST),
+            client,
+            connection: OnceCell::new(),
+            tenant,
+        }
+    }
+
+    fn key(&self, player_id: &str) -> String {
+        self.tenant.scoped_key(&format!("quests:{}", player_id))
+    }
+
+    async fn connection(&self) -> Result<ConnectionManager, FinalverseError> {
+        let connection = self
+            .connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await
+            .map_err(|e| FinalverseError::Unavailable(format!("Quest log: {}", e)))?;
+        Ok(connection.clone())
+    }
+
+    /// Write, keep and return a new quest for `player_id`, set in
+    /// `region_id` when given. Players harmony-service hasn't seen yet get
+    /// a beginner's quest. Each player may only have so many written a
+    /// minute.
+    pub async fn generate(&self, player_id: Uuid, region_id: Option<RegionId>) -> Result<DynamicQuest, FinalverseError> {
+        if let Err(retry_after) = self.limiter.check(&player_id.to_string()) {
+            return Err(FinalverseError::RateLimited {
+                message: "Too many quests requested; finish one first".to_string(),
+                retry_after_secs: Some(retry_after.as_secs().max(1)),
+            });
+        }
+        let progress = lookup(&self.services, "harmony-service", &format!("/progress/{}", player_id))
+            .await?
+            .unwrap_or_default();
+        let region = match &region_id {
+            Some(region_id) => lookup(&self.services, "world-engine", &format!("/region/{}", region_id.0)).await?,
+            None => None,
+        };
+        let subject = QuestSubject { progress, region };
+
+        let context = GenerationContext {
+            prompt: subject.prompt(),
+            region_id,
+            total_resonance: subject.total_resonance(),
+        };
+        let quest = self.engine.generate_quest(&context).await?;
+        self.save(&player_id.to_string(), &quest).await?;
+        Ok(quest)
+    }
+
+    /// Append `quest` to the player's log, dropping the oldest beyond
+    /// [`MAX_KEPT_QUESTS`].
+    async fn save(&self, player_id: &str, quest: &DynamicQuest) -> Result<(), FinalverseError> {
+        let json = serde_json::to_string(quest)?;
+        let key = self.key(player_id);
+        let mut connection = self.connection().await?;
+        redis::pipe()
+            .atomic()
+            .rpush(&key, json)
+            .ignore()
+            .ltrim(&key, -MAX_KEPT_QUESTS, -1)
+            .ignore()
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .map_err(|e| FinalverseError::Unavailable(format!("Quest log: {}", e)))
+    }
+
+    /// What triggers may check about `player_id`: their harmony-service
+    /// progress, with the melodies and harmonies they've unlocked as tags,
+    /// and the personal quests they've completed.
+    pub async fn player_facts(&self, player_id: Uuid) -> Result<PlayerFacts, FinalverseError> {
+        let progress: HarmonyProgress = lookup(&self.services, "harmony-service", &format!("/progress/{}", player_id))
+            .await?
+            .unwrap_or_default();
+        let completed_quests = self
+            .list(&player_id.to_string())
+            .await?
+            .into_iter()
+            .filter(|quest| matches!(quest.state, QuestState::Completed { .. }))
+            .map(|quest| quest.id.to_string())
+            .collect();
+        let resonance = &progress.resonance;
+        Ok(PlayerFacts {
+            attunement_tier: progress.attunement_tier,
+            total_resonance: resonance.creative + resonance.exploration + resonance.restoration,
+            tags: progress.unlocked_melodies.into_iter().chain(progress.unlocked_harmonies).collect(),
+            completed_quests,
+        })
+    }
+
+    /// Every quest written for `player_id`, oldest first.
+    pub async fn list(&self, player_id: &str) -> Result<Vec<DynamicQuest>, FinalverseError> {
+        let mut connection = self.connection().await?;
+        let stored: Vec<String> = connection
+            .lrange(self.key(player_id), 0, -1)
+            .await
+            .map_err(|e| FinalverseError::Unavailable(format!("Quest log: {}", e)))?;
+        Ok(stored
+            .iter()
+            .filter_map(|json| match serde_json::from_str(json) {
+                Ok(quest) => Some(quest),
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable quest for {}: {}", player_id, e);
+                    None
+                }
+            })
+            .collect())
+    }
+}
@@ -1,8 +1,9 @@
 // services/story-engine/src/quest_system.rs
-// Dynamic quest generation and management system
+// Personal quests written by ai-orchestra and turned into objectives
 
 use finalverse_core::*;
 use serde::{Deserialize, Serialize};
+use service_registry::ServiceClient;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -23,15 +24,7 @@ pub struct DynamicQuest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QuestType {
-    // Solo quests
     Personal { narrative_weight: f32 },
-    Discovery { exploration_focus: bool },
-    Restoration { harmony_requirement: f32 },
-    
-    // Group quests
-    Community { min_participants: u32, max_participants: u32 },
-    Symphony { required_echoes: Vec<EchoType> },
-    WorldEvent { affected_regions: Vec<RegionId> },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,25 +40,16 @@ pub struct DynamicObjective {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ObjectiveType {
     // Location-based
-    ReachLocation { coordinates: Coordinates, radius: f32 },
     ExploreArea { region_id: RegionId, coverage_percent: f32 },
-    
+
     // Interaction-based
     InteractWithEcho { echo_type: EchoType, min_bond_level: u32 },
     TalkToNPC { npc_id: String, dialogue_branch: Option<String> },
-    
+
     // Action-based
-    PerformMelody { melody_type: Option<String>, location: Option<Coordinates> },
+    PerformMelody { melody_type: Option<String> },
     RestoreHarmony { region_id: RegionId, target_level: f32 },
     CollectItems { item_type: String, quantity: u32 },
-    
-    // Social
-    GatherPlayers { count: u32, location: Option<Coordinates> },
-    TeachMelody { student_count: u32 },
-    
-    // Time-based
-    SurviveTime { duration_seconds: u64, conditions: Vec<String> },
-    CompleteBeforeTime { deadline: chrono::DateTime<chrono::Utc> },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,11 +101,7 @@ pub struct QuestContext {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QuestGenerator {
-    System { template_id: String },
     AI { prompt_hash: String, model: String },
-    Player { creator_id: PlayerId },
-    Echo { echo_type: EchoType },
-    WorldEvent { event_id: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,239 +121,60 @@ pub enum CompletionStyle {
     Collaborative, // Completed with help
 }
 
-// Quest generation system
+/// Asks ai-orchestra for quest narratives.
 pub struct QuestGenerationEngine {
-    templates: HashMap<String, QuestTemplate>,
-    ai_service_url: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct QuestTemplate {
-    id: String,
-    name: String,
-    base_description: String,
-    objective_templates: Vec<ObjectiveTemplate>,
-    variable_elements: Vec<VariableElement>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ObjectiveTemplate {
-    description_template: String,
-    objective_type: String,
-    variables: HashMap<String, serde_json::Value>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct VariableElement {
-    name: String,
-    options: Vec<String>,
-    affects: Vec<String>,
+    services: ServiceClient,
 }
 
 impl QuestGenerationEngine {
-    pub fn new(ai_service_url: String) -> Self {
-        let mut templates = HashMap::new();
-        
-        // Add some basic templates
-        templates.insert("restoration_basic".to_string(), QuestTemplate {
-            id: "restoration_basic".to_string(),
-            name: "Basic Restoration Quest".to_string(),
-            base_description: "Help restore harmony to a troubled area".to_string(),
-            objective_templates: vec![
-                ObjectiveTemplate {
-                    description_template: "Travel to the affected area".to_string(),
-                    objective_type: "reach_location".to_string(),
-                    variables: HashMap::new(),
-                },
-                ObjectiveTemplate {
-                    description_template: "Perform healing melodies to restore harmony".to_string(),
-                    objective_type: "perform_melody".to_string(),
-                    variables: HashMap::new(),
-                },
-            ],
-            variable_elements: vec![
-                VariableElement {
-                    name: "location".to_string(),
-                    options: vec!["grove".to_string(), "lake".to_string(), "ruins".to_string()],
-                    affects: vec!["description".to_string(), "objectives".to_string()],
-                },
-            ],
-        });
-        
-        Self {
-            templates,
-            ai_service_url,
-        }
+    pub fn new(services: ServiceClient) -> Self {
+        Self { services }
     }
-    
-    pub async fn generate_quest(
-        &self,
-        player_profile: &PlayerProfile,
-        context: &GenerationContext,
-    ) -> Result<DynamicQuest, String> {
-        match context.generation_type {
-            GenerationType::Template { template_id } => {
-                self.generate_from_template(template_id, player_profile, context).await
-            }
-            GenerationType::AI { parameters } => {
-                self.generate_with_ai(player_profile, context, parameters).await
-            }
-            GenerationType::Emergent { world_state } => {
-                self.generate_emergent_quest(player_profile, world_state).await
-            }
-        }
-    }
-    
-    async fn generate_from_template(
-        &self,
-        template_id: &str,
-        player_profile: &PlayerProfile,
-        context: &GenerationContext,
-    ) -> Result<DynamicQuest, String> {
-        let template = self.templates.get(template_id)
-            .ok_or_else(|| format!("Template {} not found", template_id))?;
-        
-        // Apply variations based on player profile
-        let difficulty_modifier = calculate_difficulty_modifier(player_profile);
-        
-        let quest = DynamicQuest {
-            id: Uuid::new_v4(),
-            title: self.generate_title(&template.name, context),
-            description: self.customize_description(&template.base_description, context),
-            quest_type: QuestType::Personal { narrative_weight: 0.7 },
-            objectives: self.generate_objectives(&template.objective_templates, context),
-            prerequisites: self.calculate_prerequisites(player_profile),
-            rewards: self.calculate_rewards(player_profile, difficulty_modifier),
-            context: QuestContext {
-                generated_by: QuestGenerator::System { template_id: template_id.to_string() },
-                narrative_tags: vec!["restoration".to_string(), "harmony".to_string()],
-                difficulty_rating: difficulty_modifier,
-                estimated_duration: 30,
-            },
-            state: QuestState::Available,
-            created_at: chrono::Utc::now(),
-            expires_at: None,
-        };
-        
-        Ok(quest)
-    }
-    
-    async fn generate_with_ai(
-        &self,
-        player_profile: &PlayerProfile,
-        context: &GenerationContext,
-        parameters: &HashMap<String, serde_json::Value>,
-    ) -> Result<DynamicQuest, String> {
-        // Call AI service to generate quest
-        let client = reqwest::Client::new();
-        
-        let ai_request = serde_json::json!({
-            "context": {
-                "player_level": player_profile.total_resonance(),
+
+    pub async fn generate_quest(&self, context: &GenerationContext) -> Result<DynamicQuest> {
+        let prompt = &context.prompt;
+        let request = serde_json::json!({
+            "player_context": prompt.player_context,
+            "world_state": prompt.world_state,
+            "quest_type": "personal",
+            "world": {
+                "harmony_level": prompt.harmony_level,
                 "region": context.region_id,
-                "recent_quests": player_profile.recent_quest_types(),
-                "preferred_play_style": player_profile.play_style,
             },
-            "parameters": parameters,
         });
-        
-        let response = client
-            .post(&format!("{}/quest/generate", self.ai_service_url))
-            .json(&ai_request)
-            .send()
+        let generated: AiQuestResponse = self
+            .services
+            .post_json("ai-orchestra", "/api/quest", &request)
             .await
-            .map_err(|e| format!("AI service error: {}", e))?;
-        
-        if !response.status().is_success() {
-            return Err("Failed to generate quest with AI".to_string());
-        }
-        
-        let ai_response: serde_json::Value = response.json().await
-            .map_err(|e| format!("Failed to parse AI response: {}", e))?;
-        
-        // Convert AI response to quest
-        self.parse_ai_quest(ai_response, player_profile)
-    }
-    
-    async fn generate_emergent_quest(
-        &self,
-        player_profile: &PlayerProfile,
-        world_state: &WorldState,
-    ) -> Result<DynamicQuest, String> {
-        // Analyze world state for emergent opportunities
-        let opportunities = self.analyze_world_state(world_state);
-        
-        if let Some(opportunity) = opportunities.first() {
-            // Create quest based on world conditions
-            let quest = match opportunity {
-                EmergentOpportunity::HarmonyCrisis { region_id, severity } => {
-                    self.create_crisis_quest(region_id, *severity, player_profile)
-                }
-                EmergentOpportunity::PlayerGathering { location, count } => {
-                    self.create_social_quest(location, *count)
-                }
-                EmergentOpportunity::EchoNeed { echo_type, need } => {
-                    self.create_echo_quest(echo_type, need)
-                }
-            };
-            
-            Ok(quest)
-        } else {
-            // Fallback to template generation
-            self.generate_from_template("restoration_basic", player_profile, &GenerationContext::default()).await
-        }
-    }
-    
-    // Helper methods
-    fn generate_title(&self, base_title: &str, context: &GenerationContext) -> String {
-        format!("{} - {}", base_title, context.region_name.as_deref().unwrap_or("Unknown Lands"))
-    }
-    
-    fn customize_description(&self, base_desc: &str, context: &GenerationContext) -> String {
-        // Add contextual details to description
-        format!("{} The {} calls for your aid.", base_desc, context.region_name.as_deref().unwrap_or("region"))
-    }
-    
-    fn generate_objectives(
-        &self,
-        templates: &[ObjectiveTemplate],
-        context: &GenerationContext,
-    ) -> Vec<DynamicObjective> {
-        templates.iter().map(|template| {
-            DynamicObjective {
-                id: Uuid::new_v4(),
-                description: template.description_template.clone(),
-                objective_type: self.parse_objective_type(&template.objective_type, &template.variables),
-                progress: ObjectiveProgress::NotStarted,
-                hidden: false,
-                optional: false,
-            }
-        }).collect()
+            .map_err(|e| FinalverseError::upstream("ai-orchestra", e))?;
+
+        Ok(quest_from_narrative(&generated, context))
     }
-    
-    fn parse_objective_type(
-        &self,
-        type_str: &str,
-        variables: &HashMap<String, serde_json::Value>,
-    ) -> ObjectiveType {
-        match type_str {
-            "reach_location" => ObjectiveType::ReachLocation {
-                coordinates: Coordinates { x: 100.0, y: 50.0, z: 200.0 },
-                radius: 50.0,
-            },
-            "perform_melody" => ObjectiveType::PerformMelody {
-                melody_type: variables.get("melody_type").and_then(|v| v.as_str()).map(String::from),
-                location: None,
-            },
-            _ => ObjectiveType::RestoreHarmony {
-                region_id: RegionId(Uuid::new_v4()),
-                target_level: 75.0,
-            },
-        }
+}
+
+/// A playable quest from an AI-written narrative: its heading becomes the
+/// title, its list items the objectives and the rest the description.
+fn quest_from_narrative(generated: &AiQuestResponse, context: &GenerationContext) -> DynamicQuest {
+    let parsed = ParsedNarrative::parse(&generated.quest_narrative);
+    let mut objectives: Vec<DynamicObjective> = parsed
+        .steps
+        .iter()
+        .take(MAX_NARRATIVE_OBJECTIVES)
+        .map(|step| objective_from_step(step, context.region_id.as_ref()))
+        .collect();
+    if objectives.iter().all(|objective| objective.optional) {
+        // Every quest needs something to do; fall back to the story's gist
+        objectives.insert(0, objective_from_step(&parsed.description, context.region_id.as_ref()));
     }
-    
-    fn calculate_prerequisites(&self, player_profile: &PlayerProfile) -> QuestPrerequisites {
-        QuestPrerequisites {
+    let difficulty = difficulty_modifier(context.total_resonance).max(0.5);
+
+    DynamicQuest {
+        id: Uuid::new_v4(),
+        title: parsed.title.unwrap_or_else(|| "A Personal Quest".to_string()),
+        description: parsed.description,
+        quest_type: QuestType::Personal { narrative_weight: 0.8 },
+        objectives,
+        prerequisites: QuestPrerequisites {
             min_resonance: Some(Resonance {
                 creative: 10,
                 exploration: 10,
@@ -382,515 +183,262 @@ impl QuestGenerationEngine {
             required_quests: vec![],
             required_echo_bonds: HashMap::new(),
             region_harmony: None,
-        }
-    }
-    
-    fn calculate_rewards(
-        &self,
-        player_profile: &PlayerProfile,
-        difficulty: f32,
-    ) -> QuestRewards {
-        let base_reward = 10.0 * difficulty;
-        
-        QuestRewards {
-            resonance: Resonance {
-                creative: (base_reward * 1.5) as u64,
-                exploration: (base_reward * 1.0) as u64,
-                restoration: (base_reward * 2.0) as u64,
-            },
-            items: vec![],
-            unlocks: vec![],
-            narrative_impact: NarrativeImpact {
-                world_state_changes: HashMap::new(),
-                relationship_changes: HashMap::new(),
-                legend_entry: Some("Restored harmony to a troubled land".to_string()),
-            },
-        }
-    }
-    
-    fn parse_ai_quest(
-        &self,
-        ai_response: serde_json::Value,
-        player_profile: &PlayerProfile,
-    ) -> Result<DynamicQuest, String> {
-        // Parse AI-generated quest data
-        let quest_data = ai_response.get("quest")
-            .ok_or("No quest data in AI response")?;
-        
-        Ok(DynamicQuest {
-            id: Uuid::new_v4(),
-            title: quest_data.get("title")
-                .and_then(|v| v.as_str())
-                .unwrap_or("AI Generated Quest")
-                .to_string(),
-            description: quest_data.get("description")
-                .and_then(|v| v.as_str())
-                .unwrap_or("A mysterious quest awaits")
-                .to_string(),
-            quest_type: QuestType::Personal { narrative_weight: 0.8 },
-            objectives: vec![], // TODO: Parse objectives from AI
-            prerequisites: self.calculate_prerequisites(player_profile),
-            rewards: self.calculate_rewards(player_profile, 1.0),
-            context: QuestContext {
-                generated_by: QuestGenerator::AI {
-                    prompt_hash: "ai_generated".to_string(),
-                    model: ai_response.get("model_used")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown")
-                        .to_string(),
-                },
-                narrative_tags: vec!["ai_generated".to_string()],
-                difficulty_rating: 1.0,
-                estimated_duration: 45,
-            },
-            state: QuestState::Available,
-            created_at: chrono::Utc::now(),
-            expires_at: None,
-        })
-    }
-    
-    fn analyze_world_state(&self, world_state: &WorldState) -> Vec<EmergentOpportunity> {
-        let mut opportunities = Vec::new();
-        
-        // Check for harmony crises
-        for (region_id, harmony) in &world_state.region_harmonies {
-            if *harmony < 30.0 {
-                opportunities.push(EmergentOpportunity::HarmonyCrisis {
-                    region_id: region_id.clone(),
-                    severity: calculate_crisis_severity(*harmony),
-                });
-            }
-        }
-        
-        // Check for player gatherings
-        for (location, players) in &world_state.player_concentrations {
-            if players.len() >= 5 {
-                opportunities.push(EmergentOpportunity::PlayerGathering {
-                    location: location.clone(),
-                    count: players.len() as u32,
-                });
-            }
-        }
-        
-        // Check for Echo needs
-        for (echo_type, state) in &world_state.echo_states {
-            if let Some(need) = self.analyze_echo_state(state) {
-                opportunities.push(EmergentOpportunity::EchoNeed {
-                    echo_type: echo_type.clone(),
-                    need,
-                });
-            }
-        }
-        
-        opportunities
-    }
-    
-    fn analyze_echo_state(&self, state: &EchoState) -> Option<String> {
-        if state.energy < 20.0 {
-            Some("low_energy".to_string())
-        } else if state.loneliness > 80.0 {
-            Some("needs_companionship".to_string())
-        } else if state.unfulfilled_requests > 3 {
-            Some("has_urgent_request".to_string())
-        } else {
-            None
-        }
-    }
-    
-    fn create_crisis_quest(
-        &self,
-        region_id: &RegionId,
-        severity: f32,
-        player_profile: &PlayerProfile,
-    ) -> DynamicQuest {
-        let urgency = if severity > 0.8 { "Critical" } else if severity > 0.5 { "Urgent" } else { "Important" };
-        
-        DynamicQuest {
-            id: Uuid::new_v4(),
-            title: format!("{} Harmony Crisis", urgency),
-            description: format!(
-                "The harmony in this region has fallen dangerously low. \
-                The Silence threatens to consume everything if action isn't taken immediately."
-            ),
-            quest_type: QuestType::Restoration {
-                harmony_requirement: 60.0,
-            },
-            objectives: vec![
-                DynamicObjective {
-                    id: Uuid::new_v4(),
-                    description: "Rush to the affected region".to_string(),
-                    objective_type: ObjectiveType::ReachLocation {
-                        coordinates: Coordinates { x: 0.0, y: 0.0, z: 0.0 }, // Would be set from region
-                        radius: 100.0,
-                    },
-                    progress: ObjectiveProgress::NotStarted,
-                    hidden: false,
-                    optional: false,
-                },
-                DynamicObjective {
-                    id: Uuid::new_v4(),
-                    description: "Perform restoration melodies to push back the Silence".to_string(),
-                    objective_type: ObjectiveType::RestoreHarmony {
-                        region_id: region_id.clone(),
-                        target_level: 60.0,
-                    },
-                    progress: ObjectiveProgress::NotStarted,
-                    hidden: false,
-                    optional: false,
-                },
-                DynamicObjective {
-                    id: Uuid::new_v4(),
-                    description: "Rally other Songweavers to help (Optional)".to_string(),
-                    objective_type: ObjectiveType::GatherPlayers {
-                        count: 3,
-                        location: None,
-                    },
-                    progress: ObjectiveProgress::NotStarted,
-                    hidden: false,
-                    optional: true,
-                },
-            ],
-            prerequisites: QuestPrerequisites {
-                min_resonance: None, // Emergency - no prerequisites
-                required_quests: vec![],
-                required_echo_bonds: HashMap::new(),
-                region_harmony: None,
-            },
-            rewards: QuestRewards {
-                resonance: Resonance {
-                    creative: (30.0 * severity) as u64,
-                    exploration: (20.0 * severity) as u64,
-                    restoration: (50.0 * severity) as u64,
-                },
-                items: vec!["Harmony Shard".to_string()],
-                unlocks: vec![],
-                narrative_impact: NarrativeImpact {
-                    world_state_changes: {
-                        let mut changes = HashMap::new();
-                        changes.insert("saved_region".to_string(), serde_json::json!(region_id));
-                        changes
-                    },
-                    relationship_changes: {
-                        let mut changes = HashMap::new();
-                        changes.insert("region_inhabitants".to_string(), 50);
-                        changes
-                    },
-                    legend_entry: Some(format!("Saved a region from the {} crisis", urgency.to_lowercase())),
-                },
-            },
-            context: QuestContext {
-                generated_by: QuestGenerator::WorldEvent {
-                    event_id: format!("harmony_crisis_{}", region_id.0),
-                },
-                narrative_tags: vec!["emergency".to_string(), "restoration".to_string(), "crisis".to_string()],
-                difficulty_rating: severity,
-                estimated_duration: (20.0 * severity) as u64,
-            },
-            state: QuestState::Available,
-            created_at: chrono::Utc::now(),
-            expires_at: Some(chrono::Utc::now() + chrono::Duration::hours(2)), // Urgent!
-        }
-    }
-    
-    fn create_social_quest(
-        &self,
-        location: &Coordinates,
-        player_count: u32,
-    ) -> DynamicQuest {
-        DynamicQuest {
-            id: Uuid::new_v4(),
-            title: "Convergence of Songweavers".to_string(),
-            description: format!(
-                "A gathering of {} Songweavers has formed. \
-                This is a rare opportunity to perform a group harmony that could strengthen the entire region.",
-                player_count
-            ),
-            quest_type: QuestType::Community {
-                min_participants: 3,
-                max_participants: player_count + 5,
-            },
-            objectives: vec![
-                DynamicObjective {
-                    id: Uuid::new_v4(),
-                    description: "Join the gathering of Songweavers".to_string(),
-                    objective_type: ObjectiveType::ReachLocation {
-                        coordinates: location.clone(),
-                        radius: 50.0,
-                    },
-                    progress: ObjectiveProgress::NotStarted,
-                    hidden: false,
-                    optional: false,
-                },
-                DynamicObjective {
-                    id: Uuid::new_v4(),
-                    description: "Synchronize your melody with other players".to_string(),
-                    objective_type: ObjectiveType::PerformMelody {
-                        melody_type: Some("harmony".to_string()),
-                        location: Some(location.clone()),
-                    },
-                    progress: ObjectiveProgress::NotStarted,
-                    hidden: false,
-                    optional: false,
-                },
-            ],
-            prerequisites: QuestPrerequisites {
-                min_resonance: Some(Resonance {
-                    creative: 20,
-                    exploration: 20,
-                    restoration: 20,
-                }),
-                required_quests: vec![],
-                required_echo_bonds: HashMap::new(),
-                region_harmony: None,
-            },
-            rewards: QuestRewards {
-                resonance: Resonance {
-                    creative: 40,
-                    exploration: 40,
-                    restoration: 40,
-                },
-                items: vec!["Harmony Amplifier".to_string()],
-                unlocks: vec![QuestUnlock::NewMelody {
-                    melody_id: "group_harmony".to_string(),
-                }],
-                narrative_impact: NarrativeImpact {
-                    world_state_changes: HashMap::new(),
-                    relationship_changes: {
-                        let mut changes = HashMap::new();
-                        changes.insert("songweaver_community".to_string(), 25);
-                        changes
-                    },
-                    legend_entry: Some("Participated in a grand convergence of Songweavers".to_string()),
-                },
-            },
-            context: QuestContext {
-                generated_by: QuestGenerator::WorldEvent {
-                    event_id: "player_convergence".to_string(),
-                },
-                narrative_tags: vec!["social".to_string(), "harmony".to_string(), "community".to_string()],
-                difficulty_rating: 0.5,
-                estimated_duration: 30,
+        },
+        rewards: rewards(difficulty),
+        context: QuestContext {
+            generated_by: QuestGenerator::AI {
+                prompt_hash: generated.quest_id.clone(),
+                model: "ai-orchestra".to_string(),
             },
-            state: QuestState::Available,
-            created_at: chrono::Utc::now(),
-            expires_at: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
-        }
+            narrative_tags: vec!["ai_generated".to_string(), "personal".to_string()],
+            difficulty_rating: difficulty,
+            estimated_duration: generated.estimated_duration as u64,
+        },
+        state: QuestState::Available,
+        created_at: chrono::Utc::now(),
+        expires_at: None,
     }
-    
-    fn create_echo_quest(
-        &self,
-        echo_type: &EchoType,
-        need: &str,
-    ) -> DynamicQuest {
-        let (title, description, objective) = match (echo_type, need) {
-            (EchoType::Lumi, "low_energy") => (
-                "Lumi's Fading Light".to_string(),
-                "Lumi's hopeful glow is dimming. She needs the energy of discovery to reignite her spark.".to_string(),
-                ObjectiveType::PerformMelody {
-                    melody_type: Some("discovery".to_string()),
-                    location: None,
-                }
-            ),
-            (EchoType::KAI, "needs_companionship") => (
-                "KAI's Logical Loneliness".to_string(),
-                "KAI has been processing alone for too long. Engage in meaningful interaction to ease their isolation.".to_string(),
-                ObjectiveType::InteractWithEcho {
-                    echo_type: EchoType::KAI,
-                    min_bond_level: 30,
-                }
-            ),
-            (EchoType::Terra, "has_urgent_request") => (
-                "Terra's Call of the Wild".to_string(),
-                "Terra senses a disturbance in the natural order and needs help investigating.".to_string(),
-                ObjectiveType::ExploreArea {
-                    region_id: RegionId(Uuid::new_v4()),
-                    coverage_percent: 0.7,
-                }
-            ),
-            (EchoType::Ignis, _) => (
-                "Ignis's Challenge".to_string(),
-                "Ignis seeks a worthy Songweaver to tests in the fires of courage.".to_string(),
-                ObjectiveType::SurviveTime {
-                    duration_seconds: 300,
-                    conditions: vec!["combat_trial".to_string()],
-                }
-            ),
-            _ => (
-                format!("{:?}'s Request", echo_type),
-                "An Echo needs your assistance.".to_string(),
-                ObjectiveType::InteractWithEcho {
-                    echo_type: echo_type.clone(),
-                    min_bond_level: 20,
-                }
-            ),
-        };
-        
-        DynamicQuest {
-            id: Uuid::new_v4(),
-            title,
-            description,
-            quest_type: QuestType::Personal {
-                narrative_weight: 0.9,
-            },
-            objectives: vec![
-                DynamicObjective {
-                    id: Uuid::new_v4(),
-                    description: "Respond to the Echo's call".to_string(),
-                    objective_type: objective,
-                    progress: ObjectiveProgress::NotStarted,
-                    hidden: false,
-                    optional: false,
-                },
-            ],
-            prerequisites: QuestPrerequisites {
-                min_resonance: None,
-                required_quests: vec![],
-                required_echo_bonds: {
-                    let mut bonds = HashMap::new();
-                    bonds.insert(echo_type.clone(), 10);
-                    bonds
-                },
-                region_harmony: None,
-            },
-            rewards: QuestRewards {
-                resonance: Resonance {
-                    creative: 30,
-                    exploration: 30,
-                    restoration: 30,
-                },
-                items: vec![],
-                unlocks: vec![QuestUnlock::EchoAbility {
-                    echo_type: echo_type.clone(),
-                    ability: "special_bond".to_string(),
-                }],
-                narrative_impact: NarrativeImpact {
-                    world_state_changes: HashMap::new(),
-                    relationship_changes: {
-                        let mut changes = HashMap::new();
-                        changes.insert(format!("{:?}", echo_type), 30);
-                        changes
-                    },
-                    legend_entry: Some(format!("Answered {:?}'s call in their time of need", echo_type)),
-                },
-            },
-            context: QuestContext {
-                generated_by: QuestGenerator::Echo {
-                    echo_type: echo_type.clone(),
-                },
-                narrative_tags: vec!["echo".to_string(), "personal".to_string(), "bond".to_string()],
-                difficulty_rating: 0.7,
-                estimated_duration: 25,
-            },
-            state: QuestState::Available,
-            created_at: chrono::Utc::now(),
-            expires_at: None,
-        }
+}
+
+fn rewards(difficulty: f32) -> QuestRewards {
+    let base_reward = 10.0 * difficulty;
+
+    QuestRewards {
+        resonance: Resonance {
+            creative: (base_reward * 1.5) as u64,
+            exploration: (base_reward * 1.0) as u64,
+            restoration: (base_reward * 2.0) as u64,
+        },
+        items: vec![],
+        unlocks: vec![],
+        narrative_impact: NarrativeImpact {
+            world_state_changes: HashMap::new(),
+            relationship_changes: HashMap::new(),
+            legend_entry: Some("Restored harmony to a troubled land".to_string()),
+        },
     }
 }
 
-// Supporting types and structures
+/// What a quest is written from.
 #[derive(Debug, Clone)]
-pub struct PlayerProfile {
-    pub player_id: PlayerId,
-    pub total_resonance: Resonance,
-    pub completed_quests: Vec<Uuid>,
-    pub play_style: PlayStyle,
-    pub preferred_content: Vec<String>,
+pub struct GenerationContext {
+    pub prompt: AiQuestPrompt,
+    pub region_id: Option<RegionId>,
+    /// The player's resonance across all three paths, which sets difficulty.
+    pub total_resonance: u64,
 }
 
-impl PlayerProfile {
-    pub fn total_resonance(&self) -> u64 {
-        self.total_resonance.creative + self.total_resonance.exploration + self.total_resonance.restoration
-    }
-    
-    pub fn recent_quest_types(&self) -> Vec<String> {
-        // Analyze recent quests to avoid repetition
-        vec!["restoration".to_string(), "exploration".to_string()]
-    }
+/// What ai-orchestra is told about the player and their surroundings.
+#[derive(Debug, Clone)]
+pub struct AiQuestPrompt {
+    pub player_context: String,
+    pub world_state: String,
+    /// From 0 to 1.
+    pub harmony_level: Option<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum PlayStyle {
-    Explorer,
-    Socializer,
-    Achiever,
-    Creator,
-    Mixed,
+/// ai-orchestra's answer from `/api/quest`.
+#[derive(Debug, Clone, Deserialize)]
+struct AiQuestResponse {
+    quest_narrative: String,
+    quest_id: String,
+    estimated_duration: u32,
 }
 
-#[derive(Debug, Clone)]
-pub struct GenerationContext {
-    pub generation_type: GenerationType,
-    pub region_id: Option<RegionId>,
-    pub region_name: Option<String>,
-    pub world_events: Vec<String>,
+/// Objectives kept from one narrative; longer lists are usually the model
+/// retelling the story step by step.
+const MAX_NARRATIVE_OBJECTIVES: usize = 5;
+
+/// A narrative split into the parts a quest is made of.
+#[derive(Debug, Default)]
+struct ParsedNarrative {
+    title: Option<String>,
+    description: String,
+    /// List items, in order.
+    steps: Vec<String>,
 }
 
-impl Default for GenerationContext {
-    fn default() -> Self {
-        Self {
-            generation_type: GenerationType::Template {
-                template_id: "restoration_basic".to_string(),
-            },
-            region_id: None,
-            region_name: None,
-            world_events: vec![],
+impl ParsedNarrative {
+    fn parse(narrative: &str) -> Self {
+        let mut parsed = ParsedNarrative::default();
+        let mut paragraphs: Vec<&str> = Vec::new();
+        for line in narrative.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(step) = list_item(line) {
+                parsed.steps.push(step.to_string());
+            } else if let Some(title) = heading(line) {
+                if parsed.title.is_none() {
+                    parsed.title = Some(title.to_string());
+                }
+            } else if !line.starts_with('#') && !line.ends_with(':') {
+                // Empty headings and section labels aren't story
+                paragraphs.push(line);
+            }
         }
+        parsed.description = paragraphs.join(" ");
+        parsed
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum GenerationType {
-    Template { template_id: String },
-    AI { parameters: HashMap<String, serde_json::Value> },
-    Emergent { world_state: WorldState },
+/// The text of a bulleted or numbered line.
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix(['-', '*', '•']) {
+        return Some(rest.trim());
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(['.', ')']).map(str::trim)
 }
 
-#[derive(Debug, Clone)]
-pub struct WorldState {
-    pub region_harmonies: HashMap<RegionId, f32>,
-    pub player_concentrations: HashMap<Coordinates, Vec<PlayerId>>,
-    pub echo_states: HashMap<EchoType, EchoState>,
-    pub active_events: Vec<WorldEvent>,
+/// The text of a markdown heading or `Title:` line.
+fn heading(line: &str) -> Option<&str> {
+    let title = if line.starts_with('#') {
+        line.trim_start_matches('#')
+    } else {
+        line.strip_prefix("Title:").or_else(|| line.strip_prefix("Quest:"))?
+    };
+    Some(title.trim().trim_matches(['*', '"']).trim()).filter(|title| !title.is_empty())
 }
 
-#[derive(Debug, Clone)]
-pub struct EchoState {
-    pub energy: f32,
-    pub loneliness: f32,
-    pub unfulfilled_requests: u32,
-    pub last_interaction: chrono::DateTime<chrono::Utc>,
-}
+/// The objective a narrative step describes, judged by the words in it.
+fn objective_from_step(step: &str, region_id: Option<&RegionId>) -> DynamicObjective {
+    let text = step.trim_matches('*').trim();
+    let lower = text.to_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|word| lower.contains(word));
+    let echo = [("lumi", EchoType::Lumi), ("kai", EchoType::KAI), ("terra", EchoType::Terra), ("ignis", EchoType::Ignis)]
+        .into_iter()
+        .find(|(name, _)| lower.split(|c: char| !c.is_alphanumeric()).any(|word| word == *name))
+        .map(|(_, echo_type)| echo_type);
+    let quantity = lower
+        .split_whitespace()
+        .zip(lower.split_whitespace().skip(1))
+        .find_map(|(count, item)| Some((count.parse::<u32>().ok()?, item.trim_matches(|c: char| !c.is_alphanumeric()))));
 
-#[derive(Debug, Clone)]
-pub struct WorldEvent {
-    pub event_id: String,
-    pub event_type: String,
-    pub affected_regions: Vec<RegionId>,
-    pub severity: f32,
-}
+    let objective_type = match (echo, quantity, region_id) {
+        (_, Some((quantity, item)), _) if mentions(&["collect", "gather", "find", "bring"]) => ObjectiveType::CollectItems {
+            item_type: item.to_string(),
+            quantity,
+        },
+        _ if mentions(&["sing", "melody", "song", "perform", "play"]) => ObjectiveType::PerformMelody {
+            melody_type: ["healing", "discovery", "harmony", "restoration", "creation"]
+                .into_iter()
+                .find(|melody| lower.contains(melody))
+                .map(String::from),
+        },
+        (Some(echo_type), _, _) => ObjectiveType::InteractWithEcho { echo_type, min_bond_level: 10 },
+        // World-engine harmony runs from 0 to 1
+        (_, _, Some(region_id)) if mentions(&["restore", "heal", "mend", "cleanse"]) => ObjectiveType::RestoreHarmony {
+            region_id: region_id.clone(),
+            target_level: 0.7,
+        },
+        (_, _, Some(region_id)) if mentions(&["explore", "travel", "journey", "search", "visit", "reach"]) => {
+            ObjectiveType::ExploreArea { region_id: region_id.clone(), coverage_percent: 0.5 }
+        }
+        _ if mentions(&["talk", "speak", "ask", "meet"]) => ObjectiveType::TalkToNPC {
+            npc_id: "quest_giver".to_string(),
+            dialogue_branch: None,
+        },
+        (_, _, Some(region_id)) => ObjectiveType::RestoreHarmony { region_id: region_id.clone(), target_level: 0.6 },
+        _ => ObjectiveType::PerformMelody { melody_type: None },
+    };
 
-#[derive(Debug, Clone)]
-enum EmergentOpportunity {
-    HarmonyCrisis { region_id: RegionId, severity: f32 },
-    PlayerGathering { location: Coordinates, count: u32 },
-    EchoNeed { echo_type: EchoType, need: String },
+    DynamicObjective {
+        id: Uuid::new_v4(),
+        description: text.trim_end_matches("(optional)").trim_end_matches("(Optional)").trim().to_string(),
+        objective_type,
+        progress: ObjectiveProgress::NotStarted,
+        hidden: false,
+        optional: lower.contains("optional"),
+    }
 }
 
-fn calculate_difficulty_modifier(player_profile: &PlayerProfile) -> f32 {
+fn difficulty_modifier(total_resonance: u64) -> f32 {
     let base_difficulty = 1.0;
-    let level_modifier = (player_profile.total_resonance() as f32 / 100.0).min(2.0);
+    let level_modifier = (total_resonance as f32 / 100.0).min(2.0);
     base_difficulty * level_modifier
 }
 
-fn calculate_crisis_severity(harmony: f32) -> f32 {
-    if harmony < 10.0 {
-        1.0
-    } else if harmony < 20.0 {
-        0.8
-    } else if harmony < 30.0 {
-        0.6
-    } else {
-        0.4
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(region_id: Option<RegionId>) -> GenerationContext {
+        GenerationContext {
+            prompt: AiQuestPrompt {
+                player_context: String::new(),
+                world_state: String::new(),
+                harmony_level: None,
+            },
+            region_id,
+            total_resonance: 150,
+        }
+    }
+
+    fn response(narrative: &str) -> AiQuestResponse {
+        AiQuestResponse {
+            quest_narrative: narrative.to_string(),
+            quest_id: "quest-1".to_string(),
+            estimated_duration: 20,
+        }
+    }
+
+    #[test]
+    fn parse_splits_title_description_and_steps() {
+        let parsed = ParsedNarrative::parse(
+            "# **The Silent Grove**\n\nThe grove has gone quiet.\nIts birds no longer sing.\n\nObjectives:\n- Travel to the grove\n2. Sing a healing melody\n3) Speak with Lumi\n",
+        );
+        assert_eq!(parsed.title.as_deref(), Some("The Silent Grove"));
+        assert_eq!(parsed.description, "The grove has gone quiet. Its birds no longer sing.");
+        assert_eq!(parsed.steps, ["Travel to the grove", "Sing a healing melody", "Speak with Lumi"]);
+    }
+
+    #[test]
+    fn parse_takes_the_first_title_line_and_ignores_empty_headings() {
+        let parsed = ParsedNarrative::parse("#\nTitle: \"Echoes Below\"\nQuest: Another Name\nDown you go.");
+        assert_eq!(parsed.title.as_deref(), Some("Echoes Below"));
+        assert_eq!(parsed.description, "Down you go.");
+        assert!(parsed.steps.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn quest_turns_steps_into_typed_objectives() {
+        let region = RegionId(Uuid::new_v4());
+        let quest = quest_from_narrative(
+            &response("# Mending\nThe river mourns.\n- Collect 3 reeds\n- Perform a restoration song\n- Visit Terra at the falls\n- Explore the upper valley\n- Cleanse the spring (optional)"),
+            &context(Some(region.clone())),
+        );
+        assert_eq!(quest.title, "Mending");
+        assert_eq!(quest.description, "The river mourns.");
+        let types: Vec<_> = quest.objectives.iter().map(|objective| &objective.objective_type).collect();
+        assert!(matches!(types[0], ObjectiveType::CollectItems { item_type, quantity: 3 } if item_type == "reeds"));
+        assert!(matches!(types[1], ObjectiveType::PerformMelody { melody_type: Some(melody) } if melody == "restoration"));
+        assert!(matches!(types[2], ObjectiveType::InteractWithEcho { echo_type: EchoType::Terra, .. }));
+        assert!(matches!(types[3], ObjectiveType::ExploreArea { region_id, .. } if *region_id == region));
+        assert!(matches!(types[4], ObjectiveType::RestoreHarmony { region_id, .. } if *region_id == region));
+        assert!(quest.objectives[4].optional);
+        assert_eq!(quest.objectives[4].description, "Cleanse the spring");
+        assert_eq!(quest.context.estimated_duration, 20);
+        assert_eq!(quest.context.difficulty_rating, 1.5);
+    }
+
+    #[test]
+    fn quest_keeps_at_most_five_objectives() {
+        let narrative = (1..=8).map(|step| format!("{}. Sing verse {}", step, step)).collect::<Vec<_>>().join("\n");
+        let quest = quest_from_narrative(&response(&narrative), &context(None));
+        assert_eq!(quest.objectives.len(), MAX_NARRATIVE_OBJECTIVES);
+    }
+
+    #[test]
+    fn quest_without_required_steps_falls_back_to_the_description() {
+        let quest = quest_from_narrative(
+            &response("Speak with the ferryman about the drowned bell.\n- Rest by the fire (optional)"),
+            &context(None),
+        );
+        assert_eq!(quest.title, "A Personal Quest");
+        assert_eq!(quest.objectives.len(), 2);
+        assert!(!quest.objectives[0].optional);
+        assert!(matches!(quest.objectives[0].objective_type, ObjectiveType::TalkToNPC { .. }));
+        assert_eq!(quest.context.difficulty_rating, 1.5);
+    }
+}
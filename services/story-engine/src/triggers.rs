@@ -0,0 +1,313 @@
+// services/story-engine/src/triggers.rs
+// Per-entity scripting triggers authored alongside quests
+
+use chrono::Timelike;
+use finalverse_events::Coordinates;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A designer-authored rule: when an interaction on a tagged entity matches
+/// the conditions, run the actions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerDefinition {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    pub entity_tag: String,
+    #[serde(default)]
+    pub conditions: TriggerConditions,
+    pub actions: Vec<TriggerAction>,
+    /// Minimum time between firings for everyone.
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    /// Minimum time between firings for the same player.
+    #[serde(default)]
+    pub player_cooldown_secs: u64,
+    /// Maximum number of activations running at once.
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    /// How long an activation occupies a concurrency slot.
+    #[serde(default)]
+    pub active_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TriggerConditions {
+    /// Interaction that fires the trigger; any interaction when unset.
+    pub interaction: Option<InteractionType>,
+    pub time_window: Option<TimeWindow>,
+    #[serde(default)]
+    pub player: Vec<PlayerPredicate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InteractionType {
+    Touch,
+    Talk,
+    Approach,
+    PerformMelody,
+}
+
+/// World hours in which the trigger is live. Wraps past midnight when
+/// `start_hour` is greater than `end_hour`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PlayerPredicate {
+    MinAttunementTier { tier: u32 },
+    MinResonance { amount: f64 },
+    HasTag { tag: String },
+    LacksTag { tag: String },
+    CompletedQuest { quest_id: String },
+}
+
+impl PlayerPredicate {
+    fn matches(&self, player: &PlayerFacts) -> bool {
+        match self {
+            PlayerPredicate::MinAttunementTier { tier } => player.attunement_tier >= *tier,
+            PlayerPredicate::MinResonance { amount } => player.total_resonance >= *amount,
+            PlayerPredicate::HasTag { tag } => player.tags.contains(tag),
+            PlayerPredicate::LacksTag { tag } => !player.tags.contains(tag),
+            PlayerPredicate::CompletedQuest { quest_id } => player.completed_quests.contains(quest_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TriggerAction {
+    /// Play a dialogue line; generated for the NPC when `line` is unset.
+    Dialogue { npc_id: String, line: Option<String> },
+    Spawn { entity_type: String, location: Option<Coordinates> },
+    EmitEvent { name: String, #[serde(default)] payload: serde_json::Value },
+    /// Send the player into an authored vision interlude.
+    Vision { vision_id: String },
+}
+
+/// What player predicates check. Loaded by the story engine from
+/// harmony-service and the player's quest log, never taken from the caller.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerFacts {
+    #[serde(default)]
+    pub attunement_tier: u32,
+    #[serde(default)]
+    pub total_resonance: f64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub completed_quests: Vec<String>,
+}
+
+/// An interaction to match triggers against. The HTTP API builds it from
+/// the caller's token, the entity as world-engine knows it and the
+/// player's facts; only the entity and interaction come from the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityInteraction {
+    pub entity_id: String,
+    pub entity_tags: Vec<String>,
+    pub player_id: String,
+    pub interaction: InteractionType,
+    pub location: Coordinates,
+    /// In-world hour; the shared world clock is used when absent.
+    pub world_hour: Option<u32>,
+    pub player: PlayerFacts,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedTrigger {
+    pub trigger_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FiredTrigger {
+    pub trigger_id: String,
+    pub actions: Vec<TriggerAction>,
+}
+
+#[derive(Default)]
+struct TriggerRuntime {
+    last_fired: Option<Instant>,
+    player_last_fired: HashMap<String, Instant>,
+    active_until: Vec<Instant>,
+}
+
+pub struct TriggerEngine {
+    triggers: RwLock<HashMap<String, TriggerDefinition>>,
+    runtime: Mutex<HashMap<String, TriggerRuntime>>,
+}
+
+impl TriggerEngine {
+    pub fn new() -> Self {
+        Self {
+            triggers: RwLock::new(HashMap::new()),
+            runtime: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Load every `*.json` file in `dir`, each holding a list of triggers.
+    pub async fn load_dir(&self, dir: &Path) -> Result<usize, String> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+        let mut loaded = 0;
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let definitions: Vec<TriggerDefinition> = serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid trigger file {}: {}", path.display(), e))?;
+            for definition in definitions {
+                self.upsert(definition).await?;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    pub async fn upsert(&self, definition: TriggerDefinition) -> Result<(), String> {
+        validate(&definition)?;
+        self.runtime.lock().await.remove(&definition.id);
+        self.triggers.write().await.insert(definition.id.clone(), definition);
+        Ok(())
+    }
+
+    pub async fn remove(&self, id: &str) -> bool {
+        self.runtime.lock().await.remove(id);
+        self.triggers.write().await.remove(id).is_some()
+    }
+
+    pub async fn list(&self) -> Vec<TriggerDefinition> {
+        let mut triggers: Vec<_> = self.triggers.read().await.values().cloned().collect();
+        triggers.sort_by(|a, b| a.id.cmp(&b.id));
+        triggers
+    }
+
+    /// Match an interaction against every trigger bound to the entity's
+    /// tags. Triggers that fire have their cooldowns and concurrency slots
+    /// reserved before this returns.
+    pub async fn evaluate(&self, interaction: &EntityInteraction) -> (Vec<FiredTrigger>, Vec<SkippedTrigger>) {
+        let hour = interaction.world_hour.unwrap_or_else(|| match finalverse_core::world_clock::now() {
+            Some(now) => now.hour as u32,
+            None => chrono::Utc::now().hour(),
+        });
+        let now = Instant::now();
+
+        let triggers = self.triggers.read().await;
+        let mut runtime = self.runtime.lock().await;
+        let mut candidates: Vec<&TriggerDefinition> = triggers
+            .values()
+            .filter(|t| t.enabled && interaction.entity_tags.contains(&t.entity_tag))
+            .collect();
+        candidates.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut fired = Vec::new();
+        let mut skipped = Vec::new();
+        for trigger in candidates {
+            if let Some(reason) = unmet_condition(&trigger.conditions, interaction, hour) {
+                skipped.push(SkippedTrigger { trigger_id: trigger.id.clone(), reason });
+                continue;
+            }
+
+            let state = runtime.entry(trigger.id.clone()).or_default();
+            state.active_until.retain(|until| *until > now);
+
+            let reason = if state
+                .last_fired
+                .is_some_and(|at| now.duration_since(at) < Duration::from_secs(trigger.cooldown_secs))
+            {
+                Some("on cooldown")
+            } else if state
+                .player_last_fired
+                .get(&interaction.player_id)
+                .is_some_and(|at| now.duration_since(*at) < Duration::from_secs(trigger.player_cooldown_secs))
+            {
+                Some("on cooldown for player")
+            } else if trigger
+                .max_concurrent
+                .is_some_and(|max| state.active_until.len() >= max as usize)
+            {
+                Some("concurrency limit reached")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                skipped.push(SkippedTrigger { trigger_id: trigger.id.clone(), reason: reason.to_string() });
+                continue;
+            }
+
+            state.last_fired = Some(now);
+            state.player_last_fired.insert(interaction.player_id.clone(), now);
+            if trigger.active_secs > 0 {
+                state.active_until.push(now + Duration::from_secs(trigger.active_secs));
+            }
+            fired.push(FiredTrigger {
+                trigger_id: trigger.id.clone(),
+                actions: trigger.actions.clone(),
+            });
+        }
+
+        (fired, skipped)
+    }
+}
+
+fn unmet_condition(conditions: &TriggerConditions, interaction: &EntityInteraction, hour: u32) -> Option<String> {
+    if conditions.interaction.is_some_and(|wanted| wanted != interaction.interaction) {
+        return Some("interaction type does not match".to_string());
+    }
+    if let Some(window) = &conditions.time_window {
+        if !window.contains(hour) {
+            return Some(format!("outside time window at hour {}", hour));
+        }
+    }
+    conditions
+        .player
+        .iter()
+        .find(|predicate| !predicate.matches(&interaction.player))
+        .map(|predicate| format!("player predicate not met: {:?}", predicate))
+}
+
+fn validate(definition: &TriggerDefinition) -> Result<(), String> {
+    if definition.id.trim().is_empty() {
+        return Err("Trigger id cannot be empty".to_string());
+    }
+    if definition.entity_tag.trim().is_empty() {
+        return Err(format!("Trigger {} has no entity tag", definition.id));
+    }
+    if definition.actions.is_empty() {
+        return Err(format!("Trigger {} has no actions", definition.id));
+    }
+    if let Some(window) = &definition.conditions.time_window {
+        if window.start_hour > 23 || window.end_hour > 24 {
+            return Err(format!("Trigger {} has an invalid time window", definition.id));
+        }
+    }
+    if definition.max_concurrent == Some(0) {
+        return Err(format!("Trigger {} has max_concurrent of 0", definition.id));
+    }
+    Ok(())
+}
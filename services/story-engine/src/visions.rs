@@ -0,0 +1,236 @@
+// services/story-engine/src/visions.rs
+// Dream and vision interludes played in an instanced scene between chapters
+
+use chrono::{DateTime, Utc};
+use finalverse_events::Coordinates;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::triggers::TriggerAction;
+
+/// Extra time allowed past a scene's duration before the player is forced
+/// back out of the vision.
+const OVERRUN_SECS: i64 = 30;
+
+/// A designer-authored vision scene. Only entity types in the palette may
+/// appear, whether placed up front or spawned by a beat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisionScene {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    /// Where the player appears inside the instanced scene.
+    pub origin: Coordinates,
+    pub palette: Vec<String>,
+    #[serde(default)]
+    pub entities: Vec<VisionEntity>,
+    #[serde(default)]
+    pub beats: Vec<VisionBeat>,
+    pub duration_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisionEntity {
+    pub entity_type: String,
+    pub location: Coordinates,
+}
+
+/// A scripted action played a fixed time after the vision begins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisionBeat {
+    pub at_secs: u64,
+    pub action: TriggerAction,
+}
+
+/// Where the player was, and what they were doing, before the vision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReturnPoint {
+    pub location: Coordinates,
+    /// Opaque client/world state handed back untouched on return.
+    #[serde(default)]
+    pub state: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveVision {
+    pub instance_id: Uuid,
+    pub vision_id: String,
+    pub player_id: String,
+    pub started_at: DateTime<Utc>,
+    pub return_point: ReturnPoint,
+    pub must_end_by: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum VisionEndReason {
+    Completed,
+    Left,
+    TimedOut,
+    Disconnected,
+}
+
+impl std::fmt::Display for VisionEndReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            VisionEndReason::Completed => "completed",
+            VisionEndReason::Left => "left",
+            VisionEndReason::TimedOut => "timed out",
+            VisionEndReason::Disconnected => "disconnected",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+pub struct VisionDirector {
+    scenes: RwLock<HashMap<String, VisionScene>>,
+    active: RwLock<HashMap<String, ActiveVision>>,
+}
+
+impl VisionDirector {
+    pub fn new() -> Self {
+        Self {
+            scenes: RwLock::new(HashMap::new()),
+            active: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Load every `*.json` file in `dir`, each holding a list of scenes.
+    pub async fn load_dir(&self, dir: &Path) -> Result<usize, String> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+        let mut loaded = 0;
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let scenes: Vec<VisionScene> = serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid vision file {}: {}", path.display(), e))?;
+            for scene in scenes {
+                self.upsert(scene).await?;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    pub async fn upsert(&self, scene: VisionScene) -> Result<(), String> {
+        validate(&scene)?;
+        self.scenes.write().await.insert(scene.id.clone(), scene);
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Vec<VisionScene> {
+        let mut scenes: Vec<_> = self.scenes.read().await.values().cloned().collect();
+        scenes.sort_by(|a, b| a.id.cmp(&b.id));
+        scenes
+    }
+
+    pub async fn active_for(&self, player_id: &str) -> Option<ActiveVision> {
+        self.active.read().await.get(player_id).cloned()
+    }
+
+    /// Move a player into a fresh instance of the scene, remembering where
+    /// to put them back. A player can only be in one vision at a time.
+    pub async fn enter(
+        &self,
+        player_id: &str,
+        vision_id: &str,
+        return_point: ReturnPoint,
+    ) -> Result<(ActiveVision, VisionScene), String> {
+        let scene = self
+            .scenes
+            .read()
+            .await
+            .get(vision_id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown vision: {}", vision_id))?;
+
+        let mut active = self.active.write().await;
+        if let Some(current) = active.get(player_id) {
+            return Err(format!("Player {} is already in vision {}", player_id, current.vision_id));
+        }
+
+        let now = Utc::now();
+        let vision = ActiveVision {
+            instance_id: Uuid::new_v4(),
+            vision_id: scene.id.clone(),
+            player_id: player_id.to_string(),
+            started_at: now,
+            return_point,
+            must_end_by: now + chrono::Duration::seconds(scene.duration_secs as i64 + OVERRUN_SECS),
+        };
+        active.insert(player_id.to_string(), vision.clone());
+        Ok((vision, scene))
+    }
+
+    /// End the player's vision, if it is still the given instance (or any
+    /// instance when `instance_id` is unset).
+    pub async fn exit(&self, player_id: &str, instance_id: Option<Uuid>) -> Option<ActiveVision> {
+        let mut active = self.active.write().await;
+        let matches = active
+            .get(player_id)
+            .is_some_and(|vision| instance_id.is_none_or(|id| vision.instance_id == id));
+        if matches {
+            active.remove(player_id)
+        } else {
+            None
+        }
+    }
+
+    pub async fn is_current(&self, player_id: &str, instance_id: Uuid) -> bool {
+        self.active
+            .read()
+            .await
+            .get(player_id)
+            .is_some_and(|vision| vision.instance_id == instance_id)
+    }
+
+    /// Remove and return visions that ran past their scene's duration.
+    pub async fn take_overdue(&self, now: DateTime<Utc>) -> Vec<ActiveVision> {
+        let mut active = self.active.write().await;
+        let overdue: Vec<String> = active
+            .values()
+            .filter(|vision| vision.must_end_by <= now)
+            .map(|vision| vision.player_id.clone())
+            .collect();
+        overdue.iter().filter_map(|player_id| active.remove(player_id)).collect()
+    }
+}
+
+fn validate(scene: &VisionScene) -> Result<(), String> {
+    if scene.id.trim().is_empty() {
+        return Err("Vision id cannot be empty".to_string());
+    }
+    if scene.duration_secs == 0 {
+        return Err(format!("Vision {} has no duration", scene.id));
+    }
+    if scene.palette.is_empty() {
+        return Err(format!("Vision {} has an empty palette", scene.id));
+    }
+    for entity in &scene.entities {
+        if !scene.palette.contains(&entity.entity_type) {
+            return Err(format!("Vision {} places {} outside its palette", scene.id, entity.entity_type));
+        }
+    }
+    for beat in &scene.beats {
+        if beat.at_secs > scene.duration_secs {
+            return Err(format!("Vision {} has a beat after it ends", scene.id));
+        }
+        match &beat.action {
+            TriggerAction::Spawn { entity_type, .. } if !scene.palette.contains(entity_type) => {
+                return Err(format!("Vision {} spawns {} outside its palette", scene.id, entity_type));
+            }
+            TriggerAction::Vision { .. } => {
+                return Err(format!("Vision {} cannot open another vision", scene.id));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
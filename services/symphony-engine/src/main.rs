@@ -5,6 +5,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, error};
 use finalverse_logging as logging;
+use finalverse_core::WorldClockClient;
+use finalverse_health::DebugEndpoints;
 use tokio_stream::StreamExt;
 
 mod audio_generator;
@@ -12,12 +14,20 @@ mod spatial_audio;
 mod voice_synthesis;
 mod music_ai;
 mod world_audio_state;
+mod streaming;
 
 use audio_generator::AudioGenerator;
 use spatial_audio::SpatialAudioEngine;
 use voice_synthesis::VoiceSynthesizer;
 use music_ai::MusicAI;
 use world_audio_state::WorldAudioState;
+use streaming::AudioStreamer;
+
+/// UDP port ambient audio is streamed from, unless SYMPHONY_STREAM_PORT says otherwise.
+const DEFAULT_STREAM_PORT: u16 = 3013;
+
+/// HTTP port for voice lines, unless SYMPHONY_VOICE_PORT says otherwise.
+const DEFAULT_VOICE_PORT: u16 = 3014;
 
 pub struct SymphonyEngine {
     config: Config,
@@ -26,15 +36,23 @@ pub struct SymphonyEngine {
     voice_synth: Arc<VoiceSynthesizer>,
     music_ai: Arc<MusicAI>,
     world_state: Arc<RwLock<WorldAudioState>>,
+    world_clock: Arc<WorldClockClient>,
+    streamer: Arc<AudioStreamer>,
 }
 
 impl SymphonyEngine {
     pub async fn new(config: Config) -> Result<Self, Box<dyn std::error::Error>> {
         let audio_generator = Arc::new(AudioGenerator::new());
         let spatial_engine = Arc::new(SpatialAudioEngine::new());
-        let voice_synth = Arc::new(VoiceSynthesizer::new());
+        let voice_synth = Arc::new(VoiceSynthesizer::from_env());
         let music_ai = Arc::new(MusicAI::new(&config).await?);
         let world_state = Arc::new(RwLock::new(WorldAudioState::new()));
+        let world_clock = finalverse_core::world_clock::start_from_env();
+        let stream_port = std::env::var("SYMPHONY_STREAM_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(DEFAULT_STREAM_PORT);
+        let streamer = AudioStreamer::bind(([0, 0, 0, 0], stream_port).into()).await?;
 
         Ok(Self {
             config,
@@ -43,6 +61,8 @@ impl SymphonyEngine {
             voice_synth,
             music_ai,
             world_state,
+            world_clock,
+            streamer,
         })
     }
 
@@ -52,6 +72,9 @@ impl SymphonyEngine {
         // Start the audio event listener
         self.start_event_listener().await?;
 
+        // Stream generated audio to listening clients
+        self.streamer.start();
+
         // Start the ambient music generator
         self.start_ambient_generator().await?;
 
@@ -92,12 +115,15 @@ impl SymphonyEngine {
     async fn start_ambient_generator(&self) -> Result<(), Box<dyn std::error::Error>> {
         let world_state = self.world_state.clone();
         let music_ai = self.music_ai.clone();
+        let world_clock = self.world_clock.clone();
+        let streamer = self.streamer.clone();
         tokio::spawn(async move {
             let audio_gen = AudioGenerator::new();
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
 
             loop {
                 interval.tick().await;
+                world_state.write().await.set_world_time(world_clock.now());
 
                 let state = world_state.read().await;
                 let regions = state.get_active_regions();
@@ -107,8 +133,8 @@ impl SymphonyEngine {
                     let theme = music_ai.generate_regional_theme(&region).await;
                     let audio_stream = audio_gen.generate_ambient_track(theme).await;
 
-                    // Broadcast to clients in region
-                    // Implementation depends on your networking layer
+                    // Listeners in the region move to the new track
+                    streamer.publish(&region.id, &audio_stream).await;
                 }
             }
         });
@@ -117,7 +143,22 @@ impl SymphonyEngine {
     }
 
     async fn start_voice_service(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Voice synthesis service implementation
+        let port = std::env::var("SYMPHONY_VOICE_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(DEFAULT_VOICE_PORT);
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let app = voice_synthesis::routes(self.voice_synth.clone())
+            .merge(Arc::new(DebugEndpoints::new("symphony-engine", &self.config)).axum_routes());
+        info!("Voice synthesis listening on {}", addr);
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                error!("Voice synthesis server stopped: {}", e);
+            }
+        });
+
         Ok(())
     }
 }
@@ -0,0 +1,489 @@
+// services/symphony-engine/src/streaming.rs
+//! Delivers generated ambient tracks to clients as Opus over UDP.
+//!
+//! A client subscribes by sending a JSON [`StreamSubscription`] datagram to
+//! the stream port. A subscription without a valid cookie is answered only
+//! with a [`StreamCookie`], never larger than the datagram that asked for
+//! it; the client sends its subscription again carrying that cookie, which
+//! proves it receives at the address it sent from. Without this, anyone
+//! could spoof a subscription and point a stream of audio at a victim.
+//!
+//! The client re-sends its subscription at least every
+//! [`SUBSCRIPTION_TTL`] to keep listening, and takes a fresh cookie when
+//! it is answered with one. It receives one [`AudioPacket`] every 20 ms
+//! holding the next Opus frame of its region's track, encoded at the
+//! bitrate its `AudioQuality` asks for. Tracks loop until the ambient
+//! generator publishes a new one for the region.
+
+use crate::audio_generator::AudioStream;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use finalverse_audio_core::{AudioQuality, AudioStreamRequest};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::sync::RwLock;
+use tokio::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Opus runs at 48 kHz; generated tracks are resampled to it.
+pub const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+/// 20 ms frames, the usual Opus frame size for music.
+pub const FRAME_SAMPLES: usize = OPUS_SAMPLE_RATE as usize / 50;
+const FRAME_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Opus cannot be lossless; lossless listeners get its highest bitrate.
+const MAX_OPUS_BITRATE: u32 = 510_000;
+
+/// Largest Opus packet the encoder may produce, per the Opus spec.
+const MAX_PACKET_BYTES: usize = 1275;
+
+/// How long a subscription lasts without being renewed.
+pub const SUBSCRIPTION_TTL: Duration = Duration::from_secs(30);
+
+/// Stream id, sequence number and sample timestamp ahead of the payload.
+const HEADER_BYTES: usize = 16 + 4 + 4;
+
+/// Bytes of HMAC kept in a cookie.
+const COOKIE_BYTES: usize = 16;
+
+/// Most addresses streamed to at once, and from any one IP, so a flood of
+/// subscriptions can't grow the listener map or the outgoing traffic
+/// without bound.
+const MAX_LISTENERS: usize = 2048;
+const MAX_LISTENERS_PER_IP: usize = 4;
+
+pub fn opus_bitrate(quality: &AudioQuality) -> u32 {
+    quality.bitrate_kbps().map(|kbps| kbps * 1000).unwrap_or(MAX_OPUS_BITRATE)
+}
+
+/// What a client sends to start or renew listening to a region.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamSubscription {
+    pub region_id: String,
+    #[serde(flatten)]
+    pub request: AudioStreamRequest,
+    /// The last [`StreamCookie`] the server sent this address.
+    #[serde(default)]
+    pub cookie: Option<String>,
+}
+
+/// The server's answer to a subscription it can't yet stream to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamCookie {
+    pub cookie: String,
+}
+
+/// Issues return-routability cookies: an HMAC of the client's address and
+/// the current [`SUBSCRIPTION_TTL`] period under a key made at startup, so
+/// handing them out to spoofed addresses costs no memory. A cookie stays
+/// valid through the period after the one it was issued in.
+struct CookieIssuer {
+    key: [u8; 32],
+}
+
+impl CookieIssuer {
+    fn new() -> Self {
+        Self { key: rand::random() }
+    }
+
+    fn issue(&self, addr: SocketAddr, period: u64) -> String {
+        URL_SAFE_NO_PAD.encode(&self.mac(addr, period).finalize().into_bytes()[..COOKIE_BYTES])
+    }
+
+    fn verify(&self, addr: SocketAddr, cookie: &str, period: u64) -> bool {
+        let Ok(tag) = URL_SAFE_NO_PAD.decode(cookie) else {
+            return false;
+        };
+        tag.len() == COOKIE_BYTES
+            && [period, period.saturating_sub(1)]
+                .into_iter()
+                .any(|period| self.mac(addr, period).verify_truncated_left(&tag).is_ok())
+    }
+
+    fn mac(&self, addr: SocketAddr, period: u64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(addr.to_string().as_bytes());
+        mac.update(&period.to_be_bytes());
+        mac
+    }
+}
+
+fn cookie_period() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    now.as_secs() / SUBSCRIPTION_TTL.as_secs()
+}
+
+/// Whether `from` may start listening alongside `listeners`.
+fn has_room<T>(listeners: &HashMap<SocketAddr, T>, from: SocketAddr) -> bool {
+    listeners.len() < MAX_LISTENERS
+        && listeners.keys().filter(|addr| addr.ip() == from.ip()).count() < MAX_LISTENERS_PER_IP
+}
+
+/// One Opus frame on the wire: the subscriber's stream id, a sequence
+/// number for spotting loss, and the frame's position in 48 kHz samples,
+/// all big-endian, followed by the Opus payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioPacket {
+    pub stream_id: Uuid,
+    pub sequence: u32,
+    pub timestamp: u32,
+    pub payload: Vec<u8>,
+}
+
+impl AudioPacket {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_BYTES + self.payload.len());
+        bytes.extend_from_slice(self.stream_id.as_bytes());
+        bytes.extend_from_slice(&self.sequence.to_be_bytes());
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_BYTES {
+            return None;
+        }
+        let word = |at: usize| u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap());
+        Some(Self {
+            stream_id: Uuid::from_slice(&bytes[..16]).ok()?,
+            sequence: word(16),
+            timestamp: word(20),
+            payload: bytes[HEADER_BYTES..].to_vec(),
+        })
+    }
+}
+
+/// Linear resampling; plenty for ambient pads and cheap enough to run for
+/// every published track.
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let len = (samples.len() as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    (0..len)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let next = samples.get(index + 1).copied().unwrap_or(samples[index]);
+            let fraction = (position - index as f64) as f32;
+            samples[index] + (next - samples[index]) * fraction
+        })
+        .collect()
+}
+
+/// Encode mono 48 kHz samples into 20 ms Opus frames. The last frame is
+/// padded with silence.
+pub fn encode_opus(samples: &[f32], bitrate: u32) -> anyhow::Result<Vec<Vec<u8>>> {
+    let mut encoder = opus::Encoder::new(OPUS_SAMPLE_RATE, opus::Channels::Mono, opus::Application::Audio)?;
+    encoder.set_bitrate(opus::Bitrate::Bits(bitrate as i32))?;
+    let mut frame = [0.0f32; FRAME_SAMPLES];
+    samples
+        .chunks(FRAME_SAMPLES)
+        .map(|chunk| {
+            frame[..chunk.len()].copy_from_slice(chunk);
+            frame[chunk.len()..].fill(0.0);
+            Ok(encoder.encode_vec_float(&frame, MAX_PACKET_BYTES)?)
+        })
+        .collect()
+}
+
+/// A region's current track, encoded once per bitrate someone listens at.
+struct RegionTrack {
+    samples: Arc<Vec<f32>>,
+    encoded: HashMap<u32, Arc<Vec<Vec<u8>>>>,
+}
+
+struct Listener {
+    region_id: String,
+    stream_id: Uuid,
+    bitrate: u32,
+    expires_at: Instant,
+    sequence: u32,
+}
+
+pub struct AudioStreamer {
+    socket: Arc<UdpSocket>,
+    tracks: RwLock<HashMap<String, RegionTrack>>,
+    listeners: RwLock<HashMap<SocketAddr, Listener>>,
+    cookies: CookieIssuer,
+}
+
+impl AudioStreamer {
+    pub async fn bind(addr: SocketAddr) -> std::io::Result<Arc<Self>> {
+        let socket = UdpSocket::bind(addr).await?;
+        Ok(Arc::new(Self {
+            socket: Arc::new(socket),
+            tracks: RwLock::new(HashMap::new()),
+            listeners: RwLock::new(HashMap::new()),
+            cookies: CookieIssuer::new(),
+        }))
+    }
+
+    /// Accept subscriptions and send frames until the process exits.
+    pub fn start(self: &Arc<Self>) {
+        let streamer = self.clone();
+        tokio::spawn(async move { streamer.receive_subscriptions().await });
+        let streamer = self.clone();
+        tokio::spawn(async move { streamer.send_frames().await });
+    }
+
+    /// Replace a region's track; listeners move to it on their next frame.
+    pub async fn publish(&self, region_id: &str, stream: &AudioStream) {
+        let samples = Arc::new(resample(&stream.data, stream.format.sample_rate, OPUS_SAMPLE_RATE));
+        let bitrates: Vec<u32> = {
+            let listeners = self.listeners.read().await;
+            let mut bitrates: Vec<u32> = listeners
+                .values()
+                .filter(|listener| listener.region_id == region_id)
+                .map(|listener| listener.bitrate)
+                .collect();
+            bitrates.sort_unstable();
+            bitrates.dedup();
+            bitrates
+        };
+
+        let mut encoded = HashMap::new();
+        for bitrate in bitrates {
+            if let Some(frames) = encode(samples.clone(), bitrate).await {
+                encoded.insert(bitrate, frames);
+            }
+        }
+        self.tracks
+            .write()
+            .await
+            .insert(region_id.to_string(), RegionTrack { samples, encoded });
+    }
+
+    async fn receive_subscriptions(&self) {
+        let mut buffer = [0u8; 2048];
+        loop {
+            let (len, from) = match self.socket.recv_from(&mut buffer).await {
+                Ok(received) => received,
+                Err(e) => {
+                    tracing::warn!("Audio stream socket error: {}", e);
+                    continue;
+                }
+            };
+            match serde_json::from_slice::<StreamSubscription>(&buffer[..len]) {
+                Ok(subscription) => {
+                    let period = cookie_period();
+                    let routable = subscription
+                        .cookie
+                        .as_deref()
+                        .is_some_and(|cookie| self.cookies.verify(from, cookie, period));
+                    if routable {
+                        self.subscribe(from, subscription).await;
+                    } else {
+                        self.challenge(from, len, period).await;
+                    }
+                }
+                Err(e) => tracing::debug!("Ignoring datagram from {}: {}", from, e),
+            }
+        }
+    }
+
+    /// Send `to` a cookie to subscribe with, unless it would be larger than
+    /// the `request_len` bytes that asked for it.
+    async fn challenge(&self, to: SocketAddr, request_len: usize, period: u64) {
+        let cookie = StreamCookie {
+            cookie: self.cookies.issue(to, period),
+        };
+        let Ok(reply) = serde_json::to_vec(&cookie) else {
+            return;
+        };
+        if reply.len() > request_len {
+            tracing::debug!("Ignoring short subscription from {}", to);
+            return;
+        }
+        if let Err(e) = self.socket.send_to(&reply, to).await {
+            tracing::debug!("Failed to send cookie to {}: {}", to, e);
+        }
+    }
+
+    async fn subscribe(&self, from: SocketAddr, subscription: StreamSubscription) {
+        let bitrate = opus_bitrate(&subscription.request.quality);
+        let mut listeners = self.listeners.write().await;
+        if !listeners.contains_key(&from) && !has_room(&listeners, from) {
+            tracing::warn!("Refusing audio subscription from {}: listener limit reached", from);
+            return;
+        }
+        let listener = listeners.entry(from).or_insert_with(|| {
+            tracing::info!("🎧 {} listening to {} at {} bps", from, subscription.region_id, bitrate);
+            Listener {
+                region_id: subscription.region_id.clone(),
+                stream_id: subscription.request.stream_id,
+                bitrate,
+                expires_at: Instant::now(),
+                sequence: 0,
+            }
+        });
+        listener.region_id = subscription.region_id.clone();
+        listener.stream_id = subscription.request.stream_id;
+        listener.bitrate = bitrate;
+        listener.expires_at = Instant::now() + SUBSCRIPTION_TTL;
+        drop(listeners);
+
+        // Encode the current track for a bitrate nobody else uses yet
+        let samples = {
+            let tracks = self.tracks.read().await;
+            match tracks.get(&subscription.region_id) {
+                Some(track) if !track.encoded.contains_key(&bitrate) => Some(track.samples.clone()),
+                _ => None,
+            }
+        };
+        if let Some(samples) = samples {
+            if let Some(frames) = encode(samples.clone(), bitrate).await {
+                let mut tracks = self.tracks.write().await;
+                // Skip if the track was replaced while encoding
+                if let Some(track) = tracks.get_mut(&subscription.region_id) {
+                    if Arc::ptr_eq(&track.samples, &samples) {
+                        track.encoded.insert(bitrate, frames);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send_frames(&self) {
+        let mut ticker = tokio::time::interval(FRAME_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            let now = Instant::now();
+            let mut packets = Vec::new();
+            {
+                let tracks = self.tracks.read().await;
+                let mut listeners = self.listeners.write().await;
+                listeners.retain(|_, listener| listener.expires_at > now);
+                for (addr, listener) in listeners.iter_mut() {
+                    let Some(frames) = tracks
+                        .get(&listener.region_id)
+                        .and_then(|track| track.encoded.get(&listener.bitrate))
+                        .filter(|frames| !frames.is_empty())
+                    else {
+                        continue;
+                    };
+                    let frame = listener.sequence as usize % frames.len();
+                    packets.push((
+                        *addr,
+                        AudioPacket {
+                            stream_id: listener.stream_id,
+                            sequence: listener.sequence,
+                            timestamp: listener.sequence.wrapping_mul(FRAME_SAMPLES as u32),
+                            payload: frames[frame].clone(),
+                        },
+                    ));
+                    listener.sequence = listener.sequence.wrapping_add(1);
+                }
+            }
+            for (addr, packet) in packets {
+                if let Err(e) = self.socket.send_to(&packet.to_bytes(), addr).await {
+                    tracing::debug!("Failed to send audio to {}: {}", addr, e);
+                }
+            }
+        }
+    }
+}
+
+async fn encode(samples: Arc<Vec<f32>>, bitrate: u32) -> Option<Arc<Vec<Vec<u8>>>> {
+    match tokio::task::spawn_blocking(move || encode_opus(&samples, bitrate)).await {
+        Ok(Ok(frames)) => Some(Arc::new(frames)),
+        Ok(Err(e)) => {
+            tracing::error!("Failed to encode ambient track at {} bps: {}", bitrate, e);
+            None
+        }
+        Err(e) => {
+            tracing::error!("Opus encoder task failed: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets_round_trip_and_quality_sets_the_bitrate() {
+        let packet = AudioPacket {
+            stream_id: Uuid::new_v4(),
+            sequence: 7,
+            timestamp: 7 * FRAME_SAMPLES as u32,
+            payload: vec![1, 2, 3],
+        };
+        assert_eq!(AudioPacket::from_bytes(&packet.to_bytes()), Some(packet));
+        assert_eq!(AudioPacket::from_bytes(&[0; 10]), None);
+
+        assert_eq!(opus_bitrate(&AudioQuality::Low), 64_000);
+        assert_eq!(opus_bitrate(&AudioQuality::High), 256_000);
+        assert_eq!(opus_bitrate(&AudioQuality::Lossless), MAX_OPUS_BITRATE);
+    }
+
+    #[test]
+    fn tracks_are_resampled_and_cut_into_20ms_frames() {
+        let second: Vec<f32> = (0..44_100).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        let samples = resample(&second, 44_100, OPUS_SAMPLE_RATE);
+        assert_eq!(samples.len(), 48_000);
+
+        // A partial last frame is padded rather than dropped
+        let frames = encode_opus(&samples[..47_000], opus_bitrate(&AudioQuality::Medium)).unwrap();
+        assert_eq!(frames.len(), 49);
+        assert!(frames.iter().all(|frame| !frame.is_empty() && frame.len() <= MAX_PACKET_BYTES));
+    }
+
+    #[test]
+    fn cookies_are_bound_to_the_address_and_expire() {
+        let cookies = CookieIssuer::new();
+        let client: SocketAddr = "203.0.113.7:5000".parse().unwrap();
+        let cookie = cookies.issue(client, 100);
+
+        assert!(cookies.verify(client, &cookie, 100));
+        assert!(cookies.verify(client, &cookie, 101));
+        assert!(!cookies.verify(client, &cookie, 102));
+        assert!(!cookies.verify("203.0.113.7:5001".parse().unwrap(), &cookie, 100));
+        assert!(!CookieIssuer::new().verify(client, &cookie, 100));
+        assert!(!cookies.verify(client, "not a cookie", 100));
+    }
+
+    #[test]
+    fn cookie_replies_are_smaller_than_any_subscription() {
+        let subscription = serde_json::json!({
+            "region_id": "",
+            "stream_id": Uuid::nil(),
+            "stream_type": "Music",
+            "quality": "Low",
+        });
+        let subscription = serde_json::to_vec(&subscription).unwrap();
+        assert!(serde_json::from_slice::<StreamSubscription>(&subscription).is_ok());
+
+        let cookie = StreamCookie {
+            cookie: CookieIssuer::new().issue("[2001:db8::1]:5000".parse().unwrap(), 0),
+        };
+        assert!(serde_json::to_vec(&cookie).unwrap().len() < subscription.len());
+    }
+
+    #[test]
+    fn listeners_are_capped_per_ip_and_overall() {
+        let mut listeners = HashMap::new();
+        let addr = |host: u32, port: u16| SocketAddr::from((std::net::Ipv4Addr::from(host), port));
+        for port in 0..MAX_LISTENERS_PER_IP as u16 {
+            listeners.insert(addr(1, port), ());
+        }
+        assert!(!has_room(&listeners, addr(1, 9000)));
+        assert!(has_room(&listeners, addr(2, 9000)));
+
+        for host in 2..=(MAX_LISTENERS - MAX_LISTENERS_PER_IP + 1) as u32 {
+            listeners.insert(addr(host, 0), ());
+        }
+        assert_eq!(listeners.len(), MAX_LISTENERS);
+        assert!(!has_room(&listeners, addr(u32::MAX, 0)));
+    }
+}
@@ -1,16 +1,43 @@
 // services/symphony-engine/src/voice_synthesis.rs
 use finalverse_audio_core::*;
-use crate::audio_generator::{AudioStream, AudioFormat, AudioMetadata};
-use std::collections::HashMap;
+use crate::streaming::resample;
+use finalverse_core::FinalverseError;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
+/// Voice clips kept for replay and for `GET /voice/{stream_id}`.
+pub const DEFAULT_VOICE_CACHE_CLIPS: usize = 256;
+
+/// Generated voices run at the same rate as the rest of the engine.
+pub const VOICE_SAMPLE_RATE: u32 = 44_100;
+
 pub struct VoiceSynthesizer {
     voice_profiles: RwLock<HashMap<String, VoiceProfile>>,
-    tts_engine: TTSEngine,
+    backend: Arc<dyn TtsBackend>,
+    cache: Mutex<VoiceCache>,
 }
 
 impl VoiceSynthesizer {
     pub fn new() -> Self {
+        Self::with_backend(Arc::new(ToneBackend))
+    }
+
+    /// Use `TTS_URL` when it is set, otherwise the built-in tone voice.
+    pub fn from_env() -> Self {
+        match std::env::var("TTS_URL") {
+            Ok(url) => Self::with_backend(Arc::new(HttpTtsBackend::new(url))),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn with_backend(backend: Arc<dyn TtsBackend>) -> Self {
         let mut voice_profiles = HashMap::new();
 
         // Initialize Echo voice profiles
@@ -22,6 +49,7 @@ impl VoiceSynthesizer {
                 timbre: Timbre::Bright,
                 melodic_inflection: 0.8, // High melodic variation
                 reverb: 0.3,
+                voice_id: "lumi".to_string(),
                 character_traits: vec![
                     "childlike".to_string(),
                     "hopeful".to_string(),
@@ -38,6 +66,7 @@ impl VoiceSynthesizer {
                 timbre: Timbre::Digital,
                 melodic_inflection: 0.3, // Less melodic, more measured
                 reverb: 0.1,
+                voice_id: "kai".to_string(),
                 character_traits: vec![
                     "logical".to_string(),
                     "calm".to_string(),
@@ -54,6 +83,7 @@ impl VoiceSynthesizer {
                 timbre: Timbre::Warm,
                 melodic_inflection: 0.5,
                 reverb: 0.4,
+                voice_id: "terra".to_string(),
                 character_traits: vec![
                     "wise".to_string(),
                     "patient".to_string(),
@@ -70,6 +100,7 @@ impl VoiceSynthesizer {
                 timbre: Timbre::Bold,
                 melodic_inflection: 0.6,
                 reverb: 0.2,
+                voice_id: "ignis".to_string(),
                 character_traits: vec![
                     "courageous".to_string(),
                     "passionate".to_string(),
@@ -80,50 +111,83 @@ impl VoiceSynthesizer {
 
         Self {
             voice_profiles: RwLock::new(voice_profiles),
-            tts_engine: TTSEngine::new(),
+            backend,
+            cache: Mutex::new(VoiceCache::new(DEFAULT_VOICE_CACHE_CLIPS)),
+        }
+    }
+
+    /// Give a character a voice, or change the one it has.
+    pub async fn set_profile(&self, character_id: &str, profile: VoiceProfile) {
+        self.voice_profiles.write().await.insert(character_id.to_string(), profile);
+    }
+
+    /// The voice profile key for an Echo.
+    pub fn echo_character_id(echo: &EchoType) -> &'static str {
+        match echo {
+            EchoType::Lumi => "lumi",
+            EchoType::KAI => "kai",
+            EchoType::Terra => "terra",
+            EchoType::Ignis => "ignis",
         }
     }
 
+    /// Speak `text` in a character's voice. The same line in the same
+    /// voice and emotion is synthesized once and then served from cache.
     pub async fn synthesize_dialogue(
         &self,
         character_id: &str,
         text: &str,
         emotion: EmotionalState,
         context: DialogueContext,
-    ) -> Result<AudioStream, Box<dyn std::error::Error>> {
-        let profiles = self.voice_profiles.read().await;
-        let profile = profiles.get(character_id)
-            .ok_or("Character voice profile not found")?;
-
-        // Adjust voice parameters based on emotion
-        let adjusted_profile = self.adjust_for_emotion(profile, emotion);
+    ) -> Result<Arc<VoiceClip>, FinalverseError> {
+        let key = CacheKey {
+            text: text.to_string(),
+            character_id: character_id.to_string(),
+            emotion: format!("{:?}", emotion),
+        };
+        if let Some(clip) = self.cache.lock().unwrap().get(&key) {
+            return Ok(clip);
+        }
 
-        // Convert text to phonemes with melodic inflection
-        let phonemes = self.text_to_melodic_phonemes(text, &adjusted_profile, &context);
+        let profile = self
+            .voice_profiles
+            .read()
+            .await
+            .get(character_id)
+            .cloned()
+            .ok_or_else(|| FinalverseError::NotFound(format!("no voice for character '{}'", character_id)))?;
 
-        // Generate audio
+        // Adjust voice parameters based on emotion
+        let adjusted_profile = self.adjust_for_emotion(&profile, emotion.clone());
+
+        let request = TtsRequest {
+            text: text.to_string(),
+            phonemes: self.text_to_melodic_phonemes(text, &adjusted_profile, &context),
+            profile: adjusted_profile.clone(),
+            emotion,
+        };
         let audio_data = self
-            .tts_engine
-            .synthesize(phonemes, adjusted_profile.clone())
-            .await?;
+            .backend
+            .synthesize(&request)
+            .await
+            .map_err(|e| FinalverseError::upstream("tts", e))?;
 
         // Apply character-specific effects
-        let processed_audio = self.apply_character_effects(
-            audio_data,
-            character_id,
-            &adjusted_profile,
-        );
+        let processed_audio = self.apply_character_effects(audio_data, character_id, &adjusted_profile);
+
+        let clip = Arc::new(VoiceClip {
+            stream_id: uuid::Uuid::new_v4(),
+            character_id: character_id.to_string(),
+            duration: std::time::Duration::from_secs_f64(processed_audio.len() as f64 / VOICE_SAMPLE_RATE as f64),
+            samples: processed_audio,
+        });
+        self.cache.lock().unwrap().insert(key, clip.clone());
+        Ok(clip)
+    }
 
-        Ok(AudioStream {
-            id: uuid::Uuid::new_v4(),
-            data: processed_audio,
-            format: AudioFormat::default(),
-            metadata: AudioMetadata {
-                theme_id: format!("{}_dialogue", character_id),
-                duration: std::time::Duration::from_secs(text.len() as u64 / 10), // Rough estimate
-                loop_point: None,
-            },
-        })
+    /// A clip synthesized earlier, while it is still cached.
+    pub fn clip(&self, stream_id: uuid::Uuid) -> Option<Arc<VoiceClip>> {
+        self.cache.lock().unwrap().by_stream(stream_id)
     }
 
     fn adjust_for_emotion(&self, base_profile: &VoiceProfile, emotion: EmotionalState) -> VoiceProfile {
@@ -317,10 +381,13 @@ pub struct VoiceProfile {
     pub timbre: Timbre,
     pub melodic_inflection: f32,
     pub reverb: f32,
+    /// The voice the TTS backend is asked for.
+    pub voice_id: String,
     pub character_traits: Vec<String>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Timbre {
     Bright,
     Warm,
@@ -342,35 +409,298 @@ pub struct Phoneme {
     pub stress: bool,
 }
 
-pub struct TTSEngine {
-    // In production, this would interface with a real TTS system
+/// A spoken line, synthesized once and kept for retrieval.
+pub struct VoiceClip {
+    pub stream_id: uuid::Uuid,
+    pub character_id: String,
+    pub duration: std::time::Duration,
+    /// Mono at [`VOICE_SAMPLE_RATE`].
+    pub samples: Vec<f32>,
 }
 
-impl TTSEngine {
-    pub fn new() -> Self {
-        Self {}
+impl VoiceClip {
+    /// The clip as a 16-bit mono WAV file.
+    pub fn to_wav(&self) -> Result<Vec<u8>, hound::Error> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: VOICE_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec)?;
+        for sample in &self.samples {
+            writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+        }
+        writer.finalize()?;
+        Ok(wav.into_inner())
     }
+}
 
-    pub async fn synthesize(
-        &self,
-        phonemes: Vec<Phoneme>,
-        profile: VoiceProfile,
-    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
-        // Simplified synthesis - in production, use proper TTS
-        let sample_rate = 44100;
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    text: String,
+    character_id: String,
+    emotion: String,
+}
+
+/// Bounded clip cache that drops the least recently used clip when full.
+struct VoiceCache {
+    capacity: usize,
+    clips: HashMap<CacheKey, Arc<VoiceClip>>,
+    /// Least recently used first.
+    order: VecDeque<CacheKey>,
+}
+
+impl VoiceCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clips: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn touch(&mut self, key: &CacheKey) {
+        if let Some(index) = self.order.iter().position(|cached| cached == key) {
+            let key = self.order.remove(index).unwrap();
+            self.order.push_back(key);
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<Arc<VoiceClip>> {
+        let clip = self.clips.get(key).cloned()?;
+        self.touch(key);
+        Some(clip)
+    }
+
+    fn by_stream(&mut self, stream_id: uuid::Uuid) -> Option<Arc<VoiceClip>> {
+        let (key, clip) = self.clips.iter().find(|(_, clip)| clip.stream_id == stream_id)?;
+        let (key, clip) = (key.clone(), clip.clone());
+        self.touch(&key);
+        Some(clip)
+    }
+
+    fn insert(&mut self, key: CacheKey, clip: Arc<VoiceClip>) {
+        if self.clips.insert(key.clone(), clip).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.clips.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// What a backend is asked to speak.
+pub struct TtsRequest {
+    pub text: String,
+    pub phonemes: Vec<Phoneme>,
+    pub profile: VoiceProfile,
+    pub emotion: EmotionalState,
+}
+
+/// Turns text into mono samples at [`VOICE_SAMPLE_RATE`].
+#[async_trait::async_trait]
+pub trait TtsBackend: Send + Sync {
+    async fn synthesize(&self, request: &TtsRequest) -> anyhow::Result<Vec<f32>>;
+}
+
+/// Sings the phonemes as tones. Needs nothing external, so it is the
+/// fallback when no TTS service is configured.
+pub struct ToneBackend;
+
+#[async_trait::async_trait]
+impl TtsBackend for ToneBackend {
+    async fn synthesize(&self, request: &TtsRequest) -> anyhow::Result<Vec<f32>> {
+        let sample_rate = VOICE_SAMPLE_RATE as f32;
         let mut audio = Vec::new();
 
-        for phoneme in phonemes {
-            let samples = (phoneme.duration * sample_rate as f32) as usize;
-            let frequency = 220.0 * profile.pitch * phoneme.pitch; // Base frequency
+        for phoneme in &request.phonemes {
+            let samples = (phoneme.duration * sample_rate) as usize;
+            let frequency = 220.0 * request.profile.pitch * phoneme.pitch; // Base frequency
 
             for i in 0..samples {
-                let t = i as f32 / sample_rate as f32;
-                let sample = (2.0 * std::f32::consts::PI * frequency * t).sin() * 0.3;
-                audio.push(sample);
+                let t = i as f32 / sample_rate;
+                audio.push((2.0 * std::f32::consts::PI * frequency * t).sin() * 0.3);
             }
         }
 
         Ok(audio)
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+struct HttpTtsRequest<'a> {
+    text: &'a str,
+    voice: &'a str,
+    emotion: String,
+    pitch: f32,
+    speed: f32,
+    timbre: &'a Timbre,
+}
+
+/// Any TTS service that takes a JSON `{text, voice, emotion, pitch,
+/// speed, timbre}` POST and answers with a WAV file.
+pub struct HttpTtsBackend {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpTtsBackend {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TtsBackend for HttpTtsBackend {
+    async fn synthesize(&self, request: &TtsRequest) -> anyhow::Result<Vec<f32>> {
+        let body = HttpTtsRequest {
+            text: &request.text,
+            voice: &request.profile.voice_id,
+            emotion: format!("{:?}", request.emotion).to_lowercase(),
+            pitch: request.profile.pitch,
+            speed: request.profile.speed,
+            timbre: &request.profile.timbre,
+        };
+        let wav = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        decode_wav(&wav)
+    }
+}
+
+/// Mono samples at [`VOICE_SAMPLE_RATE`] from a WAV file of any rate,
+/// depth or channel count.
+pub fn decode_wav(wav: &[u8]) -> anyhow::Result<Vec<f32>> {
+    let mut reader = hound::WavReader::new(std::io::Cursor::new(wav))?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok(resample(&mono, spec.sample_rate, VOICE_SAMPLE_RATE))
+}
+
+#[derive(Deserialize)]
+struct SpeakRequest {
+    character_id: String,
+    text: String,
+    emotion: EmotionalState,
+    #[serde(default)]
+    is_question: bool,
+}
+
+#[derive(Serialize)]
+struct SpeakResponse {
+    stream_id: uuid::Uuid,
+    duration_ms: u64,
+}
+
+/// `POST /voice` speaks a line and answers with its stream id; clients
+/// fetch the audio from `GET /voice/{stream_id}` as WAV.
+pub fn routes(synth: Arc<VoiceSynthesizer>) -> Router {
+    Router::new()
+        .route("/voice", post(speak))
+        .route("/voice/:stream_id", get(voice_clip))
+        .with_state(synth)
+}
+
+async fn speak(
+    State(synth): State<Arc<VoiceSynthesizer>>,
+    Json(request): Json<SpeakRequest>,
+) -> Result<Json<SpeakResponse>, FinalverseError> {
+    let context = DialogueContext {
+        is_question: request.is_question,
+        is_emphasis: false,
+        emphasis_word_index: 0,
+        emotional_context: Vec::new(),
+    };
+    let clip = synth
+        .synthesize_dialogue(&request.character_id, &request.text, request.emotion, context)
+        .await?;
+    Ok(Json(SpeakResponse {
+        stream_id: clip.stream_id,
+        duration_ms: clip.duration.as_millis() as u64,
+    }))
+}
+
+async fn voice_clip(
+    State(synth): State<Arc<VoiceSynthesizer>>,
+    Path(stream_id): Path<uuid::Uuid>,
+) -> Result<Response, FinalverseError> {
+    let clip = synth
+        .clip(stream_id)
+        .ok_or_else(|| FinalverseError::NotFound(format!("no voice stream {}", stream_id)))?;
+    let wav = clip.to_wav().map_err(|e| FinalverseError::Internal(e.to_string()))?;
+    Ok(([(header::CONTENT_TYPE, "audio/wav")], wav).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counting(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl TtsBackend for Counting {
+        async fn synthesize(&self, request: &TtsRequest) -> anyhow::Result<Vec<f32>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            ToneBackend.synthesize(request).await
+        }
+    }
+
+    fn context() -> DialogueContext {
+        DialogueContext {
+            is_question: false,
+            is_emphasis: false,
+            emphasis_word_index: 0,
+            emotional_context: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn lines_are_cached_per_voice_and_emotion_and_served_as_wav() {
+        let backend = Arc::new(Counting(AtomicUsize::new(0)));
+        let synth = VoiceSynthesizer::with_backend(backend.clone());
+
+        let first = synth.synthesize_dialogue("lumi", "Hello!", EmotionalState::Joyful, context()).await.unwrap();
+        let again = synth.synthesize_dialogue("lumi", "Hello!", EmotionalState::Joyful, context()).await.unwrap();
+        assert_eq!(first.stream_id, again.stream_id);
+        synth.synthesize_dialogue("lumi", "Hello!", EmotionalState::Sad, context()).await.unwrap();
+        assert_eq!(backend.0.load(Ordering::SeqCst), 2);
+
+        assert!(matches!(
+            synth.synthesize_dialogue("nobody", "Hello!", EmotionalState::Sad, context()).await,
+            Err(FinalverseError::NotFound(_))
+        ));
+
+        let wav = synth.clip(first.stream_id).unwrap().to_wav().unwrap();
+        assert_eq!(decode_wav(&wav).unwrap().len(), first.samples.len());
+    }
+}
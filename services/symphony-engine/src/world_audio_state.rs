@@ -47,6 +47,11 @@ impl WorldAudioState {
         }
     }
 
+    /// Follow the shared world clock for day/night ambience.
+    pub fn set_world_time(&mut self, now: finalverse_core::WorldInstant) {
+        self.celestial_state.time_of_day = now.hour;
+    }
+
     pub fn get_active_regions(&self) -> Vec<&RegionAudioState> {
         self.regions.values().collect()
     }
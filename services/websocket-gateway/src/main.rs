@@ -1,167 +1,334 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Path, Query, State,
     },
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
 use finalverse_core::{
-    events::{FinalverseEvent, HarmonyEvent, SongEvent},
-    types::{Coordinates, EchoId, Melody, PlayerId, RegionId},
+    events::{HarmonyEvent, SongEvent},
+    types::{ClientCapabilities, PlayerId, RegionId},
+    StaminaStatus,
 };
 use futures::{stream::SplitSink, stream::SplitStream, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 use finalverse_logging as logging;
 use std::{
     collections::HashMap,
     net::SocketAddr,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tokio::sync::mpsc;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use uuid::Uuid;
-use reqwest;
-use finalverse_health::HealthMonitor;
-use service_registry::LocalServiceRegistry;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum WSMessage {
-    // Player Actions
-    SongweavingPerformed {
-        melody: Melody,
-        target: Coordinates,
-    },
-    EchoInteraction {
-        echo_id: EchoId,
-        interaction_type: String,
-    },
-    // Server Updates
-    WorldUpdate {
-        region: RegionId,
-        harmony_level: f32,
-    },
-    EventNotification {
-        event: FinalverseEvent,
-    },
-    // Connection
-    Connected {
-        player_id: PlayerId,
-    },
-    Error {
-        message: String,
-    },
-}
+use finalverse_health::{require_operator, DebugEndpoints, HealthMonitor, OperatorGuard, OPERATOR_TOKEN_HEADER};
+use finalverse_events::{Event, EventMetadata, EventType, GameEventBus, LocalEventBus, NatsEventBus, PlayerEvent, TenantEventBus};
+use finalverse_config::GatewaySection;
+use finalverse_protocol::{EncodedFrame, WireFormat};
+pub use finalverse_protocol::realtime::WSMessage;
+use finalverse_ratelimit::{axum::limit_requests, RateLimits};
+use service_registry::{LocalServiceRegistry, ServiceClient};
 
-#[derive(Debug, Clone)]
+mod sessions;
+use sessions::{Heartbeat, Outbox, ResumePolicy};
+
+#[derive(Clone)]
 pub struct GameState {
     players: HashMap<PlayerId, PlayerSession>,
+    resume_tokens: HashMap<String, PlayerId>,
+    resume_policy: ResumePolicy,
     harmony_levels: HashMap<RegionId, f32>,
+    motd: Option<String>,
+    logins_blocked: bool,
+    services: ServiceClient,
+    rate_limits: Arc<RateLimits>,
+    heartbeat: Heartbeat,
+    event_bus: Arc<dyn GameEventBus>,
+    /// Guards the operator routes, and signs song events for song-engine.
+    operator: OperatorGuard,
+    settings: GatewaySection,
 }
 
 #[derive(Debug, Clone)]
 pub struct PlayerSession {
     player_id: PlayerId,
     current_region: RegionId,
-    sender: Option<mpsc::UnboundedSender<WSMessage>>,
+    capabilities: ClientCapabilities,
+    outbox: Outbox,
+}
+
+impl PlayerSession {
+    /// Send a message to the player, downgrading it to match their declared
+    /// capabilities. Messages for a dropped connection are held until it
+    /// resumes. Returns false if nothing was delivered or queued.
+    pub fn deliver(&self, message: WSMessage) -> bool {
+        match message.downgrade_for(&self.capabilities) {
+            Some(message) => self.outbox.send(message),
+            None => false,
+        }
+    }
 }
 
 type SharedGameState = Arc<RwLock<GameState>>;
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(
+        services: ServiceClient,
+        resume_policy: ResumePolicy,
+        rate_limits: Arc<RateLimits>,
+        heartbeat: Heartbeat,
+        event_bus: Arc<dyn GameEventBus>,
+        operator: OperatorGuard,
+        settings: GatewaySection,
+    ) -> Self {
         Self {
             players: HashMap::new(),
+            resume_tokens: HashMap::new(),
+            resume_policy,
             harmony_levels: HashMap::new(),
+            motd: None,
+            logins_blocked: false,
+            services,
+            rate_limits,
+            heartbeat,
+            event_bus,
+            operator,
+            settings,
+        }
+    }
+
+    /// Find the session a resume token belongs to, or start a new one.
+    /// Either way the session gets a fresh token, so each token resumes
+    /// at most once.
+    fn open_session(&mut self, resume_token: Option<&str>) -> (PlayerSession, String, bool) {
+        let resumed = resume_token
+            .and_then(|token| self.resume_tokens.remove(token))
+            .and_then(|player_id| self.players.get(&player_id).cloned());
+        let is_resume = resumed.is_some();
+        let session = resumed.unwrap_or_else(|| {
+            let session = PlayerSession {
+                player_id: PlayerId(Uuid::new_v4()),
+                current_region: RegionId(Uuid::new_v4()),
+                capabilities: ClientCapabilities::default(),
+                outbox: Outbox::new(self.resume_policy.max_pending),
+            };
+            self.players.insert(session.player_id.clone(), session.clone());
+            session
+        });
+
+        let token = Uuid::new_v4().simple().to_string();
+        self.resume_tokens.insert(token.clone(), session.player_id.clone());
+        (session, token, is_resume)
+    }
+
+    /// Park the session for resuming, or end it if resuming is disabled.
+    fn close_connection(&mut self, player_id: &PlayerId, connection: Uuid) {
+        let Some(session) = self.players.get(player_id) else { return };
+        if session.outbox.detach(connection) && self.resume_policy.window.is_zero() {
+            self.remove_session(player_id);
         }
     }
+
+    fn remove_session(&mut self, player_id: &PlayerId) {
+        self.players.remove(player_id);
+        self.resume_tokens.retain(|_, owner| owner != player_id);
+    }
+
+    /// End sessions whose resume window has passed. Returns how many ended.
+    fn expire_detached(&mut self) -> usize {
+        let window = self.resume_policy.window;
+        let expired: Vec<PlayerId> = self
+            .players
+            .values()
+            .filter(|session| session.outbox.detached_for().is_some_and(|away| away >= window))
+            .map(|session| session.player_id.clone())
+            .collect();
+        for player_id in &expired {
+            self.remove_session(player_id);
+        }
+        expired.len()
+    }
 }
 
-#[derive(Serialize)]
-struct ServiceInfo {
-    name: String,
-    version: String,
-    status: String,
+#[derive(Debug, Deserialize)]
+pub struct ConnectParams {
+    /// Token from an earlier `Connected` message.
+    session: Option<String>,
+    /// `json` (the default) or `msgpack` for binary frames.
+    format: Option<String>,
 }
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Query(params): Query<ConnectParams>,
     State(state): State<SharedGameState>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_websocket(socket, state))
+    let format = match WireFormat::negotiate(params.format.as_deref()) {
+        Ok(format) => format,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    let (services, rate_limits, max_message_bytes) = {
+        let game_state = state.read().unwrap();
+        let enabled = &game_state.settings.wire_formats;
+        if !enabled.iter().any(|enabled| enabled.parse::<WireFormat>().is_ok_and(|enabled| enabled == format)) {
+            return (StatusCode::BAD_REQUEST, format!("Wire format {} is not enabled on this gateway", format)).into_response();
+        }
+        if game_state.logins_blocked {
+            return (StatusCode::SERVICE_UNAVAILABLE, "Server is entering maintenance").into_response();
+        }
+        // Calls made on behalf of this session carry its trace headers
+        let services = game_state.services.propagating(
+            headers
+                .iter()
+                .filter_map(|(name, value)| value.to_str().ok().map(|value| (name.as_str(), value))),
+        );
+        (services, game_state.rate_limits.clone(), game_state.settings.max_message_bytes)
+    };
+    ws.max_message_size(max_message_bytes).on_upgrade(move |socket| handle_websocket(socket, state, services, rate_limits, params.session, format))
 }
 
-async fn handle_websocket(socket: WebSocket, state: SharedGameState) {
+async fn handle_websocket(
+    socket: WebSocket,
+    state: SharedGameState,
+    services: ServiceClient,
+    rate_limits: Arc<RateLimits>,
+    resume_token: Option<String>,
+    format: WireFormat,
+) {
     let (sender, receiver) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel();
+    let connection = Uuid::new_v4();
 
-    // Generate a unique player ID
-    let player_id = PlayerId(Uuid::new_v4());
-
-    // Add player to game state
-    {
-        let mut game_state = state.write().unwrap();
-        game_state.players.insert(
-            player_id.clone(),
-            PlayerSession {
-                player_id: player_id.clone(),
-                current_region: RegionId(Uuid::new_v4()),
-                sender: Some(tx.clone()),
-            },
-        );
-    }
+    let (session, session_token, resumed) = state.write().unwrap().open_session(resume_token.as_deref());
+    let (heartbeat, event_bus) = {
+        let game_state = state.read().unwrap();
+        (game_state.heartbeat, game_state.event_bus.clone())
+    };
+    let player_id = session.player_id.clone();
 
-    // Send connection confirmation
+    // Confirm the connection before replaying anything missed while away
     let _ = tx.send(WSMessage::Connected {
         player_id: player_id.clone(),
+        session_token,
+        resumed,
     });
+    if let Some(motd) = state.read().unwrap().motd.clone() {
+        let _ = tx.send(WSMessage::TextDescription { text: motd });
+    }
+    let replayed = session.outbox.attach(connection, tx.clone());
+    if resumed {
+        info!("Player {} resumed their session; replayed {} events", player_id.0, replayed);
+    }
 
-    // Spawn task to handle outgoing messages
+    // Spawn task to handle outgoing messages, pinging between them
     let mut sender = sender;
     tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            if let Ok(json_msg) = serde_json::to_string(&msg) {
-                if sender.send(Message::Text(json_msg)).await.is_err() {
-                    break;
+        let mut ping = tokio::time::interval(heartbeat.ping_interval);
+        ping.reset();
+        loop {
+            let msg = tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = ping.tick() => {
+                    if sender.send(Message::Ping(Vec::new())).await.is_err() {
+                        break;
+                    }
+                    continue;
                 }
+            };
+            let closing = matches!(msg, WSMessage::ServerClosing { .. });
+            let frame = match format.encode(&msg) {
+                Ok(EncodedFrame::Text(text)) => Message::Text(text),
+                Ok(EncodedFrame::Binary(bytes)) => Message::Binary(bytes),
+                Err(e) => {
+                    warn!("Dropping message that failed to encode: {}", e);
+                    continue;
+                }
+            };
+            if sender.send(frame).await.is_err() {
+                break;
+            }
+            if closing {
+                let _ = sender.send(Message::Close(None)).await;
+                break;
             }
         }
     });
 
-    // Handle incoming messages
+    // Handle incoming messages. Any frame, pongs included, proves the
+    // socket is alive; silence past the idle timeout means it isn't.
     let mut receiver = receiver;
-    while let Some(msg) = receiver.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Ok(ws_message) = serde_json::from_str::<WSMessage>(&text) {
-                    handle_message(ws_message, &state, &player_id, &tx).await;
-                }
+    loop {
+        let msg = match tokio::time::timeout(heartbeat.idle_timeout, receiver.next()).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(_) => {
+                info!("Player {} went silent for {:?}; dropping the connection", player_id.0, heartbeat.idle_timeout);
+                break;
             }
+        };
+        // Text frames are always JSON; binary frames use the negotiated format
+        let decoded = match msg {
+            Ok(Message::Text(text)) => WireFormat::decode_text::<WSMessage>(&text),
+            Ok(Message::Binary(bytes)) => format.decode::<WSMessage>(&bytes),
             Ok(Message::Close(_)) => {
                 break;
             }
-            _ => {}
+            _ => continue,
+        };
+        if let Ok(ws_message) = decoded {
+            // Player actions fan out to other services, so they share the player's HTTP limit
+            if let Err(e) = rate_limits.check(None, Some(&player_id)) {
+                let _ = tx.send(WSMessage::Error { message: e.to_string() });
+                continue;
+            }
+            handle_message(ws_message, &state, &services, &player_id, &tx).await;
         }
     }
 
-    // Remove player from state when disconnected
-    {
-        let mut game_state = state.write().unwrap();
-        game_state.players.remove(&player_id);
+    state.write().unwrap().close_connection(&player_id, connection);
+    publish_disconnected(&event_bus, &player_id).await;
+}
+
+/// Lets world and harmony services react to a drop without waiting for the
+/// resume window to end.
+async fn publish_disconnected(event_bus: &Arc<dyn GameEventBus>, player_id: &PlayerId) {
+    let event = Event::new(EventType::Player(PlayerEvent::Disconnected {
+        player_id: player_id.clone(),
+    }))
+    .with_metadata(EventMetadata {
+        source: Some("websocket-gateway".to_string()),
+        ..Default::default()
+    });
+    if let Err(e) = event_bus.publish(event).await {
+        warn!("Failed to publish disconnect for player {}: {}", player_id.0, e);
     }
 }
 
 async fn handle_message(
     message: WSMessage,
     state: &SharedGameState,
+    services: &ServiceClient,
     player_id: &PlayerId,
     tx: &mpsc::UnboundedSender<WSMessage>,
 ) {
     match message {
+        WSMessage::Handshake { capabilities } => {
+            info!("Player {} declared capabilities: {:?}", player_id.0, capabilities);
+            if let Some(session) = state.write().unwrap().players.get_mut(player_id) {
+                session.capabilities = capabilities.clone();
+            }
+            let _ = tx.send(WSMessage::HandshakeAccepted { capabilities });
+        }
         WSMessage::SongweavingPerformed { melody, target } => {
             // Process songweaving action
             let harmony_event = HarmonyEvent::ResonanceGained {
@@ -171,7 +338,8 @@ async fn handle_message(
             };
 
             // Send to Song Engine
-            send_to_song_engine(SongEvent::MelodyWoven {
+            let operator = state.read().unwrap().operator.clone();
+            send_to_song_engine(services, &operator, SongEvent::MelodyWoven {
                 player_id: player_id.clone(),
                 melody: melody.clone(),
                 target,
@@ -186,6 +354,15 @@ async fn handle_message(
                 region: RegionId(Uuid::new_v4()),
                 harmony_level: 0.75,
             });
+
+            // Keep the stamina bar current whether or not the melody landed
+            let path = format!("/api/stamina/{}", player_id.0);
+            match services.get_json::<StaminaStatus>("song-engine", &path).await {
+                Ok(stamina) => {
+                    let _ = tx.send(WSMessage::StaminaUpdate { stamina });
+                }
+                Err(e) => warn!("Failed to fetch stamina for player {}: {}", player_id.0, e),
+            }
         }
         WSMessage::EchoInteraction {
             echo_id,
@@ -201,24 +378,22 @@ async fn handle_message(
     }
 }
 
-async fn send_to_song_engine(event: SongEvent) {
-    // In a real implementation, this would send to the Song Engine service
+/// song-engine only takes events from services, which name the player
+/// themselves, so they carry the operator token.
+async fn send_to_song_engine(services: &ServiceClient, operator: &OperatorGuard, event: SongEvent) {
+    let Some(token) = operator.token() else {
+        warn!("Not sending {:?} to Song Engine: no operator token is configured", event);
+        return;
+    };
     info!("Sending to Song Engine: {:?}", event);
 
-    // For now, simulate with HTTP call
-    let client = reqwest::Client::new();
-    let response = client
-        .post("http://localhost:3001/api/events")
-        .json(&event)
-        .send()
-        .await;
-
-    if let Ok(response) = response {
-        if response.status().is_success() {
-            if let Ok(data) = response.json::<serde_json::Value>().await {
-                info!("Song Engine response: {:?}", data);
-            }
-        }
+    match services
+        .with_header(OPERATOR_TOKEN_HEADER, token)
+        .post_json::<_, serde_json::Value>("song-engine", "/api/events", &event)
+        .await
+    {
+        Ok(data) => info!("Song Engine response: {:?}", data),
+        Err(e) => warn!("Failed to send event to Song Engine: {}", e),
     }
 }
 
@@ -234,40 +409,202 @@ async fn broadcast_harmony_update(state: &SharedGameState, region: &RegionId, le
     };
 
     for (_, player_session) in players {
-        if let Some(sender) = &player_session.sender {
-            let _ = sender.send(update_message.clone());
-        }
+        player_session.deliver(update_message.clone());
+    }
+}
+
+async fn get_capabilities(
+    State(state): State<SharedGameState>,
+    Path(player_id): Path<Uuid>,
+) -> Result<Json<ClientCapabilities>, StatusCode> {
+    let game_state = state.read().unwrap();
+    game_state
+        .players
+        .get(&PlayerId(player_id))
+        .map(|session| Json(session.capabilities.clone()))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+#[derive(Serialize)]
+struct DeliveryResult {
+    delivered: bool,
+    downgraded: bool,
+}
+
+/// Lets services push a payload to a player; the gateway applies the
+/// capability downgrade so services don't have to. Needs the operator
+/// token.
+async fn deliver_to_player(
+    State(state): State<SharedGameState>,
+    Path(player_id): Path<Uuid>,
+    Json(message): Json<WSMessage>,
+) -> Result<Json<DeliveryResult>, StatusCode> {
+    let session = {
+        let game_state = state.read().unwrap();
+        game_state.players.get(&PlayerId(player_id)).cloned()
     }
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    let downgraded = message
+        .required_feature()
+        .is_some_and(|feature| !session.capabilities.supports(feature));
+    let delivered = session.deliver(message);
+
+    Ok(Json(DeliveryResult { delivered, downgraded }))
 }
 
 
+#[derive(Debug, Deserialize)]
+struct MaintenanceNoticeRequest {
+    message: String,
+    seconds_remaining: u64,
+    #[serde(default)]
+    block_logins: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DrainRequest {
+    reason: String,
+}
+
+async fn get_motd(State(state): State<SharedGameState>) -> Json<serde_json::Value> {
+    let game_state = state.read().unwrap();
+    Json(serde_json::json!({
+        "motd": game_state.motd,
+        "logins_blocked": game_state.logins_blocked,
+    }))
+}
+
+/// Broadcast a maintenance countdown notice and pin it as the MOTD.
+async fn maintenance_notice(
+    State(state): State<SharedGameState>,
+    Json(request): Json<MaintenanceNoticeRequest>,
+) -> Json<serde_json::Value> {
+    let players = {
+        let mut game_state = state.write().unwrap();
+        game_state.motd = Some(request.message.clone());
+        game_state.logins_blocked = request.block_logins;
+        game_state.players.clone()
+    };
+
+    let notice = WSMessage::MaintenanceNotice {
+        message: request.message,
+        seconds_remaining: request.seconds_remaining,
+    };
+    let notified = players.values().filter(|session| session.deliver(notice.clone())).count();
+
+    Json(serde_json::json!({ "notified": notified }))
+}
+
+/// Disconnect every session after telling the client why.
+async fn maintenance_drain(
+    State(state): State<SharedGameState>,
+    Json(request): Json<DrainRequest>,
+) -> Json<serde_json::Value> {
+    let players = {
+        let mut game_state = state.write().unwrap();
+        game_state.logins_blocked = true;
+        game_state.players.clone()
+    };
+
+    let closing = WSMessage::ServerClosing { reason: request.reason };
+    let drained = players.values().filter(|session| session.deliver(closing.clone())).count();
+    info!("Draining {} sessions for maintenance", drained);
+
+    Json(serde_json::json!({ "drained": drained }))
+}
+
+async fn maintenance_clear(State(state): State<SharedGameState>) -> Json<serde_json::Value> {
+    let mut game_state = state.write().unwrap();
+    game_state.motd = None;
+    game_state.logins_blocked = false;
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     logging::init(None);
 
-    let state = Arc::new(RwLock::new(GameState::new()));
+    let settings = finalverse_config::load_default_config()
+        .map(|config| config.gateway)
+        .unwrap_or_default();
     let monitor = Arc::new(HealthMonitor::new("websocket-gateway", env!("CARGO_PKG_VERSION")));
     let registry = LocalServiceRegistry::new();
     registry
-        .register_service("websocket-gateway".to_string(), "http://localhost:3000".to_string())
+        .register_service("websocket-gateway".to_string(), format!("http://localhost:{}", settings.websocket_port))
         .await;
+    let sessions = finalverse_config::load_default_config()
+        .map(|config| config.security.sessions)
+        .unwrap_or_default();
+    let resume_policy = ResumePolicy::from_config(&sessions);
+    let heartbeat = finalverse_config::load_default_config()
+        .map(|config| Heartbeat::from_config(&config.network.heartbeat))
+        .unwrap_or_default();
+    let rate_limits = Arc::new(RateLimits::load());
+    let event_bus: Arc<dyn GameEventBus> = if let Ok(nats_url) = std::env::var("NATS_URL") {
+        info!("📡 Connecting to NATS at {}", nats_url);
+        Arc::new(NatsEventBus::new(&nats_url).await?)
+    } else {
+        info!("📦 Using local event bus");
+        Arc::new(LocalEventBus::new())
+    };
+    let event_bus = TenantEventBus::from_env(event_bus);
+    let operator = OperatorGuard::load();
+    let state = Arc::new(RwLock::new(GameState::new(
+        ServiceClient::new(registry),
+        resume_policy,
+        rate_limits.clone(),
+        heartbeat,
+        event_bus,
+        operator.clone(),
+        settings.clone(),
+    )));
+
+    if !resume_policy.window.is_zero() {
+        let state = state.clone();
+        let sweep_every = (resume_policy.window / 4).max(Duration::from_secs(1));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sweep_every);
+            loop {
+                interval.tick().await;
+                let expired = state.write().unwrap().expire_detached();
+                if expired > 0 {
+                    info!("Ended {} sessions that were not resumed in time", expired);
+                }
+            }
+        });
+    }
+
+    // Reaching into players' sessions and maintenance are for services and
+    // operators only
+    let operator_routes = Router::new()
+        .route("/sessions/:player_id/deliver", post(deliver_to_player))
+        .route("/maintenance/notice", post(maintenance_notice))
+        .route("/maintenance/drain", post(maintenance_drain))
+        .route("/maintenance/clear", post(maintenance_clear))
+        .route_layer(axum::middleware::from_fn_with_state(operator, require_operator));
 
     let app = Router::new()
         .route("/ws", get(websocket_handler))
+        .route("/sessions/:player_id/capabilities", get(get_capabilities))
+        .merge(operator_routes)
+        .route("/motd", get(get_motd))
+        .layer(axum::middleware::from_fn_with_state(rate_limits, limit_requests))
         .with_state(state.clone())
         .merge(monitor.clone().axum_routes())
+        .merge(Arc::new(DebugEndpoints::load("websocket-gateway")).axum_routes())
         .layer(
             ServiceBuilder::new()
                 .layer(CorsLayer::permissive())
                 .into_inner(),
         );
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+    let addr = SocketAddr::from(([0, 0, 0, 0], settings.websocket_port));
     info!("WebSocket Gateway listening on {}", addr);
 
     // Use axum::serve instead of the deprecated Server
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
\ No newline at end of file
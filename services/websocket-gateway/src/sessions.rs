@@ -0,0 +1,184 @@
+// services/websocket-gateway/src/sessions.rs
+//! Resumable sessions. When a socket drops, its session stays parked for
+//! the resume window and queues what would have been sent, so a client
+//! that reconnects with its session token keeps its player, region and
+//! missed events.
+
+use finalverse_config::{HeartbeatConfig, SessionPolicyConfig};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::WSMessage;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResumePolicy {
+    pub window: Duration,
+    pub max_pending: usize,
+}
+
+impl ResumePolicy {
+    pub fn from_config(sessions: &SessionPolicyConfig) -> Self {
+        Self {
+            window: Duration::from_secs(sessions.resume_window_secs),
+            max_pending: sessions.max_pending_events,
+        }
+    }
+}
+
+impl Default for ResumePolicy {
+    fn default() -> Self {
+        Self::from_config(&SessionPolicyConfig::default())
+    }
+}
+
+/// Pings keep a quiet connection's socket honest; one that sends nothing
+/// for `idle_timeout` is treated as dropped and its session parked.
+#[derive(Debug, Clone, Copy)]
+pub struct Heartbeat {
+    pub ping_interval: Duration,
+    pub idle_timeout: Duration,
+}
+
+impl Heartbeat {
+    pub fn from_config(heartbeat: &HeartbeatConfig) -> Self {
+        Self {
+            ping_interval: Duration::from_secs(heartbeat.ping_interval_secs),
+            idle_timeout: Duration::from_secs(heartbeat.idle_timeout_secs),
+        }
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::from_config(&HeartbeatConfig::default())
+    }
+}
+
+/// Where a session's messages go: the live socket when attached,
+/// otherwise a bounded queue. Clones share the same outbox.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    inner: Arc<Mutex<OutboxState>>,
+}
+
+#[derive(Debug)]
+struct OutboxState {
+    /// The attached socket and the connection it belongs to.
+    socket: Option<(Uuid, mpsc::UnboundedSender<WSMessage>)>,
+    pending: VecDeque<WSMessage>,
+    max_pending: usize,
+    detached_at: Option<Instant>,
+}
+
+impl Outbox {
+    pub fn new(max_pending: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(OutboxState {
+                socket: None,
+                pending: VecDeque::new(),
+                max_pending,
+                detached_at: None,
+            })),
+        }
+    }
+
+    /// Send to the socket, or queue while detached. Returns false only if
+    /// the message was lost.
+    pub fn send(&self, message: WSMessage) -> bool {
+        let mut state = self.inner.lock().unwrap();
+        if let Some((_, socket)) = &state.socket {
+            if socket.send(message.clone()).is_ok() {
+                return true;
+            }
+            // The socket closed before the connection handler noticed
+            state.socket = None;
+            state.detached_at.get_or_insert_with(Instant::now);
+        }
+        if state.max_pending == 0 {
+            return false;
+        }
+        if state.pending.len() >= state.max_pending {
+            state.pending.pop_front();
+        }
+        state.pending.push_back(message);
+        true
+    }
+
+    /// Route messages to a new connection, first flushing what queued up
+    /// while detached. Replaces any connection still attached.
+    pub fn attach(&self, connection: Uuid, socket: mpsc::UnboundedSender<WSMessage>) -> usize {
+        let mut state = self.inner.lock().unwrap();
+        let flushed = state.pending.len();
+        for message in state.pending.drain(..) {
+            let _ = socket.send(message);
+        }
+        state.socket = Some((connection, socket));
+        state.detached_at = None;
+        flushed
+    }
+
+    /// Start queueing, unless another connection has taken over since.
+    pub fn detach(&self, connection: Uuid) -> bool {
+        let mut state = self.inner.lock().unwrap();
+        match &state.socket {
+            Some((attached, _)) if *attached != connection => false,
+            _ => {
+                state.socket = None;
+                state.detached_at.get_or_insert_with(Instant::now);
+                true
+            }
+        }
+    }
+
+    /// How long the session has been without a connection.
+    pub fn detached_for(&self) -> Option<Duration> {
+        self.inner.lock().unwrap().detached_at.map(|at| at.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> WSMessage {
+        WSMessage::TextDescription { text: text.to_string() }
+    }
+
+    fn drain(rx: &mut mpsc::UnboundedReceiver<WSMessage>) -> Vec<String> {
+        let mut texts = Vec::new();
+        while let Ok(WSMessage::TextDescription { text }) = rx.try_recv() {
+            texts.push(text);
+        }
+        texts
+    }
+
+    #[test]
+    fn detached_outbox_keeps_the_latest_events_for_the_next_connection() {
+        let outbox = Outbox::new(2);
+        let first = Uuid::new_v4();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        outbox.attach(first, tx);
+        assert!(outbox.send(text("live")));
+        assert_eq!(drain(&mut rx), vec!["live"]);
+
+        assert!(outbox.detach(first));
+        assert!(outbox.detached_for().is_some());
+        for message in ["one", "two", "three"] {
+            assert!(outbox.send(text(message)));
+        }
+
+        let second = Uuid::new_v4();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        assert_eq!(outbox.attach(second, tx), 2);
+        assert_eq!(drain(&mut rx), vec!["two", "three"]);
+        assert!(outbox.detached_for().is_none());
+
+        // The first socket closing late doesn't detach its replacement
+        assert!(!outbox.detach(first));
+        assert!(outbox.send(text("still live")));
+        assert_eq!(drain(&mut rx), vec!["still live"]);
+    }
+}
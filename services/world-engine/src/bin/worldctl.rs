@@ -0,0 +1,99 @@
+// services/world-engine/src/bin/worldctl.rs
+//! Export a running world to a scenario file and load scenario files into
+//! another world-engine through its admin API.
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use finalverse_health::OPERATOR_TOKEN_HEADER;
+use std::path::PathBuf;
+use world_engine::{ImportSummary, WorldScenario};
+
+#[derive(Parser)]
+#[command(name = "worldctl")]
+#[command(about = "Export and import Finalverse world scenarios")]
+struct Cli {
+    /// world-engine HTTP API
+    #[arg(long, env = "WORLD_ENGINE_URL", default_value = "http://localhost:3002")]
+    url: String,
+
+    /// Operator token, needed to export and import
+    #[arg(long, env = "FINALVERSE_OPERATOR_TOKEN", hide_env_values = true)]
+    operator_token: Option<String>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Write the current world to a scenario file
+    Export {
+        file: PathBuf,
+        /// Name stored in the file to tell scenarios apart
+        #[arg(short, long)]
+        label: Option<String>,
+    },
+    /// Replace the world with a scenario file
+    Import { file: PathBuf },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client = reqwest::Client::new();
+    let base = cli.url.trim_end_matches('/');
+
+    match cli.command {
+        Commands::Export { file, label } => {
+            let mut request = client.get(format!("{}/admin/world/export", base));
+            if let Some(label) = &label {
+                request = request.query(&[("label", label)]);
+            }
+            if let Some(token) = &cli.operator_token {
+                request = request.header(OPERATOR_TOKEN_HEADER, token);
+            }
+            let scenario: WorldScenario = check(request.send().await?).await?.json().await?;
+            let json = serde_json::to_string_pretty(&scenario)?;
+            std::fs::write(&file, json).with_context(|| format!("writing {}", file.display()))?;
+            println!(
+                "Exported {} regions and {} species to {}",
+                scenario.regions.len(),
+                scenario.species.len(),
+                file.display()
+            );
+        }
+        Commands::Import { file } => {
+            let json = std::fs::read_to_string(&file).with_context(|| format!("reading {}", file.display()))?;
+            // Catch bad files here rather than after uploading them
+            let scenario: WorldScenario =
+                serde_json::from_str(&json).with_context(|| format!("{} is not a world scenario", file.display()))?;
+            scenario.validate()?;
+            let mut request = client.post(format!("{}/admin/world/import", base)).json(&scenario);
+            if let Some(token) = &cli.operator_token {
+                request = request.header(OPERATOR_TOKEN_HEADER, token);
+            }
+            let response = request.send().await?;
+            let summary: ImportSummary = check(response).await?.json().await?;
+            println!(
+                "Imported {} ({} regions, {} species, {} active events)",
+                summary.label.as_deref().unwrap_or("unlabelled scenario"),
+                summary.region_count,
+                summary.species_count,
+                summary.active_event_count
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    match body.get("error").and_then(|error| error.as_str()) {
+        Some(error) => bail!("world-engine answered {}: {}", status, error),
+        None => bail!("world-engine answered {}", status),
+    }
+}
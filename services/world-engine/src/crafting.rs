@@ -0,0 +1,277 @@
+// services/world-engine/src/crafting.rs
+//! Player inventories as a service: granting, crafting and using items,
+//! with inventories kept in Redis and `ItemCrafted` / `ItemConsumed`
+//! published for story-engine's chronicles.
+
+use crate::inventory::{Catalog, Inventory};
+use async_trait::async_trait;
+use finalverse_core::types::ItemType;
+use finalverse_core::{FinalverseError, TenantId};
+use finalverse_events::{Event, EventMetadata, EventType, GameEventBus, PlayerEvent, PlayerId};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::Deserialize;
+use service_registry::ServiceClient;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{OnceCell, RwLock};
+
+/// Where inventories outlive restarts.
+#[async_trait]
+pub trait InventoryStore: Send + Sync {
+    async fn load(&self, player_id: &str) -> anyhow::Result<Option<Inventory>>;
+    async fn save(&self, player_id: &str, inventory: &Inventory) -> anyhow::Result<()>;
+}
+
+/// Inventories in Redis, one JSON value per player, scoped to the tenant
+/// this process serves.
+pub struct RedisInventoryStore {
+    client: redis::Client,
+    /// Made on first use so world-engine starts without Redis.
+    connection: OnceCell<ConnectionManager>,
+    tenant: TenantId,
+}
+
+impl RedisInventoryStore {
+    pub fn new(client: redis::Client, tenant: TenantId) -> Self {
+        Self {
+            client,
+            connection: OnceCell::new(),
+            tenant,
+        }
+    }
+
+    fn key(&self, player_id: &str) -> String {
+        self.tenant.scoped_key(&format!("inventory:{}", player_id))
+    }
+
+    async fn connection(&self) -> redis::RedisResult<ConnectionManager> {
+        Ok(self
+            .connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await?
+            .clone())
+    }
+}
+
+#[async_trait]
+impl InventoryStore for RedisInventoryStore {
+    async fn load(&self, player_id: &str) -> anyhow::Result<Option<Inventory>> {
+        let mut connection = self.connection().await?;
+        let json: Option<String> = connection.get(self.key(player_id)).await?;
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn save(&self, player_id: &str, inventory: &Inventory) -> anyhow::Result<()> {
+        let mut connection = self.connection().await?;
+        let _: () = connection.set(self.key(player_id), serde_json::to_string(inventory)?).await?;
+        Ok(())
+    }
+}
+
+/// A player's attunement tier, which recipes are gated by.
+#[async_trait]
+pub trait TierLookup: Send + Sync {
+    async fn tier(&self, player_id: &str) -> Result<u32, FinalverseError>;
+}
+
+#[derive(Deserialize)]
+struct Progress {
+    attunement_tier: u32,
+}
+
+/// Asks harmony-service, which owns attunement.
+pub struct HarmonyTiers(pub ServiceClient);
+
+#[async_trait]
+impl TierLookup for HarmonyTiers {
+    async fn tier(&self, player_id: &str) -> Result<u32, FinalverseError> {
+        let path = format!("/progress/{}", player_id);
+        match self.0.get_json::<Progress>("harmony-service", &path).await {
+            Ok(progress) => Ok(progress.attunement_tier),
+            Err(e) => Err(FinalverseError::upstream("harmony-service", e)),
+        }
+    }
+}
+
+pub struct InventoryService {
+    catalog: Catalog,
+    inventories: RwLock<HashMap<String, Inventory>>,
+    store: Option<Arc<dyn InventoryStore>>,
+    event_bus: Option<Arc<dyn GameEventBus>>,
+    /// Without one every player crafts as tier 0.
+    tiers: Option<Arc<dyn TierLookup>>,
+}
+
+impl Default for InventoryService {
+    fn default() -> Self {
+        Self::new(Catalog::default())
+    }
+}
+
+impl InventoryService {
+    pub fn new(catalog: Catalog) -> Self {
+        Self {
+            catalog,
+            inventories: RwLock::new(HashMap::new()),
+            store: None,
+            event_bus: None,
+            tiers: None,
+        }
+    }
+
+    pub fn with_store(self, store: Arc<dyn InventoryStore>) -> Self {
+        Self { store: Some(store), ..self }
+    }
+
+    pub fn with_event_bus(self, event_bus: Arc<dyn GameEventBus>) -> Self {
+        Self { event_bus: Some(event_bus), ..self }
+    }
+
+    pub fn with_tiers(self, tiers: Arc<dyn TierLookup>) -> Self {
+        Self { tiers: Some(tiers), ..self }
+    }
+
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    pub async fn inventory(&self, player_id: &str) -> Result<Inventory, FinalverseError> {
+        if let Some(inventory) = self.inventories.read().await.get(player_id) {
+            return Ok(inventory.clone());
+        }
+        let stored = match &self.store {
+            Some(store) => store
+                .load(player_id)
+                .await
+                .map_err(|e| FinalverseError::Unavailable(format!("inventory store: {}", e)))?,
+            None => None,
+        };
+        let inventory = stored.unwrap_or_default();
+        Ok(self
+            .inventories
+            .write()
+            .await
+            .entry(player_id.to_string())
+            .or_insert(inventory)
+            .clone())
+    }
+
+    /// Apply `change` to a player's inventory and store the result. The
+    /// inventory is left as it was if `change` fails.
+    async fn update(
+        &self,
+        player_id: &str,
+        change: impl FnOnce(&mut Inventory) -> Result<(), FinalverseError>,
+    ) -> Result<Inventory, FinalverseError> {
+        let mut inventory = self.inventory(player_id).await?;
+        let mut inventories = self.inventories.write().await;
+        // Another request may have changed it while it was loading
+        if let Some(current) = inventories.get(player_id) {
+            inventory = current.clone();
+        }
+        change(&mut inventory)?;
+        inventories.insert(player_id.to_string(), inventory.clone());
+        drop(inventories);
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save(player_id, &inventory).await {
+                tracing::warn!("Failed to store the inventory of {}: {}", player_id, e);
+            }
+        }
+        Ok(inventory)
+    }
+
+    /// Give a player items, from quests, loot or admins.
+    pub async fn grant(&self, player_id: &str, item_id: &str, quantity: u32) -> Result<Inventory, FinalverseError> {
+        let item = self.catalog.item(item_id)?;
+        self.update(player_id, |inventory| inventory.add(item, quantity)).await
+    }
+
+    pub async fn craft(&self, player_id: &str, recipe_id: &str) -> Result<Inventory, FinalverseError> {
+        let recipe = self.catalog.recipe(recipe_id)?;
+        let tier = match &self.tiers {
+            Some(tiers) if recipe.required_tier > 0 => tiers.tier(player_id).await?,
+            _ => 0,
+        };
+        let inventory = self
+            .update(player_id, |inventory| inventory.craft(&self.catalog, recipe, tier))
+            .await?;
+
+        let item = self.catalog.item(&recipe.output.item_id)?;
+        self.publish(PlayerEvent::ItemCrafted {
+            player_id: PlayerId::from_legacy(player_id),
+            recipe_id: recipe.id.clone(),
+            item_id: item.id.clone(),
+            item_name: item.name.clone(),
+            quantity: recipe.output.quantity,
+        })
+        .await;
+        Ok(inventory)
+    }
+
+    /// Use up consumable items.
+    pub async fn consume(&self, player_id: &str, item_id: &str, quantity: u32) -> Result<Inventory, FinalverseError> {
+        let item = self.catalog.item(item_id)?;
+        if !matches!(item.item_type, ItemType::Consumable) {
+            return Err(FinalverseError::BadRequest(format!("{} can't be used up", item.name)));
+        }
+        if quantity == 0 {
+            return Err(FinalverseError::BadRequest("quantity must be at least 1".to_string()));
+        }
+        let inventory = self.update(player_id, |inventory| inventory.remove(item_id, quantity)).await?;
+
+        self.publish(PlayerEvent::ItemConsumed {
+            player_id: PlayerId::from_legacy(player_id),
+            item_id: item.id.clone(),
+            item_name: item.name.clone(),
+            quantity,
+        })
+        .await;
+        Ok(inventory)
+    }
+
+    async fn publish(&self, event: PlayerEvent) {
+        let Some(event_bus) = &self.event_bus else {
+            return;
+        };
+        let event = Event::new(EventType::Player(event)).with_metadata(EventMetadata {
+            source: Some("world-engine".to_string()),
+            ..Default::default()
+        });
+        if let Err(e) = event_bus.publish(event).await {
+            tracing::warn!("Failed to publish inventory event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedTier(u32);
+
+    #[async_trait]
+    impl TierLookup for FixedTier {
+        async fn tier(&self, _player_id: &str) -> Result<u32, FinalverseError> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn crafting_asks_for_the_players_tier_and_only_consumables_are_used() {
+        let service = InventoryService::default().with_tiers(Arc::new(FixedTier(1)));
+        service.grant("kael", "echo-shard", 7).await.unwrap();
+        service.grant("kael", "resonant-reed", 3).await.unwrap();
+
+        service.craft("kael", "song-flute").await.unwrap();
+        service.craft("kael", "harmony-tonic").await.unwrap();
+        assert!(matches!(service.craft("kael", "lumi-lantern").await, Err(FinalverseError::Forbidden(_))));
+
+        assert!(matches!(service.consume("kael", "song-flute", 1).await, Err(FinalverseError::BadRequest(_))));
+        let inventory = service.consume("kael", "harmony-tonic", 1).await.unwrap();
+        assert_eq!(inventory.count("echo-shard"), 4);
+        assert_eq!(inventory.count("harmony-tonic"), 0);
+        assert_eq!(service.inventory("kael").await.unwrap(), inventory);
+    }
+}
@@ -0,0 +1,366 @@
+// services/world-engine/src/ecs.rs
+//! A small entity component system for dynamic world objects. Entities
+//! are ids; components are plain serde types stored per type, so a
+//! system can walk every door or harvestable without knowing what else
+//! an entity carries. Each component serializes under its `NAME`, which
+//! lets an entity stream to clients as one JSON object and be spawned
+//! back from one.
+
+use chrono::{DateTime, Utc};
+use finalverse_core::FinalverseError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EntityId(pub u64);
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub trait Component: Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    /// Key the component appears under in an [`EntitySnapshot`].
+    const NAME: &'static str;
+}
+
+/// Storage for one component type, with the type erased so `Entities`
+/// can hold every kind side by side.
+trait Column: Send + Sync {
+    fn remove(&mut self, id: EntityId);
+    fn to_json(&self, id: EntityId) -> Option<Value>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<C: Component> Column for BTreeMap<EntityId, C> {
+    fn remove(&mut self, id: EntityId) {
+        BTreeMap::remove(self, &id);
+    }
+
+    fn to_json(&self, id: EntityId) -> Option<Value> {
+        self.get(&id).and_then(|component| serde_json::to_value(component).ok())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+type Decoder = fn(&mut Entities, EntityId, Value) -> Result<(), serde_json::Error>;
+
+fn decode<C: Component>(entities: &mut Entities, id: EntityId, value: Value) -> Result<(), serde_json::Error> {
+    let component: C = serde_json::from_value(value)?;
+    entities.insert(id, component);
+    Ok(())
+}
+
+/// An entity with every component it carries, keyed by component name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    pub id: EntityId,
+    pub components: BTreeMap<String, Value>,
+}
+
+/// What clients streaming entities need to apply since the last batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum EntityChange {
+    Updated { entity: EntitySnapshot },
+    Despawned { id: EntityId },
+}
+
+#[derive(Default)]
+pub struct Entities {
+    next_id: u64,
+    alive: BTreeSet<EntityId>,
+    columns: HashMap<TypeId, Box<dyn Column>>,
+    /// Column order for snapshots, and decoders for spawning by name.
+    names: BTreeMap<&'static str, (TypeId, Decoder)>,
+    changed: BTreeSet<EntityId>,
+    despawned: Vec<EntityId>,
+}
+
+impl Entities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `C` spawnable by name through [`Entities::spawn_with`].
+    pub fn register<C: Component>(&mut self) {
+        self.names.insert(C::NAME, (TypeId::of::<C>(), decode::<C>));
+    }
+
+    pub fn spawn(&mut self) -> EntityId {
+        self.next_id += 1;
+        let id = EntityId(self.next_id);
+        self.alive.insert(id);
+        self.changed.insert(id);
+        id
+    }
+
+    /// Spawn an entity from components keyed by name, as found in an
+    /// [`EntitySnapshot`]. Nothing is spawned if any component is unknown
+    /// or malformed.
+    pub fn spawn_with(&mut self, components: BTreeMap<String, Value>) -> Result<EntityId, FinalverseError> {
+        let mut decoders = Vec::with_capacity(components.len());
+        for (name, value) in components {
+            let Some((_, decoder)) = self.names.get(name.as_str()) else {
+                return Err(FinalverseError::BadRequest(format!("Unknown component '{}'", name)));
+            };
+            decoders.push((name, *decoder, value));
+        }
+
+        let id = self.spawn();
+        for (name, decoder, value) in decoders {
+            if let Err(e) = decoder(self, id, value) {
+                self.despawn(id);
+                self.despawned.retain(|despawned| *despawned != id);
+                return Err(FinalverseError::BadRequest(format!("Invalid '{}' component: {}", name, e)));
+            }
+        }
+        Ok(id)
+    }
+
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        if !self.alive.remove(&id) {
+            return false;
+        }
+        for column in self.columns.values_mut() {
+            column.remove(id);
+        }
+        self.changed.remove(&id);
+        self.despawned.push(id);
+        true
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.alive.contains(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive.is_empty()
+    }
+
+    /// Attach `component`, replacing any of the same type. Ignored for
+    /// entities that don't exist.
+    pub fn insert<C: Component>(&mut self, id: EntityId, component: C) {
+        if !self.contains(id) {
+            return;
+        }
+        self.register::<C>();
+        self.column_mut::<C>().insert(id, component);
+        self.changed.insert(id);
+    }
+
+    pub fn remove<C: Component>(&mut self, id: EntityId) -> Option<C> {
+        let removed = self.column_mut::<C>().remove(&id);
+        if removed.is_some() {
+            self.changed.insert(id);
+        }
+        removed
+    }
+
+    pub fn get<C: Component>(&self, id: EntityId) -> Option<&C> {
+        self.column::<C>()?.get(&id)
+    }
+
+    /// Mutable access; the entity is streamed again whether or not the
+    /// component actually changes.
+    pub fn get_mut<C: Component>(&mut self, id: EntityId) -> Option<&mut C> {
+        if !self.column::<C>()?.contains_key(&id) {
+            return None;
+        }
+        self.changed.insert(id);
+        self.column_mut::<C>().get_mut(&id)
+    }
+
+    /// Every entity carrying `C`, in id order.
+    pub fn iter<C: Component>(&self) -> impl Iterator<Item = (EntityId, &C)> {
+        self.column::<C>().into_iter().flat_map(|column| column.iter().map(|(id, component)| (*id, component)))
+    }
+
+    /// Ids of entities carrying `C`, for systems that need to touch
+    /// other components while going through them.
+    pub fn with<C: Component>(&self) -> Vec<EntityId> {
+        self.iter::<C>().map(|(id, _)| id).collect()
+    }
+
+    pub fn snapshot(&self, id: EntityId) -> Option<EntitySnapshot> {
+        if !self.contains(id) {
+            return None;
+        }
+        let components = self
+            .names
+            .iter()
+            .filter_map(|(name, (type_id, _))| {
+                let value = self.columns.get(type_id)?.to_json(id)?;
+                Some((name.to_string(), value))
+            })
+            .collect();
+        Some(EntitySnapshot { id, components })
+    }
+
+    pub fn snapshots(&self) -> Vec<EntitySnapshot> {
+        self.alive.iter().filter_map(|id| self.snapshot(*id)).collect()
+    }
+
+    /// Entities spawned, changed or despawned since the last call.
+    pub fn take_changes(&mut self) -> Vec<EntityChange> {
+        let changed = std::mem::take(&mut self.changed);
+        let despawned = std::mem::take(&mut self.despawned);
+        changed
+            .into_iter()
+            .filter_map(|id| self.snapshot(id))
+            .map(|entity| EntityChange::Updated { entity })
+            .chain(despawned.into_iter().map(|id| EntityChange::Despawned { id }))
+            .collect()
+    }
+
+    fn column<C: Component>(&self) -> Option<&BTreeMap<EntityId, C>> {
+        self.columns.get(&TypeId::of::<C>())?.as_any().downcast_ref()
+    }
+
+    fn column_mut<C: Component>(&mut self) -> &mut BTreeMap<EntityId, C> {
+        self.columns
+            .entry(TypeId::of::<C>())
+            .or_insert_with(|| Box::new(BTreeMap::<EntityId, C>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .expect("column stored under its own type id")
+    }
+}
+
+/// Time passed to systems on each run.
+#[derive(Debug, Clone, Copy)]
+pub struct Tick {
+    pub now: DateTime<Utc>,
+    /// Seconds since the previous run; zero on the first.
+    pub delta_seconds: f64,
+}
+
+pub trait System: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn run(&mut self, entities: &mut Entities, tick: Tick);
+}
+
+/// Systems run in the order they were added.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<Box<dyn System>>,
+    last_run: Option<DateTime<Utc>>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_system(mut self, system: impl System + 'static) -> Self {
+        self.systems.push(Box::new(system));
+        self
+    }
+
+    pub fn system_names(&self) -> Vec<&'static str> {
+        self.systems.iter().map(|system| system.name()).collect()
+    }
+
+    pub fn run(&mut self, entities: &mut Entities, now: DateTime<Utc>) {
+        let delta_seconds = self
+            .last_run
+            .map(|last| (now - last).num_milliseconds().max(0) as f64 / 1000.0)
+            .unwrap_or(0.0);
+        self.last_run = Some(now);
+        let tick = Tick { now, delta_seconds };
+        for system in &mut self.systems {
+            system.run(entities, tick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Glow(f32);
+
+    impl Component for Glow {
+        const NAME: &'static str = "glow";
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Label(String);
+
+    impl Component for Label {
+        const NAME: &'static str = "label";
+    }
+
+    struct Fade;
+
+    impl System for Fade {
+        fn name(&self) -> &'static str {
+            "fade"
+        }
+
+        fn run(&mut self, entities: &mut Entities, tick: Tick) {
+            for id in entities.with::<Glow>() {
+                let glow = entities.get_mut::<Glow>(id).unwrap();
+                glow.0 = (glow.0 - tick.delta_seconds as f32).max(0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn components_snapshot_stream_and_respawn_by_name() {
+        let mut entities = Entities::new();
+        entities.register::<Glow>();
+        entities.register::<Label>();
+        let lantern = entities.spawn();
+        entities.insert(lantern, Glow(5.0));
+        entities.insert(lantern, Label("lantern".to_string()));
+        let stone = entities.spawn();
+        entities.insert(stone, Label("stone".to_string()));
+
+        assert_eq!(entities.iter::<Glow>().count(), 1);
+        let changes = entities.take_changes();
+        assert_eq!(changes.len(), 2);
+        assert!(entities.take_changes().is_empty());
+
+        let mut schedule = Schedule::new().with_system(Fade);
+        let start = Utc::now();
+        schedule.run(&mut entities, start);
+        schedule.run(&mut entities, start + chrono::Duration::seconds(2));
+        assert_eq!(entities.get::<Glow>(lantern), Some(&Glow(3.0)));
+        // Only the entity a system touched is streamed again
+        let changes = entities.take_changes();
+        let [EntityChange::Updated { entity }] = &changes[..] else {
+            panic!("expected one update, got {:?}", changes);
+        };
+        assert_eq!(entity.components["glow"], serde_json::json!(3.0));
+        assert_eq!(entity.components["label"], serde_json::json!("lantern"));
+
+        let copy = entities.spawn_with(entity.components.clone()).unwrap();
+        assert_eq!(entities.get::<Label>(copy), Some(&Label("lantern".to_string())));
+        let unknown = BTreeMap::from([("sparkle".to_string(), Value::Null)]);
+        assert!(entities.spawn_with(unknown).is_err());
+
+        assert!(entities.despawn(stone));
+        assert!(entities.get::<Label>(stone).is_none());
+        assert!(entities.take_changes().contains(&EntityChange::Despawned { id: stone }));
+    }
+}
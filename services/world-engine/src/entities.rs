@@ -0,0 +1,343 @@
+// services/world-engine/src/entities.rs
+//! The dynamic objects players find in the world: harvestables that
+//! regrow, doors that close behind them and spawners that keep an area
+//! stocked. Each is an entity in [`crate::ecs`] with a [`Placement`], and
+//! the systems here advance them on every simulation tick.
+
+use crate::ecs::{Component, Entities, EntityChange, EntityId, EntitySnapshot, Schedule, System, Tick};
+use crate::{Position3D, RegionId};
+use chrono::{DateTime, Utc};
+use finalverse_core::FinalverseError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Where an entity is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Placement {
+    pub region_id: RegionId,
+    pub position: Position3D,
+}
+
+impl Component for Placement {
+    const NAME: &'static str = "placement";
+}
+
+/// Story tags that story-engine triggers bind to, such as `shrine` or
+/// `echo-lumi`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Tags(pub Vec<String>);
+
+impl Component for Tags {
+    const NAME: &'static str = "tags";
+}
+
+/// Gives `item_id` when harvested, one unit at a time, regrowing a unit
+/// every `regrow_seconds` up to `capacity`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Harvestable {
+    pub item_id: String,
+    pub remaining: u32,
+    pub capacity: u32,
+    pub regrow_seconds: f64,
+    #[serde(default)]
+    pub regrow_elapsed: f64,
+}
+
+impl Component for Harvestable {
+    const NAME: &'static str = "harvestable";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Door {
+    pub open: bool,
+    #[serde(default)]
+    pub locked: bool,
+    /// Swings shut this long after opening; stays open when unset.
+    #[serde(default)]
+    pub close_after_seconds: Option<f64>,
+    #[serde(default)]
+    pub open_for: f64,
+}
+
+impl Component for Door {
+    const NAME: &'static str = "door";
+}
+
+/// Spawns an entity from `template` every `every_seconds` while fewer
+/// than `max_alive` of its spawns exist. Spawns without a placement of
+/// their own appear where the spawner is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spawner {
+    pub template: BTreeMap<String, Value>,
+    pub every_seconds: f64,
+    pub max_alive: u32,
+    #[serde(default)]
+    pub elapsed: f64,
+}
+
+impl Component for Spawner {
+    const NAME: &'static str = "spawner";
+}
+
+/// Marks an entity made by a spawner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpawnedBy(pub EntityId);
+
+impl Component for SpawnedBy {
+    const NAME: &'static str = "spawned_by";
+}
+
+pub struct RegrowSystem;
+
+impl System for RegrowSystem {
+    fn name(&self) -> &'static str {
+        "regrow"
+    }
+
+    fn run(&mut self, entities: &mut Entities, tick: Tick) {
+        for id in entities.with::<Harvestable>() {
+            if entities.get::<Harvestable>(id).is_some_and(|h| h.remaining >= h.capacity) {
+                continue;
+            }
+            let Some(harvestable) = entities.get_mut::<Harvestable>(id) else { continue };
+            harvestable.regrow_elapsed += tick.delta_seconds;
+            while harvestable.regrow_elapsed >= harvestable.regrow_seconds && harvestable.remaining < harvestable.capacity {
+                harvestable.regrow_elapsed -= harvestable.regrow_seconds;
+                harvestable.remaining += 1;
+            }
+            if harvestable.remaining >= harvestable.capacity {
+                harvestable.regrow_elapsed = 0.0;
+            }
+        }
+    }
+}
+
+pub struct DoorSystem;
+
+impl System for DoorSystem {
+    fn name(&self) -> &'static str {
+        "doors"
+    }
+
+    fn run(&mut self, entities: &mut Entities, tick: Tick) {
+        for id in entities.with::<Door>() {
+            let closes = entities.get::<Door>(id).is_some_and(|door| door.open && door.close_after_seconds.is_some());
+            if !closes {
+                continue;
+            }
+            let Some(door) = entities.get_mut::<Door>(id) else { continue };
+            door.open_for += tick.delta_seconds;
+            if door.close_after_seconds.is_some_and(|after| door.open_for >= after) {
+                door.open = false;
+                door.open_for = 0.0;
+            }
+        }
+    }
+}
+
+pub struct SpawnerSystem;
+
+impl System for SpawnerSystem {
+    fn name(&self) -> &'static str {
+        "spawners"
+    }
+
+    fn run(&mut self, entities: &mut Entities, tick: Tick) {
+        for id in entities.with::<Spawner>() {
+            let alive = entities.iter::<SpawnedBy>().filter(|(_, by)| by.0 == id).count() as u32;
+            let Some(spawner) = entities.get::<Spawner>(id).cloned() else { continue };
+            if alive >= spawner.max_alive {
+                if spawner.elapsed != 0.0 {
+                    entities.get_mut::<Spawner>(id).unwrap().elapsed = 0.0;
+                }
+                continue;
+            }
+
+            let elapsed = spawner.elapsed + tick.delta_seconds;
+            if elapsed < spawner.every_seconds {
+                entities.get_mut::<Spawner>(id).unwrap().elapsed = elapsed;
+                continue;
+            }
+            entities.get_mut::<Spawner>(id).unwrap().elapsed = 0.0;
+            match entities.spawn_with(spawner.template.clone()) {
+                Ok(spawned) => {
+                    entities.insert(spawned, SpawnedBy(id));
+                    if entities.get::<Placement>(spawned).is_none() {
+                        if let Some(placement) = entities.get::<Placement>(id).cloned() {
+                            entities.insert(spawned, placement);
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Spawner {} has a bad template: {}", id, e),
+            }
+        }
+    }
+}
+
+/// What interacting with an entity did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Interaction {
+    Harvested { item_id: String, remaining: u32 },
+    DoorOpened,
+    DoorClosed,
+}
+
+/// The world's dynamic objects and the systems that run them.
+pub struct DynamicObjects {
+    entities: Entities,
+    schedule: Schedule,
+}
+
+impl Default for DynamicObjects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicObjects {
+    pub fn new() -> Self {
+        let mut entities = Entities::new();
+        entities.register::<Placement>();
+        entities.register::<Tags>();
+        entities.register::<Harvestable>();
+        entities.register::<Door>();
+        entities.register::<Spawner>();
+        entities.register::<SpawnedBy>();
+        let schedule = Schedule::new()
+            .with_system(RegrowSystem)
+            .with_system(DoorSystem)
+            .with_system(SpawnerSystem);
+        Self { entities, schedule }
+    }
+
+    pub fn entities(&self) -> &Entities {
+        &self.entities
+    }
+
+    pub fn spawn(&mut self, components: BTreeMap<String, Value>) -> Result<EntitySnapshot, FinalverseError> {
+        let id = self.entities.spawn_with(components)?;
+        Ok(self.entities.snapshot(id).expect("entity was just spawned"))
+    }
+
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        self.entities.despawn(id)
+    }
+
+    pub fn snapshot(&self, id: EntityId) -> Option<EntitySnapshot> {
+        self.entities.snapshot(id)
+    }
+
+    /// Every entity, or only those placed in `region_id`.
+    pub fn snapshots(&self, region_id: Option<&RegionId>) -> Vec<EntitySnapshot> {
+        match region_id {
+            None => self.entities.snapshots(),
+            Some(region_id) => self
+                .entities
+                .iter::<Placement>()
+                .filter(|(_, placement)| placement.region_id == *region_id)
+                .filter_map(|(id, _)| self.entities.snapshot(id))
+                .collect(),
+        }
+    }
+
+    /// Run every system, returning the changes to stream out.
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Vec<EntityChange> {
+        self.schedule.run(&mut self.entities, now);
+        self.entities.take_changes()
+    }
+
+    /// Changes made outside a tick, e.g. by spawning or interacting.
+    pub fn take_changes(&mut self) -> Vec<EntityChange> {
+        self.entities.take_changes()
+    }
+
+    /// Harvest one unit or open/close a door.
+    pub fn interact(&mut self, id: EntityId) -> Result<Interaction, FinalverseError> {
+        if !self.entities.contains(id) {
+            return Err(FinalverseError::NotFound(format!("Entity {}", id)));
+        }
+        if let Some(harvestable) = self.entities.get::<Harvestable>(id) {
+            if harvestable.remaining == 0 {
+                return Err(FinalverseError::Conflict(format!("{} has nothing left to harvest", id)));
+            }
+            let harvestable = self.entities.get_mut::<Harvestable>(id).unwrap();
+            harvestable.remaining -= 1;
+            return Ok(Interaction::Harvested {
+                item_id: harvestable.item_id.clone(),
+                remaining: harvestable.remaining,
+            });
+        }
+        if let Some(door) = self.entities.get::<Door>(id) {
+            if door.locked {
+                return Err(FinalverseError::Forbidden(format!("Door {} is locked", id)));
+            }
+            let door = self.entities.get_mut::<Door>(id).unwrap();
+            door.open = !door.open;
+            door.open_for = 0.0;
+            return Ok(if door.open { Interaction::DoorOpened } else { Interaction::DoorClosed });
+        }
+        Err(FinalverseError::BadRequest(format!("Nothing to do with entity {}", id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    #[test]
+    fn harvestables_regrow_doors_close_and_spawners_restock() {
+        let region = RegionId(uuid::Uuid::new_v4());
+        let placement = json!({"region_id": region, "position": {"x": 4.0, "y": 0.0, "z": -2.0}});
+        let mut objects = DynamicObjects::new();
+
+        let berries = objects
+            .spawn(BTreeMap::from([
+                ("placement".to_string(), placement.clone()),
+                (
+                    "harvestable".to_string(),
+                    json!({"item_id": "moonberry", "remaining": 1, "capacity": 2, "regrow_seconds": 30.0}),
+                ),
+            ]))
+            .unwrap()
+            .id;
+        let gate = objects
+            .spawn(BTreeMap::from([("door".to_string(), json!({"open": false, "close_after_seconds": 10.0}))]))
+            .unwrap()
+            .id;
+        let grove = objects
+            .spawn(BTreeMap::from([
+                ("placement".to_string(), placement),
+                (
+                    "spawner".to_string(),
+                    json!({
+                        "template": {"harvestable": {"item_id": "glowcap", "remaining": 1, "capacity": 1, "regrow_seconds": 60.0}},
+                        "every_seconds": 20.0,
+                        "max_alive": 1
+                    }),
+                ),
+            ]))
+            .unwrap()
+            .id;
+
+        let start = Utc::now();
+        objects.tick(start);
+        assert_eq!(objects.interact(berries).unwrap(), Interaction::Harvested { item_id: "moonberry".into(), remaining: 0 });
+        assert!(matches!(objects.interact(berries), Err(FinalverseError::Conflict(_))));
+        assert_eq!(objects.interact(gate).unwrap(), Interaction::DoorOpened);
+
+        let changes = objects.tick(start + Duration::seconds(30));
+        assert_eq!(objects.entities().get::<Harvestable>(berries).unwrap().remaining, 1);
+        assert!(!objects.entities().get::<Door>(gate).unwrap().open);
+        // The grove's spawn appears beside it, and only one at a time
+        let spawned: Vec<_> = objects.entities().iter::<SpawnedBy>().map(|(id, _)| id).collect();
+        assert_eq!(spawned.len(), 1);
+        assert!(changes.iter().any(|change| matches!(change, EntityChange::Updated { entity } if entity.id == spawned[0])));
+        assert_eq!(objects.snapshots(Some(&region)).len(), 3);
+        objects.tick(start + Duration::seconds(60));
+        assert_eq!(objects.entities().iter::<SpawnedBy>().filter(|(_, by)| by.0 == grove).count(), 1);
+    }
+}
@@ -1,9 +1,11 @@
 // services/world-engine/src/grpc_server.rs
 use tonic::{Request, Response, Status};
+use finalverse_core::{FinalverseError, RegionEffect, RegionEffects};
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 // Import types from the world_engine library so this module works
 // both when compiled as part of the library and the binary.
@@ -17,25 +19,38 @@ use crate::{
     RegionState,
     WeatherState,
     WorldEvent,
+    WorldTime,
+    Observer,
+    RegionDelta,
+    RegionWatch,
 };
 use finalverse_proto::world::{
     world_service_server::WorldService,
     GetWorldStateRequest, WorldStateResponse,
-    StreamUpdatesRequest, WorldUpdate as ProtoWorldUpdate,
+    StreamUpdatesRequest, WatchWorldStateRequest, WorldUpdate as ProtoWorldUpdate,
     PlayerActionRequest, ActionResponse,
     GetRegionRequest, RegionResponse,
     UpdateHarmonyRequest, UpdateHarmonyResponse,
+    ApplyRegionEffectsRequest, ApplyRegionEffectsResponse,
     Region as ProtoRegion, WeatherState as ProtoWeatherState,
     WorldTime as ProtoWorldTime,
-    RegionUpdate,
+    RegionUpdate, RegionRemoved, EventUpdate, TimeUpdate,
     WorldEvent as ProtoWorldEvent,
+    world_event,
     world_update,
     player_action_request,
 };
+use finalverse_proto::world as proto;
+
+/// How often watchers check the region snapshot for changes.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// World events buffered for slow watchers before they start missing some.
+const WATCH_EVENT_BUFFER: usize = 256;
 
 pub struct WorldServiceImpl {
     engine: Arc<WorldEngine>,
     update_channels: Arc<RwLock<HashMap<String, tokio::sync::mpsc::Sender<ProtoWorldUpdate>>>>,
+    events: broadcast::Sender<WorldEvent>,
 }
 
 impl WorldServiceImpl {
@@ -43,13 +58,38 @@ impl WorldServiceImpl {
         Self {
             engine,
             update_channels: Arc::new(RwLock::new(HashMap::new())),
+            events: broadcast::channel(WATCH_EVENT_BUFFER).0,
         }
     }
+
+    /// Register this with the engine so watchers asking for events get them.
+    pub fn event_observer(&self) -> Arc<dyn Observer> {
+        Arc::new(EventFeed(self.events.clone()))
+    }
+}
+
+struct EventFeed(broadcast::Sender<WorldEvent>);
+
+#[async_trait::async_trait]
+impl Observer for EventFeed {
+    async fn notify(&self, event: &WorldEvent) {
+        // No receivers just means nobody is watching events
+        let _ = self.0.send(event.clone());
+    }
+}
+
+/// The next event for a watcher, or never if it didn't ask for events.
+async fn next_event(events: &mut Option<broadcast::Receiver<WorldEvent>>) -> Result<WorldEvent, broadcast::error::RecvError> {
+    match events {
+        Some(events) => events.recv().await,
+        None => std::future::pending().await,
+    }
 }
 
 #[tonic::async_trait]
 impl WorldService for WorldServiceImpl {
     type StreamWorldUpdatesStream = WorldUpdateStream;
+    type WatchWorldStateStream = WorldUpdateStream;
     async fn get_world_state(
         &self,
         request: Request<GetWorldStateRequest>,
@@ -117,16 +157,7 @@ impl WorldService for WorldServiceImpl {
                 for region_id in &region_ids {
                     if let Ok(uuid) = uuid::Uuid::parse_str(region_id) {
                         if let Some(region) = state.regions.get(&RegionId(uuid)) {
-                            let update = ProtoWorldUpdate {
-                                update: Some(world_update::Update::RegionUpdate(RegionUpdate {
-                                region_id: region.id.0.to_string(),
-                                harmony_level: region.harmony_level as f32,
-                                discord_level: region.discord_level as f32,
-                                weather: Some(weather_to_proto(&region.weather)),
-                            })),
-                            };
-
-                            if tx.send(update).await.is_err() {
+                            if tx.send(region_update(region)).await.is_err() {
                                 break;
                             }
                         }
@@ -139,6 +170,81 @@ impl WorldService for WorldServiceImpl {
         Ok(Response::new(Box::pin(stream) as Self::StreamWorldUpdatesStream))
     }
 
+    async fn watch_world_state(
+        &self,
+        request: Request<WatchWorldStateRequest>,
+    ) -> Result<Response<Self::WatchWorldStateStream>, Status> {
+        let req = request.into_inner();
+        let filter = if req.region_ids.is_empty() {
+            None
+        } else {
+            let ids = req.region_ids.iter()
+                .map(|id| uuid::Uuid::parse_str(id).map(RegionId))
+                .collect::<Result<HashSet<_>, _>>()
+                .map_err(|_| FinalverseError::BadRequest("Invalid region id".to_string()))?;
+            Some(ids)
+        };
+        let mut events = req.include_events.then(|| self.events.subscribe());
+        let (tx, rx) = mpsc::channel(100);
+        let engine = self.engine.clone();
+
+        tokio::spawn(async move {
+            let mut watch = RegionWatch::new(filter);
+            let mut poll = tokio::time::interval(WATCH_POLL_INTERVAL);
+            let mut last_hour = None;
+
+            loop {
+                let updates = tokio::select! {
+                    _ = tx.closed() => return,
+                    _ = poll.tick() => {
+                        let mut updates: Vec<ProtoWorldUpdate> = watch
+                            .poll(engine.metabolism().snapshot())
+                            .into_iter()
+                            .map(delta_to_proto)
+                            .collect();
+                        // Clients interpolate within the hour themselves
+                        let time: WorldTime = engine.world_time().await.into();
+                        if last_hour != Some((time.day, time.hour as u32)) {
+                            last_hour = Some((time.day, time.hour as u32));
+                            updates.push(ProtoWorldUpdate {
+                                update: Some(world_update::Update::TimeUpdate(TimeUpdate {
+                                    time: Some(ProtoWorldTime { day: time.day, hour: time.hour }),
+                                })),
+                            });
+                        }
+                        updates
+                    }
+                    event = next_event(&mut events) => match event {
+                        Ok(event) => {
+                            let event = event_to_proto(&event);
+                            if event.event.is_none() {
+                                continue;
+                            }
+                            vec![ProtoWorldUpdate {
+                                update: Some(world_update::Update::EventUpdate(EventUpdate {
+                                    event: Some(event),
+                                    timestamp: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
+                                })),
+                            }]
+                        }
+                        // Region deltas are still exact; only events were missed
+                        Err(broadcast::error::RecvError::Lagged(_)) => Vec::new(),
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    },
+                };
+
+                for update in updates {
+                    if tx.send(update).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let stream = ReceiverStream::new(rx).map(Ok);
+        Ok(Response::new(Box::pin(stream) as Self::WatchWorldStateStream))
+    }
+
     async fn process_action(
         &self,
         request: Request<PlayerActionRequest>,
@@ -162,11 +268,11 @@ impl WorldService for WorldServiceImpl {
             Some(player_action_request::Action::Craft(craft)) => {
                 ActionType::Craft(craft.item_id)
             }
-            None => return Err(Status::invalid_argument("No action specified")),
+            None => return Err(FinalverseError::BadRequest("No action specified".to_string()).into()),
         };
 
         let player_action = PlayerAction {
-            player_id: PlayerId(req.player_id),
+            player_id: PlayerId::from_legacy(&req.player_id),
             action,
             timestamp: req.timestamp,
         };
@@ -184,17 +290,14 @@ impl WorldService for WorldServiceImpl {
         &self,
         request: Request<GetRegionRequest>,
     ) -> Result<Response<RegionResponse>, Status> {
-        let id_str = request.into_inner().region_id;
-        let uuid = uuid::Uuid::parse_str(&id_str)
-            .map_err(|_| Status::invalid_argument("Invalid region id"))?;
-        let region_id = RegionId(uuid);
+        let region_id = parse_region_id(&request.into_inner().region_id)?;
 
-        if let Some(region) = self.engine.metabolism().get_region(&region_id).await {
+        if let Some(region) = self.engine.metabolism().get_region(&region_id) {
             Ok(Response::new(RegionResponse {
                 region: Some(region_to_proto(&region)),
             }))
         } else {
-            Err(Status::not_found("Region not found"))
+            Err(FinalverseError::NotFound("Region not found".to_string()).into())
         }
     }
 
@@ -203,13 +306,11 @@ impl WorldService for WorldServiceImpl {
         request: Request<UpdateHarmonyRequest>,
     ) -> Result<Response<UpdateHarmonyResponse>, Status> {
         let req = request.into_inner();
-        let uuid = uuid::Uuid::parse_str(&req.region_id)
-            .map_err(|_| Status::invalid_argument("Invalid region id"))?;
-        let region_id = RegionId(uuid);
+        let region_id = parse_region_id(&req.region_id)?;
 
-        // Update harmony through the engine
+        // Update harmony through the engine; the region missing is its only failure
         let update_result = self.engine.update_region_harmony(&region_id, req.delta).await
-            .map_err(|e| Status::internal(format!("Failed to update harmony: {}", e)))?;
+            .map_err(|e| FinalverseError::NotFound(format!("Failed to update harmony: {}", e)))?;
 
         Ok(Response::new(UpdateHarmonyResponse {
             new_harmony_level: update_result.new_harmony_level,
@@ -218,9 +319,41 @@ impl WorldService for WorldServiceImpl {
                 .collect(),
         }))
     }
+
+    async fn apply_region_effects(
+        &self,
+        request: Request<ApplyRegionEffectsRequest>,
+    ) -> Result<Response<ApplyRegionEffectsResponse>, Status> {
+        let req = request.into_inner();
+        let region_id = parse_region_id(&req.region_id)?;
+        let effects = req.effects.iter()
+            .map(effect_from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let region = self.engine.apply_region_effects(&region_id, &RegionEffects::new(req.source, effects)).await?;
+        Ok(Response::new(ApplyRegionEffectsResponse {
+            region: Some(region_update_to_proto(&region)),
+        }))
+    }
 }
 
 // Conversion functions
+fn parse_region_id(id: &str) -> Result<RegionId, FinalverseError> {
+    uuid::Uuid::parse_str(id)
+        .map(RegionId)
+        .map_err(|_| FinalverseError::BadRequest("Invalid region id".to_string()))
+}
+
+fn effect_from_proto(effect: &proto::RegionEffect) -> Result<RegionEffect, FinalverseError> {
+    use proto::region_effect::Effect;
+    match effect.effect {
+        Some(Effect::HarmonyDelta(delta)) => Ok(RegionEffect::Harmony { delta: delta as f64 }),
+        Some(Effect::DiscordDelta(delta)) => Ok(RegionEffect::Discord { delta: delta as f64 }),
+        Some(Effect::TensionDelta(delta)) => Ok(RegionEffect::Tension { delta: delta as f64 }),
+        None => Err(FinalverseError::BadRequest("Region effect without a change".to_string())),
+    }
+}
+
 fn region_to_proto(region: &RegionState) -> ProtoRegion {
     ProtoRegion {
         id: region.id.0.to_string(),
@@ -233,6 +366,34 @@ fn region_to_proto(region: &RegionState) -> ProtoRegion {
     }
 }
 
+fn region_update_to_proto(region: &RegionState) -> RegionUpdate {
+    RegionUpdate {
+        region_id: region.id.0.to_string(),
+        harmony_level: region.harmony_level as f32,
+        discord_level: region.discord_level as f32,
+        weather: Some(weather_to_proto(&region.weather)),
+        terrain_type: format!("{:?}", region.terrain_type),
+        political_tension: region.political_tension as f32,
+    }
+}
+
+fn region_update(region: &RegionState) -> ProtoWorldUpdate {
+    ProtoWorldUpdate {
+        update: Some(world_update::Update::RegionUpdate(region_update_to_proto(region))),
+    }
+}
+
+fn delta_to_proto(delta: RegionDelta) -> ProtoWorldUpdate {
+    match delta {
+        RegionDelta::Changed(region) => region_update(&region),
+        RegionDelta::Removed(id) => ProtoWorldUpdate {
+            update: Some(world_update::Update::RegionRemoved(RegionRemoved {
+                region_id: id.0.to_string(),
+            })),
+        },
+    }
+}
+
 fn weather_to_proto(weather: &WeatherState) -> ProtoWeatherState {
     ProtoWeatherState {
         weather_type: format!("{:?}", weather.weather_type),
@@ -242,12 +403,58 @@ fn weather_to_proto(weather: &WeatherState) -> ProtoWeatherState {
     }
 }
 
-fn event_to_proto(_event: &WorldEvent) -> ProtoWorldEvent {
-    // Convert internal event to proto event
-    // This is a simplified version - expand based on your needs
-    ProtoWorldEvent {
-        event: None, // Implement full conversion
-    }
+/// Events the proto has no message for convert to an empty event.
+fn event_to_proto(event: &WorldEvent) -> ProtoWorldEvent {
+    let event = match event {
+        WorldEvent::CreatureMigration { species, from, to } => {
+            Some(world_event::Event::CreatureMigration(proto::CreatureMigration {
+                species: species.clone(),
+                from_region: from.0.to_string(),
+                to_region: to.0.to_string(),
+            }))
+        }
+        WorldEvent::CelestialEvent { event_type, duration } => {
+            Some(world_event::Event::CelestialEvent(proto::CelestialEvent {
+                event_type: format!("{:?}", event_type),
+                duration: *duration,
+            }))
+        }
+        WorldEvent::SilenceOutbreak { epicenter, radius, intensity } => {
+            Some(world_event::Event::SilenceOutbreak(proto::SilenceOutbreak {
+                epicenter: Some(proto::Position3D {
+                    x: epicenter.x as f32,
+                    y: epicenter.y as f32,
+                    z: epicenter.z as f32,
+                }),
+                radius: *radius as f32,
+                intensity: *intensity as f32,
+            }))
+        }
+        WorldEvent::HarmonyRestored { region_id, amount } => {
+            Some(world_event::Event::HarmonyRestored(proto::HarmonyRestored {
+                region_id: region_id.0.to_string(),
+                amount: *amount as f32,
+            }))
+        }
+        WorldEvent::EchoAppeared { echo_type, position } => {
+            Some(world_event::Event::EchoAppeared(proto::EchoAppeared {
+                echo_type: format!("{:?}", echo_type),
+                position: Some(proto::Position3D { x: position.x, y: position.y, z: position.z }),
+            }))
+        }
+        WorldEvent::WeatherChanged { region_id, from, to, intensity } => {
+            Some(world_event::Event::WeatherChanged(proto::WeatherChanged {
+                region_id: region_id.0.to_string(),
+                from: format!("{:?}", from),
+                to: format!("{:?}", to),
+                intensity: *intensity as f32,
+            }))
+        }
+        WorldEvent::SpeciesStatusChanged { .. }
+        | WorldEvent::SilenceManifested { .. }
+        | WorldEvent::Festival { .. } => None,
+    };
+    ProtoWorldEvent { event }
 }
 
 pub type WorldUpdateStream = Pin<Box<dyn Stream<Item = Result<ProtoWorldUpdate, Status>> + Send + 'static>>;
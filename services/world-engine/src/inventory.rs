@@ -0,0 +1,297 @@
+// services/world-engine/src/inventory.rs
+//! Items, the stacks players carry them in, and the recipes that turn
+//! some items into others. Recipes are gated by the attunement tier
+//! harmony-service grants; see [`crate::crafting`] for the service that
+//! applies them to stored inventories.
+
+use finalverse_core::types::{ItemType, Rarity};
+use finalverse_core::FinalverseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Stacks a player can carry before new items stop fitting.
+pub const DEFAULT_INVENTORY_SLOTS: usize = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemDefinition {
+    pub id: String,
+    pub name: String,
+    pub item_type: ItemType,
+    pub rarity: Rarity,
+    /// Most of this item one slot holds.
+    pub max_stack: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item_id: String,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: String,
+    pub inputs: Vec<ItemStack>,
+    pub output: ItemStack,
+    /// Lowest attunement tier that may craft this.
+    #[serde(default)]
+    pub required_tier: u32,
+}
+
+/// Every item and recipe the world knows, read from the file named by
+/// `world_engine.item_catalog` or built in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    items: Vec<ItemDefinition>,
+    recipes: Vec<Recipe>,
+}
+
+impl Catalog {
+    pub fn new(items: Vec<ItemDefinition>, recipes: Vec<Recipe>) -> Result<Self, String> {
+        let catalog = Self { items, recipes };
+        catalog.validate()?;
+        Ok(catalog)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let catalog: Self = serde_json::from_str(&json)?;
+        catalog.validate().map_err(anyhow::Error::msg)?;
+        Ok(catalog)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = std::collections::HashSet::new();
+        for item in &self.items {
+            if !ids.insert(item.id.as_str()) {
+                return Err(format!("item '{}' is defined twice", item.id));
+            }
+            if item.max_stack == 0 {
+                return Err(format!("item '{}' must stack at least 1", item.id));
+            }
+        }
+        let mut recipe_ids = std::collections::HashSet::new();
+        for recipe in &self.recipes {
+            if !recipe_ids.insert(recipe.id.as_str()) {
+                return Err(format!("recipe '{}' is defined twice", recipe.id));
+            }
+            for stack in recipe.inputs.iter().chain(std::iter::once(&recipe.output)) {
+                if !ids.contains(stack.item_id.as_str()) {
+                    return Err(format!("recipe '{}' uses unknown item '{}'", recipe.id, stack.item_id));
+                }
+                if stack.quantity == 0 {
+                    return Err(format!("recipe '{}' has an empty stack of '{}'", recipe.id, stack.item_id));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn items(&self) -> &[ItemDefinition] {
+        &self.items
+    }
+
+    pub fn recipes(&self) -> &[Recipe] {
+        &self.recipes
+    }
+
+    pub fn item(&self, id: &str) -> Result<&ItemDefinition, FinalverseError> {
+        self.items
+            .iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| FinalverseError::NotFound(format!("no item '{}'", id)))
+    }
+
+    pub fn recipe(&self, id: &str) -> Result<&Recipe, FinalverseError> {
+        self.recipes
+            .iter()
+            .find(|recipe| recipe.id == id)
+            .ok_or_else(|| FinalverseError::NotFound(format!("no recipe '{}'", id)))
+    }
+}
+
+impl Default for Catalog {
+    /// A few starter items so crafting works without a catalog file.
+    fn default() -> Self {
+        let item = |id: &str, name: &str, item_type, rarity, max_stack| ItemDefinition {
+            id: id.to_string(),
+            name: name.to_string(),
+            item_type,
+            rarity,
+            max_stack,
+        };
+        let stack = |item_id: &str, quantity| ItemStack { item_id: item_id.to_string(), quantity };
+        Self {
+            items: vec![
+                item("echo-shard", "Echo Shard", ItemType::Essence, Rarity::Common, 50),
+                item("resonant-reed", "Resonant Reed", ItemType::Tool, Rarity::Common, 20),
+                item("harmony-tonic", "Harmony Tonic", ItemType::Consumable, Rarity::Uncommon, 10),
+                item("song-flute", "Song Flute", ItemType::Instrument, Rarity::Rare, 1),
+                item("lumi-lantern", "Lumi's Lantern", ItemType::Artifact, Rarity::Epic, 1),
+            ],
+            recipes: vec![
+                Recipe {
+                    id: "harmony-tonic".to_string(),
+                    inputs: vec![stack("echo-shard", 2)],
+                    output: stack("harmony-tonic", 1),
+                    required_tier: 0,
+                },
+                Recipe {
+                    id: "song-flute".to_string(),
+                    inputs: vec![stack("resonant-reed", 3), stack("echo-shard", 1)],
+                    output: stack("song-flute", 1),
+                    required_tier: 1,
+                },
+                Recipe {
+                    id: "lumi-lantern".to_string(),
+                    inputs: vec![stack("song-flute", 1), stack("echo-shard", 5)],
+                    output: stack("lumi-lantern", 1),
+                    required_tier: 3,
+                },
+            ],
+        }
+    }
+}
+
+/// What a player carries: up to `capacity` stacks, each within its
+/// item's `max_stack`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    pub capacity: usize,
+    pub slots: Vec<ItemStack>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_INVENTORY_SLOTS,
+            slots: Vec::new(),
+        }
+    }
+}
+
+impl Inventory {
+    pub fn count(&self, item_id: &str) -> u32 {
+        self.slots.iter().filter(|slot| slot.item_id == item_id).map(|slot| slot.quantity).sum()
+    }
+
+    /// Top up existing stacks, then open new slots. Nothing is added
+    /// unless everything fits.
+    pub fn add(&mut self, item: &ItemDefinition, quantity: u32) -> Result<(), FinalverseError> {
+        let mut slots = self.slots.clone();
+        let mut remaining = quantity;
+        for slot in slots.iter_mut().filter(|slot| slot.item_id == item.id) {
+            let moved = remaining.min(item.max_stack.saturating_sub(slot.quantity));
+            slot.quantity += moved;
+            remaining -= moved;
+        }
+        while remaining > 0 {
+            if slots.len() >= self.capacity {
+                return Err(FinalverseError::Conflict(format!("no room for {} {}", quantity, item.name)));
+            }
+            let moved = remaining.min(item.max_stack);
+            slots.push(ItemStack { item_id: item.id.clone(), quantity: moved });
+            remaining -= moved;
+        }
+        self.slots = slots;
+        Ok(())
+    }
+
+    /// Take from the newest stacks first, freeing slots that empty.
+    pub fn remove(&mut self, item_id: &str, quantity: u32) -> Result<(), FinalverseError> {
+        let held = self.count(item_id);
+        if held < quantity {
+            return Err(FinalverseError::Conflict(format!(
+                "needs {} '{}' but only {} are carried",
+                quantity, item_id, held
+            )));
+        }
+        let mut remaining = quantity;
+        for slot in self.slots.iter_mut().rev().filter(|slot| slot.item_id == item_id) {
+            let taken = remaining.min(slot.quantity);
+            slot.quantity -= taken;
+            remaining -= taken;
+            if remaining == 0 {
+                break;
+            }
+        }
+        self.slots.retain(|slot| slot.quantity > 0);
+        Ok(())
+    }
+
+    /// Spend `recipe`'s inputs and add its output, all or nothing.
+    pub fn craft(&mut self, catalog: &Catalog, recipe: &Recipe, tier: u32) -> Result<(), FinalverseError> {
+        if tier < recipe.required_tier {
+            return Err(FinalverseError::Forbidden(format!(
+                "'{}' needs attunement tier {}, not {}",
+                recipe.id, recipe.required_tier, tier
+            )));
+        }
+        let mut crafted = self.clone();
+        for input in &recipe.inputs {
+            crafted.remove(&input.item_id, input.quantity)?;
+        }
+        crafted.add(catalog.item(&recipe.output.item_id)?, recipe.output.quantity)?;
+        *self = crafted;
+        Ok(())
+    }
+
+    /// Totals per item, for summaries.
+    pub fn totals(&self) -> HashMap<String, u32> {
+        let mut totals = HashMap::new();
+        for slot in &self.slots {
+            *totals.entry(slot.item_id.clone()).or_default() += slot.quantity;
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacks_fill_before_new_slots_and_nothing_partial_is_added() {
+        let catalog = Catalog::default();
+        let reed = catalog.item("resonant-reed").unwrap();
+        let mut inventory = Inventory { capacity: 2, slots: Vec::new() };
+
+        inventory.add(reed, 15).unwrap();
+        inventory.add(reed, 10).unwrap();
+        assert_eq!(inventory.slots.iter().map(|slot| slot.quantity).collect::<Vec<_>>(), vec![20, 5]);
+
+        // 16 more would need a third slot
+        assert!(matches!(inventory.add(reed, 16), Err(FinalverseError::Conflict(_))));
+        assert_eq!(inventory.count("resonant-reed"), 25);
+
+        inventory.remove("resonant-reed", 7).unwrap();
+        assert_eq!(inventory.slots, vec![ItemStack { item_id: "resonant-reed".to_string(), quantity: 18 }]);
+        assert!(inventory.remove("resonant-reed", 19).is_err());
+    }
+
+    #[test]
+    fn crafting_is_gated_by_tier_and_spends_inputs() {
+        let catalog = Catalog::default();
+        let flute = catalog.recipe("song-flute").unwrap();
+        let mut inventory = Inventory::default();
+        inventory.add(catalog.item("resonant-reed").unwrap(), 3).unwrap();
+
+        assert!(matches!(inventory.craft(&catalog, flute, 0), Err(FinalverseError::Forbidden(_))));
+        // Missing the echo shard leaves the reeds alone
+        assert!(inventory.craft(&catalog, flute, 1).is_err());
+        assert_eq!(inventory.count("resonant-reed"), 3);
+
+        inventory.add(catalog.item("echo-shard").unwrap(), 1).unwrap();
+        inventory.craft(&catalog, flute, 1).unwrap();
+        assert_eq!(inventory.totals(), HashMap::from([("song-flute".to_string(), 1)]));
+
+        assert!(Catalog::new(catalog.items().to_vec(), vec![Recipe {
+            id: "broken".to_string(),
+            inputs: vec![],
+            output: ItemStack { item_id: "moonstone".to_string(), quantity: 1 },
+            required_tier: 0,
+        }])
+        .is_err());
+    }
+}
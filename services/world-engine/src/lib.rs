@@ -1,6 +1,16 @@
 // services/world-engine/src/lib.rs
 pub mod grid_generation;
+pub mod crafting;
+pub mod ecs;
+pub mod entities;
+pub mod inventory;
 pub mod world;
+pub mod snapshot;
+pub mod scenario;
+pub mod scheduler;
+pub mod terraform;
+pub mod weather;
+pub mod watch;
 
 pub mod server;
 pub mod grpc_server;
@@ -13,15 +23,26 @@ pub use finalverse_core::{RegionId, TerrainType, WeatherType};
 
 // Re-export the main types from world module
 pub use world::{WorldEngine, WorldState, WorldUpdate, WorldTime};
+pub use snapshot::{WorldSnapshot, SnapshotSummary, SnapshotDiff, DiffFilter};
+pub use scenario::{ImportSummary, WorldScenario, SCENARIO_FORMAT_VERSION};
+pub use scheduler::{EventScheduler, ScheduledEvent, Trigger};
+pub use crafting::{HarmonyTiers, InventoryService, InventoryStore, RedisInventoryStore, TierLookup};
+pub use inventory::{Catalog, Inventory, ItemDefinition, ItemStack, Recipe};
+pub use terraform::{Terraforming, TerraformStatus};
+pub use weather::{FrontPhase, WeatherShift, WeatherSystem};
+pub use watch::{RegionDelta, RegionWatch};
+pub use ecs::{EntityChange, EntityId, EntitySnapshot};
+pub use entities::{DynamicObjects, Interaction};
 
 // Re-export other important types
-pub use finalverse_ecosystem::{EcosystemSimulator, Species, SpeciesProfile, MigrationPhase};
-pub use finalverse_metobolism::{MetabolismSimulator, RegionState, WeatherState};
+pub use finalverse_ecosystem::{
+    EcosystemSimulator, Species, SpeciesProfile, MigrationPhase, ConservationStatus, RollupQuery,
+};
+pub use finalverse_metobolism::{DiffusionRates, MetabolismSimulator, RegionGraph, RegionState, WeatherState};
 
 
 // Core types that are shared across modules
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlayerId(pub String);
+pub use finalverse_core::PlayerId;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Coordinates {
@@ -41,6 +62,12 @@ pub enum WorldEvent {
         event_type: CelestialEventType,
         duration: u64,
     },
+    SpeciesStatusChanged {
+        species: String,
+        from: ConservationStatus,
+        to: ConservationStatus,
+        population: u64,
+    },
     SilenceOutbreak {
         epicenter: Coordinates,
         radius: f64,
@@ -58,6 +85,18 @@ pub enum WorldEvent {
         echo_type: EchoType,
         position: Position3D
     },
+    WeatherChanged {
+        region_id: RegionId,
+        from: WeatherType,
+        to: WeatherType,
+        intensity: f64,
+    },
+    /// A scripted celebration; `region_id` is `None` for world-wide ones.
+    Festival {
+        name: String,
+        region_id: Option<RegionId>,
+        duration: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -5,19 +5,25 @@ use tonic::transport::Server;
 pub use world_engine::{
     WorldEngine, Observer, WorldEvent, RegionState, RegionId, TerrainType,
     WeatherState, WeatherType, Species, SpeciesProfile, MigrationPhase,
-    PlayerAction, PlayerId, ActionType, Coordinates,
+    PlayerAction, PlayerId, ActionType, Coordinates, DiffusionRates,
+    WorldTime, RegionDelta, RegionWatch, Catalog, HarmonyTiers, InventoryService,
+    RedisInventoryStore,
 };
+use finalverse_events::{GameEventBus, LocalEventBus, NatsEventBus, TenantEventBus};
+use service_registry::{LocalServiceRegistry, ServiceClient};
 use finalverse_proto::world::world_service_server::WorldServiceServer;
 
 mod grpc_server;
 use grpc_server::WorldServiceImpl;
-use finalverse_audio_core::{AudioEvent, AudioEventType, AudioSource};
+use finalverse_audio_core::{AudioEvent, AudioEventType, AudioSource, WeatherType as AudioWeatherType};
 use nalgebra::Vector3;
+use finalverse_core::{RegionMap, TenantId};
 use redis::Client as RedisClient;
 use uuid::Uuid;
 use chrono::Utc;
 use serde_json;
-use tracing::info;
+use tracing::{info, warn};
+use warp::Filter;
 use finalverse_logging as logging;
 
 // Example observer for logging events
@@ -25,6 +31,7 @@ struct LoggingObserver;
 
 struct AudioObserver {
     redis_client: RedisClient,
+    tenant: TenantId,
 }
 
 #[async_trait::async_trait]
@@ -34,6 +41,9 @@ impl Observer for LoggingObserver {
             WorldEvent::CreatureMigration { species, from, to } => {
                 info!("🦌 {} migrating from {} to {}", species, from.0, to.0);
             }
+            WorldEvent::SpeciesStatusChanged { species, from, to, population } => {
+                info!("🐾 {} went from {:?} to {:?} (population {})", species, from, to, population);
+            }
             WorldEvent::CelestialEvent { event_type, duration } => {
                 info!("✨ Celestial event: {:?} for {} seconds", event_type, duration);
             }
@@ -41,6 +51,13 @@ impl Observer for LoggingObserver {
                 info!("🌑 Silence outbreak at ({:.2}, {:.2}, {:.2}), radius: {:.2}, intensity: {:.2}",
                          epicenter.x, epicenter.y, epicenter.z, radius, intensity);
             },
+            WorldEvent::WeatherChanged { region_id, from, to, intensity } => {
+                info!("🌦️ Weather in {} turned from {:?} to {:?} (intensity {:.2})", region_id.0, from, to, intensity);
+            }
+            WorldEvent::Festival { name, region_id, duration } => match region_id {
+                Some(region_id) => info!("🎉 {} begins in {} for {} seconds", name, region_id.0, duration),
+                None => info!("🎉 {} begins across the world for {} seconds", name, duration),
+            },
             &WorldEvent::HarmonyRestored { .. } | &WorldEvent::SilenceManifested { .. } | &WorldEvent::EchoAppeared { .. } => todo!()
         }
     }
@@ -64,6 +81,13 @@ impl Observer for AudioObserver {
                 source: AudioSource::Environment("silence".to_string()),
                 timestamp: chrono::Utc::now().timestamp(),
             }),
+            WorldEvent::WeatherChanged { to, .. } => Some(AudioEvent {
+                id: uuid::Uuid::new_v4(),
+                event_type: AudioEventType::WeatherChange { weather_type: audio_weather(to) },
+                position: None,
+                source: AudioSource::World,
+                timestamp: chrono::Utc::now().timestamp(),
+            }),
             _ => None,
         };
 
@@ -71,7 +95,7 @@ impl Observer for AudioObserver {
             if let Ok(mut con) = self.redis_client.get_async_connection().await {
                 if let Ok(event_json) = serde_json::to_string(&audio_event) {
                     let _ : Result<(), _> = redis::cmd("PUBLISH")
-                        .arg("world:events")
+                        .arg(self.tenant.scoped_key("world:events"))
                         .arg(event_json)
                         .query_async(&mut con)
                         .await;
@@ -81,6 +105,17 @@ impl Observer for AudioObserver {
     }
 }
 
+/// The audio layer has fewer weather moods than the simulation.
+fn audio_weather(weather: &WeatherType) -> AudioWeatherType {
+    match weather {
+        WeatherType::Clear | WeatherType::Cloudy | WeatherType::Fog => AudioWeatherType::Clear,
+        WeatherType::Rain | WeatherType::Snow => AudioWeatherType::Rain,
+        WeatherType::Storm => AudioWeatherType::Storm,
+        WeatherType::DissonanceStorm | WeatherType::SilenceMist => AudioWeatherType::DissonanceStorm,
+        WeatherType::HarmonyStorm => AudioWeatherType::CelestialLight,
+    }
+}
+
 #[tokio::main]
 async fn main() {
     logging::init(None);
@@ -88,12 +123,85 @@ async fn main() {
     info!("🌍 Starting World Engine...");
 
     // Create world engine
-    let engine = Arc::new(WorldEngine::new());
+    let config = finalverse_config::load_default_config();
+    let section = config.as_ref().map(|config| config.world_engine.clone()).unwrap_or_default();
+    let world_settings = config.map(|config| config.game.world_settings);
+    let day_length = world_settings
+        .as_ref()
+        .map(|settings| settings.day_night_cycle_minutes)
+        .unwrap_or(finalverse_core::world_clock::DEFAULT_DAY_LENGTH_MINUTES);
+    let weather_change_probability = world_settings
+        .as_ref()
+        .map(|settings| settings.weather_change_probability as f64)
+        .unwrap_or(world_engine::weather::DEFAULT_WEATHER_CHANGE_PROBABILITY);
+    let region_map = match world_settings.as_ref().ok().and_then(|settings| settings.region_map.as_deref()) {
+        Some(path) => RegionMap::load(path).unwrap_or_else(|e| {
+            warn!("Not placing regions from {}: {}", path, e);
+            RegionMap::default()
+        }),
+        None => RegionMap::default(),
+    };
+    let diffusion = world_settings
+        .map(|settings| DiffusionRates {
+            dissonance: settings.region_diffusion.dissonance_rate as f64,
+            political_tension: settings.region_diffusion.political_tension_rate as f64,
+        })
+        .unwrap_or_default();
+
+    // Inventories publish crafting to story-engine and ask harmony-service
+    // for attunement tiers
+    let event_bus: Arc<dyn GameEventBus> = match std::env::var("NATS_URL") {
+        Ok(nats_url) => match NatsEventBus::new(&nats_url).await {
+            Ok(bus) => {
+                info!("📡 Connected to NATS at {}", nats_url);
+                Arc::new(bus)
+            }
+            Err(e) => {
+                warn!("Not connecting to NATS at {}, using the local event bus: {}", nats_url, e);
+                Arc::new(LocalEventBus::new())
+            }
+        },
+        Err(_) => Arc::new(LocalEventBus::new()),
+    };
+    let harmony_services = ServiceClient::new(LocalServiceRegistry::new());
+
+    // Rehearse outages when built with chaos support
+    #[cfg(feature = "chaos")]
+    let (event_bus, harmony_services) = match finalverse_chaos::FaultInjector::from_default_config() {
+        Some(injector) => {
+            warn!("⚠️  Chaos scenarios active: {}", injector.scenarios().len());
+            (
+                finalverse_chaos::wrap_event_bus(event_bus, Some(&injector)),
+                harmony_services.with_fault_injector(injector),
+            )
+        }
+        None => (event_bus, harmony_services),
+    };
+    let catalog = match section.item_catalog.as_deref() {
+        Some(path) => Catalog::load(path).unwrap_or_else(|e| {
+            warn!("Not loading items from {}, using the built-in catalog: {}", path, e);
+            Catalog::default()
+        }),
+        None => Catalog::default(),
+    };
+    let redis_client = RedisClient::open("redis://127.0.0.1/").unwrap();
+    let inventory = InventoryService::new(catalog)
+        .with_store(Arc::new(RedisInventoryStore::new(redis_client.clone(), TenantId::from_env())))
+        .with_event_bus(TenantEventBus::from_env(event_bus))
+        .with_tiers(Arc::new(HarmonyTiers(harmony_services)));
+
+    let engine = Arc::new(
+        WorldEngine::new()
+            .with_day_length(day_length)
+            .with_region_diffusion(diffusion)
+            .with_region_map(region_map)
+            .with_weather_change_probability(weather_change_probability)
+            .with_inventory(inventory),
+    );
 
     // Register observers
     engine.register_observer(Arc::new(LoggingObserver)).await;
-    let redis_client = RedisClient::open("redis://127.0.0.1/").unwrap();
-    engine.register_observer(Arc::new(AudioObserver { redis_client })).await;
+    engine.register_observer(Arc::new(AudioObserver { redis_client, tenant: TenantId::from_env() })).await;
 
     // Initialize some tests data
     let test_region = RegionState {
@@ -107,6 +215,7 @@ async fn main() {
             wind_direction: 45.0,
             wind_speed: 10.0,
         },
+        political_tension: 0.0,
     };
 
     engine.metabolism().add_region(test_region).await;
@@ -125,10 +234,27 @@ async fn main() {
             RegionId(Uuid::new_v4()),
         ],
         preferred_terrain: vec![TerrainType::Forest, TerrainType::Plains],
+        region_population: Default::default(),
     };
 
     engine.ecosystem().add_species(star_deer).await;
 
+    // Load the scripted event timeline
+    if let Some(path) = section.event_timeline.as_deref() {
+        match world_engine::scheduler::load_timeline(path) {
+            Ok(timeline) => {
+                for event in timeline {
+                    let id = event.id.clone();
+                    if let Err(e) = engine.schedule_event(event).await {
+                        warn!("Not scheduling {} from {}: {}", id, path, e);
+                    }
+                }
+                info!("📅 Scheduled {} scripted events from {}", engine.scheduled_events().await.len(), path);
+            }
+            Err(e) => warn!("Not loading the event timeline from {}: {}", path, e),
+        }
+    }
+
     // Start simulation loop
     let engine_sim = engine.clone();
     tokio::spawn(async move {
@@ -141,26 +267,42 @@ async fn main() {
         }
     });
 
+    // Roll population statistics up for the ecosystem dashboard
+    let engine_rollup = engine.clone();
+    let rollup_every = Duration::from_secs(section.ecosystem_rollup_secs);
+    tokio::spawn(async move {
+        let mut rollup_interval = interval(rollup_every);
+
+        loop {
+            rollup_interval.tick().await;
+            engine_rollup.ecosystem().rollup().await;
+        }
+    });
+
     // Start gRPC server
-    let grpc_engine = engine.clone();
+    let grpc_service = WorldServiceImpl::new(engine.clone());
+    engine.register_observer(grpc_service.event_observer()).await;
     let grpc_port: u16 = std::env::var("WORLD_ENGINE_GRPC_PORT")
         .ok()
         .and_then(|p| p.parse().ok())
-        .unwrap_or(3003);
+        .unwrap_or(section.grpc_port);
     tokio::spawn(async move {
         info!("🚀 World Engine gRPC starting on port {}", grpc_port);
         Server::builder()
-            .add_service(WorldServiceServer::new(WorldServiceImpl::new(grpc_engine)))
+            .add_service(WorldServiceServer::new(grpc_service))
             .serve(([0, 0, 0, 0], grpc_port).into())
             .await
             .expect("gRPC server failed");
     });
 
     // Start HTTP server
-    let routes = world_engine::server::create_routes(engine);
+    let debug = Arc::new(finalverse_health::DebugEndpoints::load("world-engine")).warp_routes();
+    let operator = finalverse_health::OperatorGuard::load();
+    let auth = finalverse_auth::PlayerAuth::load();
+    let routes = world_engine::server::create_routes(engine, operator, auth).or(debug);
 
-    info!("🚀 World Engine HTTP API starting on port 3002");
+    info!("🚀 World Engine HTTP API starting on port {}", section.http_port);
     warp::serve(routes)
-        .run(([0, 0, 0, 0], 3002))
+        .run(([0, 0, 0, 0], section.http_port))
         .await;
 }
\ No newline at end of file
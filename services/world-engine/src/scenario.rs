@@ -0,0 +1,152 @@
+// services/world-engine/src/scenario.rs
+//! World scenarios: the whole simulated world written to a versioned JSON
+//! file by `worldctl export` and loaded into another instance by
+//! `worldctl import`, so designers can author and share starting worlds.
+
+use crate::terraform::Terraforming;
+use crate::weather::FrontPhase;
+use crate::{RegionGraph, RegionId, RegionState, SpeciesProfile, WorldEvent};
+use chrono::{DateTime, Utc};
+use finalverse_core::world_clock::WorldClockState;
+use finalverse_core::FinalverseError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Bumped whenever a field changes meaning or becomes required. Files
+/// from older versions are still read; newer ones are refused.
+pub const SCENARIO_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldScenario {
+    pub format_version: u32,
+    pub label: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub clock: WorldClockState,
+    pub regions: Vec<RegionState>,
+    #[serde(default)]
+    pub borders: RegionGraph,
+    #[serde(default)]
+    pub weather_fronts: HashMap<RegionId, FrontPhase>,
+    #[serde(default)]
+    pub species: Vec<SpeciesProfile>,
+    #[serde(default)]
+    pub active_events: Vec<WorldEvent>,
+    #[serde(default)]
+    pub yield_bonuses: HashMap<RegionId, f64>,
+    #[serde(default)]
+    pub terraforming: Vec<Terraforming>,
+}
+
+/// What an import replaced the world with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub label: Option<String>,
+    pub format_version: u32,
+    pub region_count: usize,
+    pub species_count: usize,
+    pub active_event_count: usize,
+}
+
+impl WorldScenario {
+    /// Refuses files written by a newer world-engine and worlds whose
+    /// fronts, yield bonuses or terraforming point at regions the file
+    /// lacks. Species may roam regions outside the scenario.
+    pub fn validate(&self) -> Result<(), FinalverseError> {
+        if self.format_version == 0 || self.format_version > SCENARIO_FORMAT_VERSION {
+            return Err(FinalverseError::BadRequest(format!(
+                "unsupported scenario format version {} (this world-engine reads 1..={})",
+                self.format_version, SCENARIO_FORMAT_VERSION
+            )));
+        }
+
+        let mut regions = HashSet::new();
+        for region in &self.regions {
+            if !regions.insert(&region.id) {
+                return Err(FinalverseError::BadRequest(format!("region {} appears twice", region.id.0)));
+            }
+        }
+        let unknown = |id: &RegionId, what: &str| {
+            FinalverseError::BadRequest(format!("{} refers to unknown region {}", what, id.0))
+        };
+        if let Some(id) = self.weather_fronts.keys().find(|id| !regions.contains(id)) {
+            return Err(unknown(id, "a weather front"));
+        }
+        if let Some(id) = self.yield_bonuses.keys().find(|id| !regions.contains(id)) {
+            return Err(unknown(id, "a yield bonus"));
+        }
+        if let Some(job) = self.terraforming.iter().find(|job| !regions.contains(&job.region_id)) {
+            return Err(unknown(&job.region_id, "terraforming"));
+        }
+        Ok(())
+    }
+
+    pub fn summary(&self) -> ImportSummary {
+        ImportSummary {
+            label: self.label.clone(),
+            format_version: self.format_version,
+            region_count: self.regions.len(),
+            species_count: self.species.len(),
+            active_event_count: self.active_events.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TerrainType, WeatherState, WeatherType, WorldEngine};
+
+    fn region() -> RegionState {
+        RegionState {
+            id: RegionId(uuid::Uuid::new_v4()),
+            harmony_level: 0.6,
+            discord_level: 0.1,
+            terrain_type: TerrainType::Ocean,
+            weather: WeatherState {
+                weather_type: WeatherType::Fog,
+                intensity: 0.3,
+                wind_direction: 90.0,
+                wind_speed: 4.0,
+            },
+            political_tension: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn scenarios_round_trip_into_a_fresh_world() {
+        let source = WorldEngine::new();
+        let (a, b) = (region(), region());
+        source.metabolism().add_region(a.clone()).await;
+        source.metabolism().add_region(b.clone()).await;
+        source.metabolism().connect_regions(&a.id, &b.id).await;
+        source.set_yield_bonus(a.id.clone(), 0.25).await;
+        source.set_time_paused(true).await;
+
+        let json = serde_json::to_string(&source.export_scenario(Some("twin coves".to_string())).await).unwrap();
+        let scenario: WorldScenario = serde_json::from_str(&json).unwrap();
+
+        let target = WorldEngine::new();
+        let summary = target.import_scenario(scenario).await.unwrap();
+        assert_eq!((summary.region_count, summary.label.as_deref()), (2, Some("twin coves")));
+        assert_eq!(target.metabolism().neighbors(&a.id), vec![b.id.clone()]);
+        assert_eq!(target.yield_bonus(&a.id).await, 0.25);
+        assert_eq!(target.world_time().await, source.world_time().await);
+    }
+
+    #[tokio::test]
+    async fn newer_or_inconsistent_scenarios_are_refused() {
+        let world = WorldEngine::new();
+        world.metabolism().add_region(region()).await;
+
+        let mut newer = world.export_scenario(None).await;
+        newer.format_version = SCENARIO_FORMAT_VERSION + 1;
+        assert!(matches!(world.import_scenario(newer).await, Err(FinalverseError::BadRequest(_))));
+
+        let mut dangling = world.export_scenario(None).await;
+        dangling.yield_bonuses.insert(RegionId(uuid::Uuid::new_v4()), 0.5);
+        assert!(dangling.validate().is_err());
+
+        // A refused import leaves the world as it was
+        assert_eq!(world.metabolism().regions().len(), 1);
+    }
+}
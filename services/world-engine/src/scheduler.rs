@@ -0,0 +1,283 @@
+// services/world-engine/src/scheduler.rs
+//! Scripted world events. Designers list celestial events, outbreaks and
+//! festivals on a timeline, either in the file named by
+//! `world_engine.event_timeline` or through `/admin/schedule`, and each
+//! one is sent to the observers when world time reaches it.
+//!
+//! Recurring events use a cron-like `"<minute> <hour> <day>"` pattern over
+//! world time. Each field is `*`, a number, a range `a-b`, a step `*/n` or
+//! `a-b/n`, or a comma-separated list of those; `"0 20 */7"` fires at
+//! 20:00 on days 1, 8, 15 and so on.
+
+use crate::WorldEvent;
+use finalverse_core::world_clock::{WorldInstant, SECONDS_PER_WORLD_DAY};
+use finalverse_core::FinalverseError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Most world time one tick looks back over. After a long pause or a clock
+/// jump, recurring events fire once instead of once per missed day.
+const MAX_CATCH_UP_MINUTES: u64 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Trigger {
+    /// Fires once when world time reaches `hour` on `day`. Times already
+    /// past when the event is scheduled never fire.
+    Once { day: u32, hour: f32 },
+    /// Fires at every world minute matching `cron`.
+    Recurring { cron: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub id: String,
+    pub trigger: Trigger,
+    pub event: WorldEvent,
+}
+
+/// Read a timeline: a JSON list of scheduled events.
+pub fn load_timeline(path: impl AsRef<Path>) -> anyhow::Result<Vec<ScheduledEvent>> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FieldPart {
+    start: u64,
+    end: Option<u64>,
+    step: u64,
+}
+
+impl FieldPart {
+    fn matches(&self, value: u64) -> bool {
+        value >= self.start && self.end.is_none_or(|end| value <= end) && (value - self.start).is_multiple_of(self.step)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field(Vec<FieldPart>);
+
+impl Field {
+    /// `max` is `None` for days, which never run out.
+    fn parse(field: &str, name: &str, min: u64, max: Option<u64>) -> Result<Self, String> {
+        let invalid = || format!("invalid {} field '{}'", name, field);
+        let number = |text: &str| -> Result<u64, String> {
+            let value: u64 = text.parse().map_err(|_| invalid())?;
+            if value < min || max.is_some_and(|max| value > max) {
+                return Err(invalid());
+            }
+            Ok(value)
+        };
+
+        let parts = field
+            .split(',')
+            .map(|part| {
+                let (range, step) = match part.split_once('/') {
+                    Some((range, step)) => (range, step.parse::<u64>().ok().filter(|step| *step > 0).ok_or_else(invalid)?),
+                    None => (part, 1),
+                };
+                let (start, end) = match range {
+                    "*" => (min, max),
+                    _ => match range.split_once('-') {
+                        Some((start, end)) => (number(start)?, Some(number(end)?)),
+                        // `5/10` means from 5 onwards, like `5-max/10`
+                        None if step > 1 => (number(range)?, max),
+                        None => {
+                            let value = number(range)?;
+                            (value, Some(value))
+                        }
+                    },
+                };
+                if end.is_some_and(|end| end < start) {
+                    return Err(invalid());
+                }
+                Ok(FieldPart { start, end, step })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self(parts))
+    }
+
+    fn matches(&self, value: u64) -> bool {
+        self.0.iter().any(|part| part.matches(value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CronSchedule {
+    minutes: Field,
+    hours: Field,
+    days: Field,
+}
+
+impl CronSchedule {
+    fn parse(cron: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = cron.split_whitespace().collect();
+        let [minutes, hours, days] = fields[..] else {
+            return Err(format!("cron '{}' must have three fields: <minute> <hour> <day>", cron));
+        };
+        Ok(Self {
+            minutes: Field::parse(minutes, "minute", 0, Some(59))?,
+            hours: Field::parse(hours, "hour", 0, Some(23))?,
+            days: Field::parse(days, "day", 1, None)?,
+        })
+    }
+
+    /// `minute` counts world minutes since the start of day 1.
+    fn matches(&self, minute: u64) -> bool {
+        let day = minute / (24 * 60) + 1;
+        let hour = minute / 60 % 24;
+        self.minutes.matches(minute % 60) && self.hours.matches(hour) && self.days.matches(day)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    event: ScheduledEvent,
+    cron: Option<CronSchedule>,
+}
+
+/// The timeline and how far through it the world has got.
+#[derive(Debug, Default)]
+pub struct EventScheduler {
+    entries: Vec<Entry>,
+    /// World seconds at the last [`EventScheduler::due`] call.
+    checked_until: Option<f64>,
+}
+
+impl EventScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, event: ScheduledEvent) -> Result<(), FinalverseError> {
+        if event.id.trim().is_empty() {
+            return Err(FinalverseError::BadRequest("scheduled events need an id".to_string()));
+        }
+        if self.entries.iter().any(|entry| entry.event.id == event.id) {
+            return Err(FinalverseError::Conflict(format!("event '{}' is already scheduled", event.id)));
+        }
+        let cron = match &event.trigger {
+            Trigger::Once { day, hour } => {
+                if *day == 0 || !(0.0..24.0).contains(hour) {
+                    return Err(FinalverseError::BadRequest(format!(
+                        "event '{}' must fire on day 1 or later at an hour in 0..24",
+                        event.id
+                    )));
+                }
+                None
+            }
+            Trigger::Recurring { cron } => Some(CronSchedule::parse(cron).map_err(FinalverseError::BadRequest)?),
+        };
+        self.entries.push(Entry { event, cron });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<ScheduledEvent> {
+        let index = self.entries.iter().position(|entry| entry.event.id == id)?;
+        Some(self.entries.remove(index).event)
+    }
+
+    pub fn events(&self) -> Vec<ScheduledEvent> {
+        self.entries.iter().map(|entry| entry.event.clone()).collect()
+    }
+
+    /// The events whose time came since the last call, in timeline order.
+    /// One-shot events are dropped once they fire. The first call only
+    /// marks where the world is, so nothing fires for time before startup.
+    pub fn due(&mut self, now: WorldInstant) -> Vec<WorldEvent> {
+        let Some(since) = self.checked_until.replace(now.seconds) else {
+            return Vec::new();
+        };
+        if now.seconds <= since {
+            return Vec::new();
+        }
+
+        let first_minute = (since / 60.0).floor() as u64 + 1;
+        let last_minute = (now.seconds / 60.0).floor() as u64;
+        let first_minute = first_minute.max(last_minute.saturating_sub(MAX_CATCH_UP_MINUTES - 1));
+
+        let mut fired = Vec::new();
+        self.entries.retain(|entry| match (&entry.event.trigger, &entry.cron) {
+            (Trigger::Once { day, hour }, _) => {
+                let at = (*day - 1) as f64 * SECONDS_PER_WORLD_DAY + *hour as f64 * 3600.0;
+                if at > since && at <= now.seconds {
+                    fired.push(entry.event.event.clone());
+                    return false;
+                }
+                true
+            }
+            (Trigger::Recurring { .. }, Some(cron)) => {
+                if (first_minute..=last_minute).any(|minute| cron.matches(minute)) {
+                    fired.push(entry.event.event.clone());
+                }
+                true
+            }
+            (Trigger::Recurring { .. }, None) => true,
+        });
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CelestialEventType;
+
+    fn at(day: u32, hour: f64) -> WorldInstant {
+        WorldInstant::from_seconds((day - 1) as f64 * SECONDS_PER_WORLD_DAY + hour * 3600.0)
+    }
+
+    fn festival(id: &str, trigger: Trigger) -> ScheduledEvent {
+        ScheduledEvent {
+            id: id.to_string(),
+            trigger,
+            event: WorldEvent::Festival { name: id.to_string(), region_id: None, duration: 3600 },
+        }
+    }
+
+    #[test]
+    fn cron_fields_cover_steps_ranges_and_lists() {
+        let weekly = CronSchedule::parse("0 20 */7").unwrap();
+        assert!(weekly.matches(20 * 60));
+        assert!(weekly.matches(7 * 24 * 60 + 20 * 60));
+        assert!(!weekly.matches(24 * 60 + 20 * 60));
+
+        let dusk = CronSchedule::parse("0,30 18-19 *").unwrap();
+        assert!(dusk.matches(18 * 60 + 30) && dusk.matches(19 * 60));
+        assert!(!dusk.matches(20 * 60));
+
+        assert!(CronSchedule::parse("0 24 *").is_err());
+        assert!(CronSchedule::parse("0 20 0").is_err());
+        assert!(CronSchedule::parse("*/0 * *").is_err());
+        assert!(CronSchedule::parse("0 20").is_err());
+    }
+
+    #[test]
+    fn events_fire_once_when_world_time_passes_them() {
+        let mut scheduler = EventScheduler::new();
+        scheduler.add(festival("lantern-night", Trigger::Recurring { cron: "0 20 *".to_string() })).unwrap();
+        scheduler
+            .add(ScheduledEvent {
+                id: "first-eclipse".to_string(),
+                trigger: Trigger::Once { day: 2, hour: 12.0 },
+                event: WorldEvent::CelestialEvent { event_type: CelestialEventType::Eclipse, duration: 600 },
+            })
+            .unwrap();
+        assert!(matches!(
+            scheduler.add(festival("lantern-night", Trigger::Once { day: 1, hour: 0.0 })),
+            Err(FinalverseError::Conflict(_))
+        ));
+
+        // Nothing fires for time before the first check
+        assert!(scheduler.due(at(1, 21.0)).is_empty());
+        assert!(scheduler.due(at(1, 23.0)).is_empty());
+        let fired = scheduler.due(at(2, 21.0));
+        assert_eq!(fired.len(), 2);
+        assert!(matches!(fired[1], WorldEvent::CelestialEvent { .. }));
+
+        // The eclipse was one-shot; a long jump fires the festival only once
+        assert_eq!(scheduler.events().len(), 1);
+        assert_eq!(scheduler.due(at(9, 21.0)).len(), 1);
+    }
+}
@@ -1,24 +1,255 @@
 // services/world-engine/src/server.rs
-use crate::{WorldEngine, RegionId, PlayerAction};
+use crate::{WorldEngine, WorldScenario, ScheduledEvent, RegionId, PlayerAction, DiffFilter, RollupQuery, EntityId};
+use finalverse_auth::{AuthenticatedPlayer, PlayerAuth};
+use finalverse_core::{FinalverseError, RegionEffects, TerraformKind};
+use finalverse_health::OperatorGuard;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use tokio_stream::StreamExt;
 use warp::Filter;
 
+/// Largest scenario file `POST /admin/world/import` accepts.
+pub const MAX_SCENARIO_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SnapshotRequest {
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportQuery {
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GrantRequest {
+    pub item_id: String,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CraftRequest {
+    pub recipe_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UseItemRequest {
+    pub item_id: String,
+    #[serde(default = "one")]
+    pub quantity: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarmonyRequest {
+    pub delta: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimeRateRequest {
+    pub rate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YieldBonusRequest {
+    pub bonus: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EntityQuery {
+    pub region_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpawnRequest {
+    pub components: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TerraformRequest {
+    pub proposal_id: uuid::Uuid,
+    pub kind: TerraformKind,
+    pub stage_hours: i64,
+}
+
 pub async fn health_handler() -> Result<impl warp::Reply, warp::Rejection> {
     Ok(warp::reply::json(&serde_json::json!({"status": "healthy"})))
 }
 
+/// Every region as of the last simulation write.
+pub async fn regions_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    let snapshot = engine.metabolism().snapshot();
+    let regions: Vec<_> = snapshot.values().collect();
+    Ok(warp::reply::json(&regions))
+}
+
+/// Where each region lies.
+pub async fn region_map_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(engine.region_map()))
+}
+
 pub async fn region_handler(
     id: String,
     engine: Arc<WorldEngine>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     if let Ok(uuid) = uuid::Uuid::parse_str(&id) {
-        if let Some(region) = engine.metabolism().get_region(&RegionId(uuid)).await {
+        if let Some(region) = engine.metabolism().get_region(&RegionId(uuid)) {
             return Ok(warp::reply::json(&region));
         }
     }
     Ok(warp::reply::json(&serde_json::json!({"error": "Region not found"})))
 }
 
+/// A region's current weather and which way its front is moving.
+pub async fn region_weather_handler(
+    id: String,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let region = uuid::Uuid::parse_str(&id)
+        .ok()
+        .and_then(|uuid| engine.metabolism().get_region(&RegionId(uuid)));
+    match region {
+        Some(region) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({
+                "region_id": region.id,
+                "weather": region.weather,
+                "front": engine.weather().front(&region.id),
+                "harmony_level": region.harmony_level,
+                "discord_level": region.discord_level,
+            })),
+            warp::http::StatusCode::OK,
+        )),
+        None => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "Region not found"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+pub async fn harmony_handler(
+    id: String,
+    request: HarmonyRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // Harmony is on a 0-1 scale, so one update may move it by at most the whole range
+    if !request.delta.is_finite() || !(-1.0..=1.0).contains(&request.delta) {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "Harmony delta must be between -1 and 1"})),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    let result = match uuid::Uuid::parse_str(&id) {
+        Ok(uuid) => engine.update_region_harmony(&RegionId(uuid), request.delta).await,
+        Err(e) => Err(e.into()),
+    };
+    match result {
+        Ok(update) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"harmony_level": update.new_harmony_level})),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+/// Effects another service had on a region, applied here so every
+/// service reads the same region state back.
+pub async fn region_effects_handler(
+    id: String,
+    effects: RegionEffects,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let region_id = uuid::Uuid::parse_str(&id)
+        .map(RegionId)
+        .map_err(|_| finalverse_core::warp::reject(FinalverseError::BadRequest(format!("Invalid region id '{}'", id))))?;
+    match engine.apply_region_effects(&region_id, &effects).await {
+        Ok(region) => Ok(warp::reply::json(&region)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn get_yield_handler(
+    id: String,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bonus = match uuid::Uuid::parse_str(&id) {
+        Ok(uuid) => engine.yield_bonus(&RegionId(uuid)).await,
+        Err(_) => 0.0,
+    };
+    Ok(warp::reply::json(&serde_json::json!({"bonus": bonus})))
+}
+
+pub async fn set_yield_handler(
+    id: String,
+    request: YieldBonusRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match uuid::Uuid::parse_str(&id) {
+        Ok(uuid) => {
+            engine.set_yield_bonus(RegionId(uuid), request.bonus).await;
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"bonus": request.bonus})),
+                warp::http::StatusCode::OK,
+            ))
+        }
+        Err(_) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "Invalid region id"})),
+            warp::http::StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+pub async fn start_terraform_handler(
+    id: String,
+    request: TerraformRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match uuid::Uuid::parse_str(&id) {
+        Ok(uuid) => {
+            engine
+                .start_terraforming(RegionId(uuid), request.proposal_id, request.kind, request.stage_hours)
+                .await
+        }
+        Err(_) => Err("Invalid region id".to_string()),
+    };
+    match result {
+        Ok(status) => Ok(warp::reply::with_status(
+            warp::reply::json(&status),
+            warp::http::StatusCode::CREATED,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e})),
+            warp::http::StatusCode::CONFLICT,
+        )),
+    }
+}
+
+pub async fn terraform_status_handler(
+    id: String,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let status = match uuid::Uuid::parse_str(&id) {
+        Ok(uuid) => engine.terraform_status(&RegionId(uuid)).await,
+        Err(_) => None,
+    };
+    match status {
+        Some(status) => Ok(warp::reply::with_status(
+            warp::reply::json(&status),
+            warp::http::StatusCode::OK,
+        )),
+        None => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "Region is not being terraformed"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
 pub async fn action_handler(
     action: PlayerAction,
     engine: Arc<WorldEngine>,
@@ -27,19 +258,334 @@ pub async fn action_handler(
     Ok(warp::reply::json(&serde_json::json!({"success": true})))
 }
 
+pub async fn take_snapshot_handler(
+    request: SnapshotRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let summary = engine.take_snapshot(request.label).await;
+    Ok(warp::reply::json(&summary))
+}
+
+pub async fn list_snapshots_handler(
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.list_snapshots().await))
+}
+
+pub async fn diff_snapshots_handler(
+    from: String,
+    to: String,
+    filter: DiffFilter,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.diff_snapshots(&from, &to, &filter).await {
+        Some(diff) => Ok(warp::reply::with_status(
+            warp::reply::json(&diff),
+            warp::http::StatusCode::OK,
+        )),
+        None => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "Snapshot not found"})),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+/// The whole world as a scenario file, for `worldctl export`.
+pub async fn export_world_handler(
+    query: ExportQuery,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.export_scenario(query.label).await))
+}
+
+/// Replace the world with a scenario file, for `worldctl import`.
+pub async fn import_world_handler(
+    scenario: WorldScenario,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.import_scenario(scenario).await {
+        Ok(summary) => Ok(warp::reply::json(&summary)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn list_schedule_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.scheduled_events().await))
+}
+
+pub async fn schedule_event_handler(
+    event: ScheduledEvent,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let reply = warp::reply::json(&event);
+    match engine.schedule_event(event).await {
+        Ok(()) => Ok(warp::reply::with_status(reply, warp::http::StatusCode::CREATED)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn unschedule_event_handler(
+    id: String,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.unschedule_event(&id).await {
+        Some(event) => Ok(warp::reply::json(&event)),
+        None => Err(finalverse_core::warp::reject(FinalverseError::NotFound(format!(
+            "no scheduled event '{}'",
+            id
+        )))),
+    }
+}
+
+pub async fn items_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.inventory().catalog().items()))
+}
+
+pub async fn recipes_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.inventory().catalog().recipes()))
+}
+
+pub async fn inventory_handler(
+    player_id: String,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.inventory().inventory(&player_id).await {
+        Ok(inventory) => Ok(warp::reply::json(&inventory)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn grant_items_handler(
+    player_id: String,
+    request: GrantRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.inventory().grant(&player_id, &request.item_id, request.quantity).await {
+        Ok(inventory) => Ok(warp::reply::json(&inventory)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+/// The inventory in the path, if it's the caller's own.
+fn own_inventory(player_id: &str, caller: &AuthenticatedPlayer) -> Result<String, warp::Rejection> {
+    match uuid::Uuid::parse_str(player_id) {
+        Ok(id) if id == caller.player_id.0 => Ok(id.to_string()),
+        _ => Err(finalverse_core::warp::reject(FinalverseError::Forbidden(
+            "Players can only use their own inventory".to_string(),
+        ))),
+    }
+}
+
+pub async fn craft_handler(
+    player_id: String,
+    caller: AuthenticatedPlayer,
+    request: CraftRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let player_id = own_inventory(&player_id, &caller)?;
+    match engine.inventory().craft(&player_id, &request.recipe_id).await {
+        Ok(inventory) => Ok(warp::reply::json(&inventory)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn use_item_handler(
+    player_id: String,
+    caller: AuthenticatedPlayer,
+    request: UseItemRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let player_id = own_inventory(&player_id, &caller)?;
+    match engine.inventory().consume(&player_id, &request.item_id, request.quantity).await {
+        Ok(inventory) => Ok(warp::reply::json(&inventory)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn entities_handler(
+    query: EntityQuery,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let region_id = query.region_id.map(RegionId);
+    Ok(warp::reply::json(&engine.entities(region_id.as_ref()).await))
+}
+
+pub async fn spawn_entity_handler(
+    request: SpawnRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.spawn_entity(request.components).await {
+        Ok(entity) => Ok(warp::reply::with_status(
+            warp::reply::json(&entity),
+            warp::http::StatusCode::CREATED,
+        )),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+pub async fn get_entity_handler(id: u64, engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.entity(EntityId(id)).await {
+        Some(entity) => Ok(warp::reply::json(&entity)),
+        None => Err(finalverse_core::warp::reject(FinalverseError::NotFound(format!("Entity {}", id)))),
+    }
+}
+
+pub async fn despawn_entity_handler(
+    id: u64,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if engine.despawn_entity(EntityId(id)).await {
+        Ok(warp::reply::json(&serde_json::json!({"success": true})))
+    } else {
+        Err(finalverse_core::warp::reject(FinalverseError::NotFound(format!("Entity {}", id))))
+    }
+}
+
+/// Harvests go to the authenticated player's inventory.
+pub async fn interact_handler(
+    id: u64,
+    player: AuthenticatedPlayer,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.interact_with_entity(&player.player_id.0.to_string(), EntityId(id)).await {
+        Ok(interaction) => Ok(warp::reply::json(&interaction)),
+        Err(e) => Err(finalverse_core::warp::reject(e)),
+    }
+}
+
+/// Entity changes as server-sent events, one `entities` event per batch.
+pub async fn entity_stream_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut changes = engine.subscribe_entities();
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    tokio::spawn(async move {
+        loop {
+            match changes.recv().await {
+                Ok(batch) => {
+                    if tx.send(batch).await.is_err() {
+                        return;
+                    }
+                }
+                // A lagging client catches up from the next batch
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    });
+    let events = tokio_stream::wrappers::ReceiverStream::new(rx)
+        .map(|batch| warp::sse::Event::default().event("entities").json_data(batch));
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+}
+
+pub async fn ecosystem_rollups_handler(
+    query: RollupQuery,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.ecosystem().query_rollups(&query).await))
+}
+
+pub async fn clock_handler(engine: Arc<WorldEngine>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.clock_sync().await))
+}
+
+pub async fn set_time_rate_handler(
+    request: TimeRateRequest,
+    engine: Arc<WorldEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.set_time_rate(request.rate).await {
+        Ok(sync) => Ok(warp::reply::with_status(
+            warp::reply::json(&sync),
+            warp::http::StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": e})),
+            warp::http::StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+/// World-engine's HTTP API. Admin routes, which change the world for
+/// everyone, need `operator`'s token; routes acting for a player need
+/// their token, checked by `auth`.
 pub fn create_routes(
-    engine: Arc<WorldEngine>
+    engine: Arc<WorldEngine>,
+    operator: OperatorGuard,
+    auth: PlayerAuth,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let operator = operator.warp_filter();
+    let player = finalverse_auth::warp::player(auth);
+
     let health = warp::path!("health")
         .and(warp::get())
         .and_then(health_handler);
 
+    let engine_regions = engine.clone();
+    let list_regions = warp::path!("regions")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_regions.clone()))
+        .and_then(regions_handler);
+
+    let engine_map = engine.clone();
+    let region_map = warp::path!("regions" / "map")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_map.clone()))
+        .and_then(region_map_handler);
+
     let engine_get = engine.clone();
     let get_region = warp::path!("region" / String)
         .and(warp::get())
         .and(warp::any().map(move || engine_get.clone()))
         .and_then(region_handler);
 
+    let engine_weather = engine.clone();
+    let region_weather = warp::path!("regions" / String / "weather")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_weather.clone()))
+        .and_then(region_weather_handler);
+
+    let engine_harmony = engine.clone();
+    let region_harmony = warp::path!("region" / String / "harmony")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_harmony.clone()))
+        .and_then(harmony_handler);
+
+    let engine_effects = engine.clone();
+    let region_effects = warp::path!("regions" / String / "effects")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_effects.clone()))
+        .and_then(region_effects_handler);
+
+    let engine_yield = engine.clone();
+    let get_yield = warp::path!("region" / String / "yield")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_yield.clone()))
+        .and_then(get_yield_handler);
+
+    let engine_set_yield = engine.clone();
+    let set_yield = warp::path!("region" / String / "yield")
+        .and(warp::put())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_set_yield.clone()))
+        .and_then(set_yield_handler);
+
+    let engine_terraform = engine.clone();
+    let start_terraform = warp::path!("region" / String / "terraform")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_terraform.clone()))
+        .and_then(start_terraform_handler);
+
+    let engine_terraform_status = engine.clone();
+    let terraform_status = warp::path!("region" / String / "terraform")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_terraform_status.clone()))
+        .and_then(terraform_status_handler);
+
     let engine_post = engine.clone();
     let post_action = warp::path!("action")
         .and(warp::post())
@@ -47,5 +593,242 @@ pub fn create_routes(
         .and(warp::any().map(move || engine_post.clone()))
         .and_then(action_handler);
 
-    health.or(get_region).or(post_action)
+    let engine_snap = engine.clone();
+    let take_snapshot = warp::path!("snapshots")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_snap.clone()))
+        .and_then(take_snapshot_handler);
+
+    let engine_list = engine.clone();
+    let list_snapshots = warp::path!("snapshots")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_list.clone()))
+        .and_then(list_snapshots_handler);
+
+    let engine_diff = engine.clone();
+    let diff_snapshots = warp::path!("snapshots" / "diff" / String / String)
+        .and(warp::get())
+        .and(warp::query::<DiffFilter>())
+        .and(warp::any().map(move || engine_diff.clone()))
+        .and_then(diff_snapshots_handler);
+
+    let engine_export = engine.clone();
+    let export_world = warp::path!("admin" / "world" / "export")
+        .and(warp::get())
+        .and(operator.clone())
+        .and(warp::query::<ExportQuery>())
+        .and(warp::any().map(move || engine_export.clone()))
+        .and_then(export_world_handler);
+
+    let engine_import = engine.clone();
+    let import_world = warp::path!("admin" / "world" / "import")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::content_length_limit(MAX_SCENARIO_BYTES))
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_import.clone()))
+        .and_then(import_world_handler);
+
+    let engine_schedule = engine.clone();
+    let list_schedule = warp::path!("admin" / "schedule")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_schedule.clone()))
+        .and_then(list_schedule_handler);
+
+    let engine_add_event = engine.clone();
+    let schedule_event = warp::path!("admin" / "schedule")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_add_event.clone()))
+        .and_then(schedule_event_handler);
+
+    let engine_remove_event = engine.clone();
+    let unschedule_event = warp::path!("admin" / "schedule" / String)
+        .and(warp::delete())
+        .and(operator.clone())
+        .and(warp::any().map(move || engine_remove_event.clone()))
+        .and_then(unschedule_event_handler);
+
+    let engine_items = engine.clone();
+    let list_items = warp::path!("items")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_items.clone()))
+        .and_then(items_handler);
+
+    let engine_recipes = engine.clone();
+    let list_recipes = warp::path!("recipes")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_recipes.clone()))
+        .and_then(recipes_handler);
+
+    let engine_inventory = engine.clone();
+    let get_inventory = warp::path!("inventory" / String)
+        .and(warp::get())
+        .and(warp::any().map(move || engine_inventory.clone()))
+        .and_then(inventory_handler);
+
+    let engine_grant = engine.clone();
+    let grant_items = warp::path!("inventory" / String / "items")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_grant.clone()))
+        .and_then(grant_items_handler);
+
+    let engine_craft = engine.clone();
+    let craft = warp::path!("inventory" / String / "craft")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_craft.clone()))
+        .and_then(craft_handler);
+
+    let engine_use = engine.clone();
+    let use_item = warp::path!("inventory" / String / "use")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_use.clone()))
+        .and_then(use_item_handler);
+
+    let engine_entities = engine.clone();
+    let list_entities = warp::path!("entities")
+        .and(warp::get())
+        .and(warp::query::<EntityQuery>())
+        .and(warp::any().map(move || engine_entities.clone()))
+        .and_then(entities_handler);
+
+    let engine_spawn = engine.clone();
+    let spawn_entity = warp::path!("entities")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_spawn.clone()))
+        .and_then(spawn_entity_handler);
+
+    let engine_stream = engine.clone();
+    let entity_stream = warp::path!("entities" / "stream")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_stream.clone()))
+        .and_then(entity_stream_handler);
+
+    let engine_entity = engine.clone();
+    let get_entity = warp::path!("entities" / u64)
+        .and(warp::get())
+        .and(warp::any().map(move || engine_entity.clone()))
+        .and_then(get_entity_handler);
+
+    let engine_despawn = engine.clone();
+    let despawn_entity = warp::path!("entities" / u64)
+        .and(warp::delete())
+        .and(operator.clone())
+        .and(warp::any().map(move || engine_despawn.clone()))
+        .and_then(despawn_entity_handler);
+
+    let engine_interact = engine.clone();
+    let interact = warp::path!("entities" / u64 / "interact")
+        .and(warp::post())
+        .and(player.clone())
+        .and(warp::any().map(move || engine_interact.clone()))
+        .and_then(interact_handler);
+
+    let engine_rollups = engine.clone();
+    let ecosystem_rollups = warp::path!("ecosystem" / "rollups")
+        .and(warp::get())
+        .and(warp::query::<RollupQuery>())
+        .and(warp::any().map(move || engine_rollups.clone()))
+        .and_then(ecosystem_rollups_handler);
+
+    let engine_clock = engine.clone();
+    let get_clock = warp::path!("clock")
+        .and(warp::get())
+        .and(warp::any().map(move || engine_clock.clone()))
+        .and_then(clock_handler);
+
+    let engine_rate = engine.clone();
+    let set_rate = warp::path!("clock" / "rate")
+        .and(warp::put())
+        .and(operator.clone())
+        .and(warp::body::json())
+        .and(warp::any().map(move || engine_rate.clone()))
+        .and_then(set_time_rate_handler);
+
+    let engine_pause = engine.clone();
+    let pause_clock = warp::path!("clock" / "pause")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::any().map(move || engine_pause.clone()))
+        .and_then(|engine: Arc<WorldEngine>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&engine.set_time_paused(true).await))
+        });
+
+    let engine_resume = engine.clone();
+    let resume_clock = warp::path!("clock" / "resume")
+        .and(warp::post())
+        .and(operator.clone())
+        .and(warp::any().map(move || engine_resume.clone()))
+        .and_then(|engine: Arc<WorldEngine>| async move {
+            Ok::<_, warp::Rejection>(warp::reply::json(&engine.set_time_paused(false).await))
+        });
+
+    // Routes are boxed in groups: a single chain of all of them is too deep
+    // for the compiler to lay out
+    let clock = get_clock
+        .or(set_rate)
+        .or(pause_clock)
+        .or(resume_clock)
+        .map(warp::Reply::into_response)
+        .boxed();
+    let regions = list_regions
+        .or(region_map)
+        .or(get_region)
+        .or(region_weather)
+        .or(region_harmony)
+        .or(region_effects)
+        .or(get_yield)
+        .or(set_yield)
+        .or(start_terraform)
+        .or(terraform_status)
+        .or(post_action)
+        .map(warp::Reply::into_response)
+        .boxed();
+    let admin = take_snapshot
+        .or(list_snapshots)
+        .or(diff_snapshots)
+        .or(export_world)
+        .or(import_world)
+        .or(list_schedule)
+        .or(schedule_event)
+        .or(unschedule_event)
+        .map(warp::Reply::into_response)
+        .boxed();
+    let inventory = list_items
+        .or(list_recipes)
+        .or(get_inventory)
+        .or(grant_items)
+        .or(craft)
+        .or(use_item)
+        .map(warp::Reply::into_response)
+        .boxed();
+    let entities = list_entities
+        .or(spawn_entity)
+        .or(entity_stream)
+        .or(get_entity)
+        .or(despawn_entity)
+        .or(interact)
+        .or(ecosystem_rollups)
+        .map(warp::Reply::into_response)
+        .boxed();
+
+    health
+        .or(clock)
+        .or(regions)
+        .or(admin)
+        .or(inventory)
+        .or(entities)
+        .recover(finalverse_core::warp::recover)
+        .recover(finalverse_auth::warp::recover)
 }
\ No newline at end of file
@@ -0,0 +1,308 @@
+// services/world-engine/src/snapshot.rs
+//! World snapshots and the diff engine designers use to review balance changes.
+
+use crate::{RegionId, RegionState, SpeciesProfile, TerrainType, WeatherType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Snapshots a world keeps for diffing. Each holds every region and
+/// species, so older ones are dropped past this.
+pub const MAX_SNAPSHOTS: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub id: String,
+    pub label: Option<String>,
+    pub taken_at: DateTime<Utc>,
+    pub regions: Vec<RegionState>,
+    pub species: Vec<SpeciesProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub label: Option<String>,
+    pub taken_at: DateTime<Utc>,
+    pub region_count: usize,
+    pub species_count: usize,
+}
+
+impl WorldSnapshot {
+    pub fn summary(&self) -> SnapshotSummary {
+        SnapshotSummary {
+            id: self.id.clone(),
+            label: self.label.clone(),
+            taken_at: self.taken_at,
+            region_count: self.regions.len(),
+            species_count: self.species.len(),
+        }
+    }
+
+    fn average_harmony(&self) -> f64 {
+        if self.regions.is_empty() {
+            return 0.0;
+        }
+        self.regions.iter().map(|r| r.harmony_level).sum::<f64>() / self.regions.len() as f64
+    }
+}
+
+/// Filtering and thresholds applied when building a diff report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffFilter {
+    /// Ignore regions whose harmony and discord moved less than this.
+    pub min_harmony_delta: f64,
+    /// Ignore species whose population moved less than this.
+    pub min_population_change: u64,
+    /// Restrict the report to a single region.
+    pub region: Option<String>,
+    /// Only report regions whose terrain changed.
+    pub terrain_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainChange {
+    pub from: TerrainType,
+    pub to: TerrainType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherChange {
+    pub from: WeatherType,
+    pub to: WeatherType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionDiff {
+    pub region_id: RegionId,
+    pub change: RegionChangeKind,
+    pub harmony_delta: f64,
+    pub discord_delta: f64,
+    pub terrain: Option<TerrainChange>,
+    pub weather: Option<WeatherChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopulationDiff {
+    pub species_id: String,
+    pub name: String,
+    pub before: u64,
+    pub after: u64,
+    pub delta: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub from: String,
+    pub to: String,
+    pub average_harmony_delta: f64,
+    pub regions: Vec<RegionDiff>,
+    pub populations: Vec<PopulationDiff>,
+}
+
+/// Compare two snapshots and produce a structured report.
+pub fn diff_snapshots(from: &WorldSnapshot, to: &WorldSnapshot, filter: &DiffFilter) -> SnapshotDiff {
+    let before: HashMap<&RegionId, &RegionState> = from.regions.iter().map(|r| (&r.id, r)).collect();
+    let after: HashMap<&RegionId, &RegionState> = to.regions.iter().map(|r| (&r.id, r)).collect();
+
+    let mut regions = Vec::new();
+    for (id, new) in &after {
+        let diff = match before.get(id) {
+            Some(old) => RegionDiff {
+                region_id: (*id).clone(),
+                change: RegionChangeKind::Modified,
+                harmony_delta: new.harmony_level - old.harmony_level,
+                discord_delta: new.discord_level - old.discord_level,
+                terrain: (old.terrain_type != new.terrain_type).then(|| TerrainChange {
+                    from: old.terrain_type.clone(),
+                    to: new.terrain_type.clone(),
+                }),
+                weather: (old.weather.weather_type != new.weather.weather_type).then(|| WeatherChange {
+                    from: old.weather.weather_type.clone(),
+                    to: new.weather.weather_type.clone(),
+                }),
+            },
+            None => RegionDiff {
+                region_id: (*id).clone(),
+                change: RegionChangeKind::Added,
+                harmony_delta: new.harmony_level,
+                discord_delta: new.discord_level,
+                terrain: None,
+                weather: None,
+            },
+        };
+        regions.push(diff);
+    }
+    for (id, old) in &before {
+        if !after.contains_key(id) {
+            regions.push(RegionDiff {
+                region_id: (*id).clone(),
+                change: RegionChangeKind::Removed,
+                harmony_delta: -old.harmony_level,
+                discord_delta: -old.discord_level,
+                terrain: None,
+                weather: None,
+            });
+        }
+    }
+    regions.retain(|diff| region_passes(diff, filter));
+    regions.sort_by(|a, b| b.harmony_delta.abs().total_cmp(&a.harmony_delta.abs()));
+
+    let old_species: HashMap<&str, &SpeciesProfile> = from.species.iter().map(|s| (s.id.as_str(), s)).collect();
+    let new_species: HashMap<&str, &SpeciesProfile> = to.species.iter().map(|s| (s.id.as_str(), s)).collect();
+    let mut species_ids: Vec<&str> = old_species.keys().chain(new_species.keys()).copied().collect();
+    species_ids.sort_unstable();
+    species_ids.dedup();
+
+    let populations = species_ids
+        .into_iter()
+        .filter_map(|id| {
+            let before = old_species.get(id).map(|s| s.population).unwrap_or(0);
+            let after = new_species.get(id).map(|s| s.population).unwrap_or(0);
+            let name = new_species.get(id).or_else(|| old_species.get(id))?.name.clone();
+            let change = before.abs_diff(after);
+            if change == 0 || change < filter.min_population_change {
+                return None;
+            }
+            Some(PopulationDiff {
+                species_id: id.to_string(),
+                name,
+                before,
+                after,
+                delta: after as i64 - before as i64,
+            })
+        })
+        .collect();
+
+    SnapshotDiff {
+        from: from.id.clone(),
+        to: to.id.clone(),
+        average_harmony_delta: to.average_harmony() - from.average_harmony(),
+        regions,
+        populations,
+    }
+}
+
+fn region_passes(diff: &RegionDiff, filter: &DiffFilter) -> bool {
+    if let Some(region) = &filter.region {
+        if diff.region_id.0.to_string() != *region {
+            return false;
+        }
+    }
+    if filter.terrain_only {
+        return diff.terrain.is_some();
+    }
+    let changed = diff.terrain.is_some()
+        || diff.weather.is_some()
+        || !matches!(diff.change, RegionChangeKind::Modified);
+    let magnitude = diff.harmony_delta.abs().max(diff.discord_delta.abs());
+    changed || (magnitude > 0.0 && magnitude >= filter.min_harmony_delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MigrationPhase, Species, WeatherState, WorldEngine};
+
+    fn region(harmony_level: f64, terrain_type: TerrainType) -> RegionState {
+        RegionState {
+            id: RegionId(uuid::Uuid::new_v4()),
+            harmony_level,
+            discord_level: 0.1,
+            terrain_type,
+            weather: WeatherState {
+                weather_type: WeatherType::Clear,
+                intensity: 0.2,
+                wind_direction: 0.0,
+                wind_speed: 2.0,
+            },
+            political_tension: 0.0,
+        }
+    }
+
+    fn deer(population: u64) -> SpeciesProfile {
+        SpeciesProfile {
+            id: "star-deer".to_string(),
+            name: "Star-Horned Deer".to_string(),
+            species: Species::StarHornedStag { herd_size: 3, migration_phase: MigrationPhase::Resting },
+            population,
+            migration_pattern: Vec::new(),
+            preferred_terrain: vec![TerrainType::Forest],
+            region_population: Default::default(),
+        }
+    }
+
+    fn snapshot(id: &str, regions: Vec<RegionState>, species: Vec<SpeciesProfile>) -> WorldSnapshot {
+        WorldSnapshot { id: id.to_string(), label: None, taken_at: Utc::now(), regions, species }
+    }
+
+    #[test]
+    fn diffs_report_added_removed_and_modified_regions() {
+        let kept = region(0.5, TerrainType::Forest);
+        let dropped = region(0.4, TerrainType::Plains);
+        let added = region(0.7, TerrainType::Ocean);
+        let mut grown = kept.clone();
+        grown.harmony_level = 0.8;
+        grown.terrain_type = TerrainType::Plains;
+        let from = snapshot("a", vec![kept.clone(), dropped.clone()], vec![deer(150)]);
+        let to = snapshot("b", vec![grown, added.clone()], vec![deer(120)]);
+
+        let diff = diff_snapshots(&from, &to, &DiffFilter::default());
+        assert_eq!(diff.regions.len(), 3);
+        // Largest harmony movement first
+        assert_eq!(diff.regions[0].region_id, added.id);
+        assert!(matches!(diff.regions[0].change, RegionChangeKind::Added));
+        let modified = diff.regions.iter().find(|r| r.region_id == kept.id).unwrap();
+        assert!((modified.harmony_delta - 0.3).abs() < 1e-9);
+        assert!(modified.terrain.is_some());
+        let removed = diff.regions.iter().find(|r| r.region_id == dropped.id).unwrap();
+        assert!(matches!(removed.change, RegionChangeKind::Removed));
+        assert_eq!(diff.populations.len(), 1);
+        assert_eq!((diff.populations[0].before, diff.populations[0].delta), (150, -30));
+        assert!((diff.average_harmony_delta - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn filters_drop_small_and_unrelated_changes() {
+        let (calm, stirred) = (region(0.5, TerrainType::Forest), region(0.5, TerrainType::Forest));
+        let mut calm_after = calm.clone();
+        calm_after.harmony_level = 0.52;
+        let mut stirred_after = stirred.clone();
+        stirred_after.harmony_level = 0.9;
+        let from = snapshot("a", vec![calm, stirred.clone()], vec![deer(150)]);
+        let to = snapshot("b", vec![calm_after, stirred_after], vec![deer(148)]);
+
+        let filter = DiffFilter { min_harmony_delta: 0.1, min_population_change: 5, ..Default::default() };
+        let diff = diff_snapshots(&from, &to, &filter);
+        assert_eq!(diff.regions.len(), 1);
+        assert_eq!(diff.regions[0].region_id, stirred.id);
+        assert!(diff.populations.is_empty());
+
+        let terrain_only = DiffFilter { terrain_only: true, ..Default::default() };
+        assert!(diff_snapshots(&from, &to, &terrain_only).regions.is_empty());
+
+        let one_region = DiffFilter { region: Some(stirred.id.0.to_string()), ..Default::default() };
+        assert_eq!(diff_snapshots(&from, &to, &one_region).regions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn the_oldest_snapshots_are_dropped() {
+        let world = WorldEngine::new();
+        let first = world.take_snapshot(Some("first".to_string())).await;
+        for _ in 0..MAX_SNAPSHOTS {
+            world.take_snapshot(None).await;
+        }
+        let stored = world.list_snapshots().await;
+        assert_eq!(stored.len(), MAX_SNAPSHOTS);
+        assert!(stored.iter().all(|s| s.id != first.id));
+    }
+}
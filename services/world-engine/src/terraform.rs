@@ -0,0 +1,98 @@
+// services/world-engine/src/terraform.rs
+//! Staged transformations of a region's terrain, started once the
+//! community approves a terraforming proposal. Each stage is visible to
+//! clients for `stage_hours` before the next begins; the target terrain is
+//! applied when the last stage is reached.
+
+use crate::{RegionId, TerrainType};
+use chrono::{DateTime, Duration, Utc};
+use finalverse_core::TerraformKind;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Harmony restored to the region as each stage is reached.
+pub const STAGE_HARMONY: f64 = 0.05;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Terraforming {
+    pub proposal_id: Uuid,
+    pub region_id: RegionId,
+    pub kind: TerraformKind,
+    pub from_terrain: TerrainType,
+    pub started_at: DateTime<Utc>,
+    pub stage_hours: i64,
+    /// Index into `kind.stages()` of the stage currently shown.
+    pub stage: usize,
+}
+
+/// The visible state of an ongoing or finished transformation.
+#[derive(Debug, Clone, Serialize)]
+pub struct TerraformStatus {
+    pub proposal_id: Uuid,
+    pub region_id: RegionId,
+    pub kind: TerraformKind,
+    pub from_terrain: TerrainType,
+    pub target_terrain: TerrainType,
+    pub stage: &'static str,
+    pub stage_index: usize,
+    pub stage_count: usize,
+    /// Overall progress, 0.0..=1.0.
+    pub progress: f32,
+    pub complete: bool,
+    pub next_stage_at: Option<DateTime<Utc>>,
+}
+
+impl Terraforming {
+    pub fn new(proposal_id: Uuid, region_id: RegionId, kind: TerraformKind, from_terrain: TerrainType, stage_hours: i64, now: DateTime<Utc>) -> Self {
+        Self {
+            proposal_id,
+            region_id,
+            kind,
+            from_terrain,
+            started_at: now,
+            stage_hours: stage_hours.max(1),
+            stage: 0,
+        }
+    }
+
+    fn last_stage(&self) -> usize {
+        self.kind.stages().len() - 1
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.stage == self.last_stage()
+    }
+
+    /// The stage that should be showing at `now`.
+    fn stage_at(&self, now: DateTime<Utc>) -> usize {
+        let elapsed = (now - self.started_at).num_hours().max(0);
+        ((elapsed / self.stage_hours) as usize).min(self.last_stage())
+    }
+
+    /// Move to the stage due at `now`. Returns the stages newly reached.
+    pub fn advance(&mut self, now: DateTime<Utc>) -> Vec<&'static str> {
+        let due = self.stage_at(now);
+        let reached = self.kind.stages()[self.stage + 1..=due.max(self.stage)].to_vec();
+        self.stage = due.max(self.stage);
+        reached
+    }
+
+    pub fn status(&self) -> TerraformStatus {
+        let stages = self.kind.stages();
+        let complete = self.is_complete();
+        TerraformStatus {
+            proposal_id: self.proposal_id,
+            region_id: self.region_id.clone(),
+            kind: self.kind,
+            from_terrain: self.from_terrain.clone(),
+            target_terrain: self.kind.target_terrain(),
+            stage: stages[self.stage],
+            stage_index: self.stage,
+            stage_count: stages.len(),
+            progress: self.stage as f32 / self.last_stage().max(1) as f32,
+            complete,
+            next_stage_at: (!complete)
+                .then(|| self.started_at + Duration::hours(self.stage_hours * (self.stage as i64 + 1))),
+        }
+    }
+}
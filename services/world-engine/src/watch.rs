@@ -0,0 +1,115 @@
+// services/world-engine/src/watch.rs
+//! Incremental region updates for `WatchWorldState`. A watcher remembers
+//! the last snapshot it saw and reports only regions that changed since,
+//! so clients get one full picture and then deltas instead of re-fetching
+//! every region.
+
+use crate::{RegionId, RegionState};
+use finalverse_metobolism::RegionSnapshot;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub enum RegionDelta {
+    Changed(RegionState),
+    Removed(RegionId),
+}
+
+pub struct RegionWatch {
+    filter: Option<HashSet<RegionId>>,
+    last: RegionSnapshot,
+}
+
+impl RegionWatch {
+    /// Watch the given regions, or every region when `filter` is `None`.
+    pub fn new(filter: Option<HashSet<RegionId>>) -> Self {
+        Self {
+            filter,
+            last: Arc::new(HashMap::new()),
+        }
+    }
+
+    fn watches(&self, id: &RegionId) -> bool {
+        self.filter.as_ref().is_none_or(|filter| filter.contains(id))
+    }
+
+    /// What changed between the last snapshot seen and `snapshot`. The
+    /// first poll reports every watched region.
+    pub fn poll(&mut self, snapshot: RegionSnapshot) -> Vec<RegionDelta> {
+        if Arc::ptr_eq(&self.last, &snapshot) {
+            return Vec::new();
+        }
+
+        let mut deltas: Vec<RegionDelta> = snapshot
+            .values()
+            .filter(|region| self.watches(&region.id))
+            .filter(|region| self.last.get(&region.id).is_none_or(|old| region_changed(old, region)))
+            .map(|region| RegionDelta::Changed(region.clone()))
+            .collect();
+        deltas.extend(
+            self.last
+                .keys()
+                .filter(|id| self.watches(id) && !snapshot.contains_key(*id))
+                .map(|id| RegionDelta::Removed(id.clone())),
+        );
+
+        self.last = snapshot;
+        deltas
+    }
+}
+
+fn region_changed(old: &RegionState, new: &RegionState) -> bool {
+    old.harmony_level != new.harmony_level
+        || old.discord_level != new.discord_level
+        || old.political_tension != new.political_tension
+        || old.terrain_type != new.terrain_type
+        || old.weather.weather_type != new.weather.weather_type
+        || old.weather.intensity != new.weather.intensity
+        || old.weather.wind_direction != new.weather.wind_direction
+        || old.weather.wind_speed != new.weather.wind_speed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MetabolismSimulator, TerrainType, WeatherState, WeatherType};
+
+    fn region() -> RegionState {
+        RegionState {
+            id: RegionId(uuid::Uuid::new_v4()),
+            harmony_level: 0.5,
+            discord_level: 0.0,
+            terrain_type: TerrainType::Forest,
+            weather: WeatherState {
+                weather_type: WeatherType::Clear,
+                intensity: 0.0,
+                wind_direction: 0.0,
+                wind_speed: 0.0,
+            },
+            political_tension: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn watchers_get_a_snapshot_then_only_changes() {
+        let metabolism = MetabolismSimulator::new();
+        let (watched, other) = (region(), region());
+        metabolism.add_region(watched.clone()).await;
+        metabolism.add_region(other.clone()).await;
+
+        let mut everything = RegionWatch::new(None);
+        let mut one = RegionWatch::new(Some(HashSet::from([watched.id.clone()])));
+        assert_eq!(everything.poll(metabolism.snapshot()).len(), 2);
+        assert_eq!(one.poll(metabolism.snapshot()).len(), 1);
+        assert!(everything.poll(metabolism.snapshot()).is_empty());
+
+        metabolism.update_harmony(&other.id, 0.2).await;
+        let deltas = everything.poll(metabolism.snapshot());
+        assert!(matches!(&deltas[..], [RegionDelta::Changed(region)] if region.id == other.id && (region.harmony_level - 0.7).abs() < 1e-9));
+        assert!(one.poll(metabolism.snapshot()).is_empty());
+
+        // A write that changes nothing watched isn't reported
+        metabolism.set_terrain(&watched.id, TerrainType::Forest).await;
+        assert!(one.poll(metabolism.snapshot()).is_empty());
+    }
+}
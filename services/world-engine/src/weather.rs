@@ -0,0 +1,212 @@
+// services/world-engine/src/weather.rs
+//! Region weather. Each region rides a front that builds from clear skies
+//! through cloud and rain into storms and then eases off again; discord
+//! pushes fronts to build faster and harmony lets them clear sooner.
+//! Discord past [`DISSONANCE_STORM_DISCORD`] raises a Dissonance Storm that
+//! only breaks once discord falls below [`DISSONANCE_CLEAR_DISCORD`].
+
+use crate::{MetabolismSimulator, RegionId, RegionState, WeatherState, WeatherType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Matches `game.world_settings.weather_change_probability` in the default config.
+pub const DEFAULT_WEATHER_CHANGE_PROBABILITY: f64 = 0.1;
+
+pub const DISSONANCE_STORM_DISCORD: f64 = 0.6;
+pub const DISSONANCE_CLEAR_DISCORD: f64 = 0.4;
+/// Discordant regions with little harmony left are shrouded in Silence Mist.
+pub const SILENCE_MIST_DISCORD: f64 = 0.35;
+pub const SILENCE_MIST_MAX_HARMONY: f64 = 0.3;
+/// Storms over regions this harmonious become Harmony Storms.
+pub const HARMONY_STORM_HARMONY: f64 = 0.9;
+
+/// Ordinary weather from calmest to wildest. Fronts move one step a tick.
+const LADDER: [WeatherType; 4] = [WeatherType::Clear, WeatherType::Cloudy, WeatherType::Rain, WeatherType::Storm];
+
+/// Wind speed at full intensity, in m/s.
+const MAX_WIND_SPEED: f64 = 40.0;
+/// Largest change of wind direction per tick, in degrees.
+const MAX_WIND_VEER: f64 = 15.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontPhase {
+    Building,
+    Easing,
+}
+
+/// A region whose weather type changed this tick.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeatherShift {
+    pub region_id: RegionId,
+    pub from: WeatherType,
+    pub to: WeatherType,
+    pub intensity: f64,
+}
+
+pub struct WeatherSystem {
+    change_probability: f64,
+    fronts: Mutex<HashMap<RegionId, FrontPhase>>,
+}
+
+impl WeatherSystem {
+    /// `change_probability` is the chance per tick that a front in a
+    /// neutral region moves on.
+    pub fn new(change_probability: f64) -> Self {
+        Self {
+            change_probability: change_probability.clamp(0.0, 1.0),
+            fronts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn front(&self, id: &RegionId) -> FrontPhase {
+        self.fronts.lock().unwrap().get(id).copied().unwrap_or(FrontPhase::Building)
+    }
+
+    pub fn fronts(&self) -> HashMap<RegionId, FrontPhase> {
+        self.fronts.lock().unwrap().clone()
+    }
+
+    pub fn restore_fronts(&self, fronts: HashMap<RegionId, FrontPhase>) {
+        *self.fronts.lock().unwrap() = fronts;
+    }
+
+    /// Advance every region's weather and report the ones that changed type.
+    pub async fn tick(&self, metabolism: &MetabolismSimulator) -> Vec<WeatherShift> {
+        let mut updates = HashMap::new();
+        let mut shifts = Vec::new();
+        {
+            let mut fronts = self.fronts.lock().unwrap();
+            for region in metabolism.regions() {
+                let front = fronts.entry(region.id.clone()).or_insert(FrontPhase::Building);
+                let gust = rand::random::<f64>() * 2.0 - 1.0;
+                let next = next_weather(&region, front, self.change_probability, rand::random(), gust);
+                if next.weather_type != region.weather.weather_type {
+                    shifts.push(WeatherShift {
+                        region_id: region.id.clone(),
+                        from: region.weather.weather_type.clone(),
+                        to: next.weather_type.clone(),
+                        intensity: next.intensity,
+                    });
+                }
+                updates.insert(region.id, next);
+            }
+        }
+        metabolism.apply_weather(updates).await;
+        shifts
+    }
+}
+
+/// One tick of a region's weather. `roll` in `0.0..1.0` decides whether an
+/// ordinary front moves on; `gust` in `-1.0..1.0` veers the wind.
+pub fn next_weather(region: &RegionState, front: &mut FrontPhase, change_probability: f64, roll: f64, gust: f64) -> WeatherState {
+    let current = &region.weather.weather_type;
+    let (harmony, discord) = (region.harmony_level, region.discord_level);
+
+    let (weather_type, intensity) = if discord >= DISSONANCE_STORM_DISCORD
+        || (*current == WeatherType::DissonanceStorm && discord >= DISSONANCE_CLEAR_DISCORD)
+    {
+        *front = FrontPhase::Easing;
+        (WeatherType::DissonanceStorm, discord.min(1.0))
+    } else if *current == WeatherType::DissonanceStorm {
+        // The storm breaks into an ordinary one and blows itself out
+        (WeatherType::Storm, 1.0)
+    } else if discord >= SILENCE_MIST_DISCORD && harmony < SILENCE_MIST_MAX_HARMONY {
+        (WeatherType::SilenceMist, discord)
+    } else {
+        let mut step = ladder_step(current);
+        let pressure = match front {
+            FrontPhase::Building => 1.0 + discord - harmony,
+            FrontPhase::Easing => 1.0 + harmony - discord,
+        };
+        if roll < change_probability * pressure.clamp(0.2, 2.0) {
+            step = match front {
+                FrontPhase::Building => step + 1,
+                FrontPhase::Easing => step.saturating_sub(1),
+            };
+        }
+        if step == LADDER.len() - 1 {
+            *front = FrontPhase::Easing;
+        } else if step == 0 {
+            *front = FrontPhase::Building;
+        }
+
+        let intensity = step as f64 / (LADDER.len() - 1) as f64;
+        if step == LADDER.len() - 1 && harmony >= HARMONY_STORM_HARMONY {
+            (WeatherType::HarmonyStorm, intensity)
+        } else {
+            (LADDER[step].clone(), intensity)
+        }
+    };
+
+    let wind_speed = region.weather.wind_speed + (intensity * MAX_WIND_SPEED - region.weather.wind_speed) * 0.5;
+    WeatherState {
+        weather_type,
+        intensity,
+        wind_direction: (region.weather.wind_direction + gust * MAX_WIND_VEER).rem_euclid(360.0),
+        wind_speed,
+    }
+}
+
+/// Where on the ladder a weather type sits; special weather resumes from
+/// its nearest ordinary kind.
+fn ladder_step(weather: &WeatherType) -> usize {
+    match weather {
+        WeatherType::Clear => 0,
+        WeatherType::Cloudy | WeatherType::Fog | WeatherType::SilenceMist => 1,
+        WeatherType::Rain | WeatherType::Snow => 2,
+        WeatherType::Storm | WeatherType::HarmonyStorm | WeatherType::DissonanceStorm => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TerrainType;
+
+    fn region(weather_type: WeatherType, harmony_level: f64, discord_level: f64) -> RegionState {
+        RegionState {
+            id: RegionId(uuid::Uuid::new_v4()),
+            harmony_level,
+            discord_level,
+            terrain_type: TerrainType::Plains,
+            weather: WeatherState {
+                weather_type,
+                intensity: 0.0,
+                wind_direction: 350.0,
+                wind_speed: 0.0,
+            },
+            political_tension: 0.0,
+        }
+    }
+
+    #[test]
+    fn discord_raises_dissonance_storms_that_break_with_hysteresis() {
+        let mut front = FrontPhase::Building;
+        let storm = next_weather(&region(WeatherType::Rain, 0.2, 0.7), &mut front, 0.0, 0.99, 1.0);
+        assert_eq!(storm.weather_type, WeatherType::DissonanceStorm);
+        assert_eq!(storm.intensity, 0.7);
+        assert_eq!(storm.wind_direction, 5.0);
+
+        // Discord easing below the trigger isn't enough to break it...
+        let held = next_weather(&region(WeatherType::DissonanceStorm, 0.2, 0.5), &mut front, 0.0, 0.99, 0.0);
+        assert_eq!(held.weather_type, WeatherType::DissonanceStorm);
+        // ...until it drops under the clearing threshold
+        let broken = next_weather(&region(WeatherType::DissonanceStorm, 0.5, 0.3), &mut front, 0.0, 0.99, 0.0);
+        assert_eq!(broken.weather_type, WeatherType::Storm);
+        assert_eq!(front, FrontPhase::Easing);
+
+        // Harmony clears an easing front where a neutral region would hold
+        let calm = region(WeatherType::Rain, 0.8, 0.0);
+        assert_eq!(next_weather(&calm, &mut FrontPhase::Easing, 0.3, 0.5, 0.0).weather_type, WeatherType::Cloudy);
+        let neutral = region(WeatherType::Rain, 0.0, 0.0);
+        assert_eq!(next_weather(&neutral, &mut FrontPhase::Easing, 0.3, 0.5, 0.0).weather_type, WeatherType::Rain);
+
+        // A building front in a harmonious region peaks as a Harmony Storm
+        let mut building = FrontPhase::Building;
+        let peak = next_weather(&region(WeatherType::Rain, 0.95, 0.0), &mut building, 1.0, 0.0, 0.0);
+        assert_eq!(peak.weather_type, WeatherType::HarmonyStorm);
+        assert_eq!(building, FrontPhase::Easing);
+    }
+}
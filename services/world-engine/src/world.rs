@@ -2,8 +2,18 @@
 use crate::{
     RegionId, RegionState, WorldEvent, PlayerAction, ActionType, Observer,
     GridCoordinate, Position3D, EchoType, CelestialEventType, EcosystemSimulator,
-    MetabolismSimulator,
+    MetabolismSimulator, DiffusionRates, WeatherSystem,
 };
+use crate::crafting::InventoryService;
+use crate::ecs::{EntityChange, EntityId, EntitySnapshot};
+use crate::entities::{DynamicObjects, Interaction};
+use crate::scheduler::{EventScheduler, ScheduledEvent};
+use crate::scenario::{ImportSummary, WorldScenario, SCENARIO_FORMAT_VERSION};
+use crate::snapshot::{self, DiffFilter, SnapshotDiff, SnapshotSummary, WorldSnapshot, MAX_SNAPSHOTS};
+use crate::terraform::{self, TerraformStatus, Terraforming};
+use crate::weather::DEFAULT_WEATHER_CHANGE_PROBABILITY;
+use finalverse_core::{FinalverseError, RegionEffects, RegionMap, TerraformKind};
+use finalverse_core::world_clock::{ClockSync, WorldClockState, WorldInstant};
 use finalverse_ecosystem::{EcosystemEvent, EcosystemObserver};
 
 struct EcosystemAdapter {
@@ -21,15 +31,26 @@ impl EcosystemObserver for EcosystemAdapter {
                     to: to.clone(),
                 }
             }
+            EcosystemEvent::ConservationStatusChanged { species, from, to, population } => {
+                WorldEvent::SpeciesStatusChanged {
+                    species: species.clone(),
+                    from: *from,
+                    to: *to,
+                    population: *population,
+                }
+            }
         };
         self.observer.notify(&world_event).await;
     }
 }
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use serde::{Serialize, Deserialize};
 
+/// Batches of entity changes held for slow stream subscribers.
+const ENTITY_CHANGE_BUFFER: usize = 64;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldState {
     pub regions: HashMap<RegionId, RegionState>,
@@ -64,6 +85,15 @@ impl Default for WorldTime {
     }
 }
 
+impl From<WorldInstant> for WorldTime {
+    fn from(instant: WorldInstant) -> Self {
+        Self {
+            day: instant.day,
+            hour: instant.hour,
+        }
+    }
+}
+
 impl WorldTime {
     pub fn advance(&mut self, delta_hours: f32) {
         self.hour += delta_hours;
@@ -82,9 +112,19 @@ pub enum WorldUpdate {
 pub struct WorldEngine {
     state: Arc<RwLock<WorldState>>,
     metabolism: Arc<MetabolismSimulator>,
+    weather: Arc<WeatherSystem>,
     ecosystem: Arc<EcosystemSimulator>,
     observers: Arc<RwLock<Vec<Arc<dyn Observer>>>>,
     update_queue: Arc<RwLock<Vec<WorldUpdate>>>,
+    snapshots: Arc<RwLock<HashMap<String, WorldSnapshot>>>,
+    yield_bonuses: Arc<RwLock<HashMap<RegionId, f64>>>,
+    terraforming: Arc<RwLock<HashMap<RegionId, Terraforming>>>,
+    clock: Arc<RwLock<WorldClockState>>,
+    region_map: Arc<RegionMap>,
+    scheduler: Arc<RwLock<EventScheduler>>,
+    inventory: Arc<InventoryService>,
+    objects: Arc<RwLock<DynamicObjects>>,
+    entity_changes: broadcast::Sender<Vec<EntityChange>>,
 }
 
 impl WorldEngine {
@@ -92,14 +132,102 @@ impl WorldEngine {
         Self {
             state: Arc::new(RwLock::new(WorldState::new())),
             metabolism: Arc::new(MetabolismSimulator::new()),
+            weather: Arc::new(WeatherSystem::new(DEFAULT_WEATHER_CHANGE_PROBABILITY)),
             ecosystem: Arc::new(EcosystemSimulator::new()),
             observers: Arc::new(RwLock::new(Vec::new())),
             update_queue: Arc::new(RwLock::new(Vec::new())),
+            snapshots: Arc::new(RwLock::new(HashMap::new())),
+            yield_bonuses: Arc::new(RwLock::new(HashMap::new())),
+            terraforming: Arc::new(RwLock::new(HashMap::new())),
+            clock: Arc::new(RwLock::new(WorldClockState::default())),
+            region_map: Arc::new(RegionMap::default()),
+            scheduler: Arc::new(RwLock::new(EventScheduler::new())),
+            inventory: Arc::new(InventoryService::default()),
+            objects: Arc::new(RwLock::new(DynamicObjects::new())),
+            entity_changes: broadcast::channel(ENTITY_CHANGE_BUFFER).0,
+        }
+    }
+
+    /// Run the world clock so a full day/night cycle takes `minutes`.
+    pub fn with_day_length(self, minutes: u32) -> Self {
+        Self {
+            clock: Arc::new(RwLock::new(WorldClockState::for_day_length(minutes))),
+            ..self
+        }
+    }
+
+    /// Spread dissonance and political tension across region borders at
+    /// these rates.
+    pub fn with_region_diffusion(self, rates: DiffusionRates) -> Self {
+        Self {
+            metabolism: Arc::new(MetabolismSimulator::new().with_diffusion(rates)),
+            ..self
+        }
+    }
+
+    /// Where each region lies, for services placing coordinates in regions.
+    pub fn with_region_map(self, region_map: RegionMap) -> Self {
+        Self {
+            region_map: Arc::new(region_map),
+            ..self
+        }
+    }
+
+    /// Keep player inventories with this service, for its catalog, store
+    /// and event bus.
+    pub fn with_inventory(self, inventory: InventoryService) -> Self {
+        Self {
+            inventory: Arc::new(inventory),
+            ..self
+        }
+    }
+
+    pub fn inventory(&self) -> &InventoryService {
+        &self.inventory
+    }
+
+    pub fn region_map(&self) -> &RegionMap {
+        &self.region_map
+    }
+
+    /// Chance per tick that a region's weather front moves on.
+    pub fn with_weather_change_probability(self, probability: f64) -> Self {
+        Self {
+            weather: Arc::new(WeatherSystem::new(probability)),
+            ..self
         }
     }
 
     pub async fn get_state(&self) -> WorldState {
-        self.state.read().await.clone()
+        let mut state = self.state.read().await.clone();
+        state.time = self.world_time().await.into();
+        state
+    }
+
+    /// Authoritative world time; other services sync against this.
+    pub async fn world_time(&self) -> WorldInstant {
+        self.clock.read().await.at(chrono::Utc::now())
+    }
+
+    pub async fn clock_sync(&self) -> ClockSync {
+        let clock = self.clock.read().await;
+        ClockSync::capture(&clock)
+    }
+
+    pub async fn set_time_rate(&self, rate: f64) -> Result<ClockSync, String> {
+        let mut clock = self.clock.write().await;
+        clock.set_rate(rate, chrono::Utc::now())?;
+        Ok(ClockSync::capture(&clock))
+    }
+
+    pub async fn set_time_paused(&self, paused: bool) -> ClockSync {
+        let mut clock = self.clock.write().await;
+        if paused {
+            clock.pause(chrono::Utc::now());
+        } else {
+            clock.resume(chrono::Utc::now());
+        }
+        ClockSync::capture(&clock)
     }
 
     pub async fn register_observer(&self, observer: Arc<dyn Observer>) {
@@ -113,19 +241,25 @@ impl WorldEngine {
             ActionType::Move(coords) => {
                 println!("Player {} moved to {:?}", action.player_id.0, coords);
             }
-            ActionType::Interact(target) => {
-                println!("Player {} interacted with {}", action.player_id.0, target);
-            }
+            ActionType::Interact(target) => match target.parse().map(EntityId) {
+                Ok(entity) => match self.interact_with_entity(&action.player_id.to_string(), entity).await {
+                    Ok(interaction) => println!("Player {} interacted with {}: {:?}", action.player_id.0, entity, interaction),
+                    Err(e) => println!("Player {} couldn't interact with {}: {}", action.player_id.0, entity, e),
+                },
+                Err(_) => println!("Player {} interacted with {}", action.player_id.0, target),
+            },
             ActionType::UseAbility(ability) => {
                 println!("Player {} used ability {}", action.player_id.0, ability);
             }
-            ActionType::Craft(item) => {
-                println!("Player {} crafted {}", action.player_id.0, item);
+            ActionType::Craft(recipe_id) => {
+                if let Err(e) = self.inventory.craft(&action.player_id.0.to_string(), &recipe_id).await {
+                    println!("Player {} couldn't craft {}: {}", action.player_id.0, recipe_id, e);
+                }
             }
         }
     }
 
-    pub async fn update(&self, delta_time: f32) {
+    pub async fn update(&self, _delta_time: f32) {
         // Process queued updates
         let updates: Vec<WorldUpdate> = {
             let mut queue = self.update_queue.write().await;
@@ -136,9 +270,9 @@ impl WorldEngine {
             self.apply_update(update).await;
         }
 
-        // Update world time
-        let mut state = self.state.write().await;
-        state.time.advance(delta_time);
+        // World time follows the shared clock rather than tick deltas
+        let now = self.world_time().await;
+        self.state.write().await.time = now.into();
     }
 
     async fn apply_update(&self, update: WorldUpdate) {
@@ -159,7 +293,37 @@ impl WorldEngine {
     pub async fn simulate_tick(&self) {
         // Run all simulations
         self.metabolism.simulate_tick().await;
+        let weather_shifts = self.weather.tick(&self.metabolism).await;
         self.ecosystem.simulate_tick().await;
+        self.advance_terraforming().await;
+        let entity_changes = self.objects.write().await.tick(chrono::Utc::now());
+        self.stream_entity_changes(entity_changes);
+
+        if !weather_shifts.is_empty() {
+            let observers = self.observers.read().await;
+            for shift in weather_shifts {
+                let event = WorldEvent::WeatherChanged {
+                    region_id: shift.region_id,
+                    from: shift.from,
+                    to: shift.to,
+                    intensity: shift.intensity,
+                };
+                for observer in observers.iter() {
+                    observer.notify(&event).await;
+                }
+            }
+        }
+
+        let now = self.world_time().await;
+        let scripted = self.scheduler.write().await.due(now);
+        if !scripted.is_empty() {
+            let observers = self.observers.read().await;
+            for event in scripted {
+                for observer in observers.iter() {
+                    observer.notify(&event).await;
+                }
+            }
+        }
 
         // Check for celestial events
         if rand::random::<f64>() < 0.01 {
@@ -180,14 +344,250 @@ impl WorldEngine {
         }
     }
 
+    /// Dynamic objects, or only those in `region_id`.
+    pub async fn entities(&self, region_id: Option<&RegionId>) -> Vec<EntitySnapshot> {
+        self.objects.read().await.snapshots(region_id)
+    }
+
+    pub async fn entity(&self, id: EntityId) -> Option<EntitySnapshot> {
+        self.objects.read().await.snapshot(id)
+    }
+
+    pub async fn spawn_entity(
+        &self,
+        components: std::collections::BTreeMap<String, serde_json::Value>,
+    ) -> Result<EntitySnapshot, FinalverseError> {
+        let mut objects = self.objects.write().await;
+        let entity = objects.spawn(components)?;
+        self.stream_entity_changes(objects.take_changes());
+        Ok(entity)
+    }
+
+    pub async fn despawn_entity(&self, id: EntityId) -> bool {
+        let mut objects = self.objects.write().await;
+        let despawned = objects.despawn(id);
+        self.stream_entity_changes(objects.take_changes());
+        despawned
+    }
+
+    /// Interact with an entity for a player; what they harvest goes into
+    /// their inventory.
+    pub async fn interact_with_entity(&self, player_id: &str, id: EntityId) -> Result<Interaction, FinalverseError> {
+        let interaction = {
+            let mut objects = self.objects.write().await;
+            let interaction = objects.interact(id)?;
+            self.stream_entity_changes(objects.take_changes());
+            interaction
+        };
+        if let Interaction::Harvested { item_id, .. } = &interaction {
+            self.inventory.grant(player_id, item_id, 1).await?;
+        }
+        Ok(interaction)
+    }
+
+    /// Entity changes as they happen, in batches.
+    pub fn subscribe_entities(&self) -> broadcast::Receiver<Vec<EntityChange>> {
+        self.entity_changes.subscribe()
+    }
+
+    fn stream_entity_changes(&self, changes: Vec<EntityChange>) {
+        if !changes.is_empty() {
+            // Nobody listening is fine
+            let _ = self.entity_changes.send(changes);
+        }
+    }
+
+    /// Capture the current regions and species populations.
+    pub async fn capture_snapshot(&self, label: Option<String>) -> WorldSnapshot {
+        WorldSnapshot {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            taken_at: chrono::Utc::now(),
+            regions: self.metabolism.regions(),
+            species: self.ecosystem.species().await,
+        }
+    }
+
+    /// Capture and store a snapshot so it can be diffed later. Past
+    /// [`MAX_SNAPSHOTS`] the oldest stored snapshot is dropped.
+    pub async fn take_snapshot(&self, label: Option<String>) -> SnapshotSummary {
+        let snapshot = self.capture_snapshot(label).await;
+        let summary = snapshot.summary();
+        let mut snapshots = self.snapshots.write().await;
+        snapshots.insert(snapshot.id.clone(), snapshot);
+        while snapshots.len() > MAX_SNAPSHOTS {
+            let Some(oldest) = snapshots.values().min_by_key(|s| s.taken_at).map(|s| s.id.clone()) else {
+                break;
+            };
+            snapshots.remove(&oldest);
+        }
+        summary
+    }
+
+    pub async fn list_snapshots(&self) -> Vec<SnapshotSummary> {
+        let mut summaries: Vec<SnapshotSummary> = self
+            .snapshots
+            .read()
+            .await
+            .values()
+            .map(WorldSnapshot::summary)
+            .collect();
+        summaries.sort_by_key(|s| s.taken_at);
+        summaries
+    }
+
+    /// Resolve a stored snapshot by id, or the live world for `current`.
+    async fn resolve_snapshot(&self, id: &str) -> Option<WorldSnapshot> {
+        if id == "current" {
+            let mut live = self.capture_snapshot(Some("current".to_string())).await;
+            live.id = "current".to_string();
+            return Some(live);
+        }
+        self.snapshots.read().await.get(id).cloned()
+    }
+
+    pub async fn diff_snapshots(&self, from: &str, to: &str, filter: &DiffFilter) -> Option<SnapshotDiff> {
+        let from = self.resolve_snapshot(from).await?;
+        let to = self.resolve_snapshot(to).await?;
+        Some(snapshot::diff_snapshots(&from, &to, filter))
+    }
+
+    /// Add a scripted event to the timeline.
+    pub async fn schedule_event(&self, event: ScheduledEvent) -> Result<(), FinalverseError> {
+        self.scheduler.write().await.add(event)
+    }
+
+    pub async fn unschedule_event(&self, id: &str) -> Option<ScheduledEvent> {
+        self.scheduler.write().await.remove(id)
+    }
+
+    /// Scripted events still to fire, in timeline order.
+    pub async fn scheduled_events(&self) -> Vec<ScheduledEvent> {
+        self.scheduler.read().await.events()
+    }
+
+    /// Everything needed to recreate this world elsewhere.
+    pub async fn export_scenario(&self, label: Option<String>) -> WorldScenario {
+        WorldScenario {
+            format_version: SCENARIO_FORMAT_VERSION,
+            label,
+            exported_at: chrono::Utc::now(),
+            clock: self.clock.read().await.clone(),
+            regions: self.metabolism.regions(),
+            borders: self.metabolism.graph(),
+            weather_fronts: self.weather.fronts(),
+            species: self.ecosystem.species().await,
+            active_events: self.state.read().await.active_events.clone(),
+            yield_bonuses: self.yield_bonuses.read().await.clone(),
+            terraforming: self.terraforming.read().await.values().cloned().collect(),
+        }
+    }
+
+    /// Replace the whole world with `scenario`. The clock resumes from the
+    /// exported world time rather than jumping by the time since export.
+    pub async fn import_scenario(&self, scenario: WorldScenario) -> Result<ImportSummary, FinalverseError> {
+        scenario.validate()?;
+        let summary = scenario.summary();
+
+        {
+            let mut current = self.clock.write().await;
+            let mut clock = scenario.clock;
+            clock.anchor_world_seconds = clock.world_seconds_at(scenario.exported_at);
+            clock.anchor_wall = chrono::Utc::now();
+            // Clients watching the revision resync to the imported clock
+            clock.revision = current.revision + 1;
+            *current = clock;
+        }
+
+        self.metabolism.restore(scenario.regions, scenario.borders).await;
+        self.weather.restore_fronts(scenario.weather_fronts);
+        self.ecosystem.restore_species(scenario.species).await;
+        self.state.write().await.active_events = scenario.active_events;
+        *self.yield_bonuses.write().await = scenario.yield_bonuses;
+        *self.terraforming.write().await = scenario
+            .terraforming
+            .into_iter()
+            .map(|job| (job.region_id.clone(), job))
+            .collect();
+        Ok(summary)
+    }
+
     pub fn metabolism(&self) -> Arc<MetabolismSimulator> {
         self.metabolism.clone()
     }
 
+    pub fn weather(&self) -> Arc<WeatherSystem> {
+        self.weather.clone()
+    }
+
     pub fn ecosystem(&self) -> Arc<EcosystemSimulator> {
         self.ecosystem.clone()
     }
 
+    /// Resource yield multiplier bonus for a region, e.g. from stewardship.
+    pub async fn yield_bonus(&self, region_id: &RegionId) -> f64 {
+        self.yield_bonuses.read().await.get(region_id).copied().unwrap_or(0.0)
+    }
+
+    pub async fn set_yield_bonus(&self, region_id: RegionId, bonus: f64) {
+        let mut bonuses = self.yield_bonuses.write().await;
+        if bonus == 0.0 {
+            bonuses.remove(&region_id);
+        } else {
+            bonuses.insert(region_id, bonus);
+        }
+    }
+
+    /// Begin a community-approved transformation of a region.
+    pub async fn start_terraforming(
+        &self,
+        region_id: RegionId,
+        proposal_id: uuid::Uuid,
+        kind: TerraformKind,
+        stage_hours: i64,
+    ) -> Result<TerraformStatus, String> {
+        let region = self
+            .metabolism
+            .get_region(&region_id)
+            .ok_or_else(|| "Region not found".to_string())?;
+        let mut terraforming = self.terraforming.write().await;
+        if terraforming.get(&region_id).is_some_and(|current| !current.is_complete()) {
+            return Err("Region is already being terraformed".to_string());
+        }
+        let job = Terraforming::new(proposal_id, region_id.clone(), kind, region.terrain_type, stage_hours, chrono::Utc::now());
+        let status = job.status();
+        terraforming.insert(region_id, job);
+        drop(terraforming);
+
+        self.metabolism.update_harmony(&status.region_id, terraform::STAGE_HARMONY).await;
+        tracing::info!("🌱 Terraforming region {} ({:?}): {}", status.region_id.0, kind, status.stage);
+        Ok(status)
+    }
+
+    pub async fn terraform_status(&self, region_id: &RegionId) -> Option<TerraformStatus> {
+        self.terraforming.read().await.get(region_id).map(Terraforming::status)
+    }
+
+    /// Move every transformation on to its due stage, applying the target
+    /// terrain when one finishes.
+    async fn advance_terraforming(&self) {
+        let now = chrono::Utc::now();
+        let mut reached = Vec::new();
+        for job in self.terraforming.write().await.values_mut() {
+            for stage in job.advance(now) {
+                reached.push((job.region_id.clone(), job.kind, stage, job.is_complete()));
+            }
+        }
+
+        for (region_id, kind, stage, complete) in reached {
+            tracing::info!("🌱 Terraforming region {} ({:?}): {}", region_id.0, kind, stage);
+            self.metabolism.update_harmony(&region_id, terraform::STAGE_HARMONY).await;
+            if complete {
+                self.metabolism.set_terrain(&region_id, kind.target_terrain()).await;
+            }
+        }
+    }
+
     pub async fn update_region_harmony(
         &self,
         region_id: &RegionId,
@@ -204,6 +604,35 @@ impl WorldEngine {
             triggered_events: Vec::new(),
         })
     }
+
+    /// Apply what another service did to a region and return the region
+    /// as it now stands. Harmony the effects restored is announced to
+    /// observers.
+    pub async fn apply_region_effects(
+        &self,
+        region_id: &RegionId,
+        effects: &RegionEffects,
+    ) -> Result<RegionState, FinalverseError> {
+        let before = self.metabolism.get_region(region_id).map(|region| region.harmony_level);
+        let region = self
+            .metabolism
+            .apply_effects(region_id, &effects.effects)
+            .await
+            .ok_or_else(|| FinalverseError::NotFound(format!("Region {}", region_id.0)))?;
+        tracing::debug!("{} applied {} effects to region {}", effects.source, effects.effects.len(), region_id.0);
+
+        let restored = region.harmony_level - before.unwrap_or(region.harmony_level);
+        if restored > 0.0 {
+            let event = WorldEvent::HarmonyRestored {
+                region_id: region_id.clone(),
+                amount: restored,
+            };
+            for observer in self.observers.read().await.iter() {
+                observer.notify(&event).await;
+            }
+        }
+        Ok(region)
+    }
 }
 
 #[derive(Debug, Clone)]
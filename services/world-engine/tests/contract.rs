@@ -0,0 +1,59 @@
+// services/world-engine/tests/contract.rs
+// Replays the client SDK's golden fixtures against the HTTP routes
+
+use chrono::Duration;
+use finalverse_auth::{JwtAuth, PlayerAuth};
+use finalverse_core::{PlayerId, TenantId};
+use finalverse_health::OperatorGuard;
+use finalverse_sdk::contract;
+use std::sync::Arc;
+use uuid::Uuid;
+use world_engine::{RegionId, RegionState, TerrainType, WeatherState, WeatherType, WorldEngine};
+
+#[tokio::test]
+async fn sdk_fixtures_still_hold() {
+    let engine = Arc::new(WorldEngine::new());
+    engine
+        .metabolism()
+        .add_region(RegionState {
+            id: RegionId(Uuid::new_v4()),
+            harmony_level: 0.8,
+            discord_level: 0.2,
+            terrain_type: TerrainType::Forest,
+            weather: WeatherState {
+                weather_type: WeatherType::Clear,
+                intensity: 0.5,
+                wind_direction: 45.0,
+                wind_speed: 10.0,
+            },
+            political_tension: 0.0,
+        })
+        .await;
+    let tokens = JwtAuth::new("a-contract-test-secret-of-32-chars!", Duration::hours(1));
+    let routes = world_engine::server::create_routes(
+        engine,
+        OperatorGuard::default(),
+        PlayerAuth::new(tokens.clone(), TenantId::default()),
+    );
+
+    let fixtures = contract::skew_matrix("world-engine").unwrap();
+    assert!(!fixtures.is_empty());
+    let mut failures = Vec::new();
+    for fixture in fixtures {
+        let mut request = warp::test::request().method(&fixture.method).path(&fixture.path);
+        if let Some(body) = &fixture.request {
+            request = request.json(body);
+        }
+        if fixture.authenticated {
+            let (token, _) = tokens.issue(PlayerId(Uuid::new_v4()), Uuid::new_v4(), &TenantId::default());
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+
+        let reply = request.reply(&routes).await;
+        let body = serde_json::from_slice(reply.body()).unwrap_or_default();
+        if let Err(failure) = fixture.check(reply.status().as_u16(), &body) {
+            failures.push(failure.to_string());
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
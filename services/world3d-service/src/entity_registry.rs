@@ -0,0 +1,149 @@
+// services/world3d-service/src/entity_registry.rs
+// Entities other services place in the world, such as echoes, indexed by
+// the grid they stand in
+
+use dashmap::DashMap;
+use finalverse_world3d::collision::Collider;
+use finalverse_world3d::{GridCoordinate, Position3D};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Property a spawner sets to keep its own id for the entity, so spawning
+/// again after a restart updates the entity instead of duplicating it.
+pub const ENTITY_ID_PROPERTY: &str = "entity_id";
+/// Furthest a spawn is moved to find it room clear of other colliders.
+pub const MAX_PLACEMENT_SHIFT: f32 = 16.0;
+/// Echoes and NPCs stand about as big as a player.
+const CHARACTER_RADIUS: f32 = 0.5;
+const CHARACTER_HEIGHT: f32 = 1.8;
+
+/// The collider an entity standing on `base` should have. `collider_height`
+/// with `collider_radius` makes a capsule, or with `collider_width` and
+/// `collider_depth` a box; echoes and NPCs get a character-sized capsule
+/// unless told otherwise, and `collider` set to `none` opts out.
+pub fn collider_for(entity_type: &str, properties: &HashMap<String, String>, base: Position3D) -> Option<Collider> {
+    let number = |key: &str| properties.get(key).and_then(|value| value.parse::<f32>().ok()).filter(|v| *v > 0.0);
+    if properties.get("collider").is_some_and(|kind| kind == "none") {
+        return None;
+    }
+    let character = matches!(entity_type, "echo" | "npc");
+    let height = number("collider_height").or(character.then_some(CHARACTER_HEIGHT))?;
+    if let (Some(width), Some(depth)) = (number("collider_width"), number("collider_depth")) {
+        return Some(Collider::cuboid(base, width, depth, height));
+    }
+    let radius = number("collider_radius").or(character.then_some(CHARACTER_RADIUS))?;
+    Some(Collider::capsule(base, radius, height))
+}
+
+#[derive(Debug, Clone)]
+pub struct PlacedEntity {
+    pub id: Uuid,
+    pub entity_type: String,
+    pub position: Position3D,
+    pub grid: GridCoordinate,
+    pub properties: HashMap<String, String>,
+    pub state: String,
+}
+
+#[derive(Default)]
+pub struct EntityRegistry {
+    entities: DashMap<Uuid, PlacedEntity>,
+    by_grid: DashMap<GridCoordinate, HashSet<Uuid>>,
+}
+
+impl EntityRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place an entity, or replace the one with the id in its
+    /// [`ENTITY_ID_PROPERTY`]. Returns its id.
+    pub fn spawn(&self, entity_type: String, position: Position3D, properties: HashMap<String, String>) -> Uuid {
+        let id = properties
+            .get(ENTITY_ID_PROPERTY)
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .unwrap_or_else(Uuid::new_v4);
+        let grid = position.to_grid_coordinate();
+        let entity = PlacedEntity {
+            id,
+            entity_type,
+            position,
+            grid,
+            properties,
+            state: String::new(),
+        };
+        if let Some(previous) = self.entities.insert(id, entity) {
+            self.unindex(previous.grid, id);
+        }
+        self.by_grid.entry(grid).or_default().insert(id);
+        id
+    }
+
+    /// Move an entity, returning the grid it is now in; None if it isn't
+    /// placed.
+    pub fn move_to(&self, id: Uuid, position: Position3D) -> Option<GridCoordinate> {
+        let (from, to) = {
+            let mut entity = self.entities.get_mut(&id)?;
+            let from = entity.grid;
+            entity.position = position;
+            entity.grid = position.to_grid_coordinate();
+            (from, entity.grid)
+        };
+        if from != to {
+            self.unindex(from, id);
+            self.by_grid.entry(to).or_default().insert(id);
+        }
+        Some(to)
+    }
+
+    pub fn set_state(&self, id: Uuid, state: String) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(mut entity) => {
+                entity.state = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn in_grid(&self, grid: GridCoordinate) -> Vec<PlacedEntity> {
+        let Some(ids) = self.by_grid.get(&grid).map(|ids| ids.clone()) else {
+            return Vec::new();
+        };
+        ids.iter()
+            .filter_map(|id| self.entities.get(id).map(|entity| entity.clone()))
+            .collect()
+    }
+
+    fn unindex(&self, grid: GridCoordinate, id: Uuid) {
+        if let Some(mut ids) = self.by_grid.get_mut(&grid) {
+            ids.remove(&id);
+        }
+        self.by_grid.remove_if(&grid, |_, ids| ids.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_across_a_boundary_changes_grid() {
+        let registry = EntityRegistry::new();
+        let id = Uuid::new_v4();
+        let properties = HashMap::from([(ENTITY_ID_PROPERTY.to_string(), id.to_string())]);
+
+        assert_eq!(registry.spawn("echo".into(), Position3D::new(10.0, 10.0, 0.0), properties.clone()), id);
+        assert_eq!(registry.in_grid(GridCoordinate::new(0, 0)).len(), 1);
+
+        assert_eq!(registry.move_to(id, Position3D::new(300.0, 10.0, 0.0)), Some(GridCoordinate::new(1, 0)));
+        assert!(registry.in_grid(GridCoordinate::new(0, 0)).is_empty());
+        assert_eq!(registry.in_grid(GridCoordinate::new(1, 0))[0].id, id);
+
+        // Spawning again with the same id replaces the entity
+        registry.spawn("echo".into(), Position3D::new(10.0, 10.0, 0.0), properties);
+        assert!(registry.in_grid(GridCoordinate::new(1, 0)).is_empty());
+        assert_eq!(registry.in_grid(GridCoordinate::new(0, 0)).len(), 1);
+        assert_eq!(registry.move_to(Uuid::new_v4(), Position3D::new(0.0, 0.0, 0.0)), None);
+    }
+}
@@ -2,35 +2,59 @@
 mod spatial_streaming;
 mod world_manager;
 mod terrain_service;
+mod movement;
+mod entity_registry;
 
 use finalverse_world3d::{
-    Position3D, GridCoordinate, PlayerId,
+    EntityId, Position3D, GridCoordinate, PlayerId,
+    collision::CollisionWorld,
     world::World,
     region::Region,
+    terrain::Biome,
 };
+use finalverse_proto::world3d as proto;
+use finalverse_proto::world3d::world3_d_service_server::World3DServiceServer;
 use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tonic::{transport::Server, Request, Response, Status};
-use tracing::{info, error};
+use tracing::{info, warn};
 use finalverse_logging as logging;
 
 pub struct World3DService {
     world_manager: Arc<world_manager::WorldManager>,
     spatial_streamer: Arc<spatial_streaming::SpatialStreamManager>,
     terrain_service: Arc<terrain_service::TerrainService>,
+    movement: Arc<movement::MovementValidator>,
+    collision: Arc<CollisionWorld>,
+    entities: Arc<entity_registry::EntityRegistry>,
 }
 
 impl World3DService {
     pub async fn new() -> anyhow::Result<Self> {
-        let world_manager = Arc::new(world_manager::WorldManager::new().await?);
-        let spatial_streamer = Arc::new(spatial_streaming::SpatialStreamManager::new());
         let terrain_service = Arc::new(terrain_service::TerrainService::new());
+        let terrain = terrain_service.clone();
+        let mut streamer = spatial_streaming::SpatialStreamManager::new(Arc::new(move |grid: GridCoordinate| {
+            Arc::new(terrain.generate(grid, 0))
+        }));
+        if let Some(mb) = std::env::var("WORLD3D_GRID_MEMORY_MB").ok().and_then(|mb| mb.parse::<usize>().ok()) {
+            streamer = streamer.with_memory_cap(mb * 1024 * 1024);
+        }
+        if let Some(concurrency) = std::env::var("WORLD3D_PREFETCH_CONCURRENCY").ok().and_then(|c| c.parse().ok()) {
+            streamer = streamer.with_prefetch_concurrency(concurrency);
+        }
+        let spatial_streamer = Arc::new(streamer);
+        let world_manager = Arc::new(world_manager::WorldManager::new(spatial_streamer.clone()).await?);
+        let collision = Arc::new(CollisionWorld::new(terrain_service.clone()));
+        let movement = Arc::new(movement::MovementValidator::new(terrain_service.clone(), collision.clone()));
 
         Ok(Self {
             world_manager,
             spatial_streamer,
             terrain_service,
+            movement,
+            collision,
+            entities: Arc::new(entity_registry::EntityRegistry::new()),
         })
     }
 
@@ -42,12 +66,13 @@ impl World3DService {
 
         // Initialize first hour grids
         let first_hour_grids = vec![
-            GridCoordinate::new(100, 100), // Memory Grotto
-            GridCoordinate::new(101, 101), // Weaver's Landing
-            GridCoordinate::new(102, 101), // Whisperwood Grove
+            (GridCoordinate::new(100, 100), Biome::MemoryGrotto),
+            (GridCoordinate::new(101, 101), Biome::WeaversLanding),
+            (GridCoordinate::new(102, 101), Biome::WhisperwoodGrove),
         ];
 
-        for grid_coord in first_hour_grids {
+        for (grid_coord, biome) in first_hour_grids {
+            self.terrain_service.set_grid(grid_coord, biome, 0.5);
             self.world_manager.ensure_grid_loaded(grid_coord).await?;
         }
 
@@ -56,6 +81,197 @@ impl World3DService {
     }
 }
 
+#[tonic::async_trait]
+impl proto::world3_d_service_server::World3DService for World3DService {
+    async fn generate_grid(
+        &self,
+        _request: Request<proto::GenerateGridRequest>,
+    ) -> Result<Response<proto::GenerateGridResponse>, Status> {
+        Err(Status::unimplemented("GenerateGrid is not available yet"))
+    }
+
+    async fn spawn_entity(
+        &self,
+        request: Request<proto::SpawnEntityRequest>,
+    ) -> Result<Response<proto::SpawnEntityResponse>, Status> {
+        let req = request.into_inner();
+        let position = req
+            .position
+            .ok_or_else(|| Status::invalid_argument("Entity has no position"))?;
+        let requested = Position3D::new(position.x, position.y, position.z);
+
+        // A respawn shouldn't be pushed aside by its own old collider
+        if let Some(previous) = req
+            .properties
+            .get(entity_registry::ENTITY_ID_PROPERTY)
+            .and_then(|id| uuid::Uuid::parse_str(id).ok())
+        {
+            self.collision.remove(EntityId(previous));
+        }
+
+        // Entities with colliders are stood on the ground clear of others
+        let collider = entity_registry::collider_for(&req.entity_type, &req.properties, requested);
+        let position = match collider {
+            Some(collider) => self
+                .collision
+                .find_clear_spot(collider.shape(), requested, entity_registry::MAX_PLACEMENT_SHIFT)
+                .unwrap_or_else(|| {
+                    warn!("No clear spot for {} near {:?}; placing it as requested", req.entity_type, requested);
+                    requested
+                }),
+            None => requested,
+        };
+
+        let id = self.entities.spawn(req.entity_type, position, req.properties);
+        if let Some(collider) = collider {
+            self.collision.insert(EntityId(id), collider.with_base(position));
+        }
+        Ok(Response::new(proto::SpawnEntityResponse { entity_id: id.to_string() }))
+    }
+
+    async fn update_entity_state(
+        &self,
+        request: Request<proto::UpdateEntityStateRequest>,
+    ) -> Result<Response<proto::UpdateEntityStateResponse>, Status> {
+        let req = request.into_inner();
+        let id = uuid::Uuid::parse_str(&req.entity_id).map_err(|_| Status::invalid_argument("Invalid entity id"))?;
+        let success = self.entities.set_state(id, req.new_state);
+        Ok(Response::new(proto::UpdateEntityStateResponse { success }))
+    }
+
+    async fn move_entity(
+        &self,
+        request: Request<proto::MoveEntityRequest>,
+    ) -> Result<Response<proto::MoveEntityResponse>, Status> {
+        let req = request.into_inner();
+        let id = uuid::Uuid::parse_str(&req.entity_id).map_err(|_| Status::invalid_argument("Invalid entity id"))?;
+        let position = req
+            .position
+            .ok_or_else(|| Status::invalid_argument("Move has no position"))?;
+        let position = Position3D::new(position.x, position.y, position.z);
+        let grid = self.entities.move_to(id, position);
+        if grid.is_some() {
+            self.collision.move_to(EntityId(id), position);
+        }
+        Ok(Response::new(proto::MoveEntityResponse {
+            success: grid.is_some(),
+            grid_x: grid.map_or(0, |grid| grid.x),
+            grid_y: grid.map_or(0, |grid| grid.y),
+        }))
+    }
+
+    /// Entities in a grid. Terrain comes from GetTerrainChunk instead.
+    async fn get_grid_data(
+        &self,
+        request: Request<proto::GetGridDataRequest>,
+    ) -> Result<Response<proto::GetGridDataResponse>, Status> {
+        let req = request.into_inner();
+        let entities = self
+            .entities
+            .in_grid(GridCoordinate::new(req.x, req.y))
+            .into_iter()
+            .map(|entity| {
+                let mut properties = entity.properties;
+                if !entity.state.is_empty() {
+                    properties.insert("state".to_string(), entity.state);
+                }
+                proto::EntityData {
+                    entity_id: entity.id.to_string(),
+                    entity_type: entity.entity_type,
+                    position: Some(proto::Position3D {
+                        x: entity.position.x,
+                        y: entity.position.y,
+                        z: entity.position.z,
+                    }),
+                    properties,
+                }
+            })
+            .collect();
+        Ok(Response::new(proto::GetGridDataResponse {
+            grid_data: Some(proto::GridData {
+                x: req.x,
+                y: req.y,
+                terrain_data: Vec::new(),
+                entities,
+            }),
+        }))
+    }
+
+    async fn get_terrain_chunk(
+        &self,
+        request: Request<proto::GetTerrainChunkRequest>,
+    ) -> Result<Response<proto::TerrainChunk>, Status> {
+        let req = request.into_inner();
+        let grid = GridCoordinate::new(req.x, req.y);
+        let lod = u8::try_from(req.lod)
+            .ok()
+            .filter(|lod| *lod <= terrain_service::MAX_LOD)
+            .ok_or_else(|| Status::invalid_argument(format!("LOD must be at most {}", terrain_service::MAX_LOD)))?;
+
+        // Full-resolution chunks are the streamed grids, likely already
+        // prefetched; coarser ones come from the terrain cache
+        let chunk = if lod == 0 {
+            self.world_manager
+                .ensure_grid_loaded(grid)
+                .await
+                .map_err(|e| Status::internal(format!("Terrain generation failed: {}", e)))?
+        } else {
+            let terrain = self.terrain_service.clone();
+            tokio::task::spawn_blocking(move || terrain.chunk(grid, lod))
+                .await
+                .map_err(|e| Status::internal(format!("Terrain generation failed: {}", e)))?
+                .ok_or_else(|| Status::invalid_argument("Invalid LOD"))?
+        };
+
+        Ok(Response::new(proto::TerrainChunk {
+            x: chunk.grid.x,
+            y: chunk.grid.y,
+            lod: chunk.lod as u32,
+            resolution: chunk.resolution as u32,
+            spacing: chunk.spacing,
+            heights: chunk.heights.clone(),
+            min_height: chunk.min_height,
+            max_height: chunk.max_height,
+        }))
+    }
+
+    async fn validate_move(
+        &self,
+        request: Request<proto::MoveInput>,
+    ) -> Result<Response<proto::MoveResult>, Status> {
+        let req = request.into_inner();
+        let player_id = uuid::Uuid::parse_str(&req.player_id)
+            .map(PlayerId)
+            .map_err(|_| Status::invalid_argument("Invalid player id"))?;
+        let position = req
+            .position
+            .ok_or_else(|| Status::invalid_argument("Move has no position"))?;
+
+        let now = std::time::Instant::now();
+        let result = self.movement.validate(
+            movement::MoveInput {
+                player_id: player_id.clone(),
+                sequence: req.sequence,
+                position: Position3D::new(position.x, position.y, position.z),
+                client_timestamp_ms: req.client_timestamp_ms,
+            },
+            now,
+        );
+        self.spatial_streamer.observe(player_id, result.position, now);
+
+        Ok(Response::new(proto::MoveResult {
+            sequence: result.sequence,
+            accepted: result.correction.is_none(),
+            position: Some(proto::Position3D {
+                x: result.position.x,
+                y: result.position.y,
+                z: result.position.z,
+            }),
+            correction: result.correction.map(|c| c.as_str().to_string()).unwrap_or_default(),
+        }))
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     logging::init(None);
@@ -64,6 +280,16 @@ async fn main() -> anyhow::Result<()> {
     service.initialize_first_hour_world().await?;
 
     info!("World 3D Service initialized");
-    tokio::signal::ctrl_c().await?;
+    let grpc_port: u16 = std::env::var("WORLD3D_GRPC_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(3012);
+    info!("🚀 World 3D Service gRPC starting on port {}", grpc_port);
+    Server::builder()
+        .add_service(World3DServiceServer::new(service))
+        .serve_with_shutdown(([0, 0, 0, 0], grpc_port).into(), async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
     Ok(())
 }
@@ -0,0 +1,216 @@
+// services/world3d-service/src/movement.rs
+//! Authoritative movement for client-side prediction. Clients move their
+//! player as soon as the input happens and send each input with a sequence
+//! number and timestamp. The server checks it against how far the player
+//! could have gone and what is in the way, and answers with where the
+//! player really is; the client rewinds to that position and replays the
+//! inputs the server hasn't answered yet.
+
+use crate::terrain_service::TerrainService;
+use dashmap::DashMap;
+use finalverse_world3d::collision::{CollisionWorld, Shape};
+use finalverse_world3d::{PlayerId, Position3D};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Sprinting speed, in world units per second.
+pub const DEFAULT_MAX_SPEED: f32 = 12.0;
+/// Allowance over the speed limit for timing jitter.
+const SPEED_TOLERANCE: f32 = 1.1;
+/// How far ahead of the server's clock a client's timestamps may run, so a
+/// client can't claim extra travel time by skewing its clock.
+const MAX_CLOCK_LEAD: Duration = Duration::from_millis(250);
+/// Players are capsules this wide and tall for collision.
+pub const PLAYER_SHAPE: Shape = Shape { radius: 0.5, height: 1.8 };
+/// Highest a player can be above the ground, e.g. mid-jump.
+const MAX_HEIGHT_ABOVE_GROUND: f32 = 3.0;
+/// Slack below the ground for rounding between client and server terrain.
+const GROUND_TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Clone)]
+pub struct MoveInput {
+    pub player_id: PlayerId,
+    pub sequence: u64,
+    /// Where the client predicted the player to end up.
+    pub position: Position3D,
+    pub client_timestamp_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Correction {
+    /// The input was older than one already applied.
+    OutOfOrder,
+    TooFast,
+    Blocked,
+    Underground,
+    Airborne,
+}
+
+impl Correction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Correction::OutOfOrder => "out_of_order",
+            Correction::TooFast => "too_fast",
+            Correction::Blocked => "blocked",
+            Correction::Underground => "underground",
+            Correction::Airborne => "airborne",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MoveResult {
+    /// Latest input applied for the player.
+    pub sequence: u64,
+    /// Authoritative position after that input.
+    pub position: Position3D,
+    /// Why `position` differs from the input, if it does.
+    pub correction: Option<Correction>,
+}
+
+struct PlayerMotion {
+    position: Position3D,
+    sequence: u64,
+    client_timestamp_ms: u64,
+    received_at: Instant,
+}
+
+pub struct MovementValidator {
+    terrain: Arc<TerrainService>,
+    collision: Arc<CollisionWorld>,
+    players: DashMap<PlayerId, PlayerMotion>,
+    max_speed: f32,
+}
+
+impl MovementValidator {
+    pub fn new(terrain: Arc<TerrainService>, collision: Arc<CollisionWorld>) -> Self {
+        Self {
+            terrain,
+            collision,
+            players: DashMap::new(),
+            max_speed: DEFAULT_MAX_SPEED,
+        }
+    }
+
+    /// Apply a movement input received at `now`. A player's first input
+    /// places them, grounded, wherever it says.
+    pub fn validate(&self, input: MoveInput, now: Instant) -> MoveResult {
+        let Some(mut motion) = self.players.get_mut(&input.player_id) else {
+            let (position, correction) = self.ground(input.position);
+            self.players.insert(input.player_id, PlayerMotion {
+                position,
+                sequence: input.sequence,
+                client_timestamp_ms: input.client_timestamp_ms,
+                received_at: now,
+            });
+            return MoveResult { sequence: input.sequence, position, correction };
+        };
+
+        if input.sequence <= motion.sequence {
+            return MoveResult {
+                sequence: motion.sequence,
+                position: motion.position,
+                correction: Some(Correction::OutOfOrder),
+            };
+        }
+
+        let claimed = Duration::from_millis(input.client_timestamp_ms.saturating_sub(motion.client_timestamp_ms));
+        let elapsed = claimed.min(now.saturating_duration_since(motion.received_at) + MAX_CLOCK_LEAD);
+        let reach = self.max_speed * SPEED_TOLERANCE * elapsed.as_secs_f32();
+
+        let mut correction = None;
+        let mut target = input.position;
+        let (dx, dy) = (target.x - motion.position.x, target.y - motion.position.y);
+        let distance = dx.hypot(dy);
+        if distance > reach {
+            let scale = reach / distance;
+            target.x = motion.position.x + dx * scale;
+            target.y = motion.position.y + dy * scale;
+            correction = Some(Correction::TooFast);
+        }
+
+        if let Some(hit) = self.collision.sweep(PLAYER_SHAPE, motion.position, target) {
+            target = hit.position;
+            correction = correction.or(Some(Correction::Blocked));
+        }
+
+        let (position, grounded) = self.ground(target);
+        motion.position = position;
+        motion.sequence = input.sequence;
+        motion.client_timestamp_ms = input.client_timestamp_ms;
+        motion.received_at = now;
+        MoveResult {
+            sequence: input.sequence,
+            position,
+            correction: correction.or(grounded),
+        }
+    }
+
+    /// Keep a position between the ground and jumping height above it.
+    fn ground(&self, position: Position3D) -> (Position3D, Option<Correction>) {
+        let ground = self.terrain.height_at(position.x, position.y);
+        if position.z < ground - GROUND_TOLERANCE {
+            (Position3D::new(position.x, position.y, ground), Some(Correction::Underground))
+        } else if position.z > ground + MAX_HEIGHT_ABOVE_GROUND {
+            (Position3D::new(position.x, position.y, ground), Some(Correction::Airborne))
+        } else {
+            (position, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use finalverse_world3d::collision::Collider;
+    use finalverse_world3d::EntityId;
+    use uuid::Uuid;
+
+    #[test]
+    fn moves_are_held_to_speed_colliders_and_ground() {
+        let terrain = Arc::new(TerrainService::new());
+        let collision = Arc::new(CollisionWorld::new(terrain.clone()));
+        let mut validator = MovementValidator::new(terrain.clone(), collision.clone());
+        validator.max_speed = 10.0;
+        let player = PlayerId(Uuid::new_v4());
+        let at = |x: f32, y: f32| Position3D::new(x, y, terrain.height_at(x, y));
+        let input = |sequence, position, client_timestamp_ms| MoveInput { player_id: player.clone(), sequence, position, client_timestamp_ms };
+        let start = Instant::now();
+
+        let spawned = validator.validate(input(1, at(0.0, 0.0), 1_000), start);
+        assert!(spawned.correction.is_none());
+
+        // Ten units in a second is fine
+        let now = start + Duration::from_secs(1);
+        let walked = validator.validate(input(2, at(10.0, 0.0), 2_000), now);
+        assert!(walked.correction.is_none());
+        assert_eq!(walked.position.x, 10.0);
+
+        // Claiming a long gap doesn't buy more distance than the server saw pass
+        let now = now + Duration::from_millis(100);
+        let dashed = validator.validate(input(3, at(60.0, 0.0), 7_000), now);
+        assert_eq!(dashed.correction, Some(Correction::TooFast));
+        assert!((dashed.position.x - (10.0 + 11.0 * 0.35)).abs() < 1e-3);
+
+        // A replayed input changes nothing
+        let replayed = validator.validate(input(2, at(10.0, 0.0), 2_000), now);
+        assert_eq!((replayed.sequence, replayed.correction), (3, Some(Correction::OutOfOrder)));
+
+        // Pillars stop the player at their surface
+        let mut base = at(20.0, 0.0);
+        base.z -= 2.0;
+        collision.insert(EntityId(Uuid::new_v4()), Collider::capsule(base, 1.5, 8.0));
+        let now = now + Duration::from_secs(2);
+        let blocked = validator.validate(input(4, at(25.0, 0.0), 9_000), now);
+        assert_eq!(blocked.correction, Some(Correction::Blocked));
+        assert!((blocked.position.x - 18.0).abs() < 1e-2);
+
+        // Flying is brought back to the ground
+        let now = now + Duration::from_secs(1);
+        let mut high = at(15.0, 0.0);
+        high.z += 10.0;
+        let flying = validator.validate(input(5, high, 10_000), now);
+        assert_eq!(flying.correction, Some(Correction::Airborne));
+        assert_eq!(flying.position.z, terrain.height_at(15.0, 0.0));
+    }
+}
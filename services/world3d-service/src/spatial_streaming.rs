@@ -1,5 +1,282 @@
-pub struct SpatialStreamManager;
+// services/world3d-service/src/spatial_streaming.rs
+//! Keeps the grids players are about to walk into loaded. Each move a
+//! player makes updates their velocity; the grids along where they are
+//! heading are prefetched nearest first, a few at a time, and grids
+//! nobody has touched in a while are dropped once loaded grids go over
+//! the memory cap. Loaded grids are full-resolution terrain chunks.
+
+use crate::terrain_service::TerrainChunk;
+use dashmap::DashMap;
+use finalverse_world3d::terrain::GRID_SIZE;
+use finalverse_world3d::{GridCoordinate, PlayerId, Position3D};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{OnceCell, Semaphore};
+
+/// How far ahead of a player, in seconds of travel, grids are prefetched.
+pub const DEFAULT_LOOKAHEAD_SECONDS: f32 = 8.0;
+/// Prefetches running at once; loads a client is waiting on aren't limited.
+pub const DEFAULT_PREFETCH_CONCURRENCY: usize = 4;
+/// Loaded grids kept before the least recently used are evicted.
+pub const DEFAULT_MEMORY_CAP_BYTES: usize = 256 * 1024 * 1024;
+/// Weight of the newest move in a player's velocity, smoothing out jitter.
+const VELOCITY_SMOOTHING: f32 = 0.5;
+/// Distance between points checked along a player's predicted path.
+const PATH_STEP: f32 = GRID_SIZE / 4.0;
+
+pub type GridLoader = Arc<dyn Fn(GridCoordinate) -> Arc<TerrainChunk> + Send + Sync>;
+
+struct Resident {
+    chunk: Arc<TerrainChunk>,
+    bytes: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Residency {
+    grids: HashMap<GridCoordinate, Resident>,
+    bytes: usize,
+    /// Bumped on every use, ordering grids for eviction.
+    clock: u64,
+}
+
+struct Track {
+    position: Position3D,
+    seen_at: Instant,
+    /// World units per second along x and y.
+    velocity: (f32, f32),
+}
+
+pub struct SpatialStreamManager {
+    loader: GridLoader,
+    resident: Mutex<Residency>,
+    loading: DashMap<GridCoordinate, Arc<OnceCell<Arc<TerrainChunk>>>>,
+    players: DashMap<PlayerId, Track>,
+    prefetch_permits: Arc<Semaphore>,
+    lookahead_seconds: f32,
+    memory_cap_bytes: usize,
+}
 
 impl SpatialStreamManager {
-    pub fn new() -> Self { Self }
+    pub fn new(loader: GridLoader) -> Self {
+        Self {
+            loader,
+            resident: Mutex::new(Residency::default()),
+            loading: DashMap::new(),
+            players: DashMap::new(),
+            prefetch_permits: Arc::new(Semaphore::new(DEFAULT_PREFETCH_CONCURRENCY)),
+            lookahead_seconds: DEFAULT_LOOKAHEAD_SECONDS,
+            memory_cap_bytes: DEFAULT_MEMORY_CAP_BYTES,
+        }
+    }
+
+    pub fn with_prefetch_concurrency(mut self, concurrency: usize) -> Self {
+        self.prefetch_permits = Arc::new(Semaphore::new(concurrency));
+        self
+    }
+
+    pub fn with_memory_cap(mut self, bytes: usize) -> Self {
+        self.memory_cap_bytes = bytes;
+        self
+    }
+
+    /// A grid's chunk, loading it now if it isn't loaded. Loads already in
+    /// flight, e.g. from a prefetch, are waited on rather than repeated.
+    pub async fn ensure_loaded(&self, grid: GridCoordinate) -> anyhow::Result<Arc<TerrainChunk>> {
+        if let Some(chunk) = self.touch(grid) {
+            return Ok(chunk);
+        }
+
+        let cell = self.loading.entry(grid).or_default().clone();
+        let loaded = cell
+            .get_or_try_init(|| async {
+                let loader = self.loader.clone();
+                tokio::task::spawn_blocking(move || loader(grid)).await
+            })
+            .await
+            .cloned();
+        if let Ok(chunk) = &loaded {
+            self.admit(grid, chunk.clone());
+        }
+        self.loading.remove_if(&grid, |_, pending| Arc::ptr_eq(pending, &cell));
+        Ok(loaded?)
+    }
+
+    /// Record where a player is and prefetch the grids they're heading
+    /// for, highest priority first, as far as the concurrency budget
+    /// allows. Grids skipped for lack of budget are picked up by a later
+    /// move if the player is still heading for them.
+    pub fn observe(self: &Arc<Self>, player_id: PlayerId, position: Position3D, now: Instant) {
+        let velocity = match self.players.get_mut(&player_id) {
+            Some(mut track) => {
+                let elapsed = now.saturating_duration_since(track.seen_at).as_secs_f32();
+                if elapsed > 0.0 {
+                    let moved = ((position.x - track.position.x) / elapsed, (position.y - track.position.y) / elapsed);
+                    track.velocity = (
+                        track.velocity.0 + (moved.0 - track.velocity.0) * VELOCITY_SMOOTHING,
+                        track.velocity.1 + (moved.1 - track.velocity.1) * VELOCITY_SMOOTHING,
+                    );
+                }
+                track.position = position;
+                track.seen_at = now;
+                track.velocity
+            }
+            None => {
+                self.players.insert(player_id, Track { position, seen_at: now, velocity: (0.0, 0.0) });
+                (0.0, 0.0)
+            }
+        };
+
+        for grid in predict(position, velocity, self.lookahead_seconds) {
+            // Grids about to be needed shouldn't be the next evicted
+            if self.touch(grid).is_some() || self.loading.contains_key(&grid) {
+                continue;
+            }
+            let Ok(permit) = self.prefetch_permits.clone().try_acquire_owned() else {
+                break;
+            };
+            let manager = self.clone();
+            tokio::spawn(async move {
+                let _permit = permit;
+                if let Err(e) = manager.ensure_loaded(grid).await {
+                    tracing::warn!("Prefetching grid {:?} failed: {}", grid, e);
+                }
+            });
+        }
+    }
+
+    fn touch(&self, grid: GridCoordinate) -> Option<Arc<TerrainChunk>> {
+        let mut residency = self.resident.lock().unwrap();
+        residency.clock += 1;
+        let clock = residency.clock;
+        let resident = residency.grids.get_mut(&grid)?;
+        resident.last_used = clock;
+        Some(resident.chunk.clone())
+    }
+
+    /// Keep a loaded grid, evicting the least recently used others while
+    /// over the memory cap.
+    fn admit(&self, grid: GridCoordinate, chunk: Arc<TerrainChunk>) {
+        let mut residency = self.resident.lock().unwrap();
+        residency.clock += 1;
+        let last_used = residency.clock;
+        if let Some(resident) = residency.grids.get_mut(&grid) {
+            resident.last_used = last_used;
+            return;
+        }
+        let bytes = std::mem::size_of::<TerrainChunk>() + std::mem::size_of_val(chunk.heights.as_slice());
+        residency.grids.insert(grid, Resident { chunk, bytes, last_used });
+        residency.bytes += bytes;
+
+        while residency.bytes > self.memory_cap_bytes && residency.grids.len() > 1 {
+            let Some(oldest) = residency
+                .grids
+                .iter()
+                .filter(|(coordinate, _)| **coordinate != grid)
+                .min_by_key(|(_, resident)| resident.last_used)
+                .map(|(coordinate, _)| *coordinate)
+            else {
+                break;
+            };
+            if let Some(evicted) = residency.grids.remove(&oldest) {
+                residency.bytes -= evicted.bytes;
+            }
+        }
+    }
+}
+
+/// Grids a player at `position` moving at `velocity` will need, most
+/// urgent first: the one they're in, those along their path in the order
+/// they'll reach them, then the rest of the surrounding ring, those in the
+/// direction of travel first.
+pub fn predict(position: Position3D, velocity: (f32, f32), lookahead_seconds: f32) -> Vec<GridCoordinate> {
+    let current = position.to_grid_coordinate();
+    let mut grids = vec![current];
+
+    let speed = velocity.0.hypot(velocity.1);
+    let distance = speed * lookahead_seconds;
+    if speed > 0.0 {
+        let steps = (distance / PATH_STEP).ceil() as usize;
+        for step in 1..=steps {
+            let travelled = (step as f32 * PATH_STEP).min(distance);
+            let ahead = Position3D::new(
+                position.x + velocity.0 / speed * travelled,
+                position.y + velocity.1 / speed * travelled,
+                position.z,
+            );
+            let grid = ahead.to_grid_coordinate();
+            if !grids.contains(&grid) {
+                grids.push(grid);
+            }
+        }
+    }
+
+    let mut ring: Vec<GridCoordinate> = (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| GridCoordinate::new(current.x + dx, current.y + dy)))
+        .filter(|grid| !grids.contains(grid))
+        .collect();
+    let alignment = |grid: &GridCoordinate| (grid.x - current.x) as f32 * velocity.0 + (grid.y - current.y) as f32 * velocity.1;
+    ring.sort_by(|a, b| alignment(b).total_cmp(&alignment(a)));
+    grids.extend(ring);
+    grids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn chunk(grid: GridCoordinate) -> Arc<TerrainChunk> {
+        Arc::new(TerrainChunk {
+            grid,
+            lod: 0,
+            resolution: 4,
+            spacing: GRID_SIZE / 3.0,
+            heights: vec![0.0; 16],
+            min_height: 0.0,
+            max_height: 0.0,
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn prefetches_ahead_within_budget_and_evicts_least_recently_used() {
+        let east = predict(Position3D::new(10.0, 10.0, 0.0), (80.0, 0.0), DEFAULT_LOOKAHEAD_SECONDS);
+        assert_eq!(&east[..3], &[GridCoordinate::new(0, 0), GridCoordinate::new(1, 0), GridCoordinate::new(2, 0)]);
+        let ring = &east[3..];
+        assert_eq!(ring.len(), 7);
+        assert!(ring[..2].contains(&GridCoordinate::new(1, 1)) && ring[..2].contains(&GridCoordinate::new(1, -1)));
+        assert_eq!(predict(Position3D::new(10.0, 10.0, 0.0), (0.0, 0.0), DEFAULT_LOOKAHEAD_SECONDS).len(), 9);
+
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counted = loads.clone();
+        let loader: GridLoader = Arc::new(move |grid| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            chunk(grid)
+        });
+        let grid_bytes = std::mem::size_of::<TerrainChunk>() + 16 * std::mem::size_of::<f32>();
+        let streaming = Arc::new(SpatialStreamManager::new(loader).with_prefetch_concurrency(1).with_memory_cap(2 * grid_bytes));
+        let loaded = |grid: GridCoordinate| streaming.resident.lock().unwrap().grids.contains_key(&grid);
+
+        // With one prefetch at a time only the player's own grid is loaded
+        let player = PlayerId(uuid::Uuid::new_v4());
+        let start = Instant::now();
+        streaming.observe(player.clone(), Position3D::new(10.0, 10.0, 0.0), start);
+        streaming.observe(player, Position3D::new(60.0, 10.0, 0.0), start + Duration::from_secs(1));
+        while !streaming.loading.is_empty() || !loaded(GridCoordinate::new(0, 0)) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // Over the cap the grid used longest ago goes
+        streaming.ensure_loaded(GridCoordinate::new(1, 0)).await.unwrap();
+        streaming.ensure_loaded(GridCoordinate::new(0, 0)).await.unwrap();
+        streaming.ensure_loaded(GridCoordinate::new(2, 0)).await.unwrap();
+        assert!(loaded(GridCoordinate::new(0, 0)));
+        assert!(!loaded(GridCoordinate::new(1, 0)));
+        assert_eq!(streaming.resident.lock().unwrap().bytes, 2 * grid_bytes);
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+    }
 }
@@ -1,5 +1,193 @@
-pub struct TerrainService;
+// services/world3d-service/src/terrain_service.rs
+//! Heightmap chunks for 3D clients. A chunk covers one grid at a level of
+//! detail: LOD 0 samples every world unit and each level above halves the
+//! resolution. Chunks include their far edges, sampled with the
+//! neighbouring grid's terrain, so adjacent chunks at the same LOD meet
+//! without seams.
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use finalverse_world3d::collision::Ground;
+use finalverse_world3d::terrain::{Biome, TerrainGenerator, GRID_RESOLUTION, GRID_SIZE};
+use finalverse_world3d::GridCoordinate;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Coarsest level of detail, 16 cells per side.
+pub const MAX_LOD: u8 = 4;
+/// Chunks kept in memory before the oldest are dropped.
+pub const DEFAULT_CACHE_CAPACITY: usize = 512;
+const TERRAIN_SEED: u64 = 0x5eed_f1a1;
+
+#[derive(Debug, Clone)]
+pub struct TerrainChunk {
+    pub grid: GridCoordinate,
+    pub lod: u8,
+    /// Samples per side.
+    pub resolution: usize,
+    /// World units between samples.
+    pub spacing: f32,
+    /// Row-major heights, `resolution * resolution` of them.
+    pub heights: Vec<f32>,
+    pub min_height: f32,
+    pub max_height: f32,
+}
+
+/// What shapes a grid's terrain besides the world seed.
+#[derive(Debug, Clone, Copy)]
+struct GridTerrain {
+    biome: Biome,
+    harmony_level: f32,
+}
+
+impl Default for GridTerrain {
+    fn default() -> Self {
+        Self {
+            biome: Biome::Other,
+            harmony_level: 0.5,
+        }
+    }
+}
+
+pub struct TerrainService {
+    generator: TerrainGenerator,
+    grids: DashMap<GridCoordinate, GridTerrain>,
+    chunks: DashMap<(GridCoordinate, u8), Arc<TerrainChunk>>,
+    /// Cached chunks in the order they were generated.
+    generated: Mutex<VecDeque<(GridCoordinate, u8)>>,
+    capacity: usize,
+}
 
 impl TerrainService {
-    pub fn new() -> Self { Self }
+    pub fn new() -> Self {
+        Self {
+            generator: TerrainGenerator::new(TERRAIN_SEED),
+            grids: DashMap::new(),
+            chunks: DashMap::new(),
+            generated: Mutex::new(VecDeque::new()),
+            capacity: DEFAULT_CACHE_CAPACITY,
+        }
+    }
+
+    /// Set the biome and harmony a grid's terrain is generated with. Its
+    /// cached chunks are regenerated on next request.
+    pub fn set_grid(&self, grid: GridCoordinate, biome: Biome, harmony_level: f32) {
+        self.grids.insert(grid, GridTerrain { biome, harmony_level });
+        // Neighbours sample this grid along their shared edges
+        let affected = [
+            grid,
+            GridCoordinate::new(grid.x - 1, grid.y),
+            GridCoordinate::new(grid.x, grid.y - 1),
+            GridCoordinate::new(grid.x - 1, grid.y - 1),
+        ];
+        self.chunks.retain(|(cached, _), _| !affected.contains(cached));
+        self.generated.lock().unwrap().retain(|(cached, _)| !affected.contains(cached));
+    }
+
+    /// The chunk for a grid at `lod`, generating it if it isn't cached.
+    /// `None` when `lod` is above [`MAX_LOD`].
+    pub fn chunk(&self, grid: GridCoordinate, lod: u8) -> Option<Arc<TerrainChunk>> {
+        if lod > MAX_LOD {
+            return None;
+        }
+        let chunk = match self.chunks.entry((grid, lod)) {
+            Entry::Occupied(cached) => return Some(cached.get().clone()),
+            Entry::Vacant(slot) => slot.insert(Arc::new(self.generate(grid, lod))).clone(),
+        };
+
+        let mut generated = self.generated.lock().unwrap();
+        generated.push_back((grid, lod));
+        while generated.len() > self.capacity {
+            if let Some(oldest) = generated.pop_front() {
+                self.chunks.remove(&oldest);
+            }
+        }
+        Some(chunk)
+    }
+
+    /// Ground height at a world position.
+    pub fn height_at(&self, x: f32, y: f32) -> f32 {
+        self.sample(x as f64, y as f64)
+    }
+
+    fn sample(&self, world_x: f64, world_y: f64) -> f32 {
+        let grid = GridCoordinate::new(
+            (world_x / GRID_SIZE as f64).floor() as i32,
+            (world_y / GRID_SIZE as f64).floor() as i32,
+        );
+        let terrain = self.grids.get(&grid).map(|terrain| *terrain).unwrap_or_default();
+        self.generator.height_at(grid, world_x, world_y, terrain.harmony_level, terrain.biome)
+    }
+
+    /// Generate a chunk without caching it, for callers that keep chunks
+    /// themselves such as the grid streamer.
+    pub fn generate(&self, grid: GridCoordinate, lod: u8) -> TerrainChunk {
+        let cells = GRID_RESOLUTION >> lod;
+        let resolution = cells + 1;
+        let spacing = GRID_SIZE / cells as f32;
+        let origin_x = grid.x as f64 * GRID_SIZE as f64;
+        let origin_y = grid.y as f64 * GRID_SIZE as f64;
+
+        let mut heights = Vec::with_capacity(resolution * resolution);
+        for row in 0..resolution {
+            for col in 0..resolution {
+                // Edge samples fall in, and are shaped by, the neighbouring grid
+                let world_x = origin_x + (col as f32 * spacing) as f64;
+                let world_y = origin_y + (row as f32 * spacing) as f64;
+                heights.push(self.sample(world_x, world_y));
+            }
+        }
+
+        TerrainChunk {
+            grid,
+            lod,
+            resolution,
+            spacing,
+            min_height: heights.iter().copied().fold(f32::INFINITY, f32::min),
+            max_height: heights.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            heights,
+        }
+    }
+}
+
+impl Ground for TerrainService {
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        TerrainService::height_at(self, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lods_share_samples_and_chunks_meet_their_neighbours() {
+        let mut terrain = TerrainService::new();
+        terrain.capacity = 3;
+        let height = |chunk: &TerrainChunk, col: usize, row: usize| chunk.heights[row * chunk.resolution + col];
+        let grotto = GridCoordinate::new(100, 100);
+        terrain.set_grid(grotto, Biome::MemoryGrotto, 0.8);
+
+        let fine = terrain.chunk(grotto, 0).unwrap();
+        let coarse = terrain.chunk(grotto, MAX_LOD).unwrap();
+        assert_eq!((fine.resolution, coarse.resolution), (257, 17));
+        assert_eq!(coarse.spacing, 16.0);
+        assert_eq!(height(&coarse, 3, 5), height(&fine, 3 * 16, 5 * 16));
+        assert!(terrain.chunk(grotto, MAX_LOD + 1).is_none());
+
+        // The grotto's east edge is its neighbour's west edge
+        let east = terrain.chunk(GridCoordinate::new(101, 100), MAX_LOD).unwrap();
+        for row in 0..coarse.resolution {
+            assert_eq!(height(&coarse, coarse.resolution - 1, row), height(&east, 0, row));
+        }
+
+        // Cached until the grid changes or it ages out
+        assert!(Arc::ptr_eq(&terrain.chunk(grotto, MAX_LOD).unwrap(), &coarse));
+        terrain.set_grid(grotto, Biome::MemoryGrotto, 0.2);
+        assert!(!Arc::ptr_eq(&terrain.chunk(grotto, MAX_LOD).unwrap(), &coarse));
+        terrain.chunk(GridCoordinate::new(0, 0), 2);
+        terrain.chunk(GridCoordinate::new(0, 1), 2);
+        terrain.chunk(GridCoordinate::new(0, 2), 2);
+        assert!(!terrain.chunks.contains_key(&(grotto, MAX_LOD)));
+    }
 }
@@ -1,20 +1,26 @@
+use crate::spatial_streaming::SpatialStreamManager;
+use crate::terrain_service::TerrainChunk;
 use finalverse_world3d::{WorldId, world::World, GridCoordinate};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct WorldManager {
     worlds: HashMap<WorldId, World>,
+    streaming: Arc<SpatialStreamManager>,
 }
 
 impl WorldManager {
-    pub async fn new() -> anyhow::Result<Self> {
-        Ok(Self { worlds: HashMap::new() })
+    pub async fn new(streaming: Arc<SpatialStreamManager>) -> anyhow::Result<Self> {
+        Ok(Self { worlds: HashMap::new(), streaming })
     }
 
     pub async fn create_terra_nova_world(&self) -> anyhow::Result<()> {
         Ok(())
     }
 
-    pub async fn ensure_grid_loaded(&self, _coord: GridCoordinate) -> anyhow::Result<()> {
-        Ok(())
+    /// Load a grid now unless it's loaded already or on its way from a
+    /// prefetch.
+    pub async fn ensure_grid_loaded(&self, coord: GridCoordinate) -> anyhow::Result<Arc<TerrainChunk>> {
+        self.streaming.ensure_loaded(coord).await
     }
 }